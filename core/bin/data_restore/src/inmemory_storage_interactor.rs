@@ -9,6 +9,7 @@ use zksync_types::block::Block;
 use zksync_types::{
     Account, AccountId, AccountMap, AccountUpdate, AccountUpdates, Action, BlockNumber,
     NewTokenEvent, Operation, PriorityOp, SerialId, Token, TokenId, TokenInfo, TokenKind,
+    TokenStatus,
 };
 
 use crate::{
@@ -160,6 +161,7 @@ impl InMemoryStorageInteractor {
                             decimals: 0,
                             kind: TokenKind::NFT,
                             is_nft: true,
+                            status: TokenStatus::Valid,
                         },
                     );
                 }
@@ -255,6 +257,7 @@ impl InMemoryStorageInteractor {
                     decimals: 18,
                     kind: TokenKind::ERC20,
                     is_nft: false,
+                    status: TokenStatus::Valid,
                 },
             );
         }
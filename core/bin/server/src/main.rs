@@ -14,11 +14,14 @@ use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_witness_generator::run_prover_server;
 
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_config::configs::api::{
+    AdminApiConfig, PrivateApiConfig, PrometheusConfig, TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
     ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, ProverConfig, TickerConfig, ZkSyncConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, NotifierConfig, ProverConfig, TickerConfig,
+    ZkSyncConfig,
 };
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
 use zksync_mempool::run_mempool_tx_handler;
@@ -40,6 +43,7 @@ pub enum Component {
     Web3Api,
     RpcApi,
     RpcWebSocketApi,
+    AdminApi,
 
     // Core components
     Fetchers,
@@ -63,6 +67,7 @@ impl FromStr for Component {
             "web3-api" => Ok(Component::Web3Api),
             "rpc-api" => Ok(Component::RpcApi),
             "rpc-websocket-api" => Ok(Component::RpcWebSocketApi),
+            "admin-api" => Ok(Component::AdminApi),
             "eth-sender" => Ok(Component::EthSender),
             "witness-generator" => Ok(Component::WitnessGenerator),
             "forced-exit" => Ok(Component::ForcedExit),
@@ -86,6 +91,7 @@ impl Default for ComponentsToRun {
             Component::Web3Api,
             Component::RpcApi,
             Component::RpcWebSocketApi,
+            Component::AdminApi,
             Component::EthSender,
             Component::WitnessGenerator,
             Component::ForcedExit,
@@ -119,7 +125,7 @@ struct Opt {
     /// comma-separated list of components to launch
     #[structopt(
         long,
-        default_value = "rest-api,web3-api,rpc-api,rpc-websocket-api,eth-sender,witness-generator,forced-exit,prometheus,core,rejected-task-cleaner,fetchers,prometheus-periodic-metrics"
+        default_value = "rest-api,web3-api,rpc-api,rpc-websocket-api,admin-api,eth-sender,witness-generator,forced-exit,prometheus,core,rejected-task-cleaner,fetchers,prometheus-periodic-metrics"
     )]
     components: ComponentsToRun,
 }
@@ -159,10 +165,21 @@ async fn run_server(components: &ComponentsToRun) {
 
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
+        let web3_config = Web3Config::from_env();
+        let token_config = TokenConfig::from_env();
         tasks.push(zksync_api::api_server::web3::start_rpc_server(
             connection_pool.clone(),
-            &Web3Config::from_env(),
-            &TokenConfig::from_env(),
+            &web3_config,
+            &token_config,
+        ));
+        tasks.push(zksync_api::api_server::web3::start_ws_server(
+            connection_pool.clone(),
+            &web3_config,
+            &token_config,
+            CommonApiConfig::from_env().caches_size,
+            ChainConfig::from_env()
+                .state_keeper
+                .miniblock_iteration_interval(),
         ));
     }
 
@@ -203,6 +220,7 @@ async fn run_server(components: &ComponentsToRun) {
         let token_config = TokenConfig::from_env();
         let chain_config = ChainConfig::from_env();
         let fee_ticker_config = TickerConfig::from_env();
+        let notifier_config = NotifierConfig::from_env();
         let ticker_info = Box::new(TickerInfo::new(read_only_connection_pool.clone()));
 
         let ticker = FeeTicker::new_with_default_validator(
@@ -219,6 +237,8 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                &chain_config.mempool,
+                &notifier_config,
             ));
             tasks.push(zksync_api::api_server::rpc_subscriptions::start_ws_server(
                 read_only_connection_pool.clone(),
@@ -240,6 +260,8 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
+                &chain_config.mempool,
+                &notifier_config,
             ));
             tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
                 read_only_connection_pool.clone(),
@@ -260,6 +282,8 @@ async fn run_server(components: &ComponentsToRun) {
                 connection_pool.clone(),
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
+                &chain_config.mempool,
+                &notifier_config,
             ));
             let private_config = PrivateApiConfig::from_env();
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
@@ -275,6 +299,14 @@ async fn run_server(components: &ComponentsToRun) {
         }
     }
 
+    if components.0.contains(&Component::AdminApi) {
+        // Subsidy rules are managed by the admin API, so the main database is used
+        tasks.push(zksync_api::api_server::admin_server::start_admin_server(
+            connection_pool.clone(),
+            &AdminApiConfig::from_env(),
+        ));
+    }
+
     if components.0.contains(&Component::EthSender) {
         tasks.push(run_eth_sender(connection_pool.clone()))
     }
@@ -345,6 +377,7 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
     let contract_config = ContractsConfig::from_env();
     let eth_client_config = ETHClientConfig::from_env();
     let chain_config = ChainConfig::from_env();
+    let notifier_config = NotifierConfig::from_env();
 
     let (mempool_tx_request_sender, mempool_tx_request_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
@@ -352,6 +385,8 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
         connection_pool.clone(),
         mempool_tx_request_receiver,
         chain_config.state_keeper.block_chunk_sizes,
+        &chain_config.mempool,
+        &notifier_config,
     );
     let forced_exit_task = run_forced_exit_requests_actors(
         connection_pool,
@@ -360,6 +395,7 @@ pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
         common_config,
         contract_config,
         eth_client_config.web3_url(),
+        &notifier_config,
     );
     vec![mempool_task, forced_exit_task]
 }
@@ -368,8 +404,9 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     vlog::info!("Starting the Prover server actors");
     let prover_api_config = ProverApiConfig::from_env();
     let prover_config = ProverConfig::from_env();
+    let notifier_config = NotifierConfig::from_env();
     let database = zksync_witness_generator::database::Database::new(connection_pool);
-    run_prover_server(database, prover_api_config, prover_config)
+    run_prover_server(database, prover_api_config, prover_config, &notifier_config)
 }
 
 pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
@@ -382,13 +419,34 @@ pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
         &eth_sender_config,
         contracts.contract_addr,
     );
+    let operator_gateways = eth_sender_config
+        .sender
+        .additional_operator_accounts()
+        .unwrap_or_else(|err| panic!("Incorrect ETH_SENDER_SENDER_OPERATOR_ACCOUNTS: {}", err))
+        .into_iter()
+        .map(|account| {
+            let gateway = EthereumGateway::with_operator_account(
+                &eth_client_config,
+                account.address,
+                account.private_key,
+                contracts.contract_addr,
+            );
+            (account, gateway)
+        })
+        .collect();
 
-    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
+    zksync_eth_sender::run_eth_sender(
+        connection_pool,
+        eth_gateway,
+        operator_gateways,
+        eth_sender_config,
+        &NotifierConfig::from_env(),
+    )
 }
 
 pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
     let ticker_config = TickerConfig::from_env();
-    run_updaters(connection_pool, &ticker_config)
+    run_updaters(connection_pool, &ticker_config, create_eth_gateway())
 }
 
 pub fn create_eth_gateway() -> EthereumGateway {
@@ -0,0 +1,356 @@
+use anyhow::{ensure, format_err};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zksync_config::DBConfig;
+use zksync_crypto::{
+    merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256,
+    params::{account_tree_depth, NFT_TOKEN_ID},
+    proof::EncodedAggregatedProof,
+};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation,
+        BlocksCreateProofOperation, BlocksExecuteOperation, BlocksProofOperation,
+    },
+    block::Block,
+    AccountId, AccountTree, AccountUpdate, BlockNumber, Nonce, PubKeyHash,
+};
+
+use crate::snapshot::StateSnapshot;
+
+mod snapshot;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "state_snapshot",
+    about = "Tool to export the verified state to a snapshot file and to bootstrap a fresh database from it."
+)]
+enum Opt {
+    /// Exports the state at the verified block.
+    Export {
+        /// Block to export. The last verified block is used if not set.
+        #[structopt(long)]
+        block: Option<u32>,
+        /// Path to the snapshot file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Imports the snapshot into an empty database.
+    Import {
+        /// Path to the snapshot file.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+}
+
+/// Loads the block without its operations.
+async fn load_block_header(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+) -> anyhow::Result<Block> {
+    let mut block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("Block {} is not found", block_number))?;
+    block.block_transactions.clear();
+    Ok(block)
+}
+
+/// Creates the tree from the accounts and checks that its root hash matches the block.
+fn restore_tree(snapshot: &StateSnapshot) -> anyhow::Result<AccountTree> {
+    let mut tree = AccountTree::new(account_tree_depth());
+    for (id, account) in &snapshot.accounts {
+        tree.insert(**id, account.clone());
+    }
+
+    let root_hash = tree.root_hash();
+    ensure!(
+        root_hash == snapshot.block.new_root_hash,
+        "Root hash of the accounts {} doesn't match the root hash of the block {}: {}",
+        root_hash,
+        snapshot.block.block_number,
+        snapshot.block.new_root_hash
+    );
+    Ok(tree)
+}
+
+async fn export(
+    storage: &mut StorageProcessor<'_>,
+    block: Option<BlockNumber>,
+    output: PathBuf,
+) -> anyhow::Result<()> {
+    let mut transaction = storage.start_transaction().await?;
+
+    let last_verified_block = transaction
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let block_number = block.unwrap_or(last_verified_block);
+    ensure!(
+        *block_number > 0 && block_number <= last_verified_block,
+        "Block {} is not verified, the last verified block is {}",
+        block_number,
+        last_verified_block
+    );
+    println!("Exporting the state at block {}", block_number);
+
+    let block = load_block_header(&mut transaction, block_number).await?;
+    let previous_block = load_block_header(&mut transaction, block_number - 1).await?;
+
+    let (_, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_committed_state(Some(block_number))
+        .await?;
+    let mut accounts: Vec<_> = accounts.into_iter().collect();
+    accounts.sort_by_key(|(id, _)| *id);
+    println!("{} accounts are loaded", accounts.len());
+
+    let mut tokens: Vec<_> = transaction
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect();
+    let nft_token = transaction
+        .tokens_schema()
+        .get_token(NFT_TOKEN_ID.into())
+        .await?
+        .ok_or_else(|| format_err!("Special NFT token is not found"))?;
+    tokens.push(nft_token);
+    tokens.sort_by_key(|token| token.id);
+
+    let mut nfts = transaction
+        .chain()
+        .state_schema()
+        .load_committed_nft_tokens(Some(block_number))
+        .await?;
+    nfts.sort_by_key(|nft| nft.id);
+    println!("{} tokens and {} NFTs are loaded", tokens.len(), nfts.len());
+
+    transaction.commit().await?;
+
+    let mut snapshot = StateSnapshot {
+        block,
+        previous_block,
+        accounts,
+        tokens,
+        nfts,
+        account_tree_cache: Vec::new(),
+    };
+    // The cache is calculated from the exported accounts rather than loaded from the database,
+    // since the cache for the requested block may not exist.
+    let tree = restore_tree(&snapshot)?;
+    snapshot.account_tree_cache = tree.get_internals().encode_bincode();
+
+    std::fs::write(&output, snapshot.encode()?)?;
+    println!("Snapshot is saved to {}", output.display());
+
+    Ok(())
+}
+
+/// Converts the snapshot state to the list of updates applied to the empty state.
+fn snapshot_updates(snapshot: &StateSnapshot) -> Vec<(AccountId, AccountUpdate)> {
+    let mut updates = Vec::new();
+
+    // NFTs go first: minting updates the committed nonce of the creator, which must be
+    // overwritten by the actual account nonce afterwards.
+    // Nonces of the mint transactions are not a part of the state and are not restored.
+    for nft in &snapshot.nfts {
+        updates.push((
+            nft.creator_id,
+            AccountUpdate::MintNFT {
+                token: nft.clone(),
+                nonce: Nonce(0),
+            },
+        ));
+    }
+
+    for (id, account) in &snapshot.accounts {
+        let nonce = account.nonce;
+        updates.push((
+            *id,
+            AccountUpdate::Create {
+                address: account.address,
+                nonce,
+            },
+        ));
+
+        let mut balances: Vec<_> = account.get_nonzero_balances().into_iter().collect();
+        balances.sort_by_key(|(token, _)| *token);
+        for (token, balance) in balances {
+            updates.push((
+                *id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: nonce,
+                    new_nonce: nonce,
+                    balance_update: (token, 0u32.into(), balance.0),
+                },
+            ));
+        }
+
+        if account.pub_key_hash != PubKeyHash::default() {
+            updates.push((
+                *id,
+                AccountUpdate::ChangePubKeyHash {
+                    old_pub_key_hash: PubKeyHash::default(),
+                    new_pub_key_hash: account.pub_key_hash,
+                    old_nonce: nonce,
+                    new_nonce: nonce,
+                },
+            ));
+        }
+    }
+
+    updates
+}
+
+async fn import(storage: &mut StorageProcessor<'_>, input: PathBuf) -> anyhow::Result<()> {
+    let snapshot = StateSnapshot::decode(&std::fs::read(&input)?)?;
+    let block_number = snapshot.block.block_number;
+    println!(
+        "Snapshot at block {} with {} accounts is loaded",
+        block_number,
+        snapshot.accounts.len()
+    );
+
+    let mut tree = restore_tree(&snapshot)?;
+    tree.set_internals(SparseMerkleTreeSerializableCacheBN256::decode_bincode(
+        &snapshot.account_tree_cache,
+    ));
+    ensure!(
+        tree.root_hash() == snapshot.block.new_root_hash,
+        "Root hash of the account tree cache doesn't match the root hash of the block"
+    );
+    println!("Root hash is checked");
+
+    let mut transaction = storage.start_transaction().await?;
+
+    let last_block = transaction
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+    let (_, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_verified_state()
+        .await?;
+    ensure!(
+        *last_block == 0 && accounts.is_empty(),
+        "Snapshot can only be imported into an empty database"
+    );
+
+    for token in snapshot.tokens.iter().cloned() {
+        transaction.tokens_schema().store_token(token).await?;
+    }
+    println!("`tokens` table is filled");
+
+    transaction
+        .chain()
+        .block_schema()
+        .save_full_block(snapshot.block.clone())
+        .await?;
+    println!("`blocks` table is filled");
+
+    let updates = snapshot_updates(&snapshot);
+    transaction
+        .chain()
+        .state_schema()
+        .commit_state_update(block_number, &updates, 0)
+        .await?;
+    transaction
+        .chain()
+        .state_schema()
+        .apply_state_update(block_number)
+        .await?;
+    println!("Accounts and NFTs are restored");
+
+    // Restored block is considered committed, proven and executed on Ethereum.
+    let blocks = vec![snapshot.block.clone()];
+    let operations = vec![
+        AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: snapshot.previous_block.clone(),
+            blocks: blocks.clone(),
+        }),
+        AggregatedOperation::CreateProofBlocks(BlocksCreateProofOperation {
+            blocks: blocks.clone(),
+            proofs_to_pad: 0,
+        }),
+        AggregatedOperation::PublishProofBlocksOnchain(BlocksProofOperation {
+            blocks: blocks.clone(),
+            proof: EncodedAggregatedProof::default(),
+        }),
+        AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation { blocks }),
+    ];
+    for operation in operations {
+        transaction
+            .chain()
+            .operations_schema()
+            .store_aggregated_action(operation)
+            .await?;
+    }
+    for &action_type in &[
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::CreateProofBlocks,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        AggregatedActionType::ExecuteBlocks,
+    ] {
+        transaction
+            .chain()
+            .operations_schema()
+            .confirm_aggregated_operations(block_number, block_number, action_type)
+            .await?;
+    }
+    // These operations must not be sent to Ethereum.
+    transaction
+        .chain()
+        .operations_schema()
+        .remove_eth_unprocessed_aggregated_ops()
+        .await?;
+    println!("`aggregate_operations` table is filled");
+
+    transaction
+        .chain()
+        .tree_cache_schema_bincode()
+        .store_account_tree_cache(block_number, snapshot.account_tree_cache)
+        .await?;
+    println!("`account_tree_cache` table is filled");
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+    match opt {
+        Opt::Export { block, output } => {
+            export(&mut storage, block.map(BlockNumber), output).await?;
+        }
+        Opt::Import { input } => {
+            println!("Database URL is {}", DBConfig::from_env().url);
+            println!("Proceed? [y/n]");
+
+            let mut input_str = String::new();
+            std::io::stdin().read_line(&mut input_str)?;
+            if input_str.trim().to_lowercase() != "y" {
+                println!("Quitting");
+                return Ok(());
+            }
+
+            import(&mut storage, input).await?;
+        }
+    }
+
+    println!("Done");
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Snapshot file format.
+//!
+//! The file consists of the following parts:
+//! - 8 bytes of the magic prefix (`SNAPSHOT_MAGIC`);
+//! - format version as a big-endian `u32`;
+//! - SHA-256 hash of the payload;
+//! - payload: JSON-encoded `StateSnapshot`.
+
+// Built-in uses
+// External uses
+use anyhow::ensure;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+// Workspace uses
+use zksync_types::{block::Block, Account, AccountId, Token, NFT};
+use zksync_utils::ZeroPrefixHexSerde;
+// Local uses
+
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"ZKSNAPSH";
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const HEADER_LEN: usize = SNAPSHOT_MAGIC.len() + 4 + 32;
+
+/// State of the network at some verified block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// Snapshot block. Operations of the block are not included.
+    pub block: Block,
+    /// Block preceding the snapshot one, required to restore the commit operation.
+    /// Operations of the block are not included.
+    pub previous_block: Block,
+    pub accounts: Vec<(AccountId, Account)>,
+    /// `ERC20` tokens and the special NFT token. Minted NFTs are stored separately.
+    pub tokens: Vec<Token>,
+    pub nfts: Vec<NFT>,
+    /// Account tree cache encoded with `SparseMerkleTreeSerializableCacheBN256::encode_bincode`.
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub account_tree_cache: Vec<u8>,
+}
+
+impl StateSnapshot {
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        let payload = serde_json::to_vec(self)?;
+
+        let mut encoded = Vec::with_capacity(HEADER_LEN + payload.len());
+        encoded.extend_from_slice(SNAPSHOT_MAGIC);
+        encoded.extend_from_slice(&SNAPSHOT_VERSION.to_be_bytes());
+        encoded.extend_from_slice(&Sha256::digest(&payload));
+        encoded.extend_from_slice(&payload);
+        Ok(encoded)
+    }
+
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        ensure!(
+            data.len() >= HEADER_LEN && data.starts_with(SNAPSHOT_MAGIC),
+            "File is not a state snapshot"
+        );
+        let (version, rest) = data[SNAPSHOT_MAGIC.len()..].split_at(4);
+        let version = u32::from_be_bytes([version[0], version[1], version[2], version[3]]);
+        ensure!(
+            version == SNAPSHOT_VERSION,
+            "Unsupported snapshot version {}, expected {}",
+            version,
+            SNAPSHOT_VERSION
+        );
+        let (checksum, payload) = rest.split_at(32);
+        ensure!(
+            Sha256::digest(payload).as_slice() == checksum,
+            "Snapshot checksum mismatch, the file is corrupted"
+        );
+
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_crypto::{ff::Field, Fr};
+    use zksync_types::{Address, BlockNumber, TokenId, TokenKind, H256};
+
+    fn block(number: u32) -> Block {
+        Block::new(
+            BlockNumber(number),
+            Fr::zero(),
+            AccountId(0),
+            Vec::new(),
+            (0, 0),
+            10,
+            0u32.into(),
+            0u32.into(),
+            H256::zero(),
+            0,
+        )
+    }
+
+    fn snapshot() -> StateSnapshot {
+        let mut account = Account::default_with_address(&Address::repeat_byte(0x11));
+        account.set_balance(TokenId(0), 100u32.into());
+        StateSnapshot {
+            block: block(2),
+            previous_block: block(1),
+            accounts: vec![(AccountId(1), account)],
+            tokens: vec![Token::new(
+                TokenId(0),
+                Address::zero(),
+                "ETH",
+                18,
+                TokenKind::ERC20,
+            )],
+            nfts: Vec::new(),
+            account_tree_cache: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn encode_decode() {
+        let snapshot = snapshot();
+        let decoded = StateSnapshot::decode(&snapshot.encode().unwrap()).unwrap();
+
+        assert_eq!(decoded.block.block_number, BlockNumber(2));
+        assert_eq!(decoded.previous_block.block_number, BlockNumber(1));
+        assert_eq!(decoded.accounts, snapshot.accounts);
+        assert_eq!(decoded.tokens, snapshot.tokens);
+        assert_eq!(decoded.account_tree_cache, snapshot.account_tree_cache);
+    }
+
+    #[test]
+    fn decode_corrupted() {
+        let encoded = snapshot().encode().unwrap();
+
+        let mut corrupted = encoded.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(StateSnapshot::decode(&corrupted).is_err());
+
+        let mut wrong_version = encoded.clone();
+        wrong_version[SNAPSHOT_MAGIC.len() + 3] += 1;
+        assert!(StateSnapshot::decode(&wrong_version).is_err());
+
+        assert!(StateSnapshot::decode(&encoded[1..]).is_err());
+        assert!(StateSnapshot::decode(&encoded[..HEADER_LEN - 1]).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Merkle proofs of the account balances in the state of the verified blocks.
+//!
+//! The account tree is restored in the same way as the witness generator does it:
+//! the latest stored tree cache is applied to the state of the requested block, and the
+//! accounts changed between the cached block and the requested one are rehashed.
+//! Since the state of finalized blocks never changes, restored trees are kept in memory.
+
+// Built-in uses
+use std::sync::Arc;
+// External uses
+use anyhow::format_err;
+use thiserror::Error;
+use tokio::sync::Mutex;
+// Workspace uses
+use zksync_api_types::v02::account::{AccountBalanceProof, AccountLeaf};
+use zksync_crypto::{
+    circuit::account::CircuitAccount,
+    convert::FeConvert,
+    merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256,
+    params::{account_tree_depth, balance_tree_depth},
+    Engine, Fr,
+};
+use zksync_storage::StorageProcessor;
+use zksync_types::{AccountId, AccountTree, BlockNumber, TokenId};
+// Local uses
+use crate::utils::shared_lru_cache::AsyncLruCache;
+
+/// Number of restored account trees kept in memory.
+/// Trees are large, and most of the requests are made for the last finalized block.
+const ACCOUNT_TREE_CACHE_CAPACITY: usize = 2;
+
+#[derive(Debug, Error)]
+pub enum AccountProofError {
+    #[error("Block {0} is not finalized")]
+    BlockNotFinalized(BlockNumber),
+    #[error("Account is not found")]
+    AccountNotFound,
+    #[error("Internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Restores the account tree for the block and checks it against the block root hash.
+async fn load_account_tree(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    root_hash: Fr,
+) -> anyhow::Result<AccountTree> {
+    let mut tree = AccountTree::new(account_tree_depth());
+
+    let (_, accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(Some(block_number))
+        .await?;
+    for (id, account) in &accounts {
+        tree.insert(**id, account.clone());
+    }
+
+    let cache = storage
+        .chain()
+        .tree_cache_schema_bincode()
+        .get_account_tree_cache()
+        .await?;
+    if let Some((cached_block, cache)) = cache {
+        tree.set_internals(SparseMerkleTreeSerializableCacheBN256::decode_bincode(
+            &cache,
+        ));
+        if cached_block != block_number {
+            let diff = storage
+                .chain()
+                .state_schema()
+                .load_state_diff(block_number, Some(cached_block))
+                .await?;
+            if let Some((_, account_updates)) = diff {
+                let mut updated_accounts: Vec<_> =
+                    account_updates.into_iter().map(|(id, _)| id).collect();
+                updated_accounts.sort_unstable();
+                updated_accounts.dedup();
+                for id in updated_accounts {
+                    tree.insert(*id, accounts.get(&id).cloned().unwrap_or_default());
+                }
+            }
+        }
+    }
+
+    if tree.root_hash() != root_hash {
+        return Err(format_err!(
+            "Restored account tree doesn't match the root hash of the block {}",
+            block_number
+        ));
+    }
+    Ok(tree)
+}
+
+/// Cache of the account trees restored for the finalized blocks.
+#[derive(Clone)]
+pub(crate) struct AccountTreeCache {
+    trees: AsyncLruCache<BlockNumber, Arc<AccountTree>>,
+    /// Restoring the tree is expensive, so it's done by one request at a time,
+    /// and concurrent requests for the same block reuse the restored tree.
+    restore_lock: Arc<Mutex<()>>,
+}
+
+impl AccountTreeCache {
+    pub fn new() -> Self {
+        Self {
+            trees: AsyncLruCache::new(ACCOUNT_TREE_CACHE_CAPACITY),
+            restore_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Returns the account tree for the finalized block, restoring it if it isn't cached.
+    async fn get(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+        root_hash: Fr,
+    ) -> anyhow::Result<Arc<AccountTree>> {
+        if let Some(tree) = self.trees.get(&block_number).await {
+            return Ok(tree);
+        }
+
+        let _restore_guard = self.restore_lock.lock().await;
+        // The tree could have been restored while we were waiting for the lock.
+        if let Some(tree) = self.trees.get(&block_number).await {
+            return Ok(tree);
+        }
+        let tree = Arc::new(load_account_tree(storage, block_number, root_hash).await?);
+        self.trees.insert(block_number, tree.clone()).await;
+        Ok(tree)
+    }
+}
+
+/// Builds the proof of the account balance in the state of the finalized block.
+/// The last finalized block is used if `block` is not set.
+pub(crate) async fn account_balance_proof(
+    storage: &mut StorageProcessor<'_>,
+    account_trees: &AccountTreeCache,
+    account_id: AccountId,
+    token_id: TokenId,
+    block: Option<BlockNumber>,
+) -> Result<AccountBalanceProof, AccountProofError> {
+    let mut transaction = storage.start_transaction().await?;
+
+    let last_finalized_block = transaction
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let block_number = block.unwrap_or(last_finalized_block);
+    if *block_number == 0 || block_number > last_finalized_block {
+        return Err(AccountProofError::BlockNotFinalized(block_number));
+    }
+
+    let stored_block = transaction
+        .chain()
+        .block_schema()
+        .get_storage_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("Block {} is not found", block_number))?;
+    let root_hash = Fr::from_bytes(&stored_block.root_hash).map_err(anyhow::Error::from)?;
+
+    let tree = account_trees
+        .get(&mut transaction, block_number, root_hash)
+        .await?;
+    transaction.commit().await?;
+
+    let account = tree
+        .get(*account_id)
+        .cloned()
+        .ok_or(AccountProofError::AccountNotFound)?;
+    let balance = account.get_balance(token_id);
+    let account_path = tree.merkle_path(*account_id);
+
+    let account_leaf = AccountLeaf {
+        nonce: account.nonce,
+        pub_key_hash: account.pub_key_hash,
+        address: account.address,
+    };
+    let balance_tree = CircuitAccount::<Engine>::from(account).subtree;
+    let balance_path = balance_tree.merkle_path(*token_id);
+    debug_assert_eq!(balance_path.len(), balance_tree_depth());
+
+    Ok(AccountBalanceProof {
+        block_number,
+        root_hash,
+        account_id,
+        token_id,
+        account: account_leaf,
+        balance,
+        balance_path: balance_path.into_iter().map(|(hash, _)| hash).collect(),
+        account_path: account_path.into_iter().map(|(hash, _)| hash).collect(),
+    })
+}
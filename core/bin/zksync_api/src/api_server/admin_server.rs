@@ -0,0 +1,415 @@
+//! Admin API server.
+//!
+//! Endpoints are available under `/api/admin` on the admin API port and require the JWT signed
+//! with the admin API secret as a bearer token. Changes of the subsidy rules are picked up by
+//! the API servers without restart.
+
+// Built-in uses
+use std::convert::TryFrom;
+use std::time::Instant;
+
+// External uses
+use actix_web::{
+    dev::HttpServiceFactory,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
+    web::{self, Json},
+    App, HttpServer,
+};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_http_auth::AuthTokenValidator;
+use zksync_storage::{subsidy_rules::records::StorageSubsidyRule, ConnectionPool};
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+// Local uses
+use crate::api_server::subsidies::SubsidyRule;
+
+/// Subsidy rule with the amount already subsidized by it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubsidyRuleInfo {
+    #[serde(flatten)]
+    rule: SubsidyRule,
+    /// Amount subsidized by the rule in USD scaled by 10^6.
+    used_usd_scaled: BigDecimal,
+}
+
+/// Shared data between `/api/admin` endpoints.
+struct ApiAdminData {
+    connection_pool: ConnectionPool,
+}
+
+async fn get_subsidy_rules(
+    data: web::Data<ApiAdminData>,
+) -> actix_web::Result<Json<Vec<SubsidyRule>>> {
+    let start = Instant::now();
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let rules = storage
+        .subsidy_rules_schema()
+        .load_subsidy_rules()
+        .await
+        .map_err(ErrorInternalServerError)?
+        .into_iter()
+        .map(SubsidyRule::try_from)
+        .collect::<Result<_, _>>()
+        .map_err(ErrorInternalServerError)?;
+
+    metrics::histogram!("api", start.elapsed(), "type" => "admin", "endpoint_name" => "get_subsidy_rules");
+    Ok(Json(rules))
+}
+
+async fn get_subsidy_rule(
+    data: web::Data<ApiAdminData>,
+    name: web::Path<String>,
+) -> actix_web::Result<Json<SubsidyRuleInfo>> {
+    let start = Instant::now();
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let rule = storage
+        .subsidy_rules_schema()
+        .load_subsidy_rule(&name)
+        .await
+        .map_err(ErrorInternalServerError)?
+        .ok_or_else(|| ErrorNotFound("Subsidy rule not found"))?;
+    let rule = SubsidyRule::try_from(rule).map_err(ErrorInternalServerError)?;
+    let used_usd_scaled = storage
+        .misc_schema()
+        .get_total_used_subsidy_for_type(&rule.name)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    metrics::histogram!("api", start.elapsed(), "type" => "admin", "endpoint_name" => "get_subsidy_rule");
+    Ok(Json(SubsidyRuleInfo {
+        rule,
+        used_usd_scaled,
+    }))
+}
+
+/// Creates the rule or replaces the existing one. The name of the rule is taken from the path.
+async fn put_subsidy_rule(
+    data: web::Data<ApiAdminData>,
+    name: web::Path<String>,
+    rule: Json<SubsidyRule>,
+) -> actix_web::Result<Json<SubsidyRule>> {
+    let start = Instant::now();
+    let mut rule = rule.into_inner();
+    rule.name = name.into_inner();
+    rule.validate().map_err(ErrorBadRequest)?;
+    let stored_rule = StorageSubsidyRule::try_from(rule.clone()).map_err(ErrorBadRequest)?;
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    storage
+        .subsidy_rules_schema()
+        .store_subsidy_rule(stored_rule)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    metrics::histogram!("api", start.elapsed(), "type" => "admin", "endpoint_name" => "put_subsidy_rule");
+    Ok(Json(rule))
+}
+
+async fn delete_subsidy_rule(
+    data: web::Data<ApiAdminData>,
+    name: web::Path<String>,
+) -> actix_web::Result<Json<()>> {
+    let start = Instant::now();
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let removed = storage
+        .subsidy_rules_schema()
+        .remove_subsidy_rule(&name)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    if !removed {
+        return Err(ErrorNotFound("Subsidy rule not found"));
+    }
+
+    metrics::histogram!("api", start.elapsed(), "type" => "admin", "endpoint_name" => "delete_subsidy_rule");
+    Ok(Json(()))
+}
+
+fn api_scope(connection_pool: ConnectionPool, secret_auth: String) -> impl HttpServiceFactory {
+    let auth = HttpAuthentication::bearer(move |req, credentials| {
+        let secret_auth = secret_auth.clone();
+        async move {
+            AuthTokenValidator::new(&secret_auth)
+                .validator(req, credentials)
+                .await
+        }
+    });
+
+    web::scope("/api/admin")
+        .wrap(auth)
+        .app_data(web::Data::new(ApiAdminData { connection_pool }))
+        .route("/subsidy_rules", web::get().to(get_subsidy_rules))
+        .route("/subsidy_rules/{name}", web::get().to(get_subsidy_rule))
+        .route("/subsidy_rules/{name}", web::put().to(put_subsidy_rule))
+        .route(
+            "/subsidy_rules/{name}",
+            web::delete().to(delete_subsidy_rule),
+        )
+}
+
+/// Starts the admin API server on the admin API port.
+#[must_use]
+pub fn start_admin_server(
+    connection_pool: ConnectionPool,
+    config: &AdminApiConfig,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+    let bind_to = config.bind_addr();
+    let secret_auth = config.secret_auth.clone();
+
+    std::thread::Builder::new()
+        .name("actix-admin-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+            actix_rt::System::new().block_on(async move {
+                HttpServer::new(move || {
+                    App::new().service(api_scope(connection_pool.clone(), secret_auth.clone()))
+                })
+                .workers(super::THREADS_PER_SERVER)
+                .bind(bind_to)
+                .unwrap()
+                .shutdown_timeout(60)
+                .keep_alive(10)
+                .client_timeout(60000)
+                .run()
+                .await
+                .expect("Admin API server has crashed");
+            });
+        })
+        .expect("Admin API server thread");
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{Duration, UNIX_EPOCH};
+    use zksync_http_auth::PayloadAuthToken;
+
+    const SECRET_AUTH: &str = "admin_api_test_secret";
+
+    fn auth_token(secret: &str) -> String {
+        let exp = UNIX_EPOCH.elapsed().unwrap() + Duration::from_secs(60);
+        encode(
+            &Header::default(),
+            &PayloadAuthToken {
+                sub: "Authorization".to_string(),
+                exp: exp.as_secs() as usize,
+            },
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap()
+    }
+
+    fn start_server() -> actix_test::TestServer {
+        let connection_pool = ConnectionPool::new(Some(1));
+        actix_test::start(move || {
+            App::new().service(api_scope(connection_pool.clone(), SECRET_AUTH.to_string()))
+        })
+    }
+
+    fn rule(name: &str) -> SubsidyRule {
+        SubsidyRule {
+            name: name.to_string(),
+            priority: 0,
+            enabled: true,
+            tx_types: None,
+            tokens: None,
+            addresses: None,
+            ips: Some(vec!["127.0.0.1".to_string()]),
+            valid_from: None,
+            valid_until: None,
+            max_fee_usd_scaled: 10_000,
+            budget_usd_scaled: 1_000_000,
+            max_per_address_usd_scaled: None,
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn admin_api_authorization() {
+        let server = start_server();
+
+        let response = server.get("/api/admin/subsidy_rules").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .get("/api/admin/subsidy_rules")
+            .bearer_auth("not a token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .get("/api/admin/subsidy_rules")
+            .bearer_auth(auth_token("other secret"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .put("/api/admin/subsidy_rules/admin_api_unauthorized")
+            .send_json(&rule("admin_api_unauthorized"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .delete("/api/admin/subsidy_rules/admin_api_unauthorized")
+            .bearer_auth(auth_token("other secret"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .get("/api/admin/subsidy_rules")
+            .bearer_auth(auth_token(SECRET_AUTH))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        server.stop().await;
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn admin_api_subsidy_rules() {
+        let server = start_server();
+        let token = auth_token(SECRET_AUTH);
+        let path = "/api/admin/subsidy_rules/admin_api_test";
+        let rule = rule("admin_api_test");
+
+        // The name of the rule is taken from the path.
+        let mut response = server
+            .put(path)
+            .bearer_auth(&token)
+            .send_json(&SubsidyRule {
+                name: "ignored".to_string(),
+                ..rule.clone()
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json::<SubsidyRule>().await.unwrap(), rule);
+
+        let mut response = server.get(path).bearer_auth(&token).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let info: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(info["name"], "admin_api_test");
+        assert_eq!(info["budgetUsdScaled"], 1_000_000);
+
+        let mut response = server
+            .get("/api/admin/subsidy_rules")
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let rules: Vec<SubsidyRule> = response.json().await.unwrap();
+        assert!(rules.contains(&rule));
+
+        let response = server
+            .delete(path)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = server
+            .delete(path)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = server.get(path).bearer_auth(&token).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        server.stop().await;
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn admin_api_invalid_subsidy_rules() {
+        let server = start_server();
+        let token = auth_token(SECRET_AUTH);
+        let path = "/api/admin/subsidy_rules/admin_api_invalid";
+        let rule = rule("admin_api_invalid");
+
+        let invalid_rules = vec![
+            SubsidyRule {
+                ips: Some(vec!["localhost".to_string()]),
+                ..rule.clone()
+            },
+            SubsidyRule {
+                max_fee_usd_scaled: rule.budget_usd_scaled + 1,
+                ..rule.clone()
+            },
+            SubsidyRule {
+                budget_usd_scaled: u64::MAX,
+                ..rule.clone()
+            },
+        ];
+        for invalid_rule in invalid_rules {
+            let response = server
+                .put(path)
+                .bearer_auth(&token)
+                .send_json(&invalid_rule)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let mut unknown_tx_type = serde_json::to_value(&rule).unwrap();
+        unknown_tx_type["txTypes"] = serde_json::json!(["Teleport"]);
+        let response = server
+            .put(path)
+            .bearer_auth(&token)
+            .send_json(&unknown_tx_type)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = server.get(path).bearer_auth(&token).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        server.stop().await;
+    }
+}
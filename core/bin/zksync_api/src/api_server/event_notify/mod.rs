@@ -1,4 +1,9 @@
 use super::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp};
+use super::web3::{
+    pubsub::{PubSubResult, SubscriptionKind, Web3Subscriptions},
+    types::Filter,
+    Web3RpcApp,
+};
 use futures::{channel::mpsc, select, stream::StreamExt};
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
@@ -42,6 +47,11 @@ pub enum EventSubscribeRequest {
         action: ActionType,
         subscriber: Subscriber<ResponseAccountState>,
     },
+    Web3 {
+        kind: SubscriptionKind,
+        filter: Option<Filter>,
+        subscriber: Subscriber<PubSubResult>,
+    },
 }
 
 pub enum EventNotifierRequest {
@@ -61,6 +71,7 @@ pub fn start_sub_notifier(
     api_requests_caches_size: usize,
     miniblock_interval: Duration,
     token_config: &TokenConfig,
+    web3_app: Option<Web3RpcApp>,
 ) -> tokio::task::JoinHandle<()> {
     let (new_block_sender, mut new_block_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
     let (new_txs_sender, mut new_txs_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
@@ -69,6 +80,7 @@ pub fn start_sub_notifier(
         api_requests_caches_size,
         db_pool.clone(),
         token_config.invalidate_token_cache_period(),
+        web3_app.map(Web3Subscriptions::new),
     );
 
     tokio::spawn(async move {
@@ -9,6 +9,8 @@ use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
 use zksync_types::{block::ExecutedOperations, AccountId, ActionType, Address, PriorityOpId};
 
+use crate::api_server::web3::pubsub::Web3Subscriptions;
+
 use super::{
     state::NotifierState, sub_store::SubStorage, EventNotifierRequest, EventSubscribeRequest,
     ExecutedOps,
@@ -20,6 +22,8 @@ pub struct OperationNotifier {
     tx_subs: SubStorage<TxHash, TransactionInfoResp>,
     prior_op_subs: SubStorage<PriorityOpId, ETHOpInfoResp>,
     account_subs: SubStorage<AccountId, ResponseAccountState>,
+    /// Only present if the notifier serves the web3 API.
+    web3_subs: Option<Web3Subscriptions>,
 }
 
 impl OperationNotifier {
@@ -27,12 +31,14 @@ impl OperationNotifier {
         cache_capacity: usize,
         db_pool: ConnectionPool,
         token_cache_invalidate_period: Duration,
+        web3_subs: Option<Web3Subscriptions>,
     ) -> Self {
         Self {
             state: NotifierState::new(cache_capacity, db_pool, token_cache_invalidate_period),
             tx_subs: SubStorage::new(),
             prior_op_subs: SubStorage::new(),
             account_subs: SubStorage::new(),
+            web3_subs,
         }
     }
 
@@ -64,6 +70,14 @@ impl OperationNotifier {
                     self.add_account_update_sub(address, action, subscriber)
                         .await
                 }
+                EventSubscribeRequest::Web3 {
+                    kind,
+                    filter,
+                    subscriber,
+                } => match &mut self.web3_subs {
+                    Some(web3_subs) => web3_subs.add(kind, filter, subscriber),
+                    None => Err(anyhow::format_err!("Web3 subscriptions are not supported")),
+                },
             }
             .map_err(|e| anyhow::format_err!("Failed to add sub: {}", e)),
             EventNotifierRequest::Unsub(sub_id) => self
@@ -101,9 +115,18 @@ impl OperationNotifier {
 
             for id in updated_accounts {
                 if self.account_subs.subscriber_exists(id, action) {
-                    let account_state = match self.state.get_account_state(id, action).await? {
-                        Some(account_state) => account_state,
-                        None => {
+                    let account_state = match self.state.get_account_state(id, action).await {
+                        Ok(Some(account_state)) => account_state,
+                        Err(err) => {
+                            vlog::warn!(
+                                "Failed to load account state, id: {}, block: {}, reason: {}",
+                                *id,
+                                *block.block_number,
+                                err
+                            );
+                            continue;
+                        }
+                        Ok(None) => {
                             vlog::warn!(
                                 "Account is updated but not stored in DB, id: {}, block: {:#?}",
                                 *id,
@@ -116,6 +139,17 @@ impl OperationNotifier {
                     self.account_subs.notify(id, action, account_state);
                 }
             }
+
+            // Web3 API treats finalized blocks as the latest ones.
+            if let (Some(web3_subs), ActionType::VERIFY) = (&mut self.web3_subs, action) {
+                if let Err(err) = web3_subs.handle_new_block(block.block_number).await {
+                    vlog::warn!(
+                        "Failed to notify web3 subscribers, block: {}, reason: {}",
+                        *block.block_number,
+                        err
+                    );
+                }
+            }
         }
 
         metrics::histogram!("api.notifier.handle_new_block", start.elapsed());
@@ -178,6 +212,11 @@ impl OperationNotifier {
 
     /// Removes provided subscription from the list.
     fn handle_unsub(&mut self, sub_id: SubscriptionId) -> Result<(), anyhow::Error> {
+        if let Some(web3_subs) = &mut self.web3_subs {
+            if web3_subs.remove(&sub_id) {
+                return Ok(());
+            }
+        }
         self.prior_op_subs.remove(sub_id.clone())?;
         self.tx_subs.remove(sub_id.clone())?;
         self.account_subs.remove(sub_id)?;
@@ -1,16 +1,21 @@
 //! API server handles endpoints for interaction with node.
 //!
+//! `mod admin_server` - api is used to manage the fee subsidy rules.
 //! `mod rest` - api is used for block explorer.
 //! `mod rpc_server` - JSON rpc via HTTP (for request reply functions)
 //! `mod rpc_subscriptions` - JSON rpc via WebSocket (for request reply functions and subscriptions)
 
+mod account_proof;
+pub mod admin_server;
 mod event_notify;
 pub mod forced_exit_checker;
 mod helpers;
 pub mod rest;
 pub mod rpc_server;
 pub mod rpc_subscriptions;
+mod subsidies;
 mod tx_sender;
+mod tx_simulator;
 pub mod web3;
 
 /// Amount of threads used by each server to serve requests.
@@ -10,7 +10,10 @@ use actix_web::{web, Scope};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    account::{Account, AccountAddressOrId, AccountState, IncomingAccountTxsQuery},
+    account::{
+        Account, AccountAddressOrId, AccountBalanceProof, AccountProofQuery, AccountState,
+        IncomingAccountTxsQuery,
+    },
     pagination::{
         parse_query, AccountTxsRequest, ApiEither, Paginated, PaginationQuery, PendingOpsRequest,
     },
@@ -27,21 +30,35 @@ use super::{
     paginate_trait::Paginate,
     response::ApiResult,
 };
-use crate::{api_server::helpers::get_depositing, api_try, fee_ticker::PriceError};
+use crate::{
+    api_server::{
+        account_proof::{account_balance_proof, AccountTreeCache},
+        helpers::get_depositing,
+    },
+    api_try,
+    fee_ticker::PriceError,
+};
 
 /// Shared data between `api/v02/accounts` endpoints.
 #[derive(Clone)]
 struct ApiAccountData {
     pool: ConnectionPool,
     tokens: TokenDBCache,
+    account_trees: AccountTreeCache,
     confirmations_for_eth_event: u64,
 }
 
 impl ApiAccountData {
-    fn new(pool: ConnectionPool, tokens: TokenDBCache, confirmations_for_eth_event: u64) -> Self {
+    fn new(
+        pool: ConnectionPool,
+        tokens: TokenDBCache,
+        account_trees: AccountTreeCache,
+        confirmations_for_eth_event: u64,
+    ) -> Self {
         Self {
             pool,
             tokens,
+            account_trees,
             confirmations_for_eth_event,
         }
     }
@@ -345,6 +362,30 @@ impl ApiAccountData {
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
         storage.paginate_checked(&new_query).await
     }
+
+    async fn account_proof(
+        &self,
+        account_id: AccountId,
+        token_like: TokenLike,
+        block: Option<BlockNumber>,
+    ) -> Result<AccountBalanceProof, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let token = self
+            .tokens
+            .get_token(&mut storage, token_like.clone())
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))?;
+        account_balance_proof(
+            &mut storage,
+            &self.account_trees,
+            account_id,
+            token.id,
+            block,
+        )
+        .await
+        .map_err(Error::from)
+    }
 }
 
 async fn account_committed_info(
@@ -451,12 +492,32 @@ async fn account_pending_txs(
     res
 }
 
+async fn account_proof(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<AccountProofQuery>,
+) -> ApiResult<AccountBalanceProof> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let account_id =
+        api_try!(account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound)));
+    let token_like = TokenLike::parse(&query.token);
+    let res = data
+        .account_proof(account_id, token_like, query.block)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_proof");
+    res
+}
+
 pub fn api_scope(
     pool: ConnectionPool,
     tokens: TokenDBCache,
+    account_trees: AccountTreeCache,
     confirmations_for_eth_event: u64,
 ) -> Scope {
-    let data = ApiAccountData::new(pool, tokens, confirmations_for_eth_event);
+    let data = ApiAccountData::new(pool, tokens, account_trees, confirmations_for_eth_event);
 
     web::scope("accounts")
         .app_data(web::Data::new(data))
@@ -477,6 +538,10 @@ pub fn api_scope(
             "{account_id_or_address}/transactions/pending",
             web::get().to(account_pending_txs),
         )
+        .route(
+            "{account_id_or_address}/proof",
+            web::get().to(account_proof),
+        )
 }
 
 #[cfg(test)]
@@ -534,6 +599,7 @@ mod tests {
                         TokenDBCache::new(
                             cfg.config.api.token_config.invalidate_token_cache_period(),
                         ),
+                        AccountTreeCache::new(),
                         cfg.config.eth_watch.confirmations_for_eth_event,
                     )
                 },
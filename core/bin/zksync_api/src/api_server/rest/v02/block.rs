@@ -9,8 +9,10 @@ use actix_web::{web, Scope};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    block::{BlockInfo, BlockStatus},
-    pagination::{parse_query, ApiEither, BlockAndTxHash, Paginated, PaginationQuery},
+    block::{AccountStateChange, BlockInfo, BlockStatus},
+    pagination::{
+        parse_query, ApiEither, BlockAndTxHash, BlockAndUpdateIndex, Paginated, PaginationQuery,
+    },
     transaction::{Transaction, TxData, TxHashSerializeWrapper},
 };
 use zksync_crypto::{convert::FeConvert, Fr};
@@ -127,6 +129,34 @@ impl ApiBlockData {
         storage.paginate_checked(&new_query).await
     }
 
+    async fn state_diff_page(
+        &self,
+        block_number: BlockNumber,
+        query: PaginationQuery<ApiEither<u32>>,
+    ) -> Result<Paginated<AccountStateChange, u32>, Error> {
+        // Block zero has no state diff and uncommitted blocks may still be changed.
+        let last_committed = self
+            .get_last_committed_block_number()
+            .await
+            .map_err(Error::storage)?;
+        if *block_number == 0 || block_number > last_committed {
+            return Err(Error::from(InvalidDataError::InvalidStateDiffBlock));
+        }
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+
+        let new_query = PaginationQuery {
+            from: BlockAndUpdateIndex {
+                block_number,
+                update_index: query.from,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+
+        storage.paginate_checked(&new_query).await
+    }
+
     async fn tx_data(
         &self,
         block_number: BlockNumber,
@@ -199,6 +229,19 @@ async fn block_transactions(
     res
 }
 
+async fn block_state_diff(
+    data: web::Data<ApiBlockData>,
+    block_position: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<AccountStateChange, u32>> {
+    let start = Instant::now();
+    let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
+    let query = api_try!(parse_query(query).map_err(Error::from));
+    let res = data.state_diff_page(block_number, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_state_diff");
+    res
+}
+
 async fn transaction_in_block(
     data: web::Data<ApiBlockData>,
     path: web::Path<(BlockNumber, u64)>,
@@ -221,6 +264,10 @@ pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
             "{block_position}/transactions",
             web::get().to(block_transactions),
         )
+        .route(
+            "{block_position}/state-diff",
+            web::get().to(block_state_diff),
+        )
         .route(
             "{block_position}/transactions/{block_index}",
             web::get().to(transaction_in_block),
@@ -319,6 +366,66 @@ mod tests {
             }
         }
 
+        let expected_updates = {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .chain()
+                .state_schema()
+                .load_state_diff_for_block(block_number)
+                .await?
+        };
+        assert!(expected_updates.len() >= 3);
+        let query = PaginationQuery {
+            from: ApiEither::from(1),
+            limit: 2,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client
+            .block_state_diff(&query, &*block_number.to_string())
+            .await?;
+        let paginated: Paginated<AccountStateChange, u32> = deserialize_response_result(response)?;
+        assert_eq!(paginated.pagination.count as usize, expected_updates.len());
+        assert_eq!(paginated.pagination.from, 1);
+        assert_eq!(paginated.list.len(), query.limit as usize);
+        for (change, (account_id, update)) in paginated
+            .list
+            .into_iter()
+            .zip(expected_updates.into_iter().skip(1))
+        {
+            assert_eq!(change.account_id, account_id);
+            assert_eq!(change.update, update.into());
+        }
+
+        let query = PaginationQuery {
+            from: ApiEither::from(expected_updates.len() as u32 - 1),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .block_state_diff(&query, &*block_number.to_string())
+            .await?;
+        let paginated: Paginated<AccountStateChange, u32> = deserialize_response_result(response)?;
+        assert_eq!(
+            paginated.pagination.from as usize,
+            expected_updates.len() - 1
+        );
+        assert_eq!(paginated.list.len(), query.limit as usize);
+        for (change, (account_id, update)) in paginated
+            .list
+            .into_iter()
+            .zip(expected_updates.iter().cloned().rev())
+        {
+            assert_eq!(change.account_id, account_id);
+            assert_eq!(change.update, update.into());
+        }
+
+        let expected_error = Error::from(InvalidDataError::InvalidStateDiffBlock);
+        for block_position in &["0", "1000"] {
+            let response = client.block_state_diff(&query, block_position).await?;
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(error, expected_error);
+        }
+
         for expected_tx in expected_txs {
             if !expected_tx.success {
                 continue;
@@ -7,11 +7,17 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use thiserror::Error;
 
 // Workspace uses
-use zksync_api_types::v02::pagination::{UnknownFromParameter, MAX_LIMIT};
+use zksync_api_types::v02::{
+    pagination::{UnknownFromParameter, MAX_LIMIT},
+    token::MAX_PRICE_HISTORY_POINTS,
+};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 
 // Local uses
-use crate::{api_server::tx_sender::SubmitError, fee_ticker::PriceError};
+use crate::{
+    api_server::{account_proof::AccountProofError, tx_sender::SubmitError},
+    fee_ticker::PriceError,
+};
 
 #[derive(Serialize_repr, Debug, Deserialize_repr, Clone, PartialEq)]
 #[repr(u16)]
@@ -27,6 +33,9 @@ pub enum ErrorCode {
     PaginationLimitTooBig = 206,
     QueryDeserializationError = 207,
     InvalidNFTTokenId = 208,
+    BlockNotFinalized = 209,
+    InvalidPriceHistoryRange = 210,
+    InvalidStateDiffBlock = 211,
     StorageError = 300,
     TokenNotFound = 500,
     ExternalApiError = 501,
@@ -39,6 +48,7 @@ pub enum ErrorCode {
     InappropriateFeeToken = 606,
     CommunicationCoreServer = 607,
     Toggle2FAError = 608,
+    InvalidFeeQuote = 609,
     Other = 60_000,
 }
 
@@ -103,6 +113,13 @@ pub enum InvalidDataError {
     PaginationLimitTooBig,
     #[error("NFT token ID should be greater than or equal to {}", MIN_NFT_TOKEN_ID)]
     InvalidNFTTokenId,
+    #[error(
+        "Price history range should be non-empty and contain no more than {} points",
+        MAX_PRICE_HISTORY_POINTS
+    )]
+    InvalidPriceHistoryRange,
+    #[error("State diff is only available for committed blocks starting from the first one")]
+    InvalidStateDiffBlock,
 }
 
 impl ApiError for InvalidDataError {
@@ -120,6 +137,8 @@ impl ApiError for InvalidDataError {
             Self::TransactionNotFound => ErrorCode::TransactionNotFound,
             Self::PaginationLimitTooBig => ErrorCode::PaginationLimitTooBig,
             Self::InvalidNFTTokenId => ErrorCode::InvalidNFTTokenId,
+            Self::InvalidPriceHistoryRange => ErrorCode::InvalidPriceHistoryRange,
+            Self::InvalidStateDiffBlock => ErrorCode::InvalidStateDiffBlock,
         }
     }
 }
@@ -197,6 +216,7 @@ impl ApiError for SubmitError {
             Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
             Self::TxAdd(_) => ErrorCode::TxAddError,
             Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
+            Self::InvalidFeeQuote(_) => ErrorCode::InvalidFeeQuote,
             Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
             Self::Internal(_) => ErrorCode::InternalError,
             Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
@@ -206,6 +226,23 @@ impl ApiError for SubmitError {
     }
 }
 
+impl ApiError for AccountProofError {
+    fn error_type(&self) -> String {
+        match self {
+            Self::Internal(_) => String::from("storageError"),
+            _ => String::from("invalidDataError"),
+        }
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::BlockNotFinalized(_) => ErrorCode::BlockNotFinalized,
+            Self::AccountNotFound => ErrorCode::AccountNotFound,
+            Self::Internal(_) => ErrorCode::StorageError,
+        }
+    }
+}
+
 impl ApiError for PriceError {
     fn error_type(&self) -> String {
         String::from("tokenError")
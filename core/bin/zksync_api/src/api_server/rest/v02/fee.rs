@@ -45,17 +45,28 @@ async fn get_tx_fee(
     if !token_allowed {
         return Error::from(SubmitError::InappropriateFeeToken).into();
     }
+    let tx_type = body.tx_type.into();
     // TODO implement subsidies for v02 api ZKS-888
-    let res = data
+    let mut fee: ApiFee = api_try!(data
         .tx_sender
         .ticker
-        .get_fee_from_ticker_in_wei(body.tx_type.into(), body.token_like, body.address)
+        .get_fee_from_ticker_in_wei(tx_type, body.token_like.clone(), body.address)
         .await
         .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map_err(Error::from));
+    if body.bind_quote {
+        fee.quote = data
+            .tx_sender
+            .ticker
+            .bind_fee_quote(
+                body.token_like,
+                vec![(tx_type, body.address)],
+                fee.total_fee.clone(),
+            )
+            .await;
+    }
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_tx_fee");
-    res
+    ApiResult::Ok(fee)
 }
 
 async fn get_batch_fee(
@@ -72,21 +83,27 @@ async fn get_batch_fee(
     if !token_allowed {
         return Error::from(SubmitError::InappropriateFeeToken).into();
     }
-    let txs = body
+    let txs: Vec<_> = body
         .transactions
         .into_iter()
         .map(|tx| (tx.tx_type.into(), tx.address))
         .collect();
-    let res = data
+    let mut fee: ApiFee = api_try!(data
         .tx_sender
         .ticker
-        .get_batch_from_ticker_in_wei(body.token_like, txs)
+        .get_batch_from_ticker_in_wei(body.token_like.clone(), txs.clone())
         .await
         .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map_err(Error::from));
+    if body.bind_quote {
+        fee.quote = data
+            .tx_sender
+            .ticker
+            .bind_fee_quote(body.token_like, txs, fee.total_fee.clone())
+            .await;
+    }
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch_fee");
-    res
+    ApiResult::Ok(fee)
 }
 
 pub fn api_scope(tx_sender: TxSender) -> Scope {
@@ -182,7 +199,7 @@ mod tests {
         let not_allowed_token = TokenLike::Id(TokenId(1));
 
         let response = client
-            .get_txs_fee(tx_type.clone(), address, not_allowed_token)
+            .get_txs_fee(tx_type.clone(), address, not_allowed_token, false)
             .await?;
         let expected_error = Error::from(SubmitError::InappropriateFeeToken);
         let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
@@ -191,7 +208,7 @@ mod tests {
         let allowed_token = TokenLike::Id(TokenId(2));
 
         let response = client
-            .get_txs_fee(tx_type, address, allowed_token.clone())
+            .get_txs_fee(tx_type, address, allowed_token.clone(), false)
             .await?;
         let api_fee: ApiFee = deserialize_response_result(response)?;
         assert_eq!(api_fee.gas_fee, BigUint::from(1u32));
@@ -204,7 +221,7 @@ mod tests {
         };
         let txs = vec![tx.clone(), tx.clone(), tx];
 
-        let response = client.get_batch_fee(txs, allowed_token).await?;
+        let response = client.get_batch_fee(txs, allowed_token, false).await?;
         let api_batch_fee: ApiFee = deserialize_response_result(response)?;
         assert_eq!(api_batch_fee.gas_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.zkp_fee, BigUint::from(1u32));
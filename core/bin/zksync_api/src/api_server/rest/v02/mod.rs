@@ -46,6 +46,7 @@ pub(crate) fn api_scope(
         .service(account::api_scope(
             tx_sender.pool.clone(),
             tx_sender.tokens.clone(),
+            tx_sender.account_trees.clone(),
             zk_config.eth_watch.confirmations_for_eth_event,
         ))
         .service(block::api_scope(
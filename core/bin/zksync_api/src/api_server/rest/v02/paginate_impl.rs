@@ -5,10 +5,10 @@
 // Workspace uses
 use zksync_api_types::{
     v02::{
-        block::BlockInfo,
+        block::{AccountStateChange, BlockInfo},
         pagination::{
-            AccountTxsRequest, ApiEither, BlockAndTxHash, Paginated, PaginationQuery,
-            PendingOpsRequest,
+            AccountTxsRequest, ApiEither, BlockAndTxHash, BlockAndUpdateIndex, Paginated,
+            PaginationDirection, PaginationQuery, PendingOpsRequest,
         },
         transaction::{Transaction, TxHashSerializeWrapper},
     },
@@ -194,6 +194,84 @@ impl Paginate<BlockAndTxHash> for StorageProcessor<'_> {
     }
 }
 
+#[async_trait::async_trait]
+impl Paginate<BlockAndUpdateIndex> for StorageProcessor<'_> {
+    type OutputObj = AccountStateChange;
+    type OutputId = u32;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<BlockAndUpdateIndex>,
+    ) -> Result<Paginated<AccountStateChange, u32>, Error> {
+        let block_number = query.from.block_number;
+        let count = self
+            .chain()
+            .state_schema()
+            .get_state_diff_count_for_block(block_number)
+            .await
+            .map_err(Error::storage)?;
+
+        let update_index = match query.from.update_index.inner {
+            Either::Left(update_index) => update_index,
+            Either::Right(_) => {
+                if count == 0 {
+                    return Ok(Paginated::new(
+                        Vec::new(),
+                        Default::default(),
+                        query.limit,
+                        query.direction,
+                        0,
+                    ));
+                }
+                count - 1
+            }
+        };
+
+        let (offset, limit) = match query.direction {
+            PaginationDirection::Newer => (update_index, query.limit),
+            PaginationDirection::Older => {
+                let end = if update_index < count {
+                    update_index + 1
+                } else {
+                    0
+                };
+                let start = end.saturating_sub(query.limit);
+                (start, end - start)
+            }
+        };
+
+        let updates = if limit > 0 {
+            self.chain()
+                .state_schema()
+                .load_state_diff_page_for_block(block_number, offset, limit)
+                .await
+                .map_err(Error::storage)?
+        } else {
+            Vec::new()
+        };
+        let mut list: Vec<_> = updates
+            .into_iter()
+            .enumerate()
+            .map(|(index, (account_id, update))| AccountStateChange {
+                update_index: offset + index as u32,
+                account_id,
+                update: update.into(),
+            })
+            .collect();
+        if query.direction == PaginationDirection::Older {
+            list.reverse();
+        }
+
+        Ok(Paginated::new(
+            list,
+            update_index,
+            query.limit,
+            query.direction,
+            count,
+        ))
+    }
+}
+
 #[async_trait::async_trait]
 impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
     type OutputObj = Transaction;
@@ -3,6 +3,8 @@
 // Built-in uses
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 // External uses
 use actix_web::{web, App, Scope};
@@ -29,6 +31,7 @@ use zksync_storage::{
         gen_unique_aggregated_operation_with_txs, generate_nft, get_sample_aggregated_proof,
         get_sample_single_proof, BLOCK_SIZE_CHUNKS,
     },
+    ticker::records::{FeeQuoteSnapshot, NewFeeQuote, StoredFeeQuote},
     ConnectionPool,
 };
 use zksync_test_account::ZkSyncAccount;
@@ -37,7 +40,7 @@ use zksync_types::{
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
     operations::{ChangePubKeyOp, TransferToNewOp},
     prover::ProverJobType,
-    tx::ChangePubKeyType,
+    tx::{ChangePubKeyType, TxHash},
     AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
     ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, FullExitOp, MintNFTOp, Nonce,
     PriorityOp, Token, TokenId, TokenKind, TokenLike, TokenPrice, Transfer, TransferOp, ZkSyncOp,
@@ -48,7 +51,7 @@ use zksync_utils::{big_decimal_to_ratio, scaled_u64_to_ratio, UnsignedRatioSeria
 // Local uses
 use crate::fee_ticker::{
     tests::TestToken,
-    ticker_info::BlocksInFutureAggregatedOperations,
+    ticker_info::{BlocksInFutureAggregatedOperations, GasCalibration},
     validator::{cache::TokenInMemoryCache, FeeTokenValidator},
     {FeeTicker, FeeTickerInfo, GasOperationsCost, PriceError, TickerConfig},
 };
@@ -499,6 +502,7 @@ impl TestServerConfig {
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
                     Default::default(),
                 )
                 .await?;
@@ -585,6 +589,7 @@ impl TestServerConfig {
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -626,6 +631,7 @@ impl TestServerConfig {
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -832,6 +838,7 @@ pub fn dummy_sign_verifier() -> mpsc::Sender<VerifySignatureRequest> {
 #[derive(Debug, Clone)]
 pub struct DummyFeeTickerInfo {
     prices: HashMap<TokenLike, BigDecimal>,
+    quotes: Arc<Mutex<Vec<StoredFeeQuote>>>,
 }
 
 #[async_trait::async_trait]
@@ -872,6 +879,10 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
         Ok(BigUint::from(1u64))
     }
 
+    async fn get_gas_calibration(&self) -> Result<Option<GasCalibration>, Error> {
+        Ok(None)
+    }
+
     async fn get_token(&self, token: TokenLike) -> Result<Token, Error> {
         Ok(match token {
             TokenLike::Id(id) => Token {
@@ -889,6 +900,55 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
         })
     }
 
+    async fn store_fee_quote_snapshot(&self, _snapshot: FeeQuoteSnapshot) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn store_fee_quote(&self, quote: NewFeeQuote) -> anyhow::Result<i64> {
+        let mut quotes = self.quotes.lock().await;
+        let id = quotes.len() as i64 + 1;
+        quotes.push(StoredFeeQuote {
+            id,
+            token_id: quote.token_id,
+            txs: quote.txs,
+            total_fee: quote.total_fee,
+            created_at: Utc::now(),
+            expires_at: quote.expires_at,
+            used_by_tx_hash: None,
+            used_at: None,
+        });
+        Ok(id)
+    }
+
+    async fn load_fee_quote(&self, id: i64) -> anyhow::Result<Option<StoredFeeQuote>> {
+        let quotes = self.quotes.lock().await;
+        Ok(quotes.iter().find(|quote| quote.id == id).cloned())
+    }
+
+    async fn claim_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let mut quotes = self.quotes.lock().await;
+        match quotes.iter_mut().find(|quote| quote.id == id) {
+            Some(quote) if quote.used_at.is_none() && quote.expires_at > Utc::now() => {
+                quote.used_by_tx_hash = Some(tx_hash.as_ref().to_vec());
+                quote.used_at = Some(Utc::now());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn release_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let mut quotes = self.quotes.lock().await;
+        match quotes.iter_mut().find(|quote| quote.id == id) {
+            Some(quote) if quote.used_by_tx_hash.as_deref() == Some(tx_hash.as_ref()) => {
+                quote.used_by_tx_hash = None;
+                quote.used_at = None;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
@@ -912,11 +972,22 @@ pub fn get_test_ticker_config() -> TickerConfig {
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
+        fee_quote_ttl: Duration::from_secs(0),
+        calibrate_gas_costs: false,
     }
 }
 pub fn dummy_fee_ticker(
     prices: &[(TokenLike, BigDecimal)],
     in_memory_cache: Option<TokenInMemoryCache>,
+) -> FeeTicker {
+    dummy_fee_ticker_with_quotes(prices, in_memory_cache, Default::default())
+}
+
+/// Creates the dummy fee ticker keeping the fee quotes in the provided storage.
+pub fn dummy_fee_ticker_with_quotes(
+    prices: &[(TokenLike, BigDecimal)],
+    in_memory_cache: Option<TokenInMemoryCache>,
+    quotes: Arc<Mutex<Vec<StoredFeeQuote>>>,
 ) -> FeeTicker {
     let prices: HashMap<_, _> = prices.iter().cloned().collect();
     let validator = FeeTokenValidator::new(
@@ -927,7 +998,7 @@ pub fn dummy_fee_ticker(
     );
 
     FeeTicker::new(
-        Box::new(DummyFeeTickerInfo { prices }),
+        Box::new(DummyFeeTickerInfo { prices, quotes }),
         get_test_ticker_config(),
         validator,
     )
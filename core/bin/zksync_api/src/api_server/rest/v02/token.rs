@@ -2,7 +2,7 @@
 
 // Built-in uses
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 // External uses
 use actix_web::{
@@ -10,18 +10,23 @@ use actix_web::{
     Scope,
 };
 use bigdecimal::{BigDecimal, Zero};
+use chrono::{TimeZone, Utc};
 use num::{rational::Ratio, BigUint, FromPrimitive};
 
 // Workspace uses
 use zksync_api_types::v02::{
     pagination::{parse_query, ApiEither, Paginated, PaginationQuery},
-    token::{ApiNFT, ApiToken, TokenPrice},
+    token::{
+        ApiNFT, ApiToken, PriceHistoryQuery, PriceSample, TokenPrice, TokenPriceHistory,
+        MAX_PRICE_HISTORY_POINTS,
+    },
 };
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{tx::TxHash, AccountId, Token, TokenId, TokenLike};
+use zksync_utils::ratio_to_big_decimal;
 
 // Local uses
 use super::{
@@ -34,6 +39,9 @@ use crate::{
     fee_ticker::{FeeTicker, PriceError, TokenPriceRequestType},
 };
 
+/// Number of decimal digits of the prices returned by the price history endpoint.
+const PRICE_HISTORY_PRECISION: usize = 6;
+
 /// Shared data between `api/v0.2/tokens` endpoints.
 #[derive(Clone)]
 struct ApiTokenData {
@@ -152,6 +160,55 @@ impl ApiTokenData {
         ))
     }
 
+    async fn token_price_history(
+        &self,
+        token_like: TokenLike,
+        query: PriceHistoryQuery,
+    ) -> Result<TokenPriceHistory, Error> {
+        let invalid_range = || Error::from(InvalidDataError::InvalidPriceHistoryRange);
+        if query.resolution <= 0 || query.from >= query.to {
+            return Err(invalid_range());
+        }
+        let points = query.to.checked_sub(query.from).ok_or_else(invalid_range)? / query.resolution;
+        if points >= MAX_PRICE_HISTORY_POINTS {
+            return Err(invalid_range());
+        }
+        let from = Utc
+            .timestamp_opt(query.from, 0)
+            .single()
+            .ok_or_else(invalid_range)?;
+        let to = Utc
+            .timestamp_opt(query.to, 0)
+            .single()
+            .ok_or_else(invalid_range)?;
+        let token = self.token(token_like).await?;
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let prices = storage
+            .ticker_schema()
+            .load_price_history(
+                token.id,
+                from,
+                to,
+                Duration::from_secs(query.resolution as u64),
+            )
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(TokenPriceHistory {
+            token_id: token.id,
+            token_symbol: token.symbol,
+            resolution: query.resolution,
+            prices: prices
+                .into_iter()
+                .map(|price| PriceSample {
+                    timestamp: price.last_updated,
+                    price: ratio_to_big_decimal(&price.usd_price, PRICE_HISTORY_PRECISION),
+                })
+                .collect(),
+        })
+    }
+
     async fn token_price_usd(&self, token: TokenLike) -> Result<BigDecimal, Error> {
         self.fee_ticker
             .get_token_price(token, TokenPriceRequestType::USDForOneToken)
@@ -235,6 +292,18 @@ async fn token_price(
     })
 }
 
+async fn token_price_history(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+    web::Query(query): web::Query<PriceHistoryQuery>,
+) -> ApiResult<TokenPriceHistory> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&token_like_string);
+    let res = data.token_price_history(token_like, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_price_history");
+    res
+}
+
 async fn get_nft(
     data: web::Data<ApiTokenData>,
     id: web::Path<TokenId>,
@@ -304,6 +373,10 @@ pub fn api_scope(
             "{token_like}/priceIn/{currency}",
             web::get().to(token_price),
         )
+        .route(
+            "{token_like}/price-history",
+            web::get().to(token_price_history),
+        )
         .route("nft/{id}", web::get().to(get_nft))
         .route("nft/{id}/owner", web::get().to(get_nft_owner))
         .route(
@@ -436,7 +509,7 @@ mod tests {
         };
         let mut expected_token_price = TokenPrice {
             token_id: token.id,
-            token_symbol: token.symbol,
+            token_symbol: token.symbol.clone(),
             price_in: String::from("15"),
             decimals: token.decimals,
             price: BigDecimal::from_u32(10).unwrap() / BigDecimal::from_u32(10500).unwrap(),
@@ -456,6 +529,58 @@ mod tests {
         let response = client.token_price(&token_like, "333").await?;
         assert!(response.error.is_some());
 
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            for (price, timestamp) in &[(1u32, 3600), (3, 3660), (5, 7200)] {
+                let price = zksync_types::TokenPrice {
+                    usd_price: Ratio::from_integer(BigUint::from(*price)),
+                    last_updated: Utc.timestamp(*timestamp, 0),
+                };
+                storage
+                    .ticker_schema()
+                    .store_price_sample(token.id, &price)
+                    .await?;
+            }
+        }
+        let query = PriceHistoryQuery {
+            from: 0,
+            to: 10_000,
+            resolution: 3600,
+        };
+        let response = client.token_price_history(&token_like, &query).await?;
+        let history: TokenPriceHistory = deserialize_response_result(response)?;
+        let expected_prices = vec![
+            PriceSample {
+                timestamp: Utc.timestamp(3600, 0),
+                price: BigDecimal::from(2),
+            },
+            PriceSample {
+                timestamp: Utc.timestamp(7200, 0),
+                price: BigDecimal::from(5),
+            },
+        ];
+        assert_eq!(history.token_id, token.id);
+        assert_eq!(history.prices, expected_prices);
+
+        let query = PriceHistoryQuery {
+            from: 0,
+            to: 10_000,
+            resolution: 1,
+        };
+        let response = client.token_price_history(&token_like, &query).await?;
+        assert!(response.error.is_some());
+
+        // Ranges that overflow or exceed the supported timestamps are rejected.
+        for (from, to) in &[(i64::MIN, i64::MAX), (0, i64::MAX)] {
+            let query = PriceHistoryQuery {
+                from: *from,
+                to: *to,
+                resolution: i64::MAX,
+            };
+            let response = client.token_price_history(&token_like, &query).await?;
+            assert!(response.error.is_some());
+        }
+
         let nft_id = TokenId(65542);
         let response = client.nft_by_id(nft_id).await?;
         let nft: ApiNFT = deserialize_response_result(response)?;
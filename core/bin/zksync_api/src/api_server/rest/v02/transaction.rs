@@ -9,13 +9,10 @@ use actix_web::{
 };
 
 // Workspace uses
-use zksync_api_types::{
-    v02::transaction::{
-        ApiTxBatch, IncomingTxBatch, L1Receipt, L1Transaction, Receipt, SubmitBatchResponse,
-        Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
-        TxInBlockStatus,
-    },
-    TxWithSignature,
+use zksync_api_types::v02::transaction::{
+    ApiTxBatch, IncomingTx, IncomingTxBatch, L1Receipt, L1Transaction, L2Receipt, Receipt,
+    SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData,
+    TxHashSerializeWrapper, TxInBlockStatus, TxSimulationRequest, TxSimulationResult,
 };
 use zksync_types::{tx::TxHash, EthBlockId};
 
@@ -68,7 +65,37 @@ impl ApiTransactionData {
                 id: op.serial_id,
             })))
         }
-        // 3. No operation found, return nothing.
+        // 3. Try to find the transaction that was replaced by another one.
+        else if let Some(replaced_by) = storage
+            .chain()
+            .mempool_schema()
+            .get_replacement_tx_hash(tx_hash)
+            .await
+            .map_err(Error::storage)?
+        {
+            Ok(Some(Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status: TxInBlockStatus::Replaced,
+                fail_reason: Some(format!("Replaced by transaction {}", replaced_by)),
+            })))
+        }
+        // 4. Try to find the transaction that was evicted from the full mempool.
+        else if storage
+            .chain()
+            .mempool_schema()
+            .is_tx_evicted(tx_hash)
+            .await
+            .map_err(Error::storage)?
+        {
+            Ok(Some(Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status: TxInBlockStatus::Rejected,
+                fail_reason: Some("Evicted from the full mempool".to_string()),
+            })))
+        }
+        // 5. No operation found, return nothing.
         else {
             Ok(None)
         }
@@ -162,12 +189,12 @@ async fn tx_data(
 
 async fn submit_tx(
     data: web::Data<ApiTransactionData>,
-    Json(body): Json<TxWithSignature>,
+    Json(body): Json<IncomingTx>,
 ) -> ApiResult<TxHashSerializeWrapper> {
     let start = Instant::now();
     let tx_hash = data
         .tx_sender
-        .submit_tx(body.tx, body.signature, None)
+        .submit_tx(body.tx, body.signature, body.fee_quote_id, None)
         .await;
 
     if let Err(err) = &tx_hash {
@@ -192,7 +219,7 @@ async fn submit_batch(
     let start = Instant::now();
     let response = data
         .tx_sender
-        .submit_txs_batch(body.txs, body.signature, None)
+        .submit_txs_batch(body.txs, body.signature, body.fee_quote_id, None)
         .await;
 
     if let Err(err) = &response {
@@ -225,6 +252,21 @@ async fn toggle_2fa(
     response.into()
 }
 
+async fn simulate(
+    data: web::Data<ApiTransactionData>,
+    Json(body): Json<TxSimulationRequest>,
+) -> ApiResult<TxSimulationResult> {
+    let start = Instant::now();
+    let result = match body {
+        TxSimulationRequest::Tx { tx } => data.tx_sender.simulate_tx(*tx).await,
+        TxSimulationRequest::Batch { txs } => data.tx_sender.simulate_txs_batch(txs).await,
+    }
+    .map_err(Error::from);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "simulate");
+    result.into()
+}
+
 async fn get_batch(
     data: web::Data<ApiTransactionData>,
     batch_hash: web::Path<TxHash>,
@@ -246,6 +288,7 @@ pub fn api_scope(tx_sender: TxSender) -> Scope {
         .route("/batches", web::post().to(submit_batch))
         .route("/batches/{batch_hash}", web::get().to(get_batch))
         .route("/toggle2FA", web::post().to(toggle_2fa))
+        .route("/simulate", web::post().to(simulate))
 }
 
 #[cfg(test)]
@@ -253,30 +296,39 @@ mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
         test_utils::{
-            deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
-            TestTransactions,
+            deserialize_response_result, dummy_fee_ticker, dummy_fee_ticker_with_quotes,
+            dummy_sign_verifier, TestServerConfig, TestTransactions, COMMITTED_BLOCKS_COUNT,
         },
         SharedData,
     };
     use crate::fee_ticker::validator::cache::TokenInMemoryCache;
     use chrono::Utc;
     use futures::{channel::mpsc, StreamExt};
-    use num::{rational::Ratio, BigUint};
+    use num::{rational::Ratio, BigUint, Zero};
     use std::collections::HashMap;
     use std::str::FromStr;
-    use tokio::task::JoinHandle;
-    use zksync_api_types::v02::{
-        transaction::{L2Receipt, TxHashSerializeWrapper},
-        ApiVersion,
+    use std::sync::Arc;
+    use tokio::{sync::Mutex, task::JoinHandle};
+    use zksync_api_types::{
+        v02::{
+            transaction::{L2Receipt, TxHashSerializeWrapper},
+            ApiVersion,
+        },
+        TxWithSignature,
     };
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_storage::{
+        subsidy_rules::records::StorageSubsidyRule, ticker::records::StoredFeeQuote,
+    };
+    use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
         tokens::{Token, TokenMarketVolume},
         tx::{
             EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
             TxEthSignatureVariant,
         },
-        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        AccountUpdate, Address, BlockNumber, Nonce, PubKeyHash, SignedZkSyncTx, TokenId, TokenKind,
+        TokenLike, TransferToNewOp, TxFeeTypes, ZkSyncTx,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -291,6 +343,9 @@ mod tests {
                     MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
+                    MempoolTransactionRequest::InvalidatePriorityOps(_, resp) => {
+                        resp.send(Ok(Vec::new())).unwrap_or_default()
+                    }
                     MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
                         resp.send(Ok(())).unwrap_or_default()
                     }
@@ -360,7 +415,7 @@ mod tests {
 
         let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
         let response = client
-            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None), None)
             .await?;
         let tx_hash: TxHash = deserialize_response_result(response)?;
         assert_eq!(tx.hash(), tx_hash);
@@ -406,11 +461,88 @@ mod tests {
         };
 
         let response = client
-            .submit_batch(good_batch.clone(), Some(batch_signature))
+            .submit_batch(good_batch.clone(), Some(batch_signature), None)
             .await?;
         let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
         assert_eq!(submit_batch_response, expected_response);
 
+        // Transactions of the unknown account can't be executed.
+        let response = client.simulate_tx(tx).await?;
+        let simulation: TxSimulationResult = deserialize_response_result(response)?;
+        assert!(!simulation.success);
+        assert!(simulation.fail_reason.is_some());
+        assert!(simulation.updates.is_empty());
+
+        let response = client
+            .simulate_batch(good_batch.iter().map(|tx| tx.tx.clone()).collect())
+            .await?;
+        let simulation: TxSimulationResult = deserialize_response_result(response)?;
+        assert!(!simulation.success);
+        assert_eq!(simulation.failed_tx_index, Some(0));
+
+        // Transactions of the committed account signed with its key are executed successfully.
+        let signer = ZkSyncAccount::rand();
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let account_id = storage
+                .chain()
+                .account_schema()
+                .next_free_account_id()
+                .await?;
+            signer.set_account_id(Some(account_id));
+            let updates = vec![
+                (
+                    account_id,
+                    AccountUpdate::Create {
+                        address: signer.address,
+                        nonce: Nonce(0),
+                    },
+                ),
+                (
+                    account_id,
+                    AccountUpdate::ChangePubKeyHash {
+                        old_pub_key_hash: PubKeyHash::default(),
+                        new_pub_key_hash: signer.pubkey_hash,
+                        old_nonce: Nonce(0),
+                        new_nonce: Nonce(0),
+                    },
+                ),
+                (
+                    account_id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: Nonce(0),
+                        new_nonce: Nonce(0),
+                        balance_update: (TokenId(0), 0u32.into(), 1_000_000u32.into()),
+                    },
+                ),
+            ];
+            storage
+                .chain()
+                .state_schema()
+                .commit_state_update(BlockNumber(COMMITTED_BLOCKS_COUNT), &updates, 0)
+                .await?;
+        }
+        let (transfer, _) = signer.sign_transfer(
+            TokenId(0),
+            "ETH",
+            10u32.into(),
+            100u32.into(),
+            &Address::random(),
+            None,
+            false,
+            Default::default(),
+        );
+        let response = client
+            .simulate_tx(ZkSyncTx::Transfer(Box::new(transfer)))
+            .await?;
+        let simulation: TxSimulationResult = deserialize_response_result(response)?;
+        assert!(simulation.success, "{:?}", simulation.fail_reason);
+        assert_eq!(simulation.chunks, TransferToNewOp::CHUNKS);
+        assert!(!simulation.updates.is_empty());
+        assert_eq!(simulation.fees.len(), 1);
+        assert_eq!(simulation.fees[0].token, TokenId(0));
+        assert_eq!(simulation.fees[0].amount, BigUint::from(100u32));
+
         {
             let mut storage = cfg.pool.access_storage().await?;
             let txs: Vec<_> = good_batch
@@ -493,6 +625,37 @@ mod tests {
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
         assert_eq!(tx_data.unwrap().tx.tx_hash, pending_tx_hash);
 
+        let replacement_tx_hash = {
+            let mut storage = cfg.pool.access_storage().await?;
+
+            let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+            let tx_hash = tx.hash();
+            let replaced = storage
+                .chain()
+                .mempool_schema()
+                .replace_tx(
+                    pending_tx_hash,
+                    &SignedZkSyncTx {
+                        tx,
+                        eth_sign_data: None,
+                        created_at: Utc::now(),
+                    },
+                )
+                .await?;
+            assert!(replaced);
+
+            tx_hash
+        };
+        let response = client.tx_status(pending_tx_hash).await?;
+        let tx_status: Receipt = deserialize_response_result(response)?;
+        let expected_tx_status = Receipt::L2(L2Receipt {
+            tx_hash: pending_tx_hash,
+            rollup_block: None,
+            status: TxInBlockStatus::Replaced,
+            fail_reason: Some(format!("Replaced by transaction {}", replacement_tx_hash)),
+        });
+        assert_eq!(tx_status, expected_tx_status);
+
         let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
         let response = client.tx_data(tx.hash()).await?;
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
@@ -502,4 +665,236 @@ mod tests {
         task.abort();
         Ok(())
     }
+
+    /// Checks that the transaction is rejected if the fee quote is expired, has already been used
+    /// or was issued for other token or transaction type.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn submit_tx_with_fee_quote() -> anyhow::Result<()> {
+        let (sender, _task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let quotes = Arc::new(Mutex::new(Vec::new()));
+        let tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker_with_quotes(&prices, Some(cache), quotes.clone()),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            sender,
+        );
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[1].0.clone();
+        let (tx_type, _, address, _) = tx.get_fee_info().unwrap();
+        let add_quote = |token_id: TokenId, tx_type: TxFeeTypes, expires_in: i64| {
+            let quotes = quotes.clone();
+            async move {
+                let mut quotes = quotes.lock().await;
+                let id = quotes.len() as i64 + 1;
+                quotes.push(StoredFeeQuote {
+                    id,
+                    token_id: *token_id as i32,
+                    txs: serde_json::to_value(vec![(tx_type, address)]).unwrap(),
+                    total_fee: 1u32.into(),
+                    created_at: Utc::now(),
+                    expires_at: Utc::now() + chrono::Duration::seconds(expires_in),
+                    used_by_tx_hash: None,
+                    used_at: None,
+                });
+                id
+            }
+        };
+        let submit = |fee_quote_id: i64| {
+            tx_sender.submit_tx(
+                tx.clone(),
+                TxEthSignatureVariant::Single(None),
+                Some(fee_quote_id),
+                None,
+            )
+        };
+
+        // The valid quote is claimed by the transaction.
+        let quote_id = add_quote(TokenId(0), tx_type, 60).await;
+        assert_eq!(submit(quote_id).await?, tx.hash());
+        assert_eq!(
+            quotes.lock().await[0].used_by_tx_hash,
+            Some(tx.hash().as_ref().to_vec())
+        );
+
+        // The quote can't be used twice.
+        let err = submit(quote_id).await.unwrap_err();
+        assert!(matches!(err, SubmitError::InvalidFeeQuote(_)), "{}", err);
+
+        // The expired quote is rejected.
+        let quote_id = add_quote(TokenId(0), tx_type, -60).await;
+        let err = submit(quote_id).await.unwrap_err();
+        assert!(matches!(err, SubmitError::InvalidFeeQuote(_)), "{}", err);
+
+        // The quote issued for other token is rejected.
+        let quote_id = add_quote(TokenId(1), tx_type, 60).await;
+        let err = submit(quote_id).await.unwrap_err();
+        assert!(matches!(err, SubmitError::InvalidFeeQuote(_)), "{}", err);
+
+        // The quote issued for other transaction type is rejected.
+        let quote_id = add_quote(TokenId(0), TxFeeTypes::Withdraw, 60).await;
+        let err = submit(quote_id).await.unwrap_err();
+        assert!(matches!(err, SubmitError::InvalidFeeQuote(_)), "{}", err);
+
+        // The rejected quotes are not claimed.
+        assert!(quotes.lock().await[1..]
+            .iter()
+            .all(|quote| quote.used_at.is_none()));
+
+        Ok(())
+    }
+
+    /// Checks that the transaction paying the quoted fee doesn't spend the subsidy budget and
+    /// is accepted even if the budget is exhausted.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn submit_tx_with_fee_quote_and_subsidy() -> anyhow::Result<()> {
+        const RULE_NAME: &str = "fee_quote_test";
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let quotes = Arc::new(Mutex::new(Vec::new()));
+
+        // The subsidy rules are cached, so every step uses a new sender.
+        let new_tx_sender = || {
+            let (sender, _task) = submit_txs_loopback();
+            TxSender::new(
+                cfg.pool.clone(),
+                dummy_sign_verifier(),
+                dummy_fee_ticker_with_quotes(&prices, Some(cache.clone()), quotes.clone()),
+                &cfg.config.api.common,
+                &cfg.config.api.token_config,
+                sender,
+            )
+        };
+        let store_rule = |sender: Address, budget_usd_scale6: i64| {
+            let pool = cfg.pool.clone();
+            async move {
+                pool.access_storage()
+                    .await?
+                    .subsidy_rules_schema()
+                    .store_subsidy_rule(StorageSubsidyRule {
+                        name: RULE_NAME.to_string(),
+                        priority: 0,
+                        enabled: true,
+                        tx_types: None,
+                        token_ids: None,
+                        // Transactions of the other tests are not subsidized.
+                        addresses: Some(vec![sender.as_bytes().to_vec()]),
+                        ips: None,
+                        valid_from: None,
+                        valid_until: None,
+                        max_fee_usd_scale6: 0,
+                        budget_usd_scale6,
+                        max_per_address_usd_scale6: None,
+                    })
+                    .await?;
+                Ok::<_, anyhow::Error>(())
+            }
+        };
+        let submit_with_quote = |tx_sender: TxSender, tx: ZkSyncTx| {
+            let quotes = quotes.clone();
+            async move {
+                let (tx_type, _, address, _) = tx.get_fee_info().unwrap();
+                let id = {
+                    let mut quotes = quotes.lock().await;
+                    let id = quotes.len() as i64 + 1;
+                    quotes.push(StoredFeeQuote {
+                        id,
+                        token_id: 0,
+                        txs: serde_json::to_value(vec![(tx_type, address)]).unwrap(),
+                        total_fee: 1u32.into(),
+                        created_at: Utc::now(),
+                        expires_at: Utc::now() + chrono::Duration::seconds(60),
+                        used_by_tx_hash: None,
+                        used_at: None,
+                    });
+                    id
+                };
+                tx_sender
+                    .submit_tx(tx, TxEthSignatureVariant::Single(None), Some(id), None)
+                    .await
+            }
+        };
+
+        // The provided fee covers the quoted fee only, but the subsidy is not spent.
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[1].0.clone();
+        store_rule(tx.account(), 1_000_000_000_000).await?;
+        assert_eq!(
+            submit_with_quote(new_tx_sender(), tx.clone()).await?,
+            tx.hash()
+        );
+        let used = cfg
+            .pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .get_total_used_subsidy_for_type(RULE_NAME)
+            .await?;
+        assert!(used.is_zero(), "{}", used);
+
+        // The quoted fee is enough if the subsidy budget is exhausted.
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[1].0.clone();
+        store_rule(tx.account(), 0).await?;
+        assert_eq!(
+            submit_with_quote(new_tx_sender(), tx.clone()).await?,
+            tx.hash()
+        );
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .subsidy_rules_schema()
+            .remove_subsidy_rule(RULE_NAME)
+            .await?;
+
+        Ok(())
+    }
 }
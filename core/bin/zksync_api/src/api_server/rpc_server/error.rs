@@ -3,7 +3,7 @@ use jsonrpc_core::ErrorCode;
 use zksync_types::tx::error::TxAddError;
 // Workspace uses
 // Local uses
-use crate::api_server::tx_sender::SubmitError;
+use crate::api_server::{account_proof::AccountProofError, tx_sender::SubmitError};
 
 #[derive(Debug, Clone, Copy)]
 pub enum RpcErrorCodes {
@@ -11,6 +11,11 @@ pub enum RpcErrorCodes {
     IncorrectTx = 103,
     FeeTooLow = 104,
     InappropriateFeeToken = 105,
+    ReplacementUnderpriced = 106,
+    TooManyPendingTxs = 107,
+    NonceGapTooLarge = 108,
+    MempoolIsFull = 109,
+    InvalidFeeQuote = 110,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
@@ -41,6 +46,10 @@ impl From<TxAddError> for RpcErrorCodes {
             TxAddError::BatchTooBig => Self::Other,
             TxAddError::BatchWithdrawalsOverload => Self::Other,
             TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::ReplacementUnderpriced => Self::ReplacementUnderpriced,
+            TxAddError::TooManyPendingTxs => Self::TooManyPendingTxs,
+            TxAddError::NonceGapTooLarge => Self::NonceGapTooLarge,
+            TxAddError::MempoolIsFull => Self::MempoolIsFull,
         }
     }
 }
@@ -87,6 +96,11 @@ impl From<SubmitError> for jsonrpc_core::Error {
                 message: inner.to_string(),
                 data: None,
             },
+            SubmitError::InvalidFeeQuote(_) => Self {
+                code: RpcErrorCodes::InvalidFeeQuote.into(),
+                message: inner.to_string(),
+                data: None,
+            },
             SubmitError::MempoolCommunication(reason) => Self {
                 code: RpcErrorCodes::Other.into(),
                 message: "Error communicating core server".to_string(),
@@ -110,3 +124,18 @@ impl From<SubmitError> for jsonrpc_core::Error {
         }
     }
 }
+
+impl From<AccountProofError> for jsonrpc_core::Error {
+    fn from(inner: AccountProofError) -> Self {
+        match inner {
+            AccountProofError::BlockNotFinalized(_) | AccountProofError::AccountNotFound => {
+                Self::invalid_params(inner.to_string())
+            }
+            AccountProofError::Internal(msg) => Self {
+                code: ErrorCode::InternalError,
+                message: msg.to_string(),
+                data: None,
+            },
+        }
+    }
+}
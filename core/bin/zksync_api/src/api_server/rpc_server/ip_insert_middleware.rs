@@ -47,12 +47,12 @@ fn get_call_with_ip_if_needed(
 ) -> jsonrpc_core::MethodCall {
     // Methods, which should have the information about the ip appended to them
     let methods_with_ip: HashMap<&'static str, MethodWithIpDescription> = HashMap::from_iter([
-        ("tx_submit", MethodWithIpDescription::new(1, 4)),
-        ("submit_txs_batch", MethodWithIpDescription::new(1, 3)),
-        ("get_tx_fee", MethodWithIpDescription::new(3, 4)),
+        ("tx_submit", MethodWithIpDescription::new(1, 5)),
+        ("submit_txs_batch", MethodWithIpDescription::new(1, 4)),
+        ("get_tx_fee", MethodWithIpDescription::new(3, 5)),
         (
             "get_txs_batch_fee_in_wei",
-            MethodWithIpDescription::new(3, 4),
+            MethodWithIpDescription::new(3, 5),
         ),
     ]);
 
@@ -213,6 +213,7 @@ mod tests {
             Value::String("serialized_transfer".to_owned()),
             Value::String("some_signature".to_owned()),
             Value::Null,
+            Value::Null,
             json!({ "ip": IP }),
         ]);
         test_call_ip_insertion(
@@ -221,6 +222,26 @@ mod tests {
             expected_result_params,
             Some(IP.to_owned()),
         );
+
+        // The optional `bind_quote` param is filled with null.
+        let params = Params::Array(vec![
+            Value::String("tx_type".to_owned()),
+            Value::String("address".to_owned()),
+            Value::String("token".to_owned()),
+        ]);
+        let expected_result_params = Params::Array(vec![
+            Value::String("tx_type".to_owned()),
+            Value::String("address".to_owned()),
+            Value::String("token".to_owned()),
+            Value::Null,
+            json!({ "ip": IP }),
+        ]);
+        test_call_ip_insertion(
+            "get_tx_fee".to_string(),
+            params,
+            expected_result_params,
+            Some(IP.to_owned()),
+        );
     }
 
     #[test]
@@ -229,12 +250,14 @@ mod tests {
             Value::String("serialized_transfer".to_owned()),
             Value::String("some_signature".to_owned()),
             Value::Bool(true),
+            Value::Null,
             Value::String("override_ip".to_owned()),
         ]);
         let expected_result_params = Params::Array(vec![
             Value::String("serialized_transfer".to_owned()),
             Value::String("some_signature".to_owned()),
             Value::Bool(true),
+            Value::Null,
             json!({ "ip": IP }),
         ]);
         test_call_ip_insertion(
@@ -248,12 +271,14 @@ mod tests {
             Value::String("serialized_transfer".to_owned()),
             Value::String("some_signature".to_owned()),
             Value::Bool(true),
+            Value::Null,
             Value::String("override_ip".to_owned()),
         ]);
         let expected_result_params = Params::Array(vec![
             Value::String("serialized_transfer".to_owned()),
             Value::String("some_signature".to_owned()),
             Value::Bool(true),
+            Value::Null,
         ]);
         test_call_ip_insertion(
             "tx_submit".to_string(),
@@ -320,6 +345,7 @@ mod tests {
                 Value::String("param2".to_owned()),
                 Value::String("param4".to_owned()),
                 Value::String("param5".to_owned()),
+                Value::String("param6".to_owned()),
             ]),
             Some(IP.to_owned()),
         );
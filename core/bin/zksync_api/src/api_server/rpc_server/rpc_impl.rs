@@ -3,24 +3,28 @@ use std::time::Instant;
 // External uses
 use bigdecimal::BigDecimal;
 use jsonrpc_core::{Error, Result};
+use num::{rational::Ratio, BigUint, Zero};
 // Workspace uses
 use zksync_api_types::{
     v02::{
+        account::AccountBalanceProof,
         fee::ApiTxFeeTypes,
         token::ApiNFT,
-        transaction::{Toggle2FA, Toggle2FAResponse},
+        transaction::{Toggle2FA, Toggle2FAResponse, TxSimulationResult},
     },
     TxWithSignature,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes, ZkSyncTx,
+    AccountId, Address, BlockNumber, Fee, Token, TokenId, TokenLike, TotalFee, TxFeeTypes,
+    ZkSyncTx,
 };
 // Local uses
 use crate::{
     api_server::{
-        helpers::get_depositing, rpc_server::error::RpcErrorCodes, tx_sender::SubmitError,
+        account_proof::account_balance_proof, helpers::get_depositing,
+        rpc_server::error::RpcErrorCodes, subsidies::SubsidyCandidate, tx_sender::SubmitError,
     },
     fee_ticker::TokenPriceRequestType,
 };
@@ -142,6 +146,7 @@ impl RpcApp {
         tx: Box<ZkSyncTx>,
         signature: Box<TxEthSignatureVariant>,
         fast_processing: Option<bool>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash> {
         let start = Instant::now();
@@ -152,6 +157,7 @@ impl RpcApp {
                 *tx,
                 *signature,
                 fast_processing,
+                fee_quote_id,
                 extracted_request_metadata,
             )
             .await;
@@ -173,13 +179,19 @@ impl RpcApp {
         self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Vec<TxHash>> {
         let start = Instant::now();
 
         let result = self
             .tx_sender
-            .submit_txs_batch(txs, eth_signatures, extracted_request_metadata)
+            .submit_txs_batch(
+                txs,
+                eth_signatures,
+                fee_quote_id,
+                extracted_request_metadata,
+            )
             .await
             .map(|response| {
                 response
@@ -277,6 +289,7 @@ impl RpcApp {
         tx_type: ApiTxFeeTypes,
         address: Address,
         token: TokenLike,
+        bind_quote: Option<bool>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Fee> {
         let start = Instant::now();
@@ -290,28 +303,44 @@ impl RpcApp {
             return Err(SubmitError::InappropriateFeeToken.into());
         }
 
+        let tx_type: TxFeeTypes = tx_type.into();
         let result = self
             .tx_sender
             .ticker
-            .get_fee_from_ticker_in_wei(tx_type.into(), token.clone(), address)
+            .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
             .await
             .map_err(SubmitError::Internal)?;
 
-        let should_subsidize_cpk = self
+        let normal_fee = result.normal_fee;
+        let subsidized_fee = self
             .tx_sender
-            .should_subsidize_cpk(
-                &result.normal_fee.total_fee,
-                &result.subsidized_fee.total_fee,
-                &result.subsidy_size_usd,
-                extracted_request_metadata,
+            .subsidized_fee(
+                token.clone(),
+                &[SubsidyCandidate::for_fee_request(tx_type, address)],
+                &normal_fee.total_fee,
+                &[Ratio::from(normal_fee.total_fee.clone())],
+                extracted_request_metadata.as_ref(),
             )
             .await?;
-
-        let fee = if should_subsidize_cpk {
-            result.subsidized_fee
-        } else {
-            result.normal_fee
-        };
+        let mut fee = normal_fee.clone();
+        if let Some(subsidized_fee) = subsidized_fee {
+            fee = Fee::new(
+                fee.fee_type,
+                Ratio::from(BigUint::zero()),
+                Ratio::from(subsidized_fee.total_fee),
+                Ratio::from(BigUint::zero()),
+                BigUint::zero(),
+                BigUint::zero(),
+            );
+        }
+        // The quote binds the normal fee, so the subsidy is still checked on submission.
+        if bind_quote.unwrap_or(false) {
+            fee.quote = self
+                .tx_sender
+                .ticker
+                .bind_fee_quote(token, vec![(tx_type, address)], normal_fee.total_fee)
+                .await;
+        }
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_fee");
         Ok(fee)
@@ -322,6 +351,7 @@ impl RpcApp {
         tx_types: Vec<ApiTxFeeTypes>,
         addresses: Vec<Address>,
         token: TokenLike,
+        bind_quote: Option<bool>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TotalFee> {
         let start = Instant::now();
@@ -353,30 +383,39 @@ impl RpcApp {
         let result = self
             .tx_sender
             .ticker
-            .get_batch_from_ticker_in_wei(token.clone(), transactions)
+            .get_batch_from_ticker_in_wei(token.clone(), transactions.clone())
             .await
             .map_err(SubmitError::Internal)?;
 
-        let should_subsidize_cpk = self
+        let subsidy_candidates: Vec<_> = transactions
+            .iter()
+            .map(|&(tx_type, address)| SubsidyCandidate::for_fee_request(tx_type, address))
+            .collect();
+        let subsidized_fee = self
             .tx_sender
-            .should_subsidize_cpk(
+            .subsidized_fee(
+                token.clone(),
+                &subsidy_candidates,
                 &result.normal_fee.total_fee,
-                &result.subsidized_fee.total_fee,
-                &result.subsidy_size_usd,
-                extracted_request_metadata,
+                &result.normal_tx_fees,
+                extracted_request_metadata.as_ref(),
             )
             .await?;
 
-        let fee = if should_subsidize_cpk {
-            result.subsidized_fee
+        let normal_fee = result.normal_fee.total_fee;
+        let total_fee = subsidized_fee.map_or_else(|| normal_fee.clone(), |fee| fee.total_fee);
+        // The quote binds the normal fee, so the subsidy is still checked on submission.
+        let quote = if bind_quote.unwrap_or(false) {
+            self.tx_sender
+                .ticker
+                .bind_fee_quote(token, transactions, normal_fee)
+                .await
         } else {
-            result.normal_fee
+            None
         };
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_txs_batch_fee_in_wei");
-        Ok(TotalFee {
-            total_fee: fee.total_fee,
-        })
+        Ok(TotalFee { total_fee, quote })
     }
 
     pub async fn _impl_get_token_price(self, token: TokenLike) -> Result<BigDecimal> {
@@ -451,4 +490,67 @@ impl RpcApp {
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_nft_id_by_tx_hash");
         Ok(response)
     }
+
+    pub async fn _impl_simulate_tx(self, tx: Box<ZkSyncTx>) -> Result<TxSimulationResult> {
+        let start = Instant::now();
+        let result = self.tx_sender.simulate_tx(*tx).await.map_err(Error::from);
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "simulate_tx");
+        result
+    }
+
+    pub async fn _impl_simulate_batch(self, txs: Vec<ZkSyncTx>) -> Result<TxSimulationResult> {
+        let start = Instant::now();
+        let result = self
+            .tx_sender
+            .simulate_txs_batch(txs)
+            .await
+            .map_err(Error::from);
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "simulate_batch");
+        result
+    }
+
+    pub async fn _impl_account_proof(
+        self,
+        address: Address,
+        token: TokenLike,
+        block: Option<BlockNumber>,
+    ) -> Result<AccountBalanceProof> {
+        let start = Instant::now();
+
+        let mut storage = self.access_storage().await?;
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, address);
+                Error::internal_error()
+            })?
+            .ok_or_else(|| Error::invalid_params("Account is not found"))?;
+        let token = self
+            .tx_sender
+            .tokens
+            .get_token(&mut storage, token.clone())
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, token);
+                Error::internal_error()
+            })?
+            .ok_or_else(|| Error::invalid_params("Token is not found"))?;
+        let result = account_balance_proof(
+            &mut storage,
+            &self.tx_sender.account_trees,
+            account_id,
+            token.id,
+            block,
+        )
+        .await
+        .map_err(Error::from);
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "account_proof");
+        result
+    }
 }
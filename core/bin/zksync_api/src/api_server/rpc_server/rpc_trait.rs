@@ -7,16 +7,17 @@ use jsonrpc_derive::rpc;
 // Workspace uses
 use zksync_api_types::{
     v02::{
+        account::AccountBalanceProof,
         fee::ApiTxFeeTypes,
         token::ApiNFT,
-        transaction::{Toggle2FA, Toggle2FAResponse},
+        transaction::{Toggle2FA, Toggle2FAResponse, TxSimulationResult},
     },
     TxWithSignature,
 };
 use zksync_crypto::params::ZKSYNC_VERSION;
 use zksync_types::{
     tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash},
-    AccountId, Address, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
+    AccountId, Address, BlockNumber, Fee, Token, TokenId, TokenLike, TotalFee, ZkSyncTx,
 };
 
 // Local uses
@@ -48,6 +49,7 @@ pub trait Rpc {
         tx: Box<ZkSyncTx>,
         signature: Box<TxEthSignatureVariant>,
         fast_processing: Option<bool>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<TxHash>;
 
@@ -56,6 +58,7 @@ pub trait Rpc {
         &self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<Vec<TxHash>>;
 
@@ -73,6 +76,7 @@ pub trait Rpc {
         tx_type: ApiTxFeeTypes,
         _address: Address,
         token_like: TokenLike,
+        bind_quote: Option<bool>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee>;
 
@@ -83,6 +87,7 @@ pub trait Rpc {
         tx_types: Vec<ApiTxFeeTypes>,
         _addresses: Vec<Address>,
         token_like: TokenLike,
+        bind_quote: Option<bool>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<TotalFee>;
 
@@ -110,6 +115,20 @@ pub trait Rpc {
 
     #[rpc(name = "get_nft_id_by_tx_hash", returns = "Option<TokenId>")]
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>>;
+
+    #[rpc(name = "simulate_tx", returns = "TxSimulationResult")]
+    fn simulate_tx(&self, tx: Box<ZkSyncTx>) -> BoxFutureResult<TxSimulationResult>;
+
+    #[rpc(name = "simulate_batch", returns = "TxSimulationResult")]
+    fn simulate_batch(&self, txs: Vec<ZkSyncTx>) -> BoxFutureResult<TxSimulationResult>;
+
+    #[rpc(name = "account_proof", returns = "AccountBalanceProof")]
+    fn account_proof(
+        &self,
+        address: Address,
+        token: TokenLike,
+        block: Option<BlockNumber>,
+    ) -> BoxFutureResult<AccountBalanceProof>;
 }
 
 impl Rpc for RpcApp {
@@ -131,9 +150,10 @@ impl Rpc for RpcApp {
         tx: Box<ZkSyncTx>,
         signature: Box<TxEthSignatureVariant>,
         fast_processing: Option<bool>,
+        fee_quote_id: Option<i64>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<TxHash> {
-        spawn!(self._impl_tx_submit(tx, signature, fast_processing, meta))
+        spawn!(self._impl_tx_submit(tx, signature, fast_processing, fee_quote_id, meta))
     }
 
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
@@ -141,9 +161,10 @@ impl Rpc for RpcApp {
         &self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
+        fee_quote_id: Option<i64>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<Vec<TxHash>> {
-        spawn!(self._impl_submit_txs_batch(txs, eth_signatures, meta))
+        spawn!(self._impl_submit_txs_batch(txs, eth_signatures, fee_quote_id, meta))
     }
 
     fn contract_address(&self) -> BoxFutureResult<ContractAddressResp> {
@@ -160,9 +181,10 @@ impl Rpc for RpcApp {
         tx_type: ApiTxFeeTypes,
         address: Address,
         token_like: TokenLike,
+        bind_quote: Option<bool>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee> {
-        spawn!(self._impl_get_tx_fee(tx_type, address, token_like, meta))
+        spawn!(self._impl_get_tx_fee(tx_type, address, token_like, bind_quote, meta))
     }
 
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
@@ -171,9 +193,12 @@ impl Rpc for RpcApp {
         tx_types: Vec<ApiTxFeeTypes>,
         addresses: Vec<Address>,
         token_like: TokenLike,
+        bind_quote: Option<bool>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<TotalFee> {
-        spawn!(self._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like, meta))
+        spawn!(
+            self._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like, bind_quote, meta)
+        )
     }
 
     fn get_token_price(&self, token_like: TokenLike) -> BoxFutureResult<BigDecimal> {
@@ -210,4 +235,21 @@ impl Rpc for RpcApp {
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>> {
         spawn!(self._impl_get_nft_id_by_tx_hash(tx_hash))
     }
+
+    fn simulate_tx(&self, tx: Box<ZkSyncTx>) -> BoxFutureResult<TxSimulationResult> {
+        spawn!(self._impl_simulate_tx(tx))
+    }
+
+    fn simulate_batch(&self, txs: Vec<ZkSyncTx>) -> BoxFutureResult<TxSimulationResult> {
+        spawn!(self._impl_simulate_batch(txs))
+    }
+
+    fn account_proof(
+        &self,
+        address: Address,
+        token: TokenLike,
+        block: Option<BlockNumber>,
+    ) -> BoxFutureResult<AccountBalanceProof> {
+        spawn!(self._impl_account_proof(address, token, block))
+    }
 }
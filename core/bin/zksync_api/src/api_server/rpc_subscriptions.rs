@@ -199,6 +199,7 @@ pub fn start_ws_server(
         common_config.caches_size,
         miniblock_iteration_interval,
         token_config,
+        None,
     );
 
     let req_rpc_app = super::rpc_server::RpcApp::new(
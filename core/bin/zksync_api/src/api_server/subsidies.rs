@@ -0,0 +1,565 @@
+//! Fee subsidy rules.
+//!
+//! A subsidy rule describes the transactions which fees are partially covered by the operator:
+//! the user pays no more than the fixed USD amount for every matched transaction, the rest of
+//! the fee is subsidized. Transactions are matched by the fee type, the fee token, the sender,
+//! the IP of the request origin and the time window. Every rule has its own USD budget and may cap
+//! the amount subsidized for a single sender, the amounts are tracked in the `subsidies` table with
+//! the rule name as the subsidy type.
+//!
+//! Rules are stored in the database and managed via the admin API. The rule set in the API config
+//! (`subsidized_ips`, `max_subsidy_usd_scaled` and `subsidy_name`) subsidizes the `CREATE2`
+//! `ChangePubKey` transactions and is checked after the stored ones.
+
+// Built-in uses
+use std::{
+    convert::TryFrom,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use anyhow::{ensure, format_err};
+use chrono::{DateTime, Utc};
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::{subsidy_rules::records::StorageSubsidyRule, ConnectionPool};
+use zksync_types::{
+    tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address, TokenId, TxFeeTypes,
+};
+use zksync_utils::scaled_u64_to_ratio;
+
+/// Interval after which the rules are reloaded from the database.
+const RULES_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Rule describing the subsidized transactions. Criteria set to `None` match any transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsidyRule {
+    /// Unique name of the rule, used as the type of the subsidies applied by the rule.
+    pub name: String,
+    /// Rules are checked in the ascending order of the priority, the first matching rule is applied.
+    #[serde(default)]
+    pub priority: i32,
+    pub enabled: bool,
+    /// Fee types of the transactions, unknown types are rejected on deserialization.
+    pub tx_types: Option<Vec<TxFeeTypes>>,
+    /// Tokens the fee is paid in.
+    pub tokens: Option<Vec<TokenId>>,
+    /// Allowed transaction senders.
+    pub addresses: Option<Vec<Address>>,
+    /// Allowed IPs of the request origin.
+    pub ips: Option<Vec<String>>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    /// Maximum fee paid by the user for a matched transaction in USD scaled by 10^6.
+    pub max_fee_usd_scaled: u64,
+    /// Total amount that can be subsidized by the rule in USD scaled by 10^6.
+    pub budget_usd_scaled: u64,
+    /// Amount that can be subsidized for a single sender in USD scaled by 10^6.
+    pub max_per_address_usd_scaled: Option<u64>,
+}
+
+impl SubsidyRule {
+    /// Creates the rule set in the API config. `cpk_max_fee_usd_scaled` is the price of
+    /// the subsidized `CREATE2` `ChangePubKey` configured for the fee ticker.
+    pub fn from_config(config: &CommonApiConfig, cpk_max_fee_usd_scaled: u64) -> Option<Self> {
+        if config.subsidized_ips.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: config.subsidy_name.clone(),
+            priority: i32::MAX,
+            enabled: true,
+            tx_types: Some(vec![TxFeeTypes::ChangePubKey(
+                ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::CREATE2),
+            )]),
+            tokens: None,
+            addresses: None,
+            ips: Some(config.subsidized_ips.clone()),
+            valid_from: None,
+            valid_until: None,
+            max_fee_usd_scaled: cpk_max_fee_usd_scaled,
+            budget_usd_scaled: config.max_subsidy_usd_scaled,
+            max_per_address_usd_scaled: None,
+        })
+    }
+
+    /// Checks the rule submitted via the admin API.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            !self.name.is_empty(),
+            "Subsidy rule name should not be empty"
+        );
+        if let (Some(valid_from), Some(valid_until)) = (self.valid_from, self.valid_until) {
+            ensure!(
+                valid_from < valid_until,
+                "Subsidy rule validity period should not be empty"
+            );
+        }
+        for ip in self.ips.iter().flatten() {
+            ensure!(ip.parse::<IpAddr>().is_ok(), "Invalid IP address: {}", ip);
+        }
+        ensure!(
+            self.max_fee_usd_scaled <= self.budget_usd_scaled,
+            "Maximum fee should not exceed the budget"
+        );
+        Ok(())
+    }
+
+    pub fn max_fee_usd(&self) -> Ratio<BigUint> {
+        scaled_u64_to_ratio(self.max_fee_usd_scaled)
+    }
+
+    pub fn budget_usd(&self) -> Ratio<BigUint> {
+        scaled_u64_to_ratio(self.budget_usd_scaled)
+    }
+
+    pub fn max_per_address_usd(&self) -> Option<Ratio<BigUint>> {
+        self.max_per_address_usd_scaled.map(scaled_u64_to_ratio)
+    }
+
+    /// Checks whether the transaction paying the fee in `fee_token` matches the rule.
+    pub fn matches(
+        &self,
+        tx: &SubsidyCandidate,
+        fee_token: TokenId,
+        ip: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let sender_allowed = match (&self.addresses, tx.sender) {
+            (None, _) => true,
+            (Some(addresses), Some(sender)) => addresses.contains(&sender),
+            (Some(_), None) => false,
+        };
+        let ip_allowed = match (&self.ips, ip) {
+            (None, _) => true,
+            (Some(ips), Some(ip)) => ips.iter().any(|allowed| allowed == ip),
+            (Some(_), None) => false,
+        };
+
+        self.enabled
+            && self.valid_from.map_or(true, |valid_from| valid_from <= now)
+            && self.valid_until.map_or(true, |valid_until| now < valid_until)
+            && self
+                .tx_types
+                .as_ref()
+                .map_or(true, |tx_types| tx_types.contains(&tx.tx_type))
+            && self
+                .tokens
+                .as_ref()
+                .map_or(true, |tokens| tokens.contains(&fee_token))
+            && sender_allowed
+            && ip_allowed
+            // The per-address cap can't be checked if the sender is unknown.
+            && (self.max_per_address_usd_scaled.is_none() || tx.sender.is_some())
+    }
+}
+
+impl TryFrom<StorageSubsidyRule> for SubsidyRule {
+    type Error = anyhow::Error;
+
+    fn try_from(rule: StorageSubsidyRule) -> Result<Self, Self::Error> {
+        let tx_types = rule.tx_types.map(serde_json::from_value).transpose()?;
+        let tokens = rule
+            .token_ids
+            .map(|token_ids| token_ids.into_iter().map(|id| TokenId(id as u32)).collect());
+        let addresses = rule.addresses.map(|addresses| {
+            addresses
+                .iter()
+                .map(|address| Address::from_slice(address))
+                .collect()
+        });
+
+        Ok(Self {
+            name: rule.name,
+            priority: rule.priority,
+            enabled: rule.enabled,
+            tx_types,
+            tokens,
+            addresses,
+            ips: rule.ips,
+            valid_from: rule.valid_from,
+            valid_until: rule.valid_until,
+            max_fee_usd_scaled: rule.max_fee_usd_scale6 as u64,
+            budget_usd_scaled: rule.budget_usd_scale6 as u64,
+            max_per_address_usd_scaled: rule.max_per_address_usd_scale6.map(|cap| cap as u64),
+        })
+    }
+}
+
+impl TryFrom<SubsidyRule> for StorageSubsidyRule {
+    type Error = anyhow::Error;
+
+    fn try_from(rule: SubsidyRule) -> Result<Self, Self::Error> {
+        let scaled_amount = |amount: u64, field: &str| {
+            i64::try_from(amount).map_err(|_| format_err!("{} is too large: {}", field, amount))
+        };
+
+        Ok(Self {
+            max_fee_usd_scale6: scaled_amount(rule.max_fee_usd_scaled, "maxFeeUsdScaled")?,
+            budget_usd_scale6: scaled_amount(rule.budget_usd_scaled, "budgetUsdScaled")?,
+            max_per_address_usd_scale6: rule
+                .max_per_address_usd_scaled
+                .map(|cap| scaled_amount(cap, "maxPerAddressUsdScaled"))
+                .transpose()?,
+            tx_types: rule.tx_types.map(serde_json::to_value).transpose()?,
+            token_ids: rule
+                .tokens
+                .map(|tokens| tokens.into_iter().map(|id| *id as i32).collect()),
+            addresses: rule.addresses.map(|addresses| {
+                addresses
+                    .iter()
+                    .map(|address| address.as_bytes().to_vec())
+                    .collect()
+            }),
+            name: rule.name,
+            priority: rule.priority,
+            enabled: rule.enabled,
+            ips: rule.ips,
+            valid_from: rule.valid_from,
+            valid_until: rule.valid_until,
+        })
+    }
+}
+
+/// Transaction checked against the subsidy rules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubsidyCandidate {
+    pub tx_type: TxFeeTypes,
+    /// Address passed to the fee ticker along with the transaction type.
+    pub address: Address,
+    /// Sender of the transaction, if known.
+    pub sender: Option<Address>,
+}
+
+impl SubsidyCandidate {
+    /// Creates the candidate for the fee request. Only the `ChangePubKey` sender is known
+    /// before the transaction is signed, since the fee is requested for the account itself.
+    pub fn for_fee_request(tx_type: TxFeeTypes, address: Address) -> Self {
+        let sender = if matches!(tx_type, TxFeeTypes::ChangePubKey(_)) {
+            Some(address)
+        } else {
+            None
+        };
+
+        Self {
+            tx_type,
+            address,
+            sender,
+        }
+    }
+}
+
+/// Transactions of the same sender matched by the same rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsidyGroup {
+    /// Index of the rule.
+    pub rule: usize,
+    pub sender: Option<Address>,
+    /// Indices of the transactions.
+    pub txs: Vec<usize>,
+}
+
+/// Assigns every transaction to the first matching rule, transactions are grouped by
+/// the rule and the sender.
+pub fn match_rules(
+    rules: &[SubsidyRule],
+    txs: &[SubsidyCandidate],
+    fee_token: TokenId,
+    ip: Option<&str>,
+    now: DateTime<Utc>,
+) -> Vec<SubsidyGroup> {
+    let mut groups: Vec<SubsidyGroup> = Vec::new();
+    for (tx_index, tx) in txs.iter().enumerate() {
+        let rule = match rules
+            .iter()
+            .position(|rule| rule.matches(tx, fee_token, ip, now))
+        {
+            Some(rule) => rule,
+            None => continue,
+        };
+
+        match groups
+            .iter_mut()
+            .find(|group| group.rule == rule && group.sender == tx.sender)
+        {
+            Some(group) => group.txs.push(tx_index),
+            None => groups.push(SubsidyGroup {
+                rule,
+                sender: tx.sender,
+                txs: vec![tx_index],
+            }),
+        }
+    }
+    groups
+}
+
+/// Subsidy applied to the group of transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedSubsidy {
+    pub rule_name: String,
+    pub sender: Option<Address>,
+    /// Fee of the subsidized transactions without the subsidy.
+    pub normal_fee: BigUint,
+    pub subsidized_fee: BigUint,
+    pub normal_fee_usd: Ratio<BigUint>,
+    pub subsidy_usd: Ratio<BigUint>,
+    /// Limits of the rule the subsidy is reserved within.
+    pub budget_usd_scaled: u64,
+    pub max_per_address_usd_scaled: Option<u64>,
+}
+
+/// Fee of the transactions with the subsidies applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsidizedFee {
+    pub total_fee: BigUint,
+    /// Token the fee is paid in.
+    pub token_id: TokenId,
+    pub subsidies: Vec<AppliedSubsidy>,
+}
+
+/// Subsidy rules stored in the database along with the rule set in the API config.
+/// Stored rules are reloaded periodically, so the changes made via the admin API
+/// are applied without restarting the server.
+#[derive(Debug, Clone)]
+pub struct SubsidyRules {
+    pool: ConnectionPool,
+    config_rule: Option<SubsidyRule>,
+    loaded: Arc<RwLock<Option<(Instant, Arc<Vec<SubsidyRule>>)>>>,
+}
+
+impl SubsidyRules {
+    pub fn new(pool: ConnectionPool, config_rule: Option<SubsidyRule>) -> Self {
+        Self {
+            pool,
+            config_rule,
+            loaded: Arc::default(),
+        }
+    }
+
+    /// Returns the rules in the order they should be checked.
+    pub async fn load(&self) -> anyhow::Result<Arc<Vec<SubsidyRule>>> {
+        if let Some((loaded_at, rules)) = self.loaded.read().await.as_ref() {
+            if loaded_at.elapsed() < RULES_RELOAD_INTERVAL {
+                return Ok(rules.clone());
+            }
+        }
+
+        let stored_rules = self
+            .pool
+            .access_storage()
+            .await?
+            .subsidy_rules_schema()
+            .load_subsidy_rules()
+            .await?;
+        let mut rules = Vec::with_capacity(stored_rules.len() + 1);
+        for rule in stored_rules {
+            match SubsidyRule::try_from(rule) {
+                Ok(rule) => rules.push(rule),
+                Err(err) => vlog::error!("Skipping the malformed subsidy rule: {}", err),
+            }
+        }
+        rules.extend(self.config_rule.clone());
+
+        let rules = Arc::new(rules);
+        *self.loaded.write().await = Some((Instant::now(), rules.clone()));
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ETH: TokenId = TokenId(0);
+    const CPK_CREATE2: TxFeeTypes = TxFeeTypes::ChangePubKey(
+        ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::CREATE2),
+    );
+
+    fn rule(name: &str) -> SubsidyRule {
+        SubsidyRule {
+            name: name.to_string(),
+            priority: 0,
+            enabled: true,
+            tx_types: None,
+            tokens: None,
+            addresses: None,
+            ips: None,
+            valid_from: None,
+            valid_until: None,
+            max_fee_usd_scaled: 0,
+            budget_usd_scaled: 1_000_000,
+            max_per_address_usd_scaled: None,
+        }
+    }
+
+    fn transfer(sender: u8) -> SubsidyCandidate {
+        SubsidyCandidate {
+            tx_type: TxFeeTypes::Transfer,
+            address: Address::repeat_byte(0xff),
+            sender: Some(Address::repeat_byte(sender)),
+        }
+    }
+
+    #[test]
+    fn rule_matching() {
+        let now = Utc.timestamp(1000, 0);
+        let tx = transfer(1);
+        assert!(rule("any").matches(&tx, ETH, None, now));
+
+        let disabled = SubsidyRule {
+            enabled: false,
+            ..rule("disabled")
+        };
+        assert!(!disabled.matches(&tx, ETH, None, now));
+
+        let window = SubsidyRule {
+            valid_from: Some(Utc.timestamp(1000, 0)),
+            valid_until: Some(Utc.timestamp(2000, 0)),
+            ..rule("window")
+        };
+        assert!(window.matches(&tx, ETH, None, now));
+        assert!(!window.matches(&tx, ETH, None, Utc.timestamp(999, 0)));
+        assert!(!window.matches(&tx, ETH, None, Utc.timestamp(2000, 0)));
+
+        let cpk = SubsidyRule {
+            tx_types: Some(vec![CPK_CREATE2]),
+            ..rule("cpk")
+        };
+        assert!(!cpk.matches(&tx, ETH, None, now));
+        let cpk_tx = SubsidyCandidate::for_fee_request(CPK_CREATE2, Address::repeat_byte(1));
+        assert!(cpk.matches(&cpk_tx, ETH, None, now));
+
+        let tokens = SubsidyRule {
+            tokens: Some(vec![TokenId(1)]),
+            ..rule("tokens")
+        };
+        assert!(!tokens.matches(&tx, ETH, None, now));
+        assert!(tokens.matches(&tx, TokenId(1), None, now));
+
+        let addresses = SubsidyRule {
+            addresses: Some(vec![Address::repeat_byte(1)]),
+            ..rule("addresses")
+        };
+        assert!(addresses.matches(&tx, ETH, None, now));
+        assert!(!addresses.matches(&transfer(2), ETH, None, now));
+        // The sender of the transfer is unknown when the fee is requested.
+        let transfer_fee_request =
+            SubsidyCandidate::for_fee_request(TxFeeTypes::Transfer, Address::repeat_byte(1));
+        assert!(!addresses.matches(&transfer_fee_request, ETH, None, now));
+
+        let ips = SubsidyRule {
+            ips: Some(vec!["127.0.0.1".to_string()]),
+            ..rule("ips")
+        };
+        assert!(ips.matches(&tx, ETH, Some("127.0.0.1"), now));
+        assert!(!ips.matches(&tx, ETH, Some("127.0.0.2"), now));
+        assert!(!ips.matches(&tx, ETH, None, now));
+
+        let capped = SubsidyRule {
+            max_per_address_usd_scaled: Some(1_000),
+            ..rule("capped")
+        };
+        assert!(capped.matches(&tx, ETH, None, now));
+        assert!(!capped.matches(&transfer_fee_request, ETH, None, now));
+    }
+
+    #[test]
+    fn rules_grouping() {
+        let now = Utc::now();
+        let rules = vec![
+            SubsidyRule {
+                addresses: Some(vec![Address::repeat_byte(1)]),
+                ..rule("partner")
+            },
+            SubsidyRule {
+                tx_types: Some(vec![CPK_CREATE2]),
+                ..rule("cpk")
+            },
+        ];
+        let txs = vec![
+            transfer(1),
+            SubsidyCandidate::for_fee_request(CPK_CREATE2, Address::repeat_byte(2)),
+            transfer(2),
+            transfer(1),
+        ];
+
+        let groups = match_rules(&rules, &txs, ETH, None, now);
+        assert_eq!(
+            groups,
+            vec![
+                SubsidyGroup {
+                    rule: 0,
+                    sender: Some(Address::repeat_byte(1)),
+                    txs: vec![0, 3],
+                },
+                SubsidyGroup {
+                    rule: 1,
+                    sender: Some(Address::repeat_byte(2)),
+                    txs: vec![1],
+                },
+            ]
+        );
+        assert!(match_rules(&rules, &txs[2..3], ETH, None, now).is_empty());
+    }
+
+    #[test]
+    fn storage_conversion() {
+        let rule = SubsidyRule {
+            tx_types: Some(vec![CPK_CREATE2, TxFeeTypes::Transfer]),
+            tokens: Some(vec![TokenId(1)]),
+            addresses: Some(vec![Address::repeat_byte(1)]),
+            ips: Some(vec!["127.0.0.1".to_string()]),
+            valid_from: Some(Utc.timestamp(1000, 0)),
+            max_per_address_usd_scaled: Some(1_000),
+            ..rule("partner")
+        };
+        let stored = StorageSubsidyRule::try_from(rule.clone()).unwrap();
+        assert_eq!(SubsidyRule::try_from(stored).unwrap(), rule);
+
+        let overflowing = SubsidyRule {
+            budget_usd_scaled: u64::MAX,
+            ..rule("overflowing")
+        };
+        assert!(StorageSubsidyRule::try_from(overflowing).is_err());
+    }
+
+    #[test]
+    fn rule_validation() {
+        assert!(rule("valid").validate().is_ok());
+        assert!(rule("").validate().is_err());
+
+        let empty_period = SubsidyRule {
+            valid_from: Some(Utc.timestamp(1000, 0)),
+            valid_until: Some(Utc.timestamp(1000, 0)),
+            ..rule("empty period")
+        };
+        assert!(empty_period.validate().is_err());
+
+        let ips = SubsidyRule {
+            ips: Some(vec!["127.0.0.1".to_string(), "::1".to_string()]),
+            ..rule("ips")
+        };
+        assert!(ips.validate().is_ok());
+        let invalid_ip = SubsidyRule {
+            ips: Some(vec!["localhost".to_string()]),
+            ..rule("invalid ip")
+        };
+        assert!(invalid_ip.validate().is_err());
+
+        let fee_above_budget = SubsidyRule {
+            max_fee_usd_scaled: 2_000_000,
+            budget_usd_scaled: 1_000_000,
+            ..rule("fee above budget")
+        };
+        assert!(fee_above_budget.validate().is_err());
+    }
+}
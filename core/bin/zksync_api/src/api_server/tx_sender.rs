@@ -16,19 +16,22 @@ use futures::{
     prelude::*,
 };
 use itertools::izip;
-use num::rational::Ratio;
-use num::{bigint::ToBigInt, BigUint, Zero};
+use num::{bigint::ToBigInt, rational::Ratio, BigUint, Zero};
 use thiserror::Error;
 
 // Workspace uses
 use zksync_api_types::{
-    v02::transaction::{SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper},
+    v02::transaction::{
+        SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper,
+        TxSimulationResult,
+    },
     TxWithSignature,
 };
 use zksync_storage::misc::records::Subsidy;
 use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
+    helpers::closest_packable_fee_amount,
     tx::{
         EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TxEthSignature,
         TxEthSignatureVariant, TxHash,
@@ -42,7 +45,7 @@ use zksync_utils::{
 // Local uses
 use crate::{
     api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
-    fee_ticker::{ResponseBatchFee, ResponseFee, TokenPriceRequestType},
+    fee_ticker::TokenPriceRequestType,
     signature_checker::{
         BatchRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest, VerifiedTx,
         VerifySignatureRequest,
@@ -54,8 +57,15 @@ use zksync_config::configs::api::{CommonApiConfig, TokenConfig};
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_types::tx::error::TxAddError;
 
-use super::rpc_server::types::RequestMetadata;
-use crate::fee_ticker::{FeeTicker, PriceError};
+use super::{
+    account_proof::AccountTreeCache,
+    rpc_server::types::RequestMetadata,
+    subsidies::{
+        match_rules, AppliedSubsidy, SubsidizedFee, SubsidyCandidate, SubsidyRule, SubsidyRules,
+    },
+    tx_simulator,
+};
+use crate::fee_ticker::{FeeQuoteError, FeeTicker, PriceError};
 
 const VALIDNESS_INTERVAL_MINUTES: i64 = 40;
 
@@ -70,6 +80,7 @@ pub struct TxSender {
 
     pub forced_exit_checker: ForcedExitChecker,
     pub blocks: BlockDetailsCache,
+    pub account_trees: AccountTreeCache,
     /// List of account IDs that do not have to pay fees for operations.
     pub fee_free_accounts: HashSet<AccountId>,
     pub enforce_pubkey_change_fee: bool,
@@ -77,9 +88,7 @@ pub struct TxSender {
     pub max_number_of_transactions_per_batch: usize,
     pub max_number_of_authors_per_batch: usize,
 
-    pub current_subsidy_type: String,
-    pub max_subsidy_usd: Ratio<BigUint>,
-    pub subsidized_ips: HashSet<String>,
+    pub subsidy_rules: SubsidyRules,
 }
 
 #[derive(Debug, Error)]
@@ -96,6 +105,8 @@ pub enum SubmitError {
     TxAdd(#[from] TxAddError),
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Invalid fee quote: {0}.")]
+    InvalidFeeQuote(String),
     // Not all TxAddErrors would apply to Toggle2FA, but
     // it is helpful to re-use IncorrectEthSignature and DbError
     #[error("Failed to toggle 2FA: {0}.")]
@@ -129,6 +140,15 @@ impl SubmitError {
     }
 }
 
+impl From<FeeQuoteError> for SubmitError {
+    fn from(err: FeeQuoteError) -> Self {
+        match err {
+            FeeQuoteError::DBError(_) => Self::internal(err),
+            _ => Self::InvalidFeeQuote(err.to_string()),
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! internal_error {
     ($err:tt, $input:tt) => {{
@@ -153,10 +173,14 @@ impl TxSender {
         let max_number_of_transactions_per_batch =
             config.max_number_of_transactions_per_batch as usize;
         let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
+        let config_subsidy_rule = SubsidyRule::from_config(
+            config,
+            ratio_to_scaled_u64(ticker.subsidy_cpk_price_usd().clone()),
+        );
 
         Self {
             mempool_tx_sender,
-            pool: connection_pool,
+            pool: connection_pool.clone(),
             sign_verify_requests: sign_verify_request_sender,
             ticker,
             tokens: TokenDBCache::new(token_config.invalidate_token_cache_period()),
@@ -165,13 +189,12 @@ impl TxSender {
             ),
             enforce_pubkey_change_fee: config.enforce_pubkey_change_fee,
             blocks: BlockDetailsCache::new(config.caches_size),
+            account_trees: AccountTreeCache::new(),
 
             fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
             max_number_of_transactions_per_batch,
             max_number_of_authors_per_batch,
-            current_subsidy_type: config.subsidy_name.clone(),
-            max_subsidy_usd: config.max_subsidy_usd(),
-            subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            subsidy_rules: SubsidyRules::new(connection_pool.clone(), config_subsidy_rule),
         }
     }
 
@@ -255,6 +278,54 @@ impl TxSender {
         Ok(Toggle2FAResponse { success: true })
     }
 
+    /// Executes the transaction against the committed state without submitting it to the mempool.
+    /// zkSync signature is checked the same way as for the submitted transactions, while
+    /// Ethereum signature is not required.
+    pub async fn simulate_tx(&self, mut tx: ZkSyncTx) -> Result<TxSimulationResult, SubmitError> {
+        if tx.is_close() {
+            return Err(SubmitError::AccountCloseDisabled);
+        }
+        tx.check_correctness().map_err(TxAddError::from)?;
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        tx_simulator::simulate_tx(&mut storage, tx)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
+    /// Executes the batch of transactions against the committed state without submitting it
+    /// to the mempool.
+    pub async fn simulate_txs_batch(
+        &self,
+        mut txs: Vec<ZkSyncTx>,
+    ) -> Result<TxSimulationResult, SubmitError> {
+        if txs.is_empty() {
+            return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
+        }
+        if txs.len() > self.max_number_of_transactions_per_batch {
+            return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
+        }
+        if txs.iter().any(|tx| tx.is_close()) {
+            return Err(SubmitError::AccountCloseDisabled);
+        }
+        for tx in &mut txs {
+            tx.check_correctness().map_err(TxAddError::from)?;
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        tx_simulator::simulate_txs_batch(&mut storage, txs)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
     async fn verify_toggle_2fa_request_eth_signature(
         &self,
         toggle_2fa: Toggle2FA,
@@ -361,6 +432,7 @@ impl TxSender {
         mut tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
         fast_processing: Option<bool>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash, SubmitError> {
         let fast_processing = fast_processing.unwrap_or(false);
@@ -380,7 +452,7 @@ impl TxSender {
         }
 
         let result = self
-            .submit_tx(tx, signature, extracted_request_metadata)
+            .submit_tx(tx, signature, fee_quote_id, extracted_request_metadata)
             .await;
 
         if let Err(err) = &result {
@@ -396,95 +468,257 @@ impl TxSender {
         result
     }
 
-    pub async fn can_subsidize(
+    /// Applies the subsidy rules to the transactions paying `normal_fee` in the token.
+    /// `normal_tx_fees` are the fees of the separate transactions, they are used to calculate
+    /// the fee of the transactions matched by the same rule.
+    /// Returns `None` if none of the transactions is subsidized.
+    pub async fn subsidized_fee(
         &self,
-        new_subsidy_usd: Ratio<BigUint>,
-    ) -> Result<bool, anyhow::Error> {
-        let subsidized_already = self
-            .pool
-            .access_storage()
-            .await?
-            .misc_schema()
-            .get_total_used_subsidy_for_type(&self.current_subsidy_type)
+        token: TokenLike,
+        txs: &[SubsidyCandidate],
+        normal_fee: &BigUint,
+        normal_tx_fees: &[Ratio<BigUint>],
+        extracted_request_metadata: Option<&RequestMetadata>,
+    ) -> Result<Option<SubsidizedFee>, SubmitError> {
+        let rules = self.subsidy_rules.load().await?;
+        let token = self.token_info_from_id(token).await?;
+        let ip = extracted_request_metadata.map(|meta| meta.ip.as_str());
+        let groups = match_rules(&rules, txs, token.id, ip, Utc::now());
+        if groups.is_empty() {
+            return Ok(None);
+        }
+
+        let token_price_in_usd = self
+            .ticker
+            .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
             .await?;
-        let subsidized_already_usd = scaled_big_decimal_to_ratio(subsidized_already)?;
+        let token_price_in_usd = big_decimal_to_ratio(&token_price_in_usd)?;
+        if token_price_in_usd.is_zero() {
+            return Ok(None);
+        }
+
+        let mut storage = self.pool.access_storage().await?;
+        let mut used_by_rule = HashMap::new();
+        let mut used_by_address = HashMap::new();
+        let mut total_subsidy = BigUint::zero();
+        let mut subsidies = Vec::new();
+        for group in groups {
+            let rule = &rules[group.rule];
+            let group_normal_fee = if group.txs.len() == txs.len() {
+                normal_fee.clone()
+            } else {
+                group
+                    .txs
+                    .iter()
+                    .map(|&tx| &normal_tx_fees[tx])
+                    .sum::<Ratio<BigUint>>()
+                    .to_integer()
+            };
+            let group_subsidized_fee = (rule.max_fee_usd() * BigUint::from(group.txs.len())
+                / &token_price_in_usd)
+                .to_integer();
+            if group_subsidized_fee >= group_normal_fee {
+                continue;
+            }
+            let subsidy_usd = &token_price_in_usd * (&group_normal_fee - &group_subsidized_fee);
+
+            // Amounts subsidized by the previous groups are taken into account.
+            if !used_by_rule.contains_key(&group.rule) {
+                let used = storage
+                    .misc_schema()
+                    .get_total_used_subsidy_for_type(&rule.name)
+                    .await?;
+                used_by_rule.insert(group.rule, scaled_big_decimal_to_ratio(used)?);
+            }
+            let used_by_rule_after = &used_by_rule[&group.rule] + &subsidy_usd;
+            if used_by_rule_after > rule.budget_usd() {
+                continue;
+            }
+            let mut used_by_address_after = None;
+            if let (Some(max_per_address), Some(sender)) =
+                (rule.max_per_address_usd(), group.sender)
+            {
+                let key = (group.rule, sender);
+                if !used_by_address.contains_key(&key) {
+                    let used = storage
+                        .misc_schema()
+                        .get_total_used_subsidy_for_address(&rule.name, sender)
+                        .await?;
+                    used_by_address.insert(key, scaled_big_decimal_to_ratio(used)?);
+                }
+                let used = &used_by_address[&key] + &subsidy_usd;
+                if used > max_per_address {
+                    continue;
+                }
+                used_by_address_after = Some((key, used));
+            }
+            used_by_rule.insert(group.rule, used_by_rule_after);
+            used_by_address.extend(used_by_address_after);
+
+            total_subsidy += &group_normal_fee - &group_subsidized_fee;
+            subsidies.push(AppliedSubsidy {
+                rule_name: rule.name.clone(),
+                sender: group.sender,
+                normal_fee_usd: &token_price_in_usd * &group_normal_fee,
+                subsidy_usd,
+                normal_fee: group_normal_fee,
+                subsidized_fee: group_subsidized_fee,
+                budget_usd_scaled: rule.budget_usd_scaled,
+                max_per_address_usd_scaled: rule.max_per_address_usd_scaled,
+            });
+        }
 
-        let result = if self.max_subsidy_usd > subsidized_already_usd {
-            &self.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
+        if subsidies.is_empty() {
+            return Ok(None);
+        }
+        // Fees of the groups are calculated separately, so their sum may differ from the total fee.
+        let total_fee = if normal_fee > &total_subsidy {
+            normal_fee - &total_subsidy
         } else {
-            false
+            BigUint::zero()
         };
-
-        Ok(result)
+        Ok(Some(SubsidizedFee {
+            total_fee: closest_packable_fee_amount(&total_fee),
+            token_id: token.id,
+            subsidies,
+        }))
     }
 
-    pub async fn should_subsidize_cpk(
+    /// Stores the applied subsidies if they fit the budgets of their rules. Either all the subsidies
+    /// are stored or none of them. Returns the ids of the stored subsidies or `None` if any of
+    /// the budgets is exceeded.
+    async fn reserve_subsidies(
         &self,
-        normal_fee: &BigUint,
-        subsidized_fee: &BigUint,
-        subsidy_size_usd: &Ratio<BigUint>,
-        extracted_request_metadata: Option<RequestMetadata>,
-    ) -> Result<bool, SubmitError> {
-        let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
-            self.subsidized_ips.contains(&meta.ip)
-        } else {
-            false
-        };
+        hash: TxHash,
+        fee: &SubsidizedFee,
+    ) -> Result<Option<Vec<i32>>, anyhow::Error> {
+        let mut subsidies = fee.subsidies.clone();
+        // Rules are locked in the same order to avoid deadlocks between the concurrent requests.
+        subsidies.sort_by(|a, b| a.rule_name.cmp(&b.rule_name));
+
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        let mut ids = Vec::with_capacity(subsidies.len());
+        for subsidy in subsidies {
+            let record = Subsidy {
+                usd_amount_scaled: ratio_to_scaled_u64(subsidy.subsidy_usd),
+                full_cost_usd_scaled: ratio_to_scaled_u64(subsidy.normal_fee_usd),
+                token_id: fee.token_id,
+                token_amount: biguint_to_big_decimal(subsidy.subsidized_fee),
+                full_cost_token: biguint_to_big_decimal(subsidy.normal_fee),
+                subsidy_type: subsidy.rule_name,
+                tx_hash: hash,
+                address: subsidy.sender,
+            };
+            let id = transaction
+                .misc_schema()
+                .store_subsidy_within_budget(
+                    record,
+                    subsidy.budget_usd_scaled,
+                    subsidy.max_per_address_usd_scaled,
+                )
+                .await?;
+            match id {
+                Some(id) => ids.push(id),
+                // Dropping the transaction discards the subsidies stored so far.
+                None => return Ok(None),
+            }
+        }
+        transaction.commit().await?;
 
-        let result = should_subsidize_ip
-            && subsidized_fee < normal_fee
-            && self
-                .can_subsidize(subsidy_size_usd.clone())
-                .await
-                .map_err(SubmitError::Internal)?;
+        Ok(Some(ids))
+    }
 
-        Ok(result)
+    /// Removes the subsidies reserved for the transaction rejected by the mempool.
+    async fn release_subsidies(&self, ids: &[i32]) {
+        if ids.is_empty() {
+            return;
+        }
+        let result = match self.pool.access_storage().await {
+            Ok(mut storage) => storage.misc_schema().remove_subsidies(ids).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            metrics::increment_counter!("tx_sender.release_subsidies_fail");
+            vlog::warn!("Failed to release the subsidies {:?}: {}", ids, err);
+        }
     }
 
-    pub async fn store_subsidy_data(
+    /// Returns the fee bound by the quote if the quote is valid for the transactions
+    /// paying fee in the token.
+    async fn quoted_fee(
         &self,
-        hash: TxHash,
-        normal_fee: BigUint,
-        subsidized_fee: BigUint,
-        token_id: TokenId,
-    ) -> Result<(), anyhow::Error> {
-        let token_price_in_usd = self
-            .ticker
-            .get_token_price(TokenLike::Id(token_id), TokenPriceRequestType::USDForOneWei)
-            .await?;
+        fee_quote_id: i64,
+        token: TokenLike,
+        txs: &[(TxFeeTypes, Address)],
+    ) -> Result<BigUint, SubmitError> {
+        let token = self.token_info_from_id(token).await?;
+        let fee = self.ticker.quoted_fee(fee_quote_id, token.id, txs).await?;
+        Ok(fee)
+    }
 
-        let full_cost_usd = big_decimal_to_ratio(&token_price_in_usd)? * &normal_fee;
-        let subsidized_cost_usd = big_decimal_to_ratio(&token_price_in_usd)? * &subsidized_fee;
-        if full_cost_usd < subsidized_cost_usd {
-            return Err(anyhow::Error::msg(
-                "Trying to subsidize transaction which should not be subsidized",
-            ));
+    /// Sends the transaction (or the batch) to the mempool. The subsidies are reserved and the fee
+    /// quote is claimed right before sending, so that the concurrent submissions can't exceed
+    /// the subsidy budgets or redeem the same quote. Both are released if the mempool rejects
+    /// the transaction.
+    async fn send_to_mempool(
+        &self,
+        item: MempoolTransactionRequest,
+        receiver: oneshot::Receiver<Result<(), TxAddError>>,
+        fee_quote_id: Option<i64>,
+        subsidized_fee: Option<&SubsidizedFee>,
+        tx_hash: TxHash,
+    ) -> Result<(), SubmitError> {
+        let mut subsidy_ids = Vec::new();
+        if let Some(subsidized_fee) = subsidized_fee {
+            subsidy_ids = self
+                .reserve_subsidies(tx_hash, subsidized_fee)
+                .await
+                .map_err(SubmitError::internal)?
+                .ok_or_else(|| {
+                    metrics::increment_counter!("tx_sender.subsidy_budget_exceeded");
+                    // Without the subsidy the provided fee doesn't cover the normal fee.
+                    let err = match &item {
+                        MempoolTransactionRequest::NewTxsBatch(..) => TxAddError::TxBatchFeeTooLow,
+                        _ => TxAddError::TxFeeTooLow,
+                    };
+                    SubmitError::TxAdd(err)
+                })?;
         }
 
-        let subsidy = Subsidy {
-            usd_amount_scaled: ratio_to_scaled_u64(&full_cost_usd - &subsidized_cost_usd),
-            full_cost_usd_scaled: ratio_to_scaled_u64(full_cost_usd),
-            token_id,
-            token_amount: biguint_to_big_decimal(subsidized_fee),
-            full_cost_token: biguint_to_big_decimal(normal_fee),
-            subsidy_type: self.current_subsidy_type.clone(),
-            tx_hash: hash,
-        };
+        if let Some(fee_quote_id) = fee_quote_id {
+            if let Err(err) = self.ticker.claim_fee_quote(fee_quote_id, tx_hash).await {
+                self.release_subsidies(&subsidy_ids).await;
+                return Err(err.into());
+            }
+        }
 
-        self.pool
-            .access_storage()
-            .await?
-            .misc_schema()
-            .store_subsidy(subsidy)
-            .await?;
+        let mut mempool_sender = self.mempool_tx_sender.clone();
+        let result = match mempool_sender.send(item).await {
+            Ok(()) => receiver
+                .await
+                .map_err(SubmitError::internal)
+                .and_then(|result| result.map_err(SubmitError::from)),
+            Err(err) => Err(SubmitError::mempool_communication(err)),
+        };
 
-        Ok(())
+        if result.is_err() {
+            if let Some(fee_quote_id) = fee_quote_id {
+                if let Err(err) = self.ticker.release_fee_quote(fee_quote_id, tx_hash).await {
+                    metrics::increment_counter!("tx_sender.release_fee_quote_fail");
+                    vlog::warn!("Failed to release the fee quote {}: {}", fee_quote_id, err);
+                }
+            }
+            self.release_subsidies(&subsidy_ids).await;
+        }
+        result
     }
 
     pub async fn submit_tx(
         &self,
         tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TxHash, SubmitError> {
         let labels = vec![
@@ -522,7 +756,14 @@ impl TxSender {
 
         let sign_verify_channel = self.sign_verify_requests.clone();
 
-        let mut fee_data_for_subsidy: Option<ResponseFee> = None;
+        let tx_sender = self
+            .get_tx_sender(&tx)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+
+        let mut fee_data_for_subsidy: Option<SubsidizedFee> = None;
+        let mut used_fee_quote_id = None;
+        let mut quote_snapshot = None;
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
             let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
@@ -534,41 +775,57 @@ impl TxSender {
                 return Err(SubmitError::InappropriateFeeToken);
             }
 
-            let required_fee_data = self
+            let ticker_fee = self
                 .ticker
                 .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
                 .await?;
-
-            let required_fee_data = if self
-                .should_subsidize_cpk(
-                    &required_fee_data.normal_fee.total_fee,
-                    &required_fee_data.subsidized_fee.total_fee,
-                    &required_fee_data.subsidy_size_usd,
-                    extracted_request_metadata,
+            let normal_fee = ticker_fee.normal_fee.total_fee;
+            quote_snapshot = Some(ticker_fee.snapshot);
+            let subsidy_candidate = SubsidyCandidate {
+                tx_type,
+                address,
+                sender: Some(tx_sender),
+            };
+            let subsidized_fee = self
+                .subsidized_fee(
+                    token.clone(),
+                    &[subsidy_candidate],
+                    &normal_fee,
+                    &[Ratio::from(normal_fee.clone())],
+                    extracted_request_metadata.as_ref(),
                 )
-                .await?
-            {
-                fee_data_for_subsidy = Some(required_fee_data.clone());
-                required_fee_data.subsidized_fee
-            } else {
-                required_fee_data.normal_fee
+                .await?;
+
+            // The quoted fee is accepted even if the ticker fee has grown since the quote.
+            let mut unsubsidized_fee = normal_fee;
+            if let Some(fee_quote_id) = fee_quote_id {
+                let quoted_fee = self
+                    .quoted_fee(fee_quote_id, token.clone(), &[(tx_type, address)])
+                    .await?;
+                unsubsidized_fee = unsubsidized_fee.min(quoted_fee);
+                used_fee_quote_id = Some(fee_quote_id);
+            }
+            let required_fee = match &subsidized_fee {
+                Some(fee) => fee.total_fee.clone().min(unsubsidized_fee.clone()),
+                None => unsubsidized_fee.clone(),
             };
 
             // Converting `BitUint` to `BigInt` is safe.
-            let required_fee: BigDecimal = required_fee_data.total_fee.to_bigint().unwrap().into();
+            let required_fee: BigDecimal = required_fee.to_bigint().unwrap().into();
             let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
             let scaled_provided_fee = scale_user_fee_up(provided_fee);
             if required_fee >= scaled_provided_fee && should_enforce_fee {
                 return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
             }
+            // The subsidy is spent only if the provided fee doesn't cover the normal fee
+            // or the quoted one.
+            let unsubsidized_fee: BigDecimal = unsubsidized_fee.to_bigint().unwrap().into();
+            if unsubsidized_fee >= scaled_provided_fee && should_enforce_fee {
+                fee_data_for_subsidy = subsidized_fee;
+            }
         }
 
-        let tx_sender = self
-            .get_tx_sender(&tx)
-            .await
-            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
-
         let verified_tx = verify_tx_info_message_signature(
             &tx,
             tx_sender,
@@ -594,36 +851,17 @@ impl TxSender {
 
         let (sender, receiver) = oneshot::channel();
         let item = MempoolTransactionRequest::NewTx(Box::new(verified_tx), sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::internal)?;
+        self.send_to_mempool(
+            item,
+            receiver,
+            used_fee_quote_id,
+            fee_data_for_subsidy.as_ref(),
+            tx.hash(),
+        )
+        .await?;
 
-        receiver.await.map_err(SubmitError::internal)??;
-
-        // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
-        if let Some(fee_data_for_subsidy) = fee_data_for_subsidy {
-            // The following two bad scenarios are possible when applying subsidy for the tx:
-            // - The subsidy is stored, but the tx is then rejected by the state keeper
-            // - The tx is accepted by the state keeper, but the the `store_subsidy_data` returns an error for some reason
-            //
-            // Trying to omit these scenarios unfortunately leads to large code restructure
-            // which is not worth it for subsidies (we prefer stability here)
-            self.store_subsidy_data(
-                tx.hash(),
-                fee_data_for_subsidy.normal_fee.total_fee,
-                fee_data_for_subsidy.subsidized_fee.total_fee,
-                token.id,
-            )
-            .await
-            .map_err(|e| {
-                metrics::increment_counter!("tx_sender.submit_tx.store_subsidy_data_fail");
-                SubmitError::Other(format!(
-                    "Failed to store the subsidy to database. Reason: {}",
-                    e
-                ))
-            })?;
+        if let Some(snapshot) = quote_snapshot {
+            self.ticker.store_quote_snapshot(tx.hash(), &snapshot).await;
         }
 
         // if everything is OK, return the transactions hashes.
@@ -634,6 +872,7 @@ impl TxSender {
         &self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
+        fee_quote_id: Option<i64>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<SubmitBatchResponse, SubmitError> {
         // Bring the received signatures into a vector for simplified work.
@@ -674,7 +913,7 @@ impl TxSender {
         let eth_token = TokenLike::Id(TokenId(0));
 
         let mut token_fees = HashMap::<Address, BigUint>::new();
-        let mut token_fees_ids = vec![];
+        let mut subsidy_candidates = vec![];
 
         for tx in &txs {
             let tx_fee_info = tx.tx.get_fee_info();
@@ -683,6 +922,15 @@ impl TxSender {
                 // Save the transaction type before moving on to the next one, otherwise
                 // the total fee won't get affected by it.
                 transaction_types.push((tx_type, address));
+                let sender = self
+                    .get_tx_sender(&tx.tx)
+                    .await
+                    .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+                subsidy_candidates.push(SubsidyCandidate {
+                    tx_type,
+                    address,
+                    sender: Some(sender),
+                });
 
                 if provided_fee == BigUint::zero() {
                     continue;
@@ -710,7 +958,6 @@ impl TxSender {
                     .await?;
 
                 let token_data = self.token_info_from_id(token).await?;
-                token_fees_ids.push(token_data.id);
                 let mut token_fee = token_fees.remove(&token_data.address).unwrap_or_default();
                 token_fee += &provided_fee;
                 token_fees.insert(token_data.address, token_fee);
@@ -721,29 +968,39 @@ impl TxSender {
             }
         }
 
-        let mut fee_data_for_subsidy: Option<ResponseBatchFee> = None;
+        let mut fee_data_for_subsidy: Option<SubsidizedFee> = None;
+        let quote_snapshot;
 
         // Only one token in batch
         if token_fees.len() == 1 {
             let (batch_token, fee_paid) = token_fees.into_iter().next().unwrap();
-            let batch_token_fee = self
+            let ticker_fee = self
                 .ticker
                 .get_batch_from_ticker_in_wei(batch_token.into(), transaction_types.clone())
                 .await?;
-
-            let required_fee = if self
-                .should_subsidize_cpk(
-                    &batch_token_fee.normal_fee.total_fee,
-                    &batch_token_fee.subsidized_fee.total_fee,
-                    &batch_token_fee.subsidy_size_usd,
-                    extracted_request_metadata,
+            let normal_fee = ticker_fee.normal_fee.total_fee;
+            quote_snapshot = ticker_fee.snapshot;
+            let subsidized_fee = self
+                .subsidized_fee(
+                    batch_token.into(),
+                    &subsidy_candidates,
+                    &normal_fee,
+                    &ticker_fee.normal_tx_fees,
+                    extracted_request_metadata.as_ref(),
                 )
-                .await?
-            {
-                fee_data_for_subsidy = Some(batch_token_fee.clone());
-                batch_token_fee.subsidized_fee.total_fee
-            } else {
-                batch_token_fee.normal_fee.total_fee
+                .await?;
+
+            // The quoted fee is accepted even if the ticker fee has grown since the quote.
+            let mut unsubsidized_fee = normal_fee;
+            if let Some(fee_quote_id) = fee_quote_id {
+                let quoted_fee = self
+                    .quoted_fee(fee_quote_id, batch_token.into(), &transaction_types)
+                    .await?;
+                unsubsidized_fee = unsubsidized_fee.min(quoted_fee);
+            }
+            let required_fee = match &subsidized_fee {
+                Some(fee) => fee.total_fee.clone().min(unsubsidized_fee.clone()),
+                None => unsubsidized_fee.clone(),
             };
 
             let user_provided_fee =
@@ -759,27 +1016,38 @@ impl TxSender {
                 );
                 return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
             }
+            // The subsidy is spent only if the provided fee doesn't cover the normal fee
+            // or the quoted one.
+            if BigDecimal::from(unsubsidized_fee.to_bigint().unwrap()) > user_provided_fee {
+                fee_data_for_subsidy = subsidized_fee;
+            }
         } else {
+            // Fee quotes are issued for a single token.
+            if fee_quote_id.is_some() {
+                return Err(SubmitError::InvalidFeeQuote(
+                    FeeQuoteError::Mismatch.to_string(),
+                ));
+            }
             // Calculate required fee for ethereum token
-            let required_eth_fee = self
+            let ticker_fee = self
                 .ticker
                 .get_batch_from_ticker_in_wei(eth_token.clone(), transaction_types)
                 .await?;
-
-            let required_fee = if self
-                .should_subsidize_cpk(
-                    &required_eth_fee.normal_fee.total_fee,
-                    &required_eth_fee.subsidized_fee.total_fee,
-                    &required_eth_fee.subsidy_size_usd,
-                    extracted_request_metadata,
+            let normal_fee = ticker_fee.normal_fee.total_fee;
+            quote_snapshot = ticker_fee.snapshot;
+            let subsidized_fee = self
+                .subsidized_fee(
+                    eth_token.clone(),
+                    &subsidy_candidates,
+                    &normal_fee,
+                    &ticker_fee.normal_tx_fees,
+                    extracted_request_metadata.as_ref(),
                 )
-                .await?
-            {
-                fee_data_for_subsidy = Some(required_eth_fee.clone());
-                required_eth_fee.subsidized_fee.total_fee
-            } else {
-                required_eth_fee.normal_fee.total_fee
-            };
+                .await?;
+
+            let required_fee = subsidized_fee
+                .as_ref()
+                .map_or_else(|| normal_fee.clone(), |fee| fee.total_fee.clone());
 
             let eth_price_in_usd = self
                 .ticker
@@ -801,6 +1069,12 @@ impl TxSender {
                 );
                 return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
             }
+            // The subsidy is spent only if the provided fee doesn't cover the normal fee.
+            let normal_total_usd_fee =
+                BigDecimal::from(normal_fee.to_bigint().unwrap()) * &eth_price_in_usd;
+            if normal_total_usd_fee > scaled_provided_fee_in_usd {
+                fee_data_for_subsidy = subsidized_fee;
+            }
         }
 
         for tx in txs.iter() {
@@ -876,54 +1150,23 @@ impl TxSender {
         verified_txs.extend(verified_batch.into_iter());
 
         let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
 
         let (sender, receiver) = oneshot::channel();
         let item =
             MempoolTransactionRequest::NewTxsBatch(verified_txs, verified_signatures, sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::mempool_communication)?;
-
-        receiver.await.map_err(SubmitError::internal)??;
-
-        let batch_hash = TxHash::batch_hash(&tx_hashes);
-
-        // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
-        if let Some(fee_data) = fee_data_for_subsidy {
-            let subsidy_token_id = if token_fees_ids.len() == 1 {
-                token_fees_ids[0]
-            } else {
-                // When there are more than token to pay the fee with,
-                // we get the price of the batch in ETH and then convert it to USD.
-                // Since the `subsidies` table contains the token_id field and the only fee which is fetched from the fee_ticker is
-                // in ETH, then we can consider ETH as the token_id of the subsidy. Even though formally this may not be the case.
-                TokenId(0)
-            };
-
-            // The following two bad scenarios are possible when applying subsidy for the tx:
-            // - The subsidy is stored, but the tx is then rejected by the state keeper
-            // - The tx is accepted by the state keeper, but the the `store_subsidy_data` returns an error for some reason
-            //
-            // Trying to omit these scenarios unfortunately leads to large code restructure
-            // which is not worth it for subsidies (we prefer stability here)
-            self.store_subsidy_data(
-                batch_hash,
-                fee_data.normal_fee.total_fee,
-                fee_data.subsidized_fee.total_fee,
-                subsidy_token_id,
-            )
-            .await
-            .map_err(|e| {
-                metrics::increment_counter!("tx_sender.submit_txs_batch.store_subsidy_data_fail");
+        self.send_to_mempool(
+            item,
+            receiver,
+            fee_quote_id,
+            fee_data_for_subsidy.as_ref(),
+            batch_hash,
+        )
+        .await?;
 
-                SubmitError::Other(format!(
-                    "Failed to store the subsidy to database. Reason: {}",
-                    e
-                ))
-            })?;
-        }
+        self.ticker
+            .store_quote_snapshot(batch_hash, &quote_snapshot)
+            .await;
 
         Ok(SubmitBatchResponse {
             transaction_hashes: tx_hashes.into_iter().map(TxHashSerializeWrapper).collect(),
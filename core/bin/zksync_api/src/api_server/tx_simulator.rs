@@ -0,0 +1,225 @@
+//! Dry-run execution of transactions against the committed state.
+//!
+//! Only the accounts touched by the simulated transactions are loaded from the database,
+//! so the simulation is cheap enough to be served by the API. Nonces of the loaded accounts
+//! take into account the transactions waiting in the mempool, since the simulated transactions
+//! would be executed after them.
+
+// Built-in uses
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+// External uses
+// Workspace uses
+use zksync_api_types::v02::transaction::{SimulatedFee, TxSimulationResult};
+use zksync_crypto::params::NFT_STORAGE_ACCOUNT_ID;
+use zksync_state::state::{OpSuccess, ZkSyncState};
+use zksync_storage::StorageProcessor;
+use zksync_types::{AccountId, AccountMap, Address, SignedZkSyncTx, ZkSyncTx, NFT};
+// Local uses
+
+/// Returns IDs and addresses of the accounts that may be read during the transaction execution.
+fn involved_accounts(tx: &ZkSyncTx) -> (Vec<AccountId>, Vec<Address>) {
+    let mut ids = Vec::new();
+    let mut addresses = vec![tx.account()];
+    addresses.extend(tx.to_account());
+
+    match tx {
+        ZkSyncTx::ForcedExit(tx) => {
+            ids.push(tx.initiator_account_id);
+        }
+        ZkSyncTx::Swap(tx) => {
+            ids.extend([
+                tx.submitter_id,
+                tx.orders.0.account_id,
+                tx.orders.1.account_id,
+            ]);
+            addresses.extend([tx.orders.0.recipient_address, tx.orders.1.recipient_address]);
+        }
+        ZkSyncTx::MintNFT(tx) => {
+            ids.extend([tx.creator_id, NFT_STORAGE_ACCOUNT_ID]);
+        }
+        _ => {}
+    }
+
+    (ids, addresses)
+}
+
+/// Loads the committed state of the accounts and NFTs required to execute the transactions.
+async fn load_state(
+    storage: &mut StorageProcessor<'_>,
+    txs: &[ZkSyncTx],
+) -> anyhow::Result<ZkSyncState> {
+    let mut account_ids = HashSet::new();
+    let mut addresses = HashSet::new();
+    let mut nfts = HashMap::new();
+
+    for tx in txs {
+        let (tx_account_ids, tx_addresses) = involved_accounts(tx);
+        account_ids.extend(tx_account_ids);
+        addresses.extend(tx_addresses);
+
+        if let ZkSyncTx::WithdrawNFT(tx) = tx {
+            let nft: Option<NFT> = storage
+                .chain()
+                .state_schema()
+                .get_mint_nft_update(tx.token)
+                .await?;
+            if let Some(nft) = nft {
+                addresses.insert(nft.creator_address);
+                nfts.insert(tx.token, nft);
+            }
+        }
+    }
+
+    for address in addresses {
+        if let Some(account_id) = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await?
+        {
+            account_ids.insert(account_id);
+        }
+    }
+
+    let mut accounts = AccountMap::default();
+    for account_id in account_ids {
+        let (_, committed_state) = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await?;
+        let mut account = match committed_state {
+            Some(account) => account,
+            None => continue,
+        };
+
+        account.nonce = storage
+            .chain()
+            .mempool_schema()
+            .get_pending_nonce(account_id, account.nonce)
+            .await?;
+        accounts.insert(account_id, account);
+    }
+
+    let next_free_id = storage
+        .chain()
+        .account_schema()
+        .next_free_account_id()
+        .await?;
+
+    Ok(ZkSyncState::from_partial_acc_map(
+        accounts,
+        nfts,
+        next_free_id,
+    ))
+}
+
+fn simulation_result(successes: Vec<OpSuccess>) -> TxSimulationResult {
+    let mut result = TxSimulationResult {
+        success: true,
+        updates: Vec::new(),
+        chunks: 0,
+        fees: Vec::new(),
+        fail_reason: None,
+        failed_tx_index: None,
+    };
+    for success in successes {
+        result.chunks += success.executed_op.chunks();
+        result.updates.extend(success.updates);
+        if let Some(fee) = success.fee {
+            result.fees.push(SimulatedFee {
+                token: fee.token,
+                amount: fee.amount,
+            });
+        }
+    }
+    result
+}
+
+fn failure_result(fail_reason: String, failed_tx_index: Option<usize>) -> TxSimulationResult {
+    TxSimulationResult {
+        success: false,
+        updates: Vec::new(),
+        chunks: 0,
+        fees: Vec::new(),
+        fail_reason: Some(fail_reason),
+        failed_tx_index,
+    }
+}
+
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("failed to get system time")
+        .as_secs()
+}
+
+/// Executes a single transaction against the committed state.
+pub(crate) async fn simulate_tx(
+    storage: &mut StorageProcessor<'_>,
+    tx: ZkSyncTx,
+) -> anyhow::Result<TxSimulationResult> {
+    let mut state = load_state(storage, std::slice::from_ref(&tx)).await?;
+
+    let result = match state.execute_tx(tx, current_timestamp()) {
+        Ok(success) => simulation_result(vec![success]),
+        Err(error) => failure_result(error.to_string(), None),
+    };
+    Ok(result)
+}
+
+/// Executes a batch of transactions against the committed state.
+/// Either all of the transactions succeed, or the whole batch fails.
+pub(crate) async fn simulate_txs_batch(
+    storage: &mut StorageProcessor<'_>,
+    txs: Vec<ZkSyncTx>,
+) -> anyhow::Result<TxSimulationResult> {
+    let mut state = load_state(storage, &txs).await?;
+
+    let signed_txs: Vec<SignedZkSyncTx> = txs.into_iter().map(SignedZkSyncTx::from).collect();
+    let results = state.execute_txs_batch(&signed_txs, current_timestamp());
+
+    let mut successes = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(success) => successes.push(success),
+            Err(error) => {
+                // Indices in the batch error are 1-based.
+                return Ok(failure_result(
+                    error.reason.to_string(),
+                    Some(error.failed_tx_index - 1),
+                ));
+            }
+        }
+    }
+    Ok(simulation_result(successes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{tx::Transfer, Nonce, TokenId};
+
+    #[test]
+    fn involved_accounts_transfer() {
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let (ids, addresses) = involved_accounts(&ZkSyncTx::Transfer(Box::new(transfer)));
+
+        assert!(ids.is_empty());
+        assert_eq!(
+            addresses,
+            vec![Address::repeat_byte(1), Address::repeat_byte(2)]
+        );
+    }
+}
@@ -14,10 +14,54 @@ use zksync_types::{Nonce, Token, TokenId, TokenKind, ZkSyncOp, NFT};
 // Local uses
 use super::{
     converter::{log, u256_from_biguint},
-    types::{Bytes, CommonLogData, Event, Log, H160, H256, U256},
+    types::{Bytes, CommonLogData, Event, Filter, Log, H160, H256, U256},
     NFT_FACTORY_ADDRESS, ZKSYNC_PROXY_ADDRESS,
 };
 
+/// Address and topic conditions of the logs filter.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    topics: Vec<H256>,
+    addresses: Vec<H160>,
+}
+
+impl LogFilter {
+    /// Creates the filter from the user-provided one. Block range is ignored.
+    ///
+    /// Returns `None` if no log can match the filter.
+    pub fn new(filter: Filter) -> Option<Self> {
+        let topics = if let Some(mut topics) = filter.topics {
+            // If there is non-null topic at the non-first position then nothing matches,
+            // since all our logs contain exactly one topic.
+            let has_not_first = topics
+                .iter()
+                .enumerate()
+                .any(|(i, topic)| i > 0 && topic.is_some());
+            if has_not_first {
+                return None;
+            } else if topics.is_empty() {
+                Vec::new()
+            } else {
+                topics.remove(0).unwrap_or_default().0
+            }
+        } else {
+            Vec::new()
+        };
+        let addresses = filter.address.map(|a| a.0).unwrap_or_default();
+        Some(Self { topics, addresses })
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.topics.is_empty() && !self.topics.contains(&log.topics[0]) {
+            return false;
+        }
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        true
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogsHelper {
     topic_by_event: HashMap<Event, H256>,
@@ -1,14 +1,23 @@
 // Built-in uses
+use std::{sync::Arc, time::Duration};
 // External uses
-
+use futures::channel::mpsc;
 use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
+use jsonrpc_pubsub::{PubSubHandler, Session};
+use jsonrpc_ws_server::RequestContext;
 // Workspace uses
 
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
-use self::{calls::CallsHelper, logs::LogsHelper, rpc_trait::Web3Rpc};
+use self::{
+    calls::CallsHelper,
+    logs::LogsHelper,
+    pubsub::{Web3PubSub, Web3PubSubApp},
+    rpc_trait::Web3Rpc,
+};
+use super::event_notify::start_sub_notifier;
 
 use tokio::task::JoinHandle;
 use zksync_config::configs::api::{TokenConfig, Web3Config};
@@ -16,11 +25,12 @@ use zksync_config::configs::api::{TokenConfig, Web3Config};
 mod calls;
 mod converter;
 mod logs;
+pub(crate) mod pubsub;
 mod rpc_impl;
 mod rpc_trait;
 #[cfg(test)]
 mod tests;
-mod types;
+pub(crate) mod types;
 
 pub const ZKSYNC_PROXY_ADDRESS: &str = "1000000000000000000000000000000000000000";
 pub const NFT_FACTORY_ADDRESS: &str = "2000000000000000000000000000000000000000";
@@ -85,3 +95,46 @@ pub fn start_rpc_server(
     });
     handler
 }
+
+pub fn start_ws_server(
+    connection_pool: ConnectionPool,
+    web3_config: &Web3Config,
+    token_config: &TokenConfig,
+    caches_size: usize,
+    miniblock_iteration_interval: Duration,
+) -> JoinHandle<()> {
+    let addr = web3_config.ws_bind_addr();
+
+    let rpc_app = Web3RpcApp::new(connection_pool.clone(), web3_config, token_config);
+    let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
+
+    start_sub_notifier(
+        connection_pool,
+        event_sub_receiver,
+        caches_size,
+        miniblock_iteration_interval,
+        token_config,
+        Some(rpc_app.clone()),
+    );
+
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::spawn(move || {
+        let _panic_sentinel = ThreadPanicNotify(panic_sender);
+        let mut io = PubSubHandler::new(MetaIoHandler::default());
+
+        rpc_app.extend(&mut io);
+        io.extend_with(Web3PubSubApp::new(event_sub_sender).to_delegate());
+
+        let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+            io,
+            |context: &RequestContext| Arc::new(Session::new(context.sender())),
+        )
+        .max_connections(1000)
+        .start(&addr)
+        .expect("Unable to start web3 ws server");
+
+        server.wait().expect("web3 ws server start");
+    });
+    handler
+}
@@ -0,0 +1,228 @@
+//! Pub/sub part of the web3 API: `eth_subscribe` and `eth_unsubscribe` methods.
+//!
+//! Subscriptions are stored by the `OperationNotifier` and are notified once
+//! a block becomes finalized, which corresponds to the `latest` block in the web3 API.
+
+// Built-in uses
+use std::{collections::HashMap, sync::Arc};
+// External uses
+use futures::channel::mpsc;
+use jsonrpc_core::{Error, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{
+    typed::{Sink, Subscriber},
+    Session, SubscriptionId,
+};
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_storage::StorageProcessor;
+use zksync_types::BlockNumber;
+// Local uses
+use super::{
+    logs::LogFilter,
+    types::{BlockInfo, Filter, Log},
+    Web3RpcApp,
+};
+use crate::api_server::event_notify::{EventNotifierRequest, EventSubscribeRequest};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    NewHeads,
+    Logs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum PubSubResult {
+    Header(Box<BlockInfo>),
+    Log(Log),
+}
+
+#[rpc(server)]
+pub trait Web3PubSub {
+    type Metadata;
+
+    #[pubsub(subscription = "eth_subscription", subscribe, name = "eth_subscribe")]
+    fn subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        kind: SubscriptionKind,
+        params: Option<Filter>,
+    );
+
+    #[pubsub(
+        subscription = "eth_subscription",
+        unsubscribe,
+        name = "eth_unsubscribe"
+    )]
+    fn unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
+}
+
+pub struct Web3PubSubApp {
+    event_sub_sender: mpsc::Sender<EventNotifierRequest>,
+}
+
+impl Web3PubSubApp {
+    pub fn new(event_sub_sender: mpsc::Sender<EventNotifierRequest>) -> Self {
+        Self { event_sub_sender }
+    }
+}
+
+impl Web3PubSub for Web3PubSubApp {
+    type Metadata = Arc<Session>;
+
+    fn subscribe(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: Subscriber<PubSubResult>,
+        kind: SubscriptionKind,
+        params: Option<Filter>,
+    ) {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Sub(EventSubscribeRequest::Web3 {
+                kind,
+                filter: params,
+                subscriber,
+            }))
+            .unwrap_or_default();
+    }
+
+    fn unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
+}
+
+/// Storage of the web3 subscriptions. Unlike other notifier subscriptions,
+/// these are not removed after the first notification.
+pub struct Web3Subscriptions {
+    app: Web3RpcApp,
+    new_heads: HashMap<SubscriptionId, Sink<PubSubResult>>,
+    /// `None` filter means that no log can match the subscription.
+    logs: HashMap<SubscriptionId, (Option<LogFilter>, Sink<PubSubResult>)>,
+}
+
+impl Web3Subscriptions {
+    pub fn new(app: Web3RpcApp) -> Self {
+        Self {
+            app,
+            new_heads: HashMap::new(),
+            logs: HashMap::new(),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        kind: SubscriptionKind,
+        filter: Option<Filter>,
+        subscriber: Subscriber<PubSubResult>,
+    ) -> anyhow::Result<()> {
+        if kind == SubscriptionKind::NewHeads && filter.is_some() {
+            return subscriber
+                .reject(Error::invalid_params(
+                    "`newHeads` subscription doesn't accept parameters",
+                ))
+                .map_err(|_| anyhow::format_err!("SubReject"));
+        }
+
+        let sub_id =
+            SubscriptionId::String(format!("0x{:016x}", zksync_crypto::rand::random::<u64>()));
+        let sink = subscriber
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
+        match kind {
+            SubscriptionKind::NewHeads => {
+                self.new_heads.insert(sub_id, sink);
+            }
+            SubscriptionKind::Logs => {
+                let log_filter = LogFilter::new(filter.unwrap_or_default());
+                self.logs.insert(sub_id, (log_filter, sink));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the subscription, returns `false` if it's not a web3 subscription.
+    pub fn remove(&mut self, sub_id: &SubscriptionId) -> bool {
+        self.new_heads.remove(sub_id).is_some() || self.logs.remove(sub_id).is_some()
+    }
+
+    /// Notifies subscribers about the new finalized block.
+    /// Subscriptions of disconnected clients are removed.
+    pub async fn handle_new_block(&mut self, block_number: BlockNumber) -> anyhow::Result<()> {
+        if self.new_heads.is_empty() && self.logs.is_empty() {
+            return Ok(());
+        }
+        let mut storage = self.app.connection_pool.access_storage().await?;
+
+        // Failure to notify subscriptions of one kind should not affect the other one.
+        if let Err(err) = self.notify_new_heads(&mut storage, block_number).await {
+            vlog::warn!(
+                "Failed to notify `newHeads` subscribers, block: {}, reason: {}",
+                *block_number,
+                err
+            );
+        }
+        if let Err(err) = self.notify_logs(&mut storage, block_number).await {
+            vlog::warn!(
+                "Failed to notify `logs` subscribers, block: {}, reason: {}",
+                *block_number,
+                err
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn notify_new_heads(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<()> {
+        if self.new_heads.is_empty() {
+            return Ok(());
+        }
+        let header = Web3RpcApp::block_by_number(storage, block_number, false)
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to load block: {}", err.message))?;
+        let header = PubSubResult::Header(Box::new(header));
+        self.new_heads
+            .retain(|_, sink| sink.notify(Ok(header.clone())).is_ok());
+        Ok(())
+    }
+
+    async fn notify_logs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<()> {
+        if self.logs.is_empty() {
+            return Ok(());
+        }
+        let logs = self
+            .app
+            .block_logs(storage, block_number, block_number, &LogFilter::default())
+            .await
+            .map_err(|err| anyhow::format_err!("Failed to load logs: {}", err.message))?;
+        self.logs.retain(|_, (log_filter, sink)| {
+            let log_filter = match log_filter {
+                Some(log_filter) => log_filter,
+                None => return true,
+            };
+            logs.iter()
+                .filter(|log| log_filter.matches(log))
+                .all(|log| sink.notify(Ok(PubSubResult::Log(log.clone()))).is_ok())
+        });
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@ use zksync_types::{ExecutedOperations, TokenId, ZkSyncOp};
 // Local uses
 use super::{
     converter::{resolve_block_number, transaction_from_tx_data, u256_from_biguint},
+    logs::LogFilter,
     types::{
         BlockInfo, BlockNumber, Bytes, CallRequest, CommonLogData, Filter, Log, Transaction,
         TransactionReceipt, TxData, H160, H2048, H256, U256, U64,
@@ -252,45 +253,13 @@ impl Web3RpcApp {
             )));
         }
 
-        let topics = if let Some(mut topics) = filter.topics {
-            // If there is non-null topic at the non-first position then return empty vec,
-            // since all our logs contain exactly one topic.
-            let has_not_first = topics
-                .iter()
-                .enumerate()
-                .any(|(i, topic)| i > 0 && topic.is_some());
-            if has_not_first {
-                return Ok(Vec::new());
-            } else if topics.is_empty() {
-                Vec::new()
-            } else {
-                topics.remove(0).unwrap_or_default().0
-            }
-        } else {
-            Vec::new()
+        let log_filter = match LogFilter::new(filter) {
+            Some(log_filter) => log_filter,
+            None => return Ok(Vec::new()),
         };
-        let addresses = filter.address.map(|a| a.0).unwrap_or_default();
-        let mut result = Vec::new();
-
-        let receipts = transaction
-            .chain()
-            .operations_ext_schema()
-            .web3_receipts(from_block, to_block)
-            .await
-            .map_err(|_| Error::internal_error())?;
-        for receipt in receipts {
-            let logs = self.logs_from_receipt(&mut transaction, receipt).await?;
-            let filtered = logs.into_iter().filter(|log| {
-                if !topics.is_empty() && !topics.contains(&log.topics[0]) {
-                    return false;
-                }
-                if !addresses.is_empty() && !addresses.contains(&log.address) {
-                    return false;
-                }
-                true
-            });
-            result.extend(filtered);
-        }
+        let result = self
+            .block_logs(&mut transaction, from_block, to_block, &log_filter)
+            .await?;
 
         transaction
             .commit()
@@ -314,6 +283,28 @@ impl Web3RpcApp {
         result.map(Bytes)
     }
 
+    /// Returns logs matching the filter produced by operations in the given block range.
+    pub(crate) async fn block_logs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        from_block: zksync_types::BlockNumber,
+        to_block: zksync_types::BlockNumber,
+        log_filter: &LogFilter,
+    ) -> Result<Vec<Log>> {
+        let mut result = Vec::new();
+        let receipts = storage
+            .chain()
+            .operations_ext_schema()
+            .web3_receipts(from_block, to_block)
+            .await
+            .map_err(|_| Error::internal_error())?;
+        for receipt in receipts {
+            let logs = self.logs_from_receipt(storage, receipt).await?;
+            result.extend(logs.into_iter().filter(|log| log_filter.matches(log)));
+        }
+        Ok(result)
+    }
+
     pub(crate) async fn logs_from_receipt(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -5,6 +5,7 @@ use ethabi::{ParamType, Token};
 use futures::future::{join, join5, Future};
 use jsonrpc_core::{Error, ErrorCode, IoHandler, Params};
 use jsonrpc_core_client::{RawClient, RpcError, RpcResult};
+use jsonrpc_pubsub::typed::Subscriber;
 use num::BigUint;
 use serde_json::{Map, Value};
 // Workspace uses
@@ -20,7 +21,11 @@ use zksync_types::{
 use super::{
     calls::CallsHelper,
     converter::{transaction_from_tx_data, u256_from_biguint},
-    types::{BlockInfo, Event, Log, Transaction, TransactionReceipt, H160, H256, U256, U64},
+    pubsub::{SubscriptionKind, Web3Subscriptions},
+    types::{
+        BlockInfo, Event, Filter, Log, Transaction, TransactionReceipt, ValueOrArray, H160, H256,
+        U256, U64,
+    },
     Web3RpcApp, NFT_FACTORY_ADDRESS, ZKSYNC_PROXY_ADDRESS,
 };
 use crate::api_server::rest::v02::test_utils::TestServerConfig;
@@ -908,6 +913,7 @@ async fn get_logs() -> anyhow::Result<()> {
             let config = Web3Config {
                 port: 0,
                 url: "".to_string(),
+                ws_port: 0,
                 max_block_range: 3,
                 chain_id: 9,
             };
@@ -1042,6 +1048,58 @@ async fn get_logs() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that `newHeads` and `logs` subscriptions are notified about the new blocks.
+#[tokio::test(flavor = "multi_thread")]
+#[cfg_attr(
+    not(feature = "api_test"),
+    ignore = "Use `zk test rust-api` command to perform this test"
+)]
+async fn subscriptions() -> anyhow::Result<()> {
+    let cfg = TestServerConfig::default();
+    cfg.fill_database().await?;
+    let rpc_app = Web3RpcApp::new(cfg.pool, &cfg.config.api.web3, &cfg.config.api.token_config);
+    let mut subscriptions = Web3Subscriptions::new(rpc_app);
+
+    // Checks that `newHeads` subscription with parameters is rejected.
+    let (subscriber, id_receiver, _) = Subscriber::new_test("eth_subscription");
+    subscriptions.add(
+        SubscriptionKind::NewHeads,
+        Some(Filter::default()),
+        subscriber,
+    )?;
+    assert!(id_receiver.await.unwrap().is_err());
+
+    let (subscriber, heads_id, mut heads_receiver) = Subscriber::new_test("eth_subscription");
+    subscriptions.add(SubscriptionKind::NewHeads, None, subscriber)?;
+    let heads_id = heads_id.await.unwrap().unwrap();
+
+    let (subscriber, _, mut logs_receiver) = Subscriber::new_test("eth_subscription");
+    subscriptions.add(SubscriptionKind::Logs, None, subscriber)?;
+
+    // All our logs have exactly one topic, so nothing matches this filter.
+    let filter = Filter {
+        topics: Some(vec![None, Some(ValueOrArray(vec![H256::zero()]))]),
+        ..Default::default()
+    };
+    let (subscriber, _, mut empty_receiver) = Subscriber::new_test("eth_subscription");
+    subscriptions.add(SubscriptionKind::Logs, Some(filter), subscriber)?;
+
+    subscriptions.handle_new_block(BlockNumber(1)).await?;
+    assert!(heads_receiver.try_next().unwrap().is_some());
+    assert!(heads_receiver.try_next().is_err());
+    let logs_count = std::iter::from_fn(|| logs_receiver.try_next().ok().flatten()).count();
+    assert_eq!(logs_count, 14);
+    assert!(empty_receiver.try_next().is_err());
+
+    // Checks that removed subscription is not notified anymore.
+    assert!(subscriptions.remove(&heads_id));
+    assert!(!subscriptions.remove(&heads_id));
+    subscriptions.handle_new_block(BlockNumber(1)).await?;
+    assert!(heads_receiver.try_next().is_err());
+
+    Ok(())
+}
+
 /// Tests `eth_call` method for erc20 contracts
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(
@@ -1,43 +1,30 @@
-use zksync_types::{
-    gas_counter::{CommitCost, VerifyCost},
-    ChangePubKeyOp, MintNFTOp, SwapOp, TransferOp, TransferToNewOp, WithdrawNFTOp, WithdrawOp,
-};
+use zksync_types::gas_counter::{CommitCost, VerifyCost};
 
-/// Gas cost per chunk to cover constant cost of commit, execute and prove transactions
+/// Gas cost per chunk to cover constant cost of commit, execute and prove transactions.
+/// Used unless the cost is calibrated from the gas used by the confirmed transactions.
 pub(crate) const AMORTIZED_COST_PER_CHUNK: u64 = 200;
+/// Number of percent the average gas price is scaled to in order to include the gas price risk.
+/// Used unless the scale is calibrated from the gas price paid by the confirmed transactions.
+pub(crate) const GAS_PRICE_SCALE_PERCENT: u64 = 130;
 // Base operation costs estimated via `gas_price` test.
-// Factor of AMORTIZED_COST_PER_CHUNK * CHUNKS accounts for constant overhead of the commit, execute, prove for blocks of 680 chunks
+// Amortized cost of the chunks is added on top to account for constant overhead of the commit,
+// execute, prove. Default AMORTIZED_COST_PER_CHUNK accounts for blocks of 680 chunks
 // where we assume that we commit 5 blocks at once, prove 10 and execute 5
-pub(crate) const BASE_TRANSFER_COST: u64 = VerifyCost::TRANSFER_COST
-    + CommitCost::TRANSFER_COST
-    + AMORTIZED_COST_PER_CHUNK * (TransferOp::CHUNKS as u64);
-pub(crate) const BASE_TRANSFER_TO_NEW_COST: u64 = VerifyCost::TRANSFER_TO_NEW_COST
-    + CommitCost::TRANSFER_TO_NEW_COST
-    + AMORTIZED_COST_PER_CHUNK * (TransferToNewOp::CHUNKS as u64);
-pub(crate) const BASE_WITHDRAW_COST: u64 = VerifyCost::WITHDRAW_COST
-    + VerifyCost::PENDING_WITHDRAW_COST
-    + CommitCost::WITHDRAW_COST
-    + AMORTIZED_COST_PER_CHUNK * (WithdrawOp::CHUNKS as u64);
+pub(crate) const BASE_TRANSFER_COST: u64 = VerifyCost::TRANSFER_COST + CommitCost::TRANSFER_COST;
+pub(crate) const BASE_TRANSFER_TO_NEW_COST: u64 =
+    VerifyCost::TRANSFER_TO_NEW_COST + CommitCost::TRANSFER_TO_NEW_COST;
+pub(crate) const BASE_WITHDRAW_COST: u64 =
+    VerifyCost::WITHDRAW_COST + VerifyCost::PENDING_WITHDRAW_COST + CommitCost::WITHDRAW_COST;
 pub(crate) const BASE_WITHDRAW_NFT_COST: u64 = VerifyCost::WITHDRAW_NFT_COST
     + VerifyCost::PENDING_WITHDRAW_NFT_COST
-    + CommitCost::WITHDRAW_NFT_COST
-    + AMORTIZED_COST_PER_CHUNK * (WithdrawNFTOp::CHUNKS as u64);
+    + CommitCost::WITHDRAW_NFT_COST;
 pub(crate) const BASE_OLD_CHANGE_PUBKEY_OFFCHAIN_COST: u64 =
-    CommitCost::OLD_CHANGE_PUBKEY_COST_OFFCHAIN
-        + VerifyCost::CHANGE_PUBKEY_COST
-        + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
-pub(crate) const BASE_CHANGE_PUBKEY_OFFCHAIN_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_OFFCHAIN
-    + VerifyCost::CHANGE_PUBKEY_COST
-    + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
-pub(crate) const BASE_CHANGE_PUBKEY_CREATE2_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_CREATE2
-    + VerifyCost::CHANGE_PUBKEY_COST
-    + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
-pub(crate) const BASE_CHANGE_PUBKEY_ONCHAIN_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_ONCHAIN
-    + VerifyCost::CHANGE_PUBKEY_COST
-    + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
-pub(crate) const BASE_MINT_NFT_COST: u64 = VerifyCost::MINT_NFT_COST
-    + CommitCost::MINT_TOKEN_COST
-    + AMORTIZED_COST_PER_CHUNK * (MintNFTOp::CHUNKS as u64);
-pub(crate) const BASE_SWAP_COST: u64 = CommitCost::SWAP_COST
-    + VerifyCost::SWAP_COST
-    + AMORTIZED_COST_PER_CHUNK * (SwapOp::CHUNKS as u64);
+    CommitCost::OLD_CHANGE_PUBKEY_COST_OFFCHAIN + VerifyCost::CHANGE_PUBKEY_COST;
+pub(crate) const BASE_CHANGE_PUBKEY_OFFCHAIN_COST: u64 =
+    CommitCost::CHANGE_PUBKEY_COST_OFFCHAIN + VerifyCost::CHANGE_PUBKEY_COST;
+pub(crate) const BASE_CHANGE_PUBKEY_CREATE2_COST: u64 =
+    CommitCost::CHANGE_PUBKEY_COST_CREATE2 + VerifyCost::CHANGE_PUBKEY_COST;
+pub(crate) const BASE_CHANGE_PUBKEY_ONCHAIN_COST: u64 =
+    CommitCost::CHANGE_PUBKEY_COST_ONCHAIN + VerifyCost::CHANGE_PUBKEY_COST;
+pub(crate) const BASE_MINT_NFT_COST: u64 = VerifyCost::MINT_NFT_COST + CommitCost::MINT_TOKEN_COST;
+pub(crate) const BASE_SWAP_COST: u64 = CommitCost::SWAP_COST + VerifyCost::SWAP_COST;
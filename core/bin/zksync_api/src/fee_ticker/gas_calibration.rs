@@ -0,0 +1,388 @@
+//! Calibration of the gas costs used by the fee ticker.
+//!
+//! The gas cost of the operation consists of the cost estimated by the `GasCounter` model and
+//! the constant cost of the commit, proof and execute transactions amortized over the chunks
+//! used by the operations of the processed blocks. The calibrator derives the amortized cost
+//! per chunk from the gas used by the confirmed Ethereum transactions: the gas used on top of
+//! the estimated cost of the block operations is the overhead to be amortized. The gas price
+//! paid for these transactions relative to the average gas price of the gas adjuster is used
+//! to scale the gas price in the fee. The observed values are smoothed with the exponential
+//! moving average and bounded by the configured limits.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+// External deps
+use chrono::Utc;
+// Workspace deps
+use zksync_storage::{
+    ethereum::records::{StorageGasUsage, StorageProcessedOperations},
+    ticker::records::NewGasCalibration,
+    ConnectionPool,
+};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    gas_counter::{CommitCost, GasCounter, VerifyCost},
+    ChangePubKeyOp, CloseOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, NoopOp, SwapOp,
+    TransferOp, TransferToNewOp, WithdrawNFTOp, WithdrawOp, U256,
+};
+// Local deps
+use crate::fee_ticker::ticker_info::GasCalibration;
+
+/// Gas used per chunk by the transactions of each aggregated operation type
+/// on top of the estimated cost of the operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ObservedCosts {
+    commit_cost_per_chunk: u64,
+    proof_cost_per_chunk: u64,
+    execute_cost_per_chunk: u64,
+}
+
+impl ObservedCosts {
+    /// Derives the costs from the gas used by the confirmed transactions and the operations
+    /// of the blocks processed by them.
+    /// Returns `None` if no chunks were processed by the transactions of some type.
+    fn from_gas_usage(
+        usage: &[StorageGasUsage],
+        operations: &[StorageProcessedOperations],
+    ) -> Option<Self> {
+        let chunks = used_chunks(operations);
+        let cost_per_chunk = |action: AggregatedActionType| {
+            let op_type = action.to_string();
+            let usage = usage.iter().find(|usage| usage.op_type == op_type)?;
+            let chunks = chunks.get(&op_type).copied().unwrap_or_default();
+            if chunks <= 0 {
+                return None;
+            }
+            let operations_cost = match action {
+                AggregatedActionType::CommitBlocks => {
+                    estimated_gas_cost(usage.commit_gas_limit)
+                        - usage.blocks * CommitCost::BASE_COST as i64
+                }
+                AggregatedActionType::ExecuteBlocks => {
+                    estimated_gas_cost(usage.verify_gas_limit)
+                        - usage.blocks * VerifyCost::BASE_COST as i64
+                }
+                _ => 0,
+            };
+            Some(((usage.gas_used - operations_cost).max(0) / chunks) as u64)
+        };
+
+        Some(Self {
+            commit_cost_per_chunk: cost_per_chunk(AggregatedActionType::CommitBlocks)?,
+            proof_cost_per_chunk: cost_per_chunk(AggregatedActionType::PublishProofBlocksOnchain)?,
+            execute_cost_per_chunk: cost_per_chunk(AggregatedActionType::ExecuteBlocks)?,
+        })
+    }
+
+    fn amortized_cost_per_chunk(&self) -> u64 {
+        self.commit_cost_per_chunk + self.proof_cost_per_chunk + self.execute_cost_per_chunk
+    }
+}
+
+/// Reverts the scaling applied by `GasCounter` to the estimated gas cost of the blocks
+/// to get their gas limit.
+fn estimated_gas_cost(gas_limit: i64) -> i64 {
+    let scale_percent = GasCounter::scale_up(U256::from(100)).as_u64() as i64;
+    gas_limit * 100 / scale_percent
+}
+
+/// Sums up the chunks used by the operations of the processed blocks
+/// for each aggregated operation type.
+fn used_chunks(operations: &[StorageProcessedOperations]) -> HashMap<String, i64> {
+    let mut chunks = HashMap::new();
+    for processed in operations {
+        match operation_chunks(&processed.operation_type) {
+            Some(operation_chunks) => {
+                *chunks.entry(processed.op_type.clone()).or_default() +=
+                    operation_chunks as i64 * processed.count;
+            }
+            None => {
+                vlog::warn!(
+                    "Unknown type of the executed operation: {}",
+                    processed.operation_type
+                );
+            }
+        }
+    }
+    chunks
+}
+
+/// Returns the number of chunks used by the operation of the given type.
+fn operation_chunks(operation_type: &str) -> Option<usize> {
+    let chunks = match operation_type {
+        "Noop" => NoopOp::CHUNKS,
+        "Deposit" => DepositOp::CHUNKS,
+        "TransferToNew" => TransferToNewOp::CHUNKS,
+        "Withdraw" => WithdrawOp::CHUNKS,
+        "WithdrawNFT" => WithdrawNFTOp::CHUNKS,
+        "Close" => CloseOp::CHUNKS,
+        "Transfer" => TransferOp::CHUNKS,
+        "FullExit" => FullExitOp::CHUNKS,
+        "ChangePubKeyOffchain" => ChangePubKeyOp::CHUNKS,
+        "ForcedExit" => ForcedExitOp::CHUNKS,
+        "Swap" => SwapOp::CHUNKS,
+        "MintNFTOp" => MintNFTOp::CHUNKS,
+        _ => return None,
+    };
+    Some(chunks)
+}
+
+/// Derives the ratio of the gas price paid by the confirmed transactions to the average
+/// gas price of the gas adjuster in percent.
+/// Returns `None` if there are no confirmed transactions or the average gas price is unknown.
+fn observed_gas_price_scale(usage: &[StorageGasUsage], average_gas_price: u64) -> Option<u64> {
+    let gas_used: u128 = usage.iter().map(|usage| usage.gas_used as u128).sum();
+    let fee: u128 = usage
+        .iter()
+        .map(|usage| usage.gas_used as u128 * usage.gas_price as u128)
+        .sum();
+    if gas_used == 0 || average_gas_price == 0 {
+        return None;
+    }
+    Some((fee * 100 / gas_used / average_gas_price as u128) as u64)
+}
+
+/// Periodically calibrates the amortized gas cost per chunk and the gas price scale
+/// and stores them to the database.
+#[derive(Debug, Clone)]
+pub(super) struct GasCostCalibrator {
+    db_pool: ConnectionPool,
+    /// Period of the confirmed transactions used for the calibration.
+    window: Duration,
+    /// Weight of the new observation in the smoothed values in percent.
+    smoothing_percent: u64,
+    min_cost_per_chunk: u64,
+    max_cost_per_chunk: u64,
+    min_gas_price_scale_percent: u64,
+    max_gas_price_scale_percent: u64,
+}
+
+impl GasCostCalibrator {
+    pub fn new(db_pool: ConnectionPool, config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            db_pool,
+            window: config.gas_calibration_window(),
+            smoothing_percent: config.gas_calibration_smoothing_percent.min(100),
+            min_cost_per_chunk: config.min_amortized_cost_per_chunk,
+            max_cost_per_chunk: config.max_amortized_cost_per_chunk,
+            min_gas_price_scale_percent: config.min_gas_price_scale_percent,
+            max_gas_price_scale_percent: config.max_gas_price_scale_percent,
+        }
+    }
+
+    pub async fn keep_calibrated(self, interval: Duration) {
+        loop {
+            if let Err(e) = self.calibrate().await {
+                vlog::error!("Can't calibrate the gas costs. Error: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn calibrate(&self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+        let since = Utc::now() - chrono::Duration::from_std(self.window)?;
+        let usage = storage.ethereum_schema().load_gas_usage(since).await?;
+        let operations = storage
+            .ethereum_schema()
+            .load_processed_operations(since)
+            .await?;
+        let observed = match ObservedCosts::from_gas_usage(&usage, &operations) {
+            Some(observed) => observed,
+            None => {
+                vlog::info!("Not enough confirmed transactions to calibrate the gas costs");
+                return Ok(());
+            }
+        };
+        let average_gas_price = storage
+            .ethereum_schema()
+            .load_average_gas_price()
+            .await?
+            .unwrap_or_default()
+            .as_u64();
+
+        let previous = storage
+            .ticker_schema()
+            .load_last_gas_calibration()
+            .await?
+            .map_or_else(GasCalibration::default, |calibration| GasCalibration {
+                amortized_cost_per_chunk: calibration.amortized_cost_per_chunk as u64,
+                gas_price_scale_percent: calibration.gas_price_scale_percent as u64,
+            });
+        let amortized_cost_per_chunk = smoothed_value(
+            previous.amortized_cost_per_chunk,
+            observed.amortized_cost_per_chunk(),
+            self.smoothing_percent,
+        )
+        .max(self.min_cost_per_chunk)
+        .min(self.max_cost_per_chunk);
+        // The previous scale is kept if the gas price paid can't be compared to the average one.
+        let observed_gas_price_scale = observed_gas_price_scale(&usage, average_gas_price);
+        let gas_price_scale_percent = smoothed_value(
+            previous.gas_price_scale_percent,
+            observed_gas_price_scale.unwrap_or(previous.gas_price_scale_percent),
+            self.smoothing_percent,
+        )
+        .max(self.min_gas_price_scale_percent)
+        .min(self.max_gas_price_scale_percent);
+        storage
+            .ticker_schema()
+            .store_gas_calibration(NewGasCalibration {
+                commit_cost_per_chunk: observed.commit_cost_per_chunk as i64,
+                proof_cost_per_chunk: observed.proof_cost_per_chunk as i64,
+                execute_cost_per_chunk: observed.execute_cost_per_chunk as i64,
+                amortized_cost_per_chunk: amortized_cost_per_chunk as i64,
+                gas_price_scale_percent: gas_price_scale_percent as i64,
+            })
+            .await?;
+
+        metrics::gauge!(
+            "ticker.gas_calibration.observed_cost_per_chunk",
+            observed.amortized_cost_per_chunk() as f64
+        );
+        metrics::gauge!(
+            "ticker.gas_calibration.amortized_cost_per_chunk",
+            amortized_cost_per_chunk as f64
+        );
+        if let Some(observed_gas_price_scale) = observed_gas_price_scale {
+            metrics::gauge!(
+                "ticker.gas_calibration.observed_gas_price_scale_percent",
+                observed_gas_price_scale as f64
+            );
+        }
+        metrics::gauge!(
+            "ticker.gas_calibration.gas_price_scale_percent",
+            gas_price_scale_percent as f64
+        );
+        metrics::histogram!("ticker.gas_calibration.calibrate", start.elapsed());
+        Ok(())
+    }
+}
+
+/// Applies the exponential moving average to the observed value.
+fn smoothed_value(previous_value: u64, observed_value: u64, smoothing_percent: u64) -> u64 {
+    (previous_value * (100 - smoothing_percent) + observed_value * smoothing_percent) / 100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(action: AggregatedActionType, gas_used: i64, blocks: i64) -> StorageGasUsage {
+        StorageGasUsage {
+            op_type: action.to_string(),
+            gas_used,
+            gas_price: 1000,
+            blocks,
+            // Estimated cost of the operations is 100_000 gas per block for both commit and execute.
+            commit_gas_limit: GasCounter::scale_up(U256::from(
+                blocks * (CommitCost::BASE_COST as i64 + 100_000),
+            ))
+            .as_u64() as i64,
+            verify_gas_limit: GasCounter::scale_up(U256::from(
+                blocks * (VerifyCost::BASE_COST as i64 + 100_000),
+            ))
+            .as_u64() as i64,
+        }
+    }
+
+    /// Operations using 200 chunks for each aggregated operation type.
+    fn operations() -> Vec<StorageProcessedOperations> {
+        [
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ]
+        .iter()
+        .flat_map(|action| {
+            vec![
+                StorageProcessedOperations {
+                    op_type: action.to_string(),
+                    operation_type: "Transfer".to_string(),
+                    count: 70,
+                },
+                StorageProcessedOperations {
+                    op_type: action.to_string(),
+                    operation_type: "Withdraw".to_string(),
+                    count: 10,
+                },
+            ]
+        })
+        .collect()
+    }
+
+    #[test]
+    fn observed_costs() {
+        let gas_usage = vec![
+            usage(AggregatedActionType::CommitBlocks, 2 * 100_000 + 20_000, 2),
+            usage(AggregatedActionType::PublishProofBlocksOnchain, 30_000, 2),
+            usage(AggregatedActionType::ExecuteBlocks, 2 * 100_000 + 10_000, 2),
+        ];
+        let observed = ObservedCosts::from_gas_usage(&gas_usage, &operations()).unwrap();
+        assert_eq!(
+            observed,
+            ObservedCosts {
+                commit_cost_per_chunk: 100,
+                proof_cost_per_chunk: 150,
+                execute_cost_per_chunk: 50,
+            }
+        );
+        assert_eq!(observed.amortized_cost_per_chunk(), 300);
+
+        // Gas used below the estimated cost of the operations doesn't make the cost negative.
+        let gas_usage = vec![
+            usage(AggregatedActionType::CommitBlocks, 1000, 2),
+            usage(AggregatedActionType::PublishProofBlocksOnchain, 30_000, 2),
+            usage(AggregatedActionType::ExecuteBlocks, 1000, 2),
+        ];
+        let observed = ObservedCosts::from_gas_usage(&gas_usage, &operations()).unwrap();
+        assert_eq!(observed.amortized_cost_per_chunk(), 150);
+
+        // All the transaction types are required.
+        assert!(ObservedCosts::from_gas_usage(&gas_usage[..2], &operations()).is_none());
+        let operations: Vec<_> = operations()
+            .into_iter()
+            .filter(|operations| {
+                operations.op_type != AggregatedActionType::PublishProofBlocksOnchain.to_string()
+            })
+            .collect();
+        assert!(ObservedCosts::from_gas_usage(&gas_usage, &operations).is_none());
+    }
+
+    #[test]
+    fn chunks_of_operations() {
+        let mut operations = operations();
+        operations.push(StorageProcessedOperations {
+            op_type: AggregatedActionType::CommitBlocks.to_string(),
+            operation_type: "Unknown".to_string(),
+            count: 10,
+        });
+        let chunks = used_chunks(&operations);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.values().all(|&chunks| chunks == 200));
+    }
+
+    #[test]
+    fn gas_price_scale() {
+        let mut gas_usage = vec![
+            usage(AggregatedActionType::CommitBlocks, 300_000, 2),
+            usage(AggregatedActionType::ExecuteBlocks, 100_000, 2),
+        ];
+        gas_usage[0].gas_price = 1400;
+        // (300_000 * 1400 + 100_000 * 1000) / 400_000 = 1300
+        assert_eq!(observed_gas_price_scale(&gas_usage, 1000), Some(130));
+        assert_eq!(observed_gas_price_scale(&gas_usage, 1300), Some(100));
+        assert_eq!(observed_gas_price_scale(&gas_usage, 0), None);
+        assert_eq!(observed_gas_price_scale(&[], 1000), None);
+    }
+
+    #[test]
+    fn exponential_moving_average() {
+        assert_eq!(smoothed_value(200, 300, 20), 220);
+        assert_eq!(smoothed_value(200, 100, 20), 180);
+        assert_eq!(smoothed_value(200, 300, 0), 200);
+        assert_eq!(smoothed_value(200, 300, 100), 300);
+    }
+}
@@ -12,6 +12,7 @@ use std::time::Duration;
 
 // External deps
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use num::{
     rational::Ratio,
     traits::{Inv, Pow},
@@ -24,29 +25,41 @@ use tokio::time::Instant;
 
 // Workspace deps
 
-use zksync_config::configs::ticker::TokenPriceSource;
-use zksync_storage::ConnectionPool;
+use zksync_config::configs::ticker::{DexPool, TokenPriceSource};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{
+    ticker::records::{FeeQuoteSnapshot, NewFeeQuote, StoredFeeQuote},
+    ConnectionPool,
+};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
-    gas_counter::GasCounter, tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address,
-    BatchFee, ChangePubKeyOp, Fee, MintNFTOp, OutputFeeType, SwapOp, Token, TokenId, TokenLike,
-    TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
+    gas_counter::GasCounter,
+    tokens::ChangePubKeyFeeTypeArg,
+    tx::{ChangePubKeyType, TxHash},
+    Address, BatchFee, ChangePubKeyOp, Fee, FeeQuote, MintNFTOp, OutputFeeType, SwapOp, Token,
+    TokenId, TokenLike, TokenPrice, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp,
+    WithdrawOp,
 };
-use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+use zksync_utils::{big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_big_decimal};
 
 // Local deps
-use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
 pub use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
 use crate::fee_ticker::validator::FeeTokenValidator;
 use crate::fee_ticker::{
+    gas_calibration::GasCostCalibrator,
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        aggregator::{AggregatedPriceAPI, PriceSource},
+        coingecko::CoinGeckoAPI,
+        coinmarkercap::CoinMarketCapAPI,
+        dex::DexPriceAPI,
+        FeeTickerAPI, PriceHistorySettings, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT,
     },
+    ticker_info::GasCalibration,
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
 
 mod constants;
+mod gas_calibration;
 mod ticker_api;
 pub(crate) mod ticker_info;
 pub mod validator;
@@ -57,81 +70,125 @@ pub(crate) mod tests;
 /// Contains cost of zkSync operations in Wei.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GasOperationsCost {
-    standard_cost: HashMap<OutputFeeType, BigUint>,
+    /// Cost of the operation excluding the amortized cost of its chunks.
+    standard_cost: HashMap<OutputFeeType, OperationCost>,
+    fast_processing_coeff: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+struct OperationCost {
+    gas: u64,
+    chunks: usize,
 }
 
 impl GasOperationsCost {
     pub fn from_constants(fast_processing_coeff: f64) -> Self {
-        // We increase gas price for fast withdrawals, since it will induce generating a smaller block
-        // size, resulting in us paying more gas than for bigger block.
-        let standard_fast_withdrawal_cost =
-            (constants::BASE_WITHDRAW_COST as f64 * fast_processing_coeff) as u32;
-        let standard_fast_withdrawal_nft_cost =
-            (constants::BASE_WITHDRAW_NFT_COST as f64 * fast_processing_coeff) as u32;
-
+        let cost = |gas, chunks| OperationCost { gas, chunks };
         let standard_cost = vec![
             (
                 OutputFeeType::Transfer,
-                constants::BASE_TRANSFER_COST.into(),
+                cost(constants::BASE_TRANSFER_COST, TransferOp::CHUNKS),
             ),
-            (OutputFeeType::MintNFT, constants::BASE_MINT_NFT_COST.into()),
             (
                 OutputFeeType::TransferToNew,
-                constants::BASE_TRANSFER_TO_NEW_COST.into(),
+                cost(
+                    constants::BASE_TRANSFER_TO_NEW_COST,
+                    TransferToNewOp::CHUNKS,
+                ),
             ),
             (
                 OutputFeeType::Withdraw,
-                constants::BASE_WITHDRAW_COST.into(),
+                cost(constants::BASE_WITHDRAW_COST, WithdrawOp::CHUNKS),
             ),
             (
                 OutputFeeType::FastWithdraw,
-                standard_fast_withdrawal_cost.into(),
+                cost(constants::BASE_WITHDRAW_COST, WithdrawOp::CHUNKS),
+            ),
+            (
+                OutputFeeType::Swap,
+                cost(constants::BASE_SWAP_COST, SwapOp::CHUNKS),
             ),
-            (OutputFeeType::Swap, constants::BASE_SWAP_COST.into()),
             (
                 OutputFeeType::WithdrawNFT,
-                constants::BASE_WITHDRAW_NFT_COST.into(),
+                cost(constants::BASE_WITHDRAW_NFT_COST, WithdrawNFTOp::CHUNKS),
             ),
             (
                 OutputFeeType::FastWithdrawNFT,
-                standard_fast_withdrawal_nft_cost.into(),
+                cost(constants::BASE_WITHDRAW_NFT_COST, WithdrawNFTOp::CHUNKS),
+            ),
+            (
+                OutputFeeType::MintNFT,
+                cost(constants::BASE_MINT_NFT_COST, MintNFTOp::CHUNKS),
             ),
-            (OutputFeeType::MintNFT, constants::BASE_MINT_NFT_COST.into()),
             (
                 OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::PreContracts4Version {
                     onchain_pubkey_auth: false,
                 }),
-                constants::BASE_OLD_CHANGE_PUBKEY_OFFCHAIN_COST.into(),
+                cost(
+                    constants::BASE_OLD_CHANGE_PUBKEY_OFFCHAIN_COST,
+                    ChangePubKeyOp::CHUNKS,
+                ),
             ),
             (
                 OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::PreContracts4Version {
                     onchain_pubkey_auth: true,
                 }),
-                constants::BASE_CHANGE_PUBKEY_ONCHAIN_COST.into(),
+                cost(
+                    constants::BASE_CHANGE_PUBKEY_ONCHAIN_COST,
+                    ChangePubKeyOp::CHUNKS,
+                ),
             ),
             (
                 OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
                     ChangePubKeyType::Onchain,
                 )),
-                constants::BASE_CHANGE_PUBKEY_ONCHAIN_COST.into(),
+                cost(
+                    constants::BASE_CHANGE_PUBKEY_ONCHAIN_COST,
+                    ChangePubKeyOp::CHUNKS,
+                ),
             ),
             (
                 OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
                     ChangePubKeyType::ECDSA,
                 )),
-                constants::BASE_CHANGE_PUBKEY_OFFCHAIN_COST.into(),
+                cost(
+                    constants::BASE_CHANGE_PUBKEY_OFFCHAIN_COST,
+                    ChangePubKeyOp::CHUNKS,
+                ),
             ),
             (
                 OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
                     ChangePubKeyType::CREATE2,
                 )),
-                constants::BASE_CHANGE_PUBKEY_CREATE2_COST.into(),
+                cost(
+                    constants::BASE_CHANGE_PUBKEY_CREATE2_COST,
+                    ChangePubKeyOp::CHUNKS,
+                ),
             ),
         ]
         .into_iter()
         .collect::<HashMap<_, _>>();
 
-        Self { standard_cost }
+        Self {
+            standard_cost,
+            fast_processing_coeff,
+        }
+    }
+
+    /// Returns the gas cost of the operation including the amortized cost of its chunks.
+    pub fn cost(&self, fee_type: OutputFeeType, amortized_cost_per_chunk: u64) -> BigUint {
+        let OperationCost { gas, chunks } = self.standard_cost[&fee_type];
+        let cost = gas + amortized_cost_per_chunk * chunks as u64;
+        if matches!(
+            fee_type,
+            OutputFeeType::FastWithdraw | OutputFeeType::FastWithdrawNFT
+        ) {
+            // We increase gas price for fast withdrawals, since it will induce generating a smaller block
+            // size, resulting in us paying more gas than for bigger block.
+            ((cost as f64 * self.fast_processing_coeff) as u64).into()
+        } else {
+            cost.into()
+        }
     }
 }
 
@@ -143,6 +200,17 @@ pub struct TickerConfig {
     pub scale_fee_coefficient: Ratio<BigUint>,
     pub max_blocks_to_aggregate: u32,
     pub subsidy_cpk_price_usd: Ratio<BigUint>,
+    /// Lifetime of the fee quotes, the quotes are not issued if it's zero.
+    pub fee_quote_ttl: Duration,
+    /// Whether the gas costs calibrated from the confirmed transactions are used.
+    pub calibrate_gas_costs: bool,
+}
+
+/// Prices of ETH and the fee token used for the fee quote.
+#[derive(Debug, Clone)]
+struct QuotePrices {
+    eth: TokenPrice,
+    token: TokenPrice,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -151,18 +219,72 @@ pub enum TokenPriceRequestType {
     USDForOneToken,
 }
 
+/// Prices used for the fee calculation. The snapshot is stored once the transaction
+/// paying the fee is submitted, so the charged fee can be audited later.
+#[derive(Debug, Clone)]
+pub struct QuoteSnapshot {
+    token_id: TokenId,
+    fee_types: Vec<OutputFeeType>,
+    prices: QuotePrices,
+    gas_price_wei: BigUint,
+    total_fee: BigUint,
+    subsidized_total_fee: BigUint,
+}
+
 #[derive(Debug, Clone)]
 pub struct ResponseFee {
     pub normal_fee: Fee,
     pub subsidized_fee: Fee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    pub snapshot: QuoteSnapshot,
 }
 
 #[derive(Debug, Clone)]
 pub struct ResponseBatchFee {
     pub normal_fee: BatchFee,
+    /// Normal fees of the transactions in the token, not rounded.
+    pub normal_tx_fees: Vec<Ratio<BigUint>>,
     pub subsidized_fee: BatchFee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    pub snapshot: QuoteSnapshot,
+}
+
+/// Fee quote issued by the ticker.
+#[derive(Debug, Clone)]
+struct BoundFeeQuote {
+    token_id: TokenId,
+    txs: Vec<(TxFeeTypes, Address)>,
+    total_fee: BigUint,
+    expires_at: DateTime<Utc>,
+    used: bool,
+}
+
+impl TryFrom<StoredFeeQuote> for BoundFeeQuote {
+    type Error = anyhow::Error;
+
+    fn try_from(quote: StoredFeeQuote) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token_id: TokenId(quote.token_id as u32),
+            txs: serde_json::from_value(quote.txs)?,
+            total_fee: big_decimal_to_ratio(&quote.total_fee)?.to_integer(),
+            expires_at: quote.expires_at,
+            used: quote.used_at.is_some(),
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FeeQuoteError {
+    #[error("quote not found")]
+    NotFound,
+    #[error("quote has expired")]
+    Expired,
+    #[error("quote has already been used")]
+    AlreadyUsed,
+    #[error("quote was issued for other transactions")]
+    Mismatch,
+    #[error("Database error: {0}")]
+    DBError(String),
 }
 
 #[derive(Debug, Error)]
@@ -199,6 +321,8 @@ pub struct FeeTicker {
 const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
     ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::CREATE2),
 );
+/// Precision of the prices stored in the fee quote snapshots.
+const SNAPSHOT_PRICE_PRECISION: usize = 18;
 // Make no more than (Number of tokens) queries per 5 minutes to database is a good result
 // for updating names for tokens.
 const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(5 * 60);
@@ -207,6 +331,7 @@ const TOKEN_INVALIDATE_CACHE: Duration = Duration::from_secs(5 * 60);
 pub fn run_updaters(
     db_pool: ConnectionPool,
     config: &zksync_config::TickerConfig,
+    eth_client: EthereumGateway,
 ) -> Vec<JoinHandle<()>> {
     let cache = (db_pool.clone(), TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
 
@@ -216,18 +341,69 @@ pub fn run_updaters(
     let mut tasks = vec![tokio::spawn(
         updater.keep_updated(config.token_market_update_time),
     )];
+    if config.gas_calibration_interval > 0 {
+        let calibrator = GasCostCalibrator::new(db_pool.clone(), config);
+        tasks.push(tokio::spawn(
+            calibrator.keep_calibrated(config.gas_calibration_interval()),
+        ));
+    }
     let client = reqwest::ClientBuilder::new()
         .timeout(CONNECTION_TIMEOUT)
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
     let (price_source, base_url) = config.price_source();
+    let price_history = PriceHistorySettings::from(config);
+    let dex_pools = config
+        .dex_pools()
+        .unwrap_or_else(|err| panic!("Incorrect FEE_TICKER_DEX_POOLS: {}", err));
+    let aggregated_sources = config.aggregated_price_sources();
+    if !aggregated_sources.is_empty() {
+        let sources: Vec<_> = aggregated_sources
+            .into_iter()
+            .map(|source| (source, config.price_source_url(source)))
+            .collect();
+        let max_deviation_percent = config.price_max_deviation_percent;
+        let max_age = chrono::Duration::from_std(config.price_max_age())
+            .expect("Incorrect FEE_TICKER_PRICE_MAX_AGE");
+        let config = config.clone();
+
+        tasks.push(tokio::spawn(async move {
+            let mut price_sources = Vec::with_capacity(sources.len());
+            for (source, base_url) in sources {
+                let api = token_price_api(
+                    source,
+                    client.clone(),
+                    &base_url,
+                    &config,
+                    &dex_pools,
+                    &eth_client,
+                );
+                match api.await {
+                    Ok(api) => price_sources.push(PriceSource {
+                        name: format!("{:?}", source),
+                        api,
+                    }),
+                    Err(err) => vlog::error!("Failed to init {:?} client: {}", source, err),
+                }
+            }
+            // The task fails if none of the sources could be initialized, which stops the server.
+            let token_price_api =
+                AggregatedPriceAPI::new(price_sources, max_deviation_percent, max_age)
+                    .unwrap_or_else(|err| panic!("Failed to init the price aggregator: {}", err));
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_history);
+
+            ticker_api.keep_price_updated().await;
+        }));
+        return tasks;
+    }
+
     let price_updater = match price_source {
         TokenPriceSource::CoinMarketCap => {
             let token_price_api =
                 CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
 
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_history);
             tokio::spawn(ticker_api.keep_price_updated())
         }
 
@@ -236,15 +412,62 @@ pub fn run_updaters(
                 CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
                     .await
                     .expect("failed to init CoinGecko client");
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_history);
 
             ticker_api.keep_price_updated().await;
         }),
+
+        TokenPriceSource::Dex => {
+            let token_price_api = dex_price_api(config, dex_pools, eth_client);
+            let ticker_api = TickerApi::new(db_pool, token_price_api, price_history);
+            tokio::spawn(ticker_api.keep_price_updated())
+        }
     };
     tasks.push(price_updater);
     tasks
 }
 
+/// Creates the client of the token price source.
+async fn token_price_api(
+    source: TokenPriceSource,
+    client: reqwest::Client,
+    base_url: &str,
+    config: &zksync_config::TickerConfig,
+    dex_pools: &[DexPool],
+    eth_client: &EthereumGateway,
+) -> anyhow::Result<Box<dyn TokenPriceAPI + Send + Sync>> {
+    Ok(match source {
+        TokenPriceSource::CoinMarketCap => Box::new(CoinMarketCapAPI::new(
+            client,
+            base_url.parse().expect("Correct CoinMarketCap url"),
+        )),
+        TokenPriceSource::CoinGecko => Box::new(
+            CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url")).await?,
+        ),
+        TokenPriceSource::Dex => Box::new(dex_price_api(
+            config,
+            dex_pools.to_vec(),
+            eth_client.clone(),
+        )),
+    })
+}
+
+/// Creates the on-chain price source reading the pools parsed from the config.
+fn dex_price_api(
+    config: &zksync_config::TickerConfig,
+    dex_pools: Vec<DexPool>,
+    eth_client: EthereumGateway,
+) -> DexPriceAPI {
+    DexPriceAPI::new(
+        eth_client,
+        dex_pools,
+        config.dex_usd_token,
+        config.dex_wrapped_eth,
+        config.dex_min_liquidity_usd,
+        config.dex_twap_period,
+    )
+}
+
 impl FeeTicker {
     pub fn new(
         info: Box<dyn FeeTickerInfo>,
@@ -275,6 +498,8 @@ impl FeeTicker {
             ),
             max_blocks_to_aggregate,
             subsidy_cpk_price_usd: config.subsidy_cpk_price_usd(),
+            fee_quote_ttl: config.fee_quote_ttl(),
+            calibrate_gas_costs: config.gas_calibration_interval > 0,
         };
         let validator = FeeTokenValidator::new(
             cache,
@@ -284,14 +509,19 @@ impl FeeTicker {
         );
         Self::new(info, ticker_config, validator)
     }
+
+    /// Price of the subsidized `CREATE2` `ChangePubKey` transaction in USD.
+    pub fn subsidy_cpk_price_usd(&self) -> &Ratio<BigUint> {
+        &self.config.subsidy_cpk_price_usd
+    }
 }
 
 impl FeeTicker {
-    /// Increases the gas price by a constant coefficient.
+    /// Increases the gas price by the given number of percent.
     /// Due to the high volatility of gas prices, we are include the risk
     /// in the fee in order not to go into negative territory.
-    fn risk_gas_price_estimate(gas_price: BigUint) -> BigUint {
-        gas_price * BigUint::from(130u32) / BigUint::from(100u32)
+    fn risk_gas_price_estimate(gas_price: BigUint, scale_percent: u64) -> BigUint {
+        gas_price * BigUint::from(scale_percent) / BigUint::from(100u32)
     }
 
     pub async fn get_token_price(
@@ -331,15 +561,25 @@ impl FeeTicker {
         let start = Instant::now();
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.info.get_token(token).await?;
+        let prices = self.quote_prices(&token).await?;
 
+        let gas_calibration = self.gas_calibration().await?;
+        let amortized_cost_per_chunk = gas_calibration.amortized_cost_per_chunk;
         let gas_price_wei = self.info.get_gas_price_wei().await?;
-        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
-        let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let scale_gas_price = Self::risk_gas_price_estimate(
+            gas_price_wei.clone(),
+            gas_calibration.gas_price_scale_percent,
+        );
+        let wei_price_usd = Self::unit_price_usd(&prices.eth, 18);
+        let token_usd_risk = self.token_usd_risk_for_price(&token, &prices.token)?;
 
-        let (fee_type, gas_tx_amount, op_chunks) = self.gas_tx_amount(tx_type, recipient).await?;
+        let (fee_type, gas_tx_amount, op_chunks) = self
+            .gas_tx_amount(tx_type, recipient, amortized_cost_per_chunk)
+            .await?;
 
         let zkp_fee = (zkp_cost_chunk * op_chunks) * &token_usd_risk;
+        let gas_cost =
+            (&wei_price_usd * gas_tx_amount.clone() * gas_price_wei.clone()) * &token_usd_risk;
         let mut normal_gas_fee =
             (&wei_price_usd * gas_tx_amount.clone() * scale_gas_price.clone()) * &token_usd_risk;
 
@@ -354,22 +594,21 @@ impl FeeTicker {
             normal_gas_fee *= self.config.scale_fee_coefficient.clone();
         }
 
+        let margin_fee = margin_fee(&normal_gas_fee, &gas_cost);
         let normal_fee = Fee::new(
             fee_type,
             zkp_fee,
             normal_gas_fee,
+            margin_fee,
             gas_tx_amount,
             gas_price_wei.clone(),
         );
 
         if fee_type == CPK_CREATE2_FEE_TYPE {
-            let token_price = self
-                .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
-                .await?;
+            let token_price = Self::rounded_unit_price_usd(&token, &prices.token);
 
-            // It is safe to do unwrap in the next two lines, because token being acceptable for fees
+            // It is safe to do unwrap in the next line, because token being acceptable for fees
             // assumes that the token's price is > 0
-            let token_price = big_decimal_to_ratio(&token_price).unwrap();
             let full_amount = self
                 .config
                 .subsidy_cpk_price_usd
@@ -380,6 +619,7 @@ impl FeeTicker {
                 fee_type,
                 Ratio::from(BigUint::zero()),
                 full_amount,
+                Ratio::from(BigUint::zero()),
                 BigUint::zero(),
                 BigUint::zero(),
             );
@@ -390,18 +630,36 @@ impl FeeTicker {
                 Ratio::from(BigUint::from(0u32))
             };
 
+            let snapshot = QuoteSnapshot {
+                token_id: token.id,
+                fee_types: vec![fee_type],
+                prices,
+                gas_price_wei,
+                total_fee: normal_fee.total_fee.clone(),
+                subsidized_total_fee: subsidized_fee.total_fee.clone(),
+            };
             return Ok(ResponseFee {
                 normal_fee,
                 subsidized_fee,
                 subsidy_size_usd,
+                snapshot,
             });
         }
 
+        let snapshot = QuoteSnapshot {
+            token_id: token.id,
+            fee_types: vec![fee_type],
+            prices,
+            gas_price_wei,
+            total_fee: normal_fee.total_fee.clone(),
+            subsidized_total_fee: normal_fee.total_fee.clone(),
+        };
         metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
         Ok(ResponseFee {
             normal_fee: normal_fee.clone(),
             subsidized_fee: normal_fee,
             subsidy_size_usd: Ratio::from(BigUint::from(0u32)),
+            snapshot,
         })
     }
 
@@ -414,16 +672,26 @@ impl FeeTicker {
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
 
         let token = self.info.get_token(token).await?;
+        let prices = self.quote_prices(&token).await?;
 
+        let gas_calibration = self.gas_calibration().await?;
+        let amortized_cost_per_chunk = gas_calibration.amortized_cost_per_chunk;
         let gas_price_wei = self.info.get_gas_price_wei().await?;
-        let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
-        let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let scale_gas_price = Self::risk_gas_price_estimate(
+            gas_price_wei.clone(),
+            gas_calibration.gas_price_scale_percent,
+        );
+        let wei_price_usd = Self::unit_price_usd(&prices.eth, 18);
+        let token_usd_risk = self.token_usd_risk_for_price(&token, &prices.token)?;
 
+        let mut fee_types = Vec::with_capacity(txs.len());
+        let mut normal_tx_fees = Vec::with_capacity(txs.len());
         let mut total_normal_gas_tx_amount = Ratio::from(BigUint::zero());
         let mut total_op_chunks = Ratio::from(BigUint::zero());
+        let mut total_margin_wei = Ratio::from(BigUint::zero());
         let mut total_subsidized_gas_tx_amount = Ratio::from(BigUint::zero());
         let mut total_subsidized_op_chunks = Ratio::from(BigUint::zero());
+        let mut total_subsidized_margin_wei = Ratio::from(BigUint::zero());
 
         /*
             The input of each operation in the batch gas price is the following:
@@ -437,10 +705,7 @@ impl FeeTicker {
             Thus,
             gas_amount = subsidized_fee_in_usd / (&wei_price_usd * &scale_gas_price * token_usd_risk * token_price)
         */
-        let token_price = self
-            .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
-            .await?;
-        let token_price = big_decimal_to_ratio(&token_price).unwrap();
+        let token_price = Self::rounded_unit_price_usd(&token, &prices.token);
 
         let denom_part = &wei_price_usd * &scale_gas_price * &token_usd_risk * &token_price;
 
@@ -461,8 +726,11 @@ impl FeeTicker {
         };
 
         for (tx_type, recipient) in txs {
-            let (output_fee_type, gas_tx_amount, op_chunks) =
-                self.gas_tx_amount(tx_type, recipient).await?;
+            let (output_fee_type, gas_tx_amount, op_chunks) = self
+                .gas_tx_amount(tx_type, recipient, amortized_cost_per_chunk)
+                .await?;
+            fee_types.push(output_fee_type);
+            let gas_cost_wei = Ratio::from(&gas_tx_amount * &gas_price_wei);
             // Increase fee only for L2 operations
             let gas_tx_amount: Ratio<BigUint> = if matches!(
                 output_fee_type,
@@ -475,9 +743,17 @@ impl FeeTicker {
             } else {
                 gas_tx_amount.into()
             };
+            let margin_wei = margin_fee(&(&gas_tx_amount * &scale_gas_price), &gas_cost_wei);
 
+            // The margin is a part of the gas fee.
+            normal_tx_fees.push(
+                ((&zkp_cost_chunk * &op_chunks)
+                    + (&wei_price_usd * &gas_tx_amount * &scale_gas_price))
+                    * &token_usd_risk,
+            );
             total_normal_gas_tx_amount += &gas_tx_amount;
             total_op_chunks += &op_chunks;
+            total_margin_wei += &margin_wei;
 
             if output_fee_type == CPK_CREATE2_FEE_TYPE {
                 // The subsidy cost contains only gas cost
@@ -486,6 +762,7 @@ impl FeeTicker {
                 // No subsidy applied, so the standard fee goes even for subsidized fee
                 total_subsidized_gas_tx_amount += gas_tx_amount;
                 total_subsidized_op_chunks += op_chunks;
+                total_subsidized_margin_wei += margin_wei;
             }
         }
 
@@ -493,7 +770,8 @@ impl FeeTicker {
             let total_zkp_fee = (&zkp_cost_chunk * total_op_chunks) * &token_usd_risk;
             let total_gas_fee =
                 (&wei_price_usd * total_normal_gas_tx_amount * &scale_gas_price) * &token_usd_risk;
-            BatchFee::new(total_zkp_fee, total_gas_fee)
+            let total_margin_fee = (&wei_price_usd * total_margin_wei) * &token_usd_risk;
+            BatchFee::new(total_zkp_fee, total_gas_fee, total_margin_fee)
         };
 
         let subsidized_fee = {
@@ -501,7 +779,9 @@ impl FeeTicker {
             let total_gas_fee =
                 (&wei_price_usd * total_subsidized_gas_tx_amount * &scale_gas_price)
                     * &token_usd_risk;
-            BatchFee::new(total_zkp_fee, total_gas_fee)
+            // The subsidized `ChangePubKey` fee has no margin.
+            let total_margin_fee = (&wei_price_usd * total_subsidized_margin_wei) * &token_usd_risk;
+            BatchFee::new(total_zkp_fee, total_gas_fee, total_margin_fee)
         };
 
         let subsidy_size_usd = if normal_fee.total_fee > subsidized_fee.total_fee {
@@ -509,29 +789,52 @@ impl FeeTicker {
         } else {
             Ratio::from(BigUint::from(0u32))
         };
+        let snapshot = QuoteSnapshot {
+            token_id: token.id,
+            fee_types,
+            prices,
+            gas_price_wei,
+            total_fee: normal_fee.total_fee.clone(),
+            subsidized_total_fee: subsidized_fee.total_fee.clone(),
+        };
         metrics::histogram!("ticker.get_batch_from_ticker_in_wei", start.elapsed());
 
         Ok(ResponseBatchFee {
             normal_fee,
+            normal_tx_fees,
             subsidized_fee,
             subsidy_size_usd,
+            snapshot,
         })
     }
 
     pub async fn wei_price_usd(&self) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
-        let res = self
+        let eth_price = self
             .info
             .get_last_token_price(TokenLike::Id(TokenId(0)))
-            .await?
-            .usd_price
-            / BigUint::from(10u32).pow(18u32);
+            .await?;
+        let res = Self::unit_price_usd(&eth_price, 18);
         metrics::histogram!("ticker.wei_price_usd", start.elapsed());
         Ok(res)
     }
 
     pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
+        let token_price = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?;
+        let res = self.token_usd_risk_for_price(token, &token_price);
+        metrics::histogram!("ticker.token_usd_risk", start.elapsed());
+        res
+    }
+
+    fn token_usd_risk_for_price(
+        &self,
+        token: &Token,
+        token_price: &TokenPrice,
+    ) -> anyhow::Result<Ratio<BigUint>> {
         let token_risk_factor = self
             .config
             .tokens_risk_factors
@@ -539,28 +842,195 @@ impl FeeTicker {
             .cloned()
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
 
-        let token_price_usd = self
-            .info
-            .get_last_token_price(TokenLike::Id(token.id))
-            .await?
-            .usd_price
-            / BigUint::from(10u32).pow(u32::from(token.decimals));
+        let token_price_usd = Self::unit_price_usd(token_price, token.decimals);
         // TODO Check tokens fee allowance by non-zero price (ZKS-580)
-        metrics::histogram!("ticker.token_usd_risk", start.elapsed());
         token_risk_factor
             .checked_div(&token_price_usd)
             .ok_or_else(|| anyhow::format_err!("Token is not acceptable for fee"))
     }
 
+    /// Returns the price of the smallest unit of the token in USD.
+    fn unit_price_usd(price: &TokenPrice, decimals: u8) -> Ratio<BigUint> {
+        &price.usd_price / BigUint::from(10u32).pow(u32::from(decimals))
+    }
+
+    /// Returns the price of the smallest unit of the token in USD rounded the same way
+    /// as `get_token_price` does.
+    fn rounded_unit_price_usd(token: &Token, price: &TokenPrice) -> Ratio<BigUint> {
+        let price = ratio_to_big_decimal(&Self::unit_price_usd(price, token.decimals), 100);
+        big_decimal_to_ratio(&price).unwrap()
+    }
+
+    /// Loads the prices of ETH and the token. Both prices are loaded once per quote, so the
+    /// same prices are used for the calculation and stored in the quote snapshot.
+    async fn quote_prices(&self, token: &Token) -> Result<QuotePrices, PriceError> {
+        let eth = self
+            .info
+            .get_last_token_price(TokenLike::Id(TokenId(0)))
+            .await?;
+        let token = if token.id == TokenId(0) {
+            eth.clone()
+        } else {
+            self.info
+                .get_last_token_price(TokenLike::Id(token.id))
+                .await?
+        };
+        Ok(QuotePrices { eth, token })
+    }
+
+    /// Stores the prices used for the fee of the submitted transaction (or the batch), so the charged
+    /// fee can be audited later. The transaction is not rejected if the snapshot can't be stored.
+    pub async fn store_quote_snapshot(&self, tx_hash: TxHash, snapshot: &QuoteSnapshot) {
+        let prices = &snapshot.prices;
+        let snapshot = FeeQuoteSnapshot {
+            tx_hash: tx_hash.as_ref().to_vec(),
+            token_id: *snapshot.token_id as i32,
+            fee_types: serde_json::to_value(&snapshot.fee_types)
+                .expect("Fee types are serializable"),
+            token_price_usd: ratio_to_big_decimal(
+                &prices.token.usd_price,
+                SNAPSHOT_PRICE_PRECISION,
+            ),
+            token_price_updated_at: prices.token.last_updated,
+            eth_price_usd: ratio_to_big_decimal(&prices.eth.usd_price, SNAPSHOT_PRICE_PRECISION),
+            eth_price_updated_at: prices.eth.last_updated,
+            gas_price_wei: biguint_to_big_decimal(snapshot.gas_price_wei.clone()),
+            total_fee: biguint_to_big_decimal(snapshot.total_fee.clone()),
+            subsidized_total_fee: biguint_to_big_decimal(snapshot.subsidized_total_fee.clone()),
+        };
+        if let Err(err) = self.info.store_fee_quote_snapshot(snapshot).await {
+            metrics::increment_counter!("ticker.store_quote_snapshot_fail");
+            vlog::warn!("Failed to store the fee quote snapshot: {}", err);
+        }
+    }
+
+    /// Issues the quote binding the fee for the transactions paying fee in the token: until the quote
+    /// expires, the quoted fee is accepted for these transactions even if the ticker fee has grown.
+    /// Returns `None` if the quotes are disabled or the quote can't be stored.
+    pub async fn bind_fee_quote(
+        &self,
+        token: TokenLike,
+        txs: Vec<(TxFeeTypes, Address)>,
+        total_fee: BigUint,
+    ) -> Option<FeeQuote> {
+        if self.config.fee_quote_ttl.as_secs() == 0 {
+            return None;
+        }
+        let start = Instant::now();
+        let token_id = match self.info.get_token(token).await {
+            Ok(token) => token.id,
+            Err(err) => {
+                vlog::warn!("Failed to resolve the token of the fee quote: {}", err);
+                return None;
+            }
+        };
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(self.config.fee_quote_ttl)
+                .expect("Fee quote ttl is too big");
+        let new_quote = NewFeeQuote {
+            token_id: *token_id as i32,
+            txs: serde_json::to_value(&txs).expect("Fee types are serializable"),
+            total_fee: biguint_to_big_decimal(total_fee),
+            expires_at,
+        };
+        let id = match self.info.store_fee_quote(new_quote).await {
+            Ok(id) => id,
+            Err(err) => {
+                vlog::warn!("Failed to store the fee quote: {}", err);
+                return None;
+            }
+        };
+        metrics::histogram!("ticker.bind_fee_quote", start.elapsed());
+        Some(FeeQuote { id, expires_at })
+    }
+
+    /// Returns the fee bound by the quote if the quote is valid for the transactions
+    /// paying fee in the token.
+    pub async fn quoted_fee(
+        &self,
+        id: i64,
+        token_id: TokenId,
+        txs: &[(TxFeeTypes, Address)],
+    ) -> Result<BigUint, FeeQuoteError> {
+        let quote = self
+            .info
+            .load_fee_quote(id)
+            .await
+            .map_err(|err| FeeQuoteError::DBError(err.to_string()))?
+            .ok_or(FeeQuoteError::NotFound)?;
+        let quote = BoundFeeQuote::try_from(quote)
+            .map_err(|err| FeeQuoteError::DBError(err.to_string()))?;
+
+        if quote.used {
+            return Err(FeeQuoteError::AlreadyUsed);
+        }
+        if quote.expires_at <= Utc::now() {
+            return Err(FeeQuoteError::Expired);
+        }
+        if quote.token_id != token_id || quote.txs != txs {
+            return Err(FeeQuoteError::Mismatch);
+        }
+        Ok(quote.total_fee)
+    }
+
+    /// Claims the fee quote for the transaction (or the batch), so it can't be used again.
+    /// The quote is claimed atomically, so only one of the concurrent submissions succeeds.
+    pub async fn claim_fee_quote(&self, id: i64, tx_hash: TxHash) -> Result<(), FeeQuoteError> {
+        let claimed = self
+            .info
+            .claim_fee_quote(id, tx_hash)
+            .await
+            .map_err(|err| FeeQuoteError::DBError(err.to_string()))?;
+        if claimed {
+            return Ok(());
+        }
+
+        // Find out why the quote can't be claimed.
+        let quote = self
+            .info
+            .load_fee_quote(id)
+            .await
+            .map_err(|err| FeeQuoteError::DBError(err.to_string()))?
+            .ok_or(FeeQuoteError::NotFound)?;
+        if quote.used_at.is_some() {
+            metrics::increment_counter!("ticker.fee_quote_reused");
+            Err(FeeQuoteError::AlreadyUsed)
+        } else {
+            Err(FeeQuoteError::Expired)
+        }
+    }
+
+    /// Releases the fee quote claimed by the transaction (or the batch) rejected by the mempool.
+    pub async fn release_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<()> {
+        if !self.info.release_fee_quote(id, tx_hash).await? {
+            vlog::warn!(
+                "Fee quote {} is not claimed by the tx {}",
+                id,
+                tx_hash.to_string()
+            );
+        }
+        Ok(())
+    }
+
     /// Returns `true` if account does not yet exist in the zkSync network.
     pub async fn is_account_new(&self, address: Address) -> anyhow::Result<bool> {
         self.info.is_account_new(address).await
     }
 
+    /// Returns the gas costs: the calibrated ones if the gas costs are calibrated,
+    /// the constant ones otherwise.
+    async fn gas_calibration(&self) -> anyhow::Result<GasCalibration> {
+        if !self.config.calibrate_gas_costs {
+            return Ok(GasCalibration::default());
+        }
+        Ok(self.info.get_gas_calibration().await?.unwrap_or_default())
+    }
+
     async fn gas_tx_amount(
         &self,
         tx_type: TxFeeTypes,
         recipient: Address,
+        amortized_cost_per_chunk: u64,
     ) -> anyhow::Result<(OutputFeeType, BigUint, BigUint)> {
         let start = Instant::now();
         let (fee_type, op_chunks) = match tx_type {
@@ -586,14 +1056,12 @@ impl FeeTicker {
             fee_type,
             OutputFeeType::FastWithdraw | OutputFeeType::FastWithdrawNFT
         ) {
-            self.calculate_fast_withdrawal_gas_cost(op_chunks).await?
+            self.calculate_fast_withdrawal_gas_cost(op_chunks, amortized_cost_per_chunk)
+                .await?
         } else {
             self.config
                 .gas_cost_tx
-                .standard_cost
-                .get(&fee_type)
-                .cloned()
-                .unwrap()
+                .cost(fee_type, amortized_cost_per_chunk)
         };
 
         // Convert chunks amount to `BigUint`.
@@ -604,6 +1072,7 @@ impl FeeTicker {
     async fn calculate_fast_withdrawal_gas_cost(
         &self,
         chunk_size: usize,
+        amortized_cost_per_chunk: u64,
     ) -> anyhow::Result<BigUint> {
         let start = Instant::now();
         let future_blocks = self.info.blocks_in_future_aggregated_operations().await?;
@@ -612,7 +1081,7 @@ impl FeeTicker {
             if chunk_size > chunks {
                 0
             } else {
-                chunks * AMORTIZED_COST_PER_CHUNK as usize
+                chunks * amortized_cost_per_chunk as usize
             }
         });
 
@@ -643,6 +1112,15 @@ impl FeeTicker {
     }
 }
 
+/// Returns the part of the gas fee charged over the gas cost, zero if the fee doesn't cover the cost.
+fn margin_fee(gas_fee: &Ratio<BigUint>, gas_cost: &Ratio<BigUint>) -> Ratio<BigUint> {
+    if gas_fee > gas_cost {
+        gas_fee - gas_cost
+    } else {
+        Ratio::from(BigUint::zero())
+    }
+}
+
 fn calculate_cost(base_cost: usize, max_blocks: u32, future_blocks: u32) -> usize {
     base_cost - (base_cost / max_blocks as usize) * future_blocks.rem_euclid(max_blocks) as usize
 }
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use bigdecimal::BigDecimal;
@@ -114,6 +115,8 @@ pub fn get_test_ticker_config() -> TickerConfig {
         scale_fee_coefficient: Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
         max_blocks_to_aggregate: 5,
         subsidy_cpk_price_usd: scaled_u64_to_ratio(SUBSIDY_CPK_PRICE_USD_SCALED),
+        fee_quote_ttl: Duration::from_secs(0),
+        calibrate_gas_costs: false,
     }
 }
 
@@ -130,6 +133,9 @@ impl FeeTickerAPI for MockApiProvider {
 struct MockTickerInfo {
     pub future_blocks: BlocksInFutureAggregatedOperations,
     pub remaining_chunks: Option<usize>,
+    pub snapshots: Arc<Mutex<Vec<FeeQuoteSnapshot>>>,
+    pub quotes: Arc<Mutex<Vec<StoredFeeQuote>>>,
+    pub gas_calibration: Option<GasCalibration>,
 }
 
 impl Default for MockTickerInfo {
@@ -141,6 +147,9 @@ impl Default for MockTickerInfo {
                 blocks_to_execute: 0,
             },
             remaining_chunks: None,
+            snapshots: Default::default(),
+            quotes: Default::default(),
+            gas_calibration: None,
         }
     }
 }
@@ -180,6 +189,10 @@ impl FeeTickerInfo for MockTickerInfo {
         Ok(BigUint::from(10u32).pow(7u32)) // 10 GWei
     }
 
+    async fn get_gas_calibration(&self) -> anyhow::Result<Option<GasCalibration>> {
+        Ok(self.gas_calibration)
+    }
+
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
         for test_token in TestToken::all_tokens() {
             if TokenLike::Id(test_token.id) == token {
@@ -195,6 +208,56 @@ impl FeeTickerInfo for MockTickerInfo {
         unreachable!("incorrect token input")
     }
 
+    async fn store_fee_quote_snapshot(&self, snapshot: FeeQuoteSnapshot) -> anyhow::Result<()> {
+        self.snapshots.lock().unwrap().push(snapshot);
+        Ok(())
+    }
+
+    async fn store_fee_quote(&self, quote: NewFeeQuote) -> anyhow::Result<i64> {
+        let mut quotes = self.quotes.lock().unwrap();
+        let id = quotes.len() as i64 + 1;
+        quotes.push(StoredFeeQuote {
+            id,
+            token_id: quote.token_id,
+            txs: quote.txs,
+            total_fee: quote.total_fee,
+            created_at: Utc::now(),
+            expires_at: quote.expires_at,
+            used_by_tx_hash: None,
+            used_at: None,
+        });
+        Ok(id)
+    }
+
+    async fn load_fee_quote(&self, id: i64) -> anyhow::Result<Option<StoredFeeQuote>> {
+        let quotes = self.quotes.lock().unwrap();
+        Ok(quotes.iter().find(|quote| quote.id == id).cloned())
+    }
+
+    async fn claim_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let mut quotes = self.quotes.lock().unwrap();
+        match quotes.iter_mut().find(|quote| quote.id == id) {
+            Some(quote) if quote.used_at.is_none() && quote.expires_at > Utc::now() => {
+                quote.used_by_tx_hash = Some(tx_hash.as_ref().to_vec());
+                quote.used_at = Some(Utc::now());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn release_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let mut quotes = self.quotes.lock().unwrap();
+        match quotes.iter_mut().find(|quote| quote.id == id) {
+            Some(quote) if quote.used_by_tx_hash.as_deref() == Some(tx_hash.as_ref()) => {
+                quote.used_by_tx_hash = None;
+                quote.used_at = None;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
@@ -446,6 +509,163 @@ fn test_ticker_subsidy() {
     }
 }
 
+/// Checks that the prices used for the fees are stored only when the transactions are submitted.
+#[test]
+fn test_fee_quote_snapshot() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let info = MockTickerInfo::default();
+    let snapshots = info.snapshots.clone();
+    let ticker = FeeTicker::new(Box::new(info), get_test_ticker_config(), validator);
+    let token = TestToken::hex();
+
+    let fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Transfer,
+        token.id.into(),
+        Address::default(),
+    ))
+    .unwrap();
+    let batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        token.id.into(),
+        vec![
+            (TxFeeTypes::Transfer, Address::default()),
+            (TxFeeTypes::Withdraw, Address::default()),
+        ],
+    ))
+    .unwrap();
+    assert!(snapshots.lock().unwrap().is_empty());
+
+    let tx_hash = TxHash::default();
+    block_on(ticker.store_quote_snapshot(tx_hash, &fee.snapshot));
+    block_on(ticker.store_quote_snapshot(tx_hash, &batch_fee.snapshot));
+
+    let snapshots = snapshots.lock().unwrap();
+    assert_eq!(snapshots.len(), 2);
+    for snapshot in snapshots.iter() {
+        assert_eq!(snapshot.tx_hash, tx_hash.as_ref().to_vec());
+        assert_eq!(snapshot.token_id, *token.id as i32);
+        assert_eq!(
+            snapshot.token_price_usd,
+            ratio_to_big_decimal(&token.price_usd, SNAPSHOT_PRICE_PRECISION)
+        );
+        assert_eq!(
+            snapshot.eth_price_usd,
+            ratio_to_big_decimal(&TestToken::eth().price_usd, SNAPSHOT_PRICE_PRECISION)
+        );
+        assert_eq!(snapshot.gas_price_wei, BigDecimal::from(10u64.pow(7)));
+    }
+
+    assert_eq!(snapshots[0].fee_types, serde_json::json!(["Transfer"]));
+    assert_eq!(
+        snapshots[0].total_fee,
+        biguint_to_big_decimal(fee.normal_fee.total_fee)
+    );
+    assert_eq!(
+        snapshots[1].fee_types,
+        serde_json::json!(["Transfer", "Withdraw"])
+    );
+    assert_eq!(
+        snapshots[1].total_fee,
+        biguint_to_big_decimal(batch_fee.normal_fee.total_fee)
+    );
+    assert_eq!(
+        snapshots[1].subsidized_total_fee,
+        biguint_to_big_decimal(batch_fee.subsidized_fee.total_fee)
+    );
+}
+
+/// Checks that the issued fee quotes are accepted only for the quoted transactions until they
+/// are claimed or expire.
+#[test]
+fn test_fee_quotes() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let mut config = get_test_ticker_config();
+    config.fee_quote_ttl = Duration::from_secs(60);
+    let info = MockTickerInfo::default();
+    let quotes = info.quotes.clone();
+    let ticker = FeeTicker::new(Box::new(info), config, validator);
+    let token = TestToken::hex();
+    let txs = vec![(TxFeeTypes::Transfer, Address::default())];
+    let total_fee = BigUint::from(1000u32);
+
+    let quote = block_on(ticker.bind_fee_quote(token.id.into(), txs.clone(), total_fee.clone()))
+        .expect("Quote should be issued");
+    assert!(quote.expires_at > Utc::now());
+    assert_eq!(quotes.lock().unwrap().len(), 1);
+
+    assert_eq!(
+        block_on(ticker.quoted_fee(quote.id, token.id, &txs)).unwrap(),
+        total_fee
+    );
+    // The quote is not accepted for other token or transactions.
+    assert!(matches!(
+        block_on(ticker.quoted_fee(quote.id, TokenId(0), &txs)),
+        Err(FeeQuoteError::Mismatch)
+    ));
+    assert!(matches!(
+        block_on(ticker.quoted_fee(
+            quote.id,
+            token.id,
+            &[(TxFeeTypes::Withdraw, Address::default())]
+        )),
+        Err(FeeQuoteError::Mismatch)
+    ));
+    assert!(matches!(
+        block_on(ticker.quoted_fee(quote.id + 1, token.id, &txs)),
+        Err(FeeQuoteError::NotFound)
+    ));
+
+    // The quote can be claimed only once.
+    let tx_hash = TxHash::default();
+    block_on(ticker.claim_fee_quote(quote.id, tx_hash)).unwrap();
+    assert!(matches!(
+        block_on(ticker.claim_fee_quote(quote.id, tx_hash)),
+        Err(FeeQuoteError::AlreadyUsed)
+    ));
+    assert!(matches!(
+        block_on(ticker.quoted_fee(quote.id, token.id, &txs)),
+        Err(FeeQuoteError::AlreadyUsed)
+    ));
+    assert!(quotes.lock().unwrap()[0].used_at.is_some());
+
+    // The released quote can be claimed again.
+    block_on(ticker.release_fee_quote(quote.id, tx_hash)).unwrap();
+    assert!(quotes.lock().unwrap()[0].used_at.is_none());
+    assert_eq!(
+        block_on(ticker.quoted_fee(quote.id, token.id, &txs)).unwrap(),
+        total_fee
+    );
+
+    // Expired quotes are not accepted.
+    quotes.lock().unwrap()[0].expires_at = Utc::now() - chrono::Duration::seconds(1);
+    assert!(matches!(
+        block_on(ticker.quoted_fee(quote.id, token.id, &txs)),
+        Err(FeeQuoteError::Expired)
+    ));
+    assert!(matches!(
+        block_on(ticker.claim_fee_quote(quote.id, tx_hash)),
+        Err(FeeQuoteError::Expired)
+    ));
+    assert!(matches!(
+        block_on(ticker.claim_fee_quote(quote.id + 1, tx_hash)),
+        Err(FeeQuoteError::NotFound)
+    ));
+
+    // Quotes are not issued if disabled.
+    let mut ticker = ticker;
+    ticker.config.fee_quote_ttl = Duration::from_secs(0);
+    assert!(block_on(ticker.bind_fee_quote(token.id.into(), txs, total_fee)).is_none());
+}
+
 #[test]
 fn test_ticker_formula() {
     let validator = FeeTokenValidator::new(
@@ -679,6 +899,110 @@ fn test_ticker_formula() {
     }
 }
 
+/// Checks that the calibrated gas cost and gas price scale are used if the calibration is enabled,
+/// and that the margin is a part of the gas fee.
+#[test]
+fn test_calibrated_gas_costs() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let info = MockTickerInfo {
+        gas_calibration: Some(GasCalibration {
+            amortized_cost_per_chunk: 1000,
+            gas_price_scale_percent: 150,
+        }),
+        ..Default::default()
+    };
+    let mut ticker = FeeTicker::new(Box::new(info), get_test_ticker_config(), validator);
+    let token = TestToken::hex();
+
+    let get_fee = |ticker: &FeeTicker| {
+        block_on(ticker.get_fee_from_ticker_in_wei(
+            TxFeeTypes::Withdraw,
+            token.id.into(),
+            Address::default(),
+        ))
+        .unwrap()
+        .normal_fee
+    };
+
+    // The calibrated cost is not used unless the calibration is enabled.
+    let fee = get_fee(&ticker);
+    assert_eq!(
+        fee.gas_tx_amount,
+        BigUint::from(
+            constants::BASE_WITHDRAW_COST
+                + constants::AMORTIZED_COST_PER_CHUNK * WithdrawOp::CHUNKS as u64
+        )
+    );
+
+    ticker.config.calibrate_gas_costs = true;
+    let calibrated_fee = get_fee(&ticker);
+    assert_eq!(
+        calibrated_fee.gas_tx_amount,
+        BigUint::from(constants::BASE_WITHDRAW_COST + 1000 * WithdrawOp::CHUNKS as u64)
+    );
+    assert!(calibrated_fee.gas_fee > fee.gas_fee);
+
+    // The margin is the part of the gas fee charged over the gas cost at the current gas price.
+    assert!(!calibrated_fee.margin_fee.is_zero());
+    assert!(calibrated_fee.margin_fee < calibrated_fee.gas_fee);
+    // The gas price is scaled by the calibrated 150%, so the margin is a third of the gas fee.
+    let tripled_margin_fee = &calibrated_fee.margin_fee * 3u32;
+    assert!(tripled_margin_fee <= &calibrated_fee.gas_fee + 3u32);
+    assert!(calibrated_fee.gas_fee <= &tripled_margin_fee + 3u32);
+    let batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        token.id.into(),
+        vec![(TxFeeTypes::Withdraw, Address::default())],
+    ))
+    .unwrap()
+    .normal_fee;
+    assert_eq!(batch_fee.margin_fee, calibrated_fee.margin_fee);
+}
+
+/// Checks that the fees of the separate transactions add up to the batch fee.
+#[test]
+fn test_batch_tx_fees() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let ticker = FeeTicker::new(
+        Box::new(MockTickerInfo::default()),
+        get_test_ticker_config(),
+        validator,
+    );
+    let token = TestToken::hex();
+
+    let batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        token.id.into(),
+        vec![
+            (TxFeeTypes::Transfer, Address::default()),
+            (TxFeeTypes::Withdraw, Address::default()),
+            (TxFeeTypes::FastWithdraw, Address::default()),
+        ],
+    ))
+    .unwrap();
+    assert_eq!(batch_fee.normal_tx_fees.len(), 3);
+    assert!(!batch_fee.normal_fee.margin_fee.is_zero());
+
+    // Zkp and gas fees are rounded up separately, packing is not applied to the sum.
+    let tx_fees_sum = batch_fee
+        .normal_tx_fees
+        .iter()
+        .sum::<Ratio<BigUint>>()
+        .ceil()
+        .to_integer();
+    let batch_fee_sum = &batch_fee.normal_fee.zkp_fee + &batch_fee.normal_fee.gas_fee;
+    assert!(tx_fees_sum <= batch_fee_sum);
+    assert!(batch_fee_sum <= tx_fees_sum + 1u32);
+}
+
 // It's temporary solution while zero-price tokens marked as allowed for fee
 #[test]
 fn test_zero_price_token_fee() {
@@ -0,0 +1,443 @@
+//! Aggregation of the token prices reported by several sources.
+//!
+//! All the sources are queried concurrently. Stale quotes are dropped, and the median of the
+//! remaining ones is calculated. Quotes deviating from the median by more than the configured
+//! threshold are rejected as outliers, and the median of the accepted quotes is used as the price.
+//! If the majority of the fresh quotes are outliers, the sources disagree and the last accepted
+//! price is used instead while it's fresh. Otherwise, the price is not updated.
+
+// Built-in deps
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+// External deps
+use anyhow::ensure;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use futures::future::join_all;
+use num::{rational::Ratio, BigUint, ToPrimitive, Zero};
+// Workspace deps
+use zksync_types::{Token, TokenId, TokenPrice};
+use zksync_utils::ratio_to_big_decimal;
+// Local deps
+use super::TokenPriceAPI;
+use crate::fee_ticker::PriceError;
+
+/// Token price source queried by the aggregator.
+pub struct PriceSource {
+    /// Name of the source used in the logs and metrics.
+    pub name: String,
+    pub api: Box<dyn TokenPriceAPI + Send + Sync>,
+}
+
+impl std::fmt::Debug for PriceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriceSource")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// `TokenPriceAPI` that aggregates the prices reported by several sources.
+#[derive(Debug)]
+pub struct AggregatedPriceAPI {
+    /// Sources of the prices.
+    sources: Vec<PriceSource>,
+    /// Maximum relative deviation of the accepted quote from the median.
+    max_deviation: Ratio<BigUint>,
+    /// Maximum age of the accepted quote.
+    max_age: Duration,
+    /// Last prices the sources agreed on, used if the sources disagree.
+    last_accepted: Mutex<HashMap<TokenId, TokenPrice>>,
+}
+
+impl AggregatedPriceAPI {
+    /// Creates the aggregator, returns an error if there are no price sources.
+    pub fn new(
+        sources: Vec<PriceSource>,
+        max_deviation_percent: u32,
+        max_age: Duration,
+    ) -> anyhow::Result<Self> {
+        ensure!(!sources.is_empty(), "At least one price source is required");
+        Ok(Self {
+            sources,
+            max_deviation: Ratio::new(BigUint::from(max_deviation_percent), BigUint::from(100u32)),
+            max_age,
+            last_accepted: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Selects the fresh quotes reported by the sources.
+    /// Returns `TokenNotFound` if none of the sources knows about the token.
+    fn fresh_quotes<'a>(
+        &'a self,
+        token: &Token,
+        responses: Vec<Result<TokenPrice, PriceError>>,
+    ) -> Result<Vec<(&'a str, TokenPrice)>, PriceError> {
+        let now = Utc::now();
+        let mut quotes = Vec::with_capacity(responses.len());
+        let mut not_found = 0;
+        for (source, response) in self.sources.iter().zip(responses) {
+            match response {
+                Ok(price) if now - price.last_updated > self.max_age => {
+                    vlog::warn!(
+                        "Price of {} reported by {} is stale, last updated at {}",
+                        token.symbol,
+                        source.name,
+                        price.last_updated
+                    );
+                    metrics::increment_counter!("ticker.aggregator.stale_quotes", "source" => source.name.clone());
+                }
+                Ok(price) => quotes.push((source.name.as_str(), price)),
+                Err(PriceError::TokenNotFound(_)) => not_found += 1,
+                Err(err) => {
+                    vlog::warn!(
+                        "Failed to get price of {} from {}: {}",
+                        token.symbol,
+                        source.name,
+                        err
+                    );
+                    metrics::increment_counter!("ticker.aggregator.failed_requests", "source" => source.name.clone());
+                }
+            }
+        }
+
+        if quotes.is_empty() {
+            return Err(if not_found == self.sources.len() {
+                PriceError::token_not_found(format!(
+                    "Token '{}, {:?}' is not listed on any price source",
+                    token.symbol, token.address
+                ))
+            } else {
+                PriceError::api_error(format!("No fresh price quotes for {}", token.symbol))
+            });
+        }
+        Ok(quotes)
+    }
+
+    /// Aggregates the fresh quotes.
+    fn aggregate(&self, token: &Token, quotes: Vec<(&str, TokenPrice)>) -> Option<TokenPrice> {
+        let median = median(quotes.iter().map(|(_, quote)| quote.usd_price.clone()));
+
+        let fresh_quotes = quotes.len();
+        let mut accepted = Vec::with_capacity(fresh_quotes);
+        for (source, quote) in quotes {
+            let deviation = deviation(&quote.usd_price, &median);
+            let deviation_percent = ratio_to_big_decimal(&(&deviation * BigUint::from(100u32)), 2)
+                .to_f64()
+                .unwrap_or(f64::MAX);
+            metrics::histogram!("ticker.aggregator.deviation_percent", deviation_percent, "source" => source.to_string());
+
+            if deviation > self.max_deviation {
+                vlog::warn!(
+                    "Price of {} reported by {} deviates from the median by {}%",
+                    token.symbol,
+                    source,
+                    deviation_percent
+                );
+                metrics::increment_counter!("ticker.aggregator.outliers", "source" => source.to_string());
+            } else {
+                accepted.push(quote);
+            }
+        }
+
+        if accepted.len() * 2 <= fresh_quotes {
+            return None;
+        }
+        let last_updated = accepted
+            .iter()
+            .map(|quote| quote.last_updated)
+            .min()
+            .expect("At least one quote is accepted");
+        Some(TokenPrice {
+            usd_price: median(accepted.into_iter().map(|quote| quote.usd_price)),
+            last_updated,
+        })
+    }
+}
+
+#[async_trait]
+impl TokenPriceAPI for AggregatedPriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        let responses = join_all(
+            self.sources
+                .iter()
+                .map(|source| source.api.get_price(token)),
+        )
+        .await;
+
+        let quotes = self.fresh_quotes(token, responses)?;
+        let mut last_accepted = self.last_accepted.lock().unwrap();
+        let price = match self.aggregate(token, quotes) {
+            Some(price) => {
+                last_accepted.insert(token.id, price.clone());
+                price
+            }
+            None => {
+                // Sources disagree, keep the last accepted price while it's fresh.
+                metrics::increment_counter!("ticker.aggregator.disagreements");
+                match last_accepted.get(&token.id) {
+                    Some(price) if Utc::now() - price.last_updated <= self.max_age => {
+                        vlog::warn!(
+                            "Price sources disagree on the price of {}, keeping the last accepted price",
+                            token.symbol
+                        );
+                        price.clone()
+                    }
+                    _ => {
+                        return Err(PriceError::api_error(format!(
+                            "Price sources disagree on the price of {}",
+                            token.symbol
+                        )))
+                    }
+                }
+            }
+        };
+        drop(last_accepted);
+
+        metrics::histogram!("ticker.aggregator.get_price", start.elapsed());
+        Ok(price)
+    }
+}
+
+/// Returns the median of the values, the mean of the two middle values is used for the even count.
+fn median(values: impl Iterator<Item = Ratio<BigUint>>) -> Ratio<BigUint> {
+    let mut values: Vec<_> = values.collect();
+    assert!(!values.is_empty(), "Median of the empty set");
+    values.sort();
+
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values.swap_remove(middle)
+    } else {
+        (&values[middle - 1] + &values[middle]) / BigUint::from(2u32)
+    }
+}
+
+/// Returns the relative deviation of the value from the median.
+fn deviation(value: &Ratio<BigUint>, median: &Ratio<BigUint>) -> Ratio<BigUint> {
+    let difference = if value > median {
+        value - median
+    } else {
+        median - value
+    };
+    if median.is_zero() {
+        // Zero price is not a real quote, so any other value is an outlier.
+        if difference.is_zero() {
+            Ratio::zero()
+        } else {
+            Ratio::from_integer(BigUint::from(u32::MAX))
+        }
+    } else {
+        difference / median
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fee_ticker::ticker_api::{coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI};
+    use std::sync::Arc;
+    use zksync_types::TokenKind;
+    use zksync_utils::parse_env;
+
+    type Response = Arc<Mutex<Result<TokenPrice, PriceError>>>;
+
+    /// Source reporting the price set by the test.
+    struct FixedPriceAPI(Response);
+
+    #[async_trait]
+    impl TokenPriceAPI for FixedPriceAPI {
+        async fn get_price(&self, _token: &Token) -> Result<TokenPrice, PriceError> {
+            match &*self.0.lock().unwrap() {
+                Ok(price) => Ok(price.clone()),
+                Err(PriceError::TokenNotFound(msg)) => Err(PriceError::token_not_found(msg)),
+                Err(err) => Err(PriceError::api_error(err)),
+            }
+        }
+    }
+
+    fn price(usd_price: u32, age_secs: i64) -> Result<TokenPrice, PriceError> {
+        Ok(TokenPrice {
+            usd_price: Ratio::from_integer(BigUint::from(usd_price)),
+            last_updated: Utc::now() - Duration::seconds(age_secs),
+        })
+    }
+
+    fn aggregator(responses: &[Response]) -> AggregatedPriceAPI {
+        let sources = responses
+            .iter()
+            .enumerate()
+            .map(|(i, response)| PriceSource {
+                name: format!("source-{}", i),
+                api: Box::new(FixedPriceAPI(response.clone())),
+            })
+            .collect();
+        AggregatedPriceAPI::new(sources, 10, Duration::minutes(30)).unwrap()
+    }
+
+    fn responses(responses: Vec<Result<TokenPrice, PriceError>>) -> Vec<Response> {
+        responses
+            .into_iter()
+            .map(|response| Arc::new(Mutex::new(response)))
+            .collect()
+    }
+
+    fn token() -> Token {
+        Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20)
+    }
+
+    fn aggregated_price(api: &AggregatedPriceAPI) -> Result<u32, PriceError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime");
+        let price = runtime.block_on(api.get_price(&token()))?;
+        assert!(price.usd_price.is_integer(), "{}", price.usd_price);
+        Ok(price.usd_price.to_integer().to_u32().unwrap())
+    }
+
+    fn get_price(quotes: Vec<Result<TokenPrice, PriceError>>) -> Result<u32, PriceError> {
+        aggregated_price(&aggregator(&responses(quotes)))
+    }
+
+    #[test]
+    fn no_sources() {
+        assert!(AggregatedPriceAPI::new(Vec::new(), 10, Duration::minutes(30)).is_err());
+    }
+
+    #[test]
+    fn median_and_deviation() {
+        let values = |values: &[u32]| {
+            values
+                .iter()
+                .map(|value| Ratio::from_integer(BigUint::from(*value)))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        assert_eq!(median(values(&[3, 1, 2])), Ratio::from_integer(2u32.into()));
+        assert_eq!(
+            median(values(&[4, 1, 2, 3])),
+            Ratio::new(5u32.into(), 2u32.into())
+        );
+
+        let ratio = |value: u32| Ratio::from_integer(BigUint::from(value));
+        assert_eq!(
+            deviation(&ratio(90), &ratio(100)),
+            Ratio::new(1u32.into(), 10u32.into())
+        );
+        assert_eq!(
+            deviation(&ratio(110), &ratio(100)),
+            Ratio::new(1u32.into(), 10u32.into())
+        );
+        assert!(deviation(&ratio(0), &ratio(0)).is_zero());
+        assert!(deviation(&ratio(1), &ratio(0)) > Ratio::from_integer(1u32.into()));
+    }
+
+    /// Checks that the median of the accepted quotes is used and the outliers are rejected.
+    #[test]
+    fn outliers_are_rejected() {
+        assert_eq!(get_price(vec![price(100, 0)]).unwrap(), 100);
+        assert_eq!(
+            get_price(vec![price(100, 0), price(104, 0), price(102, 0)]).unwrap(),
+            102
+        );
+        // 1000 is an outlier, the median of the remaining quotes is used.
+        assert_eq!(
+            get_price(vec![price(1000, 0), price(100, 0), price(104, 0)]).unwrap(),
+            102
+        );
+    }
+
+    /// Checks that the stale quotes and failed sources are ignored.
+    #[test]
+    fn unavailable_sources_are_ignored() {
+        assert_eq!(
+            get_price(vec![price(200, 3600), price(100, 0), price(104, 0)]).unwrap(),
+            102
+        );
+        assert_eq!(
+            get_price(vec![
+                Err(PriceError::api_error("timeout")),
+                price(100, 0),
+                Err(PriceError::token_not_found("unknown token")),
+            ])
+            .unwrap(),
+            100
+        );
+
+        assert!(matches!(
+            get_price(vec![
+                price(100, 3600),
+                Err(PriceError::api_error("timeout"))
+            ]),
+            Err(PriceError::ApiError(_))
+        ));
+        assert!(matches!(
+            get_price(vec![
+                Err(PriceError::token_not_found("unknown token")),
+                Err(PriceError::token_not_found("unknown token")),
+            ]),
+            Err(PriceError::TokenNotFound(_))
+        ));
+    }
+
+    /// Checks that the last accepted price is used if the sources disagree.
+    #[test]
+    fn disagreement_fallback() {
+        // There is no accepted price yet.
+        assert!(matches!(
+            get_price(vec![price(100, 0), price(200, 0)]),
+            Err(PriceError::ApiError(_))
+        ));
+
+        let responses = responses(vec![price(100, 0), price(102, 0), price(104, 0)]);
+        let api = aggregator(&responses);
+        assert_eq!(aggregated_price(&api).unwrap(), 102);
+
+        // Sources disagree, the last accepted price is kept.
+        for (response, usd_price) in responses.iter().zip(&[100, 200, 400]) {
+            *response.lock().unwrap() = price(*usd_price, 0);
+        }
+        assert_eq!(aggregated_price(&api).unwrap(), 102);
+
+        // The last accepted price is stale.
+        api.last_accepted
+            .lock()
+            .unwrap()
+            .insert(token().id, price(102, 3600).unwrap());
+        assert!(matches!(
+            aggregated_price(&api),
+            Err(PriceError::ApiError(_))
+        ));
+    }
+
+    #[tokio::test]
+    // Should be run in the dev environment
+    async fn test_aggregated_dev_ticker() {
+        let client = reqwest::Client::new();
+        let coingecko =
+            CoinGeckoAPI::new(client.clone(), parse_env("FEE_TICKER_COINGECKO_BASE_URL"))
+                .await
+                .unwrap();
+        let coinmarketcap =
+            CoinMarketCapAPI::new(client, parse_env("FEE_TICKER_COINMARKETCAP_BASE_URL"));
+        let api = AggregatedPriceAPI::new(
+            vec![
+                PriceSource {
+                    name: "CoinGecko".to_string(),
+                    api: Box::new(coingecko),
+                },
+                PriceSource {
+                    name: "CoinMarketCap".to_string(),
+                    api: Box::new(coinmarketcap),
+                },
+            ],
+            10,
+            Duration::minutes(30),
+        )
+        .unwrap();
+        api.get_price(&token())
+            .await
+            .expect("Failed to get data from ticker");
+    }
+}
@@ -0,0 +1,713 @@
+//! Token prices derived from the on-chain DEX pools.
+//!
+//! Every priced token is mapped to a Uniswap-V2-style pair or to a Uniswap V3 pool. The price of
+//! the token in the counterpart token of the pool is the time-weighted average: for the V2 pair
+//! it's calculated from the cumulative prices recorded by the pair at the latest block and at the
+//! block lagging behind by the TWAP period, for the V3 pool from the average tick reported by the
+//! pool oracle. Prices set within the latest block have no weight, so they can't be manipulated
+//! with a flash loan. The counterpart token is either the USD stablecoin or a token having its own
+//! pool, so the prices are chained until the stablecoin is reached. Pools holding less than the
+//! configured value of the counterpart token are not trusted, since their price can be moved cheaply.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::time::Instant;
+// External deps
+use async_trait::async_trait;
+use chrono::Utc;
+use ethabi::{Contract, Token as AbiToken};
+use num::{rational::Ratio, BigRational, BigUint, One, ToPrimitive, Zero};
+use web3::{
+    contract::{
+        tokens::{Detokenize, Tokenize},
+        Options,
+    },
+    types::{BlockId, BlockNumber},
+};
+// Workspace deps
+use zksync_config::configs::ticker::{DexPool, DexPoolKind};
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{Address, Token, TokenPrice, U256};
+// Local deps
+use super::TokenPriceAPI;
+use crate::fee_ticker::PriceError;
+
+/// Maximum number of pools between the priced token and the USD stablecoin.
+const MAX_ROUTE_LENGTH: usize = 3;
+/// Maximum tick of the Uniswap V3 pool.
+const MAX_TICK: i64 = 887_272;
+/// Price ratio between the neighbouring ticks of the Uniswap V3 pool.
+const TICK_BASE: f64 = 1.0001;
+/// Average Ethereum block time used to find the block lagging behind by the TWAP period.
+const ETH_BLOCK_TIME_SECS: u64 = 12;
+/// Number of the fractional bits of the cumulative prices of the V2 pair (UQ112x112).
+const CUMULATIVE_PRICE_FRACTION_BITS: usize = 112;
+
+const POOL_ABI: &str = r#"[
+    {"type": "function", "name": "token0", "inputs": [], "outputs": [{"name": "", "type": "address"}], "stateMutability": "view"},
+    {"type": "function", "name": "token1", "inputs": [], "outputs": [{"name": "", "type": "address"}], "stateMutability": "view"},
+    {"type": "function", "name": "getReserves", "inputs": [], "outputs": [{"name": "reserve0", "type": "uint112"}, {"name": "reserve1", "type": "uint112"}, {"name": "blockTimestampLast", "type": "uint32"}], "stateMutability": "view"},
+    {"type": "function", "name": "price0CumulativeLast", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "price1CumulativeLast", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "liquidity", "inputs": [], "outputs": [{"name": "", "type": "uint128"}], "stateMutability": "view"},
+    {"type": "function", "name": "observe", "inputs": [{"name": "secondsAgos", "type": "uint32[]"}], "outputs": [{"name": "tickCumulatives", "type": "int56[]"}, {"name": "secondsPerLiquidityCumulativeX128s", "type": "uint160[]"}], "stateMutability": "view"}
+]"#;
+
+const TOKEN_ABI: &str = r#"[
+    {"type": "function", "name": "decimals", "inputs": [], "outputs": [{"name": "", "type": "uint8"}], "stateMutability": "view"}
+]"#;
+
+/// Price of the token in the counterpart token of its pool.
+#[derive(Debug)]
+struct PoolQuote {
+    /// Price of the whole token in the whole counterpart tokens.
+    price: Ratio<BigUint>,
+    counterpart: Address,
+    counterpart_decimals: u8,
+    /// Amount of the whole counterpart tokens held by the pool.
+    counterpart_reserve: Ratio<BigUint>,
+}
+
+/// State of the V2 pair at some block.
+#[derive(Debug, Clone, PartialEq)]
+struct PairObservation {
+    /// Reserve of the priced token in the smallest units.
+    reserve: U256,
+    /// Reserve of the counterpart token in the smallest units.
+    counterpart_reserve: U256,
+    /// Cumulative price of the priced token in the counterpart token as of `timestamp`.
+    price_cumulative: U256,
+    /// Timestamp of the last update of the pair.
+    timestamp: u32,
+}
+
+/// `TokenPriceAPI` reading the token prices from the DEX pools.
+#[derive(Debug, Clone)]
+pub struct DexPriceAPI {
+    eth_client: EthereumGateway,
+    pools: HashMap<Address, DexPool>,
+    pool_abi: Contract,
+    token_abi: Contract,
+    /// Stablecoin considered to be worth exactly 1 USD.
+    usd_token: Address,
+    /// Wrapped ETH, ETH is priced by its pool.
+    wrapped_eth: Address,
+    min_liquidity_usd: Ratio<BigUint>,
+    twap_period: u32,
+}
+
+impl DexPriceAPI {
+    pub fn new(
+        eth_client: EthereumGateway,
+        pools: Vec<DexPool>,
+        usd_token: Address,
+        wrapped_eth: Address,
+        min_liquidity_usd: u64,
+        twap_period: u32,
+    ) -> Self {
+        assert!(twap_period > 0, "TWAP period must be positive");
+        Self {
+            eth_client,
+            pools: pools.into_iter().map(|pool| (pool.token, pool)).collect(),
+            pool_abi: Contract::load(POOL_ABI.as_bytes()).expect("DEX pool abi"),
+            token_abi: Contract::load(TOKEN_ABI.as_bytes()).expect("DEX token abi"),
+            usd_token,
+            wrapped_eth,
+            min_liquidity_usd: Ratio::from_integer(BigUint::from(min_liquidity_usd)),
+            twap_period,
+        }
+    }
+
+    async fn call<R, P>(
+        &self,
+        abi: &Contract,
+        func: &str,
+        params: P,
+        address: Address,
+    ) -> Result<R, PriceError>
+    where
+        R: Detokenize + Unpin,
+        P: Tokenize + Clone,
+    {
+        self.call_at_block(abi, func, params, address, None).await
+    }
+
+    async fn call_at_block<R, P>(
+        &self,
+        abi: &Contract,
+        func: &str,
+        params: P,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> Result<R, PriceError>
+    where
+        R: Detokenize + Unpin,
+        P: Tokenize + Clone,
+    {
+        self.eth_client
+            .call_contract_function::<R, _, _, _>(
+                func,
+                params,
+                None,
+                Options::default(),
+                block,
+                address,
+                abi.clone(),
+            )
+            .await
+            .map_err(|err| {
+                PriceError::api_error(format!("Call of {} at {:?} failed: {}", func, address, err))
+            })
+    }
+
+    async fn decimals(&self, token: Address) -> Result<u8, PriceError> {
+        let decimals: U256 = self.call(&self.token_abi, "decimals", (), token).await?;
+        if decimals > U256::from(u8::MAX) {
+            return Err(PriceError::api_error(format!(
+                "Token {:?} reported {} decimals",
+                token, decimals
+            )));
+        }
+        Ok(decimals.as_u32() as u8)
+    }
+
+    /// Returns the latest block and the block lagging behind it by the TWAP period.
+    async fn twap_blocks(&self) -> Result<(BlockId, BlockId), PriceError> {
+        let latest = self
+            .eth_client
+            .block_number()
+            .await
+            .map_err(|err| PriceError::api_error(format!("Failed to get block number: {}", err)))?
+            .as_u64();
+        let lag = (self.twap_period as u64 / ETH_BLOCK_TIME_SECS).max(1);
+        let block = |number: u64| BlockId::Number(BlockNumber::Number(number.into()));
+        Ok((block(latest), block(latest.saturating_sub(lag))))
+    }
+
+    /// Reads the reserves and the cumulative price of the pair at the block.
+    async fn pair_observation(
+        &self,
+        pool: &DexPool,
+        is_token0: bool,
+        block: BlockId,
+    ) -> Result<PairObservation, PriceError> {
+        let (reserve0, reserve1, timestamp): (U256, U256, U256) = self
+            .call_at_block(&self.pool_abi, "getReserves", (), pool.pool, Some(block))
+            .await?;
+        let (reserve, counterpart_reserve, func) = if is_token0 {
+            (reserve0, reserve1, "price0CumulativeLast")
+        } else {
+            (reserve1, reserve0, "price1CumulativeLast")
+        };
+        let price_cumulative: U256 = self
+            .call_at_block(&self.pool_abi, func, (), pool.pool, Some(block))
+            .await?;
+        Ok(PairObservation {
+            reserve,
+            counterpart_reserve,
+            price_cumulative,
+            timestamp: timestamp.low_u32(),
+        })
+    }
+
+    /// Reads the price of the pool token in its counterpart token.
+    async fn pool_quote(&self, pool: &DexPool, decimals: u8) -> Result<PoolQuote, PriceError> {
+        let token0: Address = self.call(&self.pool_abi, "token0", (), pool.pool).await?;
+        let token1: Address = self.call(&self.pool_abi, "token1", (), pool.pool).await?;
+        let (counterpart, is_token0) = if token0 == pool.token {
+            (token1, true)
+        } else if token1 == pool.token {
+            (token0, false)
+        } else {
+            return Err(PriceError::api_error(format!(
+                "Pool {:?} does not contain the token {:?}",
+                pool.pool, pool.token
+            )));
+        };
+        let counterpart_decimals = self.decimals(counterpart).await?;
+
+        let (price, counterpart_reserve) = match pool.kind {
+            DexPoolKind::V2 => {
+                let (latest_block, lagged_block) = self.twap_blocks().await?;
+                let latest = self.pair_observation(pool, is_token0, latest_block).await?;
+                let lagged = self.pair_observation(pool, is_token0, lagged_block).await?;
+                if latest.reserve.is_zero() || lagged.reserve.is_zero() {
+                    return Err(PriceError::token_not_found(format!(
+                        "Pool {:?} is empty",
+                        pool.pool
+                    )));
+                }
+                let price = pair_twap(&lagged, &latest) * BigUint::from(10u32).pow(decimals as u32)
+                    / BigUint::from(10u32).pow(counterpart_decimals as u32);
+                // The reserves can be inflated within the block, so the smaller one is used.
+                let counterpart_reserve =
+                    latest.counterpart_reserve.min(lagged.counterpart_reserve);
+                (
+                    price,
+                    whole_tokens(counterpart_reserve, counterpart_decimals),
+                )
+            }
+            DexPoolKind::V3 => {
+                let tick = self.average_tick(pool).await?;
+                // Price of the token0 in the token1 is `1.0001^tick` in the smallest units.
+                let (decimals0, decimals1) = if is_token0 {
+                    (decimals, counterpart_decimals)
+                } else {
+                    (counterpart_decimals, decimals)
+                };
+                let price0 =
+                    TICK_BASE.powi(tick as i32) * 10f64.powi(decimals0 as i32 - decimals1 as i32);
+                let price = if is_token0 { price0 } else { price0.recip() };
+                let price = float_to_ratio(price).ok_or_else(|| {
+                    PriceError::api_error(format!(
+                        "Incorrect price {} reported by the pool {:?}",
+                        price, pool.pool
+                    ))
+                })?;
+
+                // The in-range liquidity is converted into the virtual reserve of the counterpart
+                // token: `L * sqrt(P)` of the token1 or `L / sqrt(P)` of the token0, where `P` is
+                // the price of the token0 in the token1 in the smallest units.
+                let liquidity: U256 = self
+                    .call(&self.pool_abi, "liquidity", (), pool.pool)
+                    .await?;
+                let sqrt_price = TICK_BASE.powf(tick as f64 / 2.0);
+                let virtual_reserve = if is_token0 {
+                    liquidity.as_u128() as f64 * sqrt_price
+                } else {
+                    liquidity.as_u128() as f64 / sqrt_price
+                };
+                let counterpart_reserve =
+                    float_to_ratio(virtual_reserve / 10f64.powi(counterpart_decimals as i32))
+                        .unwrap_or_else(Ratio::zero);
+                (price, counterpart_reserve)
+            }
+        };
+
+        Ok(PoolQuote {
+            price,
+            counterpart,
+            counterpart_decimals,
+            counterpart_reserve,
+        })
+    }
+
+    /// Calculates the time-weighted average tick of the V3 pool over the `twap_period`.
+    async fn average_tick(&self, pool: &DexPool) -> Result<i64, PriceError> {
+        let seconds_agos = vec![U256::from(self.twap_period), U256::zero()];
+        let (tick_cumulatives, _): (AbiToken, AbiToken) = self
+            .call(&self.pool_abi, "observe", seconds_agos, pool.pool)
+            .await?;
+        let tick_cumulatives: Vec<_> = match tick_cumulatives {
+            AbiToken::Array(values) => values
+                .into_iter()
+                .map(|value| match value {
+                    AbiToken::Int(value) => Some(value.low_u64() as i64),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let (past, current) = match tick_cumulatives.as_slice() {
+            [Some(past), Some(current)] => (*past, *current),
+            _ => {
+                return Err(PriceError::api_error(format!(
+                    "Pool {:?} returned malformed observations",
+                    pool.pool
+                )))
+            }
+        };
+
+        // Rounded towards negative infinity, same as in the Uniswap oracle library.
+        let period = self.twap_period as i64;
+        let delta = current - past;
+        let mut tick = delta / period;
+        if delta < 0 && delta % period != 0 {
+            tick -= 1;
+        }
+        if tick.abs() > MAX_TICK {
+            return Err(PriceError::api_error(format!(
+                "Pool {:?} reported the tick {} out of range",
+                pool.pool, tick
+            )));
+        }
+        Ok(tick)
+    }
+}
+
+#[async_trait]
+impl TokenPriceAPI for DexPriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let start = Instant::now();
+        let mut address = if token.address == Address::zero() {
+            self.wrapped_eth
+        } else {
+            token.address
+        };
+        let mut decimals = token.decimals;
+
+        let mut route = Vec::new();
+        while address != self.usd_token {
+            if route.len() == MAX_ROUTE_LENGTH {
+                return Err(PriceError::api_error(format!(
+                    "Route of {} to the USD token is longer than {} pools",
+                    token.symbol, MAX_ROUTE_LENGTH
+                )));
+            }
+            let pool = self.pools.get(&address).ok_or_else(|| {
+                PriceError::token_not_found(format!("No DEX pool for the token {:?}", address))
+            })?;
+            let quote = self.pool_quote(pool, decimals).await?;
+            address = quote.counterpart;
+            decimals = quote.counterpart_decimals;
+            route.push((pool, quote));
+        }
+
+        // Counterpart token of the last pool is the USD token, so the prices are calculated
+        // starting from the end of the route.
+        let mut usd_price = Ratio::one();
+        for (pool, quote) in route.into_iter().rev() {
+            let liquidity = &quote.counterpart_reserve * &usd_price;
+            if liquidity < self.min_liquidity_usd {
+                metrics::increment_counter!("ticker.dex.thin_pools", "pool" => format!("{:?}", pool.pool));
+                return Err(PriceError::token_not_found(format!(
+                    "Pool {:?} holds {:.2} USD of the counterpart token, less than required",
+                    pool.pool,
+                    liquidity.to_f64().unwrap_or_default()
+                )));
+            }
+            usd_price = quote.price * usd_price;
+        }
+
+        metrics::histogram!("ticker.dex.request", start.elapsed());
+        Ok(TokenPrice {
+            usd_price,
+            last_updated: Utc::now(),
+        })
+    }
+}
+
+fn u256_to_biguint(value: U256) -> BigUint {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    BigUint::from_bytes_be(&bytes)
+}
+
+/// Converts the amount in the smallest units into the whole tokens.
+fn whole_tokens(amount: U256, decimals: u8) -> Ratio<BigUint> {
+    Ratio::new(
+        u256_to_biguint(amount),
+        BigUint::from(10u32).pow(decimals as u32),
+    )
+}
+
+/// Calculates the time-weighted average price of the token in the counterpart token (in the smallest
+/// units) between the observations of the V2 pair. If the pair was not updated in between, its
+/// reserves have not changed, so they define the price.
+fn pair_twap(lagged: &PairObservation, latest: &PairObservation) -> Ratio<BigUint> {
+    // Both the timestamps and the cumulative prices are expected to overflow.
+    let elapsed = latest.timestamp.wrapping_sub(lagged.timestamp);
+    if elapsed == 0 {
+        return Ratio::new(
+            u256_to_biguint(lagged.counterpart_reserve),
+            u256_to_biguint(lagged.reserve),
+        );
+    }
+    let (delta, _) = latest
+        .price_cumulative
+        .overflowing_sub(lagged.price_cumulative);
+    Ratio::new(
+        u256_to_biguint(delta),
+        BigUint::from(elapsed) << CUMULATIVE_PRICE_FRACTION_BITS,
+    )
+}
+
+fn float_to_ratio(value: f64) -> Option<Ratio<BigUint>> {
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+    let value = BigRational::from_float(value)?;
+    Some(Ratio::new(
+        value.numer().to_biguint()?,
+        value.denom().to_biguint()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use zksync_eth_client::clients::mock::MockEthereum;
+    use zksync_types::{TokenId, TokenKind};
+
+    const MIN_LIQUIDITY_USD: u64 = 1000;
+    const TWAP_PERIOD: u32 = 1800;
+
+    fn address(s: &str) -> Address {
+        Address::from_str(s).unwrap()
+    }
+
+    fn usdc() -> Address {
+        address("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+    }
+
+    fn weth() -> Address {
+        address("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+    }
+
+    fn dai() -> Address {
+        address("6b175474e89094c44da98b954eedeac495271d0f")
+    }
+
+    fn uni() -> Address {
+        address("1f9840a85d5af5bf1d1762f925bdaddc4201f984")
+    }
+
+    fn token(address: Address, symbol: &str, decimals: u8) -> Token {
+        Token::new(TokenId(1), address, symbol, decimals, TokenKind::ERC20)
+    }
+
+    fn int_token(value: i64) -> AbiToken {
+        if value >= 0 {
+            AbiToken::Int(U256::from(value))
+        } else {
+            AbiToken::Int(!U256::from(-(value + 1)))
+        }
+    }
+
+    fn units(amount: u64, decimals: u8) -> U256 {
+        U256::from(amount) * U256::exp10(decimals as usize)
+    }
+
+    /// Pools used in tests:
+    /// - DAI/USDC V2 pair with the DAI price of 1.01 USD;
+    /// - USDC/WETH V3 pool with the ETH price of ~2000 USD;
+    /// - UNI/WETH V2 pair with the UNI price of 0.001 ETH holding 1 ETH.
+    async fn mock_pools() -> (MockEthereum, Vec<DexPool>) {
+        let mut eth = MockEthereum::default();
+        let dai_usdc = Address::repeat_byte(1);
+        let usdc_weth = Address::repeat_byte(2);
+        let uni_weth = Address::repeat_byte(3);
+
+        for (token, decimals) in [(usdc(), 6u8), (weth(), 18), (dai(), 18), (uni(), 18)] {
+            eth.add_contract_call_response(
+                token,
+                "decimals",
+                vec![AbiToken::Uint(decimals.into())],
+            )
+            .await;
+        }
+
+        let pair = |pool: Address, token0: Address, token1: Address| {
+            vec![
+                (pool, "token0", vec![AbiToken::Address(token0)]),
+                (pool, "token1", vec![AbiToken::Address(token1)]),
+            ]
+        };
+        let mut calls = Vec::new();
+        calls.extend(pair(dai_usdc, dai(), usdc()));
+        calls.extend(pair(usdc_weth, usdc(), weth()));
+        calls.extend(pair(uni_weth, uni(), weth()));
+        // Mock responses don't depend on the block, so the pairs look as if they were not updated
+        // during the TWAP period and the price is defined by the reserves.
+        calls.push((
+            dai_usdc,
+            "getReserves",
+            vec![
+                AbiToken::Uint(units(1_000_000, 18)),
+                AbiToken::Uint(units(1_010_000, 6)),
+                AbiToken::Uint(0.into()),
+            ],
+        ));
+        calls.push((
+            uni_weth,
+            "getReserves",
+            vec![
+                AbiToken::Uint(units(1000, 18)),
+                AbiToken::Uint(units(1, 18)),
+                AbiToken::Uint(0.into()),
+            ],
+        ));
+        for pool in [dai_usdc, uni_weth] {
+            calls.push((pool, "price0CumulativeLast", vec![AbiToken::Uint(0.into())]));
+            calls.push((pool, "price1CumulativeLast", vec![AbiToken::Uint(0.into())]));
+        }
+        // USDC price is 1/2000 WETH, that is `1e12 / 2000` in the smallest units.
+        let tick = (5e8f64.ln() / TICK_BASE.ln()).round() as i64;
+        let base = -1_000_000_000;
+        calls.push((
+            usdc_weth,
+            "observe",
+            vec![
+                AbiToken::Array(vec![
+                    int_token(base),
+                    int_token(base + tick * TWAP_PERIOD as i64),
+                ]),
+                AbiToken::Array(Vec::new()),
+            ],
+        ));
+        // Virtual reserve of 5M USDC: `L = x * sqrt(P)`.
+        let liquidity = 5e12 * 5e8f64.sqrt();
+        calls.push((
+            usdc_weth,
+            "liquidity",
+            vec![AbiToken::Uint((liquidity as u128).into())],
+        ));
+        for (contract, func, response) in calls {
+            eth.add_contract_call_response(contract, func, response)
+                .await;
+        }
+
+        let pools = vec![
+            DexPool {
+                kind: DexPoolKind::V2,
+                token: dai(),
+                pool: dai_usdc,
+            },
+            DexPool {
+                kind: DexPoolKind::V3,
+                token: weth(),
+                pool: usdc_weth,
+            },
+            DexPool {
+                kind: DexPoolKind::V2,
+                token: uni(),
+                pool: uni_weth,
+            },
+        ];
+        (eth, pools)
+    }
+
+    async fn dex_api(min_liquidity_usd: u64) -> DexPriceAPI {
+        let (eth, pools) = mock_pools().await;
+        DexPriceAPI::new(
+            EthereumGateway::Mock(eth),
+            pools,
+            usdc(),
+            weth(),
+            min_liquidity_usd,
+            TWAP_PERIOD,
+        )
+    }
+
+    fn assert_price_close(price: &TokenPrice, expected: f64) {
+        let price = price.usd_price.to_f64().unwrap();
+        assert!(
+            (price - expected).abs() / expected < 1e-4,
+            "price {}, expected {}",
+            price,
+            expected
+        );
+    }
+
+    /// Checks the price calculated from the reserves of the V2 pair.
+    #[tokio::test]
+    async fn v2_pair_price() {
+        let api = dex_api(MIN_LIQUIDITY_USD).await;
+        let price = api.get_price(&token(dai(), "DAI", 18)).await.unwrap();
+        assert_eq!(
+            price.usd_price,
+            Ratio::new(BigUint::from(101u32), BigUint::from(100u32))
+        );
+
+        let price = api.get_price(&token(usdc(), "USDC", 6)).await.unwrap();
+        assert_eq!(price.usd_price, Ratio::one());
+    }
+
+    /// Checks the V3 TWAP price and the price chained through the WETH pool.
+    #[tokio::test]
+    async fn v3_twap_and_route() {
+        let api = dex_api(MIN_LIQUIDITY_USD).await;
+        let eth_price = api
+            .get_price(&token(Address::zero(), "ETH", 18))
+            .await
+            .unwrap();
+        assert_price_close(&eth_price, 2000.0);
+
+        let uni_price = api.get_price(&token(uni(), "UNI", 18)).await.unwrap();
+        assert_price_close(&uni_price, 2.0);
+    }
+
+    /// Checks that the thin pools and the tokens without pools are reported as not found.
+    #[tokio::test]
+    async fn untrusted_pools() {
+        // USDC/WETH pool holds the virtual reserve of 5M USDC.
+        let api = dex_api(10_000_000).await;
+        let eth = token(Address::zero(), "ETH", 18);
+        let err = api.get_price(&eth).await.unwrap_err();
+        assert!(matches!(err, PriceError::TokenNotFound(_)), "{}", err);
+        assert!(dex_api(4_000_000).await.get_price(&eth).await.is_ok());
+
+        // UNI/WETH pair holds 1 ETH, that is ~2000 USD.
+        let api = dex_api(10_000).await;
+        let err = api.get_price(&token(uni(), "UNI", 18)).await.unwrap_err();
+        assert!(matches!(err, PriceError::TokenNotFound(_)), "{}", err);
+        assert!(api.get_price(&token(dai(), "DAI", 18)).await.is_ok());
+
+        let unknown = token(Address::repeat_byte(0xff), "UNKNOWN", 18);
+        let err = api.get_price(&unknown).await.unwrap_err();
+        assert!(matches!(err, PriceError::TokenNotFound(_)), "{}", err);
+    }
+
+    /// Checks the time-weighted average price of the V2 pair.
+    #[test]
+    fn v2_pair_twap() {
+        let observation = |price_cumulative: U256, timestamp: u32| PairObservation {
+            reserve: 1000.into(),
+            counterpart_reserve: 3000.into(),
+            price_cumulative,
+            timestamp,
+        };
+        let q112 = U256::one() << CUMULATIVE_PRICE_FRACTION_BITS;
+
+        // The price was 1 for 100 seconds and 3 for 300 seconds.
+        let lagged = observation(q112 * 1000, 1000);
+        let latest = observation(q112 * 2000, 1400);
+        assert_eq!(
+            pair_twap(&lagged, &latest),
+            Ratio::new(BigUint::from(5u32), BigUint::from(2u32))
+        );
+
+        // Both the cumulative price and the timestamp have overflown.
+        let lagged = observation(U256::max_value() - q112 * 100 + 1, u32::MAX - 99);
+        let latest = observation(q112 * 100, 100);
+        assert_eq!(pair_twap(&lagged, &latest), Ratio::one());
+
+        // The pair was not updated, the price is defined by the reserves.
+        assert_eq!(
+            pair_twap(&latest, &latest),
+            Ratio::from_integer(BigUint::from(3u32))
+        );
+    }
+
+    /// Checks that the failed contract calls are reported as API errors.
+    #[tokio::test]
+    async fn failed_calls() {
+        let (eth, mut pools) = mock_pools().await;
+        // The pair doesn't contain the token.
+        pools.push(DexPool {
+            kind: DexPoolKind::V2,
+            token: Address::repeat_byte(0xff),
+            pool: Address::repeat_byte(1),
+        });
+        // The pool contract doesn't exist.
+        pools.push(DexPool {
+            kind: DexPoolKind::V2,
+            token: Address::repeat_byte(0xfe),
+            pool: Address::repeat_byte(0xfe),
+        });
+        let api = DexPriceAPI::new(
+            EthereumGateway::Mock(eth),
+            pools,
+            usdc(),
+            weth(),
+            MIN_LIQUIDITY_USD,
+            TWAP_PERIOD,
+        );
+
+        for address in [Address::repeat_byte(0xff), Address::repeat_byte(0xfe)] {
+            let err = api.get_price(&token(address, "TKN", 18)).await.unwrap_err();
+            assert!(matches!(err, PriceError::ApiError(_)), "{}", err);
+        }
+    }
+}
@@ -3,15 +3,17 @@ use super::PriceError;
 use anyhow::format_err;
 use async_trait::async_trait;
 use chrono::Utc;
-use num::rational::Ratio;
+use num::{rational::Ratio, Zero};
 
 use std::time::{Duration, Instant};
 
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenPrice};
 
+pub mod aggregator;
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod dex;
 
 const UPDATE_PRICE_INTERVAL_SECS: u64 = 10 * 60;
 /// The limit of time we are willing to wait for response.
@@ -30,18 +32,48 @@ pub trait FeeTickerAPI {
     async fn keep_price_updated(self);
 }
 
+/// Retention settings of the token price history and the fee quote snapshots.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PriceHistorySettings {
+    /// Samples older than this are removed.
+    pub retention: Duration,
+    /// Samples older than this are downsampled.
+    pub downsample_after: Duration,
+    /// Interval between the downsampled samples.
+    pub downsample_interval: Duration,
+    /// Fee quote snapshots and the fee quotes used earlier than this are removed.
+    pub snapshot_retention: Duration,
+}
+
+impl From<&zksync_config::TickerConfig> for PriceHistorySettings {
+    fn from(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            retention: config.price_history_retention(),
+            downsample_after: config.price_history_downsample_after(),
+            downsample_interval: config.price_history_downsample_interval(),
+            snapshot_retention: config.fee_quote_snapshot_retention(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct TickerApi<T: TokenPriceAPI> {
     db_pool: ConnectionPool,
 
     token_price_api: T,
+    price_history: PriceHistorySettings,
 }
 
 impl<T: TokenPriceAPI> TickerApi<T> {
-    pub fn new(db_pool: ConnectionPool, token_price_api: T) -> Self {
+    pub fn new(
+        db_pool: ConnectionPool,
+        token_price_api: T,
+        price_history: PriceHistorySettings,
+    ) -> Self {
         Self {
             db_pool,
             token_price_api,
+            price_history,
         }
     }
 
@@ -68,13 +100,89 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             .access_storage()
             .await
             .map_err(|e| format_err!("Can't access storage: {}", e))?;
-
-        storage
+        let mut transaction = storage.start_transaction().await?;
+
+        // Tokens that are not listed have zero price, there is no point in keeping its history.
+        if !price.usd_price.is_zero() {
+            transaction
+                .ticker_schema()
+                .store_price_sample(token_id, &price)
+                .await
+                .map_err(|e| format_err!("Can't store price sample: {}", e))?;
+        }
+        transaction
             .tokens_schema()
             .update_historical_ticker_price(token_id, price)
             .await
             .map_err(|e| format_err!("Can't update historical ticker price from storage: {}", e))?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Removes the expired price samples and downsamples the old ones.
+    async fn compact_price_history(&self) -> Result<(), anyhow::Error> {
+        let now = Utc::now();
+        let retention_cutoff = now - chrono::Duration::from_std(self.price_history.retention)?;
+        let downsample_cutoff =
+            now - chrono::Duration::from_std(self.price_history.downsample_after)?;
+
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|e| format_err!("Can't access storage: {}", e))?;
+        let removed = storage
+            .ticker_schema()
+            .compact_price_history(
+                retention_cutoff,
+                downsample_cutoff,
+                self.price_history.downsample_interval,
+            )
+            .await
+            .map_err(|e| format_err!("Can't compact price history: {}", e))?;
+
+        metrics::counter!("ticker.price_history.removed_samples", removed);
+        Ok(())
+    }
+
+    /// Removes the expired fee quote snapshots.
+    async fn remove_expired_fee_quote_snapshots(&self) -> Result<(), anyhow::Error> {
+        let cutoff =
+            Utc::now() - chrono::Duration::from_std(self.price_history.snapshot_retention)?;
+
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|e| format_err!("Can't access storage: {}", e))?;
+        let removed = storage
+            .ticker_schema()
+            .remove_fee_quote_snapshots(cutoff)
+            .await
+            .map_err(|e| format_err!("Can't remove fee quote snapshots: {}", e))?;
+
+        metrics::counter!("ticker.fee_quote_snapshots.removed", removed);
+        Ok(())
+    }
+
+    /// Removes the expired unused fee quotes and the used ones older than the snapshots.
+    async fn remove_expired_fee_quotes(&self) -> Result<(), anyhow::Error> {
+        let now = Utc::now();
+        let used_cutoff = now - chrono::Duration::from_std(self.price_history.snapshot_retention)?;
+
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|e| format_err!("Can't access storage: {}", e))?;
+        let removed = storage
+            .ticker_schema()
+            .remove_fee_quotes(now, used_cutoff)
+            .await
+            .map_err(|e| format_err!("Can't remove fee quotes: {}", e))?;
 
+        metrics::counter!("ticker.fee_quotes.removed", removed);
         Ok(())
     }
     async fn update_price(&self, token: &Token) -> Result<(), PriceError> {
@@ -115,6 +223,15 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
             } else {
                 vlog::warn!("Can't get info from the database; waiting for the next iteration");
             };
+            if let Err(e) = self.compact_price_history().await {
+                vlog::error!("Can't compact the token price history. Error: {}", e);
+            }
+            if let Err(e) = self.remove_expired_fee_quote_snapshots().await {
+                vlog::error!("Can't remove the expired fee quote snapshots. Error: {}", e);
+            }
+            if let Err(e) = self.remove_expired_fee_quotes().await {
+                vlog::error!("Can't remove the expired fee quotes. Error: {}", e);
+            }
             tokio::time::sleep(Duration::from_secs(UPDATE_PRICE_INTERVAL_SECS)).await;
         }
     }
@@ -5,21 +5,30 @@
 #[cfg(test)]
 use std::any::Any;
 
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 // External deps
 use anyhow::format_err;
 use async_trait::async_trait;
 use chrono::Utc;
 use num::rational::Ratio;
 use num::BigUint;
+use tokio::sync::RwLock;
 // Workspace deps
-use zksync_storage::ConnectionPool;
+use zksync_storage::{
+    ticker::records::{FeeQuoteSnapshot, NewFeeQuote, StoredFeeQuote},
+    ConnectionPool,
+};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::{Address, Token, TokenId, TokenLike, TokenPrice};
+use zksync_types::{tx::TxHash, Address, Token, TokenId, TokenLike, TokenPrice};
 // Local deps
+use crate::fee_ticker::constants::{AMORTIZED_COST_PER_CHUNK, GAS_PRICE_SCALE_PERCENT};
 use crate::fee_ticker::PriceError;
 
+/// Interval after which the cached gas calibration is reloaded from the database.
+const GAS_CALIBRATION_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
 pub trait FeeTickerClone {
     fn clone_box(&self) -> Box<dyn FeeTickerInfo>;
 }
@@ -58,8 +67,27 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
     /// Get current gas price in ETH
     async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error>;
 
+    /// Get the latest gas calibration, if the gas costs have been calibrated
+    async fn get_gas_calibration(&self) -> anyhow::Result<Option<GasCalibration>>;
+
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error>;
 
+    /// Store the prices used for the fee quote
+    async fn store_fee_quote_snapshot(&self, snapshot: FeeQuoteSnapshot) -> anyhow::Result<()>;
+
+    /// Store the fee quote, returns the identifier of the quote
+    async fn store_fee_quote(&self, quote: NewFeeQuote) -> anyhow::Result<i64>;
+
+    async fn load_fee_quote(&self, id: i64) -> anyhow::Result<Option<StoredFeeQuote>>;
+
+    /// Atomically claim the unused and not expired fee quote for the transaction (or the batch).
+    /// Returns `false` if the quote can't be claimed.
+    async fn claim_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool>;
+
+    /// Release the fee quote claimed by the transaction (or the batch).
+    /// Returns `false` if the quote isn't claimed by this transaction.
+    async fn release_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool>;
+
     /// Make boxed value to any. Helpful for downcasting in tests
     #[cfg(test)]
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
@@ -69,6 +97,8 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 pub struct TickerInfo {
     db: ConnectionPool,
     token_db_cache: TokenDBCache,
+    /// Latest gas calibration along with the moment it was loaded at.
+    gas_calibration: Arc<RwLock<Option<(Option<GasCalibration>, Instant)>>>,
 }
 
 impl TickerInfo {
@@ -76,6 +106,25 @@ impl TickerInfo {
         Self {
             db,
             token_db_cache: Default::default(),
+            gas_calibration: Default::default(),
+        }
+    }
+}
+
+/// Gas costs used by the fee ticker, calibrated from the confirmed Ethereum transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasCalibration {
+    /// Gas cost per chunk to cover constant cost of commit, execute and prove transactions.
+    pub amortized_cost_per_chunk: u64,
+    /// Scale of the average gas price used to estimate the gas price risk in percent.
+    pub gas_price_scale_percent: u64,
+}
+
+impl Default for GasCalibration {
+    fn default() -> Self {
+        Self {
+            amortized_cost_per_chunk: AMORTIZED_COST_PER_CHUNK,
+            gas_price_scale_percent: GAS_PRICE_SCALE_PERCENT,
         }
     }
 }
@@ -235,6 +284,29 @@ impl FeeTickerInfo for TickerInfo {
         Ok(average_gas_price)
     }
 
+    async fn get_gas_calibration(&self) -> anyhow::Result<Option<GasCalibration>> {
+        let start = Instant::now();
+        if let Some((calibration, update_time)) = *self.gas_calibration.read().await {
+            if update_time.elapsed() < GAS_CALIBRATION_REFRESH_INTERVAL {
+                return Ok(calibration);
+            }
+        }
+
+        let mut storage = self.db.access_storage().await?;
+        let calibration = storage
+            .ticker_schema()
+            .load_last_gas_calibration()
+            .await?
+            .map(|calibration| GasCalibration {
+                amortized_cost_per_chunk: calibration.amortized_cost_per_chunk as u64,
+                gas_price_scale_percent: calibration.gas_price_scale_percent as u64,
+            });
+        *self.gas_calibration.write().await = Some((calibration, Instant::now()));
+
+        metrics::histogram!("ticker_info.get_gas_calibration", start.elapsed());
+        Ok(calibration)
+    }
+
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
         let start = Instant::now();
         // Try to find the token in the cache first.
@@ -258,6 +330,56 @@ impl FeeTickerInfo for TickerInfo {
         result
     }
 
+    async fn store_fee_quote_snapshot(&self, snapshot: FeeQuoteSnapshot) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        storage
+            .ticker_schema()
+            .store_fee_quote_snapshot(snapshot)
+            .await?;
+        metrics::histogram!("ticker_info.store_fee_quote_snapshot", start.elapsed());
+        Ok(())
+    }
+
+    async fn store_fee_quote(&self, quote: NewFeeQuote) -> anyhow::Result<i64> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        let id = storage.ticker_schema().store_fee_quote(quote).await?;
+        metrics::histogram!("ticker_info.store_fee_quote", start.elapsed());
+        Ok(id)
+    }
+
+    async fn load_fee_quote(&self, id: i64) -> anyhow::Result<Option<StoredFeeQuote>> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        let quote = storage.ticker_schema().load_fee_quote(id).await?;
+        metrics::histogram!("ticker_info.load_fee_quote", start.elapsed());
+        Ok(quote)
+    }
+
+    async fn claim_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        let claimed = storage
+            .ticker_schema()
+            .claim_fee_quote(id, &tx_hash)
+            .await?
+            .is_some();
+        metrics::histogram!("ticker_info.claim_fee_quote", start.elapsed());
+        Ok(claimed)
+    }
+
+    async fn release_fee_quote(&self, id: i64, tx_hash: TxHash) -> anyhow::Result<bool> {
+        let start = Instant::now();
+        let mut storage = self.db.access_storage().await?;
+        let released = storage
+            .ticker_schema()
+            .release_fee_quote(id, &tx_hash)
+            .await?;
+        metrics::histogram!("ticker_info.release_fee_quote", start.elapsed());
+        Ok(released)
+    }
+
     #[cfg(test)]
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
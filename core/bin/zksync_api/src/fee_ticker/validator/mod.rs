@@ -121,6 +121,10 @@ impl FeeTokenValidator {
     pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
+            // Tokens that failed the validation are rejected regardless of their market volume.
+            if !token.status.is_safe() {
+                return Ok(false);
+            }
             if self.unconditionally_valid.contains(&token.address) {
                 return Ok(true);
             }
@@ -168,7 +172,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::Mutex;
-    use zksync_types::{TokenId, TokenKind};
+    use zksync_types::{TokenId, TokenKind, TokenStatus};
 
     #[derive(Clone)]
     struct InMemoryTokenWatcher {
@@ -199,7 +203,21 @@ mod tests {
 
         let eth_address = Address::from_str("0000000000000000000000000000000000000000").unwrap();
         let eth_token = Token::new(TokenId(2), eth_address, "ETH", 18, TokenKind::ERC20);
-        let all_tokens = vec![dai_token.clone(), phnx_token.clone()];
+        let rebasing_token_address =
+            Address::from_str("d46ba6d942050d489dbd938a2c909a5d5039a161").unwrap();
+        let rebasing_token = Token::new(
+            TokenId(3),
+            rebasing_token_address,
+            "AMPL",
+            9,
+            TokenKind::ERC20,
+        )
+        .with_status(TokenStatus::Quarantined);
+        let all_tokens = vec![
+            dai_token.clone(),
+            phnx_token.clone(),
+            rebasing_token.clone(),
+        ];
 
         let mut market = HashMap::new();
         market.insert(
@@ -216,14 +234,26 @@ mod tests {
                 last_updated: Utc::now(),
             },
         );
+        market.insert(
+            rebasing_token.id,
+            TokenMarketVolume {
+                market_volume: Ratio::new(BigUint::from(200u32), BigUint::from(1u32)),
+                last_updated: Utc::now(),
+            },
+        );
 
         let mut tokens = HashMap::new();
         tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
         tokens.insert(TokenLike::Address(phnx_token_address), phnx_token.clone());
         tokens.insert(TokenLike::Address(eth_address), eth_token);
+        tokens.insert(
+            TokenLike::Address(rebasing_token_address),
+            rebasing_token.clone(),
+        );
         let mut amounts = HashMap::new();
         amounts.insert(dai_token_address, BigDecimal::from(200));
         amounts.insert(phnx_token_address, BigDecimal::from(10));
+        amounts.insert(rebasing_token_address, BigDecimal::from(200));
         let mut unconditionally_valid = HashSet::new();
         unconditionally_valid.insert(eth_address);
 
@@ -280,8 +310,14 @@ mod tests {
             .token_allowed(TokenLike::Address(eth_address))
             .await
             .unwrap();
+        // Token with a high market volume is rejected because it failed the validation.
+        let rebasing_allowed = validator
+            .token_allowed(TokenLike::Address(rebasing_token_address))
+            .await
+            .unwrap();
         assert!(dai_allowed);
         assert!(!phnx_allowed);
         assert!(eth_allowed);
+        assert!(!rebasing_allowed);
     }
 }
@@ -13,7 +13,7 @@ use web3::{
 use zksync_contracts::{governance_contract, zksync_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, U256,
+    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, H256, U256,
 };
 
 struct ContractTopics {
@@ -59,6 +59,7 @@ pub trait EthClient {
         to: BlockNumber,
     ) -> anyhow::Result<Vec<NewTokenEvent>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
+    async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce)
         -> anyhow::Result<u64>;
@@ -224,6 +225,14 @@ impl EthClient for EthHttpClient {
         Ok(self.client.block_number().await?.as_u64())
     }
 
+    async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        let start = Instant::now();
+
+        let result = self.client.get_block_hash(block_number).await;
+        metrics::histogram!("eth_watcher.get_block_hash", start.elapsed());
+        result
+    }
+
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>> {
         self.client
             .call_main_contract_function(
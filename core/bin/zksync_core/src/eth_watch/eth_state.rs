@@ -1,8 +1,8 @@
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 // External uses
 // Workspace deps
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 // Local deps
 use super::received_ops::ReceivedPriorityOp;
 
@@ -39,6 +39,9 @@ pub struct ETHState {
     new_tokens: Vec<NewTokenEvent>,
     /// List of events denoting registered factories for NFT withdrawing
     register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+    /// Hashes of the recent blocks containing the accepted events.
+    /// They are re-checked to detect reorgs deeper than the number of confirmations.
+    accepted_block_hashes: BTreeMap<u64, H256>,
 }
 
 impl ETHState {
@@ -49,6 +52,7 @@ impl ETHState {
         priority_queue: HashMap<SerialId, ReceivedPriorityOp>,
         new_tokens: Vec<NewTokenEvent>,
         register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+        accepted_block_hashes: BTreeMap<u64, H256>,
     ) -> Self {
         assert!(
             last_ethereum_block_backup <= last_ethereum_block,
@@ -67,6 +71,7 @@ impl ETHState {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            accepted_block_hashes,
         }
     }
 
@@ -90,6 +95,10 @@ impl ETHState {
         &self.new_tokens
     }
 
+    pub fn accepted_block_hashes(&self) -> &BTreeMap<u64, H256> {
+        &self.accepted_block_hashes
+    }
+
     pub fn next_priority_op_id(&self) -> SerialId {
         self.next_priority_op_id
     }
@@ -98,6 +107,51 @@ impl ETHState {
         self.last_ethereum_block = self.last_ethereum_block_backup;
     }
 
+    /// Creates the state without the events of the blocks reverted by the reorg.
+    /// Both the last known block and its backup are set to `last_ethereum_block`,
+    /// so the events of the canonical chain are received again with the next update.
+    pub fn revert_blocks(&self, first_reverted_block: u64, last_ethereum_block: u64) -> Self {
+        let priority_queue: HashMap<_, _> = self
+            .priority_queue
+            .iter()
+            .filter(|(_, op)| op.as_ref().eth_block < first_reverted_block)
+            .map(|(serial_id, op)| (*serial_id, op.clone()))
+            .collect();
+        // Reverted operations are expected again, even if none of the older ones is left in the queue.
+        let next_priority_op_id = self
+            .priority_queue
+            .iter()
+            .filter(|(serial_id, _)| !priority_queue.contains_key(serial_id))
+            .map(|(serial_id, _)| *serial_id)
+            .min()
+            .unwrap_or(self.next_priority_op_id);
+
+        Self {
+            last_ethereum_block,
+            last_ethereum_block_backup: last_ethereum_block,
+            next_priority_op_id,
+            unconfirmed_queue: Vec::new(),
+            priority_queue,
+            new_tokens: self
+                .new_tokens
+                .iter()
+                .filter(|token| token.eth_block_number < first_reverted_block)
+                .cloned()
+                .collect(),
+            register_nft_factory_events: self
+                .register_nft_factory_events
+                .iter()
+                .filter(|event| event.eth_block < first_reverted_block)
+                .cloned()
+                .collect(),
+            accepted_block_hashes: self
+                .accepted_block_hashes
+                .range(..first_reverted_block)
+                .map(|(block, hash)| (*block, *hash))
+                .collect(),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn last_ethereum_block_backup(&self) -> u64 {
         self.last_ethereum_block_backup
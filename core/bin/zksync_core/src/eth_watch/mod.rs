@@ -4,12 +4,17 @@
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//!
+//! Hashes of the recent blocks containing the accepted events are re-checked on every poll, so reorgs
+//! deeper than the number of confirmations are detected as well. Reverted priority operations are removed
+//! from the mempool, and if some of them were already executed, the state keeper is halted.
 
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 // External uses
+use anyhow::format_err;
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt, StreamExt,
@@ -21,14 +26,16 @@ use itertools::Itertools;
 use tokio::{task::JoinHandle, time};
 use web3::types::BlockNumber;
 
-use zksync_config::{ContractsConfig, ETHWatchConfig};
+use zksync_config::{ContractsConfig, ETHWatchConfig, NotifierConfig};
 use zksync_crypto::params::PRIORITY_EXPIRATION;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_notifier::{Notifier, NotifyEvent};
+use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 
 // Local deps
 use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
+use crate::state_keeper::StateKeeperHalt;
 
 mod client;
 mod eth_state;
@@ -83,6 +90,12 @@ pub struct EthWatch<W: EthClient> {
     eth_state: ETHState,
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
     number_of_confirmations_for_event: u64,
+    /// Number of the latest blocks whose hashes are re-checked to detect deeper reorgs.
+    reorg_check_depth: u64,
+    /// Halts the state keeper once the reorg reverted already executed priority operations.
+    state_keeper_halt: StateKeeperHalt,
+    /// Notifier for the detected reorgs.
+    notifier: Notifier,
     mode: WatcherMode,
 }
 
@@ -91,6 +104,9 @@ impl<W: EthClient> EthWatch<W> {
         client: W,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
         number_of_confirmations_for_event: u64,
+        reorg_check_depth: u64,
+        state_keeper_halt: StateKeeperHalt,
+        notifier: Notifier,
     ) -> Self {
         Self {
             client,
@@ -98,6 +114,9 @@ impl<W: EthClient> EthWatch<W> {
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
             number_of_confirmations_for_event,
+            reorg_check_depth,
+            state_keeper_halt,
+            notifier,
         }
     }
 
@@ -191,6 +210,17 @@ impl<W: EthClient> EthWatch<W> {
         register_nft_factory_events.sort_by_key(|factory_event| factory_event.creator_address);
         register_nft_factory_events.dedup_by_key(|factory_event| factory_event.creator_address);
 
+        // Hashes of the blocks that left the checked range are not needed anymore.
+        let oldest_checked_block = last_ethereum_block.saturating_sub(self.reorg_check_depth);
+        let accepted_block_hashes = self
+            .eth_state
+            .accepted_block_hashes()
+            .iter()
+            .chain(updated_state.accepted_block_hashes())
+            .filter(|(block, _)| **block > oldest_checked_block)
+            .map(|(block, hash)| (*block, *hash))
+            .collect();
+
         let new_state = ETHState::new(
             last_ethereum_block,
             previous_ethereum_block,
@@ -198,6 +228,7 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            accepted_block_hashes,
         );
         self.set_new_state(new_state);
         Ok(())
@@ -254,6 +285,20 @@ impl<W: EthClient> EthWatch<W> {
             )
             .await?;
 
+        let accepted_blocks = priority_queue
+            .iter()
+            .map(|op| op.eth_block)
+            .chain(new_tokens.iter().map(|token| token.eth_block_number))
+            .chain(
+                new_register_nft_factory_events
+                    .iter()
+                    .map(|event| event.eth_block),
+            )
+            .collect();
+        let accepted_block_hashes = self
+            .get_block_hashes(current_ethereum_block, accepted_blocks)
+            .await?;
+
         let mut new_priority_op_ids: Vec<_> = priority_queue_map.keys().cloned().collect();
         new_priority_op_ids.sort_unstable();
         vlog::debug!(
@@ -295,10 +340,134 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue_map,
             new_tokens,
             new_register_nft_factory_events,
+            accepted_block_hashes,
         );
         Ok(state)
     }
 
+    /// Loads the hashes of the given blocks that are within the range re-checked for reorgs.
+    async fn get_block_hashes(
+        &self,
+        current_ethereum_block: u64,
+        blocks: HashSet<u64>,
+    ) -> anyhow::Result<BTreeMap<u64, H256>> {
+        let oldest_checked_block = current_ethereum_block.saturating_sub(self.reorg_check_depth);
+
+        let mut block_hashes = BTreeMap::new();
+        for block in blocks {
+            if block <= oldest_checked_block {
+                continue;
+            }
+            let hash =
+                self.client.get_block_hash(block).await?.ok_or_else(|| {
+                    format_err!("Block {} with accepted events is not found", block)
+                })?;
+            block_hashes.insert(block, hash);
+        }
+        Ok(block_hashes)
+    }
+
+    /// Re-checks the hashes of the blocks containing the accepted events.
+    /// Returns the first block that is not a part of the canonical chain anymore.
+    async fn find_reverted_block(&self) -> anyhow::Result<Option<u64>> {
+        for (block, hash) in self.eth_state.accepted_block_hashes() {
+            let canonical_hash = self.client.get_block_hash(*block).await?;
+            if canonical_hash != Some(*hash) {
+                return Ok(Some(*block));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Rolls the state back to the block preceding the reverted one, so the events of the canonical
+    /// chain are accepted again with the next update.
+    ///
+    /// Priority operations that are not re-included into the canonical chain are removed from the mempool.
+    /// If some of them were already executed, the state keeper is halted until the operator resolves
+    /// the situation.
+    async fn process_reorg(&mut self, first_reverted_block: u64) -> anyhow::Result<()> {
+        metrics::increment_counter!("eth_watcher.reorg_detected");
+
+        let last_ethereum_block =
+            (first_reverted_block + self.number_of_confirmations_for_event).saturating_sub(1);
+        let new_state = self
+            .eth_state
+            .revert_blocks(first_reverted_block, last_ethereum_block);
+
+        // Operations with the same serial id and Ethereum transaction in the canonical chain
+        // are not affected by the reorg.
+        let canonical_ops: HashSet<_> = self
+            .client
+            .get_priority_op_events(
+                BlockNumber::Number(first_reverted_block.into()),
+                BlockNumber::Latest,
+            )
+            .await?
+            .into_iter()
+            .map(|op| (op.serial_id, op.eth_hash))
+            .collect();
+        let mut reverted_ops: Vec<_> = self
+            .eth_state
+            .priority_queue()
+            .values()
+            .map(|op| op.as_ref())
+            .filter(|op| !new_state.priority_queue().contains_key(&op.serial_id))
+            .chain(self.eth_state.unconfirmed_queue())
+            .filter(|op| !canonical_ops.contains(&(op.serial_id, op.eth_hash)))
+            .map(|op| op.serial_id)
+            .collect();
+        reverted_ops.sort_unstable();
+        reverted_ops.dedup();
+
+        let executed_ops = if reverted_ops.is_empty() {
+            Vec::new()
+        } else {
+            let (sender, receiver) = oneshot::channel();
+            self.mempool_tx_sender
+                .send(MempoolTransactionRequest::InvalidatePriorityOps(
+                    reverted_ops.clone(),
+                    sender,
+                ))
+                .await?;
+            receiver.await.expect("Mempool actor was dropped")?
+        };
+
+        let mut details = format!(
+            "priority operations {:?}, {} new token events and {} NFT factory events are reverted",
+            reverted_ops,
+            self.eth_state.new_tokens().len() - new_state.new_tokens().len(),
+            self.eth_state.new_register_nft_factory_events().len()
+                - new_state.new_register_nft_factory_events().len(),
+        );
+        self.set_new_state(new_state);
+
+        if !executed_ops.is_empty() {
+            self.state_keeper_halt.halt();
+            details = format!(
+                "{}. Operations {:?} are already executed, state keeper is halted",
+                details, executed_ops
+            );
+            metrics::increment_counter!("eth_watcher.reorg_reverted_executed_ops");
+        }
+
+        vlog::error!(
+            "Ethereum reorg detected starting from block {}: {}",
+            first_reverted_block,
+            details
+        );
+        if let Err(err) = self
+            .notifier
+            .notify(NotifyEvent::L1Reorg {
+                first_reverted_block,
+                details,
+            })
+            .await
+        {
+            vlog::warn!("Failed to send the reorg notification: {}", err);
+        }
+        Ok(())
+    }
+
     fn get_register_factory_event(
         &self,
         last_block_number: Option<u64>,
@@ -334,6 +503,10 @@ impl<W: EthClient> EthWatch<W> {
         let last_block_number = self.client.block_number().await?;
 
         if last_block_number > self.eth_state.last_ethereum_block() {
+            // Hashes are re-checked only when new blocks appear, which is enough to notice a reorg.
+            if let Some(first_reverted_block) = self.find_reverted_block().await? {
+                self.process_reorg(first_reverted_block).await?;
+            }
             self.process_new_blocks(last_block_number).await?;
         }
 
@@ -455,7 +628,9 @@ pub async fn start_eth_watch(
     eth_gateway: EthereumGateway,
     contract_config: &ContractsConfig,
     eth_watcher_config: &ETHWatchConfig,
+    notifier_config: &NotifierConfig,
     mempool_req_sender: mpsc::Sender<MempoolTransactionRequest>,
+    state_keeper_halt: StateKeeperHalt,
 ) -> JoinHandle<()> {
     let eth_client = EthHttpClient::new(
         eth_gateway,
@@ -463,10 +638,15 @@ pub async fn start_eth_watch(
         contract_config.governance_addr,
     );
 
+    let notifier =
+        Notifier::from_config(notifier_config).with_mattermost_url(&eth_watcher_config.webhook_url);
     let mut eth_watch = EthWatch::new(
         eth_client,
         mempool_req_sender,
         eth_watcher_config.confirmations_for_eth_event,
+        eth_watcher_config.reorg_check_depth,
+        state_keeper_halt,
+        notifier,
     );
 
     eth_watch.restore_from_eth_using_latest_block_number().await;
@@ -1,5 +1,5 @@
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use web3::types::{Address, BlockNumber};
@@ -13,13 +13,17 @@ use futures::channel::mpsc;
 use futures::StreamExt;
 use tokio::sync::RwLock;
 use zksync_mempool::MempoolTransactionRequest;
+use zksync_notifier::Notifier;
 
 use super::is_missing_priority_op_error;
 use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::state_keeper::StateKeeperHalt;
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
     last_block_number: u64,
+    /// Number of reorgs that replaced the block, used to calculate the block hash.
+    block_versions: HashMap<u64, u8>,
 }
 
 impl FakeEthClientData {
@@ -27,9 +31,30 @@ impl FakeEthClientData {
         Self {
             priority_ops: Default::default(),
             last_block_number: 0,
+            block_versions: Default::default(),
         }
     }
 
+    fn block_hash(&self, block_number: u64) -> H256 {
+        let mut hash = H256::from_low_u64_be(block_number);
+        hash.0[0] = self
+            .block_versions
+            .get(&block_number)
+            .cloned()
+            .unwrap_or_default();
+        hash
+    }
+
+    /// Replaces the blocks starting from `first_reverted_block` with the blocks containing `ops`.
+    fn reorg(&mut self, first_reverted_block: u64, ops: &[PriorityOp]) {
+        self.priority_ops
+            .retain(|block, _| *block < first_reverted_block);
+        for block in first_reverted_block..=self.last_block_number {
+            *self.block_versions.entry(block).or_default() += 1;
+        }
+        self.add_operations(ops);
+    }
+
     fn add_operations(&mut self, ops: &[PriorityOp]) {
         for op in ops {
             self.last_block_number = max(op.eth_block, self.last_block_number);
@@ -70,6 +95,10 @@ impl FakeEthClient {
         let mut inner = self.inner.write().await;
         inner.last_block_number = block_number;
     }
+
+    async fn reorg(&mut self, first_reverted_block: u64, ops: &[PriorityOp]) {
+        self.inner.write().await.reorg(first_reverted_block, ops);
+    }
 }
 
 #[async_trait::async_trait]
@@ -111,6 +140,14 @@ impl EthClient for FakeEthClient {
         Ok(self.inner.read().await.last_block_number)
     }
 
+    async fn get_block_hash(&self, block_number: u64) -> Result<Option<H256>, anyhow::Error> {
+        let inner = self.inner.read().await;
+        if block_number > inner.last_block_number {
+            return Ok(None);
+        }
+        Ok(Some(inner.block_hash(block_number)))
+    }
+
     async fn get_auth_fact(
         &self,
         _address: Address,
@@ -132,12 +169,28 @@ fn create_watcher<T: EthClient>(
     client: T,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
 ) -> EthWatch<T> {
-    EthWatch::new(client, mempool_tx_sender, 1)
+    EthWatch::new(
+        client,
+        mempool_tx_sender,
+        1,
+        10,
+        StateKeeperHalt::default(),
+        Notifier::new(),
+    )
 }
 
 async fn fake_mempool(
+    receiver: mpsc::Receiver<MempoolTransactionRequest>,
+    data: Arc<RwLock<HashMap<SerialId, (PriorityOp, bool)>>>,
+) {
+    fake_mempool_with_executed_ops(receiver, data, HashSet::new()).await
+}
+
+/// Fake mempool which treats the priority operations with the given ids as already executed.
+async fn fake_mempool_with_executed_ops(
     mut receiver: mpsc::Receiver<MempoolTransactionRequest>,
     data: Arc<RwLock<HashMap<SerialId, (PriorityOp, bool)>>>,
+    executed_ops: HashSet<SerialId>,
 ) {
     while let Some(a) = receiver.next().await {
         match a {
@@ -151,6 +204,18 @@ async fn fake_mempool(
                 }
                 channel.send(Ok(())).unwrap_or_default()
             }
+            MempoolTransactionRequest::InvalidatePriorityOps(ops, channel) => {
+                let mut lock = data.write().await;
+                let mut executed = Vec::new();
+                for serial_id in ops {
+                    if executed_ops.contains(&serial_id) {
+                        executed.push(serial_id);
+                    } else {
+                        lock.remove(&serial_id);
+                    }
+                }
+                channel.send(Ok(executed)).unwrap_or_default()
+            }
             MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
         }
     }
@@ -546,3 +611,106 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+fn deposit_op(serial_id: SerialId, eth_hash: H256, eth_block: u64) -> PriorityOp {
+    PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: [2u8; 20].into(),
+        }),
+        deadline_block: 0,
+        eth_hash,
+        eth_block,
+        eth_block_index: Some(1),
+    }
+}
+
+/// Checks that the accepted priority operations reverted by a reorg deeper than the number
+/// of confirmations are replaced with the operations from the canonical chain.
+#[tokio::test]
+async fn test_reorg_reverts_unprocessed_ops() {
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    let mut client = FakeEthClient::new();
+    client
+        .add_operations(&[
+            deposit_op(0, [1; 32].into(), 1),
+            deposit_op(1, [2; 32].into(), 2),
+        ])
+        .await;
+    client.set_last_block_number(4).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    let halt = watcher.state_keeper_halt.clone();
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.priority_queue().len(), 2);
+    assert_eq!(watcher.eth_state.accepted_block_hashes().len(), 2);
+
+    // The second operation is moved to another Ethereum transaction by the reorg.
+    client.reorg(2, &[deposit_op(1, [3; 32].into(), 3)]).await;
+    client.set_last_block_number(5).await;
+    watcher.poll_eth_node().await.unwrap();
+
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 5);
+    assert_eq!(watcher.eth_state.next_priority_op_id(), 2);
+    let priority_queue = watcher.eth_state.priority_queue();
+    assert_eq!(priority_queue.len(), 2);
+    assert_eq!(priority_queue[&1].as_ref().eth_hash, H256::from([3; 32]));
+    assert_eq!(
+        watcher
+            .eth_state
+            .accepted_block_hashes()
+            .keys()
+            .collect::<Vec<_>>(),
+        vec![&1, &3]
+    );
+
+    let reader = data.read().await;
+    let (op, confirmed) = reader.get(&1).unwrap();
+    assert_eq!(op.eth_hash, H256::from([3; 32]));
+    assert!(confirmed);
+    assert!(!halt.is_halted());
+}
+
+/// Checks that the state keeper is halted if a reorg reverts an already executed operation,
+/// while operations re-included into the canonical chain don't affect it.
+#[tokio::test]
+async fn test_reorg_of_executed_ops() {
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    let executed_ops = vec![0].into_iter().collect();
+    tokio::spawn(fake_mempool_with_executed_ops(
+        receiver,
+        data.clone(),
+        executed_ops,
+    ));
+    let mut client = FakeEthClient::new();
+    client
+        .add_operations(&[deposit_op(0, [1; 32].into(), 1)])
+        .await;
+    client.set_last_block_number(3).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    let halt = watcher.state_keeper_halt.clone();
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.priority_queue().len(), 1);
+
+    // The same transaction is included into another block.
+    client.reorg(1, &[deposit_op(0, [1; 32].into(), 2)]).await;
+    client.set_last_block_number(4).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert!(!halt.is_halted());
+    assert_eq!(watcher.eth_state.priority_queue()[&0].as_ref().eth_block, 2);
+
+    // The executed operation is not a part of the canonical chain anymore.
+    client.reorg(2, &[]).await;
+    client.set_last_block_number(5).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert!(halt.is_halted());
+    assert!(watcher.eth_state.priority_queue().is_empty());
+    assert_eq!(watcher.eth_state.next_priority_op_id(), 0);
+}
@@ -3,7 +3,9 @@ use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
     committer::run_committer,
     eth_watch::start_eth_watch,
-    state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
+    state_keeper::{
+        start_root_hash_calculator, start_state_keeper, StateKeeperHalt, ZkSyncStateKeeper,
+    },
     token_handler::run_token_handler,
 };
 use futures::{channel::mpsc, future};
@@ -11,6 +13,7 @@ use tokio::task::JoinHandle;
 use zksync_config::{ChainConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_mempool::{run_mempool_block_handler, run_mempool_tx_handler};
+use zksync_notifier::Notifier;
 use zksync_storage::ConnectionPool;
 use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
 
@@ -103,11 +106,15 @@ pub async fn run_core(
 
     let (processed_tx_events_sender, processed_tx_events_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+    // Ethereum watcher halts the state keeper if a reorg reverts already executed priority operations.
+    let state_keeper_halt = StateKeeperHalt::default();
 
     let mempool_tx_handler_task = run_mempool_tx_handler(
         connection_pool.clone(),
         mempool_tx_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        &config.chain.mempool,
+        &config.notifier,
     );
 
     // Run health check api for core
@@ -115,6 +122,7 @@ pub async fn run_core(
         connection_pool.clone(),
         read_only_connection_pool,
         eth_gateway.clone(),
+        state_keeper_halt.clone(),
         config.api.private.clone(),
     );
 
@@ -125,7 +133,9 @@ pub async fn run_core(
         eth_gateway.clone(),
         &config.contracts,
         &config.eth_watch,
+        &config.notifier,
         mempool_tx_request_sender.clone(),
+        state_keeper_halt.clone(),
     )
     .await;
 
@@ -149,6 +159,7 @@ pub async fn run_core(
         config.chain.state_keeper.miniblock_iterations as usize,
         config.chain.state_keeper.fast_block_miniblock_iterations as usize,
         processed_tx_events_sender,
+        state_keeper_halt,
     );
 
     // Execute reverted blocks before start
@@ -157,6 +168,8 @@ pub async fn run_core(
     let state_keeper_task = start_state_keeper(
         state_keeper,
         config.chain.state_keeper.miniblock_iteration_interval(),
+        Notifier::from_config(&config.notifier),
+        config.notifier.thresholds.root_hash_throttle(),
     );
     let root_hash_calculator_task = start_root_hash_calculator(root_hash_calculator);
 
@@ -172,13 +185,16 @@ pub async fn run_core(
         connection_pool.clone(),
         mempool_block_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        &config.chain.mempool,
     );
 
     // Start token handler.
     let token_handler_task = run_token_handler(
         connection_pool.clone(),
         eth_gateway.clone(),
+        config.contracts.contract_addr,
         &config.token_handler,
+        &config.notifier,
         eth_watch_req_sender.clone(),
     );
 
@@ -14,13 +14,15 @@ use actix_web::{web, App, HttpResponse, HttpServer};
 use futures::{channel::mpsc, StreamExt};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{CoreStatus, GatewayStatus};
 
 use zksync_config::configs::api::PrivateApiConfig;
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+use crate::state_keeper::StateKeeperHalt;
+
 const STATUS_INVALIDATION_PERIOD: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
@@ -28,11 +30,13 @@ struct AppState {
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    state_keeper_halt: StateKeeperHalt,
     status_cache: RwLock<Option<(CoreStatus, Instant)>>,
 }
 
 /// Health check.
-/// The core actor is expected have connection to web3 and both main/replica databases
+/// The core actor is expected have connection to web3 and both main/replica databases.
+/// Paused state of the Ethereum sender and halted state of the state keeper are reported as well.
 #[actix_web::get("/status")]
 async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
     if let Some((status, data)) = data.status_cache.read().await.as_ref() {
@@ -45,7 +49,18 @@ async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
     // with multiple requests from other API nodes when the cache has been invalidated.
 
     let mut status = data.status_cache.write().await;
-    let main_database_status = data.connection_pool.access_storage().await.is_ok();
+    let (main_database_status, eth_sender_paused) =
+        match data.connection_pool.access_storage().await {
+            Ok(mut storage) => {
+                let eth_sender_paused = storage
+                    .ethereum_schema()
+                    .is_eth_sender_paused()
+                    .await
+                    .unwrap_or_default();
+                (true, eth_sender_paused)
+            }
+            Err(_) => (false, false),
+        };
     let replica_database_status = data
         .read_only_connection_pool
         .access_storage()
@@ -57,16 +72,41 @@ async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
         main_database_available: main_database_status,
         replica_database_available: replica_database_status,
         web3_available: eth_status,
+        eth_sender_paused,
+        state_keeper_halted: data.state_keeper_halt.is_halted(),
     };
     *status = Some((response.clone(), Instant::now()));
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Health state of the Ethereum gateways.
+/// The list is empty if the core uses a single gateway.
+#[actix_web::get("/gateways")]
+async fn gateways(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let response: Vec<_> = data
+        .eth_client
+        .gateways_health()
+        .into_iter()
+        .map(|report| GatewayStatus {
+            name: report.name,
+            score: report.score,
+            latency_ms: report.latency.as_millis() as u64,
+            error_rate: report.error_rate,
+            requests: report.requests,
+            disagreements: report.disagreements,
+            evicted: report.evicted,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
 pub fn start_private_core_api(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    state_keeper_halt: StateKeeperHalt,
     config: PrivateApiConfig,
 ) -> JoinHandle<()> {
     let (panic_sender, mut panic_receiver) = mpsc::channel(1);
@@ -84,6 +124,7 @@ pub fn start_private_core_api(
                         connection_pool: connection_pool.clone(),
                         read_only_connection_pool: read_only_connection_pool.clone(),
                         eth_client: eth_client.clone(),
+                        state_keeper_halt: state_keeper_halt.clone(),
                         status_cache: Default::default(),
                     };
 
@@ -94,6 +135,7 @@ pub fn start_private_core_api(
                         .app_data(web::Data::new(app_state))
                         .app_data(web::JsonConfig::default().limit(2usize.pow(32)))
                         .service(status)
+                        .service(gateways)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
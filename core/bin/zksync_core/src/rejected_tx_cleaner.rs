@@ -1,5 +1,6 @@
 //! The cleaner is responsible for removing rejected transactions from the database
 //! that were stored 2 or more weeks ago (this value is configurable as well as the actor's sleep time).
+//! Records about the transactions replaced in or evicted from the mempool are removed the same way.
 //!
 //! The purpose is not to store the information about the failed transaction execution
 //! which is useful only for a short period of time. Since such transactions are not actually
@@ -33,6 +34,22 @@ pub fn run_rejected_tx_cleaner(config: &DBConfig, db_pool: ConnectionPool) -> Jo
             {
                 vlog::error!("Can't delete rejected transactions {:?}", e);
             }
+            if let Err(e) = storage
+                .chain()
+                .mempool_schema()
+                .remove_replaced_txs(max_age)
+                .await
+            {
+                vlog::error!("Can't delete replaced transactions {:?}", e);
+            }
+            if let Err(e) = storage
+                .chain()
+                .mempool_schema()
+                .remove_evicted_txs(max_age)
+                .await
+            {
+                vlog::error!("Can't delete evicted transactions {:?}", e);
+            }
             timer.tick().await;
         }
     })
@@ -11,6 +11,7 @@ use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
 use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
+use zksync_notifier::{Notifier, NotifyEvent};
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::{
     block::{
@@ -37,7 +38,7 @@ use crate::{
 pub use self::{
     init_params::ZkSyncStateInitParams,
     root_hash_calculator::{start_root_hash_calculator, BlockRootHashJobQueue},
-    types::StateKeeperTestkitRequest,
+    types::{StateKeeperHalt, StateKeeperTestkitRequest},
 };
 
 mod init_params;
@@ -70,6 +71,9 @@ pub struct ZkSyncStateKeeper {
     /// Queue of reverted blocks
     /// They will be executed before the start of the StateKeeper
     reverted_blocks: VecDeque<IncompleteBlock>,
+
+    /// Flag set by other components to stop the block production.
+    halt: StateKeeperHalt,
 }
 
 impl ZkSyncStateKeeper {
@@ -86,6 +90,7 @@ impl ZkSyncStateKeeper {
         max_miniblock_iterations: usize,
         fast_miniblock_iterations: usize,
         processed_tx_events_sender: mpsc::Sender<ProcessedOperations>,
+        halt: StateKeeperHalt,
     ) -> (Self, RootHashCalculator) {
         // We need two copies of state:
         // 1. For state keeper itself (`sk_state`). We will apply all the updates from incomplete blocks on it in order
@@ -158,6 +163,7 @@ impl ZkSyncStateKeeper {
 
             root_hash_queue,
             reverted_blocks: initial_state.reverted_blocks.clone(),
+            halt,
         };
         keeper.initialize(initial_state.pending_block);
 
@@ -275,7 +281,12 @@ impl ZkSyncStateKeeper {
     }
 
     // Generate and execute new miniblock every miniblock_interval
-    async fn run(mut self, miniblock_interval: Duration) {
+    async fn run(
+        mut self,
+        miniblock_interval: Duration,
+        notifier: Notifier,
+        throttle_notify_threshold: Duration,
+    ) {
         let mut timer = time::interval(miniblock_interval);
         loop {
             let start = Instant::now();
@@ -285,11 +296,36 @@ impl ZkSyncStateKeeper {
             // spend more time in the loop iteration than this interval.
             metrics::histogram!("state_keeper.miniblock_interval", start.elapsed());
 
+            if self.halt.is_halted() {
+                // Nothing is executed until the restart, the reason is reported by the component
+                // that halted the state keeper.
+                metrics::increment_counter!("state_keeper.halted_iterations");
+                continue;
+            }
+
             let start = Instant::now();
             // `.throttle()` method will postpone the next miniblock iteration if currently we have too
             // many blocks for which root hash is not yet calculated.
             self.root_hash_queue.throttle().await;
-            metrics::histogram!("state_keeper.throttle", start.elapsed());
+            let throttle_duration = start.elapsed();
+            metrics::histogram!("state_keeper.throttle", throttle_duration);
+            if throttle_duration >= throttle_notify_threshold {
+                vlog::warn!(
+                    "State keeper was throttled by the root hash calculator for {:?}",
+                    throttle_duration
+                );
+                // Notification is sent in the background so that it doesn't delay the block production.
+                let notifier = notifier.clone();
+                let event = NotifyEvent::RootHashCalculatorThrottled {
+                    duration: throttle_duration,
+                    queue_size: self.root_hash_queue.size(),
+                };
+                tokio::spawn(async move {
+                    if let Err(err) = notifier.notify(event).await {
+                        vlog::warn!("Failed to send the throttling notification: {}", err);
+                    }
+                });
+            }
 
             let block_timestamp = self.pending_block.timestamp;
             let proposed_block = self.propose_new_block(block_timestamp).await;
@@ -888,7 +924,14 @@ impl ZkSyncStateKeeper {
     }
 }
 
+/// Starts the state keeper. Throttling by the root hash calculator longer than `throttle_notify_threshold`
+/// is reported via the notifier.
 #[must_use]
-pub fn start_state_keeper(sk: ZkSyncStateKeeper, miniblock_interval: Duration) -> JoinHandle<()> {
-    tokio::spawn(sk.run(miniblock_interval))
+pub fn start_state_keeper(
+    sk: ZkSyncStateKeeper,
+    miniblock_interval: Duration,
+    notifier: Notifier,
+    throttle_notify_threshold: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(sk.run(miniblock_interval, notifier, throttle_notify_threshold))
 }
@@ -38,5 +38,6 @@ fn test_create_incorrect_state_keeper() {
         MAX_ITERATIONS,
         FAST_ITERATIONS,
         events_sender,
+        Default::default(),
     );
 }
@@ -44,6 +44,7 @@ impl StateKeeperTester {
             max_iterations,
             fast_iterations,
             events_sender,
+            Default::default(),
         );
 
         Self {
@@ -1,3 +1,8 @@
+// Built-in uses
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 // External uses
 use futures::channel::oneshot;
 use itertools::Itertools;
@@ -17,6 +22,22 @@ pub enum StateKeeperTestkitRequest {
     GetCurrentState(oneshot::Sender<ZkSyncStateInitParams>),
 }
 
+/// Shared flag used to halt the block production, e.g. once the Ethereum reorg reverted
+/// priority operations that were already executed. The halted state keeper doesn't execute
+/// transactions until the restart, so the operator can resolve the situation.
+#[derive(Debug, Clone, Default)]
+pub struct StateKeeperHalt(Arc<AtomicBool>);
+
+impl StateKeeperHalt {
+    pub fn halt(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 #[derive(Debug)]
 pub enum ApplyOutcome<T> {
     Included(T),
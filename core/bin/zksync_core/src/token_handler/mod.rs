@@ -3,7 +3,11 @@
 //!
 //! To set the name and the decimals parameter for the token, a match is searched for with the
 //! token list (which is taken from the environment). If the token address is not found in the
-//! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//! trusted token list, then the metadata reported by the token contract is used. Malformed
+//! metadata is replaced with the default values (name = "ERC20-{id}", decimals = 18).
+//!
+//! Every new token is validated (see the [`validation`] module) and stored with the resulting
+//! [`TokenStatus`]. Tokens that are not `Valid` can't be used to pay fees.
 
 // Built-in deps
 use std::collections::HashMap;
@@ -14,27 +18,33 @@ use futures::{
 };
 use tokio::task::JoinHandle;
 // Workspace uses
-use zksync_config::TokenHandlerConfig;
-use zksync_notifier::Notifier;
+use zksync_config::{NotifierConfig, TokenHandlerConfig};
+use zksync_notifier::{Notifier, NotifyEvent};
 use zksync_storage::{tokens::StoreTokenError, ConnectionPool, StorageProcessor};
 use zksync_types::{
     tokens::{NewTokenEvent, Token, TokenInfo},
-    Address, TokenId, TokenKind, U256,
+    Address, TokenId, TokenStatus,
 };
 // Local uses
+use self::validation::{ContractProbe, TokenValidation};
 use crate::eth_watch::EthWatchRequest;
-use web3::contract::Options;
-use zksync_contracts::erc20_contract;
 use zksync_eth_client::EthereumGateway;
 
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
+
 struct TokenHandler {
     connection_pool: ConnectionPool,
     poll_interval: std::time::Duration,
     eth_watcher_req: mpsc::Sender<EthWatchRequest>,
     eth_client: EthereumGateway,
+    /// Address of the zkSync contract holding the deposited tokens.
+    zksync_contract: Address,
     token_list: HashMap<Address, TokenInfo>,
     last_eth_block: Option<u64>,
-    notifier: Option<Notifier>,
+    notifier: Notifier,
 }
 
 impl TokenHandler {
@@ -42,7 +52,9 @@ impl TokenHandler {
         connection_pool: ConnectionPool,
         eth_watcher_req: mpsc::Sender<EthWatchRequest>,
         eth_client: EthereumGateway,
+        zksync_contract: Address,
         config: TokenHandlerConfig,
+        notifier: Notifier,
     ) -> Self {
         let poll_interval = config.poll_interval();
         let token_list = config
@@ -51,12 +63,12 @@ impl TokenHandler {
             .map(|token| (token.address, token))
             .collect::<HashMap<Address, TokenInfo>>();
 
-        let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
-        let notifier = webhook_url.map(Notifier::with_mattermost);
+        let notifier = notifier.with_mattermost_url(&config.webhook_url);
 
         Self {
             connection_pool,
             eth_client,
+            zksync_contract,
             token_list,
             poll_interval,
             notifier,
@@ -79,26 +91,32 @@ impl TokenHandler {
         receiver.await.expect("Err response from eth watch")
     }
 
-    async fn is_contract_erc20(&self, address: Address) -> bool {
-        self.eth_client
-            .call_contract_function::<U256, _, _, _>(
-                "balanceOf",
-                address,
-                None,
-                Options::default(),
-                None,
-                address,
-                erc20_contract(),
-            )
+    async fn validate_token(&self, address: Address) -> TokenValidation {
+        let mut validation = ContractProbe::fetch(&self.eth_client, address, self.zksync_contract)
             .await
-            .is_ok()
+            .validate();
+
+        // Symbols of the trusted tokens can't be taken by the other tokens.
+        if let Some(symbol) = &validation.symbol {
+            let is_taken = self
+                .token_list
+                .values()
+                .any(|token| token.address != address && token.symbol.eq_ignore_ascii_case(symbol));
+            if is_taken {
+                validation
+                    .metadata_issues
+                    .push(format!("symbol {} belongs to the trusted token", symbol));
+                validation.symbol = None;
+            }
+        }
+        validation
     }
 
     async fn save_new_tokens(
         &self,
         storage: &mut StorageProcessor<'_>,
         tokens: Vec<NewTokenEvent>,
-    ) -> anyhow::Result<Vec<Token>> {
+    ) -> anyhow::Result<Vec<(Token, String)>> {
         let mut transaction = storage.start_transaction().await?;
         let mut token_schema = transaction.tokens_schema();
 
@@ -110,73 +128,55 @@ impl TokenHandler {
                 continue;
             }
 
-            // Find a token in the list of trusted tokens
-            // or use default values (name = "ERC20-{id}", decimals = 18).
+            let validation = self.validate_token(token_event.address).await;
+
+            // Find a token in the list of trusted tokens or use the metadata of the contract.
+            // Default values are used instead of the malformed metadata
+            // (name = "ERC20-{id}", decimals = 18).
             let default_symbol = format!("ERC20-{}", token_event.id);
             let default_decimals = 18;
 
-            let is_erc20 = self.is_contract_erc20(token_event.address).await;
-            let token_kind = if is_erc20 {
-                TokenKind::ERC20
-            } else {
-                TokenKind::None
-            };
-
-            let token_from_list = {
-                let token_info = self.token_list.get(&token_event.address).cloned();
-
-                if let Some(token_info) = token_info {
-                    Some(Token::new(
-                        token_event.id,
-                        token_info.address,
-                        &token_info.symbol,
-                        token_info.decimals,
-                        token_kind,
-                    ))
-                } else {
-                    None
-                }
+            let token_info = self.token_list.get(&token_event.address);
+            let status = validation.status(token_info.is_some());
+            let (symbol, decimals) = match token_info {
+                Some(token_info) => (token_info.symbol.clone(), token_info.decimals),
+                None => (
+                    validation
+                        .symbol
+                        .clone()
+                        .unwrap_or_else(|| default_symbol.clone()),
+                    validation.decimals.unwrap_or(default_decimals),
+                ),
             };
+            if status != TokenStatus::Valid {
+                vlog::warn!(
+                    "Token {} ({:#x}) is {:?}: {}",
+                    token_event.id,
+                    token_event.address,
+                    status,
+                    validation.issues()
+                );
+            }
 
-            let token = match token_from_list {
-                Some(token_from_list) => {
-                    let try_insert_token = token_schema.store_token(token_from_list.clone()).await;
+            let token = Token::new(
+                token_event.id,
+                token_event.address,
+                &symbol,
+                decimals,
+                validation.kind,
+            )
+            .with_status(status);
 
-                    match try_insert_token {
-                        Ok(..) => token_from_list,
-                        Err(StoreTokenError::TokenAlreadyExistsError(..)) => {
-                            // If a token with such parameters already exists in the database
-                            // then try insert token with other symbol.
-                            let token = Token::new(
-                                token_from_list.id,
-                                token_from_list.address,
-                                &default_symbol,
-                                token_from_list.decimals,
-                                token_kind,
-                            );
-                            let try_insert_token = token_schema.store_token(token.clone()).await;
-                            match try_insert_token {
-                                Ok(..) => (),
-                                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
-                                    vlog::warn!("failed to store token in database: {}", err)
-                                }
-                            }
-
-                            token
-                        }
-                        Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                    }
-                }
-                None => {
-                    // Token with default parameters.
-                    let token = Token::new(
-                        token_event.id,
-                        token_event.address,
-                        &default_symbol,
-                        default_decimals,
-                        token_kind,
-                    );
+            let try_insert_token = token_schema.store_token(token.clone()).await;
+            let token = match try_insert_token {
+                Ok(..) => token,
+                Err(StoreTokenError::TokenAlreadyExistsError(..)) if symbol != default_symbol => {
+                    // If a token with such parameters already exists in the database
+                    // then try insert token with other symbol.
+                    let token = Token {
+                        symbol: default_symbol,
+                        ..token
+                    };
                     let try_insert_token = token_schema.store_token(token.clone()).await;
                     match try_insert_token {
                         Ok(..) => (),
@@ -188,9 +188,14 @@ impl TokenHandler {
 
                     token
                 }
+                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                    vlog::warn!("failed to store token in database: {}", err);
+                    token
+                }
+                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
             };
 
-            new_tokens.push(token);
+            new_tokens.push((token, validation.issues()));
         }
 
         transaction.commit().await?;
@@ -223,15 +228,18 @@ impl TokenHandler {
                 .expect("failed to add tokens to the database");
 
             // Send a notification that the token has been successfully added to the database.
-            if let Some(notifier) = &self.notifier {
-                for token in new_tokens {
-                    notifier
-                        .send_new_token_notify(token)
-                        .await
-                        .unwrap_or_else(|e| {
-                            vlog::error!("Failed to send a token insertion notification: {}", e);
-                        });
-                }
+            for (token, issues) in new_tokens {
+                let event = if token.status.is_safe() {
+                    NotifyEvent::NewToken { token }
+                } else {
+                    NotifyEvent::SuspiciousToken {
+                        token,
+                        details: issues,
+                    }
+                };
+                self.notifier.notify(event).await.unwrap_or_else(|e| {
+                    vlog::error!("Failed to send a token insertion notification: {}", e);
+                });
             }
         }
     }
@@ -241,13 +249,22 @@ impl TokenHandler {
 pub fn run_token_handler(
     db_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    zksync_contract: Address,
     config: &TokenHandlerConfig,
+    notifier_config: &NotifierConfig,
     eth_watcher_req: mpsc::Sender<EthWatchRequest>,
 ) -> JoinHandle<()> {
     let config = config.clone();
+    let notifier = Notifier::from_config(notifier_config);
     tokio::spawn(async move {
-        let mut token_handler =
-            TokenHandler::new(db_pool, eth_watcher_req, eth_client, config.clone());
+        let mut token_handler = TokenHandler::new(
+            db_pool,
+            eth_watcher_req,
+            eth_client,
+            zksync_contract,
+            config.clone(),
+            notifier,
+        );
 
         token_handler.run().await
     })
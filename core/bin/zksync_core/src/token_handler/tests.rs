@@ -0,0 +1,200 @@
+use ethabi::Token as AbiToken;
+use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+use zksync_types::{Address, TokenKind, TokenStatus, H256, U256};
+
+use super::validation::*;
+
+fn erc20_probe() -> ContractProbe {
+    ContractProbe {
+        is_erc20: true,
+        symbol: Some("DAI".to_string()),
+        name: Some("Dai Stablecoin".to_string()),
+        decimals: Some(U256::from(18)),
+        transfer_fee_getter: None,
+        rebasing_getter: None,
+        transfer_simulation: TransferSimulation::Succeeded,
+    }
+}
+
+/// Checks that the regular ERC20 token passes the validation.
+#[test]
+fn valid_token() {
+    let validation = erc20_probe().validate();
+    assert_eq!(
+        validation,
+        TokenValidation {
+            kind: TokenKind::ERC20,
+            symbol: Some("DAI".to_string()),
+            decimals: Some(18),
+            metadata_issues: Vec::new(),
+            behavior_issues: Vec::new(),
+        }
+    );
+    assert_eq!(validation.status(false), TokenStatus::Valid);
+
+    // Name is optional.
+    let probe = ContractProbe {
+        name: None,
+        ..erc20_probe()
+    };
+    assert_eq!(probe.validate().status(false), TokenStatus::Valid);
+
+    // Transfer may return nothing.
+    let probe = ContractProbe {
+        transfer_simulation: TransferSimulation::NoReturnValue,
+        ..erc20_probe()
+    };
+    assert_eq!(probe.validate().status(false), TokenStatus::Valid);
+}
+
+/// Checks that the tokens with the malformed metadata are flagged unless they're trusted.
+#[test]
+fn malformed_metadata() {
+    let probes = vec![
+        ContractProbe {
+            symbol: None,
+            ..erc20_probe()
+        },
+        ContractProbe {
+            symbol: Some("".to_string()),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            symbol: Some("DAI STABLECOIN".to_string()),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            symbol: Some("ERC20-1".to_string()),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            name: Some("Dai\nStablecoin".to_string()),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            decimals: None,
+            ..erc20_probe()
+        },
+        ContractProbe {
+            decimals: Some(U256::from(255)),
+            ..erc20_probe()
+        },
+    ];
+
+    for probe in probes {
+        let validation = probe.clone().validate();
+        assert_eq!(validation.metadata_issues.len(), 1, "{:?}", probe);
+        assert!(validation.behavior_issues.is_empty());
+        assert_eq!(validation.status(false), TokenStatus::Flagged);
+        assert_eq!(validation.status(true), TokenStatus::Valid);
+    }
+
+    let validation = ContractProbe {
+        symbol: Some("DAI\u{0}".to_string()),
+        decimals: Some(U256::MAX),
+        ..erc20_probe()
+    }
+    .validate();
+    assert_eq!(validation.symbol, None);
+    assert_eq!(validation.decimals, None);
+    assert_eq!(validation.metadata_issues.len(), 2);
+}
+
+/// Checks that the tokens with the unsafe behavior are quarantined even if they're trusted.
+#[test]
+fn unsafe_behavior() {
+    let probes = vec![
+        ContractProbe {
+            is_erc20: false,
+            ..erc20_probe()
+        },
+        ContractProbe {
+            transfer_fee_getter: Some("basisPointsRate"),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            rebasing_getter: Some("sharesOf"),
+            ..erc20_probe()
+        },
+        ContractProbe {
+            transfer_simulation: TransferSimulation::ReturnedFalse,
+            ..erc20_probe()
+        },
+        ContractProbe {
+            transfer_simulation: TransferSimulation::Reverted,
+            ..erc20_probe()
+        },
+    ];
+
+    for probe in probes {
+        let validation = probe.clone().validate();
+        assert_eq!(validation.behavior_issues.len(), 1, "{:?}", probe);
+        assert_eq!(validation.status(false), TokenStatus::Quarantined);
+        assert_eq!(validation.status(true), TokenStatus::Quarantined);
+    }
+
+    let validation = ContractProbe {
+        is_erc20: false,
+        ..Default::default()
+    }
+    .validate();
+    assert_eq!(validation.kind, TokenKind::None);
+    assert_eq!(validation.status(false), TokenStatus::Quarantined);
+}
+
+/// Checks the decoding of the metadata returned as `bytes32`.
+#[test]
+fn bytes32_metadata() {
+    let mut value = H256::zero();
+    value[..3].copy_from_slice(b"MKR");
+    assert_eq!(bytes32_to_string(value), Some("MKR".to_string()));
+
+    let mut value = H256::repeat_byte(b'A');
+    value[31] = 0;
+    assert_eq!(bytes32_to_string(value), Some("A".repeat(31)));
+
+    assert_eq!(bytes32_to_string(H256::zero()), Some(String::new()));
+    assert_eq!(bytes32_to_string(H256::repeat_byte(0xff)), None);
+}
+
+/// Checks that the token contract is probed with the trial `balanceOf` and `transfer` calls.
+#[tokio::test]
+async fn fetch_probe() {
+    let token = Address::repeat_byte(1);
+    let zksync_contract = Address::repeat_byte(2);
+    let mut eth = MockEthereum::default();
+    let responses = vec![
+        ("balanceOf", vec![AbiToken::Uint(100.into())]),
+        ("symbol", vec![AbiToken::String("DAI".to_string())]),
+        ("name", vec![AbiToken::String("Dai Stablecoin".to_string())]),
+        ("decimals", vec![AbiToken::Uint(18.into())]),
+        ("transfer", vec![AbiToken::Bool(true)]),
+    ];
+    for (func, response) in responses {
+        eth.add_contract_call_response(token, func, response).await;
+    }
+    let eth_client = EthereumGateway::Mock(eth.clone());
+    assert_eq!(
+        ContractProbe::fetch(&eth_client, token, zksync_contract).await,
+        erc20_probe()
+    );
+
+    let transfer_results = vec![
+        (
+            vec![AbiToken::Bool(false)],
+            TransferSimulation::ReturnedFalse,
+        ),
+        (Vec::new(), TransferSimulation::NoReturnValue),
+    ];
+    for (response, expected) in transfer_results {
+        eth.add_contract_call_response(token, "transfer", response)
+            .await;
+        let probe = ContractProbe::fetch(&eth_client, token, zksync_contract).await;
+        assert_eq!(probe.transfer_simulation, expected);
+    }
+
+    // The contract without any functions.
+    let probe = ContractProbe::fetch(&eth_client, Address::repeat_byte(3), zksync_contract).await;
+    assert!(!probe.is_erc20);
+    assert_eq!(probe.transfer_simulation, TransferSimulation::Reverted);
+}
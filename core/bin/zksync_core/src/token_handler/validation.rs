@@ -0,0 +1,425 @@
+//! Validation of the tokens added to the contract.
+//!
+//! The token contract is queried for its metadata (`symbol`, `name` and `decimals`), both the
+//! `string` and the legacy `bytes32` return types are supported. Besides that, the contract is
+//! probed with `eth_call`s of the functions that are exposed by the tokens taking a fee on
+//! transfers or rebasing balances, because the balances of such tokens in zkSync would diverge
+//! from the amount of tokens actually held by the contract.
+//!
+//! Finally, the withdrawal of the whole balance held by the zkSync contract is simulated with
+//! the trial `balanceOf` and `transfer` calls. Tokens that revert such a transfer (e.g. paused or
+//! blacklisting the zkSync contract) or report its failure can't be withdrawn.
+
+// External uses
+use ethabi::{Contract, Token as AbiToken};
+use web3::contract::{
+    tokens::{Detokenize, Tokenize},
+    Error as ContractError, Options,
+};
+// Workspace uses
+use zksync_eth_client::EthereumGateway;
+use zksync_types::{Address, TokenKind, TokenStatus, H256, U256};
+
+/// Maximum length of the token symbol.
+pub const MAX_SYMBOL_LENGTH: usize = 20;
+/// Maximum length of the token name.
+pub const MAX_NAME_LENGTH: usize = 64;
+/// Maximum number of decimals of the token.
+pub const MAX_DECIMALS: u8 = 36;
+/// Prefix of the default symbols assigned to the tokens, it can't be used by the token contracts.
+pub const DEFAULT_SYMBOL_PREFIX: &str = "ERC20-";
+
+/// Getters of the transfer fee, a non-zero fee means that the token takes a fee on transfers.
+const TRANSFER_FEE_GETTERS: &[&str] = &[
+    "basisPointsRate",
+    "transferFee",
+    "taxFee",
+    "_taxFee",
+    "_liquidityFee",
+];
+/// Functions converting the balance of the account into the underlying shares, they are exposed
+/// by the rebasing tokens (e.g. `stETH`, `AMPL` or Aave `aTokens`).
+const REBASING_GETTERS: &[&str] = &["sharesOf", "scaledBalanceOf"];
+
+const METADATA_ABI: &str = r#"[
+    {"type": "function", "name": "symbol", "inputs": [], "outputs": [{"name": "", "type": "string"}], "stateMutability": "view"},
+    {"type": "function", "name": "name", "inputs": [], "outputs": [{"name": "", "type": "string"}], "stateMutability": "view"},
+    {"type": "function", "name": "decimals", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"}
+]"#;
+
+const METADATA_BYTES32_ABI: &str = r#"[
+    {"type": "function", "name": "symbol", "inputs": [], "outputs": [{"name": "", "type": "bytes32"}], "stateMutability": "view"},
+    {"type": "function", "name": "name", "inputs": [], "outputs": [{"name": "", "type": "bytes32"}], "stateMutability": "view"}
+]"#;
+
+const PROBES_ABI: &str = r#"[
+    {"type": "function", "name": "basisPointsRate", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "transferFee", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "taxFee", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "_taxFee", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "_liquidityFee", "inputs": [], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "sharesOf", "inputs": [{"name": "account", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "scaledBalanceOf", "inputs": [{"name": "user", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"}
+]"#;
+
+const TRANSFER_ABI: &str = r#"[
+    {"type": "function", "name": "balanceOf", "inputs": [{"name": "account", "type": "address"}], "outputs": [{"name": "", "type": "uint256"}], "stateMutability": "view"},
+    {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}], "outputs": [{"name": "", "type": "bool"}], "stateMutability": "nonpayable"}
+]"#;
+
+/// `transfer` of the tokens that don't return the result (e.g. USDT).
+const TRANSFER_NO_RETURN_ABI: &str = r#"[
+    {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint256"}], "outputs": [], "stateMutability": "nonpayable"}
+]"#;
+
+/// Outcome of the simulated `transfer` call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferSimulation {
+    /// Transfer returned `true`.
+    Succeeded,
+    /// Transfer didn't revert and returned nothing.
+    NoReturnValue,
+    /// Transfer returned `false`.
+    ReturnedFalse,
+    /// Transfer reverted.
+    Reverted,
+}
+
+impl Default for TransferSimulation {
+    fn default() -> Self {
+        Self::Reverted
+    }
+}
+
+/// Output of the call that is not decoded.
+struct IgnoredOutput;
+
+impl Detokenize for IgnoredOutput {
+    fn from_tokens(_tokens: Vec<AbiToken>) -> Result<Self, ContractError> {
+        Ok(Self)
+    }
+}
+
+/// Results of the calls to the token contract.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractProbe {
+    /// Whether the contract responds to `balanceOf`.
+    pub is_erc20: bool,
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub decimals: Option<U256>,
+    /// Getter that reported a non-zero transfer fee.
+    pub transfer_fee_getter: Option<&'static str>,
+    /// Share conversion function that is exposed by the contract.
+    pub rebasing_getter: Option<&'static str>,
+    /// Result of the transfer of the whole balance held by the zkSync contract.
+    pub transfer_simulation: TransferSimulation,
+}
+
+impl ContractProbe {
+    /// Queries the token contract, `zksync_contract` is used as the holder of the tokens.
+    /// Failed calls are treated as missing functions.
+    pub async fn fetch(
+        eth_client: &EthereumGateway,
+        address: Address,
+        zksync_contract: Address,
+    ) -> Self {
+        let metadata_abi = Contract::load(METADATA_ABI.as_bytes()).expect("token metadata abi");
+        let bytes32_abi =
+            Contract::load(METADATA_BYTES32_ABI.as_bytes()).expect("token bytes32 metadata abi");
+        let probes_abi = Contract::load(PROBES_ABI.as_bytes()).expect("token probes abi");
+
+        let balance = call::<U256, _>(
+            eth_client,
+            &transfer_abi(),
+            "balanceOf",
+            zksync_contract,
+            None,
+            address,
+        )
+        .await;
+        let is_erc20 = balance.is_ok();
+        let transfer_simulation = simulate_transfer(
+            eth_client,
+            address,
+            zksync_contract,
+            balance.unwrap_or_default(),
+        )
+        .await;
+
+        let symbol = fetch_string(eth_client, &metadata_abi, &bytes32_abi, "symbol", address).await;
+        let name = fetch_string(eth_client, &metadata_abi, &bytes32_abi, "name", address).await;
+        let decimals = call::<U256, _>(eth_client, &metadata_abi, "decimals", (), None, address)
+            .await
+            .ok();
+
+        let mut transfer_fee_getter = None;
+        for &getter in TRANSFER_FEE_GETTERS {
+            if let Ok(fee) =
+                call::<U256, _>(eth_client, &probes_abi, getter, (), None, address).await
+            {
+                if !fee.is_zero() {
+                    transfer_fee_getter = Some(getter);
+                    break;
+                }
+            }
+        }
+        let mut rebasing_getter = None;
+        for &getter in REBASING_GETTERS {
+            if call::<U256, _>(eth_client, &probes_abi, getter, address, None, address)
+                .await
+                .is_ok()
+            {
+                rebasing_getter = Some(getter);
+                break;
+            }
+        }
+
+        Self {
+            is_erc20,
+            symbol,
+            name,
+            decimals,
+            transfer_fee_getter,
+            rebasing_getter,
+            transfer_simulation,
+        }
+    }
+
+    /// Checks the results of the calls.
+    pub fn validate(self) -> TokenValidation {
+        let mut validation = TokenValidation {
+            kind: if self.is_erc20 {
+                TokenKind::ERC20
+            } else {
+                TokenKind::None
+            },
+            ..Default::default()
+        };
+
+        if !self.is_erc20 {
+            validation
+                .behavior_issues
+                .push("contract doesn't implement `balanceOf`".to_string());
+        }
+        if let Some(getter) = self.transfer_fee_getter {
+            validation.behavior_issues.push(format!(
+                "token takes a fee on transfers (`{}` is not zero)",
+                getter
+            ));
+        }
+        if let Some(getter) = self.rebasing_getter {
+            validation.behavior_issues.push(format!(
+                "token rebases balances (`{}` is implemented)",
+                getter
+            ));
+        }
+        match self.transfer_simulation {
+            TransferSimulation::Succeeded | TransferSimulation::NoReturnValue => {}
+            TransferSimulation::ReturnedFalse => validation
+                .behavior_issues
+                .push("simulated `transfer` returned false".to_string()),
+            TransferSimulation::Reverted => validation
+                .behavior_issues
+                .push("simulated `transfer` reverted".to_string()),
+        }
+
+        match self.symbol {
+            Some(symbol) => match check_symbol(&symbol) {
+                Ok(()) => validation.symbol = Some(symbol),
+                Err(err) => validation.metadata_issues.push(err),
+            },
+            None => validation
+                .metadata_issues
+                .push("`symbol` is not implemented".to_string()),
+        }
+        // Name is not stored, yet the malformed one is a sign of the malicious token.
+        if let Some(name) = self.name {
+            if let Err(err) = check_name(&name) {
+                validation.metadata_issues.push(err);
+            }
+        }
+        match self.decimals {
+            Some(decimals) if decimals <= U256::from(MAX_DECIMALS) => {
+                validation.decimals = Some(decimals.as_u32() as u8)
+            }
+            Some(decimals) => validation.metadata_issues.push(format!(
+                "too many decimals: {}, maximum is {}",
+                decimals, MAX_DECIMALS
+            )),
+            None => validation
+                .metadata_issues
+                .push("`decimals` is not implemented".to_string()),
+        }
+
+        validation
+    }
+}
+
+/// Outcome of the token validation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenValidation {
+    pub kind: TokenKind,
+    /// Symbol reported by the contract if it's valid.
+    pub symbol: Option<String>,
+    /// Decimals reported by the contract if they're valid.
+    pub decimals: Option<u8>,
+    /// Problems with the metadata, the default values are used instead of the malformed ones.
+    pub metadata_issues: Vec<String>,
+    /// Unsafe behavior of the token contract.
+    pub behavior_issues: Vec<String>,
+}
+
+impl TokenValidation {
+    /// Returns the status of the token.
+    /// Metadata of the tokens from the trusted list is not used, so its issues are ignored.
+    pub fn status(&self, trusted: bool) -> TokenStatus {
+        if !self.behavior_issues.is_empty() {
+            TokenStatus::Quarantined
+        } else if !trusted && !self.metadata_issues.is_empty() {
+            TokenStatus::Flagged
+        } else {
+            TokenStatus::Valid
+        }
+    }
+
+    /// Returns the human-readable description of the found issues.
+    pub fn issues(&self) -> String {
+        self.behavior_issues
+            .iter()
+            .chain(&self.metadata_issues)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Converts the `bytes32` value returned by the legacy tokens into the string.
+/// The value is expected to be a UTF-8 string padded with zero bytes.
+pub fn bytes32_to_string(value: H256) -> Option<String> {
+    let bytes = value.as_bytes();
+    let len = bytes
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |pos| pos + 1);
+    String::from_utf8(bytes[..len].to_vec()).ok()
+}
+
+/// Checks that the symbol consists of the printable ASCII characters without spaces.
+pub fn check_symbol(symbol: &str) -> Result<(), String> {
+    if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(format!(
+            "symbol length must be from 1 to {}, got {:?}",
+            MAX_SYMBOL_LENGTH, symbol
+        ));
+    }
+    if !symbol.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(format!("symbol contains invalid characters: {:?}", symbol));
+    }
+    if symbol.starts_with(DEFAULT_SYMBOL_PREFIX) {
+        return Err(format!("symbol uses the reserved prefix: {:?}", symbol));
+    }
+    Ok(())
+}
+
+/// Checks that the name is not empty and consists of the printable characters.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "name length must be from 1 to {}, got {:?}",
+            MAX_NAME_LENGTH, name
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(format!("name contains invalid characters: {:?}", name));
+    }
+    Ok(())
+}
+
+/// Calls the metadata getter returning either `string` or `bytes32`.
+async fn fetch_string(
+    eth_client: &EthereumGateway,
+    string_abi: &Contract,
+    bytes32_abi: &Contract,
+    func: &str,
+    address: Address,
+) -> Option<String> {
+    match call::<String, _>(eth_client, string_abi, func, (), None, address).await {
+        Ok(value) => Some(value),
+        Err(_) => call::<H256, _>(eth_client, bytes32_abi, func, (), None, address)
+            .await
+            .ok()
+            .and_then(bytes32_to_string),
+    }
+}
+
+fn transfer_abi() -> Contract {
+    Contract::load(TRANSFER_ABI.as_bytes()).expect("token transfer abi")
+}
+
+/// Simulates the transfer of `amount` tokens from `holder` with `eth_call`.
+async fn simulate_transfer(
+    eth_client: &EthereumGateway,
+    address: Address,
+    holder: Address,
+    amount: U256,
+) -> TransferSimulation {
+    let recipient = Address::from_low_u64_be(0xdead);
+    let params = (recipient, amount);
+    match call::<bool, _>(
+        eth_client,
+        &transfer_abi(),
+        "transfer",
+        params,
+        Some(holder),
+        address,
+    )
+    .await
+    {
+        Ok(true) => return TransferSimulation::Succeeded,
+        Ok(false) => return TransferSimulation::ReturnedFalse,
+        Err(_) => {}
+    }
+
+    // The call either reverted or returned nothing, which can't be decoded as `bool`.
+    let no_return_abi =
+        Contract::load(TRANSFER_NO_RETURN_ABI.as_bytes()).expect("token transfer abi");
+    match call::<IgnoredOutput, _>(
+        eth_client,
+        &no_return_abi,
+        "transfer",
+        params,
+        Some(holder),
+        address,
+    )
+    .await
+    {
+        Ok(_) => TransferSimulation::NoReturnValue,
+        Err(_) => TransferSimulation::Reverted,
+    }
+}
+
+async fn call<R, P>(
+    eth_client: &EthereumGateway,
+    abi: &Contract,
+    func: &str,
+    params: P,
+    from: Option<Address>,
+    address: Address,
+) -> anyhow::Result<R>
+where
+    R: Detokenize + Unpin,
+    P: Tokenize + Clone,
+{
+    eth_client
+        .call_contract_function::<R, _, _, _>(
+            func,
+            params,
+            from,
+            Options::default(),
+            None,
+            address,
+            abi.clone(),
+        )
+        .await
+}
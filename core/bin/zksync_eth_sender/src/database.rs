@@ -1,14 +1,16 @@
 // Built-in deps
 use std::collections::VecDeque;
 use std::str::FromStr;
+use std::time::Duration;
 // External uses
+use chrono::Utc;
 use num::BigUint;
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, H256, U256};
 // Workspace uses
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
 // Local uses
-use super::transactions::ETHStats;
+use super::transactions::{ETHStats, PaidFee};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::block::Block;
 
@@ -48,13 +50,17 @@ pub(super) trait DatabaseInterface {
     ) -> anyhow::Result<()>;
 
     /// Saves a new unconfirmed operation to the database.
+    /// The nonce is assigned from the given operator account, or the main one if `operator` is `None`.
+    #[allow(clippy::too_many_arguments)]
     async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         op_type: AggregatedActionType,
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
         raw_tx: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse>;
 
@@ -73,37 +79,85 @@ pub(super) trait DatabaseInterface {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()>;
 
-    /// Marks an operation as completed in the database.
+    /// Assigns a new nonce of the operator account to the operation with a failed transaction
+    /// and removes the hashes of the sent transactions. Returns the new nonce.
+    async fn reset_failed_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        operator: Option<Address>,
+    ) -> anyhow::Result<U256>;
+
+    /// Stores the additional operator account with the given nonce, if it's not stored yet.
+    async fn initialize_operator_account(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: U256,
+    ) -> anyhow::Result<()>;
+
+    /// Stores whether the sender is paused after a failed transaction.
+    async fn set_paused(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        paused: bool,
+    ) -> anyhow::Result<()>;
+
+    /// Marks an operation as completed in the database and stores the fee paid for the transaction.
     async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        fee: &PaidFee,
+    ) -> anyhow::Result<()>;
+
+    /// Stores the fee paid for the failed transaction.
+    async fn save_failed_tx_fee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        op: &ETHOperation,
+        fee: &PaidFee,
     ) -> anyhow::Result<()>;
 
+    /// Loads the total fee paid for the mined transactions of the given type within the last `period`.
+    async fn load_spent_fee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_type: AggregatedActionType,
+        period: Duration,
+    ) -> anyhow::Result<U256>;
+
     /// Loads the stored Ethereum operations stats.
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats>;
 
-    /// Loads the stored gas price limit.
+    /// Loads the stored gas price limit of the operator account (the main one if `operator` is `None`).
     async fn load_gas_price_limit(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
     ) -> anyhow::Result<U256>;
 
-    /// Updates the stored gas price limit.
+    /// Updates the stored gas price limit of the operator account (the main one if `operator` is `None`).
     async fn update_gas_price_params(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         gas_price_limit: U256,
         average_gas_price: U256,
     ) -> anyhow::Result<()>;
 
+    /// Checks whether the operation sent before the given one from the same lane
+    /// (i.e. one of the `lane_types`) is confirmed.
     async fn is_previous_operation_confirmed(
         &self,
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
+        lane_types: &[AggregatedActionType],
     ) -> anyhow::Result<bool>;
 }
 
@@ -177,22 +231,27 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn save_new_eth_tx(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         op_type: AggregatedActionType,
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
         raw_tx: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse> {
         let result = connection
             .ethereum_schema()
-            .save_new_eth_tx(
+            .save_new_operator_eth_tx(
+                operator,
                 op_type,
                 op,
                 deadline_block,
                 BigUint::from_str(&used_gas_price.to_string()).unwrap(),
+                used_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
                 raw_tx,
             )
             .await?;
@@ -218,6 +277,7 @@ impl DatabaseInterface for Database {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()> {
         Ok(connection
             .ethereum_schema()
@@ -225,27 +285,60 @@ impl DatabaseInterface for Database {
                 eth_op_id,
                 new_deadline_block,
                 BigUint::from_str(&new_gas_value.to_string()).unwrap(),
+                new_priority_fee.map(|fee| BigUint::from_str(&fee.to_string()).unwrap()),
             )
             .await?)
     }
 
+    async fn reset_failed_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        operator: Option<Address>,
+    ) -> anyhow::Result<U256> {
+        let nonce = connection
+            .ethereum_schema()
+            .reset_failed_eth_tx(eth_op_id, operator)
+            .await?;
+
+        Ok(nonce.into())
+    }
+
+    async fn initialize_operator_account(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: U256,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .initialize_operator_account(address, nonce.as_u64() as i64)
+            .await?)
+    }
+
+    async fn set_paused(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        paused: bool,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .ethereum_schema()
+            .set_eth_sender_paused(paused)
+            .await?)
+    }
+
     async fn is_previous_operation_confirmed(
         &self,
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
+        lane_types: &[AggregatedActionType],
     ) -> anyhow::Result<bool> {
-        // If the ID of the current operation is 1, then this is the first transaction
-        // and it is not needed for checking confirmation.
-        if op.id == 1 {
-            return Ok(true);
-        }
-
-        // Since the operations are sent to the Ethereum one by one,
-        // we simply consider the operation with ID less by one.
-        let previous_op = op.id - 1;
+        // Since the operations of the lane are sent to the Ethereum one by one,
+        // we simply consider the last operation of the lane stored before the current one.
+        // Operations of the other lanes have independent nonces and are not taken into account.
         let confirmed = connection
             .ethereum_schema()
-            .is_aggregated_op_confirmed(previous_op)
+            .is_previous_eth_op_confirmed(op.id, lane_types)
             .await?;
 
         Ok(confirmed)
@@ -256,6 +349,7 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        fee: &PaidFee,
     ) -> anyhow::Result<()> {
         let mut transaction = connection.start_transaction().await?;
 
@@ -313,11 +407,55 @@ impl DatabaseInterface for Database {
         }
 
         transaction.ethereum_schema().confirm_eth_tx(hash).await?;
+        transaction
+            .ethereum_schema()
+            .save_eth_tx_fee(
+                hash,
+                BigUint::from_str(&fee.gas_used.to_string()).unwrap(),
+                BigUint::from_str(&fee.gas_price.to_string()).unwrap(),
+                true,
+            )
+            .await?;
         transaction.commit().await?;
 
         Ok(())
     }
 
+    async fn save_failed_tx_fee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        _op: &ETHOperation,
+        fee: &PaidFee,
+    ) -> anyhow::Result<()> {
+        connection
+            .ethereum_schema()
+            .save_eth_tx_fee(
+                hash,
+                BigUint::from_str(&fee.gas_used.to_string()).unwrap(),
+                BigUint::from_str(&fee.gas_price.to_string()).unwrap(),
+                false,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_spent_fee(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op_type: AggregatedActionType,
+        period: Duration,
+    ) -> anyhow::Result<U256> {
+        let since = Utc::now() - chrono::Duration::from_std(period)?;
+        let spent_fee = connection
+            .ethereum_schema()
+            .load_total_eth_tx_fee(op_type, since)
+            .await?;
+
+        Ok(U256::from_dec_str(&spent_fee.to_string())?)
+    }
+
     async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
         let stats = connection.ethereum_schema().load_stats().await?;
         Ok(stats.into())
@@ -326,21 +464,41 @@ impl DatabaseInterface for Database {
     async fn load_gas_price_limit(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
     ) -> anyhow::Result<U256> {
-        let limit = connection.ethereum_schema().load_gas_price_limit().await?;
+        let limit = match operator {
+            Some(address) => {
+                connection
+                    .ethereum_schema()
+                    .load_operator_gas_price_limit(address)
+                    .await?
+            }
+            None => connection.ethereum_schema().load_gas_price_limit().await?,
+        };
         Ok(limit)
     }
 
     async fn update_gas_price_params(
         &self,
         connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         gas_price_limit: U256,
         average_gas_price: U256,
     ) -> anyhow::Result<()> {
-        connection
-            .ethereum_schema()
-            .update_gas_price(gas_price_limit, average_gas_price)
-            .await?;
+        match operator {
+            Some(address) => {
+                connection
+                    .ethereum_schema()
+                    .update_operator_gas_price(address, gas_price_limit, average_gas_price)
+                    .await?
+            }
+            None => {
+                connection
+                    .ethereum_schema()
+                    .update_gas_price(gas_price_limit, average_gas_price)
+                    .await?
+            }
+        }
         Ok(())
     }
 }
@@ -1,7 +1,8 @@
 // Built-in deps
 use std::{collections::VecDeque, marker::PhantomData, time::Instant};
 // External deps
-use zksync_basic_types::U256;
+use zksync_basic_types::{Address, U256};
+use zksync_config::configs::eth_sender::EthTxType;
 use zksync_eth_client::EthereumGateway;
 // Local deps
 use crate::database::DatabaseInterface;
@@ -20,10 +21,22 @@ mod tests;
 /// transactions only), which guarantees that we will increase the
 /// gas price for transactions that were not mined by the network
 /// within a reasonable time.
+///
+/// For EIP-1559 transactions the base fee and the priority fee are adjusted
+/// separately, while the limit is applied to the max fee per gas.
 #[derive(Debug)]
 pub(super) struct GasAdjuster<DB: DatabaseInterface> {
+    /// Type of the transactions to adjust the fees for.
+    tx_type: EthTxType,
+    /// Operator account which sends the transactions, `None` for the main account.
+    operator: Option<Address>,
     /// Collected statistics about recently used gas prices.
+    /// For EIP-1559 transactions the effective gas price (base fee + priority fee) is collected.
     statistics: GasStatistics,
+    /// Collected statistics about recently observed base fees (EIP-1559 transactions only).
+    base_fee_statistics: GasStatistics,
+    /// Collected statistics about recently used priority fees (EIP-1559 transactions only).
+    priority_fee_statistics: GasStatistics,
     /// Timestamp of the last maximum gas price update.
     last_price_renewal: Instant,
     /// Timestamp of the last sample added to the `statistics`.
@@ -32,17 +45,22 @@ pub(super) struct GasAdjuster<DB: DatabaseInterface> {
 }
 
 impl<DB: DatabaseInterface> GasAdjuster<DB> {
-    pub async fn new(db: &DB) -> Self {
+    pub async fn new(db: &DB, tx_type: EthTxType, operator: Option<Address>) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
             .expect("Unable to connect to DB");
         let gas_price_limit = db
-            .load_gas_price_limit(&mut connection)
+            .load_gas_price_limit(&mut connection, operator)
             .await
             .expect("Can't load the gas price limit");
         Self {
+            tx_type,
+            operator,
             statistics: GasStatistics::new(gas_price_limit),
+            // Limits of the fee statistics are not used, the max fee is limited by `statistics`.
+            base_fee_statistics: GasStatistics::new(gas_price_limit),
+            priority_fee_statistics: GasStatistics::new(gas_price_limit),
             last_price_renewal: Instant::now(),
             last_sample_added: Instant::now(),
 
@@ -93,16 +111,128 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
         Ok(price)
     }
 
+    async fn get_suggested_fees(
+        &self,
+        ethereum: &EthereumGateway,
+        old_tx_fees: Option<EIP1559Fees>,
+    ) -> anyhow::Result<(U256, EIP1559Fees)> {
+        let base_fee = match self.base_fee_statistics.get_average_price() {
+            Some(base_fee) => base_fee,
+            None => ethereum.get_base_fee().await?,
+        };
+        let network_priority_fee = match self.priority_fee_statistics.get_average_price() {
+            Some(priority_fee) => priority_fee,
+            None => ethereum.get_priority_fee().await?,
+        };
+
+        let fees = if let Some(old_fees) = old_tx_fees {
+            // Stuck transaction, both fees must be scaled up for the replacement
+            // transaction to be accepted by the network.
+            let priority_fee =
+                self.scale_up(old_fees.max_priority_fee_per_gas, network_priority_fee);
+            EIP1559Fees {
+                max_fee_per_gas: self.scale_up(
+                    old_fees.max_fee_per_gas,
+                    max_fee_per_gas(base_fee, priority_fee),
+                ),
+                max_priority_fee_per_gas: priority_fee,
+            }
+        } else {
+            // New transaction, use the network fees as the base.
+            EIP1559Fees {
+                max_fee_per_gas: max_fee_per_gas(base_fee, network_priority_fee),
+                max_priority_fee_per_gas: network_priority_fee,
+            }
+        };
+        Ok((base_fee, fees))
+    }
+
+    /// Calculates the fees for the EIP-1559 transaction.
+    /// For the replacement of the stuck tx both the max fee and the priority fee are
+    /// made at least 15% higher.
+    pub async fn get_eip1559_fees(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_fees: Option<EIP1559Fees>,
+    ) -> anyhow::Result<EIP1559Fees> {
+        let (base_fee, scaled_fees) = self.get_suggested_fees(ethereum, old_tx_fees).await?;
+        // Now, cut the max fee if it's too big. Priority fee can't exceed the max fee.
+        let max_fee = self.limit_max(scaled_fees.max_fee_per_gas);
+        let fees = EIP1559Fees {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: std::cmp::min(scaled_fees.max_priority_fee_per_gas, max_fee),
+        };
+
+        if fees.max_fee_per_gas == self.get_current_max_price() {
+            // We're suggesting the max price, so we must notify the log
+            // entry about it.
+            vlog::warn!(
+                "Maximum possible max fee per gas will be used: <{}>",
+                fees.max_fee_per_gas
+            );
+        }
+
+        // Report used fees to be gathered by the statistics module.
+        self.priority_fee_statistics
+            .add_sample(fees.max_priority_fee_per_gas);
+        self.statistics
+            .add_sample(fees.effective_gas_price(base_fee));
+
+        Ok(fees)
+    }
+
+    /// Calculates the fees for the transaction of the configured type.
+    /// `old_tx_fees` are the fees of the stuck transaction to be replaced, if any.
+    pub async fn get_tx_fees(
+        &mut self,
+        ethereum: &EthereumGateway,
+        old_tx_fees: Option<TxFees>,
+    ) -> anyhow::Result<TxFees> {
+        let fees = match self.tx_type {
+            EthTxType::Legacy => TxFees {
+                gas_price: self
+                    .get_gas_price(ethereum, old_tx_fees.map(|fees| fees.gas_price))
+                    .await?,
+                priority_fee: None,
+            },
+            EthTxType::EIP1559 => {
+                let old_tx_fees = old_tx_fees.map(|fees| EIP1559Fees {
+                    max_fee_per_gas: fees.gas_price,
+                    // Legacy transaction pays its whole gas price to the miner.
+                    max_priority_fee_per_gas: fees.priority_fee.unwrap_or(fees.gas_price),
+                });
+                self.get_eip1559_fees(ethereum, old_tx_fees).await?.into()
+            }
+        };
+        Ok(fees)
+    }
+
+    /// Reports the current network fees to the statistics module.
+    async fn add_network_sample(&mut self, ethereum: &EthereumGateway) -> anyhow::Result<()> {
+        match self.tx_type {
+            EthTxType::Legacy => {
+                let network_price = ethereum.get_gas_price().await?;
+                self.statistics.add_sample(network_price);
+            }
+            EthTxType::EIP1559 => {
+                let base_fee = ethereum.get_base_fee().await?;
+                let priority_fee = ethereum.get_priority_fee().await?;
+                self.base_fee_statistics.add_sample(base_fee);
+                self.priority_fee_statistics.add_sample(priority_fee);
+                self.statistics.add_sample(base_fee + priority_fee);
+            }
+        }
+        Ok(())
+    }
+
     /// Performs an actualization routine for `GasAdjuster`:
     /// This method is intended to be invoked periodically, and it updates the
     /// current max gas price limit according to the configurable update interval.
     pub async fn keep_updated(&mut self, ethereum: &EthereumGateway, db: &DB) {
         if self.last_sample_added.elapsed() >= parameters::sample_adding_interval() {
             // Report the current price to be gathered by the statistics module.
-            match ethereum.get_gas_price().await {
-                Ok(network_price) => {
-                    self.statistics.add_sample(network_price);
-
+            match self.add_network_sample(ethereum).await {
+                Ok(()) => {
                     self.last_sample_added = Instant::now();
                 }
                 Err(err) => {
@@ -137,6 +267,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             let result = db
                 .update_gas_price_params(
                     &mut connection,
+                    self.operator,
                     self.statistics.get_limit(),
                     average_gas_price,
                 )
@@ -167,6 +298,48 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
     }
 }
 
+/// Calculates the max fee per gas for the EIP-1559 transaction.
+/// The doubled base fee keeps the transaction valid for at least 6 consecutive full blocks,
+/// since the base fee can't grow by more than 12.5% per block.
+pub(super) fn max_fee_per_gas(base_fee: U256, priority_fee: U256) -> U256 {
+    base_fee * 2 + priority_fee
+}
+
+/// Fees of the EIP-1559 transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct EIP1559Fees {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl EIP1559Fees {
+    /// Returns the gas price paid by the transaction included into the block with the given base fee.
+    pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+        std::cmp::min(
+            self.max_fee_per_gas,
+            base_fee + self.max_priority_fee_per_gas,
+        )
+    }
+}
+
+/// Fees of the Ethereum transaction of any type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct TxFees {
+    /// Gas price of the legacy transaction or max fee per gas of the EIP-1559 transaction.
+    pub gas_price: U256,
+    /// Max priority fee per gas, set only for the EIP-1559 transaction.
+    pub priority_fee: Option<U256>,
+}
+
+impl From<EIP1559Fees> for TxFees {
+    fn from(fees: EIP1559Fees) -> Self {
+        Self {
+            gas_price: fees.max_fee_per_gas,
+            priority_fee: Some(fees.max_priority_fee_per_gas),
+        }
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the average gas price, and providing the gas price limit.
 #[derive(Debug)]
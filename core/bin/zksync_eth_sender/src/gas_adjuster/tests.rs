@@ -1,9 +1,10 @@
 // Built-in uses
 // Workspace uses
 use zksync_basic_types::U256;
+use zksync_config::configs::eth_sender::EthTxType;
 // Local uses
 use crate::{
-    gas_adjuster::{parameters::limit_scale_factor, GasStatistics},
+    gas_adjuster::{parameters::limit_scale_factor, EIP1559Fees, GasStatistics, TxFees},
     tests::mock::{default_eth_sender, MockDatabase},
     DatabaseInterface, GasAdjuster,
 };
@@ -12,7 +13,7 @@ use zksync_eth_client::ethereum_gateway::EthereumGateway;
 
 /// Creates `Ethereum` and `Database` instances for the `GasAdjuster` tests.
 async fn eth_and_db_clients() -> (EthereumGateway, MockDatabase) {
-    let eth_sender = default_eth_sender().await;
+    let eth_sender = default_eth_sender(EthTxType::Legacy).await;
 
     (eth_sender.ethereum, eth_sender.db)
 }
@@ -30,13 +31,14 @@ fn scale_gas_limit(value: u64) -> u64 {
 async fn initial_price() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Vector of ethereum client prices.
     let test_vector = vec![
         0,
         13,
-        db.load_gas_price_limit(&mut connection)
+        db.load_gas_price_limit(&mut connection, None)
             .await
             .unwrap()
             .low_u64(),
@@ -62,7 +64,8 @@ async fn initial_price() {
 async fn lower_gas_limit() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Test vector of pairs (ethereum client price, price of the last tx, expected price).
     let test_vector = vec![
@@ -99,7 +102,8 @@ async fn gas_price_limit_restore() {
 
     let (_, db) = eth_and_db_clients().await;
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     assert_eq!(gas_adjuster.get_current_max_price(), PRICE_LIMIT.into());
 }
@@ -115,7 +119,8 @@ async fn initial_upper_gas_limit() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Set the gas price in Ethereum, which is greater than the current limit.
     ethereum
@@ -155,7 +160,8 @@ async fn average_gas_price_stored_correctly() {
     let (mut ethereum, db) = eth_and_db_clients().await;
 
     db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     let initial_db_price = db.average_gas_price().await;
     assert_eq!(initial_db_price, 0u64.into()); // Check just in case.
@@ -204,7 +210,8 @@ async fn gas_price_limit_scaling() {
 
     db.update_gas_price_limit(PRICE_LIMIT as i64).await.unwrap();
 
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -249,7 +256,10 @@ async fn gas_price_limit_scaling() {
         }
 
         // Check that new limit is scaled old limit (and also check that it's stored in the DB).
-        let new_limit = db.load_gas_price_limit(&mut connection).await.unwrap();
+        let new_limit = db
+            .load_gas_price_limit(&mut connection, None)
+            .await
+            .unwrap();
         assert_eq!(new_limit, scale_gas_limit(expected_price).into());
 
         // Update the expected price for the next round.
@@ -287,7 +297,8 @@ async fn gas_price_limit_average_basis() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
     db.update_gas_price_limit(PRICE_LIMIT as i64).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -329,7 +340,10 @@ async fn gas_price_limit_average_basis() {
         gas_adjuster.keep_updated(&ethereum, &db).await;
 
         // Check that new limit is based on the average of previous N samples.
-        let new_limit = db.load_gas_price_limit(&mut connection).await.unwrap();
+        let new_limit = db
+            .load_gas_price_limit(&mut connection, None)
+            .await
+            .unwrap();
 
         current_limit = scale_gas_limit(samples_sum / N_SAMPLES as u64);
         assert_eq!(new_limit, current_limit.into());
@@ -352,7 +366,8 @@ async fn gas_price_limit_preservation() {
     let (mut ethereum, db) = eth_and_db_clients().await;
     let mut connection = db.acquire_connection().await.unwrap();
     db.update_gas_price_limit(price_limit as i64).await.unwrap();
-    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::Legacy, None).await;
 
     // Set the client price way beyond the limit.
     ethereum
@@ -374,7 +389,185 @@ async fn gas_price_limit_preservation() {
 
         // Keep the limit updated (it should not change).
         gas_adjuster.keep_updated(&ethereum, &db).await;
-        let new_limit = db.load_gas_price_limit(&mut connection).await.unwrap();
+        let new_limit = db
+            .load_gas_price_limit(&mut connection, None)
+            .await
+            .unwrap();
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Sets the base fee and the priority fee in the mock Ethereum client.
+async fn set_network_fees(ethereum: &mut EthereumGateway, base_fee: u64, priority_fee: u64) {
+    let mock = ethereum.get_mut_mock().unwrap();
+    mock.set_base_fee(base_fee.into()).await.unwrap();
+    mock.set_priority_fee(priority_fee.into()).await.unwrap();
+}
+
+/// Checks that for the new EIP-1559 transaction GasAdjuster suggests the network priority fee
+/// and the max fee covering the doubled base fee.
+#[tokio::test]
+async fn eip1559_initial_fees() {
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::EIP1559, None).await;
+
+    // Test vector of (base fee, priority fee, expected max fee).
+    let test_vector = vec![(0, 0, 0), (10, 2, 22), (100, 0, 200)];
+
+    for (base_fee, priority_fee, expected_max_fee) in test_vector {
+        set_network_fees(&mut ethereum, base_fee, priority_fee).await;
+
+        let fees = gas_adjuster
+            .get_eip1559_fees(&ethereum, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            fees,
+            EIP1559Fees {
+                max_fee_per_gas: expected_max_fee.into(),
+                max_priority_fee_per_gas: priority_fee.into(),
+            }
+        );
+    }
+}
+
+/// Checks that for the stuck EIP-1559 transaction both the max fee and the priority fee
+/// are increased by at least 15%, so the replacement transaction is accepted by the network.
+#[tokio::test]
+async fn eip1559_stuck_fees() {
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::EIP1559, None).await;
+
+    // Test vector of (base fee, priority fee, old max fee, old priority fee,
+    // expected max fee, expected priority fee).
+    let test_vector = vec![
+        (10, 1, 100, 10, 115, 11), // Network fees are too low, increase both by 15%
+        (100, 20, 100, 10, 220, 20), // Network fees are higher, use them
+        (10, 20, 100, 10, 115, 20), // Only the priority fee is taken from the network
+        (100, 5, 100, 30, 234, 34), // Max fee is based on the increased priority fee
+        (0, 0, 0, 0, 0, 0),        // 0 fees do not lead to crash
+    ];
+
+    for (base_fee, priority_fee, old_max_fee, old_priority_fee, max_fee, expected_priority_fee) in
+        test_vector
+    {
+        set_network_fees(&mut ethereum, base_fee, priority_fee).await;
+
+        let old_fees = EIP1559Fees {
+            max_fee_per_gas: old_max_fee.into(),
+            max_priority_fee_per_gas: old_priority_fee.into(),
+        };
+        let fees = gas_adjuster
+            .get_eip1559_fees(&ethereum, Some(old_fees))
+            .await
+            .unwrap();
+        assert_eq!(
+            fees,
+            EIP1559Fees {
+                max_fee_per_gas: max_fee.into(),
+                max_priority_fee_per_gas: expected_priority_fee.into(),
+            }
+        );
+    }
+}
+
+/// Checks that the stuck legacy transaction is replaced with the EIP-1559 one paying
+/// at least 15% more to the miner.
+#[tokio::test]
+async fn eip1559_replaces_legacy_tx() {
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::EIP1559, None).await;
+    set_network_fees(&mut ethereum, 10, 1).await;
+
+    let old_fees = TxFees {
+        gas_price: 100.into(),
+        priority_fee: None,
+    };
+    let fees = gas_adjuster
+        .get_tx_fees(&ethereum, Some(old_fees))
+        .await
+        .unwrap();
+    assert_eq!(
+        fees,
+        TxFees {
+            gas_price: 135.into(),
+            priority_fee: Some(115.into()),
+        }
+    );
+}
+
+/// Checks that the max fee is clamped according to the current limit and the priority fee
+/// never exceeds the max fee.
+#[tokio::test]
+async fn eip1559_fees_limit() {
+    // Initial price limit to set.
+    const PRICE_LIMIT: i64 = 1000;
+
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::EIP1559, None).await;
+
+    // Max fee for these network fees is greater than the limit.
+    set_network_fees(&mut ethereum, PRICE_LIMIT as u64, 10).await;
+    let fees = gas_adjuster
+        .get_eip1559_fees(&ethereum, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        fees,
+        EIP1559Fees {
+            max_fee_per_gas: PRICE_LIMIT.into(),
+            max_priority_fee_per_gas: 10.into(),
+        }
+    );
+
+    // Priority fee is greater than the limit as well.
+    set_network_fees(&mut ethereum, 1, PRICE_LIMIT as u64 * 2).await;
+    let fees = gas_adjuster
+        .get_eip1559_fees(&ethereum, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        fees,
+        EIP1559Fees {
+            max_fee_per_gas: PRICE_LIMIT.into(),
+            max_priority_fee_per_gas: PRICE_LIMIT.into(),
+        }
+    );
+}
+
+/// Checks that for EIP-1559 transactions the average of the base fee and the priority fee
+/// sum is stored to the database.
+#[tokio::test]
+async fn eip1559_average_gas_price_stored_correctly() {
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    let mut gas_adjuster: GasAdjuster<MockDatabase> =
+        GasAdjuster::new(&db, EthTxType::EIP1559, None).await;
+    set_network_fees(&mut ethereum, 3, 1).await;
+
+    for _ in 0..GasStatistics::GAS_PRICE_SAMPLES_AMOUNT {
+        gas_adjuster.keep_updated(&ethereum, &db).await;
+    }
+
+    let current_db_price = db.average_gas_price().await;
+    assert_eq!(current_db_price, 4u64.into());
+
+    // Averages of the network fees are used once the statistics are gathered.
+    // Max fee (3 * 2 + 1) is clamped by the updated limit (4 * 1.5).
+    set_network_fees(&mut ethereum, 100, 100).await;
+    let fees = gas_adjuster
+        .get_eip1559_fees(&ethereum, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        fees,
+        EIP1559Fees {
+            max_fee_per_gas: scale_gas_limit(4).into(),
+            max_priority_fee_per_gas: 1.into(),
+        }
+    );
+}
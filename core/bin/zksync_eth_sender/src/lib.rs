@@ -4,24 +4,31 @@
 //! every transaction is executed successfully and confirmed.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::time::{Duration, Instant};
 // External uses
 use anyhow::format_err;
 use tokio::{task::JoinHandle, time};
 use web3::{
     contract::Options,
-    types::{TransactionReceipt, H256, U256},
+    types::{Address, TransactionReceipt, H256, U256},
 };
 // Workspace uses
-use zksync_config::ETHSenderConfig;
-use zksync_eth_client::{EthereumGateway, SignedCallResult};
+use zksync_config::{
+    configs::eth_sender::{FailurePolicy, OperatorAccount},
+    ETHSenderConfig, NotifierConfig,
+};
+use zksync_eth_client::{
+    ethereum_gateway::{ExecutedTxStatus, GasEstimation},
+    EthereumGateway, SignedCallResult,
+};
+use zksync_notifier::{Notifier, NotifyEvent};
 use zksync_storage::ConnectionPool;
 use zksync_types::ethereum::ETHOperation;
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
-    gas_adjuster::GasAdjuster,
+    gas_adjuster::{GasAdjuster, TxFees},
     transactions::*,
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
 };
@@ -42,6 +49,10 @@ mod tests;
 const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
 const RATE_LIMIT_HTTP_CODE: &str = "429";
+/// Amount of wei in one gwei.
+const WEI_IN_GWEI: u64 = 1_000_000_000;
+/// Stuck operation is reported via the notifier every time this amount of transactions is sent for it.
+const STUCK_TX_NOTIFY_ATTEMPTS: usize = 3;
 
 /// `TxCheckMode` enum determines the policy on the obtaining the tx status.
 /// The latest sent transaction can be pending (we're still waiting for it),
@@ -104,27 +115,101 @@ enum TxCheckMode {
 ///
 /// # Failure policy
 ///
-/// By default, `ETHSender` expects no transactions to fail, and thus upon a failure it will
-/// report the incident to the log and then panic to prevent continue working in a probably
-/// erroneous conditions. Failure handling policy is determined by a corresponding callback,
-/// which can be changed if needed.
+/// `ETHSender` expects no transactions to fail, so every failure is reported to the log and
+/// handled according to the configured `FailurePolicy`:
+///
+/// - `Panic` stops the node to prevent continue working in a probably erroneous conditions.
+/// - `Pause` sends the transaction again if it ran out of gas or lost a nonce race (i.e. was reverted
+///   for being applied out of order, see `TxFailure`). The nonce of the failed transaction is already
+///   used, so the new one gets the next nonce and a higher gas limit. Any other failure (or too many
+///   failed attempts) pauses the sender: no transactions are sent until the restart, and the paused
+///   state is reported by the private API.
+///
+/// The failed operation is sent again only once the other transactions sent from the same account
+/// are mined, and no new operations are sent from the account until then. The transactions sent after
+/// the failed one may fail as well, in which case they are sent again after it, in the same order.
+///
+/// In both cases a notification about the failure is sent if the webhook is configured.
+///
+/// # Pre-flight check
+///
+/// If enabled, every new transaction is simulated with `eth_estimateGas` against the pending state
+/// before it's stored and sent. A transaction that would be reverted is not sent: the operation is
+/// returned to the queue and checked again on the next iteration, and a notification is sent.
+/// Estimated gas diverging from the `GasCounter` limit is reported to the log and to the metrics.
+///
+/// # Operator accounts
+///
+/// Operations of certain types (e.g. verify or execute) can be sent from the additional operator
+/// accounts, so they don't wait for the commit transactions in the same nonce queue. Every account
+/// has its own nonce lane and gas price statistics, while the main (commit) account sends the
+/// operations of the remaining types. The order of the dependent operations sent from the different
+/// lanes is maintained by the `TxQueue`: e.g. a verify operation is sent only after the commit of
+/// the same blocks is confirmed.
+///
+/// # Spending limits
+///
+/// Fee paid for every mined transaction, including the failed ones, is stored in the database. Before
+/// a new transaction is sent, the fees paid for the transactions of the same type within the rolling
+/// window, the maximum fees of the pending ones and the estimated fee of the new one are checked against
+/// the configured limit. Once the limit is reached, the operations of this type are held in the queue (while the
+/// operations of other types are still sent) until the older fees leave the window.
+///
+/// Execute operations are not critical for the network, so they are held as well while the balance of
+/// the main operator account is below the configured minimum, leaving the remaining funds to commit
+/// blocks. Both situations are reported once via the notifier.
 struct ETHSender<DB: DatabaseInterface> {
     /// Ongoing operations queue.
     ongoing_ops: VecDeque<ETHOperation>,
     /// Connection to the database.
     db: DB,
-    /// Ethereum intermediator.
+    /// Ethereum intermediator of the main operator account.
     ethereum: EthereumGateway,
     /// Queue for ordered transaction processing.
     tx_queue: TxQueue,
-    /// Utility for managing the gas price for transactions.
+    /// Utility for managing the gas price for transactions of the main operator account.
     gas_adjuster: GasAdjuster<DB>,
+    /// Additional operator accounts with their own nonce lanes.
+    operator_lanes: Vec<OperatorLane<DB>>,
     /// Settings for the `ETHSender`.
     options: ETHSenderConfig,
+    /// Notifier for the failed and stuck transactions.
+    notifier: Notifier,
+    /// Whether sending transactions is paused after a failure.
+    paused: bool,
+    /// ID of the aggregated operation that failed the last pre-flight check.
+    /// Used to send only one notification per operation.
+    preflight_reverted_op: Option<i64>,
+    /// IDs of the operations with failed transactions that are sent again once
+    /// the in-flight transactions of the same account settle.
+    ops_awaiting_resend: HashSet<i64>,
+    /// Types of the operations held after reaching the spending limit.
+    /// Used to send only one notification until the operations are sent again.
+    spending_limit_reached: HashSet<AggregatedActionType>,
+    /// Whether the balance of the main operator account was below the minimum on the last check.
+    low_operator_balance: bool,
+}
+
+/// Additional operator account sending the operations of certain types.
+struct OperatorLane<DB: DatabaseInterface> {
+    /// Address of the operator account.
+    address: Address,
+    /// Types of the operations sent from the account.
+    action_types: Vec<AggregatedActionType>,
+    /// Ethereum intermediator signing the transactions with the account key.
+    ethereum: EthereumGateway,
+    /// Utility for managing the gas price for transactions of the account.
+    gas_adjuster: GasAdjuster<DB>,
 }
 
 impl<DB: DatabaseInterface> ETHSender<DB> {
-    pub async fn new(options: ETHSenderConfig, db: DB, ethereum: EthereumGateway) -> Self {
+    pub async fn new(
+        options: ETHSenderConfig,
+        db: DB,
+        ethereum: EthereumGateway,
+        operator_gateways: Vec<(OperatorAccount, EthereumGateway)>,
+        notifier: Notifier,
+    ) -> Self {
         let mut connection = db
             .acquire_connection()
             .await
@@ -157,14 +242,69 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .await
             .expect("Failed loading ETH operations stats");
 
-        let tx_queue = TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
-            .with_sent_pending_txs(ongoing_ops.len())
+        // Nonces of the new operator accounts start from their current nonces on the Ethereum.
+        for (account, gateway) in &operator_gateways {
+            let nonce = gateway
+                .pending_nonce()
+                .await
+                .expect("Can't get the nonce of the operator account");
+            db.initialize_operator_account(&mut transaction, account.address, nonce)
+                .await
+                .expect("Can't initialize the operator account");
+        }
+
+        // The main lane sends the operations not covered by the additional operator accounts.
+        let main_lane = [
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ]
+        .iter()
+        .copied()
+        .filter(|op_type| {
+            !operator_gateways
+                .iter()
+                .any(|(account, _)| account.action_types.contains(op_type))
+        })
+        .collect();
+        let mut lanes = vec![main_lane];
+        lanes.extend(
+            operator_gateways
+                .iter()
+                .map(|(account, _)| account.action_types.clone()),
+        );
+
+        let mut tx_queue_builder = TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
+            .with_lanes(lanes)
+            .with_sent_pending_operations(ongoing_ops.iter().map(|op| op.op_type).collect())
             .with_commit_operations_count(stats.last_committed_block)
             .with_verify_operations_count(stats.last_verified_block)
-            .with_execute_operations_count(stats.last_executed_block)
-            .build();
+            .with_execute_operations_count(stats.last_executed_block);
+        // Blocks of the unconfirmed operations can't be considered confirmed by the dependent operations.
+        let first_unconfirmed_block = |op_type: AggregatedActionType| {
+            ongoing_ops
+                .iter()
+                .filter(|op| op.op_type == op_type)
+                .filter_map(|op| op.op.as_ref())
+                .map(|(_, op)| op.get_block_range().0)
+                .min()
+        };
+        if let Some(block) = first_unconfirmed_block(AggregatedActionType::CommitBlocks) {
+            tx_queue_builder = tx_queue_builder.with_confirmed_commit_block(block - 1);
+        }
+        if let Some(block) =
+            first_unconfirmed_block(AggregatedActionType::PublishProofBlocksOnchain)
+        {
+            tx_queue_builder = tx_queue_builder.with_confirmed_verify_block(block - 1);
+        }
+        let tx_queue = tx_queue_builder.build();
+
+        let gas_adjuster = GasAdjuster::new(&db, options.sender.tx_type, None).await;
 
-        let gas_adjuster = GasAdjuster::new(&db).await;
+        // The paused state lasts until the restart: the failed operations are checked again on start.
+        db.set_paused(&mut transaction, false)
+            .await
+            .expect("Failed resetting the paused state");
 
         transaction
             .commit()
@@ -172,13 +312,49 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .expect("Failed commit database transaction");
         drop(connection);
 
+        // Transactions of the unconfirmed operations are tracked by the accounts that sent them,
+        // even if their action types are assigned to other accounts now.
+        for op in &ongoing_ops {
+            if let Some(address) = op.operator {
+                assert!(
+                    operator_gateways
+                        .iter()
+                        .any(|(account, _)| account.address == address),
+                    "Unconfirmed ETH operation <id: {}> was sent from the operator account {:?} which is not configured",
+                    op.id,
+                    address
+                );
+            }
+        }
+
+        let mut operator_lanes = Vec::new();
+        for (account, ethereum) in operator_gateways {
+            let gas_adjuster =
+                GasAdjuster::new(&db, options.sender.tx_type, Some(account.address)).await;
+            operator_lanes.push(OperatorLane {
+                address: account.address,
+                action_types: account.action_types,
+                ethereum,
+                gas_adjuster,
+            });
+        }
+
+        let notifier = notifier.with_mattermost_url(&options.sender.webhook_url);
+
         Self {
             ongoing_ops,
             db,
             ethereum,
             tx_queue,
             gas_adjuster,
+            operator_lanes,
             options,
+            notifier,
+            paused: false,
+            preflight_reverted_op: None,
+            ops_awaiting_resend: HashSet::new(),
+            spending_limit_reached: HashSet::new(),
+            low_operator_balance: false,
         }
     }
 
@@ -203,6 +379,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 self.gas_adjuster
                     .keep_updated(&self.ethereum, &self.db)
                     .await;
+                for lane in &mut self.operator_lanes {
+                    lane.gas_adjuster
+                        .keep_updated(&lane.ethereum, &self.db)
+                        .await;
+                }
             }
         }
     }
@@ -248,6 +429,10 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     ///
     /// It returns ethereum block number for which these things were done.
     async fn proceed_next_operations(&mut self, last_used_block: u64) -> u64 {
+        // Nothing is sent while the sender is paused after a failure.
+        if self.paused {
+            return last_used_block;
+        }
         let start = Instant::now();
 
         let current_block = match self.ethereum.block_number().await {
@@ -258,8 +443,30 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             }
         };
 
+        // Operations held by the spending limits are checked again on every iteration.
+        self.tx_queue.release_held();
+        // New operations would get nonces ahead of the failed ones waiting to be sent again,
+        // so the lanes of the failed operations are held until then.
+        for op_type in self.awaiting_resend_action_types() {
+            self.tx_queue.hold(op_type);
+        }
         while let Some(tx) = self.tx_queue.pop_front() {
-            if let Err(e) = self.initialize_operation(tx.clone(), current_block).await {
+            let result = match self.is_held_by_spending_limits(&tx).await {
+                Ok(false) => self.initialize_operation(tx.clone(), current_block).await,
+                Ok(true) => {
+                    // Operations of other types can still be sent.
+                    if let Err(err_message) = self.tx_queue.return_popped(tx.clone()) {
+                        panic!(
+                            "Failed return previous sent operation to the queue: {}",
+                            err_message
+                        );
+                    }
+                    self.tx_queue.hold(tx.op_type);
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
                 Self::process_error(e).await;
                 // Return the unperformed operation to the queue, since failing the
                 // operation initialization means that it was not stored in the database.
@@ -269,6 +476,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         err_message
                     );
                 }
+                // The returned operation would be popped again, so it's retried on the next iteration.
+                break;
             }
         }
 
@@ -283,6 +492,13 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
             // Commit the next operations (if any).
             while let Some(mut current_op) = self.ongoing_ops.pop_front() {
+                // Once the sender is paused, the remaining operations are left untouched.
+                // Failed operations are left untouched until they are sent again.
+                if self.paused || self.ops_awaiting_resend.contains(&current_op.id) {
+                    new_ongoing_ops.push_back(current_op);
+                    continue;
+                }
+
                 // We perform a commitment step here. In case of error, we suppose that this is some
                 // network issue which won't appear the next time, so we report the situation to the
                 // log and consider the operation pending (meaning that we won't process it on this
@@ -300,8 +516,15 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
                 match commitment {
                     OperationCommitment::Committed => {
-                        // Free a slot for the next tx in the queue.
-                        self.tx_queue.report_commitment();
+                        // Free a slot for the next tx in the lane and let the dependent
+                        // operations of the other lanes proceed.
+                        let (_, last_block) = current_op
+                            .op
+                            .as_ref()
+                            .map(|(_, op)| op.get_block_range())
+                            .expect("Committed ETH operation has no aggregated operation");
+                        self.tx_queue
+                            .report_commitment(current_op.op_type, last_block);
                     }
                     OperationCommitment::Pending => {
                         // Poll this operation on the next iteration.
@@ -315,6 +538,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             );
             // Store the ongoing operations for the next round.
             self.ongoing_ops = new_ongoing_ops;
+
+            if !self.paused {
+                if let Err(e) = self.resend_settled_failed_ops(current_block).await {
+                    Self::process_error(e).await;
+                }
+            }
         }
 
         metrics::histogram!("eth_sender.proceed_next_operations", start.elapsed());
@@ -337,11 +566,23 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
     /// Stores the new operation in the database and sends the corresponding transaction.
     async fn initialize_operation(&mut self, tx: TxData, current_block: u64) -> anyhow::Result<()> {
+        // The pending state of the node may not include the in-flight transactions yet
+        // (e.g. they were sent through another node), so the operations depending on them
+        // can't be simulated reliably.
+        if self.options.sender.preflight_check_enabled {
+            if self.ongoing_ops.is_empty() {
+                self.preflight_check(&tx).await?;
+            } else {
+                vlog::debug!(
+                    "Pre-flight check of the aggregated operation <id: {}, type: {:?}> is skipped: {} operations are in flight",
+                    tx.operation.0, tx.op_type, self.ongoing_ops.len()
+                );
+                metrics::increment_counter!("eth_sender.preflight_skipped", "op_type" => tx.op_type.to_string());
+            }
+        }
+
         let deadline_block = self.get_deadline_block(current_block);
-        let gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, None)
-            .await?;
+        let tx_fees = self.get_tx_fees(tx.op_type, None).await?;
 
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
@@ -354,10 +595,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 .db
                 .save_new_eth_tx(
                     &mut transaction,
+                    self.lane_operator(tx.op_type),
                     tx.op_type,
                     Some(tx.operation.clone()),
                     deadline_block as i64,
-                    gas_price,
+                    tx_fees.gas_price,
+                    tx_fees.priority_fee,
                     tx.raw.clone(),
                 )
                 .await?;
@@ -368,15 +611,20 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 op: Some(tx.operation),
                 nonce: assigned_data.nonce,
                 last_deadline_block: deadline_block,
-                last_used_gas_price: gas_price,
+                last_used_gas_price: tx_fees.gas_price,
+                last_used_priority_fee: tx_fees.priority_fee,
                 used_tx_hashes: vec![], // No hash yet, will be added below.
                 encoded_tx_data: tx.raw,
                 confirmed: false,
                 final_hash: None,
+                failed_attempts: 0,
+                operator: self.lane_operator(tx.op_type),
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let signed_tx = self
+                .sign_new_tx(self.lane_ethereum(new_op.op_type), &new_op)
+                .await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -396,7 +644,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
             new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
         );
-        if let Err(e) = self.ethereum.send_raw_tx(signed_tx.raw_tx).await {
+        if let Err(e) = self
+            .lane_ethereum(new_op.op_type)
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+        {
             // Sending tx error is not critical: this will result in transaction being considered stuck,
             // and resent. We can't do anything about this failure either, since it's most probably is not
             // related to the node logic, so we just log this error and pretend to have this operation
@@ -409,15 +661,176 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(())
     }
 
+    /// Simulates the transaction against the pending state.
+    /// Returns an error if the transaction would be reverted or the node failed to simulate it.
+    async fn preflight_check(&mut self, tx: &TxData) -> anyhow::Result<()> {
+        let (op_id, op) = &tx.operation;
+        match self
+            .lane_ethereum(tx.op_type)
+            .estimate_gas(tx.raw.clone())
+            .await?
+        {
+            GasEstimation::Reverted(reason) => {
+                vlog::error!(
+                    "Pre-flight check of the aggregated operation <id: {}, type: {:?}> failed, transaction is not sent. Revert reason: {}",
+                    op_id, tx.op_type, reason
+                );
+                metrics::increment_counter!("eth_sender.preflight_reverted", "op_type" => tx.op_type.to_string());
+
+                if self.preflight_reverted_op != Some(*op_id) {
+                    self.preflight_reverted_op = Some(*op_id);
+                    self.notify(NotifyEvent::EthPreflightFailed {
+                        op_id: *op_id,
+                        op_type: tx.op_type.to_string(),
+                        revert_reason: reason.clone(),
+                    })
+                    .await;
+                }
+
+                Err(format_err!(
+                    "Pre-flight check of the aggregated operation {} failed: {}",
+                    op_id,
+                    reason
+                ))
+            }
+            GasEstimation::Success(estimated_gas) => {
+                let gas_limit = Self::gas_limit_for_aggregated_op(op);
+                let difference = if estimated_gas > gas_limit {
+                    estimated_gas - gas_limit
+                } else {
+                    gas_limit - estimated_gas
+                };
+                let divergence_percent = (difference * U256::from(100) / gas_limit).as_u64();
+                metrics::histogram!("eth_sender.preflight_gas_divergence_percent", divergence_percent as f64, "op_type" => tx.op_type.to_string());
+
+                if divergence_percent > self.options.sender.preflight_gas_divergence_percent {
+                    vlog::warn!(
+                        "Estimated gas for the aggregated operation <id: {}, type: {:?}> diverges from the gas limit by {}%: estimated gas: {}, gas limit: {}",
+                        op_id, tx.op_type, divergence_percent, estimated_gas, gas_limit
+                    );
+                    metrics::increment_counter!("eth_sender.preflight_gas_divergence", "op_type" => tx.op_type.to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks whether the operation must be held by the spending limits:
+    /// - The fees of the transactions of the same type within the rolling window, including
+    ///   the pending transactions and the new one, must not exceed the configured limit.
+    /// - Execute operations sent from the main operator account are held while its balance is low.
+    async fn is_held_by_spending_limits(&mut self, tx: &TxData) -> anyhow::Result<bool> {
+        if let Some(limit) = self.options.spending_limit.limit(tx.op_type) {
+            let limit = U256::from(limit) * U256::from(WEI_IN_GWEI);
+
+            let mut connection = self.db.acquire_connection().await?;
+            let spent_fee = self
+                .db
+                .load_spent_fee(
+                    &mut connection,
+                    tx.op_type,
+                    self.options.spending_limit.period(),
+                )
+                .await?;
+            drop(connection);
+            // Fees of the pending transactions can't exceed their gas limits multiplied by the used gas prices.
+            let pending_fee = self
+                .ongoing_ops
+                .iter()
+                .filter(|op| op.op_type == tx.op_type)
+                .fold(U256::zero(), |fee, op| {
+                    fee + self.gas_limit_for_op(op) * op.last_used_gas_price
+                });
+            let gas_price = self.lane_ethereum(tx.op_type).get_gas_price().await?;
+            let new_tx_fee = Self::gas_limit_for_aggregated_op(&tx.operation.1) * gas_price;
+
+            let expected_fee = spent_fee + pending_fee + new_tx_fee;
+            if expected_fee > limit {
+                let details = format!(
+                    "spent: {} gwei, pending: {} gwei, new transaction: {} gwei, limit: {} gwei",
+                    spent_fee / U256::from(WEI_IN_GWEI),
+                    pending_fee / U256::from(WEI_IN_GWEI),
+                    new_tx_fee / U256::from(WEI_IN_GWEI),
+                    limit / U256::from(WEI_IN_GWEI),
+                );
+                vlog::warn!(
+                    "Spending limit for {:?} transactions is reached, the aggregated operation <id: {}> is held: {}",
+                    tx.op_type, tx.operation.0, details
+                );
+                metrics::increment_counter!("eth_sender.spending_limit_reached", "op_type" => tx.op_type.to_string());
+
+                if self.spending_limit_reached.insert(tx.op_type) {
+                    self.notify(NotifyEvent::EthSpendingLimitReached {
+                        op_type: tx.op_type.to_string(),
+                        details,
+                    })
+                    .await;
+                }
+                return Ok(true);
+            }
+            self.spending_limit_reached.remove(&tx.op_type);
+        }
+
+        // Only the balance of the main account is checked, since it's used to commit blocks.
+        let min_balance = match self.options.spending_limit.min_operator_balance {
+            Some(min_balance) if self.lane_operator(tx.op_type).is_none() => {
+                U256::from(min_balance) * U256::from(WEI_IN_GWEI)
+            }
+            _ => return Ok(false),
+        };
+        let balance = self.ethereum.sender_eth_balance().await?;
+        metrics::gauge!(
+            "eth_sender.operator_balance_gwei",
+            (balance / U256::from(WEI_IN_GWEI)).as_u64() as f64
+        );
+        if balance >= min_balance {
+            self.low_operator_balance = false;
+            return Ok(false);
+        }
+
+        let details = format!(
+            "balance: {} gwei, minimum: {} gwei",
+            balance / U256::from(WEI_IN_GWEI),
+            min_balance / U256::from(WEI_IN_GWEI),
+        );
+        if !self.low_operator_balance {
+            self.low_operator_balance = true;
+            vlog::error!(
+                "Balance of the operator account is low, execute operations are held: {}",
+                details
+            );
+            self.notify(NotifyEvent::LowOperatorBalance {
+                operator: self.options.sender.operator_commit_eth_addr,
+                details: format!("{}. Execute transactions are held", details),
+            })
+            .await;
+        }
+
+        if tx.op_type == AggregatedActionType::ExecuteBlocks {
+            metrics::increment_counter!("eth_sender.low_balance_held");
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     /// Helper method to obtain the string representation of the Ethereum transaction.
     /// Intended to be used for log entries.
     fn eth_tx_description(&self, tx: &SignedCallResult) -> String {
         // Gas price in gwei (wei / 10^9).
         let gas_price = tx.gas_price / (1_000_000_000);
-        format!(
-            "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
-            tx.hash, gas_price, tx.nonce
-        )
+        match tx.max_priority_fee_per_gas {
+            Some(priority_fee) => format!(
+                "<hash: {:#x}; max fee: {} gwei; priority fee: {} gwei; nonce: {}>",
+                tx.hash,
+                gas_price,
+                priority_fee / (1_000_000_000),
+                tx.nonce
+            ),
+            None => format!(
+                "<hash: {:#x}; gas price: {} gwei; nonce: {}>",
+                tx.hash, gas_price, tx.nonce
+            ),
+        }
     }
 
     /// Helper method to obtain the string representation of the zkSync operation.
@@ -472,7 +885,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // Transaction is pending, nothing to do yet.
                     return Ok(OperationCommitment::Pending);
                 }
-                TxCheckOutcome::Committed => {
+                TxCheckOutcome::Committed(fee) => {
                     let mut connection = self.db.acquire_connection().await?;
                     let mut transaction = connection.start_transaction().await?;
 
@@ -494,7 +907,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // operations out of order.
                     if !self
                         .db
-                        .is_previous_operation_confirmed(&mut transaction, op)
+                        .is_previous_operation_confirmed(
+                            &mut transaction,
+                            op,
+                            &self.lane_action_types(op.op_type),
+                        )
                         .await?
                     {
                         vlog::info!("ETH Operation <id: {}> is confirmed ahead of time, considering it pending for now", op.id);
@@ -506,16 +923,21 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         op.id, op.op_type, tx_hash, self.zksync_operation_description(op),
                     );
                     self.db
-                        .confirm_operation(&mut transaction, tx_hash, op)
+                        .confirm_operation(&mut transaction, tx_hash, op, &fee)
                         .await?;
                     transaction.commit().await?;
+                    metrics::counter!(
+                        "eth_sender.paid_fee_gwei",
+                        (fee.total() / U256::from(WEI_IN_GWEI)).as_u64(),
+                        "op_type" => op.op_type.to_string()
+                    );
                     return Ok(OperationCommitment::Committed);
                 }
                 TxCheckOutcome::Stuck => {
                     // We do nothing for a stuck transaction. If this will be
                     // the last entry of the list, a new tx will be sent.
                 }
-                TxCheckOutcome::Failed(receipt) => {
+                TxCheckOutcome::Failed(receipt, fee) => {
                     vlog::warn!(
                         "ETH transaction failed: tx: {:#x}, op_type: {:?}, op: {:?}; tx_receipt: {:#?} ",
                         tx_hash,
@@ -523,8 +945,19 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         op.op,
                         receipt,
                     );
+                    // Gas used by the failed transaction is paid anyway.
+                    let mut connection = self.db.acquire_connection().await?;
+                    self.db
+                        .save_failed_tx_fee(&mut connection, tx_hash, op, &fee)
+                        .await?;
+                    drop(connection);
+                    metrics::counter!(
+                        "eth_sender.paid_fee_gwei",
+                        (fee.total() / U256::from(WEI_IN_GWEI)).as_u64(),
+                        "op_type" => op.op_type.to_string()
+                    );
                     // Process the failure according to the chosen policy.
-                    self.failure_handler(&receipt).await;
+                    return self.failure_handler(op, &receipt).await;
                 }
             }
         }
@@ -545,6 +978,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 op.id,
                 deadline_block as i64,
                 new_tx.gas_price,
+                new_tx.max_priority_fee_per_gas,
             )
             .await?;
         self.db
@@ -556,26 +990,273 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             op.id,
             self.eth_tx_description(&new_tx),
         );
-        self.ethereum.send_raw_tx(new_tx.raw_tx).await?;
+        self.operator_ethereum(op.operator)
+            .send_raw_tx(new_tx.raw_tx)
+            .await?;
         transaction.commit().await?;
 
+        let attempts = op.used_tx_hashes.len();
+        if attempts % STUCK_TX_NOTIFY_ATTEMPTS == 0 {
+            self.notify(NotifyEvent::EthTxStuck {
+                op_id: op.id,
+                op_type: op.op_type.to_string(),
+                attempts,
+            })
+            .await;
+        }
+
         metrics::histogram!("eth_sender.perform_commitment_step", start.elapsed());
         Ok(OperationCommitment::Pending)
     }
 
-    /// Handles a transaction execution failure by reporting the issue to the log
-    /// and terminating the node.
-    async fn failure_handler(&self, receipt: &TransactionReceipt) -> ! {
+    /// Handles a transaction execution failure according to the configured failure policy.
+    async fn failure_handler(
+        &mut self,
+        op: &mut ETHOperation,
+        receipt: &TransactionReceipt,
+    ) -> anyhow::Result<OperationCommitment> {
         vlog::error!(
             "Ethereum transaction unexpectedly failed. Receipt: {:#?}",
             receipt
         );
-        if let Ok(Some(reason)) = self.ethereum.failure_reason(receipt.transaction_hash).await {
-            vlog::error!("Failure reason for Ethereum tx: {:#?}", reason);
+        let failure_info = match self
+            .operator_ethereum(op.operator)
+            .failure_reason(receipt.transaction_hash)
+            .await
+        {
+            Ok(Some(reason)) => {
+                vlog::error!("Failure reason for Ethereum tx: {:#?}", reason);
+                Some(reason)
+            }
+            _ => {
+                vlog::error!("Unable to receive failure reason for Ethereum tx");
+                None
+            }
+        };
+        if self.options.sender.failure_policy == FailurePolicy::Panic {
+            panic!("Cannot operate after unexpected TX failure");
+        }
+
+        let failure = TxFailure::from_failure_info(failure_info.as_ref());
+        let details = match &failure_info {
+            Some(info) => format!("{:?}, revert reason: {}", failure, info.revert_reason),
+            None => format!("{:?}", failure),
+        };
+
+        if failure.is_retryable() && op.failed_attempts < self.options.sender.max_failure_retries {
+            vlog::warn!(
+                "Failed ETH Operation <id: {}> will be sent again once the in-flight transactions settle. Failure: {}",
+                op.id,
+                details
+            );
+            self.ops_awaiting_resend.insert(op.id);
+            self.notify(NotifyEvent::EthTxFailed {
+                tx_hash: receipt.transaction_hash,
+                details: format!("{}. Transaction will be sent again", details),
+            })
+            .await;
         } else {
-            vlog::error!("Unable to receive failure reason for Ethereum tx");
+            vlog::error!(
+                "Pausing the sender after the failed ETH Operation <id: {}>. Failure: {}",
+                op.id,
+                details
+            );
+            let mut connection = self.db.acquire_connection().await?;
+            self.db.set_paused(&mut connection, true).await?;
+            self.paused = true;
+            self.notify(NotifyEvent::EthSenderPaused {
+                tx_hash: receipt.transaction_hash,
+                details,
+            })
+            .await;
+        }
+
+        Ok(OperationCommitment::Pending)
+    }
+
+    /// Sends the operations with failed transactions again once no other transactions of
+    /// their lanes are in flight. Until then, the nonce that the operation gets can't be
+    /// determined, and transactions sent after the failed one may fail for the same reason.
+    /// Failed operations of the same lane are sent again in the order of their old nonces.
+    async fn resend_settled_failed_ops(&mut self, current_block: u64) -> anyhow::Result<()> {
+        if self.ops_awaiting_resend.is_empty() {
+            return Ok(());
+        }
+
+        let in_flight_lanes: HashSet<_> = self
+            .ongoing_ops
+            .iter()
+            .filter(|op| !self.ops_awaiting_resend.contains(&op.id))
+            .map(|op| op.operator)
+            .collect();
+        let mut settled_ops: Vec<_> = (0..self.ongoing_ops.len())
+            .filter(|&idx| {
+                let op = &self.ongoing_ops[idx];
+                self.ops_awaiting_resend.contains(&op.id)
+                    && !in_flight_lanes.contains(&self.lane_operator(op.op_type))
+            })
+            .collect();
+        settled_ops.sort_by_key(|&idx| self.ongoing_ops[idx].nonce);
+
+        for idx in settled_ops {
+            let mut op = self.ongoing_ops[idx].clone();
+            self.resend_failed_tx(&mut op, current_block).await?;
+            self.ops_awaiting_resend.remove(&op.id);
+            self.ongoing_ops[idx] = op;
+        }
+        Ok(())
+    }
+
+    /// Sends the transaction for the operation again after a failure.
+    /// The nonce of the failed transaction is already used, so the operation gets a new one.
+    async fn resend_failed_tx(
+        &mut self,
+        op: &mut ETHOperation,
+        current_block: u64,
+    ) -> anyhow::Result<()> {
+        let deadline_block = self.get_deadline_block(current_block);
+        let tx_fees = self.get_tx_fees(op.op_type, None).await?;
+
+        let mut connection = self.db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+
+        // The operation is updated only after the database transaction is committed.
+        let mut new_op = op.clone();
+        new_op.nonce = self
+            .db
+            .reset_failed_eth_tx(&mut transaction, op.id, self.lane_operator(op.op_type))
+            .await?;
+        new_op.failed_attempts += 1;
+        new_op.operator = self.lane_operator(op.op_type);
+        new_op.last_deadline_block = deadline_block;
+        new_op.last_used_gas_price = tx_fees.gas_price;
+        new_op.last_used_priority_fee = tx_fees.priority_fee;
+
+        let signed_tx = self
+            .sign_new_tx(self.lane_ethereum(op.op_type), &new_op)
+            .await?;
+        new_op.used_tx_hashes = vec![signed_tx.hash];
+        self.db
+            .update_eth_tx(
+                &mut transaction,
+                op.id,
+                deadline_block as i64,
+                tx_fees.gas_price,
+                tx_fees.priority_fee,
+            )
+            .await?;
+        self.db
+            .add_hash_entry(&mut transaction, op.id, &signed_tx.hash)
+            .await?;
+
+        vlog::info!(
+            "Failed tx processing: sending tx for op, eth_op_id: {}; ETH tx: {}",
+            op.id,
+            self.eth_tx_description(&signed_tx),
+        );
+        self.lane_ethereum(op.op_type)
+            .send_raw_tx(signed_tx.raw_tx)
+            .await?;
+        transaction.commit().await?;
+
+        *op = new_op;
+        Ok(())
+    }
+
+    /// Returns the types of the operations sent from the same accounts as the operations
+    /// waiting to be sent again after a failure.
+    fn awaiting_resend_action_types(&self) -> Vec<AggregatedActionType> {
+        let mut action_types = Vec::new();
+        for op in &self.ongoing_ops {
+            if self.ops_awaiting_resend.contains(&op.id) {
+                action_types.extend(self.lane_action_types(op.op_type));
+            }
+        }
+        action_types
+    }
+
+    /// Returns the additional operator account sending the operations of the given type, if any.
+    fn operator_lane(&self, op_type: AggregatedActionType) -> Option<&OperatorLane<DB>> {
+        self.operator_lanes
+            .iter()
+            .find(|lane| lane.action_types.contains(&op_type))
+    }
+
+    /// Returns the address of the additional operator account sending the operations
+    /// of the given type, or `None` if they are sent from the main account.
+    fn lane_operator(&self, op_type: AggregatedActionType) -> Option<Address> {
+        self.operator_lane(op_type).map(|lane| lane.address)
+    }
+
+    /// Returns the Ethereum intermediator of the account sending the operations of the given type.
+    fn lane_ethereum(&self, op_type: AggregatedActionType) -> &EthereumGateway {
+        self.operator_lane(op_type)
+            .map(|lane| &lane.ethereum)
+            .unwrap_or(&self.ethereum)
+    }
+
+    /// Returns the Ethereum intermediator of the account that sent the transactions
+    /// of the operation, i.e. the additional operator account with the given address
+    /// or the main account if `operator` is `None`.
+    fn operator_ethereum(&self, operator: Option<Address>) -> &EthereumGateway {
+        match operator {
+            Some(address) => self
+                .operator_lanes
+                .iter()
+                .find(|lane| lane.address == address)
+                .map(|lane| &lane.ethereum)
+                .unwrap_or_else(|| panic!("Operator account {:?} is not configured", address)),
+            None => &self.ethereum,
+        }
+    }
+
+    /// Returns the types of the operations sent from the same account as the given type.
+    fn lane_action_types(&self, op_type: AggregatedActionType) -> Vec<AggregatedActionType> {
+        match self.operator_lane(op_type) {
+            Some(lane) => lane.action_types.clone(),
+            None => [
+                AggregatedActionType::CommitBlocks,
+                AggregatedActionType::PublishProofBlocksOnchain,
+                AggregatedActionType::ExecuteBlocks,
+            ]
+            .iter()
+            .copied()
+            .filter(|op_type| self.operator_lane(*op_type).is_none())
+            .collect(),
+        }
+    }
+
+    /// Obtains the fees for the transaction from the gas adjuster of the account
+    /// sending the operations of the given type.
+    async fn get_tx_fees(
+        &mut self,
+        op_type: AggregatedActionType,
+        old_tx_fees: Option<TxFees>,
+    ) -> anyhow::Result<TxFees> {
+        match self
+            .operator_lanes
+            .iter_mut()
+            .find(|lane| lane.action_types.contains(&op_type))
+        {
+            Some(lane) => {
+                lane.gas_adjuster
+                    .get_tx_fees(&lane.ethereum, old_tx_fees)
+                    .await
+            }
+            None => {
+                self.gas_adjuster
+                    .get_tx_fees(&self.ethereum, old_tx_fees)
+                    .await
+            }
         }
-        panic!("Cannot operate after unexpected TX failure");
+    }
+
+    /// Sends a notification about the event. Failure to send it is only logged.
+    async fn notify(&self, event: NotifyEvent) {
+        let kind = event.kind();
+        self.notifier.notify(event).await.unwrap_or_else(|e| {
+            vlog::error!("Failed to send a {} notification: {}", kind, e);
+        });
     }
 
     /// Helper method encapsulating the logic of determining the next deadline block.
@@ -593,7 +1274,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         current_block: u64,
     ) -> anyhow::Result<TxCheckOutcome> {
         let status = self
-            .ethereum
+            .operator_ethereum(op.operator)
             .get_tx_status(tx_hash, Some(current_block))
             .await?;
 
@@ -602,7 +1283,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             Some(status) if status.success => {
                 // Check if transaction has enough confirmations.
                 if status.confirmations >= self.options.sender.wait_confirmations {
-                    TxCheckOutcome::Committed
+                    TxCheckOutcome::Committed(self.paid_fee(op, &status))
                 } else {
                     TxCheckOutcome::Pending
                 }
@@ -616,7 +1297,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         "Receipt should exist for a failed transaction"
                     );
 
-                    TxCheckOutcome::Failed(Box::new(status.receipt.unwrap()))
+                    let fee = self.paid_fee(op, &status);
+                    TxCheckOutcome::Failed(Box::new(status.receipt.unwrap()), fee)
                 } else {
                     TxCheckOutcome::Pending
                 }
@@ -634,15 +1316,25 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(outcome)
     }
 
+    /// Returns the fee paid for the mined transaction.
+    /// If the node doesn't report the paid fee, its upper bound is used.
+    fn paid_fee(&self, op: &ETHOperation, status: &ExecutedTxStatus) -> PaidFee {
+        PaidFee {
+            gas_used: status.gas_used.unwrap_or_else(|| self.gas_limit_for_op(op)),
+            gas_price: status.effective_gas_price.unwrap_or(op.last_used_gas_price),
+        }
+    }
+
     /// Creates a new Ethereum operation.
     async fn sign_new_tx(
+        &self,
         ethereum: &EthereumGateway,
         op: &ETHOperation,
     ) -> anyhow::Result<SignedCallResult> {
         let tx_options = {
             // We set the gas limit for commit / verify operations as pre-calculated estimation.
             // This estimation is a higher bound based on a pre-calculated cost of every operation in the block.
-            let gas_limit = Self::gas_limit_for_op(op);
+            let gas_limit = self.gas_limit_for_op(op);
 
             assert!(
                 gas_limit > 0.into(),
@@ -656,14 +1348,15 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 gas_limit
             );
 
-            Options {
-                nonce: Some(op.nonce),
-                gas_price: Some(op.last_used_gas_price),
-                gas: Some(gas_limit),
-                max_fee_per_gas: None,
-                max_priority_fee_per_gas: None,
-                ..Default::default()
-            }
+            let tx_fees = TxFees {
+                gas_price: op.last_used_gas_price,
+                priority_fee: op.last_used_priority_fee,
+            };
+            Options::with(move |opt| {
+                set_tx_fees(opt, tx_fees);
+                opt.nonce = Some(op.nonce);
+                opt.gas = Some(gas_limit);
+            })
         };
 
         let signed_tx = ethereum
@@ -674,11 +1367,21 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
-    fn gas_limit_for_op(op: &ETHOperation) -> U256 {
-        let (_, op) = op
+    /// The limit is increased for every failed attempt to send the operation.
+    fn gas_limit_for_op(&self, eth_op: &ETHOperation) -> U256 {
+        let (_, op) = eth_op
             .op
             .as_ref()
             .expect("Operation not found - can't compute gas limit");
+        let gas_limit = Self::gas_limit_for_aggregated_op(op);
+
+        let increase_percent =
+            self.options.sender.failed_tx_gas_limit_increase_percent * eth_op.failed_attempts;
+        gas_limit * U256::from(100 + increase_percent) / U256::from(100)
+    }
+
+    /// Estimates the gas limit for the aggregated operation with `GasCounter`.
+    fn gas_limit_for_aggregated_op(op: &AggregatedOperation) -> U256 {
         match op {
             AggregatedOperation::CommitBlocks(commit) => {
                 GasCounter::commit_gas_limit_aggregated(&commit.blocks)
@@ -705,10 +1408,14 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
 
         let raw_tx = stuck_tx.encoded_tx_data.clone();
-        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
+        let signed_tx = self
+            .operator_ethereum(stuck_tx.operator)
+            .sign_prepared_tx(raw_tx, tx_options)
+            .await?;
 
         stuck_tx.last_deadline_block = deadline_block;
         stuck_tx.last_used_gas_price = signed_tx.gas_price;
+        stuck_tx.last_used_priority_fee = signed_tx.max_priority_fee_per_gas;
         stuck_tx.used_tx_hashes.push(signed_tx.hash);
 
         Ok(signed_tx)
@@ -720,14 +1427,16 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         &mut self,
         stuck_tx: &ETHOperation,
     ) -> anyhow::Result<Options> {
-        let old_tx_gas_price = stuck_tx.last_used_gas_price;
+        let old_tx_fees = TxFees {
+            gas_price: stuck_tx.last_used_gas_price,
+            priority_fee: stuck_tx.last_used_priority_fee,
+        };
 
-        let new_gas_price = self
-            .gas_adjuster
-            .get_gas_price(&self.ethereum, Some(old_tx_gas_price))
+        let new_tx_fees = self
+            .get_tx_fees(stuck_tx.op_type, Some(old_tx_fees))
             .await?;
         let nonce = stuck_tx.nonce;
-        let gas_limit = Self::gas_limit_for_op(stuck_tx);
+        let gas_limit = self.gas_limit_for_op(stuck_tx);
 
         assert!(
             gas_limit > 0.into(),
@@ -736,16 +1445,18 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         );
 
         vlog::info!(
-            "Replacing tx: hash: {:#x}, old_gas: {}, new_gas: {}, used nonce: {}, gas limit: {}",
+            "Replacing tx: hash: {:#x}, old_gas: {}, new_gas: {}, old_priority_fee: {:?}, new_priority_fee: {:?}, used nonce: {}, gas limit: {}",
             stuck_tx.used_tx_hashes.last().unwrap(),
-            old_tx_gas_price,
-            new_gas_price,
+            old_tx_fees.gas_price,
+            new_tx_fees.gas_price,
+            old_tx_fees.priority_fee,
+            new_tx_fees.priority_fee,
             nonce,
             gas_limit,
         );
 
         Ok(Options::with(move |opt| {
-            opt.gas_price = Some(new_gas_price);
+            set_tx_fees(opt, new_tx_fees);
             opt.nonce = Some(nonce);
             opt.gas = Some(gas_limit);
         }))
@@ -796,16 +1507,34 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 }
 
+/// Sets the gas price of the legacy transaction or the max fee and the priority fee
+/// of the EIP-1559 transaction to the tx options.
+fn set_tx_fees(options: &mut Options, fees: TxFees) {
+    match fees.priority_fee {
+        Some(priority_fee) => {
+            options.max_fee_per_gas = Some(fees.gas_price);
+            options.max_priority_fee_per_gas = Some(priority_fee);
+        }
+        None => {
+            options.gas_price = Some(fees.gas_price);
+        }
+    }
+}
+
 #[must_use]
 pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
+    operator_gateways: Vec<(OperatorAccount, EthereumGateway)>,
     options: ETHSenderConfig,
+    notifier_config: &NotifierConfig,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
+    let notifier = Notifier::from_config(notifier_config);
 
     tokio::spawn(async move {
-        let eth_sender = ETHSender::new(options, db, eth_gateway).await;
+        let eth_sender =
+            ETHSender::new(options, db, eth_gateway, operator_gateways, notifier).await;
 
         eth_sender.run().await
     })
@@ -1,22 +1,28 @@
 //! Mocking utilities for tests.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 // External uses
 use tokio::sync::RwLock;
 use web3::contract::Options;
-use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_basic_types::{Address, BlockNumber, H256, U256};
 // Workspace uses
-use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender};
+use zksync_config::configs::eth_sender::{
+    ETHSenderConfig, EthTxType, FailurePolicy, GasLimit, OperatorAccount, Sender, SpendingLimit,
+};
 use zksync_eth_client::EthereumGateway;
+use zksync_notifier::{Notifier, NotifierBackend, NotifyEvent};
 use zksync_storage::{ethereum::records::ETHParams, StorageProcessor};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
 // Local uses
 use super::ETHSender;
 use crate::database::DatabaseInterface;
-use crate::transactions::ETHStats;
+use crate::gas_adjuster::max_fee_per_gas;
+use crate::transactions::{ETHStats, PaidFee};
 use zksync_eth_client::clients::mock::MockEthereum;
 
 /// Mock database is capable of recording all the incoming requests for the further analysis.
@@ -26,6 +32,17 @@ pub(crate) struct MockDatabase {
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     eth_parameters: RwLock<ETHParams>,
+    operator_accounts: RwLock<HashMap<Address, MockOperatorAccount>>,
+    /// Fees paid for the mined transactions along with their confirmation time.
+    paid_fees: RwLock<Vec<(AggregatedActionType, U256, Instant)>>,
+}
+
+/// Stored state of the additional operator account.
+#[derive(Debug, Clone)]
+pub(crate) struct MockOperatorAccount {
+    pub nonce: U256,
+    pub gas_price_limit: U256,
+    pub average_gas_price: Option<U256>,
 }
 
 impl MockDatabase {
@@ -41,6 +58,8 @@ impl MockDatabase {
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
             eth_parameters: RwLock::new(eth_parameters),
+            operator_accounts: Default::default(),
+            paid_fees: Default::default(),
         }
     }
 
@@ -88,6 +107,63 @@ impl MockDatabase {
         assert!(is_confirmed);
     }
 
+    /// Every operation stored for the main account and every its resent failed transaction
+    /// uses its own nonce.
+    fn next_nonce(eth_operations: &[ETHOperation]) -> usize {
+        let main_operations = eth_operations.iter().filter(|op| op.operator.is_none());
+        main_operations
+            .map(|op| 1 + op.failed_attempts as usize)
+            .sum()
+    }
+
+    /// Returns the next nonce of the operator account, or of the main account if `operator` is `None`.
+    async fn assign_nonce(
+        &self,
+        eth_operations: &[ETHOperation],
+        operator: Option<Address>,
+    ) -> U256 {
+        match operator {
+            Some(address) => {
+                let mut operator_accounts = self.operator_accounts.write().await;
+                let account = operator_accounts
+                    .get_mut(&address)
+                    .expect("Operator account is not initialized");
+                let nonce = account.nonce;
+                account.nonce += U256::one();
+                nonce
+            }
+            None => Self::next_nonce(eth_operations).into(),
+        }
+    }
+
+    /// Returns the stored state of the operator account.
+    pub async fn operator_account(&self, address: Address) -> Option<MockOperatorAccount> {
+        self.operator_accounts.read().await.get(&address).cloned()
+    }
+
+    /// Returns whether the sender was paused after a failed transaction.
+    pub async fn is_paused(&self) -> bool {
+        self.eth_parameters.read().await.paused
+    }
+
+    /// Stores the fee paid for a transaction of the given type confirmed `ago`.
+    pub async fn add_paid_fee(&self, op_type: AggregatedActionType, fee: U256, ago: Duration) {
+        self.paid_fees
+            .write()
+            .await
+            .push((op_type, fee, Instant::now() - ago));
+    }
+
+    /// Returns the total fee paid for the transactions of the given type.
+    pub async fn paid_fee(&self, op_type: AggregatedActionType) -> U256 {
+        self.paid_fees
+            .read()
+            .await
+            .iter()
+            .filter(|(fee_op_type, _, _)| *fee_op_type == op_type)
+            .fold(U256::zero(), |paid_fee, (_, fee, _)| paid_fee + fee)
+    }
+
     /// Returns the stored average gas price.
     pub async fn average_gas_price(&self) -> U256 {
         let eth_parameters = self.eth_parameters.read().await;
@@ -142,9 +218,20 @@ impl DatabaseInterface for MockDatabase {
     async fn update_gas_price_params(
         &self,
         _connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         gas_price_limit: U256,
         average_gas_price: U256,
     ) -> anyhow::Result<()> {
+        if let Some(address) = operator {
+            let mut operator_accounts = self.operator_accounts.write().await;
+            let account = operator_accounts
+                .get_mut(&address)
+                .expect("Operator account is not initialized");
+            account.gas_price_limit = gas_price_limit;
+            account.average_gas_price = Some(average_gas_price);
+            return Ok(());
+        }
+
         let mut eth_parameters = self.eth_parameters.write().await;
         eth_parameters.gas_price_limit =
             i64::try_from(gas_price_limit).expect("Can't convert U256 to i64");
@@ -197,39 +284,42 @@ impl DatabaseInterface for MockDatabase {
         Ok(unconfirmed_operations)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn save_new_eth_tx(
         &self,
         _connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
         op_type: AggregatedActionType,
         op: Option<(i64, AggregatedOperation)>,
         deadline_block: i64,
         used_gas_price: U256,
+        used_priority_fee: Option<U256>,
         encoded_tx_data: Vec<u8>,
     ) -> anyhow::Result<InsertedOperationResponse> {
         let mut eth_operations = self.eth_operations.write().await;
         let id = eth_operations.len() as i64;
-        let nonce = eth_operations.len();
+        let nonce = self.assign_nonce(&eth_operations, operator).await;
 
         // Store with the assigned ID.
         let eth_operation = ETHOperation {
             id,
             op_type,
             op,
-            nonce: nonce.into(),
+            nonce,
             last_deadline_block: deadline_block as u64,
             last_used_gas_price: used_gas_price,
+            last_used_priority_fee: used_priority_fee,
             used_tx_hashes: vec![],
             encoded_tx_data,
             confirmed: false,
             final_hash: None,
+            failed_attempts: 0,
+            operator,
         };
 
         eth_operations.push(eth_operation);
 
-        let response = InsertedOperationResponse {
-            id,
-            nonce: nonce.into(),
-        };
+        let response = InsertedOperationResponse { id, nonce };
 
         Ok(response)
     }
@@ -261,6 +351,7 @@ impl DatabaseInterface for MockDatabase {
         eth_op_id: EthOpId,
         new_deadline_block: i64,
         new_gas_value: U256,
+        new_priority_fee: Option<U256>,
     ) -> anyhow::Result<()> {
         let mut eth_operations = self.eth_operations.write().await;
         let eth_op = eth_operations
@@ -270,6 +361,7 @@ impl DatabaseInterface for MockDatabase {
         if let Some(eth_op) = eth_op {
             eth_op.last_deadline_block = new_deadline_block as u64;
             eth_op.last_used_gas_price = new_gas_value;
+            eth_op.last_used_priority_fee = new_priority_fee;
         } else {
             panic!("Attempt to update tx that is not unconfirmed");
         }
@@ -277,11 +369,66 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn reset_failed_eth_tx(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        operator: Option<Address>,
+    ) -> anyhow::Result<U256> {
+        let mut eth_operations = self.eth_operations.write().await;
+        let nonce = self.assign_nonce(&eth_operations, operator).await;
+        let eth_op = eth_operations
+            .iter_mut()
+            .find(|eth_op| eth_op.id == eth_op_id && !eth_op.confirmed);
+
+        if let Some(eth_op) = eth_op {
+            eth_op.nonce = nonce;
+            eth_op.failed_attempts += 1;
+            eth_op.operator = operator;
+            eth_op.used_tx_hashes.clear();
+        } else {
+            panic!("Attempt to update tx that is not unconfirmed");
+        }
+
+        Ok(nonce)
+    }
+
+    async fn initialize_operator_account(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        address: Address,
+        nonce: U256,
+    ) -> anyhow::Result<()> {
+        let gas_price_limit = self.eth_parameters.read().await.gas_price_limit.into();
+        self.operator_accounts
+            .write()
+            .await
+            .entry(address)
+            .or_insert(MockOperatorAccount {
+                nonce,
+                gas_price_limit,
+                average_gas_price: None,
+            });
+
+        Ok(())
+    }
+
+    async fn set_paused(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        paused: bool,
+    ) -> anyhow::Result<()> {
+        self.eth_parameters.write().await.paused = paused;
+
+        Ok(())
+    }
+
     async fn confirm_operation(
         &self,
         _connection: &mut StorageProcessor<'_>,
         hash: &H256,
-        _op: &ETHOperation,
+        op: &ETHOperation,
+        fee: &PaidFee,
     ) -> anyhow::Result<()> {
         let mut eth_operations = self.eth_operations.write().await;
         let mut op_idx: Option<i64> = None;
@@ -298,14 +445,57 @@ impl DatabaseInterface for MockDatabase {
             op_idx.is_some(),
             "Request to confirm operation that was not stored"
         );
+        self.add_paid_fee(op.op_type, fee.total(), Duration::default())
+            .await;
+
+        Ok(())
+    }
+
+    async fn save_failed_tx_fee(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _hash: &H256,
+        op: &ETHOperation,
+        fee: &PaidFee,
+    ) -> anyhow::Result<()> {
+        self.add_paid_fee(op.op_type, fee.total(), Duration::default())
+            .await;
 
         Ok(())
     }
 
+    async fn load_spent_fee(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        op_type: AggregatedActionType,
+        period: Duration,
+    ) -> anyhow::Result<U256> {
+        let spent_fee = self
+            .paid_fees
+            .read()
+            .await
+            .iter()
+            .filter(|(fee_op_type, _, confirmed_at)| {
+                *fee_op_type == op_type && confirmed_at.elapsed() < period
+            })
+            .fold(U256::zero(), |spent_fee, (_, fee, _)| spent_fee + fee);
+
+        Ok(spent_fee)
+    }
+
     async fn load_gas_price_limit(
         &self,
         _connection: &mut StorageProcessor<'_>,
+        operator: Option<Address>,
     ) -> anyhow::Result<U256> {
+        if let Some(address) = operator {
+            let operator_accounts = self.operator_accounts.read().await;
+            let account = operator_accounts
+                .get(&address)
+                .expect("Operator account is not initialized");
+            return Ok(account.gas_price_limit);
+        }
+
         let eth_parameters = self.eth_parameters.read().await;
         let gas_price_limit = eth_parameters.gas_price_limit.into();
 
@@ -327,6 +517,7 @@ impl DatabaseInterface for MockDatabase {
         &self,
         _connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
+        lane_types: &[AggregatedActionType],
     ) -> anyhow::Result<bool> {
         let confirmed = {
             let op = op.op.as_ref().unwrap();
@@ -338,8 +529,11 @@ impl DatabaseInterface for MockDatabase {
 
             let eth_operations = self.eth_operations.read().await.clone();
 
-            // Consider an operation that affects sequential blocks.
+            // Consider an operation of the same lane that affects sequential blocks.
             let maybe_operation = eth_operations.iter().find(|eth_operation| {
+                if !lane_types.contains(&eth_operation.op_type) {
+                    return false;
+                }
                 let op_block_range = eth_operation.op.as_ref().unwrap().1.get_block_range();
 
                 op_block_range.1 == first_block - 1
@@ -367,18 +561,21 @@ pub(crate) fn default_eth_parameters() -> ETHParams {
         last_committed_block: 0,
         last_verified_block: 0,
         last_executed_block: 0,
+        paused: false,
     }
 }
 
 /// Creates a default `ETHSender` with mock Ethereum connection/database and no operations in DB.
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
-pub(crate) async fn default_eth_sender() -> ETHSender<MockDatabase> {
+pub(crate) async fn default_eth_sender(tx_type: EthTxType) -> ETHSender<MockDatabase> {
     build_eth_sender(
         1,
+        tx_type,
         Vec::new(),
         Vec::new(),
         Vec::new(),
         default_eth_parameters(),
+        Vec::new(),
     )
     .await
 }
@@ -386,13 +583,18 @@ pub(crate) async fn default_eth_sender() -> ETHSender<MockDatabase> {
 /// Creates an `ETHSender` with mock Ethereum connection/database and no operations in DB
 /// which supports multiple transactions in flight.
 /// Returns the `ETHSender` itself along with communication channels to interact with it.
-pub(crate) async fn concurrent_eth_sender(max_txs_in_flight: u64) -> ETHSender<MockDatabase> {
+pub(crate) async fn concurrent_eth_sender(
+    max_txs_in_flight: u64,
+    tx_type: EthTxType,
+) -> ETHSender<MockDatabase> {
     build_eth_sender(
         max_txs_in_flight,
+        tx_type,
         Vec::new(),
         Vec::new(),
         Vec::new(),
         default_eth_parameters(),
+        Vec::new(),
     )
     .await
 }
@@ -404,15 +606,36 @@ pub(crate) async fn restored_eth_sender(
     aggregated_operations: Vec<(i64, AggregatedOperation)>,
     unprocessed_operations: Vec<(i64, AggregatedOperation)>,
     eth_parameters: ETHParams,
+    tx_type: EthTxType,
 ) -> ETHSender<MockDatabase> {
     const MAX_TXS_IN_FLIGHT: u64 = 1;
 
     build_eth_sender(
         MAX_TXS_IN_FLIGHT,
+        tx_type,
         eth_operations,
         aggregated_operations,
         unprocessed_operations,
         eth_parameters,
+        Vec::new(),
+    )
+    .await
+}
+
+/// Creates an `ETHSender` with mock Ethereum connection/database and no operations in DB
+/// which sends the operations from the additional operator accounts as well.
+pub(crate) async fn eth_sender_with_operator_lanes(
+    max_txs_in_flight: u64,
+    operator_gateways: Vec<(OperatorAccount, EthereumGateway)>,
+) -> ETHSender<MockDatabase> {
+    build_eth_sender(
+        max_txs_in_flight,
+        EthTxType::Legacy,
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        default_eth_parameters(),
+        operator_gateways,
     )
     .await
 }
@@ -420,10 +643,12 @@ pub(crate) async fn restored_eth_sender(
 /// Helper method for configurable creation of `ETHSender`.
 async fn build_eth_sender(
     max_txs_in_flight: u64,
+    tx_type: EthTxType,
     eth_operations: Vec<ETHOperation>,
     aggregated_operations: Vec<(i64, AggregatedOperation)>,
     unprocessed_operations: Vec<(i64, AggregatedOperation)>,
     eth_parameters: ETHParams,
+    operator_gateways: Vec<(OperatorAccount, EthereumGateway)>,
 ) -> ETHSender<MockDatabase> {
     let ethereum = EthereumGateway::Mock(MockEthereum::default());
     let db = MockDatabase::with_restorable_state(
@@ -440,8 +665,16 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            tx_type,
+            failure_policy: FailurePolicy::Pause,
+            max_failure_retries: super::MAX_FAILURE_RETRIES,
+            failed_tx_gas_limit_increase_percent: super::FAILED_TX_GAS_LIMIT_INCREASE_PERCENT,
+            webhook_url: String::new(),
+            preflight_check_enabled: true,
+            preflight_gas_divergence_percent: 20,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
+            operator_accounts: Vec::new(),
         },
         gas_price_limit: GasLimit {
             default: 1000,
@@ -449,9 +682,36 @@ async fn build_eth_sender(
             update_interval: 15,
             scale_factor: 1.0f64,
         },
+        spending_limit: SpendingLimit {
+            period: 3600,
+            commit_limit: None,
+            prove_limit: None,
+            execute_limit: None,
+            min_operator_balance: None,
+        },
     };
 
-    ETHSender::new(options, db, ethereum).await
+    ETHSender::new(options, db, ethereum, operator_gateways, Notifier::new()).await
+}
+
+/// Notifier backend recording the sent events.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RecordingNotifier {
+    events: Arc<Mutex<Vec<NotifyEvent>>>,
+}
+
+impl RecordingNotifier {
+    pub fn events(&self) -> Vec<NotifyEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl NotifierBackend for RecordingNotifier {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
 }
 
 /// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
@@ -464,10 +724,18 @@ pub(crate) async fn create_signed_tx(
     deadline_block: u64,
     nonce: i64,
 ) -> ETHOperation {
-    let options = Options {
+    let mut options = Options {
         nonce: Some(nonce.into()),
         ..Default::default()
     };
+    if eth_sender.options.sender.tx_type == EthTxType::EIP1559 {
+        // Fees suggested by `GasAdjuster` for the new transaction.
+        let ethereum = eth_sender.ethereum.get_mock().unwrap();
+        let base_fee = ethereum.get_base_fee().await.unwrap();
+        let priority_fee = ethereum.get_priority_fee().await.unwrap();
+        options.max_fee_per_gas = Some(max_fee_per_gas(base_fee, priority_fee));
+        options.max_priority_fee_per_gas = Some(priority_fee);
+    }
 
     let raw_tx = eth_sender.operation_to_raw_tx(&aggregated_operation.1);
     let signed_tx = eth_sender
@@ -485,9 +753,12 @@ pub(crate) async fn create_signed_tx(
         nonce: signed_tx.nonce,
         last_deadline_block: deadline_block,
         last_used_gas_price: signed_tx.gas_price,
+        last_used_priority_fee: signed_tx.max_priority_fee_per_gas,
         used_tx_hashes: vec![signed_tx.hash],
         encoded_tx_data: raw_tx,
         confirmed: false,
         final_hash: None,
+        failed_attempts: 0,
+        operator: None,
     }
 }
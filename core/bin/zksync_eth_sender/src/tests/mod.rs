@@ -1,14 +1,30 @@
+// Built-in deps
+use std::time::Duration;
 // Local uses
 use self::mock::{
     concurrent_eth_sender, create_signed_tx, default_eth_parameters, default_eth_sender,
-    restored_eth_sender,
+    eth_sender_with_operator_lanes, restored_eth_sender, MockDatabase, RecordingNotifier,
 };
-use super::{transactions::TxCheckOutcome, ETHSender, TxCheckMode};
-use web3::types::U64;
-use zksync_eth_client::ethereum_gateway::ExecutedTxStatus;
+use super::{
+    transactions::{PaidFee, TxCheckOutcome},
+    ETHSender, TxCheckMode,
+};
+use web3::types::{Address, H256, U256, U64};
+use zksync_config::configs::eth_sender::{EthTxType, FailurePolicy, OperatorAccount};
+use zksync_eth_client::{
+    clients::mock::MockEthereum,
+    ethereum_gateway::{ExecutedTxStatus, FailureInfo, GasEstimation},
+    EthereumGateway,
+};
+use zksync_notifier::{Notifier, NotifyEvent, Severity};
+use zksync_types::{aggregated_operations::AggregatedActionType, ethereum::ETHOperation};
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 3;
+const MAX_FAILURE_RETRIES: u64 = 2;
+const FAILED_TX_GAS_LIMIT_INCREASE_PERCENT: u64 = 25;
+/// Every test is run for both types of the Ethereum transactions.
+const TX_TYPES: &[EthTxType] = &[EthTxType::Legacy, EthTxType::EIP1559];
 
 pub mod mock;
 mod test_data;
@@ -16,22 +32,26 @@ mod test_data;
 /// Basic test that `ETHSender` creation does not panic and initializes correctly.
 #[tokio::test]
 async fn basic_test() {
-    let eth_sender = default_eth_sender().await;
+    for &tx_type in TX_TYPES {
+        let eth_sender = default_eth_sender(tx_type).await;
 
-    // Check that there are no unconfirmed operations by default.
-    assert!(eth_sender.ongoing_ops.is_empty());
+        // Check that there are no unconfirmed operations by default.
+        assert!(eth_sender.ongoing_ops.is_empty());
+    }
 }
 
 /// Checks that deadline block is chosen according to the expected policy.
 #[tokio::test]
 async fn deadline_block() {
-    let eth_sender = default_eth_sender().await;
+    for &tx_type in TX_TYPES {
+        let eth_sender = default_eth_sender(tx_type).await;
 
-    assert_eq!(eth_sender.get_deadline_block(0), EXPECTED_WAIT_TIME_BLOCKS);
-    assert_eq!(
-        eth_sender.get_deadline_block(10),
-        10 + EXPECTED_WAIT_TIME_BLOCKS
-    );
+        assert_eq!(eth_sender.get_deadline_block(0), EXPECTED_WAIT_TIME_BLOCKS);
+        assert_eq!(
+            eth_sender.get_deadline_block(10),
+            10 + EXPECTED_WAIT_TIME_BLOCKS
+        );
+    }
 }
 
 /// Checks that received transaction response is reduced to the
@@ -40,191 +60,210 @@ async fn deadline_block() {
 /// Here we check every possible output of the `check_transaction_state` method.
 #[tokio::test]
 async fn transaction_state() {
-    let mut eth_sender = default_eth_sender().await;
-    let current_block = eth_sender
-        .ethereum
-        .get_mock()
-        .unwrap()
-        .block_number()
-        .await
-        .unwrap()
-        .as_u64();
-    let deadline_block = eth_sender.get_deadline_block(current_block);
-    let operations = vec![
-        test_data::commit_blocks_operation(0), // Will be committed.
-        test_data::commit_blocks_operation(1), // Will be pending because of not enough confirmations.
-        test_data::commit_blocks_operation(2), // Will be failed.
-        test_data::commit_blocks_operation(3), // Will be failed and pending (not enough confirmations).
-        test_data::commit_blocks_operation(4), // Will be stuck.
-        test_data::commit_blocks_operation(5), // Will be pending due no response.
-    ];
-    let mut eth_operations = Vec::with_capacity(operations.len());
-
-    for (eth_op_id, op) in operations.iter().enumerate() {
-        eth_operations.push(
-            create_signed_tx(
-                eth_op_id as i64,
-                &eth_sender,
-                op.clone(),
-                deadline_block,
-                eth_op_id as i64,
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = default_eth_sender(tx_type).await;
+        let current_block = eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .block_number()
+            .await
+            .unwrap()
+            .as_u64();
+        let deadline_block = eth_sender.get_deadline_block(current_block);
+        let operations = vec![
+            test_data::commit_blocks_operation(0), // Will be committed.
+            test_data::commit_blocks_operation(1), // Will be pending because of not enough confirmations.
+            test_data::commit_blocks_operation(2), // Will be failed.
+            test_data::commit_blocks_operation(3), // Will be failed and pending (not enough confirmations).
+            test_data::commit_blocks_operation(4), // Will be stuck.
+            test_data::commit_blocks_operation(5), // Will be pending due no response.
+        ];
+        let mut eth_operations = Vec::with_capacity(operations.len());
+
+        for (eth_op_id, op) in operations.iter().enumerate() {
+            eth_operations.push(
+                create_signed_tx(
+                    eth_op_id as i64,
+                    &eth_sender,
+                    op.clone(),
+                    deadline_block,
+                    eth_op_id as i64,
+                )
+                .await,
             )
-            .await,
-        )
-    }
-
-    // Committed operation.
-    let committed_response = ExecutedTxStatus {
-        confirmations: WAIT_CONFIRMATIONS,
-        success: true,
-        receipt: None,
-    };
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .add_execution(&eth_operations[0].used_tx_hashes[0], &committed_response)
-        .await;
-
-    // Pending operation.
-    let pending_response = ExecutedTxStatus {
-        confirmations: WAIT_CONFIRMATIONS - 1,
-        success: true,
-        receipt: None,
-    };
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .add_execution(&eth_operations[1].used_tx_hashes[0], &pending_response)
-        .await;
-
-    // Failed operation.
-    let failed_response = ExecutedTxStatus {
-        confirmations: WAIT_CONFIRMATIONS,
-        success: false,
-        receipt: Some(Default::default()),
-    };
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .add_execution(&eth_operations[2].used_tx_hashes[0], &failed_response)
-        .await;
-
-    // Pending failed operation.
-    let pending_failed_response = ExecutedTxStatus {
-        confirmations: WAIT_CONFIRMATIONS - 1,
-        success: false,
-        receipt: Some(Default::default()),
-    };
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .add_execution(
-            &eth_operations[3].used_tx_hashes[0],
-            &pending_failed_response,
-        )
-        .await;
+        }
 
-    // Committed operation.
-    assert_eq!(
+        // Committed operation.
+        let committed_response = ExecutedTxStatus {
+            confirmations: WAIT_CONFIRMATIONS,
+            success: true,
+            receipt: None,
+            gas_used: Some(100_000.into()),
+            effective_gas_price: Some(50.into()),
+        };
         eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[0],
-                eth_operations[0].used_tx_hashes[0],
-                current_block + committed_response.confirmations,
-            )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Committed
-    );
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_execution(&eth_operations[0].used_tx_hashes[0], &committed_response)
+            .await;
 
-    // Pending operation (no enough confirmations).
-    assert_eq!(
+        // Pending operation.
+        let pending_response = ExecutedTxStatus {
+            confirmations: WAIT_CONFIRMATIONS - 1,
+            success: true,
+            receipt: None,
+            gas_used: None,
+            effective_gas_price: None,
+        };
         eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[1],
-                eth_operations[1].used_tx_hashes[0],
-                current_block + pending_response.confirmations,
-            )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Pending
-    );
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_execution(&eth_operations[1].used_tx_hashes[0], &pending_response)
+            .await;
 
-    // Failed operation.
-    assert_eq!(
+        // Failed operation.
+        let failed_response = ExecutedTxStatus {
+            confirmations: WAIT_CONFIRMATIONS,
+            success: false,
+            receipt: Some(Default::default()),
+            gas_used: Some(80_000.into()),
+            effective_gas_price: Some(50.into()),
+        };
         eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[2],
-                eth_operations[2].used_tx_hashes[0],
-                current_block + failed_response.confirmations,
-            )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Failed(Default::default())
-    );
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_execution(&eth_operations[2].used_tx_hashes[0], &failed_response)
+            .await;
 
-    // Pending failed operation should be considered as pending.
-    assert_eq!(
+        // Pending failed operation.
+        let pending_failed_response = ExecutedTxStatus {
+            confirmations: WAIT_CONFIRMATIONS - 1,
+            success: false,
+            receipt: Some(Default::default()),
+            gas_used: None,
+            effective_gas_price: None,
+        };
         eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[3],
-                eth_operations[3].used_tx_hashes[0],
-                current_block + pending_failed_response.confirmations,
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_execution(
+                &eth_operations[3].used_tx_hashes[0],
+                &pending_failed_response,
             )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Pending
-    );
+            .await;
 
-    // Stuck operation.
-    assert_eq!(
-        eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[4],
-                eth_operations[4].used_tx_hashes[0],
-                current_block + EXPECTED_WAIT_TIME_BLOCKS,
-            )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Stuck
-    );
+        // Committed operation.
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[0],
+                    eth_operations[0].used_tx_hashes[0],
+                    current_block + committed_response.confirmations,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Committed(PaidFee {
+                gas_used: 100_000.into(),
+                gas_price: 50.into(),
+            })
+        );
 
-    // Pending operation (no response yet).
-    assert_eq!(
-        eth_sender
-            .check_transaction_state(
-                TxCheckMode::Latest,
-                &eth_operations[5],
-                eth_operations[5].used_tx_hashes[0],
-                current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
-            )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Pending
-    );
+        // Pending operation (no enough confirmations).
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[1],
+                    eth_operations[1].used_tx_hashes[0],
+                    current_block + pending_response.confirmations,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Pending
+        );
 
-    // Pending old operation should be considered stuck.
-    assert_eq!(
-        eth_sender
-            .check_transaction_state(
-                TxCheckMode::Old,
-                &eth_operations[5],
-                eth_operations[5].used_tx_hashes[0],
-                current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
+        // Failed operation.
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[2],
+                    eth_operations[2].used_tx_hashes[0],
+                    current_block + failed_response.confirmations,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Failed(
+                Default::default(),
+                PaidFee {
+                    gas_used: 80_000.into(),
+                    gas_price: 50.into(),
+                }
             )
-            .await
-            .unwrap(),
-        TxCheckOutcome::Stuck
-    );
+        );
+
+        // Pending failed operation should be considered as pending.
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[3],
+                    eth_operations[3].used_tx_hashes[0],
+                    current_block + pending_failed_response.confirmations,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Pending
+        );
+
+        // Stuck operation.
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[4],
+                    eth_operations[4].used_tx_hashes[0],
+                    current_block + EXPECTED_WAIT_TIME_BLOCKS,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Stuck
+        );
+
+        // Pending operation (no response yet).
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Latest,
+                    &eth_operations[5],
+                    eth_operations[5].used_tx_hashes[0],
+                    current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Pending
+        );
+
+        // Pending old operation should be considered stuck.
+        assert_eq!(
+            eth_sender
+                .check_transaction_state(
+                    TxCheckMode::Old,
+                    &eth_operations[5],
+                    eth_operations[5].used_tx_hashes[0],
+                    current_block + EXPECTED_WAIT_TIME_BLOCKS - 1,
+                )
+                .await
+                .unwrap(),
+            TxCheckOutcome::Stuck
+        );
+    }
 }
 
 /// Test for a normal `ETHSender` workflow:
@@ -233,19 +272,93 @@ async fn transaction_state() {
 /// - notification is sent after `execute` operation is committed.
 #[tokio::test]
 async fn operation_commitment_workflow() {
-    let mut eth_sender = default_eth_sender().await;
-
-    // In this test we will run one commit blocks operation, one publish proof blocks onchain operation
-    // and execute blocks operation and should obtain a notification about the operation being completed in the end.
-    let aggregated_operations = vec![
-        test_data::commit_blocks_operation(0),
-        test_data::publish_proof_blocks_onchain_operations(0),
-        test_data::execute_blocks_operations(0),
-    ];
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = default_eth_sender(tx_type).await;
+
+        // In this test we will run one commit blocks operation, one publish proof blocks onchain operation
+        // and execute blocks operation and should obtain a notification about the operation being completed in the end.
+        let aggregated_operations = vec![
+            test_data::commit_blocks_operation(0),
+            test_data::publish_proof_blocks_onchain_operations(0),
+            test_data::execute_blocks_operations(0),
+        ];
+
+        for (eth_op_id, aggregated_operation) in aggregated_operations.iter().enumerate() {
+            let nonce = eth_op_id as i64;
+
+            // Send an operation to `ETHSender`.
+            eth_sender
+                .db
+                .send_aggregated_operation(aggregated_operation.clone())
+                .await
+                .unwrap();
+
+            // Retrieve it there and then process.
+            eth_sender.load_new_operations().await.unwrap();
+
+            eth_sender.proceed_next_operations(0).await;
+
+            // Now we should see that transaction is stored in the database and sent to the Ethereum.
+            let deadline_block = eth_sender.get_deadline_block(
+                eth_sender
+                    .ethereum
+                    .get_mock()
+                    .unwrap()
+                    .block_number()
+                    .await
+                    .unwrap()
+                    .as_u64(),
+            );
+            let mut expected_tx = create_signed_tx(
+                eth_op_id as i64,
+                &eth_sender,
+                aggregated_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
+            expected_tx.id = eth_op_id as i64; // We have to set the ID manually.
+
+            eth_sender.db.assert_stored(&expected_tx).await;
+
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .assert_sent(&expected_tx.used_tx_hashes[0].as_bytes().to_vec())
+                .await;
 
-    for (eth_op_id, aggregated_operation) in aggregated_operations.iter().enumerate() {
-        let nonce = eth_op_id as i64;
+            // Increment block, make the transaction look successfully executed, and process the
+            // operation again.
+            eth_sender
+                .ethereum
+                .get_mut_mock()
+                .unwrap()
+                .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+                .await;
+
+            eth_sender.proceed_next_operations(0).await;
+
+            // Check that operation is confirmed.
+            expected_tx.confirmed = true;
+            expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
+            eth_sender.db.assert_confirmed(&expected_tx).await;
+        }
+    }
+}
+
+/// A simple scenario for a stuck transaction:
+/// - A transaction is sent to the Ethereum.
+/// - It is not processed after some blocks.
+/// - `ETHSender` creates a new transaction with increased gas.
+/// - This transaction is completed successfully.
+#[tokio::test]
+async fn stuck_transaction() {
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = default_eth_sender(tx_type).await;
 
+        // Workflow for the test is similar to `operation_commitment_workflow`.
+        let aggregated_operation = test_data::commit_blocks_operation(0);
         // Send an operation to `ETHSender`.
         eth_sender
             .db
@@ -253,12 +366,11 @@ async fn operation_commitment_workflow() {
             .await
             .unwrap();
 
-        // Retrieve it there and then process.
         eth_sender.load_new_operations().await.unwrap();
-
         eth_sender.proceed_next_operations(0).await;
 
-        // Now we should see that transaction is stored in the database and sent to the Ethereum.
+        let eth_op_id = 0;
+        let nonce = 0;
         let deadline_block = eth_sender.get_deadline_block(
             eth_sender
                 .ethereum
@@ -269,23 +381,59 @@ async fn operation_commitment_workflow() {
                 .unwrap()
                 .as_u64(),
         );
-        let mut expected_tx = create_signed_tx(
-            eth_op_id as i64,
+        let mut stuck_tx = create_signed_tx(
+            eth_op_id,
             &eth_sender,
             aggregated_operation.clone(),
             deadline_block,
             nonce,
         )
         .await;
-        expected_tx.id = eth_op_id as i64; // We have to set the ID manually.
 
-        eth_sender.db.assert_stored(&expected_tx).await;
+        let block_number = U64::from(
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .block_number()
+                .await
+                .unwrap()
+                .as_u64()
+                + EXPECTED_WAIT_TIME_BLOCKS,
+        );
+        // Skip some blocks and expect sender to send a new tx.
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .set_block_number(block_number)
+            .await
+            .unwrap();
+        eth_sender.proceed_next_operations(0).await;
 
+        // Check that new transaction is sent (and created based on the previous stuck tx).
+        let expected_sent_tx = eth_sender
+            .create_supplement_tx(
+                eth_sender.get_deadline_block(
+                    eth_sender
+                        .ethereum
+                        .get_mock()
+                        .unwrap()
+                        .block_number()
+                        .await
+                        .unwrap()
+                        .as_u64(),
+                ),
+                &mut stuck_tx,
+            )
+            .await
+            .unwrap();
+        eth_sender.db.assert_stored(&stuck_tx).await;
         eth_sender
             .ethereum
-            .get_mock()
+            .get_mut_mock()
             .unwrap()
-            .assert_sent(&expected_tx.used_tx_hashes[0].as_bytes().to_vec())
+            .assert_sent(&expected_sent_tx.hash.as_bytes().to_vec())
             .await;
 
         // Increment block, make the transaction look successfully executed, and process the
@@ -294,395 +442,790 @@ async fn operation_commitment_workflow() {
             .ethereum
             .get_mut_mock()
             .unwrap()
-            .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+            .add_successfull_execution(stuck_tx.used_tx_hashes[1], WAIT_CONFIRMATIONS)
             .await;
-
         eth_sender.proceed_next_operations(0).await;
 
-        // Check that operation is confirmed.
-        expected_tx.confirmed = true;
-        expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
-        eth_sender.db.assert_confirmed(&expected_tx).await;
+        // Check that operation is confirmed (we set the final hash to the second sent tx).
+        stuck_tx.confirmed = true;
+        stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[1]);
+        eth_sender.db.assert_confirmed(&stuck_tx).await;
     }
 }
 
-/// A simple scenario for a stuck transaction:
-/// - A transaction is sent to the Ethereum.
-/// - It is not processed after some blocks.
-/// - `ETHSender` creates a new transaction with increased gas.
-/// - This transaction is completed successfully.
+/// This test verifies that with multiple operations received all-together,
+/// their order is respected and no processing of the next operation is started until
+/// the previous one is committed.
+///
+/// This test includes all three operation types (commit, verify and execute).
 #[tokio::test]
-async fn stuck_transaction() {
-    let mut eth_sender = default_eth_sender().await;
+async fn operations_order() {
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = default_eth_sender(tx_type).await;
+
+        // We send multiple the operations at once to the channel.
+        let operations_count = 3;
+
+        let commit_operations = &test_data::COMMIT_BLOCKS_OPERATIONS[..operations_count];
+        let verify_operations =
+            &test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[..operations_count];
+        let execute_operations = &test_data::EXECUTE_BLOCKS_OPERATIONS[..operations_count];
+
+        // Also we create the list of expected transactions.
+        let mut expected_txs = Vec::new();
+
+        // Create expected txs from all the operations.
+        // Since we create 3 operations at each cycle iteration,
+        // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
+        // On the first iteration the indices 0, 1 and 2 will be taken, then it
+        // will be 3, 4 and 5, etc.
+        let operation_iterator = commit_operations
+            .iter()
+            .zip(verify_operations)
+            .zip(execute_operations);
+        for (idx, ((commit_operation, verify_operation), execute_operation)) in
+            operation_iterator.enumerate()
+        {
+            // Create the commit operation.
+            let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
+            let deadline_block = eth_sender.get_deadline_block(start_block);
+            let eth_op_idx = (idx * 3) as i64;
+            let nonce = eth_op_idx;
+
+            let commit_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                commit_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
 
-    // Workflow for the test is similar to `operation_commitment_workflow`.
-    let aggregated_operation = test_data::commit_blocks_operation(0);
-    // Send an operation to `ETHSender`.
-    eth_sender
-        .db
-        .send_aggregated_operation(aggregated_operation.clone())
-        .await
-        .unwrap();
+            expected_txs.push(commit_op_tx);
+            // Send commit operation
+            eth_sender
+                .db
+                .send_aggregated_operation(commit_operation.clone())
+                .await
+                .unwrap();
 
-    eth_sender.load_new_operations().await.unwrap();
-    eth_sender.proceed_next_operations(0).await;
+            // Create the verify operation, as by priority it will be processed right after `commit`.
+            let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 1) as u64;
+            let deadline_block = eth_sender.get_deadline_block(start_block);
+            let eth_op_idx = (idx * 3 + 1) as i64;
+            let nonce = eth_op_idx;
 
-    let eth_op_id = 0;
-    let nonce = 0;
-    let deadline_block = eth_sender.get_deadline_block(
-        eth_sender
-            .ethereum
-            .get_mock()
-            .unwrap()
-            .block_number()
-            .await
-            .unwrap()
-            .as_u64(),
-    );
-    let mut stuck_tx = create_signed_tx(
-        eth_op_id,
-        &eth_sender,
-        aggregated_operation.clone(),
-        deadline_block,
-        nonce,
-    )
-    .await;
+            let verify_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                verify_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
 
-    let block_number = U64::from(
-        eth_sender
-            .ethereum
-            .get_mock()
-            .unwrap()
-            .block_number()
-            .await
-            .unwrap()
-            .as_u64()
-            + EXPECTED_WAIT_TIME_BLOCKS,
-    );
-    // Skip some blocks and expect sender to send a new tx.
+            expected_txs.push(verify_op_tx);
+            // Send verify operation
+            eth_sender
+                .db
+                .send_aggregated_operation(verify_operation.clone())
+                .await
+                .unwrap();
+
+            // Create the withdraw operation.
+            let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 2) as u64;
+            let deadline_block = eth_sender.get_deadline_block(start_block);
+            let eth_op_idx = (idx * 3 + 2) as i64;
+            let nonce = eth_op_idx;
+
+            let execute_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                execute_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
+
+            expected_txs.push(execute_op_tx);
+            // Send execute operation
+            eth_sender
+                .db
+                .send_aggregated_operation(execute_operation.clone())
+                .await
+                .unwrap();
+        }
+
+        eth_sender.load_new_operations().await.unwrap();
+
+        // Then we go through the operations and check that the order of operations is preserved.
+        for mut tx in expected_txs.into_iter() {
+            let current_tx_hash = tx.used_tx_hashes[0];
+
+            eth_sender.proceed_next_operations(0).await;
+
+            // Check that current expected tx is stored.
+            eth_sender.db.assert_stored(&tx).await;
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .assert_sent(&current_tx_hash.as_bytes().to_vec())
+                .await;
+
+            // Mark the tx as successfully
+            eth_sender
+                .ethereum
+                .get_mut_mock()
+                .unwrap()
+                .add_successfull_execution(current_tx_hash, WAIT_CONFIRMATIONS)
+                .await;
+            eth_sender.proceed_next_operations(0).await;
+
+            // Update the fields in the tx and check if it's confirmed.
+            tx.confirmed = true;
+            tx.final_hash = Some(current_tx_hash);
+            eth_sender.db.assert_confirmed(&tx).await;
+        }
+    }
+}
+
+/// Check that upon a transaction failure the incident causes a panic with the `Panic` policy.
+#[tokio::test]
+#[should_panic(expected = "Cannot operate after unexpected TX failure")]
+async fn transaction_failure() {
+    check_transaction_failure(EthTxType::Legacy).await;
+}
+
+/// Same as `transaction_failure`, but for the EIP-1559 transactions.
+#[tokio::test]
+#[should_panic(expected = "Cannot operate after unexpected TX failure")]
+async fn transaction_failure_eip1559() {
+    check_transaction_failure(EthTxType::EIP1559).await;
+}
+
+async fn check_transaction_failure(tx_type: EthTxType) {
+    let (mut eth_sender, failing_tx) = send_operation_to_fail(tx_type, FailurePolicy::Panic).await;
+    let failure_info = failure_info("i", false);
+    fail_transaction(
+        &mut eth_sender,
+        failing_tx.used_tx_hashes[0],
+        Some(failure_info),
+    )
+    .await;
+}
+
+/// Creates `ETHSender` with the given failure policy and sends a commit operation with it.
+/// Returns the sender along with the sent operation.
+async fn send_operation_to_fail(
+    tx_type: EthTxType,
+    failure_policy: FailurePolicy,
+) -> (ETHSender<MockDatabase>, ETHOperation) {
+    send_first_operation(default_eth_sender(tx_type).await, failure_policy).await
+}
+
+/// Sends the first operation via the provided `ETHSender` with the specified failure policy.
+async fn send_first_operation(
+    mut eth_sender: ETHSender<MockDatabase>,
+    failure_policy: FailurePolicy,
+) -> (ETHSender<MockDatabase>, ETHOperation) {
+    eth_sender.options.sender.failure_policy = failure_policy;
+
+    // Workflow for the test is similar to `operation_commitment_workflow`.
+    let aggregated_operation = test_data::commit_blocks_operation(0);
     eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .set_block_number(block_number)
+        .db
+        .send_aggregated_operation(aggregated_operation.clone())
         .await
         .unwrap();
+
+    let eth_op_id = 0;
+    let nonce = 0;
+    let deadline_block = eth_sender.get_deadline_block(
+        eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .block_number()
+            .await
+            .unwrap()
+            .as_u64(),
+    );
+    let sent_tx = create_signed_tx(
+        eth_op_id,
+        &eth_sender,
+        aggregated_operation,
+        deadline_block,
+        nonce,
+    )
+    .await;
+
+    eth_sender.load_new_operations().await.unwrap();
     eth_sender.proceed_next_operations(0).await;
 
-    // Check that new transaction is sent (and created based on the previous stuck tx).
-    let expected_sent_tx = eth_sender
-        .create_supplement_tx(
-            eth_sender.get_deadline_block(
-                eth_sender
-                    .ethereum
-                    .get_mock()
-                    .unwrap()
-                    .block_number()
-                    .await
-                    .unwrap()
-                    .as_u64(),
-            ),
-            &mut stuck_tx,
-        )
-        .await
-        .unwrap();
-    eth_sender.db.assert_stored(&stuck_tx).await;
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .assert_sent(&expected_sent_tx.hash.as_bytes().to_vec())
+    (eth_sender, sent_tx)
+}
+
+/// Makes the transaction look failed with the provided reason and lets `ETHSender` process it.
+async fn fail_transaction(
+    eth_sender: &mut ETHSender<MockDatabase>,
+    tx_hash: H256,
+    failure_info: Option<FailureInfo>,
+) {
+    let ethereum = eth_sender.ethereum.get_mut_mock().unwrap();
+    ethereum
+        .add_failed_execution(&tx_hash, WAIT_CONFIRMATIONS)
         .await;
+    if let Some(failure_info) = failure_info {
+        ethereum.add_failure_reason(&tx_hash, failure_info).await;
+    }
+    eth_sender.proceed_next_operations(0).await;
+}
+
+/// Creates the failure reason for the transaction.
+fn failure_info(revert_reason: &str, out_of_gas: bool) -> FailureInfo {
+    let gas_limit = U256::from(1_000_000);
+    let gas_used = if out_of_gas { gas_limit } else { gas_limit / 2 };
+
+    FailureInfo {
+        revert_code: revert_reason.to_string(),
+        revert_reason: revert_reason.to_string(),
+        gas_used: Some(gas_used),
+        gas_limit,
+    }
+}
 
-    // Increment block, make the transaction look successfully executed, and process the
-    // operation again.
+/// Checks that the operation with the failed transaction is sent again with the next nonce
+/// and the increased gas limit.
+async fn check_failed_tx_resent(
+    eth_sender: &ETHSender<MockDatabase>,
+    failing_tx: &ETHOperation,
+    nonce: i64,
+    failed_attempts: u64,
+) -> ETHOperation {
+    assert!(!eth_sender.paused);
+    assert!(!eth_sender.db.is_paused().await);
+
+    let deadline_block = eth_sender.get_deadline_block(
+        eth_sender
+            .ethereum
+            .get_mock()
+            .unwrap()
+            .block_number()
+            .await
+            .unwrap()
+            .as_u64(),
+    );
+    let mut expected_tx = create_signed_tx(
+        failing_tx.id,
+        eth_sender,
+        failing_tx.op.clone().unwrap(),
+        deadline_block,
+        nonce,
+    )
+    .await;
+    expected_tx.failed_attempts = failed_attempts;
+
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops[0], expected_tx);
+    assert_eq!(eth_sender.ongoing_ops[0].nonce, nonce.into());
+    eth_sender.db.assert_stored(&expected_tx).await;
     eth_sender
         .ethereum
-        .get_mut_mock()
+        .get_mock()
         .unwrap()
-        .add_successfull_execution(stuck_tx.used_tx_hashes[1], WAIT_CONFIRMATIONS)
+        .assert_sent(&expected_tx.used_tx_hashes[0].as_bytes().to_vec())
         .await;
-    eth_sender.proceed_next_operations(0).await;
 
-    // Check that operation is confirmed (we set the final hash to the second sent tx).
-    stuck_tx.confirmed = true;
-    stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[1]);
-    eth_sender.db.assert_confirmed(&stuck_tx).await;
+    let mut initial_tx = failing_tx.clone();
+    initial_tx.failed_attempts = 0;
+    let gas_limit = eth_sender.gas_limit_for_op(&initial_tx);
+    let increase_percent = FAILED_TX_GAS_LIMIT_INCREASE_PERCENT * failed_attempts;
+    assert_eq!(
+        eth_sender.gas_limit_for_op(&expected_tx),
+        gas_limit * U256::from(100 + increase_percent) / U256::from(100)
+    );
+
+    expected_tx
 }
 
-/// This test verifies that with multiple operations received all-together,
-/// their order is respected and no processing of the next operation is started until
-/// the previous one is committed.
-///
-/// This test includes all three operation types (commit, verify and execute).
+/// Check that the transaction which ran out of gas is sent again with the `Pause` policy.
 #[tokio::test]
-async fn operations_order() {
-    let mut eth_sender = default_eth_sender().await;
+async fn transaction_failure_out_of_gas() {
+    for &tx_type in TX_TYPES {
+        let (mut eth_sender, failing_tx) =
+            send_operation_to_fail(tx_type, FailurePolicy::Pause).await;
+
+        let failure_info = failure_info("out of gas", true);
+        fail_transaction(
+            &mut eth_sender,
+            failing_tx.used_tx_hashes[0],
+            Some(failure_info),
+        )
+        .await;
 
-    // We send multiple the operations at once to the channel.
-    let operations_count = 3;
+        // The node doesn't report the used gas, so the fee of the failed transaction
+        // is counted by its upper bound.
+        assert_eq!(
+            eth_sender
+                .db
+                .paid_fee(AggregatedActionType::CommitBlocks)
+                .await,
+            eth_sender.gas_limit_for_op(&failing_tx) * failing_tx.last_used_gas_price
+        );
+        check_failed_tx_resent(&eth_sender, &failing_tx, 1, 1).await;
+    }
+}
 
-    let commit_operations = &test_data::COMMIT_BLOCKS_OPERATIONS[..operations_count];
-    let verify_operations = &test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[..operations_count];
-    let execute_operations = &test_data::EXECUTE_BLOCKS_OPERATIONS[..operations_count];
+/// Check that the transaction which lost the nonce race is sent again with the `Pause` policy.
+#[tokio::test]
+async fn transaction_failure_nonce_race() {
+    for &tx_type in TX_TYPES {
+        let (mut eth_sender, failing_tx) =
+            send_operation_to_fail(tx_type, FailurePolicy::Pause).await;
+
+        let failure_info = failure_info("n", false);
+        fail_transaction(
+            &mut eth_sender,
+            failing_tx.used_tx_hashes[0],
+            Some(failure_info),
+        )
+        .await;
 
-    // Also we create the list of expected transactions.
-    let mut expected_txs = Vec::new();
+        check_failed_tx_resent(&eth_sender, &failing_tx, 1, 1).await;
+    }
+}
 
-    // Create expected txs from all the operations.
-    // Since we create 3 operations at each cycle iteration,
-    // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
-    // On the first iteration the indices 0, 1 and 2 will be taken, then it
-    // will be 3, 4 and 5, etc.
-    let operation_iterator = commit_operations
-        .iter()
-        .zip(verify_operations)
-        .zip(execute_operations);
-    for (idx, ((commit_operation, verify_operation), execute_operation)) in
-        operation_iterator.enumerate()
-    {
-        // Create the commit operation.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3) as i64;
-        let nonce = eth_op_idx;
-
-        let commit_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            commit_operation.clone(),
-            deadline_block,
-            nonce,
+/// Check that the failed operation is sent again only after the other in-flight transactions
+/// of the same account are mined, and no new operations are sent until then.
+#[tokio::test]
+async fn transaction_failure_resent_after_in_flight_txs() {
+    for &tx_type in TX_TYPES {
+        let (mut eth_sender, failing_tx) = send_first_operation(
+            concurrent_eth_sender(3, tx_type).await,
+            FailurePolicy::Pause,
         )
         .await;
 
-        expected_txs.push(commit_op_tx);
-        // Send commit operation
+        // The next operation is sent before the failure is discovered.
         eth_sender
             .db
-            .send_aggregated_operation(commit_operation.clone())
+            .send_aggregated_operation(test_data::commit_blocks_operation(1))
             .await
             .unwrap();
-
-        // Create the verify operation, as by priority it will be processed right after `commit`.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 1) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3 + 1) as i64;
-        let nonce = eth_op_idx;
-
-        let verify_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            verify_operation.clone(),
-            deadline_block,
-            nonce,
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.proceed_next_operations(0).await;
+        assert_eq!(eth_sender.ongoing_ops.len(), 2);
+        let next_tx = eth_sender.ongoing_ops[1].clone();
+        assert_eq!(next_tx.nonce, 1.into());
+
+        // The failed operation waits for the in-flight transaction.
+        fail_transaction(
+            &mut eth_sender,
+            failing_tx.used_tx_hashes[0],
+            Some(failure_info("n", false)),
         )
         .await;
+        assert!(!eth_sender.paused);
+        assert_eq!(eth_sender.ongoing_ops[0], failing_tx);
+        assert!(eth_sender.ops_awaiting_resend.contains(&failing_tx.id));
 
-        expected_txs.push(verify_op_tx);
-        // Send verify operation
+        // New operations are held meanwhile.
         eth_sender
             .db
-            .send_aggregated_operation(verify_operation.clone())
+            .send_aggregated_operation(test_data::commit_blocks_operation(2))
             .await
             .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.proceed_next_operations(0).await;
+        assert_eq!(eth_sender.ongoing_ops.len(), 2);
+        assert_eq!(eth_sender.ongoing_ops[0], failing_tx);
+
+        // Once the in-flight transaction fails as well, both operations
+        // are sent again in the original order.
+        fail_transaction(
+            &mut eth_sender,
+            next_tx.used_tx_hashes[0],
+            Some(failure_info("out of gas", true)),
+        )
+        .await;
+        assert!(eth_sender.ops_awaiting_resend.is_empty());
+        assert_eq!(eth_sender.ongoing_ops.len(), 2);
+        for (op, (expected_id, expected_nonce)) in eth_sender
+            .ongoing_ops
+            .iter()
+            .zip([(failing_tx.id, 2), (next_tx.id, 3)])
+        {
+            assert_eq!(op.id, expected_id);
+            assert_eq!(op.nonce, expected_nonce.into());
+            assert_eq!(op.failed_attempts, 1);
+        }
+
+        // The held operation is sent after them.
+        eth_sender.proceed_next_operations(0).await;
+        assert_eq!(eth_sender.ongoing_ops.len(), 3);
+        assert_eq!(eth_sender.ongoing_ops[2].nonce, 4.into());
+    }
+}
 
-        // Create the withdraw operation.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3 + 2) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-        let eth_op_idx = (idx * 3 + 2) as i64;
-        let nonce = eth_op_idx;
+/// Check that the sender is paused after the contract revert or an unknown failure
+/// with the `Pause` policy, and no more transactions are sent.
+#[tokio::test]
+async fn transaction_failure_pause() {
+    for &tx_type in TX_TYPES {
+        for failure_info in [Some(failure_info("i", false)), None] {
+            let (mut eth_sender, failing_tx) =
+                send_operation_to_fail(tx_type, FailurePolicy::Pause).await;
+            let notifier = RecordingNotifier::default();
+            eth_sender.notifier = Notifier::new().with_backend(notifier.clone(), Severity::Info);
+
+            fail_transaction(&mut eth_sender, failing_tx.used_tx_hashes[0], failure_info).await;
+
+            assert!(eth_sender.paused);
+            assert!(eth_sender.db.is_paused().await);
+            // The pause is reported via the notifier.
+            let events = notifier.events();
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                &events[0],
+                NotifyEvent::EthSenderPaused { tx_hash, .. } if *tx_hash == failing_tx.used_tx_hashes[0]
+            ));
+            // The operation is kept untouched.
+            assert_eq!(eth_sender.ongoing_ops.len(), 1);
+            assert_eq!(eth_sender.ongoing_ops[0], failing_tx);
+
+            // Operations are not processed while the sender is paused.
+            let aggregated_operation = test_data::commit_blocks_operation(1);
+            eth_sender
+                .db
+                .send_aggregated_operation(aggregated_operation)
+                .await
+                .unwrap();
+            eth_sender.load_new_operations().await.unwrap();
+            eth_sender.proceed_next_operations(0).await;
+            assert_eq!(eth_sender.ongoing_ops.len(), 1);
+            assert_eq!(eth_sender.ongoing_ops[0], failing_tx);
+        }
+    }
+}
 
-        let execute_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            execute_operation.clone(),
-            deadline_block,
-            nonce,
+/// Check that the sender is paused once the operation has failed too many times.
+#[tokio::test]
+async fn transaction_failure_retries_limit() {
+    for &tx_type in TX_TYPES {
+        let (mut eth_sender, mut failing_tx) =
+            send_operation_to_fail(tx_type, FailurePolicy::Pause).await;
+
+        for failed_attempts in 1..=MAX_FAILURE_RETRIES {
+            let failure_info = failure_info("out of gas", true);
+            fail_transaction(
+                &mut eth_sender,
+                failing_tx.used_tx_hashes[0],
+                Some(failure_info),
+            )
+            .await;
+
+            failing_tx = check_failed_tx_resent(
+                &eth_sender,
+                &failing_tx,
+                failed_attempts as i64,
+                failed_attempts,
+            )
+            .await;
+        }
+
+        let failure_info = failure_info("out of gas", true);
+        fail_transaction(
+            &mut eth_sender,
+            failing_tx.used_tx_hashes[0],
+            Some(failure_info),
         )
         .await;
 
-        expected_txs.push(execute_op_tx);
-        // Send execute operation
+        assert!(eth_sender.paused);
+        assert!(eth_sender.db.is_paused().await);
+        assert_eq!(eth_sender.ongoing_ops[0], failing_tx);
+    }
+}
+
+/// Check that the operation is not sent if the pre-flight check reverts,
+/// and is sent once the check succeeds.
+#[tokio::test]
+async fn preflight_check_revert() {
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = default_eth_sender(tx_type).await;
         eth_sender
-            .db
-            .send_aggregated_operation(execute_operation.clone())
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .set_gas_estimation(GasEstimation::Reverted("i".to_string()))
             .await
             .unwrap();
-    }
-
-    eth_sender.load_new_operations().await.unwrap();
-
-    // Then we go through the operations and check that the order of operations is preserved.
-    for mut tx in expected_txs.into_iter() {
-        let current_tx_hash = tx.used_tx_hashes[0];
 
+        let aggregated_operation = test_data::commit_blocks_operation(0);
+        eth_sender
+            .db
+            .send_aggregated_operation(aggregated_operation.clone())
+            .await
+            .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
         eth_sender.proceed_next_operations(0).await;
 
-        // Check that current expected tx is stored.
-        eth_sender.db.assert_stored(&tx).await;
+        // Nothing is stored or sent, the operation is kept in the queue.
+        assert!(eth_sender.ongoing_ops.is_empty());
+        assert_eq!(
+            eth_sender.preflight_reverted_op,
+            Some(aggregated_operation.0)
+        );
+
+        // Estimated gas diverging from the gas limit doesn't prevent the operation from being sent.
         eth_sender
             .ethereum
-            .get_mock()
+            .get_mut_mock()
             .unwrap()
-            .assert_sent(&current_tx_hash.as_bytes().to_vec())
-            .await;
+            .set_gas_estimation(GasEstimation::Success(1.into()))
+            .await
+            .unwrap();
 
-        // Mark the tx as successfully
+        let deadline_block = eth_sender.get_deadline_block(
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .block_number()
+                .await
+                .unwrap()
+                .as_u64(),
+        );
+        let expected_tx =
+            create_signed_tx(0, &eth_sender, aggregated_operation, deadline_block, 0).await;
+        eth_sender.proceed_next_operations(0).await;
+
+        assert_eq!(eth_sender.ongoing_ops.len(), 1);
+        assert_eq!(eth_sender.ongoing_ops[0], expected_tx);
+        eth_sender.db.assert_stored(&expected_tx).await;
         eth_sender
             .ethereum
-            .get_mut_mock()
+            .get_mock()
             .unwrap()
-            .add_successfull_execution(current_tx_hash, WAIT_CONFIRMATIONS)
+            .assert_sent(&expected_tx.used_tx_hashes[0].as_bytes().to_vec())
             .await;
-        eth_sender.proceed_next_operations(0).await;
-
-        // Update the fields in the tx and check if it's confirmed.
-        tx.confirmed = true;
-        tx.final_hash = Some(current_tx_hash);
-        eth_sender.db.assert_confirmed(&tx).await;
     }
 }
 
-/// Check that upon a transaction failure the incident causes a panic by default.
+/// Check that the operations sent while other transactions are in flight
+/// are not simulated, since the pending state may not include their predecessors.
 #[tokio::test]
-#[should_panic(expected = "Cannot operate after unexpected TX failure")]
-async fn transaction_failure() {
-    let mut eth_sender = default_eth_sender().await;
-
-    // Workflow for the test is similar to `operation_commitment_workflow`.
-    let aggregated_operation = test_data::commit_blocks_operation(0);
-    eth_sender
-        .db
-        .send_aggregated_operation(aggregated_operation.clone())
-        .await
-        .unwrap();
+async fn preflight_check_skipped_with_in_flight_txs() {
+    for &tx_type in TX_TYPES {
+        let mut eth_sender = concurrent_eth_sender(2, tx_type).await;
+        eth_sender
+            .db
+            .send_aggregated_operation(test_data::commit_blocks_operation(0))
+            .await
+            .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.proceed_next_operations(0).await;
+        assert_eq!(eth_sender.ongoing_ops.len(), 1);
 
-    let eth_op_id = 0;
-    let nonce = 0;
-    let deadline_block = eth_sender.get_deadline_block(
+        // The simulation of the next operation would revert, but it's not performed.
         eth_sender
             .ethereum
-            .get_mock()
+            .get_mut_mock()
             .unwrap()
-            .block_number()
+            .set_gas_estimation(GasEstimation::Reverted("f".to_string()))
             .await
-            .unwrap()
-            .as_u64(),
-    );
-    let failing_tx = create_signed_tx(
-        eth_op_id,
-        &eth_sender,
-        aggregated_operation.clone(),
-        deadline_block,
-        nonce,
-    )
-    .await;
-
-    eth_sender.load_new_operations().await.unwrap();
-    eth_sender.proceed_next_operations(0).await;
+            .unwrap();
+        eth_sender
+            .db
+            .send_aggregated_operation(test_data::commit_blocks_operation(1))
+            .await
+            .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.proceed_next_operations(0).await;
 
-    eth_sender
-        .ethereum
-        .get_mut_mock()
-        .unwrap()
-        .add_failed_execution(&failing_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
-        .await;
-    eth_sender.proceed_next_operations(0).await;
+        assert_eq!(eth_sender.ongoing_ops.len(), 2);
+        assert_eq!(eth_sender.preflight_reverted_op, None);
+    }
 }
 
 /// Check that after recovering state with several non-processed operations
 /// they will be processed normally.
 #[tokio::test]
 async fn restore_state() {
-    let (stored_eth_operations, aggregated_operations, unprocessed_operations) = {
-        // This `eth_sender` is required to generate the input only.
-        let eth_sender = default_eth_sender().await;
-
-        // Aggregated operations for which Ethereum transactions have been created but have not yet been confirmed.
-        let processed_commit_op = test_data::commit_blocks_operation(0);
-        let processed_verify_op = test_data::publish_proof_blocks_onchain_operations(0);
-        let processed_execute_op = test_data::execute_blocks_operations(0);
-
-        let deadline_block = eth_sender.get_deadline_block(1);
-        let commit_op_tx = create_signed_tx(
-            0,
-            &eth_sender,
-            processed_commit_op.clone(),
-            deadline_block,
-            0,
-        )
-        .await;
-
-        let deadline_block = eth_sender.get_deadline_block(1 + WAIT_CONFIRMATIONS);
-        let verify_op_tx = create_signed_tx(
-            1,
-            &eth_sender,
-            processed_verify_op.clone(),
-            deadline_block,
-            1,
-        )
-        .await;
+    for &tx_type in TX_TYPES {
+        let (stored_eth_operations, aggregated_operations, unprocessed_operations) = {
+            // This `eth_sender` is required to generate the input only.
+            let eth_sender = default_eth_sender(tx_type).await;
+
+            // Aggregated operations for which Ethereum transactions have been created but have not yet been confirmed.
+            let processed_commit_op = test_data::commit_blocks_operation(0);
+            let processed_verify_op = test_data::publish_proof_blocks_onchain_operations(0);
+            let processed_execute_op = test_data::execute_blocks_operations(0);
+
+            let deadline_block = eth_sender.get_deadline_block(1);
+            let commit_op_tx = create_signed_tx(
+                0,
+                &eth_sender,
+                processed_commit_op.clone(),
+                deadline_block,
+                0,
+            )
+            .await;
 
-        let deadline_block = eth_sender.get_deadline_block(1 + 2 * WAIT_CONFIRMATIONS);
-        let execute_op_tx = create_signed_tx(
-            2,
-            &eth_sender,
-            processed_execute_op.clone(),
-            deadline_block,
-            2,
-        )
-        .await;
+            let deadline_block = eth_sender.get_deadline_block(1 + WAIT_CONFIRMATIONS);
+            let verify_op_tx = create_signed_tx(
+                1,
+                &eth_sender,
+                processed_verify_op.clone(),
+                deadline_block,
+                1,
+            )
+            .await;
 
-        let stored_eth_operations = vec![commit_op_tx, verify_op_tx, execute_op_tx];
+            let deadline_block = eth_sender.get_deadline_block(1 + 2 * WAIT_CONFIRMATIONS);
+            let execute_op_tx = create_signed_tx(
+                2,
+                &eth_sender,
+                processed_execute_op.clone(),
+                deadline_block,
+                2,
+            )
+            .await;
 
-        // Aggregated operations that have not yet been processed.
-        let unprocessed_commit_op = test_data::commit_blocks_operation(1);
-        let unprocessed_verify_op = test_data::publish_proof_blocks_onchain_operations(1);
-        let unprocessed_execute_op = test_data::execute_blocks_operations(1);
+            let stored_eth_operations = vec![commit_op_tx, verify_op_tx, execute_op_tx];
+
+            // Aggregated operations that have not yet been processed.
+            let unprocessed_commit_op = test_data::commit_blocks_operation(1);
+            let unprocessed_verify_op = test_data::publish_proof_blocks_onchain_operations(1);
+            let unprocessed_execute_op = test_data::execute_blocks_operations(1);
+
+            // All aggregated operations must be in the database even after server restart.
+            let aggregated_operations = vec![
+                processed_commit_op,
+                processed_verify_op,
+                processed_execute_op,
+                unprocessed_commit_op,
+                unprocessed_verify_op,
+                unprocessed_execute_op.clone(),
+            ];
+            // Aggregated operations from the table `eth_unprocessed_aggregated_ops` are deleted after the operation is added to the queue,
+            // therefore, after restarting the server, it may contain not all really unprocessed operations.
+            let unprocessed_operations = vec![unprocessed_execute_op];
+
+            (
+                stored_eth_operations,
+                aggregated_operations,
+                unprocessed_operations,
+            )
+        };
 
-        // All aggregated operations must be in the database even after server restart.
-        let aggregated_operations = vec![
-            processed_commit_op,
-            processed_verify_op,
-            processed_execute_op,
-            unprocessed_commit_op,
-            unprocessed_verify_op,
-            unprocessed_execute_op.clone(),
-        ];
-        // Aggregated operations from the table `eth_unprocessed_aggregated_ops` are deleted after the operation is added to the queue,
-        // therefore, after restarting the server, it may contain not all really unprocessed operations.
-        let unprocessed_operations = vec![unprocessed_execute_op];
+        let mut eth_parameters = default_eth_parameters();
+        eth_parameters.last_committed_block = 1;
+        eth_parameters.last_verified_block = 1;
+        eth_parameters.last_executed_block = 1;
 
-        (
+        let mut eth_sender = restored_eth_sender(
             stored_eth_operations,
-            aggregated_operations,
+            aggregated_operations.clone(),
             unprocessed_operations,
+            eth_parameters,
+            tx_type,
         )
-    };
+        .await;
+
+        eth_sender.load_new_operations().await.unwrap();
+
+        for (eth_op_id, aggregated_operation) in aggregated_operations.iter().enumerate() {
+            // Note that we DO NOT send an operation to `ETHSender` and neither receive it.
+
+            // We do process operations restored from the DB though.
+            // The rest of this test is the same as in `operation_commitment_workflow`.
+            eth_sender.proceed_next_operations(0).await;
+
+            let deadline_block = eth_sender.get_deadline_block(
+                eth_sender
+                    .ethereum
+                    .get_mock()
+                    .unwrap()
+                    .block_number()
+                    .await
+                    .unwrap()
+                    .as_u64(),
+            );
+            let nonce = eth_op_id as i64;
+            let mut expected_tx = create_signed_tx(
+                eth_op_id as i64,
+                &eth_sender,
+                aggregated_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
+            expected_tx.id = eth_op_id as i64;
 
-    let mut eth_parameters = default_eth_parameters();
-    eth_parameters.last_committed_block = 1;
-    eth_parameters.last_verified_block = 1;
-    eth_parameters.last_executed_block = 1;
+            eth_sender.db.assert_stored(&expected_tx).await;
+
+            eth_sender
+                .ethereum
+                .get_mut_mock()
+                .unwrap()
+                .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+                .await;
+            eth_sender.proceed_next_operations(0).await;
+
+            expected_tx.confirmed = true;
+            expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
+            eth_sender.db.assert_confirmed(&expected_tx).await;
+        }
+    }
+}
 
-    let mut eth_sender = restored_eth_sender(
-        stored_eth_operations,
-        aggregated_operations.clone(),
-        unprocessed_operations,
-        eth_parameters,
+/// Check that the sender doesn't start if the unconfirmed operation was sent
+/// from the operator account which is not configured anymore.
+#[tokio::test]
+#[should_panic(expected = "which is not configured")]
+async fn restore_state_unknown_operator() {
+    let eth_sender = default_eth_sender(EthTxType::Legacy).await;
+    let commit_op = test_data::commit_blocks_operation(0);
+    let deadline_block = eth_sender.get_deadline_block(1);
+    let mut commit_op_tx =
+        create_signed_tx(0, &eth_sender, commit_op.clone(), deadline_block, 0).await;
+    commit_op_tx.operator = Some(Address::repeat_byte(0x11));
+
+    restored_eth_sender(
+        vec![commit_op_tx],
+        vec![commit_op],
+        Vec::new(),
+        default_eth_parameters(),
+        EthTxType::Legacy,
     )
     .await;
+}
 
-    eth_sender.load_new_operations().await.unwrap();
+/// Checks that even after getting the first transaction stuck and sending the next
+/// one, confirmation for the first (stuck) transaction is processed and leads
+/// to the operation commitment.
+#[tokio::test]
+async fn confirmations_independence() {
+    for &tx_type in TX_TYPES {
+        // Workflow in the test is the same as in `stuck_transaction`, except for the fact
+        // that confirmation is obtained for the stuck transaction instead of the latter one.
 
-    for (eth_op_id, aggregated_operation) in aggregated_operations.iter().enumerate() {
-        // Note that we DO NOT send an operation to `ETHSender` and neither receive it.
+        let mut eth_sender = default_eth_sender(tx_type).await;
 
-        // We do process operations restored from the DB though.
-        // The rest of this test is the same as in `operation_commitment_workflow`.
+        let aggregated_operation = test_data::commit_blocks_operation(0);
+        eth_sender
+            .db
+            .send_aggregated_operation(aggregated_operation.clone())
+            .await
+            .unwrap();
+
+        eth_sender.load_new_operations().await.unwrap();
         eth_sender.proceed_next_operations(0).await;
 
+        let eth_op_id = 0;
+        let nonce = 0;
         let deadline_block = eth_sender.get_deadline_block(
             eth_sender
                 .ethereum
@@ -693,282 +1236,451 @@ async fn restore_state() {
                 .unwrap()
                 .as_u64(),
         );
-        let nonce = eth_op_id as i64;
-        let mut expected_tx = create_signed_tx(
-            eth_op_id as i64,
+        let mut stuck_tx = create_signed_tx(
+            eth_op_id,
             &eth_sender,
             aggregated_operation.clone(),
             deadline_block,
             nonce,
         )
         .await;
-        expected_tx.id = eth_op_id as i64;
 
-        eth_sender.db.assert_stored(&expected_tx).await;
+        let block_number = U64::from(
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .block_number()
+                .await
+                .unwrap()
+                .as_u64()
+                + EXPECTED_WAIT_TIME_BLOCKS,
+        );
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .set_block_number(block_number)
+            .await
+            .unwrap();
+        eth_sender.proceed_next_operations(0).await;
+
+        let next_tx = eth_sender
+            .create_supplement_tx(
+                eth_sender.get_deadline_block(
+                    eth_sender
+                        .ethereum
+                        .get_mock()
+                        .unwrap()
+                        .block_number()
+                        .await
+                        .unwrap()
+                        .as_u64(),
+                ),
+                &mut stuck_tx,
+            )
+            .await
+            .unwrap();
+        eth_sender.db.assert_stored(&stuck_tx).await;
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .assert_sent(&next_tx.hash.as_bytes().to_vec())
+            .await;
 
+        // Add a confirmation for a *stuck* transaction.
         eth_sender
             .ethereum
             .get_mut_mock()
             .unwrap()
-            .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+            .add_successfull_execution(stuck_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
             .await;
         eth_sender.proceed_next_operations(0).await;
 
-        expected_tx.confirmed = true;
-        expected_tx.final_hash = Some(expected_tx.used_tx_hashes[0]);
-        eth_sender.db.assert_confirmed(&expected_tx).await;
+        // Check that operation is confirmed (we set the final hash to the *first* sent tx).
+        stuck_tx.confirmed = true;
+        stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[0]);
+        eth_sender.db.assert_confirmed(&stuck_tx).await;
     }
 }
 
-/// Checks that even after getting the first transaction stuck and sending the next
-/// one, confirmation for the first (stuck) transaction is processed and leads
-/// to the operation commitment.
+/// This test is the same as `operations_order`, but configures ETH sender
+/// to use 3 transactions in flight, and checks that they are being sent concurrently.
 #[tokio::test]
-async fn confirmations_independence() {
-    // Workflow in the test is the same as in `stuck_transaction`, except for the fact
-    // that confirmation is obtained for the stuck transaction instead of the latter one.
+async fn concurrent_operations_order() {
+    for &tx_type in TX_TYPES {
+        const MAX_TXS_IN_FLIGHT: u64 = 3;
+        let mut eth_sender = concurrent_eth_sender(MAX_TXS_IN_FLIGHT, tx_type).await;
+
+        // We send multiple the operations at once to the channel.
+        let operations_count = 3;
+        let commit_operations = &test_data::COMMIT_BLOCKS_OPERATIONS[..operations_count];
+        let verify_operations =
+            &test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[..operations_count];
+        let execute_operations = &test_data::EXECUTE_BLOCKS_OPERATIONS[..operations_count];
+
+        // Also we create the list of expected transactions.
+        let mut expected_txs = Vec::new();
+
+        // Create expected txs from all the operations.
+        // Since we create 3 operations at each cycle iteration,
+        // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
+        // On the first iteration the indices 0, 1 and 2 will be taken, then it
+        // will be 3, 4 and 5, etc.
+        let operation_iterator = commit_operations
+            .iter()
+            .zip(verify_operations)
+            .zip(execute_operations);
+        for (idx, ((commit_operation, verify_operation), execute_operation)) in
+            operation_iterator.enumerate()
+        {
+            // Commit/verify/execute transactions from one iteration will be sent concurrently,
+            // thus the deadline block is the same for them.
+            let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
+            let deadline_block = eth_sender.get_deadline_block(start_block);
+
+            // Create the commit operation.
+            let eth_op_idx = (idx * 3) as i64;
+            let nonce = eth_op_idx;
+
+            let commit_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                commit_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
 
-    let mut eth_sender = default_eth_sender().await;
+            expected_txs.push(commit_op_tx);
 
-    let aggregated_operation = test_data::commit_blocks_operation(0);
-    eth_sender
-        .db
-        .send_aggregated_operation(aggregated_operation.clone())
-        .await
-        .unwrap();
+            // Create the verify operation, as by priority it will be processed right after `commit`.
+            let eth_op_idx = (idx * 3 + 1) as i64;
+            let nonce = eth_op_idx;
 
-    eth_sender.load_new_operations().await.unwrap();
-    eth_sender.proceed_next_operations(0).await;
+            let verify_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                verify_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
 
-    let eth_op_id = 0;
-    let nonce = 0;
-    let deadline_block = eth_sender.get_deadline_block(
-        eth_sender
-            .ethereum
-            .get_mock()
-            .unwrap()
-            .block_number()
-            .await
-            .unwrap()
-            .as_u64(),
-    );
-    let mut stuck_tx = create_signed_tx(
-        eth_op_id,
-        &eth_sender,
-        aggregated_operation.clone(),
-        deadline_block,
-        nonce,
-    )
-    .await;
+            expected_txs.push(verify_op_tx);
+
+            // Create the execute operation, as by priority it will be processed right after `verify`.
+            let eth_op_idx = (idx * 3 + 2) as i64;
+            let nonce = eth_op_idx;
+
+            let execute_op_tx = create_signed_tx(
+                eth_op_idx,
+                &eth_sender,
+                execute_operation.clone(),
+                deadline_block,
+                nonce,
+            )
+            .await;
+
+            expected_txs.push(execute_op_tx);
+        }
+
+        let mut operation_iterator = commit_operations
+            .iter()
+            .zip(verify_operations)
+            .zip(execute_operations);
+
+        // Then we go through the operations and check that the order of operations is preserved.
+        // Here we take N txs at each interaction.
+        for txs in expected_txs.chunks(MAX_TXS_IN_FLIGHT as usize) {
+            // We send operations by three, so the order will be "commit-verify-execute".
+            // If we'll send all the operations together, the order will be "commit-verify-execute".
+            let ((commit_op, verify_op), execute_op) = operation_iterator.next().unwrap();
+
+            eth_sender
+                .db
+                .send_aggregated_operation(commit_op.clone())
+                .await
+                .unwrap();
+            eth_sender
+                .db
+                .send_aggregated_operation(verify_op.clone())
+                .await
+                .unwrap();
+            eth_sender
+                .db
+                .send_aggregated_operation(execute_op.clone())
+                .await
+                .unwrap();
+
+            eth_sender.load_new_operations().await.unwrap();
+
+            // Call `proceed_next_operations`. Several txs should be sent.
+            eth_sender.proceed_next_operations(0).await;
+
+            let commit_tx = &txs[0];
+            let verify_tx = &txs[1];
+            let execute_tx = &txs[2];
+
+            // Check that commit/verify txs are sent and add the successful execution for them.
+            for tx in &[commit_tx, verify_tx, execute_tx] {
+                let current_tx_hash = tx.used_tx_hashes[0];
+
+                // Check that current expected tx is stored.
+                eth_sender.db.assert_stored(tx).await;
+                eth_sender
+                    .ethereum
+                    .get_mock()
+                    .unwrap()
+                    .assert_sent(&current_tx_hash.as_bytes().to_vec())
+                    .await;
+
+                // Mark the tx as successfully
+                eth_sender
+                    .ethereum
+                    .get_mut_mock()
+                    .unwrap()
+                    .add_successfull_execution(current_tx_hash, WAIT_CONFIRMATIONS)
+                    .await;
+            }
+
+            // Call `proceed_next_operations` again. Both txs should become confirmed.
+            eth_sender.proceed_next_operations(0).await;
+
+            for &tx in &[commit_tx, verify_tx, execute_tx] {
+                let mut tx = tx.clone();
+                let current_tx_hash = tx.used_tx_hashes[0];
+
+                // Update the fields in the tx and check if it's confirmed.
+                tx.confirmed = true;
+                tx.final_hash = Some(current_tx_hash);
+                eth_sender.db.assert_confirmed(&tx).await;
+            }
+        }
+    }
+}
 
-    let block_number = U64::from(
+/// Checks that the operations of the additional operator account are sent from its own nonce lane,
+/// and the dependent operations still wait for the confirmation of the preceding ones.
+#[tokio::test]
+async fn operator_lanes() {
+    let operator = Address::repeat_byte(0x11);
+    let account = OperatorAccount {
+        private_key: H256::repeat_byte(0x22),
+        address: operator,
+        action_types: vec![
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ],
+    };
+    let operator_gateway = EthereumGateway::Mock(MockEthereum::default());
+    let mut eth_sender = eth_sender_with_operator_lanes(1, vec![(account, operator_gateway)]).await;
+
+    for operation in &[
+        test_data::COMMIT_BLOCKS_OPERATIONS[0].clone(),
+        test_data::COMMIT_BLOCKS_OPERATIONS[1].clone(),
+        test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[0].clone(),
+        test_data::EXECUTE_BLOCKS_OPERATIONS[0].clone(),
+    ] {
         eth_sender
-            .ethereum
-            .get_mock()
-            .unwrap()
-            .block_number()
+            .db
+            .send_aggregated_operation(operation.clone())
             .await
-            .unwrap()
-            .as_u64()
-            + EXPECTED_WAIT_TIME_BLOCKS,
-    );
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
+
+    // Verify operation can't be sent from the other lane until the commit is confirmed.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    let commit_op = eth_sender.ongoing_ops[0].clone();
+    assert_eq!(commit_op.op_type, AggregatedActionType::CommitBlocks);
+    assert_eq!(commit_op.nonce, 0.into());
+
     eth_sender
         .ethereum
         .get_mut_mock()
         .unwrap()
-        .set_block_number(block_number)
-        .await
-        .unwrap();
+        .add_successfull_execution(commit_op.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .await;
     eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.ongoing_ops.is_empty());
 
-    let next_tx = eth_sender
-        .create_supplement_tx(
-            eth_sender.get_deadline_block(
-                eth_sender
-                    .ethereum
-                    .get_mock()
-                    .unwrap()
-                    .block_number()
-                    .await
-                    .unwrap()
-                    .as_u64(),
-            ),
-            &mut stuck_tx,
-        )
-        .await
+    // Now the next commit and the verify operations are sent in parallel with independent nonces.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    let verify_op = eth_sender
+        .ongoing_ops
+        .iter()
+        .find(|op| op.op_type == AggregatedActionType::PublishProofBlocksOnchain)
+        .cloned()
+        .unwrap();
+    let commit_op = eth_sender
+        .ongoing_ops
+        .iter()
+        .find(|op| op.op_type == AggregatedActionType::CommitBlocks)
+        .cloned()
         .unwrap();
-    eth_sender.db.assert_stored(&stuck_tx).await;
+    assert_eq!(verify_op.nonce, 0.into());
+    assert_eq!(commit_op.nonce, 1.into());
+    // The sending account is stored along with the operation.
+    assert_eq!(verify_op.operator, Some(operator));
+    assert_eq!(commit_op.operator, None);
+    eth_sender.operator_lanes[0]
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .assert_sent(&verify_op.used_tx_hashes[0].as_bytes().to_vec())
+        .await;
     eth_sender
         .ethereum
-        .get_mut_mock()
+        .get_mock()
         .unwrap()
-        .assert_sent(&next_tx.hash.as_bytes().to_vec())
+        .assert_sent(&commit_op.used_tx_hashes[0].as_bytes().to_vec())
         .await;
 
-    // Add a confirmation for a *stuck* transaction.
-    eth_sender
+    // Execute operation is sent from the same lane as the verify one, so it waits for a free slot.
+    eth_sender.operator_lanes[0]
         .ethereum
         .get_mut_mock()
         .unwrap()
-        .add_successfull_execution(stuck_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+        .add_successfull_execution(verify_op.used_tx_hashes[0], WAIT_CONFIRMATIONS)
         .await;
     eth_sender.proceed_next_operations(0).await;
+    eth_sender.proceed_next_operations(0).await;
 
-    // Check that operation is confirmed (we set the final hash to the *first* sent tx).
-    stuck_tx.confirmed = true;
-    stuck_tx.final_hash = Some(stuck_tx.used_tx_hashes[0]);
-    eth_sender.db.assert_confirmed(&stuck_tx).await;
+    let execute_op = eth_sender
+        .ongoing_ops
+        .iter()
+        .find(|op| op.op_type == AggregatedActionType::ExecuteBlocks)
+        .cloned()
+        .unwrap();
+    assert_eq!(execute_op.nonce, 1.into());
+    let operator_account = eth_sender.db.operator_account(operator).await.unwrap();
+    assert_eq!(operator_account.nonce, 2.into());
 }
 
-/// This test is the same as `operations_order`, but configures ETH sender
-/// to use 3 transactions in flight, and checks that they are being sent concurrently.
+/// Checks that the operations are held once the spending limit for their type is reached,
+/// taking into account the fees paid within the window and the fees of the pending transactions.
 #[tokio::test]
-async fn concurrent_operations_order() {
-    const MAX_TXS_IN_FLIGHT: u64 = 3;
-    let mut eth_sender = concurrent_eth_sender(MAX_TXS_IN_FLIGHT).await;
-
-    // We send multiple the operations at once to the channel.
-    let operations_count = 3;
-    let commit_operations = &test_data::COMMIT_BLOCKS_OPERATIONS[..operations_count];
-    let verify_operations = &test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[..operations_count];
-    let execute_operations = &test_data::EXECUTE_BLOCKS_OPERATIONS[..operations_count];
-
-    // Also we create the list of expected transactions.
-    let mut expected_txs = Vec::new();
-
-    // Create expected txs from all the operations.
-    // Since we create 3 operations at each cycle iteration,
-    // the logic of ID calculating is (i * 3), (i * 3 + 1), (i * 3 + 2).
-    // On the first iteration the indices 0, 1 and 2 will be taken, then it
-    // will be 3, 4 and 5, etc.
-    let operation_iterator = commit_operations
-        .iter()
-        .zip(verify_operations)
-        .zip(execute_operations);
-    for (idx, ((commit_operation, verify_operation), execute_operation)) in
-        operation_iterator.enumerate()
-    {
-        // Commit/verify/execute transactions from one iteration will be sent concurrently,
-        // thus the deadline block is the same for them.
-        let start_block = 1 + WAIT_CONFIRMATIONS * (idx * 3) as u64;
-        let deadline_block = eth_sender.get_deadline_block(start_block);
-
-        // Create the commit operation.
-        let eth_op_idx = (idx * 3) as i64;
-        let nonce = eth_op_idx;
-
-        let commit_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            commit_operation.clone(),
-            deadline_block,
-            nonce,
-        )
-        .await;
+async fn spending_limit() {
+    const GWEI: u64 = 1_000_000_000;
 
-        expected_txs.push(commit_op_tx);
+    let mut eth_sender = concurrent_eth_sender(3, EthTxType::Legacy).await;
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_gas_price(GWEI.into())
+        .await
+        .unwrap();
 
-        // Create the verify operation, as by priority it will be processed right after `commit`.
-        let eth_op_idx = (idx * 3 + 1) as i64;
-        let nonce = eth_op_idx;
+    let operations = [
+        test_data::COMMIT_BLOCKS_OPERATIONS[0].clone(),
+        test_data::COMMIT_BLOCKS_OPERATIONS[1].clone(),
+    ];
+    // Fee of every commit transaction in gwei.
+    let tx_fee = ETHSender::<MockDatabase>::gas_limit_for_aggregated_op(&operations[0].1).as_u64();
+    eth_sender.options.spending_limit.commit_limit = Some(tx_fee * 5 / 2);
 
-        let verify_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            verify_operation.clone(),
-            deadline_block,
-            nonce,
+    // Only the fee paid within the window is taken into account.
+    let period = eth_sender.options.spending_limit.period();
+    eth_sender
+        .db
+        .add_paid_fee(
+            AggregatedActionType::CommitBlocks,
+            U256::from(tx_fee * GWEI) * 10,
+            period + Duration::from_secs(1),
         )
         .await;
-
-        expected_txs.push(verify_op_tx);
-
-        // Create the execute operation, as by priority it will be processed right after `verify`.
-        let eth_op_idx = (idx * 3 + 2) as i64;
-        let nonce = eth_op_idx;
-
-        let execute_op_tx = create_signed_tx(
-            eth_op_idx,
-            &eth_sender,
-            execute_operation.clone(),
-            deadline_block,
-            nonce,
+    eth_sender
+        .db
+        .add_paid_fee(
+            AggregatedActionType::CommitBlocks,
+            U256::from(tx_fee * GWEI),
+            Duration::from_secs(60),
         )
         .await;
 
-        expected_txs.push(execute_op_tx);
-    }
-
-    let mut operation_iterator = commit_operations
-        .iter()
-        .zip(verify_operations)
-        .zip(execute_operations);
-
-    // Then we go through the operations and check that the order of operations is preserved.
-    // Here we take N txs at each interaction.
-    for txs in expected_txs.chunks(MAX_TXS_IN_FLIGHT as usize) {
-        // We send operations by three, so the order will be "commit-verify-execute".
-        // If we'll send all the operations together, the order will be "commit-verify-execute".
-        let ((commit_op, verify_op), execute_op) = operation_iterator.next().unwrap();
-
-        eth_sender
-            .db
-            .send_aggregated_operation(commit_op.clone())
-            .await
-            .unwrap();
-        eth_sender
-            .db
-            .send_aggregated_operation(verify_op.clone())
-            .await
-            .unwrap();
+    for operation in &operations {
         eth_sender
             .db
-            .send_aggregated_operation(execute_op.clone())
+            .send_aggregated_operation(operation.clone())
             .await
             .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
 
-        eth_sender.load_new_operations().await.unwrap();
-
-        // Call `proceed_next_operations`. Several txs should be sent.
-        eth_sender.proceed_next_operations(0).await;
+    // The second operation would exceed the limit along with the paid fee and the pending transaction.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+    assert_eq!(eth_sender.ongoing_ops[0].op, Some(operations[0].clone()));
+    assert!(eth_sender
+        .spending_limit_reached
+        .contains(&AggregatedActionType::CommitBlocks));
 
-        let commit_tx = &txs[0];
-        let verify_tx = &txs[1];
-        let execute_tx = &txs[2];
+    // Operation is held until the limit allows to send it.
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
 
-        // Check that commit/verify txs are sent and add the successful execution for them.
-        for tx in &[commit_tx, verify_tx, execute_tx] {
-            let current_tx_hash = tx.used_tx_hashes[0];
+    eth_sender.options.spending_limit.commit_limit = Some(tx_fee * 3);
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    assert_eq!(eth_sender.ongoing_ops[1].op, Some(operations[1].clone()));
+    assert!(eth_sender.spending_limit_reached.is_empty());
+}
 
-            // Check that current expected tx is stored.
-            eth_sender.db.assert_stored(tx).await;
-            eth_sender
-                .ethereum
-                .get_mock()
-                .unwrap()
-                .assert_sent(&current_tx_hash.as_bytes().to_vec())
-                .await;
+/// Checks that the execute operations are held while the balance of the operator account is low,
+/// and the operations of other types are still sent.
+#[tokio::test]
+async fn low_operator_balance() {
+    const GWEI: u64 = 1_000_000_000;
 
-            // Mark the tx as successfully
-            eth_sender
-                .ethereum
-                .get_mut_mock()
-                .unwrap()
-                .add_successfull_execution(current_tx_hash, WAIT_CONFIRMATIONS)
-                .await;
-        }
+    let mut eth_sender = concurrent_eth_sender(3, EthTxType::Legacy).await;
+    eth_sender.options.spending_limit.min_operator_balance = Some(GWEI);
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_sender_eth_balance(U256::from(GWEI) * GWEI / 2)
+        .await
+        .unwrap();
 
-        // Call `proceed_next_operations` again. Both txs should become confirmed.
-        eth_sender.proceed_next_operations(0).await;
+    for operation in &[
+        test_data::COMMIT_BLOCKS_OPERATIONS[0].clone(),
+        test_data::PUBLISH_PROOF_BLOCKS_ONCHAIN_OPERATIONS[0].clone(),
+        test_data::EXECUTE_BLOCKS_OPERATIONS[0].clone(),
+    ] {
+        eth_sender
+            .db
+            .send_aggregated_operation(operation.clone())
+            .await
+            .unwrap();
+    }
+    eth_sender.load_new_operations().await.unwrap();
 
-        for &tx in &[commit_tx, verify_tx, execute_tx] {
-            let mut tx = tx.clone();
-            let current_tx_hash = tx.used_tx_hashes[0];
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 2);
+    assert!(eth_sender
+        .ongoing_ops
+        .iter()
+        .all(|op| op.op_type != AggregatedActionType::ExecuteBlocks));
+    assert!(eth_sender.low_operator_balance);
 
-            // Update the fields in the tx and check if it's confirmed.
-            tx.confirmed = true;
-            tx.final_hash = Some(current_tx_hash);
-            eth_sender.db.assert_confirmed(&tx).await;
-        }
-    }
+    // Once the account is funded, the execute operation is sent.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_sender_eth_balance(U256::from(GWEI) * GWEI * 2)
+        .await
+        .unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 3);
+    assert_eq!(
+        eth_sender.ongoing_ops[2].op_type,
+        AggregatedActionType::ExecuteBlocks
+    );
+    assert!(!eth_sender.low_operator_balance);
 }
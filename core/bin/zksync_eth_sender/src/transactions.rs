@@ -5,8 +5,9 @@
 
 // Built-in deps
 // External uses
-use zksync_basic_types::TransactionReceipt;
+use zksync_basic_types::{TransactionReceipt, U256};
 // Workspace uses
+use zksync_eth_client::ethereum_gateway::FailureInfo;
 use zksync_storage::ethereum::records::ETHStats as StorageETHStats;
 
 /// Collected statistics of the amount of operations sent to the Ethereum.
@@ -37,13 +38,77 @@ impl From<StorageETHStats> for ETHStats {
 #[derive(Debug, PartialEq)]
 pub enum TxCheckOutcome {
     /// Transaction was committed and confirmed.
-    Committed,
+    Committed(PaidFee),
     /// Transaction is pending yet.
     Pending,
     /// Transaction is considered stuck, a replacement should be made.
     Stuck,
     /// Transaction execution failed. Receipt is boxed to reduce the enum object size.
-    Failed(Box<TransactionReceipt>),
+    /// The fee is paid for the failed transaction as well.
+    Failed(Box<TransactionReceipt>, PaidFee),
+}
+
+/// Fee paid for the executed Ethereum transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaidFee {
+    /// Amount of gas used by the transaction.
+    pub gas_used: U256,
+    /// Price paid per unit of gas.
+    pub gas_price: U256,
+}
+
+impl PaidFee {
+    /// Returns the total fee paid for the transaction.
+    pub fn total(&self) -> U256 {
+        self.gas_used * self.gas_price
+    }
+}
+
+/// Revert codes of the zkSync contract meaning that the operation was applied out of order:
+/// - `o1`: proven blocks don't follow the last proven block;
+/// - `q`: proven blocks are not committed yet;
+/// - `k`: executed blocks don't follow the last executed block;
+/// - `n`: executed blocks are not proven yet.
+const NONCE_RACE_REVERT_CODES: [&str; 4] = ["o1", "q", "k", "n"];
+
+/// Kind of the Ethereum transaction failure, obtained from the failure reason.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TxFailure {
+    /// Transaction used all the provided gas.
+    OutOfGas,
+    /// Transaction lost the nonce race with a transaction sent from another operator account:
+    /// it was reverted for being applied out of order (see `NONCE_RACE_REVERT_CODES`).
+    NonceRace,
+    /// Transaction was reverted by the contract.
+    ContractRevert,
+    /// Failure reason could not be obtained.
+    Unknown,
+}
+
+impl TxFailure {
+    pub fn from_failure_info(failure_info: Option<&FailureInfo>) -> Self {
+        let failure_info = match failure_info {
+            Some(failure_info) => failure_info,
+            None => return Self::Unknown,
+        };
+
+        let out_of_gas = failure_info
+            .gas_used
+            .map(|gas_used| gas_used >= failure_info.gas_limit)
+            .unwrap_or(false);
+        if out_of_gas {
+            Self::OutOfGas
+        } else if NONCE_RACE_REVERT_CODES.contains(&failure_info.revert_reason.trim()) {
+            Self::NonceRace
+        } else {
+            Self::ContractRevert
+        }
+    }
+
+    /// Returns `true` if the transaction may succeed being sent again.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::OutOfGas | Self::NonceRace)
+    }
 }
 
 /// Enumeration denoting if the operation was successfully committed, or not yet.
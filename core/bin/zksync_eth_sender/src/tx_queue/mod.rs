@@ -53,29 +53,50 @@ impl TxData {
 #[derive(Debug)]
 pub struct TxQueueBuilder {
     max_pending_txs: usize,
-    sent_pending_txs: usize,
+    lanes: Vec<Vec<AggregatedActionType>>,
+    sent_pending_operations: Vec<AggregatedActionType>,
 
     commit_operations_count: usize,
     verify_operations_count: usize,
     execute_operations_count: usize,
+
+    confirmed_commit_block: Option<BlockNumber>,
+    confirmed_verify_block: Option<BlockNumber>,
 }
 
 impl TxQueueBuilder {
     /// Initializes queue building process.
+    /// By default all the operations are sent from the single lane.
     pub fn new(max_pending_txs: usize) -> Self {
         Self {
             max_pending_txs,
-            sent_pending_txs: 0,
+            lanes: vec![vec![
+                AggregatedActionType::CommitBlocks,
+                AggregatedActionType::PublishProofBlocksOnchain,
+                AggregatedActionType::ExecuteBlocks,
+            ]],
+            sent_pending_operations: Vec::new(),
             commit_operations_count: 0,
             verify_operations_count: 0,
             execute_operations_count: 0,
+            confirmed_commit_block: None,
+            confirmed_verify_block: None,
         }
     }
 
-    /// Sets the amount of transactions sent to the Ethereum blockchain, but not confirmed yet.
-    pub fn with_sent_pending_txs(self, sent_pending_txs: usize) -> Self {
+    /// Sets the action types sent by every lane (operator account).
+    /// Every action type must belong to exactly one lane.
+    pub fn with_lanes(self, lanes: Vec<Vec<AggregatedActionType>>) -> Self {
+        Self { lanes, ..self }
+    }
+
+    /// Sets the types of operations sent to the Ethereum blockchain, but not confirmed yet.
+    pub fn with_sent_pending_operations(
+        self,
+        sent_pending_operations: Vec<AggregatedActionType>,
+    ) -> Self {
         Self {
-            sent_pending_txs,
+            sent_pending_operations,
             ..self
         }
     }
@@ -104,11 +125,42 @@ impl TxQueueBuilder {
         }
     }
 
+    /// Sets the last block with the confirmed `commit` operation.
+    /// If not set, all the sent `commit` operations are considered confirmed.
+    pub fn with_confirmed_commit_block(self, confirmed_commit_block: BlockNumber) -> Self {
+        Self {
+            confirmed_commit_block: Some(confirmed_commit_block),
+            ..self
+        }
+    }
+
+    /// Sets the last block with the confirmed `verify` operation.
+    /// If not set, all the sent `verify` operations are considered confirmed.
+    pub fn with_confirmed_verify_block(self, confirmed_verify_block: BlockNumber) -> Self {
+        Self {
+            confirmed_verify_block: Some(confirmed_verify_block),
+            ..self
+        }
+    }
+
     /// Finishes the queue building process.
     pub fn build(self) -> TxQueue {
+        let mut lanes: Vec<_> = self
+            .lanes
+            .into_iter()
+            .map(|action_types| TxLane {
+                action_types,
+                sent_pending_txs: 0,
+            })
+            .collect();
+        for op_type in self.sent_pending_operations {
+            let lane = lane_index(&lanes, op_type);
+            lanes[lane].sent_pending_txs += 1;
+        }
+
         TxQueue {
             max_pending_txs: self.max_pending_txs,
-            sent_pending_txs: self.sent_pending_txs,
+            lanes,
 
             commit_operations: OperationQueue::new(BlockNumber(
                 self.commit_operations_count as u32,
@@ -119,16 +171,41 @@ impl TxQueueBuilder {
             execute_operations: OperationQueue::new(BlockNumber(
                 self.execute_operations_count as u32,
             )),
+
+            confirmed_commit_block: self
+                .confirmed_commit_block
+                .unwrap_or(BlockNumber(self.commit_operations_count as u32)),
+            confirmed_verify_block: self
+                .confirmed_verify_block
+                .unwrap_or(BlockNumber(self.verify_operations_count as u32)),
+
+            held_types: Vec::new(),
         }
     }
 }
 
+/// Lane of the operator account sending the transactions of the given action types.
+/// Transactions of different lanes have independent nonces.
+#[derive(Debug)]
+struct TxLane {
+    action_types: Vec<AggregatedActionType>,
+    sent_pending_txs: usize,
+}
+
+/// Returns the index of the lane sending the operations of the given type.
+fn lane_index(lanes: &[TxLane], op_type: AggregatedActionType) -> usize {
+    lanes
+        .iter()
+        .position(|lane| lane.action_types.contains(&op_type))
+        .unwrap_or_else(|| panic!("No lane is set for {:?} operations", op_type))
+}
+
 /// Transaction queue combines the underlying operations queues and determines
 /// the transaction sending policy. It chooses the next operation to send out of
 /// these queues, using the following rules:
 ///
-/// 1. If the amount of sent transactions is equal to the `MAX_PENDING_TXS` value,
-///   no transaction is yielded until some of already sent ones are committed.
+/// 1. If the amount of sent transactions of the lane is equal to the `MAX_PENDING_TXS` value,
+///   no transaction of this lane is yielded until some of already sent ones are committed.
 /// 2. Otherwise, transactions are yielded according to the following policy:
 ///   - If `execute` queue contains elements for some blocks, and `verify` operations
 ///     for corresponding blocks is committed, the `execute` operation is yielded.
@@ -136,14 +213,27 @@ impl TxQueueBuilder {
 ///     for corresponding blocks is committed, the `verify` operation is yielded.
 ///   - Otherwise, if `commit` queue is not empty, a `commit` operation is yielded.
 /// 3. If all the queues are empty, no operation is returned.
+///
+/// Operations of the held types are not yielded until released, while the operations
+/// of the other types are yielded according to the rules above.
+///
+/// Every lane corresponds to the operator account with its own nonces. If the operations
+/// of the dependent types are sent from the same lane, the nonces guarantee the order of
+/// their execution, so it's enough for the preceding operation to be sent. Otherwise the
+/// preceding operation must be confirmed on the Ethereum blockchain.
 #[derive(Debug)]
 pub struct TxQueue {
     max_pending_txs: usize,
-    sent_pending_txs: usize,
+    lanes: Vec<TxLane>,
 
     commit_operations: OperationQueue,
     verify_operations: OperationQueue,
     execute_operations: OperationQueue,
+
+    confirmed_commit_block: BlockNumber,
+    confirmed_verify_block: BlockNumber,
+
+    held_types: Vec<AggregatedActionType>,
 }
 
 impl TxQueue {
@@ -156,7 +246,7 @@ impl TxQueue {
             Sent pending txs count: {}, \
             max pending txs count: {}, \
             size of commit queue: {}",
-            self.sent_pending_txs,
+            self.sent_pending_txs(AggregatedActionType::CommitBlocks),
             self.max_pending_txs,
             self.commit_operations.len()
         );
@@ -172,7 +262,7 @@ impl TxQueue {
             Sent pending txs count: {}, \
             max pending txs count: {}, \
             size of verify queue: {}",
-            self.sent_pending_txs,
+            self.sent_pending_txs(AggregatedActionType::PublishProofBlocksOnchain),
             self.max_pending_txs,
             self.verify_operations.len()
         );
@@ -188,7 +278,7 @@ impl TxQueue {
             Sent pending txs count: {}, \
             max pending txs count: {}, \
             size of execute queue: {}",
-            self.sent_pending_txs,
+            self.sent_pending_txs(AggregatedActionType::ExecuteBlocks),
             self.max_pending_txs,
             self.execute_operations.len()
         );
@@ -197,8 +287,9 @@ impl TxQueue {
 
     /// Returns a previously popped element to the front of the queue.
     pub fn return_popped(&mut self, element: TxData) -> anyhow::Result<()> {
+        let lane = lane_index(&self.lanes, element.op_type);
         assert!(
-            self.sent_pending_txs > 0,
+            self.lanes[lane].sent_pending_txs > 0,
             "No transactions are expected to be returned"
         );
 
@@ -221,24 +312,70 @@ impl TxQueue {
 
         // We've incremented the counter when transaction was popped.
         // Now it's returned and counter should be decremented back.
-        self.sent_pending_txs -= 1;
+        self.lanes[lane].sent_pending_txs -= 1;
         Ok(())
     }
 
     /// Gets the next transaction to send, according to the transaction sending policy.
     /// For details, see the structure doc-comment.
     pub fn pop_front(&mut self) -> Option<TxData> {
-        if self.sent_pending_txs >= self.max_pending_txs {
-            return None;
+        // Get the next operation and increment the sent counter of its lane.
+        let op = self.get_next_operation()?;
+        let lane = lane_index(&self.lanes, op.op_type);
+        self.lanes[lane].sent_pending_txs += 1;
+        Some(op)
+    }
+
+    /// Holds the operations of the given type, so they are not yielded until released.
+    pub fn hold(&mut self, op_type: AggregatedActionType) {
+        if !self.is_held(op_type) {
+            self.held_types.push(op_type);
         }
+    }
 
-        // Get the next operation and increment the sent counter if needed.
-        match self.get_next_operation() {
-            Some(op) => {
-                self.sent_pending_txs += 1;
-                Some(op)
-            }
-            None => None,
+    /// Releases the operations of all the held types.
+    pub fn release_held(&mut self) {
+        self.held_types.clear();
+    }
+
+    /// Checks whether the operations of the given type are held.
+    fn is_held(&self, op_type: AggregatedActionType) -> bool {
+        self.held_types.contains(&op_type)
+    }
+
+    /// Returns the amount of sent transactions of the lane sending the operations of the given type.
+    fn sent_pending_txs(&self, op_type: AggregatedActionType) -> usize {
+        self.lanes[lane_index(&self.lanes, op_type)].sent_pending_txs
+    }
+
+    /// Checks whether the lane sending the operations of the given type can send one more transaction.
+    fn has_free_slot(&self, op_type: AggregatedActionType) -> bool {
+        self.sent_pending_txs(op_type) < self.max_pending_txs
+    }
+
+    /// Returns the last block of the `previous_type` operations that allows to send the
+    /// operations of `op_type` for the same blocks.
+    fn last_ready_block(
+        &self,
+        op_type: AggregatedActionType,
+        previous_type: AggregatedActionType,
+    ) -> BlockNumber {
+        let (sent_block, confirmed_block) = match previous_type {
+            AggregatedActionType::CommitBlocks => (
+                self.commit_operations.get_last_block_number(),
+                self.confirmed_commit_block,
+            ),
+            AggregatedActionType::PublishProofBlocksOnchain => (
+                self.verify_operations.get_last_block_number(),
+                self.confirmed_verify_block,
+            ),
+            _ => unreachable!("No operations depend on {:?}", previous_type),
+        };
+
+        if lane_index(&self.lanes, op_type) == lane_index(&self.lanes, previous_type) {
+            sent_block
+        } else {
+            confirmed_block
         }
     }
 
@@ -247,33 +384,62 @@ impl TxQueue {
     fn get_next_operation(&mut self) -> Option<TxData> {
         // 1. Highest priority: execute operations.
         if let Some(next_execute_block) = self.execute_operations.get_next_last_block_number() {
-            let current_verify_block = self.verify_operations.get_last_block_number();
-            if *next_execute_block <= *current_verify_block {
+            let current_verify_block = self.last_ready_block(
+                AggregatedActionType::ExecuteBlocks,
+                AggregatedActionType::PublishProofBlocksOnchain,
+            );
+            if *next_execute_block <= *current_verify_block
+                && self.has_free_slot(AggregatedActionType::ExecuteBlocks)
+                && !self.is_held(AggregatedActionType::ExecuteBlocks)
+            {
                 return Some(self.execute_operations.pop_front().unwrap());
             }
         }
 
         // 2. After execute operations we should process verify operation.
         if let Some(next_verify_block) = self.verify_operations.get_next_last_block_number() {
-            let current_commit_block = self.commit_operations.get_last_block_number();
-            if *next_verify_block <= *current_commit_block {
+            let current_commit_block = self.last_ready_block(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                AggregatedActionType::CommitBlocks,
+            );
+            if *next_verify_block <= *current_commit_block
+                && self.has_free_slot(AggregatedActionType::PublishProofBlocksOnchain)
+                && !self.is_held(AggregatedActionType::PublishProofBlocksOnchain)
+            {
                 return Some(self.verify_operations.pop_front().unwrap());
             }
         }
 
         // 3. Finally, check the commit queue.
-        self.commit_operations.pop_front()
+        if self.has_free_slot(AggregatedActionType::CommitBlocks)
+            && !self.is_held(AggregatedActionType::CommitBlocks)
+        {
+            return self.commit_operations.pop_front();
+        }
+        None
     }
 
     /// Notifies the queue about the transaction being confirmed on the Ethereum blockchain.
-    /// Decrements the amount of transactions "in the fly".
-    pub fn report_commitment(&mut self) {
+    /// Decrements the amount of transactions "in the fly" of the lane.
+    pub fn report_commitment(&mut self, op_type: AggregatedActionType, last_block: BlockNumber) {
+        let lane = lane_index(&self.lanes, op_type);
         assert!(
-            self.sent_pending_txs > 0,
+            self.lanes[lane].sent_pending_txs > 0,
             "No transactions are expected to be confirmed"
         );
 
-        self.sent_pending_txs -= 1;
+        self.lanes[lane].sent_pending_txs -= 1;
+        match op_type {
+            AggregatedActionType::CommitBlocks => {
+                self.confirmed_commit_block =
+                    std::cmp::max(self.confirmed_commit_block, last_block);
+            }
+            AggregatedActionType::PublishProofBlocksOnchain => {
+                self.confirmed_verify_block =
+                    std::cmp::max(self.confirmed_verify_block, last_block);
+            }
+            _ => {}
+        }
     }
 }
 
@@ -367,7 +533,7 @@ mod tests {
         assert_eq!(queue.pop_front(), None);
 
         // Report that one operation is completed.
-        queue.report_commitment();
+        queue.report_commitment(AggregatedActionType::CommitBlocks, BlockNumber(1));
 
         // Now we should obtain the next commit operation.
         let op_4 = queue.pop_front().unwrap();
@@ -377,11 +543,14 @@ mod tests {
         assert_eq!(queue.pop_front(), None);
 
         // Report the remaining three operations as completed.
-        assert_eq!(queue.sent_pending_txs, MAX_IN_FLY);
-        for _ in 0..MAX_IN_FLY {
-            queue.report_commitment();
-        }
-        assert_eq!(queue.sent_pending_txs, 0);
+        assert_eq!(queue.lanes[0].sent_pending_txs, MAX_IN_FLY);
+        queue.report_commitment(
+            AggregatedActionType::PublishProofBlocksOnchain,
+            BlockNumber(1),
+        );
+        queue.report_commitment(AggregatedActionType::ExecuteBlocks, BlockNumber(1));
+        queue.report_commitment(AggregatedActionType::CommitBlocks, BlockNumber(2));
+        assert_eq!(queue.lanes[0].sent_pending_txs, 0);
 
         // Pop remaining operations.
         let op_5 = queue.pop_front().unwrap();
@@ -393,19 +562,144 @@ mod tests {
         // Though the limit is not met (2 txs in fly, and limit is 3), there should be no txs in the queue.
         assert_eq!(queue.pop_front(), None);
 
-        let pending_count = queue.sent_pending_txs;
+        let pending_count = queue.lanes[0].sent_pending_txs;
 
         // Return the operation to the queue.
         queue.return_popped(op_6).unwrap();
 
         // Now, as we've returned tx to queue, pending count should be decremented.
-        assert_eq!(queue.sent_pending_txs, pending_count - 1);
+        assert_eq!(queue.lanes[0].sent_pending_txs, pending_count - 1);
 
         let op_6 = queue.pop_front().unwrap();
         assert_eq!(op_6.raw, vec![EXECUTE_MARK, 1]);
 
         // We've popped the tx once again, now pending count should be increased.
-        assert_eq!(queue.sent_pending_txs, pending_count);
+        assert_eq!(queue.lanes[0].sent_pending_txs, pending_count);
+    }
+
+    /// Checks that the lanes are limited independently, and the operations sent from another
+    /// lane wait for the preceding operations to be confirmed.
+    #[test]
+    fn lanes() {
+        const MAX_IN_FLY: usize = 1;
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+        const EXECUTE_MARK: u8 = 2;
+
+        // Commit operations are sent from the first lane, the others are sent from the second one.
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY)
+            .with_lanes(vec![
+                vec![AggregatedActionType::CommitBlocks],
+                vec![
+                    AggregatedActionType::PublishProofBlocksOnchain,
+                    AggregatedActionType::ExecuteBlocks,
+                ],
+            ])
+            .build();
+
+        for block in 1..=2 {
+            queue
+                .add_commit_operation(get_tx_data(
+                    AggregatedActionType::CommitBlocks,
+                    BlockNumber(block),
+                    vec![COMMIT_MARK, block as u8],
+                ))
+                .unwrap();
+            queue
+                .add_verify_operation(get_tx_data(
+                    AggregatedActionType::PublishProofBlocksOnchain,
+                    BlockNumber(block),
+                    vec![VERIFY_MARK, block as u8],
+                ))
+                .unwrap();
+            queue
+                .add_execute_operation(get_tx_data(
+                    AggregatedActionType::ExecuteBlocks,
+                    BlockNumber(block),
+                    vec![EXECUTE_MARK, block as u8],
+                ))
+                .unwrap();
+        }
+
+        // `verify` can't be sent until the `commit` from another lane is confirmed,
+        // and the next `commit` can't be sent since the first lane is full.
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![COMMIT_MARK, 1]);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.report_commitment(AggregatedActionType::CommitBlocks, BlockNumber(1));
+
+        // Both lanes have a free slot now.
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![VERIFY_MARK, 1]);
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![COMMIT_MARK, 2]);
+        assert_eq!(queue.pop_front(), None);
+
+        // `execute` is sent from the same lane as `verify`, so it doesn't wait for
+        // the `verify` confirmation, but only for a free slot.
+        queue.report_commitment(
+            AggregatedActionType::PublishProofBlocksOnchain,
+            BlockNumber(1),
+        );
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![EXECUTE_MARK, 1]);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.report_commitment(AggregatedActionType::ExecuteBlocks, BlockNumber(1));
+        queue.report_commitment(AggregatedActionType::CommitBlocks, BlockNumber(2));
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![VERIFY_MARK, 2]);
+        assert_eq!(queue.pop_front(), None);
+    }
+
+    /// Checks that the operations of the held types are skipped until released.
+    #[test]
+    fn held_operations() {
+        const MAX_IN_FLY: usize = 4;
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+        const EXECUTE_MARK: u8 = 2;
+
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY).build();
+        for block in 1..=2 {
+            queue
+                .add_commit_operation(get_tx_data(
+                    AggregatedActionType::CommitBlocks,
+                    BlockNumber(block),
+                    vec![COMMIT_MARK, block as u8],
+                ))
+                .unwrap();
+        }
+        queue
+            .add_verify_operation(get_tx_data(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                BlockNumber(1),
+                vec![VERIFY_MARK, 1],
+            ))
+            .unwrap();
+        queue
+            .add_execute_operation(get_tx_data(
+                AggregatedActionType::ExecuteBlocks,
+                BlockNumber(1),
+                vec![EXECUTE_MARK, 1],
+            ))
+            .unwrap();
+
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![COMMIT_MARK, 1]);
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![VERIFY_MARK, 1]);
+
+        // Held `execute` operation is skipped, so the next `commit` is yielded instead.
+        queue.hold(AggregatedActionType::ExecuteBlocks);
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![COMMIT_MARK, 2]);
+        assert_eq!(queue.pop_front(), None);
+
+        queue.release_held();
+        let op = queue.pop_front().unwrap();
+        assert_eq!(op.raw, vec![EXECUTE_MARK, 1]);
     }
 
     #[test]
@@ -5,10 +5,13 @@
 // Built-in uses
 // Workspace uses
 use zksync_config::ZkSyncConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::event::EventId;
 // External uses
 use actix::prelude::*;
 use actix_web::{web, App, Error, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
+use serde::Deserialize;
 // Local uses
 use listener::EventListener;
 use messages::RegisterServerHandle;
@@ -20,17 +23,34 @@ pub mod messages;
 pub mod monitor;
 pub mod subscriber;
 
+/// Size of the connection pool shared by subscribers to replay stored events.
+const REPLAY_DB_POOL_SIZE: u32 = 4;
+
 #[derive(Debug)]
 struct AppState {
     server_monitor: Addr<ServerMonitor>,
+    db_pool: ConnectionPool,
+}
+
+/// Query parameters accepted on connect.
+#[derive(Debug, Deserialize)]
+struct ConnectQuery {
+    /// The id of the last event processed by the client.
+    last_event_id: Option<u64>,
 }
 
 async fn ws_index(
     req: HttpRequest,
     stream: web::Payload,
     data: web::Data<AppState>,
+    query: web::Query<ConnectQuery>,
 ) -> Result<HttpResponse, Error> {
-    ws::start(Subscriber::new(data.server_monitor.clone()), &req, stream)
+    let subscriber = Subscriber::new(
+        data.server_monitor.clone(),
+        data.db_pool.clone(),
+        query.last_event_id.map(EventId),
+    );
+    ws::start(subscriber, &req, stream)
 }
 
 pub async fn run_event_server(config: ZkSyncConfig) {
@@ -42,6 +62,7 @@ pub async fn run_event_server(config: ZkSyncConfig) {
 
     let state = web::Data::new(AppState {
         server_monitor: monitor.clone(),
+        db_pool: ConnectionPool::new(Some(REPLAY_DB_POOL_SIZE)),
     });
 
     let server = HttpServer::new(move || {
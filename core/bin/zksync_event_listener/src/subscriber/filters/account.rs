@@ -8,6 +8,7 @@ use zksync_types::{
     AccountId, TokenId,
 };
 // Local uses
+use super::Filter;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -17,8 +18,8 @@ pub struct AccountFilter {
     pub status: Option<AccountStateChangeStatus>,
 }
 
-impl AccountFilter {
-    pub fn matches(&self, event: &ZkSyncEvent) -> bool {
+impl Filter for AccountFilter {
+    fn matches(&self, event: &ZkSyncEvent) -> bool {
         let account_event = match &event.data {
             EventData::Account(account_event) => account_event,
             _ => return false,
@@ -4,6 +4,7 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_types::event::{block::*, EventData, ZkSyncEvent};
 // Local uses
+use super::Filter;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -11,8 +12,8 @@ pub struct BlockFilter {
     pub status: Option<BlockStatus>,
 }
 
-impl BlockFilter {
-    pub fn matches(&self, event: &ZkSyncEvent) -> bool {
+impl Filter for BlockFilter {
+    fn matches(&self, event: &ZkSyncEvent) -> bool {
         let block_event = match &event.data {
             EventData::Block(block_event) => block_event,
             _ => return false,
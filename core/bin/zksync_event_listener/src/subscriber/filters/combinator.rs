@@ -0,0 +1,104 @@
+// Built-in uses
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_types::event::ZkSyncEvent;
+// Local uses
+
+/// Common interface of the event filters.
+pub trait Filter {
+    fn matches(&self, event: &ZkSyncEvent) -> bool;
+}
+
+/// Boolean expression over the filters of the same kind.
+///
+/// Deserialized either from a plain filter or from an object with a single
+/// `any`, `all` or `not` key, e.g.
+/// `{ "any": [{ "types": ["Swap"] }, { "not": { "tokens": [0] } }] }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FilterExpr<F> {
+    Any(AnyOf<F>),
+    All(AllOf<F>),
+    Not(NotOf<F>),
+    Filter(F),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnyOf<F> {
+    pub any: Vec<FilterExpr<F>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AllOf<F> {
+    pub all: Vec<FilterExpr<F>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotOf<F> {
+    pub not: Box<FilterExpr<F>>,
+}
+
+impl<F: Filter> Filter for FilterExpr<F> {
+    fn matches(&self, event: &ZkSyncEvent) -> bool {
+        match self {
+            FilterExpr::Any(expr) => expr.any.iter().any(|filter| filter.matches(event)),
+            FilterExpr::All(expr) => expr.all.iter().all(|filter| filter.matches(event)),
+            FilterExpr::Not(expr) => !expr.not.matches(event),
+            FilterExpr::Filter(filter) => filter.matches(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subscriber::filters::block::BlockFilter;
+    use zksync_types::event::{block::BlockStatus, test_data::get_block_event};
+
+    fn deserialize(input: &str) -> FilterExpr<BlockFilter> {
+        serde_json::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_filter_expr() {
+        let committed = get_block_event(BlockStatus::Committed);
+        let finalized = get_block_event(BlockStatus::Finalized);
+        let reverted = get_block_event(BlockStatus::Reverted);
+
+        // Plain filter.
+        let filter = deserialize(r#"{ "status": "committed" }"#);
+        assert!(matches!(filter, FilterExpr::Filter(_)));
+        assert!(filter.matches(&committed));
+        assert!(!filter.matches(&finalized));
+
+        let filter = deserialize(r#"{ "not": { "status": "committed" } }"#);
+        assert!(!filter.matches(&committed));
+        assert!(filter.matches(&finalized));
+        assert!(filter.matches(&reverted));
+
+        let filter =
+            deserialize(r#"{ "any": [{ "status": "committed" }, { "status": "finalized" }] }"#);
+        assert!(filter.matches(&committed));
+        assert!(filter.matches(&finalized));
+        assert!(!filter.matches(&reverted));
+
+        // Nested expressions.
+        let filter =
+            deserialize(r#"{ "all": [{}, { "not": { "any": [{ "status": "reverted" }] } }] }"#);
+        assert!(filter.matches(&committed));
+        assert!(filter.matches(&finalized));
+        assert!(!filter.matches(&reverted));
+
+        // Empty combinators.
+        assert!(!deserialize(r#"{ "any": [] }"#).matches(&committed));
+        assert!(deserialize(r#"{ "all": [] }"#).matches(&committed));
+
+        // Combinators can't be mixed with the filter fields.
+        let input = r#"{ "not": { "status": "committed" }, "status": "reverted" }"#;
+        assert!(serde_json::from_str::<FilterExpr<BlockFilter>>(input).is_err());
+    }
+}
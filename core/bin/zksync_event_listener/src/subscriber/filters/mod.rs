@@ -3,15 +3,17 @@ use std::collections::HashMap;
 use std::fmt;
 // Workspace uses
 use zksync_storage::event::{get_event_type, EventType};
-use zksync_types::event::ZkSyncEvent;
+use zksync_types::event::{EventId, ZkSyncEvent};
 // External uses
 use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 // Local uses
+use self::combinator::{Filter, FilterExpr};
 use self::{account::AccountFilter, block::BlockFilter, transaction::TransactionFilter};
 
 mod account;
 mod block;
+mod combinator;
 mod transaction;
 
 #[cfg(test)]
@@ -19,13 +21,13 @@ mod tests;
 
 #[derive(Debug, Clone)]
 pub enum EventFilter {
-    Account(AccountFilter),
-    Block(BlockFilter),
-    Transaction(TransactionFilter),
+    Account(FilterExpr<AccountFilter>),
+    Block(FilterExpr<BlockFilter>),
+    Transaction(FilterExpr<TransactionFilter>),
 }
 
-impl EventFilter {
-    pub fn matches(&self, event: &ZkSyncEvent) -> bool {
+impl Filter for EventFilter {
+    fn matches(&self, event: &ZkSyncEvent) -> bool {
         match self {
             EventFilter::Account(account_filter) => account_filter.matches(event),
             EventFilter::Block(block_filter) => block_filter.matches(event),
@@ -47,6 +49,18 @@ impl SubscriberFilters {
     }
 }
 
+/// The message sent by the client to register its interests.
+/// Besides the filters, it may contain the id of the last event
+/// processed by the client, in which case the stored events are
+/// replayed before switching to the live delivery.
+#[derive(Debug, Deserialize)]
+pub struct SubscribeRequest {
+    #[serde(default)]
+    pub last_event_id: Option<EventId>,
+    #[serde(flatten)]
+    pub filters: SubscriberFilters,
+}
+
 struct EventFiltersVisitor;
 
 impl<'de> Visitor<'de> for EventFiltersVisitor {
@@ -64,11 +78,9 @@ impl<'de> Visitor<'de> for EventFiltersVisitor {
 
         while let Some(key) = access.next_key()? {
             let value = match key {
-                EventType::Account => EventFilter::Account(access.next_value::<AccountFilter>()?),
-                EventType::Block => EventFilter::Block(access.next_value::<BlockFilter>()?),
-                EventType::Transaction => {
-                    EventFilter::Transaction(access.next_value::<TransactionFilter>()?)
-                }
+                EventType::Account => EventFilter::Account(access.next_value()?),
+                EventType::Block => EventFilter::Block(access.next_value()?),
+                EventType::Transaction => EventFilter::Transaction(access.next_value()?),
             };
 
             map.insert(key, value);
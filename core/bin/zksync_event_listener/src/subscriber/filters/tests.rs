@@ -4,10 +4,10 @@
 use zksync_storage::event::EventType;
 use zksync_types::{
     event::{account::*, block::*, test_data::*, transaction::*},
-    AccountId, TokenId,
+    AccountId, EventId, TokenId,
 };
 // Local uses
-use super::{EventFilter, SubscriberFilters};
+use super::{EventFilter, SubscribeRequest, SubscriberFilters};
 
 fn deserialize_valid(input: &str) -> SubscriberFilters {
     serde_json::from_str(input)
@@ -201,3 +201,33 @@ fn test_subscriber_filters() {
     assert!(filters.matches(&block_event));
     assert!(filters.matches(&tx_event));
 }
+
+/// Checks that the subscribe request may optionally carry the id
+/// of the last processed event alongside the filters.
+#[test]
+fn test_subscribe_request_deserialize() {
+    let request: SubscribeRequest = serde_json::from_str("{}").unwrap();
+    assert!(request.last_event_id.is_none());
+    assert!(request.filters.0.is_empty());
+
+    let input = r#"{
+        "last_event_id": 42,
+        "block": {
+            "status": "committed"
+        }
+    }"#;
+    let request: SubscribeRequest = serde_json::from_str(input).unwrap();
+    assert_eq!(request.last_event_id, Some(EventId(42)));
+    assert!(matches!(
+        request.filters.0.get(&EventType::Block).unwrap(),
+        EventFilter::Block(_)
+    ));
+    assert_eq!(request.filters.0.len(), 1);
+
+    // Unknown fields are still rejected.
+    let input = r#"{
+        "last_event_id": 42,
+        "blocks": {}
+    }"#;
+    assert!(serde_json::from_str::<SubscribeRequest>(input).is_err());
+}
@@ -5,9 +5,11 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_types::{
     event::{transaction::*, EventData, ZkSyncEvent},
-    AccountId, TokenId,
+    AccountId, Address, TokenId,
 };
+use zksync_utils::BigUintSerdeWrapper;
 // Local uses
+use super::Filter;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -16,10 +18,18 @@ pub struct TransactionFilter {
     pub accounts: Option<HashSet<AccountId>>,
     pub tokens: Option<HashSet<TokenId>>,
     pub status: Option<TransactionStatus>,
+    /// Tokens the fee is paid in. Priority operations don't match this filter.
+    pub fee_tokens: Option<HashSet<TokenId>>,
+    /// Creators of the minted NFTs. Only `MintNFT` transactions match this filter.
+    pub nft_creators: Option<HashSet<Address>>,
+    /// Inclusive bounds for the amount of the transaction. Only transactions
+    /// moving a single amount (transfers, withdrawals and deposits) match these filters.
+    pub min_amount: Option<BigUintSerdeWrapper>,
+    pub max_amount: Option<BigUintSerdeWrapper>,
 }
 
-impl TransactionFilter {
-    pub fn matches(&self, event: &ZkSyncEvent) -> bool {
+impl Filter for TransactionFilter {
+    fn matches(&self, event: &ZkSyncEvent) -> bool {
         let tx_event = match &event.data {
             EventData::Transaction(tx_event) => tx_event,
             _ => return false,
@@ -47,6 +57,30 @@ impl TransactionFilter {
                 return false;
             }
         }
+        if let Some(fee_tokens) = &self.fee_tokens {
+            match tx_event.fee_token() {
+                Some(fee_token) if fee_tokens.contains(&fee_token) => {}
+                _ => return false,
+            }
+        }
+        if let Some(creators) = &self.nft_creators {
+            match tx_event.nft_creator_address() {
+                Some(creator) if creators.contains(&creator) => {}
+                _ => return false,
+            }
+        }
+        if self.min_amount.is_some() || self.max_amount.is_some() {
+            let amount = match tx_event.amount() {
+                Some(amount) => amount,
+                None => return false,
+            };
+            if matches!(&self.min_amount, Some(min_amount) if amount < min_amount.0) {
+                return false;
+            }
+            if matches!(&self.max_amount, Some(max_amount) if amount > max_amount.0) {
+                return false;
+            }
+        }
         true
     }
 }
@@ -54,7 +88,8 @@ impl TransactionFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use zksync_types::event::test_data::get_transaction_event;
+    use serde_json::json;
+    use zksync_types::event::test_data::{get_transaction_event, get_transaction_event_with_data};
 
     #[test]
     fn test_transaction_filter() {
@@ -64,6 +99,10 @@ mod tests {
             accounts: None,
             tokens: None,
             status: None,
+            fee_tokens: None,
+            nft_creators: None,
+            min_amount: None,
+            max_amount: None,
         };
 
         let event = get_transaction_event(
@@ -142,4 +181,57 @@ mod tests {
             assert!(tx_filter.matches(&event));
         }
     }
+
+    #[test]
+    fn test_transaction_filter_by_data() {
+        let transfer = |amount: &str, fee_token: u32| {
+            get_transaction_event_with_data(
+                json!({ "type": "Transfer", "amount": amount }),
+                AccountId(1),
+                TokenId(fee_token),
+                TransactionStatus::Committed,
+            )
+        };
+        let creator = Address::repeat_byte(7);
+        let mint_nft = get_transaction_event_with_data(
+            json!({ "type": "MintNFT", "creatorAddress": creator, "feeToken": 2 }),
+            AccountId(1),
+            TokenId(2),
+            TransactionStatus::Committed,
+        );
+        let deposit = get_transaction_event_with_data(
+            json!({ "type": "Deposit", "priority_op": { "amount": "500" } }),
+            AccountId(1),
+            TokenId(0),
+            TransactionStatus::Committed,
+        );
+
+        // Withdrawals of at least 100 tokens.
+        let tx_filter: TransactionFilter =
+            serde_json::from_value(json!({ "min_amount": "100", "max_amount": "1000" })).unwrap();
+        assert!(tx_filter.matches(&transfer("100", 0)));
+        assert!(tx_filter.matches(&transfer("1000", 0)));
+        assert!(!tx_filter.matches(&transfer("99", 0)));
+        assert!(!tx_filter.matches(&transfer("1001", 0)));
+        assert!(tx_filter.matches(&deposit));
+        // Transactions without amount are filtered out.
+        assert!(!tx_filter.matches(&mint_nft));
+
+        // Fee token filter.
+        let tx_filter: TransactionFilter =
+            serde_json::from_value(json!({ "fee_tokens": [2] })).unwrap();
+        assert!(tx_filter.matches(&transfer("1", 2)));
+        assert!(tx_filter.matches(&mint_nft));
+        assert!(!tx_filter.matches(&transfer("1", 0)));
+        assert!(!tx_filter.matches(&deposit));
+
+        // NFT creator filter.
+        let tx_filter: TransactionFilter =
+            serde_json::from_value(json!({ "nft_creators": [creator] })).unwrap();
+        assert!(tx_filter.matches(&mint_nft));
+        assert!(!tx_filter.matches(&transfer("1", 2)));
+        let tx_filter: TransactionFilter =
+            serde_json::from_value(json!({ "nft_creators": [Address::repeat_byte(8)] })).unwrap();
+        assert!(!tx_filter.matches(&mint_nft));
+    }
 }
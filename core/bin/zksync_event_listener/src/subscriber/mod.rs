@@ -1,14 +1,29 @@
 // Built-in uses
+use std::convert::TryFrom;
 // External uses
 use actix::prelude::*;
 use actix_web_actors::ws;
+use serde::Serialize;
 // Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::event::{EventId, ZkSyncEvent};
 // Local uses
 use crate::messages::{NewEvents, RegisterSubscriber, RemoveSubscriber, Shutdown};
 use crate::monitor::ServerMonitor;
-use filters::SubscriberFilters;
+use filters::{SubscribeRequest, SubscriberFilters};
+use replay::ReplayState;
 
 mod filters;
+mod replay;
+
+/// The message delivered to the client, carries the id of the event
+/// so the client is able to resume the stream after reconnecting.
+#[derive(Debug, Serialize)]
+struct EventMessage<'a> {
+    event_id: EventId,
+    #[serde(flatten)]
+    event: &'a ZkSyncEvent,
+}
 
 /// The WebSocket actor. Created for each connected client.
 #[derive(Debug)]
@@ -19,13 +34,104 @@ pub struct Subscriber {
     filters: Option<SubscriberFilters>,
     /// The address of the [`ServerMonitor`] for registering.
     monitor: Addr<ServerMonitor>,
+    /// Pool of connections to the database, used to replay stored events.
+    db_pool: ConnectionPool,
+    /// The id of the last event processed by the client, provided on connect.
+    /// May be overridden by the filters message.
+    last_event_id: Option<EventId>,
+    /// Tracks processed events to avoid delivering the same event twice
+    /// and buffers live events during the replay.
+    replay: ReplayState,
 }
 
 impl Subscriber {
-    pub fn new(monitor: Addr<ServerMonitor>) -> Self {
+    /// The maximum number of stored events loaded at once during the replay.
+    const REPLAY_BATCH_SIZE: u32 = 1000;
+
+    pub fn new(
+        monitor: Addr<ServerMonitor>,
+        db_pool: ConnectionPool,
+        last_event_id: Option<EventId>,
+    ) -> Self {
         Self {
             filters: None,
             monitor,
+            db_pool,
+            last_event_id,
+            replay: ReplayState::default(),
+        }
+    }
+
+    /// Sends the event to the client if it matches the filters
+    /// and wasn't processed yet.
+    fn deliver(&mut self, event: &ZkSyncEvent, ctx: &mut <Self as Actor>::Context) {
+        let filters = match &self.filters {
+            Some(filters) => filters,
+            None => return,
+        };
+        if !self.replay.process(event) || !filters.matches(event) {
+            return;
+        }
+        let message = EventMessage {
+            event_id: event.id,
+            event,
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        ctx.text(json);
+    }
+
+    /// Starts replaying stored events with ids greater than `from`.
+    /// Live events are buffered until the replay is finished.
+    fn start_replay(&mut self, from: EventId, ctx: &mut <Self as Actor>::Context) {
+        self.replay.start(from);
+        self.replay_batch(from, ctx);
+    }
+
+    fn replay_batch(&mut self, from: EventId, ctx: &mut <Self as Actor>::Context) {
+        let pool = self.db_pool.clone();
+        async move {
+            Ok(pool
+                .access_storage()
+                .await?
+                .event_schema()
+                .fetch_events_batch(from, Self::REPLAY_BATCH_SIZE)
+                .await?
+                .into_iter()
+                .map(ZkSyncEvent::try_from)
+                .collect::<Result<_, _>>()?)
+        }
+        .into_actor(self)
+        .map(|result: anyhow::Result<Vec<ZkSyncEvent>>, act, ctx| {
+            let events = match result {
+                Ok(events) => events,
+                Err(err) => {
+                    vlog::error!("Couldn't replay stored events, reason: {}", err);
+                    let reason = Some(ws::CloseReason {
+                        code: ws::CloseCode::Error,
+                        description: Some("internal server error".to_string()),
+                    });
+                    ctx.close(reason);
+                    return act.shutdown(ctx);
+                }
+            };
+            for event in &events {
+                act.deliver(event, ctx);
+            }
+            match ReplayState::next_batch_start(&events, Self::REPLAY_BATCH_SIZE) {
+                Some(from) => act.replay_batch(from, ctx),
+                None => act.finish_replay(ctx),
+            }
+        })
+        .spawn(ctx);
+    }
+
+    /// Delivers live events received during the replay and
+    /// switches to the live delivery.
+    fn finish_replay(&mut self, ctx: &mut <Self as Actor>::Context) {
+        for events in self.replay.finish() {
+            for event in events.as_ref() {
+                self.deliver(event, ctx);
+            }
         }
     }
 
@@ -89,9 +195,12 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Subscriber {
                 if self.filters.is_some() {
                     return;
                 }
-                match serde_json::from_str(&text) {
-                    Ok(filters) => {
-                        self.filters = Some(filters);
+                match serde_json::from_str::<SubscribeRequest>(&text) {
+                    Ok(request) => {
+                        self.filters = Some(request.filters);
+                        if let Some(last_event_id) = request.last_event_id.or(self.last_event_id) {
+                            self.start_replay(last_event_id, ctx);
+                        }
                     }
                     Err(err) => {
                         // The client provided invalid JSON, give
@@ -132,16 +241,15 @@ impl Handler<NewEvents> for Subscriber {
     type Result = ();
 
     fn handle(&mut self, msg: NewEvents, ctx: &mut Self::Context) {
-        let filters = match &self.filters {
-            Some(filters) => filters,
+        if self.filters.is_none() {
+            return;
+        }
+        let events = match self.replay.buffer_live(msg.0) {
+            Some(events) => events,
             None => return,
         };
-        for event in msg.0.as_ref() {
-            if !filters.matches(event) {
-                continue;
-            }
-            let json = serde_json::to_string(&event).unwrap();
-            ctx.text(json);
+        for event in events.as_ref() {
+            self.deliver(event, ctx);
         }
     }
 }
@@ -0,0 +1,141 @@
+// Built-in uses
+use std::sync::Arc;
+// External uses
+// Workspace uses
+use zksync_types::event::{EventId, ZkSyncEvent};
+// Local uses
+
+/// Keeps track of the events processed by the subscriber, so that
+/// the stored events replayed from the database and the live events
+/// received in the meantime are delivered exactly once and in order.
+#[derive(Debug, Default)]
+pub struct ReplayState {
+    /// The id of the last event passed through the filters.
+    last_processed_event_id: Option<EventId>,
+    /// Live events received while the stored ones are being replayed.
+    /// `None` if the subscriber is not replaying.
+    buffer: Option<Vec<Arc<Vec<ZkSyncEvent>>>>,
+}
+
+impl ReplayState {
+    /// Starts the replay of events with ids greater than `from`.
+    pub fn start(&mut self, from: EventId) {
+        self.buffer = Some(Vec::new());
+        self.last_processed_event_id = Some(from);
+    }
+
+    /// Buffers live events if the replay is in progress.
+    /// Otherwise, returns them back to be delivered immediately.
+    pub fn buffer_live(&mut self, events: Arc<Vec<ZkSyncEvent>>) -> Option<Arc<Vec<ZkSyncEvent>>> {
+        match &mut self.buffer {
+            Some(buffer) => {
+                buffer.push(events);
+                None
+            }
+            None => Some(events),
+        }
+    }
+
+    /// Marks the event as processed. Returns `false` if it was already processed.
+    pub fn process(&mut self, event: &ZkSyncEvent) -> bool {
+        if matches!(self.last_processed_event_id, Some(id) if id >= event.id) {
+            return false;
+        }
+        self.last_processed_event_id = Some(event.id);
+        true
+    }
+
+    /// Returns the id to load the next batch of stored events from,
+    /// or `None` if the loaded batch was the last one.
+    pub fn next_batch_start(events: &[ZkSyncEvent], batch_size: u32) -> Option<EventId> {
+        match events.last() {
+            Some(last) if events.len() == batch_size as usize => Some(last.id),
+            _ => None,
+        }
+    }
+
+    /// Finishes the replay, returning live events buffered during it.
+    pub fn finish(&mut self) -> Vec<Arc<Vec<ZkSyncEvent>>> {
+        self.buffer.take().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::event::{block::BlockStatus, test_data::get_block_event};
+
+    fn events(ids: impl IntoIterator<Item = u64>) -> Vec<ZkSyncEvent> {
+        ids.into_iter()
+            .map(|id| {
+                let mut event = get_block_event(BlockStatus::Committed);
+                event.id = EventId(id);
+                event
+            })
+            .collect()
+    }
+
+    fn processed_ids<'a>(
+        state: &mut ReplayState,
+        events: impl IntoIterator<Item = &'a ZkSyncEvent>,
+    ) -> Vec<u64> {
+        events
+            .into_iter()
+            .filter(|event| state.process(event))
+            .map(|event| *event.id)
+            .collect()
+    }
+
+    /// Checks that live events received during the replay are delivered after
+    /// the stored ones, and the events present in both are delivered once.
+    #[test]
+    fn replay_with_live_events() {
+        const BATCH_SIZE: u32 = 3;
+        let mut state = ReplayState::default();
+        state.start(EventId(2));
+
+        // Live events arrive before the replay has caught up with them.
+        assert!(state.buffer_live(Arc::new(events(5..8))).is_none());
+
+        let mut delivered = Vec::new();
+        let batch = events(3..6);
+        delivered.extend(processed_ids(&mut state, &batch));
+        assert_eq!(
+            ReplayState::next_batch_start(&batch, BATCH_SIZE),
+            Some(EventId(5))
+        );
+        assert!(state.buffer_live(Arc::new(events(8..9))).is_none());
+
+        let batch = events(6..8);
+        delivered.extend(processed_ids(&mut state, &batch));
+        assert_eq!(ReplayState::next_batch_start(&batch, BATCH_SIZE), None);
+
+        for live in state.finish() {
+            delivered.extend(processed_ids(&mut state, live.as_ref()));
+        }
+        assert_eq!(delivered, vec![3, 4, 5, 6, 7, 8]);
+
+        // After the replay, live events are not buffered anymore.
+        let live = state.buffer_live(Arc::new(events(8..10))).unwrap();
+        assert_eq!(processed_ids(&mut state, live.as_ref()), vec![9]);
+    }
+
+    /// Checks that the replay skips events that were processed by the client.
+    #[test]
+    fn replay_skips_processed_events() {
+        let mut state = ReplayState::default();
+        state.start(EventId(10));
+        assert_eq!(processed_ids(&mut state, &events(8..12)), vec![11]);
+        assert!(state.finish().is_empty());
+        assert_eq!(ReplayState::next_batch_start(&[], 3), None);
+    }
+
+    /// Checks that without the replay every live event is delivered.
+    #[test]
+    fn no_replay() {
+        let mut state = ReplayState::default();
+        let live = state.buffer_live(Arc::new(events(0..3))).unwrap();
+        assert_eq!(processed_ids(&mut state, live.as_ref()), vec![0, 1, 2]);
+        assert!(state.finish().is_empty());
+    }
+}
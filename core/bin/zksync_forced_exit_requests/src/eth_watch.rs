@@ -15,7 +15,7 @@ use web3::{
     types::{BlockNumber, FilterBuilder, Log},
     Web3,
 };
-use zksync_config::ForcedExitRequestsConfig;
+use zksync_config::{ForcedExitRequestsConfig, NotifierConfig};
 use zksync_storage::ConnectionPool;
 
 use zksync_contracts::forced_exit_contract;
@@ -23,6 +23,7 @@ use zksync_types::H160;
 
 use zksync_core::eth_watch::{get_web3_block_number, WatcherMode};
 use zksync_mempool::MempoolTransactionRequest;
+use zksync_notifier::Notifier;
 use zksync_types::forced_exit_requests::FundsReceivedEvent;
 
 use super::prepare_forced_exit_sender::prepare_forced_exit_sender_account;
@@ -390,7 +391,9 @@ pub fn run_forced_exit_contract_watcher(
     forced_exit_minimum_account_age_secs: u64,
     contract: Address,
     web3_url: String,
+    notifier_config: &NotifierConfig,
 ) -> JoinHandle<()> {
+    let notifier = Notifier::from_config(notifier_config);
     let transport = web3::transports::Http::new(&web3_url).unwrap();
     let web3 = web3::Web3::new(transport);
     let eth_client = EthHttpClient::new(web3, contract);
@@ -415,8 +418,12 @@ pub fn run_forced_exit_contract_watcher(
         );
         // It is ok to unwrap here, since if forced_exit_sender is not created, then
         // the watcher is meaningless
-        let mut forced_exit_sender =
-            MempoolForcedExitSender::new(core_interaction_wrapper.clone(), config.clone(), id);
+        let mut forced_exit_sender = MempoolForcedExitSender::new(
+            core_interaction_wrapper.clone(),
+            config.clone(),
+            id,
+            notifier,
+        );
 
         // In case there were some transactions which were submitted
         // but were not committed we will try to wait until they are committed
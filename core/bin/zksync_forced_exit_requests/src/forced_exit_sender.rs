@@ -5,6 +5,7 @@ use num::BigUint;
 use tokio::time;
 
 use zksync_config::ForcedExitRequestsConfig;
+use zksync_notifier::{Notifier, NotifyEvent};
 
 use zksync_types::{
     forced_exit_requests::ForcedExitRequest, tx::TimeRange, tx::TxHash, AccountId, Address, Nonce,
@@ -32,6 +33,7 @@ pub struct MempoolForcedExitSender<T: CoreInteractionWrapper> {
     config: ForcedExitRequestsConfig,
     forced_exit_sender_account_id: AccountId,
     sender_private_key: PrivateKey<Engine>,
+    notifier: Notifier,
 }
 
 #[async_trait::async_trait]
@@ -46,14 +48,24 @@ impl<T: CoreInteractionWrapper + Sync + Send> ForcedExitSender for MempoolForced
                 .try_process_request(amount.clone(), submission_time)
                 .await;
 
-            if processing_attempt.is_ok() {
-                return;
-            } else {
-                attempts += 1;
-            }
+            let err = match processing_attempt {
+                Ok(()) => return,
+                Err(err) => err,
+            };
+            attempts += 1;
 
             if attempts >= PROCESSING_ATTEMPTS {
                 // We should not get stuck processing requests that possibly could never be processed
+                vlog::error!(
+                    "Failed to process the forced exit request after {} attempts: {}",
+                    attempts,
+                    err
+                );
+                self.notify_failure(format!(
+                    "the request was not processed after {} attempts: {}",
+                    attempts, err
+                ))
+                .await;
                 break;
             }
         }
@@ -65,6 +77,7 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
         core_interaction_wrapper: T,
         config: ForcedExitRequestsConfig,
         forced_exit_sender_account_id: AccountId,
+        notifier: Notifier,
     ) -> Self {
         let sender_private_key =
             hex::decode(&config.sender_private_key[2..]).expect("Decoding private key failed");
@@ -76,9 +89,20 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             config,
             forced_exit_sender_account_id,
             sender_private_key,
+            notifier,
         }
     }
 
+    /// Sends a notification about the failed request. Failure to send it is only logged.
+    async fn notify_failure(&self, details: String) {
+        self.notifier
+            .notify(NotifyEvent::ForcedExitFailed { details })
+            .await
+            .unwrap_or_else(|e| {
+                vlog::warn!("Failed to send the forced exit failure notification: {}", e);
+            });
+    }
+
     pub fn build_forced_exit(
         &self,
         nonce: Nonce,
@@ -175,13 +199,18 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
         for request in unfullied_requests.into_iter() {
             let await_result = self.await_unconfirmed_request(&request).await;
 
-            if await_result.is_err() {
+            if let Err(err) = await_result {
                 // A transaction has failed. That is not intended.
                 // We can safely cancel such transaction, since we will re-try to
                 // send it again later
                 vlog::error!(
                     "A previously sent forced exit transaction has failed. Canceling the tx."
                 );
+                self.notify_failure(format!(
+                    "transaction of the request {} has failed and is canceled to be sent again: {}",
+                    request.id, err
+                ))
+                .await;
                 self.core_interaction_wrapper
                     .set_fulfilled_by(request.id, None)
                     .await?;
@@ -288,6 +317,7 @@ mod test {
             core_interaction_wrapper,
             config,
             AccountId(TEST_ACCOUNT_FORCED_EXIT_SENDER_ID),
+            Notifier::new(),
         )
     }
 
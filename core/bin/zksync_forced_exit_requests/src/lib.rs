@@ -1,6 +1,6 @@
 use futures::channel::mpsc;
 use tokio::task::JoinHandle;
-use zksync_config::{ContractsConfig, ForcedExitRequestsConfig};
+use zksync_config::{ContractsConfig, ForcedExitRequestsConfig, NotifierConfig};
 use zksync_storage::ConnectionPool;
 
 use forced_exit_sender::ForcedExitSender;
@@ -24,6 +24,7 @@ pub fn run_forced_exit_requests_actors(
     common: CommonApiConfig,
     contracts: ContractsConfig,
     web3_url: String,
+    notifier_config: &NotifierConfig,
 ) -> JoinHandle<()> {
     eth_watch::run_forced_exit_contract_watcher(
         sender,
@@ -32,5 +33,6 @@ pub fn run_forced_exit_requests_actors(
         common.forced_exit_minimum_account_age_secs,
         contracts.forced_exit_addr,
         web3_url,
+        notifier_config,
     )
 }
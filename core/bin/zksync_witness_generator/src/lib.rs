@@ -3,26 +3,21 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 // External
-use actix_web::dev::ServiceRequest;
 use actix_web::{web, App, HttpResponse, HttpServer};
-use actix_web_httpauth::extractors::{
-    bearer::{BearerAuth, Config},
-    AuthenticationError,
-};
 use actix_web_httpauth::middleware::HttpAuthentication;
 
-use jsonwebtoken::errors::Error as JwtError;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 // Workspace deps
-use zksync_config::ProverConfig;
+use zksync_config::{NotifierConfig, ProverConfig};
 // Local deps
 use self::database_interface::DatabaseInterface;
 use self::scaler::ScalerOracle;
 use tokio::task::JoinHandle;
 use zksync_circuit::serialization::ProverData;
 use zksync_config::configs::api::ProverApiConfig;
+use zksync_http_auth::AuthTokenValidator;
+use zksync_notifier::{Notifier, NotifyEvent};
 use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
     WorkingOn,
@@ -44,14 +39,6 @@ mod database_interface;
 mod scaler;
 mod witness_generator;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PayloadAuthToken {
-    /// Subject (whom auth token refers to).
-    sub: String,
-    /// Expiration time (as UTC timestamp).
-    exp: usize,
-}
-
 #[derive(Debug, Clone)]
 struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
@@ -81,39 +68,6 @@ impl<DB: DatabaseInterface> AppState<DB> {
     }
 }
 
-/// The structure that stores the secret key for checking JsonWebToken matching.
-struct AuthTokenValidator<'a> {
-    decoding_key: DecodingKey<'a>,
-}
-
-impl<'a> AuthTokenValidator<'a> {
-    fn new(secret: &'a str) -> Self {
-        Self {
-            decoding_key: DecodingKey::from_secret(secret.as_ref()),
-        }
-    }
-
-    /// Checks whether the secret key and the authorization token match.
-    fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
-        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
-
-        Ok(())
-    }
-
-    async fn validator(
-        &self,
-        req: ServiceRequest,
-        credentials: BearerAuth,
-    ) -> actix_web::Result<ServiceRequest> {
-        let config = req.app_data::<Config>().cloned().unwrap_or_default();
-
-        self.validate_auth_token(credentials.token())
-            .map_err(|_| AuthenticationError::from(config))?;
-
-        Ok(req)
-    }
-}
-
 async fn status() -> actix_web::Result<String> {
     Ok("alive".into())
 }
@@ -287,10 +241,15 @@ async fn required_replicas<DB: DatabaseInterface>(
 async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     database: DB,
     prepare_data_interval: Duration,
+    notifier: Notifier,
+    queue_lag_threshold: u32,
 ) {
     // We use `prepare_data_interval` as timeout in this function to align creating prover jobs
     // with witness generator routine.
 
+    // Whether the prover queue lag was reported. Used to send only one notification until
+    // the queue is shortened.
+    let mut queue_lag_reported = false;
     let mut interval = tokio::time::interval(prepare_data_interval);
     loop {
         interval.tick().await;
@@ -300,6 +259,46 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
             });
+
+        match check_prover_queue_lag(&database, queue_lag_threshold).await {
+            Ok(Some(pending_jobs)) => {
+                if !queue_lag_reported {
+                    queue_lag_reported = true;
+                    vlog::warn!(
+                        "Prover queue is lagging: {} pending jobs (threshold is {})",
+                        pending_jobs,
+                        queue_lag_threshold
+                    );
+                    notifier
+                        .notify(NotifyEvent::ProverQueueLag {
+                            pending_jobs,
+                            threshold: queue_lag_threshold,
+                        })
+                        .await
+                        .unwrap_or_else(|e| {
+                            vlog::warn!("Failed to send the prover queue lag notification: {}", e);
+                        });
+                }
+            }
+            Ok(None) => queue_lag_reported = false,
+            Err(e) => vlog::warn!("Failed to check prover queue lag: {}", e),
+        }
+    }
+}
+
+/// Returns the amount of the pending prover jobs if it reaches the threshold.
+async fn check_prover_queue_lag<DB: DatabaseInterface>(
+    database: &DB,
+    threshold: u32,
+) -> anyhow::Result<Option<u32>> {
+    let mut connection = database.acquire_connection().await?;
+    let pending_jobs = database.pending_jobs_count(&mut connection).await?;
+    metrics::gauge!("witness_generator.pending_prover_jobs", pending_jobs as f64);
+
+    if pending_jobs >= threshold {
+        Ok(Some(pending_jobs))
+    } else {
+        Ok(None)
     }
 }
 
@@ -390,9 +389,12 @@ pub fn run_prover_server<DB: DatabaseInterface>(
     database: DB,
     prover_api_opts: ProverApiConfig,
     prover_opts: ProverConfig,
+    notifier_config: &NotifierConfig,
 ) -> JoinHandle<()> {
     let witness_generator_opts = prover_opts.witness_generator;
     let core_opts = prover_opts.core;
+    let notifier = Notifier::from_config(notifier_config);
+    let queue_lag_threshold = notifier_config.thresholds.prover_queue_lag;
     let (handler, panic_sender) = spawn_panic_handler();
 
     thread::Builder::new()
@@ -405,6 +407,8 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 tokio::spawn(update_prover_job_queue_loop(
                     database.clone(),
                     witness_generator_opts.prepare_data_interval(),
+                    notifier,
+                    queue_lag_threshold,
                 ));
 
                 let last_verified_block = {
@@ -9,7 +9,7 @@ use zksync_config::{
         api::ProverApiConfig,
         prover::{Core, Prover, WitnessGenerator},
     },
-    ProverConfig,
+    NotifierConfig, ProverConfig,
 };
 use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
 use zksync_prover::{client, ApiClient};
@@ -57,7 +57,12 @@ impl Default for MockProverOptions {
 async fn spawn_server(database: MockDatabase) {
     let prover_options = MockProverOptions::default();
 
-    run_prover_server(database, prover_options.0, prover_options.1);
+    run_prover_server(
+        database,
+        prover_options.0,
+        prover_options.1,
+        &NotifierConfig::from_env(),
+    );
 }
 
 #[tokio::test]
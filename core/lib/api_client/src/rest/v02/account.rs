@@ -1,6 +1,7 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
+    account::AccountProofQuery,
     pagination::{ApiEither, PaginationQuery},
     Response,
 };
@@ -56,4 +57,18 @@ impl Client {
         .send()
         .await
     }
+
+    pub async fn account_proof(
+        &self,
+        account_id_or_address: &str,
+        query: &AccountProofQuery,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/proof", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
 }
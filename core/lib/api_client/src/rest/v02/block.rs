@@ -36,6 +36,20 @@ impl Client {
         .await
     }
 
+    pub async fn block_state_diff(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<u32>>,
+        block_position: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/state-diff", block_position),
+        )
+        .query(&pagination_query)
+        .send()
+        .await
+    }
+
     pub async fn block_pagination(
         &self,
         pagination_query: &PaginationQuery<ApiEither<BlockNumber>>,
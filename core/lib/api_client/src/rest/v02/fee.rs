@@ -12,12 +12,14 @@ impl Client {
         tx_type: ApiTxFeeTypes,
         address: Address,
         token_like: TokenLike,
+        bind_quote: bool,
     ) -> Result<Response> {
         self.post_with_scope(super::API_V02_SCOPE, "fee")
             .body(&TxFeeRequest {
                 tx_type,
                 address,
                 token_like,
+                bind_quote,
             })
             .send()
             .await
@@ -27,11 +29,13 @@ impl Client {
         &self,
         transactions: Vec<TxInBatchFeeRequest>,
         token_like: TokenLike,
+        bind_quote: bool,
     ) -> Result<Response> {
         self.post_with_scope(super::API_V02_SCOPE, "fee/batch")
             .body(&BatchFeeRequest {
                 transactions,
                 token_like,
+                bind_quote,
             })
             .send()
             .await
@@ -1,6 +1,7 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
     pagination::{ApiEither, PaginationQuery},
+    token::PriceHistoryQuery,
     Response,
 };
 use zksync_types::{tx::TxHash, TokenId, TokenLike};
@@ -31,6 +32,20 @@ impl Client {
         .await
     }
 
+    pub async fn token_price_history(
+        &self,
+        token: &TokenLike,
+        query: &PriceHistoryQuery,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("tokens/{}/price-history", token),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
     pub async fn nft_by_id(&self, id: TokenId) -> Result<Response> {
         self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/nft/{}", id))
             .send()
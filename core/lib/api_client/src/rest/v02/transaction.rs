@@ -1,6 +1,9 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::{
-    v02::{transaction::IncomingTxBatch, Response},
+    v02::{
+        transaction::{IncomingTx, IncomingTxBatch, TxSimulationRequest},
+        Response,
+    },
     TxWithSignature,
 };
 use zksync_types::tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx};
@@ -10,9 +13,14 @@ impl Client {
         &self,
         tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
+        fee_quote_id: Option<i64>,
     ) -> Result<Response> {
         self.post_with_scope(super::API_V02_SCOPE, "transactions")
-            .body(&TxWithSignature { tx, signature })
+            .body(&IncomingTx {
+                tx,
+                signature,
+                fee_quote_id,
+            })
             .send()
             .await
     }
@@ -21,9 +29,28 @@ impl Client {
         &self,
         txs: Vec<TxWithSignature>,
         signature: Option<EthBatchSignatures>,
+        fee_quote_id: Option<i64>,
     ) -> Result<Response> {
         self.post_with_scope(super::API_V02_SCOPE, "transactions/batches")
-            .body(&IncomingTxBatch { txs, signature })
+            .body(&IncomingTxBatch {
+                txs,
+                signature,
+                fee_quote_id,
+            })
+            .send()
+            .await
+    }
+
+    pub async fn simulate_tx(&self, tx: ZkSyncTx) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/simulate")
+            .body(&TxSimulationRequest::Tx { tx: Box::new(tx) })
+            .send()
+            .await
+    }
+
+    pub async fn simulate_batch(&self, txs: Vec<ZkSyncTx>) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/simulate")
+            .body(&TxSimulationRequest::Batch { txs })
             .send()
             .await
     }
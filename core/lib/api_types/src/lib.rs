@@ -34,4 +34,26 @@ pub struct CoreStatus {
     pub main_database_available: bool,
     pub replica_database_available: bool,
     pub web3_available: bool,
+    /// Whether the Ethereum sender is paused after a failed transaction.
+    #[serde(default)]
+    pub eth_sender_paused: bool,
+    /// Whether the state keeper is halted after a reorg reverted executed priority operations.
+    #[serde(default)]
+    pub state_keeper_halted: bool,
+}
+
+/// Health state of the Ethereum gateway used by the core server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GatewayStatus {
+    pub name: String,
+    /// Health score from 0 (unusable) to 1 (healthy).
+    pub score: f64,
+    /// Average latency of the requests, in milliseconds.
+    pub latency_ms: u64,
+    pub error_rate: f64,
+    pub requests: u64,
+    /// Number of the responses disagreeing with the other gateways.
+    pub disagreements: u64,
+    /// Whether the gateway is temporarily not used because of the low score.
+    pub evicted: bool,
 }
@@ -3,6 +3,10 @@ use std::collections::{BTreeMap, HashMap};
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 
+use zksync_crypto::{
+    serialization::{FrSerde, VecFrSerde},
+    Fr,
+};
 use zksync_types::{
     AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, TokenId, ZkSyncPriorityOp,
 };
@@ -115,3 +119,42 @@ pub struct IncomingAccountTxsQuery {
     pub token: Option<String>,
     pub second_account: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProofQuery {
+    pub token: String,
+    pub block: Option<BlockNumber>,
+}
+
+/// Account fields that are hashed into the leaf of the account tree
+/// along with the root of the account balance tree.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountLeaf {
+    pub nonce: Nonce,
+    pub pub_key_hash: PubKeyHash,
+    pub address: Address,
+}
+
+/// Merkle proof of the account token balance in the state of the verified block.
+///
+/// Paths contain the hashes of the sibling nodes starting from the leaf level.
+/// Directions are not included, since they are determined by the bits of the token
+/// and account IDs.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalanceProof {
+    pub block_number: BlockNumber,
+    #[serde(with = "FrSerde")]
+    pub root_hash: Fr,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub account: AccountLeaf,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub balance: BigUint,
+    #[serde(with = "VecFrSerde")]
+    pub balance_path: Vec<Fr>,
+    #[serde(with = "VecFrSerde")]
+    pub account_path: Vec<Fr>,
+}
@@ -1,7 +1,13 @@
 use chrono::{DateTime, Utc};
+use num::BigUint;
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{serialization::FrSerde, Fr};
-use zksync_types::{BlockNumber, H256};
+use zksync_types::{
+    AccountId, AccountUpdate, Address, BlockNumber, Nonce, PubKeyHash, TokenId, H256,
+};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use super::token::NFT;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
@@ -23,3 +29,84 @@ pub struct BlockInfo {
     pub finalized_at: Option<DateTime<Utc>>,
     pub status: BlockStatus,
 }
+
+/// Change of the account state caused by a block.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum AccountStateUpdate {
+    #[serde(rename_all = "camelCase")]
+    Create { address: Address, nonce: Nonce },
+    #[serde(rename_all = "camelCase")]
+    Delete { address: Address, nonce: Nonce },
+    #[serde(rename_all = "camelCase")]
+    BalanceUpdate {
+        token_id: TokenId,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        balance_before: BigUint,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        balance_after: BigUint,
+        nonce_before: Nonce,
+        nonce_after: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    PubKeyHashUpdate {
+        pub_key_hash_before: PubKeyHash,
+        pub_key_hash_after: PubKeyHash,
+        nonce_before: Nonce,
+        nonce_after: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    MintNFT { nft: NFT, nonce: Nonce },
+    #[serde(rename_all = "camelCase")]
+    RemoveNFT { nft: NFT, nonce: Nonce },
+}
+
+impl From<AccountUpdate> for AccountStateUpdate {
+    fn from(update: AccountUpdate) -> Self {
+        match update {
+            AccountUpdate::Create { address, nonce } => Self::Create { address, nonce },
+            AccountUpdate::Delete { address, nonce } => Self::Delete { address, nonce },
+            AccountUpdate::UpdateBalance {
+                old_nonce,
+                new_nonce,
+                balance_update: (token_id, balance_before, balance_after),
+            } => Self::BalanceUpdate {
+                token_id,
+                balance_before,
+                balance_after,
+                nonce_before: old_nonce,
+                nonce_after: new_nonce,
+            },
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash,
+                new_pub_key_hash,
+                old_nonce,
+                new_nonce,
+            } => Self::PubKeyHashUpdate {
+                pub_key_hash_before: old_pub_key_hash,
+                pub_key_hash_after: new_pub_key_hash,
+                nonce_before: old_nonce,
+                nonce_after: new_nonce,
+            },
+            AccountUpdate::MintNFT { token, nonce } => Self::MintNFT {
+                nft: token.into(),
+                nonce,
+            },
+            AccountUpdate::RemoveNFT { token, nonce } => Self::RemoveNFT {
+                nft: token.into(),
+                nonce,
+            },
+        }
+    }
+}
+
+/// Single entry of the block state diff.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateChange {
+    /// Position of the update in the block state diff, used for pagination.
+    pub update_index: u32,
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub update: AccountStateUpdate,
+}
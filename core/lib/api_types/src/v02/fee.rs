@@ -1,6 +1,8 @@
 use num::BigUint;
 use serde::{Deserialize, Serialize};
-use zksync_types::{tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenLike, TxFeeTypes};
+use zksync_types::{
+    tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, FeeQuote, TokenLike, TxFeeTypes,
+};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -8,18 +10,26 @@ use zksync_utils::BigUintSerdeAsRadix10Str;
 pub struct ApiFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub gas_fee: BigUint,
+    /// Part of the `gas_fee` charged over the L1 cost estimated with the current gas price.
+    #[serde(default, with = "BigUintSerdeAsRadix10Str")]
+    pub margin_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Quote binding the `total_fee`, if issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<FeeQuote>,
 }
 
 impl From<Fee> for ApiFee {
     fn from(fee: Fee) -> Self {
         ApiFee {
             gas_fee: fee.gas_fee,
+            margin_fee: fee.margin_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            quote: fee.quote,
         }
     }
 }
@@ -28,8 +38,10 @@ impl From<BatchFee> for ApiFee {
     fn from(fee: BatchFee) -> Self {
         ApiFee {
             gas_fee: fee.gas_fee,
+            margin_fee: fee.margin_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            quote: None,
         }
     }
 }
@@ -77,6 +89,9 @@ pub struct TxFeeRequest {
     pub tx_type: ApiTxFeeTypes,
     pub address: Address,
     pub token_like: TokenLike,
+    /// Whether to issue the fee quote binding the returned fee.
+    #[serde(default)]
+    pub bind_quote: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -91,4 +106,7 @@ pub struct TxInBatchFeeRequest {
 pub struct BatchFeeRequest {
     pub transactions: Vec<TxInBatchFeeRequest>,
     pub token_like: TokenLike,
+    /// Whether to issue the fee quote binding the returned fee.
+    #[serde(default)]
+    pub bind_quote: bool,
 }
@@ -125,6 +125,12 @@ pub struct BlockAndTxHash {
     pub tx_hash: ApiEither<TxHash>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct BlockAndUpdateIndex {
+    pub block_number: BlockNumber,
+    pub update_index: ApiEither<u32>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct PendingOpsRequest {
     pub address: Address,
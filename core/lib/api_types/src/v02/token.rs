@@ -1,6 +1,7 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use zksync_types::{AccountId, Address, Token, TokenId, H256};
+use zksync_types::{AccountId, Address, Token, TokenId, TokenStatus, H256};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +11,7 @@ pub struct ApiToken {
     pub symbol: String,
     pub decimals: u8,
     pub enabled_for_fees: bool,
+    pub status: TokenStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -22,14 +24,46 @@ pub struct TokenPrice {
     pub price: BigDecimal,
 }
 
+/// Maximum number of the points returned by the price history endpoint.
+pub const MAX_PRICE_HISTORY_POINTS: i64 = 1000;
+
+/// Query of the token price history.
+/// `from` and `to` are UNIX timestamps, `resolution` is the interval between the points in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceHistoryQuery {
+    pub from: i64,
+    pub to: i64,
+    pub resolution: i64,
+}
+
+/// Token price in USD averaged over the interval starting at `timestamp`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceSample {
+    pub timestamp: DateTime<Utc>,
+    pub price: BigDecimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPriceHistory {
+    pub token_id: TokenId,
+    pub token_symbol: String,
+    pub resolution: i64,
+    pub prices: Vec<PriceSample>,
+}
+
 impl ApiToken {
+    /// Tokens that failed the validation are never enabled for fees, regardless of `eligibility`.
     pub fn from_token_and_eligibility(token: Token, eligibility: bool) -> Self {
         ApiToken {
             id: token.id,
             address: token.address,
             symbol: token.symbol,
             decimals: token.decimals,
-            enabled_for_fees: eligibility,
+            enabled_for_fees: eligibility && token.status.is_safe(),
+            status: token.status,
         }
     }
 }
@@ -6,18 +6,32 @@ use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{
         ChangePubKey, Close, EthBatchSignatures, ForcedExit, MintNFT, Swap, Transfer,
-        TxEthSignature, TxHash, Withdraw, WithdrawNFT,
+        TxEthSignature, TxEthSignatureVariant, TxHash, Withdraw, WithdrawNFT,
     },
-    AccountId, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId, ZkSyncOp,
-    ZkSyncPriorityOp, H256,
+    AccountId, AccountUpdates, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId,
+    ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 use zksync_utils::{BigUintSerdeAsRadix10Str, ZeroPrefixHexSerde};
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingTx {
+    pub tx: ZkSyncTx,
+    #[serde(default)]
+    pub signature: TxEthSignatureVariant,
+    /// Identifier of the fee quote binding the transaction fee.
+    #[serde(default)]
+    pub fee_quote_id: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IncomingTxBatch {
     pub txs: Vec<TxWithSignature>,
     pub signature: Option<EthBatchSignatures>,
+    /// Identifier of the fee quote binding the batch fee.
+    #[serde(default)]
+    pub fee_quote_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
@@ -27,6 +41,9 @@ pub enum TxInBlockStatus {
     Committed,
     Finalized,
     Rejected,
+    /// Transaction was removed from the mempool in favor of a transaction
+    /// with the same nonce and a higher fee.
+    Replaced,
 }
 
 impl From<BlockStatus> for TxInBlockStatus {
@@ -296,3 +313,36 @@ impl Toggle2FA {
 pub struct Toggle2FAResponse {
     pub success: bool,
 }
+
+/// Transaction or batch of transactions to be executed against the committed state
+/// without being submitted to the mempool.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum TxSimulationRequest {
+    Tx { tx: Box<ZkSyncTx> },
+    Batch { txs: Vec<ZkSyncTx> },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedFee {
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+}
+
+/// Outcome of the transaction (or batch) execution against the committed state.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulationResult {
+    pub success: bool,
+    /// Changes of the accounts state caused by the execution. Empty if the execution failed.
+    pub updates: AccountUpdates,
+    /// Number of block chunks used by the executed operations.
+    pub chunks: usize,
+    /// Fees paid by the transactions.
+    pub fees: Vec<SimulatedFee>,
+    pub fail_reason: Option<String>,
+    /// Index of the transaction that caused the batch to fail.
+    pub failed_tx_index: Option<usize>,
+}
@@ -16,6 +16,8 @@ pub struct ChainConfig {
     pub eth: Eth,
     /// State keeper / block generating configuration.
     pub state_keeper: StateKeeper,
+    /// Mempool configuration.
+    pub mempool: Mempool,
 }
 
 impl ChainConfig {
@@ -24,6 +26,7 @@ impl ChainConfig {
             circuit: envy_load!("circuit", "CHAIN_CIRCUIT_"),
             eth: envy_load!("eth", "CHAIN_ETH_"),
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
+            mempool: envy_load!("mempool", "CHAIN_MEMPOOL_"),
         }
     }
     pub fn max_blocks_to_aggregate(&self) -> u32 {
@@ -115,6 +118,32 @@ impl StateKeeper {
     }
 }
 
+/// Order in which ready L2 transactions are proposed for the next block.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum MempoolOrdering {
+    /// Transactions are taken in the order of their nonces.
+    Fifo,
+    /// Transactions paying more per chunk (in USD) are taken first.
+    /// Nonce order is still preserved for every account.
+    FeePriority,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Mempool {
+    /// Ordering of the ready L2 transactions.
+    pub ordering: MempoolOrdering,
+    /// Minimal fee increase (in percent) required for a transaction to replace the queued
+    /// transaction of the same account with the same nonce.
+    pub replacement_fee_bump_percent: u32,
+    /// Maximal number of queued transactions of a single account.
+    pub max_pending_txs_per_account: u32,
+    /// Maximal difference between the transaction nonce and the committed nonce of the account.
+    pub max_nonce_gap: u32,
+    /// Maximal number of transactions in the mempool. Once it is reached, transactions paying
+    /// the lowest fee are evicted in favor of the ones paying more.
+    pub max_mempool_size: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +177,13 @@ mod tests {
                 block_execute_deadline: 4_000,
                 max_aggregated_tx_gas: 4_000_000,
             },
+            mempool: Mempool {
+                ordering: MempoolOrdering::FeePriority,
+                replacement_fee_bump_percent: 10,
+                max_pending_txs_per_account: 50,
+                max_nonce_gap: 20,
+                max_mempool_size: 10000,
+            },
         }
     }
 
@@ -175,6 +211,11 @@ CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE="300"
 CHAIN_STATE_KEEPER_BLOCK_PROVE_DEADLINE="3000"
 CHAIN_STATE_KEEPER_BLOCK_EXECUTE_DEADLINE="4000"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
+CHAIN_MEMPOOL_ORDERING="FeePriority"
+CHAIN_MEMPOOL_REPLACEMENT_FEE_BUMP_PERCENT="10"
+CHAIN_MEMPOOL_MAX_PENDING_TXS_PER_ACCOUNT="50"
+CHAIN_MEMPOOL_MAX_NONCE_GAP="20"
+CHAIN_MEMPOOL_MAX_MEMPOOL_SIZE="10000"
         "#;
         set_env(config);
 
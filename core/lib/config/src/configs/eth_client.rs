@@ -1,3 +1,5 @@
+// Built-in uses
+use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
@@ -14,6 +16,15 @@ pub struct ETHClientConfig {
     pub gas_price_factor: f64,
     /// Address of the Ethereum node API.
     pub web3_url: Vec<String>,
+    /// How many gateways must return the same response for the security-critical reads
+    /// (logs, transaction statuses and main contract calls). `1` disables the quorum mode.
+    pub read_quorum: usize,
+    /// Gateways with the health score (from 0 to 1) below this value are temporarily evicted.
+    pub gateway_min_health_score: f64,
+    /// How long an evicted gateway is not used. Value in seconds.
+    pub gateway_eviction_period: u64,
+    /// Requests slower than this lower the health score of the gateway. Value in milliseconds.
+    pub gateway_slow_request_threshold: u64,
 }
 
 impl ETHClientConfig {
@@ -28,6 +39,16 @@ impl ETHClientConfig {
             .cloned()
             .expect("Should be at least one")
     }
+
+    /// Converts `self.gateway_eviction_period` into `Duration`.
+    pub fn gateway_eviction_period(&self) -> Duration {
+        Duration::from_secs(self.gateway_eviction_period)
+    }
+
+    /// Converts `self.gateway_slow_request_threshold` into `Duration`.
+    pub fn gateway_slow_request_threshold(&self) -> Duration {
+        Duration::from_millis(self.gateway_slow_request_threshold)
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +64,10 @@ mod tests {
                 "http://127.0.0.1:8545".into(),
                 "http://127.0.0.1:8546".into(),
             ],
+            read_quorum: 2,
+            gateway_min_health_score: 0.5,
+            gateway_eviction_period: 60,
+            gateway_slow_request_threshold: 1000,
         }
     }
 
@@ -52,11 +77,20 @@ mod tests {
 ETH_CLIENT_CHAIN_ID="9"
 ETH_CLIENT_GAS_PRICE_FACTOR="1"
 ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545,http://127.0.0.1:8546"
+ETH_CLIENT_READ_QUORUM="2"
+ETH_CLIENT_GATEWAY_MIN_HEALTH_SCORE="0.5"
+ETH_CLIENT_GATEWAY_EVICTION_PERIOD="60"
+ETH_CLIENT_GATEWAY_SLOW_REQUEST_THRESHOLD="1000"
         "#;
         set_env(config);
 
         let actual = ETHClientConfig::from_env();
         assert_eq!(actual, expected_config());
         assert_eq!(actual.web3_url(), "http://127.0.0.1:8545");
+        assert_eq!(actual.gateway_eviction_period(), Duration::from_secs(60));
+        assert_eq!(
+            actual.gateway_slow_request_threshold(),
+            Duration::from_millis(1000)
+        );
     }
 }
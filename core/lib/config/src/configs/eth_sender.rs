@@ -1,9 +1,12 @@
 // Built-in uses
+use std::str::FromStr;
 use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_types::{Address, H256};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, tx::PackedEthSignature, Address, H256,
+};
 // Local uses
 use crate::envy_load;
 
@@ -14,6 +17,8 @@ pub struct ETHSenderConfig {
     pub sender: Sender,
     /// Options related to the `gas_adjuster` submodule.
     pub gas_price_limit: GasLimit,
+    /// Limits of the operator spendings on the Ethereum transactions.
+    pub spending_limit: SpendingLimit,
 }
 
 impl ETHSenderConfig {
@@ -24,16 +29,49 @@ impl ETHSenderConfig {
                 "eth_sender.gas_price_limit",
                 "ETH_SENDER_GAS_PRICE_LIMIT_"
             ),
+            spending_limit: envy_load!("eth_sender.spending_limit", "ETH_SENDER_SPENDING_LIMIT_"),
         }
     }
 }
 
+/// Type of the Ethereum transactions sent by the operator.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum EthTxType {
+    /// Transactions with a single gas price.
+    Legacy,
+    /// EIP-1559 (type 2) transactions with the max fee and the priority fee per gas.
+    EIP1559,
+}
+
+/// Policy of handling the failed Ethereum transactions.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum FailurePolicy {
+    /// Stop the node on any failed transaction.
+    Panic,
+    /// Send the transaction again if the failure is retryable, otherwise pause the sender.
+    Pause,
+}
+
+/// Operator account sending the transactions of the given action types.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorAccount {
+    /// Private key of the account.
+    pub private_key: H256,
+    /// Address of the account.
+    pub address: Address,
+    /// Types of the aggregated operations sent from the account.
+    pub action_types: Vec<AggregatedActionType>,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Sender {
     /// Private key of the operator account.
     pub operator_private_key: H256,
     /// Address of the operator account.
     pub operator_commit_eth_addr: Address,
+    /// Additional operator accounts, formatted as `<action type>:<private key>`.
+    /// Action types without an account are sent from the main operator account.
+    pub operator_accounts: Vec<String>,
     /// mount of confirmations required to consider L1 transaction committed.
     pub wait_confirmations: u64,
     /// Amount of blocks we will wait before considering L1 transaction stuck.
@@ -44,6 +82,24 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Type of the transactions to send.
+    pub tx_type: EthTxType,
+    /// Policy of handling the failed transactions.
+    pub failure_policy: FailurePolicy,
+    /// The maximum amount of times a failed operation is sent again before the sender is paused.
+    pub max_failure_retries: u64,
+    /// Every failed attempt to send the operation increases its gas limit by this percentage.
+    pub failed_tx_gas_limit_increase_percent: u64,
+    /// Link to MatterMost channel for notifications about the failed transactions.
+    pub webhook_url: String,
+    /// Whether the transactions are simulated against the pending state before being sent.
+    /// Only the operations sent while no other transactions are in flight are simulated. Commit, prove
+    /// and execute operations usually overlap, so most of the operations are not checked; the skipped
+    /// checks are counted by the `eth_sender.preflight_skipped` metric.
+    pub preflight_check_enabled: bool,
+    /// Divergence (in percents) of the estimated gas from the gas limit that is reported
+    /// by the pre-flight check.
+    pub preflight_gas_divergence_percent: u64,
 }
 
 impl Sender {
@@ -51,6 +107,84 @@ impl Sender {
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
     }
+
+    /// Parses `self.operator_accounts` and returns the operator accounts other than the main one
+    /// (`operator_commit_eth_addr`) along with the action types sent from them. Action types
+    /// sharing an account are grouped. All the other action types are sent from the main account.
+    pub fn additional_operator_accounts(&self) -> Result<Vec<OperatorAccount>, String> {
+        let mut accounts: Vec<OperatorAccount> = Vec::new();
+        let mut action_types = Vec::new();
+        for entry in &self.operator_accounts {
+            let OperatorActionKey {
+                action_type,
+                private_key,
+            } = entry.parse()?;
+            if action_types.contains(&action_type) {
+                return Err(format!(
+                    "Several operator accounts are set for {:?}",
+                    action_type
+                ));
+            }
+            action_types.push(action_type);
+
+            let address =
+                PackedEthSignature::address_from_private_key(&private_key).map_err(|err| {
+                    format!(
+                        "Incorrect private key of the operator account for {:?}: {}",
+                        action_type, err
+                    )
+                })?;
+            if address == self.operator_commit_eth_addr {
+                continue;
+            }
+            match accounts
+                .iter_mut()
+                .find(|account| account.address == address)
+            {
+                Some(account) => account.action_types.push(action_type),
+                None => accounts.push(OperatorAccount {
+                    private_key,
+                    address,
+                    action_types: vec![action_type],
+                }),
+            }
+        }
+        Ok(accounts)
+    }
+}
+
+/// Entry of `Sender::operator_accounts`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OperatorActionKey {
+    action_type: AggregatedActionType,
+    private_key: H256,
+}
+
+impl FromStr for OperatorActionKey {
+    type Err = String;
+
+    /// Parses the `<action type>:<private key>` string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.trim().split(':').collect();
+        if parts.len() != 2 {
+            return Err(format!(
+                "Expected `<action type>:<private key>`, got {:?}",
+                s
+            ));
+        }
+        let action_type = match parts[0].parse()? {
+            AggregatedActionType::CreateProofBlocks => {
+                return Err("Proofs are not created by the operator accounts".to_string())
+            }
+            action_type => action_type,
+        };
+        let private_key = H256::from_str(parts[1].trim_start_matches("0x"))
+            .map_err(|err| format!("Incorrect private key for {:?}: {}", action_type, err))?;
+        Ok(Self {
+            action_type,
+            private_key,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -77,6 +211,41 @@ impl GasLimit {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SpendingLimit {
+    /// Length of the rolling window the spending limits are applied to, in seconds.
+    pub period: u64,
+    /// The maximum amount of ETH (in gwei) spent on the commit transactions within the window.
+    /// Not limited if not set.
+    pub commit_limit: Option<u64>,
+    /// The maximum amount of ETH (in gwei) spent on the proof transactions within the window.
+    /// Not limited if not set.
+    pub prove_limit: Option<u64>,
+    /// The maximum amount of ETH (in gwei) spent on the execute transactions within the window.
+    /// Not limited if not set.
+    pub execute_limit: Option<u64>,
+    /// Balance of the main operator account (in gwei) below which the execute transactions
+    /// sent from this account are held. Not checked if not set.
+    pub min_operator_balance: Option<u64>,
+}
+
+impl SpendingLimit {
+    /// Converts `self.period` into `Duration`.
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.period)
+    }
+
+    /// Returns the spending limit (in gwei) for the transactions of the given type, if any.
+    pub fn limit(&self, action_type: AggregatedActionType) -> Option<u64> {
+        match action_type {
+            AggregatedActionType::CommitBlocks => self.commit_limit,
+            AggregatedActionType::PublishProofBlocksOnchain => self.prove_limit,
+            AggregatedActionType::ExecuteBlocks => self.execute_limit,
+            AggregatedActionType::CreateProofBlocks => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,10 +259,21 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                tx_type: EthTxType::EIP1559,
+                failure_policy: FailurePolicy::Pause,
+                max_failure_retries: 3,
+                failed_tx_gas_limit_increase_percent: 25,
+                webhook_url: "http://127.0.0.1".to_string(),
+                preflight_check_enabled: true,
+                preflight_gas_divergence_percent: 20,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
                 operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+                operator_accounts: vec![
+                    "PublishProofBlocksOnchain:0xa0a1fd2bfa5ee1f83d7dd4e98e0b61a4e2d7a3e9d6a1e11f1e0de4e5d7e2b8e4".to_string(),
+                    "ExecuteBlocks:0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be".to_string(),
+                ],
             },
             gas_price_limit: GasLimit {
                 default: 400000000000,
@@ -101,6 +281,13 @@ mod tests {
                 sample_interval: 15,
                 scale_factor: 1.0f64,
             },
+            spending_limit: SpendingLimit {
+                period: 86400,
+                commit_limit: Some(5000000000),
+                prove_limit: None,
+                execute_limit: Some(1000000000),
+                min_operator_balance: Some(2000000000),
+            },
         }
     }
 
@@ -112,12 +299,24 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_TX_TYPE="EIP1559"
+ETH_SENDER_SENDER_FAILURE_POLICY="Pause"
+ETH_SENDER_SENDER_MAX_FAILURE_RETRIES="3"
+ETH_SENDER_SENDER_FAILED_TX_GAS_LIMIT_INCREASE_PERCENT="25"
+ETH_SENDER_SENDER_WEBHOOK_URL="http://127.0.0.1"
+ETH_SENDER_SENDER_PREFLIGHT_CHECK_ENABLED="true"
+ETH_SENDER_SENDER_PREFLIGHT_GAS_DIVERGENCE_PERCENT="20"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+ETH_SENDER_SENDER_OPERATOR_ACCOUNTS="PublishProofBlocksOnchain:0xa0a1fd2bfa5ee1f83d7dd4e98e0b61a4e2d7a3e9d6a1e11f1e0de4e5d7e2b8e4,ExecuteBlocks:0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
 ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL="150"
 ETH_SENDER_GAS_PRICE_LIMIT_SAMPLE_INTERVAL="15"
 ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
+ETH_SENDER_SPENDING_LIMIT_PERIOD="86400"
+ETH_SENDER_SPENDING_LIMIT_COMMIT_LIMIT="5000000000"
+ETH_SENDER_SPENDING_LIMIT_EXECUTE_LIMIT="1000000000"
+ETH_SENDER_SPENDING_LIMIT_MIN_OPERATOR_BALANCE="2000000000"
         "#;
         set_env(config);
 
@@ -134,6 +333,17 @@ ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
             config.sender.tx_poll_period(),
             Duration::from_secs(config.sender.tx_poll_period)
         );
+        // Execute operations are sent from the main account.
+        let prove_private_key =
+            hash("a0a1fd2bfa5ee1f83d7dd4e98e0b61a4e2d7a3e9d6a1e11f1e0de4e5d7e2b8e4");
+        assert_eq!(
+            config.sender.additional_operator_accounts(),
+            Ok(vec![OperatorAccount {
+                private_key: prove_private_key,
+                address: PackedEthSignature::address_from_private_key(&prove_private_key).unwrap(),
+                action_types: vec![AggregatedActionType::PublishProofBlocksOnchain],
+            }])
+        );
 
         assert_eq!(
             config.gas_price_limit.update_interval(),
@@ -143,5 +353,22 @@ ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
             config.gas_price_limit.sample_interval(),
             Duration::from_secs(config.gas_price_limit.sample_interval)
         );
+
+        assert_eq!(
+            config.spending_limit.period(),
+            Duration::from_secs(config.spending_limit.period)
+        );
+        assert_eq!(
+            config
+                .spending_limit
+                .limit(AggregatedActionType::CommitBlocks),
+            config.spending_limit.commit_limit
+        );
+        assert_eq!(
+            config
+                .spending_limit
+                .limit(AggregatedActionType::PublishProofBlocksOnchain),
+            None
+        );
     }
 }
@@ -14,6 +14,11 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Number of the latest blocks whose hashes are re-checked to detect the reorgs
+    /// deeper than the amount of confirmations.
+    pub reorg_check_depth: u64,
+    /// Link to MatterMost channel for notifications about the detected reorgs.
+    pub webhook_url: String,
 }
 
 impl ETHWatchConfig {
@@ -36,6 +41,8 @@ mod tests {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
             eth_node_poll_interval: 300,
+            reorg_check_depth: 64,
+            webhook_url: "http://127.0.0.1".to_string(),
         }
     }
 
@@ -44,6 +51,8 @@ mod tests {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_REORG_CHECK_DEPTH="64"
+ETH_WATCH_WEBHOOK_URL="http://127.0.0.1"
         "#;
         set_env(config);
 
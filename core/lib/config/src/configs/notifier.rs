@@ -0,0 +1,182 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Severity of the notification event.
+/// Each notifier backend receives only the events at least as severe as its configured minimum.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Regular events that require no actions, e.g. a new token being added.
+    Info,
+    /// Events that may require attention of the node administrators.
+    Warning,
+    /// Events that stop (some parts of) the node until the administrators intervene.
+    Critical,
+}
+
+/// Configuration of the notifications about the server events.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct NotifierConfig {
+    /// Generic webhook receiving the events as JSON objects.
+    pub webhook: WebhookBackend,
+    /// Slack-compatible incoming webhook.
+    pub slack: WebhookBackend,
+    /// MatterMost incoming webhook.
+    pub mattermost: WebhookBackend,
+    /// Email notifications sent via the SMTP server.
+    pub smtp: SmtpBackend,
+    /// Thresholds for the events emitted by the subsystems.
+    pub thresholds: Thresholds,
+}
+
+impl NotifierConfig {
+    pub fn from_env() -> Self {
+        Self {
+            webhook: envy_load!("notifier.webhook", "NOTIFIER_WEBHOOK_"),
+            slack: envy_load!("notifier.slack", "NOTIFIER_SLACK_"),
+            mattermost: envy_load!("notifier.mattermost", "NOTIFIER_MATTERMOST_"),
+            smtp: envy_load!("notifier.smtp", "NOTIFIER_SMTP_"),
+            thresholds: envy_load!("notifier.thresholds", "NOTIFIER_THRESHOLDS_"),
+        }
+    }
+}
+
+/// Settings of the notifier backend posting the events to the webhook.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WebhookBackend {
+    /// URL of the webhook. Backend is disabled if the URL is empty.
+    pub url: String,
+    /// Minimum severity of the events sent to the webhook.
+    pub min_severity: Severity,
+}
+
+impl WebhookBackend {
+    pub fn is_enabled(&self) -> bool {
+        !self.url.is_empty()
+    }
+}
+
+/// Settings of the notifier backend sending the events via email.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct SmtpBackend {
+    /// Host of the SMTP server. Backend is disabled if the host is empty.
+    pub server: String,
+    /// Port of the SMTP server.
+    pub port: u16,
+    /// Whether the connection should be upgraded to TLS with the `STARTTLS` command.
+    pub starttls: bool,
+    /// Username for the SMTP authentication. Authentication is skipped if the username is empty.
+    pub username: String,
+    /// Password for the SMTP authentication.
+    pub password: String,
+    /// Sender address of the emails.
+    pub from: String,
+    /// Recipient addresses of the emails.
+    pub recipients: Vec<String>,
+    /// Minimum severity of the events sent via email.
+    pub min_severity: Severity,
+}
+
+impl SmtpBackend {
+    pub fn is_enabled(&self) -> bool {
+        !self.server.is_empty()
+    }
+}
+
+/// Thresholds for the events that are reported once the monitored value becomes too high.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Thresholds {
+    /// Amount of the pending prover jobs to report the prover queue lag.
+    pub prover_queue_lag: u32,
+    /// Mempool size to report, in percents of the maximum mempool size.
+    pub mempool_size_percent: u32,
+    /// Duration of the state keeper throttling by the root hash calculator to report it.
+    /// Value in milliseconds.
+    pub root_hash_throttle: u64,
+}
+
+impl Thresholds {
+    /// Converts `self.root_hash_throttle` into `Duration`.
+    pub fn root_hash_throttle(&self) -> Duration {
+        Duration::from_millis(self.root_hash_throttle)
+    }
+
+    /// Returns the mempool size to report given the maximum mempool size.
+    pub fn mempool_size(&self, max_mempool_size: u32) -> u32 {
+        (max_mempool_size as u64 * self.mempool_size_percent as u64 / 100) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> NotifierConfig {
+        NotifierConfig {
+            webhook: WebhookBackend {
+                url: "http://127.0.0.1:8080/events".to_string(),
+                min_severity: Severity::Info,
+            },
+            slack: WebhookBackend {
+                url: String::new(),
+                min_severity: Severity::Warning,
+            },
+            mattermost: WebhookBackend {
+                url: "http://127.0.0.1".to_string(),
+                min_severity: Severity::Info,
+            },
+            smtp: SmtpBackend {
+                server: "smtp.example.com".to_string(),
+                port: 587,
+                starttls: true,
+                username: "alerts".to_string(),
+                password: "secret".to_string(),
+                from: "alerts@example.com".to_string(),
+                recipients: vec![
+                    "admin@example.com".to_string(),
+                    "oncall@example.com".to_string(),
+                ],
+                min_severity: Severity::Critical,
+            },
+            thresholds: Thresholds {
+                prover_queue_lag: 50,
+                mempool_size_percent: 90,
+                root_hash_throttle: 10000,
+            },
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+NOTIFIER_WEBHOOK_URL="http://127.0.0.1:8080/events"
+NOTIFIER_WEBHOOK_MIN_SEVERITY="Info"
+NOTIFIER_SLACK_URL=""
+NOTIFIER_SLACK_MIN_SEVERITY="Warning"
+NOTIFIER_MATTERMOST_URL="http://127.0.0.1"
+NOTIFIER_MATTERMOST_MIN_SEVERITY="Info"
+NOTIFIER_SMTP_SERVER="smtp.example.com"
+NOTIFIER_SMTP_PORT="587"
+NOTIFIER_SMTP_STARTTLS="true"
+NOTIFIER_SMTP_USERNAME="alerts"
+NOTIFIER_SMTP_PASSWORD="secret"
+NOTIFIER_SMTP_FROM="alerts@example.com"
+NOTIFIER_SMTP_RECIPIENTS="admin@example.com,oncall@example.com"
+NOTIFIER_SMTP_MIN_SEVERITY="Critical"
+NOTIFIER_THRESHOLDS_PROVER_QUEUE_LAG="50"
+NOTIFIER_THRESHOLDS_MEMPOOL_SIZE_PERCENT="90"
+NOTIFIER_THRESHOLDS_ROOT_HASH_THROTTLE="10000"
+        "#;
+        set_env(config);
+
+        let actual = NotifierConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert!(actual.webhook.is_enabled());
+        assert!(!actual.slack.is_enabled());
+        assert_eq!(actual.thresholds.mempool_size(10000), 9000);
+    }
+}
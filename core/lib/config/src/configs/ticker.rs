@@ -1,5 +1,7 @@
-use num::{rational::Ratio, BigUint};
+// Built-in uses
+use std::{str::FromStr, time::Duration};
 // External uses
+use num::{rational::Ratio, BigUint};
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::Address;
@@ -11,6 +13,57 @@ use crate::envy_load;
 pub enum TokenPriceSource {
     CoinGecko,
     CoinMarketCap,
+    /// Prices derived from the on-chain DEX pools listed in `dex_pools`.
+    Dex,
+}
+
+/// Type of the DEX pool used by the on-chain price source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexPoolKind {
+    /// Uniswap V2 pair, the price is the time-weighted average of the cumulative pair price
+    /// over `dex_twap_period`.
+    V2,
+    /// Uniswap V3 pool, the price is the time-weighted average over `dex_twap_period`.
+    V3,
+}
+
+/// DEX pool the price of the token is derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DexPool {
+    pub kind: DexPoolKind,
+    /// Token priced by the pool.
+    pub token: Address,
+    /// Address of the pool contract.
+    pub pool: Address,
+}
+
+impl FromStr for DexPool {
+    type Err = String;
+
+    /// Parses the pool from the `<V2|V3>:<token address>:<pool address>` string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<_> = s.trim().split(':').collect();
+        if parts.len() != 3 {
+            return Err(format!(
+                "Expected `<V2|V3>:<token address>:<pool address>`, got {:?}",
+                s
+            ));
+        }
+        let kind = match parts[0] {
+            "V2" => DexPoolKind::V2,
+            "V3" => DexPoolKind::V3,
+            kind => return Err(format!("Unknown DEX pool type {:?}", kind)),
+        };
+        let parse_address = |address: &str| {
+            Address::from_str(address.trim_start_matches("0x"))
+                .map_err(|err| format!("Incorrect address {:?}: {}", address, err))
+        };
+        Ok(Self {
+            kind,
+            token: parse_address(parts[1])?,
+            pool: parse_address(parts[2])?,
+        })
+    }
 }
 
 /// Configuration for the fee ticker.
@@ -40,6 +93,47 @@ pub struct TickerConfig {
     pub number_of_ticker_actors: u8,
     /// Subsidized price for ChangePubKey in cents scaled by SUBSIDY_USD_AMOUNTS_SCALE
     pub subsidy_cpk_price_usd_scaled: u64,
+    /// Number of days the token price samples are stored for.
+    pub price_history_retention_days: u64,
+    /// Number of days after which the token price samples are downsampled.
+    pub price_history_downsample_after_days: u64,
+    /// Interval between the downsampled token price samples in seconds.
+    pub price_history_downsample_interval: u64,
+    /// Number of days the prices used for the fees of the submitted transactions and the used
+    /// fee quotes are stored for.
+    pub fee_quote_snapshot_retention_days: u64,
+    /// Sources aggregated with `token_price_source`. If empty, only `token_price_source` is used.
+    pub aggregated_price_sources: Vec<TokenPriceSource>,
+    /// Maximum deviation of the aggregated source price from the median in percent.
+    pub price_max_deviation_percent: u32,
+    /// Maximum age of the aggregated source price in seconds.
+    pub price_max_age: u64,
+    /// Pools used by the on-chain price source, formatted as `<V2|V3>:<token address>:<pool address>`.
+    pub dex_pools: Vec<String>,
+    /// Stablecoin considered to be worth exactly 1 USD by the on-chain price source.
+    pub dex_usd_token: Address,
+    /// Wrapped ETH, ETH is priced by its pool.
+    pub dex_wrapped_eth: Address,
+    /// Minimum value of the pool reserves of the counterpart token in USD for the pool to be trusted.
+    pub dex_min_liquidity_usd: u64,
+    /// Period of the time-weighted average price of the pools in seconds.
+    pub dex_twap_period: u32,
+    /// Lifetime of the fee quotes in seconds. If zero, the fee quotes are not issued.
+    pub fee_quote_ttl: u64,
+    /// Interval between the gas cost calibrations in seconds. If zero, the constant gas costs are used.
+    pub gas_calibration_interval: u64,
+    /// Period of the confirmed Ethereum transactions used for the gas cost calibration in seconds.
+    pub gas_calibration_window: u64,
+    /// Weight of the new observation in the smoothed gas cost in percent.
+    pub gas_calibration_smoothing_percent: u64,
+    /// Lower bound of the calibrated amortized gas cost per chunk.
+    pub min_amortized_cost_per_chunk: u64,
+    /// Upper bound of the calibrated amortized gas cost per chunk.
+    pub max_amortized_cost_per_chunk: u64,
+    /// Lower bound of the calibrated scale of the average gas price in percent.
+    pub min_gas_price_scale_percent: u64,
+    /// Upper bound of the calibrated scale of the average gas price in percent.
+    pub max_gas_price_scale_percent: u64,
 }
 
 impl TickerConfig {
@@ -51,13 +145,94 @@ impl TickerConfig {
         envy_load!("fee_ticker", "FEE_TICKER_")
     }
 
+    /// Converts `self.price_history_retention_days` into `Duration`.
+    pub fn price_history_retention(&self) -> Duration {
+        Duration::from_secs(self.price_history_retention_days * 24 * 60 * 60)
+    }
+
+    /// Converts `self.price_history_downsample_after_days` into `Duration`.
+    pub fn price_history_downsample_after(&self) -> Duration {
+        Duration::from_secs(self.price_history_downsample_after_days * 24 * 60 * 60)
+    }
+
+    /// Converts `self.price_history_downsample_interval` into `Duration`.
+    pub fn price_history_downsample_interval(&self) -> Duration {
+        Duration::from_secs(self.price_history_downsample_interval)
+    }
+
+    /// Converts `self.fee_quote_snapshot_retention_days` into `Duration`.
+    pub fn fee_quote_snapshot_retention(&self) -> Duration {
+        Duration::from_secs(self.fee_quote_snapshot_retention_days * 24 * 60 * 60)
+    }
+
+    /// Converts `self.price_max_age` into `Duration`.
+    pub fn price_max_age(&self) -> Duration {
+        Duration::from_secs(self.price_max_age)
+    }
+
+    /// Converts `self.fee_quote_ttl` into `Duration`.
+    pub fn fee_quote_ttl(&self) -> Duration {
+        Duration::from_secs(self.fee_quote_ttl)
+    }
+
+    /// Converts `self.gas_calibration_interval` into `Duration`.
+    pub fn gas_calibration_interval(&self) -> Duration {
+        Duration::from_secs(self.gas_calibration_interval)
+    }
+
+    /// Converts `self.gas_calibration_window` into `Duration`.
+    pub fn gas_calibration_window(&self) -> Duration {
+        Duration::from_secs(self.gas_calibration_window)
+    }
+
     /// Returns the token price source type and the corresponding API URL.
     pub fn price_source(&self) -> (TokenPriceSource, String) {
-        let url = match self.token_price_source {
+        (
+            self.token_price_source,
+            self.price_source_url(self.token_price_source),
+        )
+    }
+
+    /// Returns the API URL of the token price source.
+    pub fn price_source_url(&self, source: TokenPriceSource) -> String {
+        match source {
             TokenPriceSource::CoinGecko => self.coingecko_base_url.clone(),
             TokenPriceSource::CoinMarketCap => self.coinmarketcap_base_url.clone(),
-        };
-        (self.token_price_source, url)
+            // On-chain source uses the Ethereum client.
+            TokenPriceSource::Dex => String::new(),
+        }
+    }
+
+    /// Parses `self.dex_pools`. Returns an error if the pool is malformed or there are several pools
+    /// for the same token.
+    pub fn dex_pools(&self) -> Result<Vec<DexPool>, String> {
+        let mut pools: Vec<DexPool> = Vec::with_capacity(self.dex_pools.len());
+        for pool in &self.dex_pools {
+            let pool: DexPool = pool.parse()?;
+            if pools.iter().any(|other| other.token == pool.token) {
+                return Err(format!(
+                    "Several DEX pools are set for the token {:?}",
+                    pool.token
+                ));
+            }
+            pools.push(pool);
+        }
+        Ok(pools)
+    }
+
+    /// Returns the token price sources to be aggregated, starting with `token_price_source`.
+    /// Empty if the aggregation is disabled.
+    pub fn aggregated_price_sources(&self) -> Vec<TokenPriceSource> {
+        if self.aggregated_price_sources.is_empty() {
+            return Vec::new();
+        }
+        let mut sources = vec![self.token_price_source];
+        for source in &self.aggregated_price_sources {
+            if !sources.contains(source) {
+                sources.push(*source);
+            }
+        }
+        sources
     }
 }
 
@@ -80,6 +255,29 @@ mod tests {
             token_market_update_time: 120,
             number_of_ticker_actors: 4,
             subsidy_cpk_price_usd_scaled: 100,
+            price_history_retention_days: 365,
+            price_history_downsample_after_days: 7,
+            price_history_downsample_interval: 3600,
+            fee_quote_snapshot_retention_days: 90,
+            aggregated_price_sources: vec![TokenPriceSource::CoinMarketCap],
+            price_max_deviation_percent: 10,
+            price_max_age: 1800,
+            dex_pools: vec![
+                "V2:0x6b175474e89094c44da98b954eedeac495271d0f:0xa478c2975ab1ea89e8196811f51a7b7ade33eb11".to_string(),
+                "V3:0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2:0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
+            ],
+            dex_usd_token: addr("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+            dex_wrapped_eth: addr("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+            dex_min_liquidity_usd: 100000,
+            dex_twap_period: 1800,
+            fee_quote_ttl: 120,
+            gas_calibration_interval: 600,
+            gas_calibration_window: 86400,
+            gas_calibration_smoothing_percent: 20,
+            min_amortized_cost_per_chunk: 100,
+            max_amortized_cost_per_chunk: 2000,
+            min_gas_price_scale_percent: 100,
+            max_gas_price_scale_percent: 200,
         }
     }
 
@@ -99,6 +297,26 @@ FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
 FEE_TICKER_SCALE_FEE_PERCENT=100
 FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
+FEE_TICKER_PRICE_HISTORY_RETENTION_DAYS=365
+FEE_TICKER_PRICE_HISTORY_DOWNSAMPLE_AFTER_DAYS=7
+FEE_TICKER_PRICE_HISTORY_DOWNSAMPLE_INTERVAL=3600
+FEE_TICKER_FEE_QUOTE_SNAPSHOT_RETENTION_DAYS=90
+FEE_TICKER_AGGREGATED_PRICE_SOURCES="CoinMarketCap"
+FEE_TICKER_PRICE_MAX_DEVIATION_PERCENT=10
+FEE_TICKER_PRICE_MAX_AGE=1800
+FEE_TICKER_DEX_POOLS="V2:0x6b175474e89094c44da98b954eedeac495271d0f:0xa478c2975ab1ea89e8196811f51a7b7ade33eb11,V3:0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2:0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640"
+FEE_TICKER_DEX_USD_TOKEN="0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+FEE_TICKER_DEX_WRAPPED_ETH="0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+FEE_TICKER_DEX_MIN_LIQUIDITY_USD=100000
+FEE_TICKER_DEX_TWAP_PERIOD=1800
+FEE_TICKER_FEE_QUOTE_TTL=120
+FEE_TICKER_GAS_CALIBRATION_INTERVAL=600
+FEE_TICKER_GAS_CALIBRATION_WINDOW=86400
+FEE_TICKER_GAS_CALIBRATION_SMOOTHING_PERCENT=20
+FEE_TICKER_MIN_AMORTIZED_COST_PER_CHUNK=100
+FEE_TICKER_MAX_AMORTIZED_COST_PER_CHUNK=2000
+FEE_TICKER_MIN_GAS_PRICE_SCALE_PERCENT=100
+FEE_TICKER_MAX_GAS_PRICE_SCALE_PERCENT=200
         "#;
         set_env(config);
 
@@ -128,5 +346,65 @@ FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
             config.price_source(),
             (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
         );
+
+        assert_eq!(
+            config.price_history_retention(),
+            Duration::from_secs(365 * 24 * 60 * 60)
+        );
+        assert_eq!(
+            config.price_history_downsample_interval(),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            config.fee_quote_snapshot_retention(),
+            Duration::from_secs(90 * 24 * 60 * 60)
+        );
+        assert_eq!(config.price_max_age(), Duration::from_secs(1800));
+        assert_eq!(config.fee_quote_ttl(), Duration::from_secs(120));
+        assert_eq!(config.gas_calibration_interval(), Duration::from_secs(600));
+        assert_eq!(config.gas_calibration_window(), Duration::from_secs(86400));
+
+        assert_eq!(
+            config.aggregated_price_sources(),
+            vec![TokenPriceSource::CoinMarketCap]
+        );
+        config.aggregated_price_sources =
+            vec![TokenPriceSource::CoinGecko, TokenPriceSource::CoinMarketCap];
+        config.token_price_source = TokenPriceSource::CoinGecko;
+        assert_eq!(
+            config.aggregated_price_sources(),
+            vec![TokenPriceSource::CoinGecko, TokenPriceSource::CoinMarketCap]
+        );
+        config.aggregated_price_sources = Vec::new();
+        assert!(config.aggregated_price_sources().is_empty());
+
+        assert_eq!(
+            config.dex_pools(),
+            Ok(vec![
+                DexPool {
+                    kind: DexPoolKind::V2,
+                    token: addr("6b175474e89094c44da98b954eedeac495271d0f"),
+                    pool: addr("a478c2975ab1ea89e8196811f51a7b7ade33eb11"),
+                },
+                DexPool {
+                    kind: DexPoolKind::V3,
+                    token: addr("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+                    pool: addr("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640"),
+                },
+            ])
+        );
+        config.dex_pools.push(
+            "V3:0x6b175474e89094c44da98b954eedeac495271d0f:0x5777d92f208679db4b9778590fa3cab3ac9e2168"
+                .to_string(),
+        );
+        assert!(config.dex_pools().is_err());
+        config.dex_pools = vec!["V2:0x6b175474e89094c44da98b954eedeac495271d0f".to_string()];
+        assert!(config.dex_pools().is_err());
+        assert!("V4:0x6b175474e89094c44da98b954eedeac495271d0f:0xa478c2975ab1ea89e8196811f51a7b7ade33eb11"
+            .parse::<DexPool>()
+            .is_err());
+        assert!("V2:0x6b175474e89094c44da98b954eedeac495271d0f"
+            .parse::<DexPool>()
+            .is_err());
     }
 }
@@ -1,8 +1,8 @@
 pub use crate::configs::{
     ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
     ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, NotifierConfig, ProverConfig,
+    TickerConfig, TokenHandlerConfig,
 };
 
 pub mod configs;
@@ -23,6 +23,7 @@ pub struct ZkSyncConfig {
     pub prover: ProverConfig,
     pub ticker: TickerConfig,
     pub forced_exit_requests: ForcedExitRequestsConfig,
+    pub notifier: NotifierConfig,
 }
 
 impl ZkSyncConfig {
@@ -41,6 +42,7 @@ impl ZkSyncConfig {
             prover: ProverConfig::from_env(),
             ticker: TickerConfig::from_env(),
             forced_exit_requests: ForcedExitRequestsConfig::from_env(),
+            notifier: NotifierConfig::from_env(),
         }
     }
 }
@@ -0,0 +1,240 @@
+//! Health tracking of the gateways used by the multiplexed Ethereum client.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Weight of the latest sample in the moving averages of the gateway statistics.
+const SAMPLE_WEIGHT: f64 = 0.1;
+/// Number of the latest requests the gateway latency is calculated over.
+const LATENCY_WINDOW_SIZE: usize = 20;
+
+/// Rules of the gateway eviction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthPolicy {
+    /// Gateways with the health score below this value are evicted.
+    pub min_score: f64,
+    /// How long an evicted gateway is not used.
+    pub eviction_period: Duration,
+    /// Requests slower than this lower the health score.
+    pub slow_request_threshold: Duration,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            min_score: 0.5,
+            eviction_period: Duration::from_secs(60),
+            slow_request_threshold: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Statistics of the gateway collected during the lifetime of the client.
+///
+/// The score is calculated from the median latency of the latest requests, and the moving averages
+/// of the error rate and the rate of responses disagreeing with the other gateways. Once the score drops below the
+/// policy limit, the gateway is evicted for the eviction period and gets a fresh start afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct GatewayHealth {
+    /// Latencies of the latest requests, at most `LATENCY_WINDOW_SIZE` of them.
+    latencies: VecDeque<Duration>,
+    /// Moving average of the failed requests share.
+    error_rate: f64,
+    /// Moving average of the share of responses disagreeing with the other gateways.
+    disagreement_rate: f64,
+    /// Total number of the requests.
+    requests: u64,
+    /// Total number of the responses disagreeing with the other gateways.
+    disagreements: u64,
+    /// The gateway is not used until this moment.
+    evicted_until: Option<Instant>,
+}
+
+/// Health state of the gateway, intended for the monitoring.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GatewayHealthReport {
+    pub name: String,
+    pub score: f64,
+    pub latency: Duration,
+    pub error_rate: f64,
+    pub requests: u64,
+    pub disagreements: u64,
+    pub evicted: bool,
+}
+
+fn moving_average(average: f64, sample: f64) -> f64 {
+    average * (1.0 - SAMPLE_WEIGHT) + sample * SAMPLE_WEIGHT
+}
+
+impl GatewayHealth {
+    /// Median latency of the latest requests, so that occasional slow requests
+    /// don't affect the score.
+    pub fn latency(&self) -> Duration {
+        let mut latencies: Vec<_> = self.latencies.iter().copied().collect();
+        latencies.sort_unstable();
+        latencies
+            .get(latencies.len() / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Health score of the gateway from 0 (unusable) to 1 (healthy).
+    pub fn score(&self, policy: &HealthPolicy) -> f64 {
+        let latency = self.latency();
+        let latency_factor = if latency > policy.slow_request_threshold {
+            policy.slow_request_threshold.as_secs_f64() / latency.as_secs_f64()
+        } else {
+            1.0
+        };
+        (1.0 - self.error_rate) * (1.0 - self.disagreement_rate) * latency_factor
+    }
+
+    /// Returns whether the gateway is not evicted. Statistics of the gateway are reset once
+    /// the eviction period is over.
+    pub fn is_available(&mut self) -> bool {
+        match self.evicted_until {
+            Some(evicted_until) if evicted_until > Instant::now() => false,
+            Some(_) => {
+                *self = Self {
+                    requests: self.requests,
+                    disagreements: self.disagreements,
+                    ..Self::default()
+                };
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Updates the statistics with the finished request.
+    /// Returns `true` if the gateway got evicted.
+    pub fn report_request(
+        &mut self,
+        latency: Duration,
+        success: bool,
+        policy: &HealthPolicy,
+    ) -> bool {
+        if self.latencies.len() == LATENCY_WINDOW_SIZE {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+        self.error_rate = moving_average(self.error_rate, if success { 0.0 } else { 1.0 });
+        self.requests += 1;
+
+        self.check_eviction(policy)
+    }
+
+    /// Updates the statistics with the result of the response comparison with the other gateways.
+    /// Returns `true` if the gateway got evicted.
+    pub fn report_agreement(&mut self, agreed: bool, policy: &HealthPolicy) -> bool {
+        self.disagreement_rate =
+            moving_average(self.disagreement_rate, if agreed { 0.0 } else { 1.0 });
+        if !agreed {
+            self.disagreements += 1;
+        }
+
+        self.check_eviction(policy)
+    }
+
+    /// Returns the health state of the gateway with the given name.
+    pub fn report(&self, name: &str, policy: &HealthPolicy) -> GatewayHealthReport {
+        GatewayHealthReport {
+            name: name.to_owned(),
+            score: self.score(policy),
+            latency: self.latency(),
+            error_rate: self.error_rate,
+            requests: self.requests,
+            disagreements: self.disagreements,
+            evicted: self
+                .evicted_until
+                .map(|evicted_until| evicted_until > Instant::now())
+                .unwrap_or(false),
+        }
+    }
+
+    fn check_eviction(&mut self, policy: &HealthPolicy) -> bool {
+        if self.evicted_until.is_none() && self.score(policy) < policy.min_score {
+            self.evicted_until = Some(Instant::now() + policy.eviction_period);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(eviction_period: Duration) -> HealthPolicy {
+        HealthPolicy {
+            min_score: 0.5,
+            eviction_period,
+            slow_request_threshold: Duration::from_millis(100),
+        }
+    }
+
+    #[test]
+    fn failed_requests_eviction() {
+        let policy = policy(Duration::from_secs(60));
+        let mut health = GatewayHealth::default();
+
+        health.report_request(Duration::from_millis(10), true, &policy);
+        assert_eq!(health.score(&policy), 1.0);
+
+        // Single failure doesn't evict the gateway.
+        assert!(!health.report_request(Duration::from_millis(10), false, &policy));
+        assert!(health.is_available());
+
+        let evicted = (0..10)
+            .map(|_| health.report_request(Duration::from_millis(10), false, &policy))
+            .filter(|evicted| *evicted)
+            .count();
+        assert_eq!(evicted, 1);
+        assert!(!health.is_available());
+        assert!(health.report("gateway", &policy).evicted);
+    }
+
+    #[test]
+    fn slow_requests_and_disagreements_score() {
+        let policy = policy(Duration::from_secs(60));
+        let mut health = GatewayHealth::default();
+
+        // Slow gateway is evicted.
+        assert!(health.report_request(Duration::from_millis(400), true, &policy));
+        assert!((health.score(&policy) - 0.25).abs() < 1e-9);
+
+        // Occasional slow requests don't affect the score.
+        let mut health = GatewayHealth::default();
+        for _ in 0..2 {
+            health.report_request(Duration::from_millis(10), true, &policy);
+        }
+        assert!(!health.report_request(Duration::from_secs(1), true, &policy));
+        assert_eq!(health.score(&policy), 1.0);
+        assert_eq!(health.latency(), Duration::from_millis(10));
+
+        // Only the latest requests are taken into account.
+        for _ in 0..LATENCY_WINDOW_SIZE {
+            health.report_request(Duration::from_millis(20), true, &policy);
+        }
+        assert_eq!(health.latency(), Duration::from_millis(20));
+
+        let mut health = GatewayHealth::default();
+        health.report_request(Duration::from_millis(10), true, &policy);
+        health.report_agreement(false, &policy);
+        assert!((health.score(&policy) - 0.9).abs() < 1e-9);
+        assert_eq!(health.report("gateway", &policy).disagreements, 1);
+    }
+
+    #[test]
+    fn eviction_expiration() {
+        let policy = policy(Duration::from_secs(0));
+        let mut health = GatewayHealth::default();
+
+        assert!(health.report_request(Duration::from_secs(1), true, &policy));
+        // The gateway gets a fresh start once the eviction period is over.
+        assert!(health.is_available());
+        assert_eq!(health.score(&policy), 1.0);
+        assert_eq!(health.report("gateway", &policy).requests, 1);
+    }
+}
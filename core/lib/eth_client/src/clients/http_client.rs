@@ -20,15 +20,36 @@ use web3::{
 };
 
 // Workspace uses
-use zksync_eth_signer::{raw_ethereum_tx::RawTransaction, EthereumSigner};
+use zksync_eth_signer::{
+    raw_ethereum_tx::{RawTransaction, EIP1559_TX_ID},
+    EthereumSigner,
+};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, GasEstimation, SignedCallResult};
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
+/// JSON RPC error code used by the nodes for the reverted calls with the revert data.
+const EXECUTION_REVERTED_CODE: i64 = 3;
+/// Prefix of the error message reported by the nodes for the reverted calls.
+const EXECUTION_REVERTED_MESSAGE: &str = "execution reverted";
+
+/// Returns the revert reason if the RPC error was caused by the reverted call,
+/// or `None` if the error is not related to the call execution.
+fn revert_reason(code: i64, message: &str) -> Option<String> {
+    if code != EXECUTION_REVERTED_CODE && !message.starts_with(EXECUTION_REVERTED_MESSAGE) {
+        return None;
+    }
+    let reason = message
+        .trim_start_matches(EXECUTION_REVERTED_MESSAGE)
+        .trim_start_matches(':')
+        .trim();
+    Some(reason.to_string())
+}
+
 struct ETHDirectClientInner<S: EthereumSigner> {
     eth_signer: S,
     sender_account: Address,
@@ -153,6 +174,41 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(network_gas_price)
     }
 
+    /// Returns the base fee per gas of the latest block.
+    pub async fn get_base_fee(&self) -> Result<U256, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let block = self
+            .inner
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Latest))
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Latest block is not found"))?;
+        let base_fee = block
+            .base_fee_per_gas
+            .ok_or_else(|| anyhow::format_err!("Latest block has no base fee"))?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.get_base_fee", start.elapsed());
+        Ok(base_fee)
+    }
+
+    /// Returns the priority fee per gas, which is the part of the network gas price
+    /// exceeding the base fee of the latest block.
+    pub async fn get_priority_fee(&self) -> Result<U256, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let network_gas_price = self.inner.web3.eth().gas_price().await?;
+        let base_fee = self.get_base_fee().await?;
+        let percent_gas_price_factor =
+            U256::from((self.inner.gas_price_factor * 100.0).round() as u64);
+        let priority_fee = (network_gas_price.saturating_sub(base_fee) * percent_gas_price_factor)
+            / U256::from(100);
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.get_priority_fee", start.elapsed());
+        Ok(priority_fee)
+    }
+
     pub async fn sign_prepared_tx(
         &self,
         data: Vec<u8>,
@@ -171,10 +227,21 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         #[cfg(feature = "with-metrics")]
         let start = Instant::now();
 
-        // fetch current gas_price
-        let gas_price = match options.gas_price {
-            Some(gas_price) => gas_price,
-            None => self.get_gas_price().await?,
+        // EIP-1559 transaction is created if the max fee per gas is set,
+        // in this case it's used instead of the gas price.
+        let (gas_price, max_priority_fee_per_gas) = match options.max_fee_per_gas {
+            Some(max_fee_per_gas) => {
+                let priority_fee = match options.max_priority_fee_per_gas {
+                    Some(priority_fee) => priority_fee,
+                    None => self.get_priority_fee().await?,
+                };
+                (max_fee_per_gas, Some(priority_fee))
+            }
+            // fetch current gas_price
+            None => match options.gas_price {
+                Some(gas_price) => (gas_price, None),
+                None => (self.get_gas_price().await?, None),
+            },
         };
 
         let nonce = match options.nonce {
@@ -200,16 +267,16 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         // form and sign tx
         let tx = RawTransaction {
             chain_id: self.inner.chain_id,
-            transaction_type: None,
+            transaction_type: max_priority_fee_per_gas.map(|_| EIP1559_TX_ID.into()),
             access_list: None,
-            max_fee_per_gas: Default::default(),
+            max_fee_per_gas: max_priority_fee_per_gas.map(|_| gas_price),
             nonce,
             to: Some(contract_addr),
             value: options.value.unwrap_or_default(),
             gas_price,
             gas,
             data,
-            max_priority_fee_per_gas: Default::default(),
+            max_priority_fee_per_gas,
         };
 
         let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
@@ -228,6 +295,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(SignedCallResult {
             raw_tx: signed_tx,
             gas_price,
+            max_priority_fee_per_gas,
             nonce,
             hash,
         })
@@ -333,6 +401,42 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         }
     }
 
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<GasEstimation, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let call_request = web3::types::CallRequest {
+            from: Some(self.inner.sender_account),
+            to: Some(self.inner.contract_addr),
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(Bytes(data)),
+            transaction_type: None,
+            access_list: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let estimation = match self
+            .inner
+            .web3
+            .eth()
+            .estimate_gas(call_request, Some(BlockNumber::Pending))
+            .await
+        {
+            Ok(gas) => GasEstimation::Success(gas),
+            // Node reports the reverted call as an RPC error, other RPC errors
+            // (e.g. rate limits or internal node failures) are returned as is.
+            Err(web3::Error::Rpc(e)) => match revert_reason(e.code.code(), &e.message) {
+                Some(reason) => GasEstimation::Reverted(reason),
+                None => return Err(web3::Error::Rpc(e).into()),
+            },
+            Err(e) => return Err(e.into()),
+        };
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.estimate_gas", start.elapsed());
+        Ok(estimation)
+    }
+
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         #[cfg(feature = "with-metrics")]
         let start = Instant::now();
@@ -434,6 +538,8 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
             Some(TransactionReceipt {
                 block_number: Some(tx_block_number),
                 status: Some(status),
+                gas_used,
+                effective_gas_price,
                 ..
             }) => {
                 let current_block = match current_block {
@@ -454,6 +560,8 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                     confirmations,
                     success,
                     receipt,
+                    gas_used,
+                    effective_gas_price,
                 }))
             }
             _ => Ok(None),
@@ -515,4 +623,43 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         metrics::histogram!("eth_client.direct.get_tx", start.elapsed());
         Ok(tx)
     }
+
+    /// Returns the hash of the block with the given number, if the block exists.
+    pub async fn get_block_hash(&self, block_number: u64) -> Result<Option<H256>, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let block = self
+            .inner
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Number(block_number.into())))
+            .await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.get_block_hash", start.elapsed());
+        Ok(block.and_then(|block| block.hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revert_reason_from_rpc_error() {
+        assert_eq!(
+            revert_reason(EXECUTION_REVERTED_CODE, "execution reverted: i"),
+            Some("i".to_string())
+        );
+        assert_eq!(
+            revert_reason(-32000, "execution reverted"),
+            Some(String::new())
+        );
+        assert_eq!(
+            revert_reason(-32000, "execution reverted: o1"),
+            Some("o1".to_string())
+        );
+        // Errors that are not caused by the call execution.
+        assert_eq!(revert_reason(-32005, "limit exceeded"), None);
+        assert_eq!(revert_reason(-32000, "header not found"), None);
+    }
 }
@@ -12,7 +12,7 @@ use web3::types::{BlockId, Filter, Log, Transaction, U64};
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::{
-    ethereum_gateway::{ExecutedTxStatus, FailureInfo},
+    ethereum_gateway::{ExecutedTxStatus, FailureInfo, GasEstimation},
     SignedCallResult,
 };
 
@@ -20,8 +20,14 @@ use crate::{
 struct MockEthereumInner {
     block_number: u64,
     gas_price: U256,
+    base_fee: U256,
+    priority_fee: U256,
+    balance: U256,
+    gas_estimation: GasEstimation,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
+    failure_reasons: Arc<RwLock<HashMap<H256, FailureInfo>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    contract_calls: Arc<RwLock<HashMap<(Address, String), Vec<ethabi::Token>>>>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -35,8 +41,14 @@ impl Default for MockEthereumInner {
         Self {
             block_number: 1,
             gas_price: 100.into(),
+            base_fee: 80.into(),
+            priority_fee: 20.into(),
+            balance: U256::from(10).pow(18.into()),
+            gas_estimation: GasEstimation::Success(1_000_000.into()),
             tx_statuses: Default::default(),
+            failure_reasons: Default::default(),
             sent_txs: Default::default(),
+            contract_calls: Default::default(),
         }
     }
 }
@@ -83,6 +95,8 @@ impl MockEthereum {
             confirmations,
             success: true,
             receipt: None,
+            gas_used: None,
+            effective_gas_price: None,
         };
         self.inner.tx_statuses.write().await.insert(tx_hash, status);
     }
@@ -94,10 +108,40 @@ impl MockEthereum {
         let status = ExecutedTxStatus {
             confirmations,
             success: false,
-            receipt: Some(Default::default()),
+            receipt: Some(TransactionReceipt {
+                transaction_hash: *hash,
+                ..Default::default()
+            }),
+            gas_used: None,
+            effective_gas_price: None,
         };
         self.inner.tx_statuses.write().await.insert(*hash, status);
     }
+
+    /// Sets the failure reason to be reported for the failed transaction.
+    pub async fn add_failure_reason(&mut self, hash: &H256, failure: FailureInfo) {
+        self.inner
+            .failure_reasons
+            .write()
+            .await
+            .insert(*hash, failure);
+    }
+
+    /// Sets the tokens returned by the calls of the contract function.
+    /// Calls of the functions without a response fail.
+    pub async fn add_contract_call_response(
+        &mut self,
+        contract: Address,
+        func: &str,
+        response: Vec<ethabi::Token>,
+    ) {
+        self.inner
+            .contract_calls
+            .write()
+            .await
+            .insert((contract, func.to_string()), response);
+    }
+
     pub async fn get_tx_status(
         &self,
         hash: H256,
@@ -124,6 +168,36 @@ impl MockEthereum {
         Ok(self.inner.gas_price)
     }
 
+    pub async fn get_base_fee(&self) -> anyhow::Result<U256> {
+        Ok(self.inner.base_fee)
+    }
+
+    pub async fn set_base_fee(&mut self, val: U256) -> anyhow::Result<U256> {
+        Arc::get_mut(&mut self.inner).unwrap().base_fee = val;
+        Ok(self.inner.base_fee)
+    }
+
+    pub async fn get_priority_fee(&self) -> anyhow::Result<U256> {
+        Ok(self.inner.priority_fee)
+    }
+
+    pub async fn set_priority_fee(&mut self, val: U256) -> anyhow::Result<U256> {
+        Arc::get_mut(&mut self.inner).unwrap().priority_fee = val;
+        Ok(self.inner.priority_fee)
+    }
+
+    pub async fn estimate_gas(&self, _data: Vec<u8>) -> anyhow::Result<GasEstimation> {
+        Ok(self.inner.gas_estimation.clone())
+    }
+
+    pub async fn set_gas_estimation(
+        &mut self,
+        val: GasEstimation,
+    ) -> anyhow::Result<GasEstimation> {
+        Arc::get_mut(&mut self.inner).unwrap().gas_estimation = val;
+        Ok(self.inner.gas_estimation.clone())
+    }
+
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
         // Cut hash of transaction
         let mut hash: [u8; 32] = Default::default();
@@ -137,13 +211,27 @@ impl MockEthereum {
         raw_tx: Vec<u8>,
         options: Options,
     ) -> anyhow::Result<SignedCallResult> {
-        let gas_price = options.gas_price.unwrap_or(self.inner.gas_price);
+        // Same as the real client, EIP-1559 transaction is created if the max fee per gas is set.
+        let (gas_price, max_priority_fee_per_gas) = match options.max_fee_per_gas {
+            Some(max_fee_per_gas) => (
+                max_fee_per_gas,
+                Some(
+                    options
+                        .max_priority_fee_per_gas
+                        .unwrap_or(self.inner.priority_fee),
+                ),
+            ),
+            None => (options.gas_price.unwrap_or(self.inner.gas_price), None),
+        };
         let nonce = options.nonce.expect("Nonce must be set for every tx");
 
         // Nonce and gas_price are appended to distinguish the same transactions
         // with different gas by their hash in tests.
         let mut data_for_hash = raw_tx.clone();
         data_for_hash.append(&mut ethabi::encode(gas_price.into_tokens().as_ref()));
+        if let Some(priority_fee) = max_priority_fee_per_gas {
+            data_for_hash.append(&mut ethabi::encode(priority_fee.into_tokens().as_ref()));
+        }
         data_for_hash.append(&mut ethabi::encode(nonce.into_tokens().as_ref()));
         let hash = Self::fake_sha256(data_for_hash.as_ref()); // Okay for test purposes.
                                                               // Concatenate raw_tx plus hash for test purposes
@@ -152,6 +240,7 @@ impl MockEthereum {
         Ok(SignedCallResult {
             raw_tx: new_raw_tx,
             gas_price,
+            max_priority_fee_per_gas,
             nonce,
             hash,
         })
@@ -159,13 +248,20 @@ impl MockEthereum {
 
     pub async fn failure_reason(
         &self,
-        _tx_hash: H256,
+        tx_hash: H256,
     ) -> Result<Option<FailureInfo>, anyhow::Error> {
-        Ok(None)
+        Ok(self
+            .inner
+            .failure_reasons
+            .read()
+            .await
+            .get(&tx_hash)
+            .cloned())
     }
 
     pub async fn pending_nonce(&self) -> Result<U256, Error> {
-        unreachable!()
+        // Nonces of the sent transactions are not tracked by the mock.
+        Ok(U256::zero())
     }
 
     pub async fn current_nonce(&self) -> Result<U256, Error> {
@@ -173,7 +269,12 @@ impl MockEthereum {
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, Error> {
-        unreachable!()
+        Ok(self.inner.balance)
+    }
+
+    pub async fn set_sender_eth_balance(&mut self, val: U256) -> anyhow::Result<U256> {
+        Arc::get_mut(&mut self.inner).unwrap().balance = val;
+        Ok(self.inner.balance)
     }
 
     pub async fn sign_prepared_tx_for_addr(
@@ -241,12 +342,12 @@ impl MockEthereum {
     #[allow(clippy::too_many_arguments)]
     pub async fn call_contract_function<R, A, B, P>(
         &self,
-        _func: &str,
+        func: &str,
         _params: P,
         _from: A,
         _options: Options,
         _block: B,
-        _token_address: Address,
+        token_address: Address,
         _erc20_abi: ethabi::Contract,
     ) -> Result<R, anyhow::Error>
     where
@@ -255,7 +356,17 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        let response = self
+            .inner
+            .contract_calls
+            .read()
+            .await
+            .get(&(token_address, func.to_string()))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::format_err!("Call of {} at {:?} reverted", func, token_address)
+            })?;
+        Ok(R::from_tokens(response)?)
     }
 
     pub fn create_contract(
@@ -269,4 +380,8 @@ impl MockEthereum {
     pub async fn get_tx(&self, _hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
         unreachable!()
     }
+
+    pub async fn get_block_hash(&self, _block_number: u64) -> Result<Option<H256>, anyhow::Error> {
+        unreachable!()
+    }
 }
@@ -1,6 +1,7 @@
 use ethabi::Contract;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use web3::{
     contract::tokens::{Detokenize, Tokenize},
     contract::Options,
@@ -10,15 +11,42 @@ use web3::{
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::clients::health::{GatewayHealth, GatewayHealthReport, HealthPolicy};
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, GasEstimation, SignedCallResult};
 use crate::ETHDirectClient;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct MultiplexerEthereumClientInner {
     clients: Vec<(String, ETHDirectClient<PrivateKeySigner>)>,
+    /// Health statistics of the clients, in the same order as `clients`.
+    health: Vec<Mutex<GatewayHealth>>,
     preferred: AtomicUsize,
+    /// How many clients must return the same response for the quorum reads.
+    quorum: usize,
+    health_policy: HealthPolicy,
 }
 
+impl Default for MultiplexerEthereumClientInner {
+    fn default() -> Self {
+        Self {
+            clients: Vec::new(),
+            health: Vec::new(),
+            preferred: AtomicUsize::new(0),
+            quorum: 1,
+            health_policy: HealthPolicy::default(),
+        }
+    }
+}
+
+/// Ethereum client which sends the requests to the multiple gateways.
+///
+/// The requests are sent to the preferred gateway first, and fall through to the next gateway
+/// on error. Security-critical reads (logs, transaction statuses and main contract calls) may
+/// require a quorum of the agreeing responses instead.
+///
+/// Every gateway has a health score based on the latency, the error rate and the disagreements
+/// with the other gateways. Gateways with a low score are temporarily evicted, unless all of
+/// them are evicted.
 #[derive(Debug, Default, Clone)]
 pub struct MultiplexerEthereumClient {
     inner: Arc<MultiplexerEthereumClientInner>,
@@ -26,8 +54,11 @@ pub struct MultiplexerEthereumClient {
 
 macro_rules! multiple_call {
     ($self:expr, $func:ident($($attr:expr),*)) => {
-        for (name, client) in $self.clients() {
-            match client.$func($($attr.clone()),*).await {
+        for (idx, name, client) in $self.available_clients() {
+            let start = Instant::now();
+            let result = client.$func($($attr.clone()),*).await;
+            $self.report_request(idx, start.elapsed(), result.is_ok());
+            match result {
                 Ok(res) => return Ok(res),
                 Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
             }
@@ -36,6 +67,52 @@ macro_rules! multiple_call {
     };
 }
 
+/// Requests the clients one by one until the quorum of them return the same response.
+/// Clients which returned a different response are reported as disagreeing.
+macro_rules! quorum_call {
+    ($self:expr, $func:ident($($attr:expr),*)) => {
+        let quorum = $self.inner.quorum;
+        if quorum <= 1 {
+            multiple_call!($self, $func($($attr),*));
+        }
+
+        let mut responses: Vec<(_, Vec<usize>)> = Vec::new();
+        for (idx, name, client) in $self.available_clients() {
+            let start = Instant::now();
+            let result = client.$func($($attr.clone()),*).await;
+            $self.report_request(idx, start.elapsed(), result.is_ok());
+            let res = match result {
+                Ok(res) => res,
+                Err(err) => {
+                    vlog::error!("Error in interface: {}, {} ", name, err);
+                    continue;
+                }
+            };
+
+            let position = match responses.iter().position(|(response, _)| *response == res) {
+                Some(position) => position,
+                None => {
+                    responses.push((res, Vec::new()));
+                    responses.len() - 1
+                }
+            };
+            responses[position].1.push(idx);
+
+            if responses[position].1.len() >= quorum {
+                let (res, agreed) = responses.swap_remove(position);
+                for idx in agreed {
+                    $self.report_agreement(idx, true);
+                }
+                for idx in responses.into_iter().flat_map(|(_, disagreed)| disagreed) {
+                    $self.report_agreement(idx, false);
+                }
+                return Ok(res);
+            }
+        }
+        anyhow::bail!("Quorum of {} agreeing interfaces is not reached", quorum)
+    };
+}
+
 impl MultiplexerEthereumClient {
     pub fn new() -> Self {
         Self::default()
@@ -46,10 +123,33 @@ impl MultiplexerEthereumClient {
         name: String,
         client: ETHDirectClient<PrivateKeySigner>,
     ) -> &mut Self {
-        Arc::get_mut(&mut self.inner)
-            .unwrap()
-            .clients
-            .push((name, client));
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        inner.clients.push((name, client));
+        inner.health.push(Default::default());
+        self
+    }
+
+    /// Sets how many clients must return the same response for the security-critical reads.
+    /// `1` disables the quorum mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quorum is zero or exceeds the number of the added clients,
+    /// since such a quorum can never be reached.
+    pub fn with_quorum(&mut self, quorum: usize) -> &mut Self {
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        assert!(
+            quorum >= 1 && quorum <= inner.clients.len(),
+            "Read quorum must be between 1 and the number of clients ({}), got {}",
+            inner.clients.len(),
+            quorum
+        );
+        inner.quorum = quorum;
+        self
+    }
+
+    pub fn with_health_policy(&mut self, health_policy: HealthPolicy) -> &mut Self {
+        Arc::get_mut(&mut self.inner).unwrap().health_policy = health_policy;
         self
     }
 
@@ -61,21 +161,102 @@ impl MultiplexerEthereumClient {
         }
     }
 
+    /// Returns the clients which are not evicted, starting from the preferred one.
     pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<PrivateKeySigner>)> {
+        self.available_clients()
+            .map(|(_, name, client)| (name, client))
+    }
+
+    fn available_clients(
+        &self,
+    ) -> impl Iterator<Item = (usize, &str, &ETHDirectClient<PrivateKeySigner>)> {
+        let clients_count = self.inner.clients.len();
         let preferred = self.inner.preferred.load(Ordering::Relaxed);
+        let ordered = std::iter::once(preferred)
+            .chain(0..preferred)
+            .chain(preferred + 1..clients_count)
+            .filter(move |idx| *idx < clients_count);
+
+        let mut available: Vec<_> = ordered
+            .clone()
+            .filter(|idx| self.inner.health[*idx].lock().unwrap().is_available())
+            .collect();
+        // Evicted clients are still used if there are no other options.
+        if available.is_empty() {
+            available = ordered.collect();
+        }
+
+        available.into_iter().map(move |idx| {
+            let (name, client) = &self.inner.clients[idx];
+            (idx, name.as_str(), client)
+        })
+    }
+
+    fn client_index(&self, name: &str) -> Option<usize> {
+        self.inner.clients.iter().position(|(key, _)| key == name)
+    }
+
+    fn report_request(&self, idx: usize, latency: Duration, success: bool) {
+        let policy = &self.inner.health_policy;
+        let mut health = self.inner.health[idx].lock().unwrap();
+        let evicted = health.report_request(latency, success, policy);
+        self.update_health_metrics(idx, &health, evicted);
+    }
+
+    fn report_agreement(&self, idx: usize, agreed: bool) {
+        let policy = &self.inner.health_policy;
+        let mut health = self.inner.health[idx].lock().unwrap();
+        let evicted = health.report_agreement(agreed, policy);
+        self.update_health_metrics(idx, &health, evicted);
+    }
+
+    fn update_health_metrics(&self, idx: usize, health: &GatewayHealth, evicted: bool) {
+        let name = &self.inner.clients[idx].0;
+        if evicted {
+            vlog::warn!(
+                "Ethereum Gateway `{}` is evicted for {}s: {:?}",
+                name,
+                self.inner.health_policy.eviction_period.as_secs(),
+                health.report(name, &self.inner.health_policy)
+            );
+            #[cfg(feature = "with-metrics")]
+            metrics::increment_counter!("eth_client.multiplexed.evicted", "address" => name.clone());
+        }
+        #[cfg(feature = "with-metrics")]
+        metrics::gauge!(
+            "eth_client.multiplexed.health_score",
+            health.score(&self.inner.health_policy),
+            "address" => name.clone()
+        );
+    }
+
+    /// Reports the finished request to the client, made outside of the multiplexer (e.g. by the gateway watcher).
+    pub fn report_client_request(&self, name: &str, latency: Duration, success: bool) {
+        if let Some(idx) = self.client_index(name) {
+            self.report_request(idx, latency, success);
+        }
+    }
+
+    /// Reports whether the response of the client agrees with the other clients.
+    pub fn report_client_agreement(&self, name: &str, agreed: bool) {
+        if let Some(idx) = self.client_index(name) {
+            self.report_agreement(idx, agreed);
+        }
+    }
+
+    /// Returns the health state of every client.
+    pub fn health_reports(&self) -> Vec<GatewayHealthReport> {
         self.inner
             .clients
-            .get(preferred)
-            .into_iter()
-            .chain(self.inner.clients.get(..preferred).unwrap_or(&[]).iter())
-            .chain(
-                self.inner
-                    .clients
-                    .get(1 + preferred..)
-                    .unwrap_or(&[])
-                    .iter(),
-            )
-            .map(|(name, client)| (name.as_str(), client))
+            .iter()
+            .zip(&self.inner.health)
+            .map(|((name, _), health)| {
+                health
+                    .lock()
+                    .unwrap()
+                    .report(name, &self.inner.health_policy)
+            })
+            .collect()
     }
 
     pub fn create_contract(
@@ -106,6 +287,14 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, get_gas_price());
     }
 
+    pub async fn get_base_fee(&self) -> Result<U256, anyhow::Error> {
+        multiple_call!(self, get_base_fee());
+    }
+
+    pub async fn get_priority_fee(&self) -> Result<U256, anyhow::Error> {
+        multiple_call!(self, get_priority_fee());
+    }
+
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         multiple_call!(self, sender_eth_balance());
     }
@@ -148,6 +337,10 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, failure_reason(tx_hash));
     }
 
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<GasEstimation, anyhow::Error> {
+        multiple_call!(self, estimate_gas(data));
+    }
+
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         multiple_call!(self, eth_balance(address));
     }
@@ -192,12 +385,12 @@ impl MultiplexerEthereumClient {
         block: B,
     ) -> Result<R, anyhow::Error>
     where
-        R: Detokenize + Unpin,
+        R: Detokenize + Unpin + PartialEq,
         A: Into<Option<Address>> + Clone,
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
     {
-        multiple_call!(
+        quorum_call!(
             self,
             call_main_contract_function(func, params, from, options, block)
         );
@@ -208,11 +401,11 @@ impl MultiplexerEthereumClient {
         hash: H256,
         current_block: Option<u64>,
     ) -> Result<Option<ExecutedTxStatus>, anyhow::Error> {
-        multiple_call!(self, get_tx_status(hash, current_block));
+        quorum_call!(self, get_tx_status(hash, current_block));
     }
 
     pub async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
-        multiple_call!(self, logs(filter));
+        quorum_call!(self, logs(filter));
     }
 
     pub fn encode_tx_data<P: Tokenize + Clone>(&self, func: &str, params: P) -> Vec<u8> {
@@ -226,4 +419,114 @@ impl MultiplexerEthereumClient {
     pub async fn get_tx(&self, hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
         multiple_call!(self, get_tx(hash));
     }
+
+    pub async fn get_block_hash(&self, block_number: u64) -> Result<Option<H256>, anyhow::Error> {
+        quorum_call!(self, get_block_hash(block_number));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Client returning the predefined response, `None` stands for the failed request.
+    struct TestClient(Option<u64>);
+
+    impl TestClient {
+        async fn value(&self) -> anyhow::Result<u64> {
+            self.0.ok_or_else(|| anyhow::anyhow!("Request failed"))
+        }
+    }
+
+    struct TestClientInner {
+        quorum: usize,
+    }
+
+    /// Substitute of `MultiplexerEthereumClient` recording the reported statistics.
+    struct TestMultiplexer {
+        inner: TestClientInner,
+        clients: Vec<TestClient>,
+        requests: Mutex<Vec<(usize, bool)>>,
+        agreements: Mutex<Vec<(usize, bool)>>,
+    }
+
+    impl TestMultiplexer {
+        fn new(quorum: usize, responses: Vec<Option<u64>>) -> Self {
+            Self {
+                inner: TestClientInner { quorum },
+                clients: responses.into_iter().map(TestClient).collect(),
+                requests: Default::default(),
+                agreements: Default::default(),
+            }
+        }
+
+        fn available_clients(&self) -> impl Iterator<Item = (usize, &str, &TestClient)> {
+            self.clients
+                .iter()
+                .enumerate()
+                .map(|(idx, client)| (idx, "test", client))
+        }
+
+        fn report_request(&self, idx: usize, _latency: Duration, success: bool) {
+            self.requests.lock().unwrap().push((idx, success));
+        }
+
+        fn report_agreement(&self, idx: usize, agreed: bool) {
+            self.agreements.lock().unwrap().push((idx, agreed));
+        }
+
+        async fn value(&self) -> anyhow::Result<u64> {
+            quorum_call!(self, value());
+        }
+
+        fn requests(&self) -> Vec<(usize, bool)> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        fn agreements(&self) -> Vec<(usize, bool)> {
+            self.agreements.lock().unwrap().clone()
+        }
+    }
+
+    /// Checks that the response is returned once the quorum of clients agree,
+    /// and the clients with the other responses are reported as disagreeing.
+    #[tokio::test]
+    async fn quorum_reached() {
+        let client = TestMultiplexer::new(2, vec![Some(1), Some(2), Some(1), Some(1)]);
+        assert_eq!(client.value().await.unwrap(), 1);
+        // The last client is not requested.
+        assert_eq!(client.requests(), vec![(0, true), (1, true), (2, true)]);
+        assert_eq!(client.agreements(), vec![(0, true), (2, true), (1, false)]);
+
+        // Failed requests are skipped.
+        let client = TestMultiplexer::new(2, vec![None, Some(1), Some(1)]);
+        assert_eq!(client.value().await.unwrap(), 1);
+        assert_eq!(client.requests(), vec![(0, false), (1, true), (2, true)]);
+        assert_eq!(client.agreements(), vec![(1, true), (2, true)]);
+    }
+
+    /// Checks that an error is returned if the quorum is not reached,
+    /// and no client is blamed for the disagreement.
+    #[tokio::test]
+    async fn quorum_not_reached() {
+        let client = TestMultiplexer::new(2, vec![Some(1), None, Some(2)]);
+        assert!(client.value().await.is_err());
+        assert_eq!(client.requests().len(), 3);
+        assert!(client.agreements().is_empty());
+    }
+
+    /// Checks that without the quorum the first successful response is returned.
+    #[tokio::test]
+    async fn quorum_disabled() {
+        let client = TestMultiplexer::new(1, vec![None, Some(1), Some(2)]);
+        assert_eq!(client.value().await.unwrap(), 1);
+        assert_eq!(client.requests(), vec![(0, false), (1, true)]);
+        assert!(client.agreements().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Read quorum must be between 1 and the number of clients")]
+    fn quorum_exceeding_clients() {
+        MultiplexerEthereumClient::new().with_quorum(2);
+    }
 }
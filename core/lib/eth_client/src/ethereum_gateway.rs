@@ -9,6 +9,7 @@ use zksync_contracts::zksync_contract;
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
+use crate::clients::health::{GatewayHealthReport, HealthPolicy};
 use crate::clients::mock::MockEthereum;
 use crate::clients::multiplexer::MultiplexerEthereumClient;
 use crate::ETHDirectClient;
@@ -16,13 +17,16 @@ use crate::ETHDirectClient;
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignedCallResult {
     pub raw_tx: Vec<u8>,
+    /// Gas price of the legacy transaction or max fee per gas of the EIP-1559 transaction.
     pub gas_price: U256,
+    /// Max priority fee per gas, set only for the EIP-1559 transaction.
+    pub max_priority_fee_per_gas: Option<U256>,
     pub nonce: U256,
     pub hash: H256,
 }
 
 /// State of the executed Ethereum transaction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ExecutedTxStatus {
     /// Amount of confirmations for a block containing the transaction.
     pub confirmations: u64,
@@ -31,6 +35,10 @@ pub struct ExecutedTxStatus {
     /// Receipt for a transaction. Will be set to `Some` only if the transaction
     /// failed during execution.
     pub receipt: Option<TransactionReceipt>,
+    /// Amount of gas used by the transaction, if reported by the node.
+    pub gas_used: Option<U256>,
+    /// Price paid per unit of gas used by the transaction, if reported by the node.
+    pub effective_gas_price: Option<U256>,
 }
 /// Information about transaction failure.
 #[derive(Debug, Clone)]
@@ -41,6 +49,15 @@ pub struct FailureInfo {
     pub gas_limit: U256,
 }
 
+/// Result of the simulation of the transaction against the pending state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GasEstimation {
+    /// Transaction succeeds and requires the given amount of gas.
+    Success(U256),
+    /// Transaction is reverted with the given reason.
+    Reverted(String),
+}
+
 #[derive(Debug, Clone)]
 pub enum EthereumGateway {
     Direct(ETHDirectClient<PrivateKeySigner>),
@@ -53,21 +70,45 @@ impl EthereumGateway {
         eth_client_config: &ETHClientConfig,
         eth_sender_config: &ETHSenderConfig,
         main_contract: Address,
+    ) -> Self {
+        Self::with_operator_account(
+            eth_client_config,
+            eth_sender_config.sender.operator_commit_eth_addr,
+            eth_sender_config.sender.operator_private_key,
+            main_contract,
+        )
+    }
+
+    /// Creates the gateway sending transactions from the given operator account.
+    pub fn with_operator_account(
+        eth_client_config: &ETHClientConfig,
+        operator_eth_addr: Address,
+        operator_private_key: H256,
+        main_contract: Address,
     ) -> Self {
         if eth_client_config.web3_url.len() == 1 {
+            assert_eq!(
+                eth_client_config.read_quorum, 1,
+                "Read quorum can't be reached with a single Ethereum gateway"
+            );
             let transport = web3::transports::Http::new(&eth_client_config.web3_url()).unwrap();
 
             EthereumGateway::Direct(ETHDirectClient::new(
                 transport,
                 zksync_contract(),
-                eth_sender_config.sender.operator_commit_eth_addr,
-                PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                operator_eth_addr,
+                PrivateKeySigner::new(operator_private_key),
                 main_contract,
                 eth_client_config.chain_id,
                 eth_client_config.gas_price_factor,
             ))
         } else {
             let mut client = MultiplexerEthereumClient::new();
+            client.with_health_policy(HealthPolicy {
+                min_score: eth_client_config.gateway_min_health_score,
+                eviction_period: eth_client_config.gateway_eviction_period(),
+                slow_request_threshold: eth_client_config.gateway_slow_request_threshold(),
+            });
 
             let contract = zksync_contract();
             for web3_url in eth_client_config.web3_url.iter().cloned() {
@@ -77,14 +118,16 @@ impl EthereumGateway {
                     ETHDirectClient::new(
                         transport,
                         contract.clone(),
-                        eth_sender_config.sender.operator_commit_eth_addr,
-                        PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                        operator_eth_addr,
+                        PrivateKeySigner::new(operator_private_key),
                         main_contract,
                         eth_client_config.chain_id,
                         eth_client_config.gas_price_factor,
                     ),
                 );
             }
+            // The quorum is validated against the number of the added clients.
+            client.with_quorum(eth_client_config.read_quorum);
             EthereumGateway::Multiplexed(client)
         }
     }
@@ -126,6 +169,17 @@ impl EthereumGateway {
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.get_gas_price())
     }
+
+    /// Returns the base fee per gas of the latest block.
+    pub async fn get_base_fee(&self) -> Result<U256, anyhow::Error> {
+        delegate_call!(self.get_base_fee())
+    }
+
+    /// Returns the priority fee per gas suggested by the network.
+    pub async fn get_priority_fee(&self) -> Result<U256, anyhow::Error> {
+        delegate_call!(self.get_priority_fee())
+    }
+
     /// Returns the account balance.
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.sender_eth_balance())
@@ -133,6 +187,7 @@ impl EthereumGateway {
 
     /// Signs the transaction given the previously encoded data.
     /// Fills in gas/nonce if not supplied inside options.
+    /// EIP-1559 transaction is created if the max fee per gas is set inside options.
     pub async fn sign_prepared_tx(
         &self,
         data: Vec<u8>,
@@ -173,6 +228,12 @@ impl EthereumGateway {
         delegate_call!(self.failure_reason(tx_hash))
     }
 
+    /// Estimates the gas required by the main contract call with the encoded data
+    /// against the pending state. Reports the revert reason if the call fails.
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<GasEstimation, anyhow::Error> {
+        delegate_call!(self.estimate_gas(data))
+    }
+
     /// Auxiliary function that returns the balance of the account on Ethereum.
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
         delegate_call!(self.eth_balance(address))
@@ -205,7 +266,7 @@ impl EthereumGateway {
         block: B,
     ) -> Result<R, anyhow::Error>
     where
-        R: Detokenize + Unpin,
+        R: Detokenize + Unpin + PartialEq,
         A: Into<Option<Address>> + Clone,
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
@@ -265,10 +326,24 @@ impl EthereumGateway {
         delegate_call!(self.get_tx(hash))
     }
 
+    /// Returns the hash of the block with the given number, if the block exists.
+    pub async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        delegate_call!(self.get_block_hash(block_number))
+    }
+
     pub fn is_multiplexed(&self) -> bool {
         matches!(self, EthereumGateway::Multiplexed(_))
     }
 
+    /// Returns the health state of the gateways of the multiplexed client.
+    /// Other clients don't track the health, so the list is empty for them.
+    pub fn gateways_health(&self) -> Vec<GatewayHealthReport> {
+        match self {
+            EthereumGateway::Multiplexed(client) => client.health_reports(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn get_mut_mock(&mut self) -> Option<&mut MockEthereum> {
         match self {
             EthereumGateway::Mock(ref mut m) => Some(m),
@@ -14,9 +14,9 @@ use web3::{
     types::{AccessList, Address, SignedTransaction, U256, U64},
 };
 
-const LEGACY_TX_ID: u64 = 0;
-const ACCESSLISTS_TX_ID: u64 = 1;
-const EIP1559_TX_ID: u64 = 2;
+pub const LEGACY_TX_ID: u64 = 0;
+pub const ACCESSLISTS_TX_ID: u64 = 1;
+pub const EIP1559_TX_ID: u64 = 2;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct RawTransaction {
@@ -116,7 +116,8 @@ impl MultiplexedGatewayWatcher {
     }
 
     /// Checks multiplexed client gateways and prioritizes one with longest chain,
-    /// most frequent hash and lowest latency.
+    /// most frequent hash and lowest latency. Results of the check are reported to the
+    /// health statistics of the gateways.
     async fn check_client_gateways(&self) {
         // Fetch latest block for each client.
         // Each request will resolve to (client key, client latest block) pair.
@@ -140,9 +141,11 @@ impl MultiplexedGatewayWatcher {
                     if let Ok(block) = block_fut.await {
                         let req_time = start.elapsed();
                         metrics::histogram!("eth_client.multiplexed.block", req_time, &[("address", key.to_owned())]);
+                        self.client.report_client_request(key, req_time, true);
 
                         Some((key, block, req_time))
                     } else {
+                        self.client.report_client_request(key, start.elapsed(), false);
                         vlog::error!(
                             "Failed to get latest block from Ethereum Gateway `{}` within specified timeout",
                             key
@@ -193,8 +196,15 @@ impl MultiplexedGatewayWatcher {
                 vlog::info!("Prioritized Ethereum Gateway: `{}`", preferred_client_key);
             }
             for (key, block, _) in &client_latest_blocks {
-                if let Err(err) = Self::verify_blocks(latest_block, block) {
-                    vlog::error!("Ethereum Gateway `{}` - check failed: {}", key, err);
+                match Self::verify_blocks(latest_block, block) {
+                    Ok(()) => self.client.report_client_agreement(key, true),
+                    Err(err) => {
+                        vlog::error!("Ethereum Gateway `{}` - check failed: {}", key, err);
+                        // Lagging gateways are not considered disagreeing, only the different hashes are.
+                        if let BlockVerificationError::IncorrectHash(..) = err {
+                            self.client.report_client_agreement(key, false);
+                        }
+                    }
                 }
             }
         }
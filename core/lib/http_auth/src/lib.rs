@@ -0,0 +1,88 @@
+//! Bearer token authentication shared by the internal HTTP servers.
+//!
+//! Clients authorize with a JsonWebToken signed with the secret shared with the server.
+
+// External uses
+use actix_web::dev::ServiceRequest;
+use actix_web_httpauth::extractors::{
+    bearer::{BearerAuth, Config},
+    AuthenticationError,
+};
+use jsonwebtoken::errors::Error as JwtError;
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    pub sub: String,
+    /// Expiration time (as UTC timestamp).
+    pub exp: usize,
+}
+
+/// The structure that stores the secret key for checking JsonWebToken matching.
+pub struct AuthTokenValidator<'a> {
+    decoding_key: DecodingKey<'a>,
+}
+
+impl<'a> AuthTokenValidator<'a> {
+    pub fn new(secret: &'a str) -> Self {
+        Self {
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+        }
+    }
+
+    /// Checks whether the secret key and the authorization token match.
+    pub fn validate_auth_token(&self, token: &str) -> Result<(), JwtError> {
+        decode::<PayloadAuthToken>(token, &self.decoding_key, &Validation::default())?;
+
+        Ok(())
+    }
+
+    /// Validator for the `HttpAuthentication::bearer` middleware.
+    pub async fn validator(
+        &self,
+        req: ServiceRequest,
+        credentials: BearerAuth,
+    ) -> actix_web::Result<ServiceRequest> {
+        let config = req.app_data::<Config>().cloned().unwrap_or_default();
+
+        self.validate_auth_token(credentials.token())
+            .map_err(|_| AuthenticationError::from(config))?;
+
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn token(secret: &str, lifetime: Duration) -> String {
+        let exp = UNIX_EPOCH.elapsed().unwrap() + lifetime;
+        encode(
+            &Header::default(),
+            &PayloadAuthToken {
+                sub: "Authorization".to_string(),
+                exp: exp.as_secs() as usize,
+            },
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn validate_auth_token() {
+        let validator = AuthTokenValidator::new("secret");
+
+        assert!(validator
+            .validate_auth_token(&token("secret", Duration::from_secs(60)))
+            .is_ok());
+        assert!(validator
+            .validate_auth_token(&token("other secret", Duration::from_secs(60)))
+            .is_err());
+        assert!(validator.validate_auth_token("not a token").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 
+use zksync_config::configs::chain::MempoolOrdering;
 use zksync_types::{
     mempool::SignedTxVariant,
     tx::{error::TxAddError, TxHash},
@@ -47,6 +48,7 @@ pub(crate) struct MempoolBlocksHandler {
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolBlocksRequest>,
     pub max_block_size_chunks: usize,
+    pub ordering: MempoolOrdering,
 }
 
 impl MempoolBlocksHandler {
@@ -61,7 +63,7 @@ impl MempoolBlocksHandler {
         // will be empty unless the server is restarted after reverting blocks.
         let mut tx_queue = self
             .mempool_state
-            .get_transaction_queue(executed_txs)
+            .get_transaction_queue(executed_txs, self.ordering)
             .await?;
 
         let (txs, priority_ops, chunks_left) = tx_queue
@@ -72,6 +74,9 @@ impl MempoolBlocksHandler {
                 &self.mempool_state,
             )
             .await?;
+        // Replacement of the proposed transactions is not allowed anymore. The ones replaced
+        // while the block was being proposed are excluded from it.
+        let txs = self.mempool_state.mark_txs_proposed(txs).await?;
 
         if !priority_ops.is_empty() || !txs.is_empty() {
             vlog::debug!(
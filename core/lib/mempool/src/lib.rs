@@ -10,12 +10,17 @@
 //! Communication channel with other actors:
 //! Mempool does not push information to other actors, only accepts requests. (see `MempoolRequest`)
 
+// Built-in uses
+use std::sync::atomic::AtomicBool;
+
 // External uses
 use futures::channel::mpsc;
 
 use tokio::task::JoinHandle;
 
 // Workspace uses
+use zksync_config::{configs::chain::Mempool as MempoolConfig, NotifierConfig};
+use zksync_notifier::Notifier;
 use zksync_storage::ConnectionPool;
 
 // Local uses
@@ -38,6 +43,8 @@ pub fn run_mempool_tx_handler(
     db_pool: ConnectionPool,
     tx_requests: mpsc::Receiver<MempoolTransactionRequest>,
     block_chunk_sizes: Vec<usize>,
+    config: &MempoolConfig,
+    notifier_config: &NotifierConfig,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool.clone());
     let max_block_size_chunks = *block_chunk_sizes
@@ -49,6 +56,15 @@ pub fn run_mempool_tx_handler(
         mempool_state,
         requests: tx_requests,
         max_block_size_chunks,
+        replacement_fee_bump_percent: config.replacement_fee_bump_percent,
+        max_pending_txs_per_account: config.max_pending_txs_per_account,
+        max_nonce_gap: config.max_nonce_gap,
+        max_mempool_size: config.max_mempool_size,
+        notifier: Notifier::from_config(notifier_config),
+        mempool_size_notify_threshold: notifier_config
+            .thresholds
+            .mempool_size(config.max_mempool_size),
+        mempool_size_reported: AtomicBool::new(false),
     };
     tokio::spawn(handler.run())
 }
@@ -58,6 +74,7 @@ pub fn run_mempool_block_handler(
     db_pool: ConnectionPool,
     block_requests: mpsc::Receiver<MempoolBlocksRequest>,
     block_chunk_sizes: Vec<usize>,
+    config: &MempoolConfig,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool);
     let max_block_size_chunks = *block_chunk_sizes
@@ -69,6 +86,7 @@ pub fn run_mempool_block_handler(
         mempool_state,
         requests: block_requests,
         max_block_size_chunks,
+        ordering: config.ordering,
     };
 
     tokio::spawn(blocks_handler.run())
@@ -1,9 +1,12 @@
+use crate::state::{total_fee_usd, tx_fee};
 use crate::MempoolState;
+use num::{rational::Ratio, BigUint};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use zksync_config::configs::chain::MempoolOrdering;
 use zksync_types::mempool::SignedTxVariant;
 use zksync_types::tx::error::TxAddError;
-use zksync_types::PriorityOp;
+use zksync_types::{AccountId, Nonce, PriorityOp, TokenId};
 
 #[derive(Debug, Clone)]
 struct MempoolPendingTransaction {
@@ -35,6 +38,115 @@ impl PartialOrd for MempoolPendingTransaction {
     }
 }
 
+/// Ready transaction with its fee per chunk, used to pick the most profitable
+/// transaction among the ones which can be executed next.
+#[derive(Debug, PartialEq, Eq)]
+struct ReadyTxPriority {
+    fee_per_chunk: Ratio<BigUint>,
+    /// Position of the transaction in the nonce-ordered queue.
+    index: usize,
+}
+
+impl Ord for ReadyTxPriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Transactions with the higher fee go first, ties are resolved in favor of the earlier transaction.
+        self.fee_per_chunk
+            .cmp(&other.fee_per_chunk)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for ReadyTxPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reorders transactions so that the ones paying more per chunk go first,
+/// while every account still has its transactions executed in the nonce order.
+///
+/// Transactions (and batches) are expected to be sorted by nonce.
+fn order_by_fee_priority(
+    txs: Vec<SignedTxVariant>,
+    fees_per_chunk: Vec<Ratio<BigUint>>,
+) -> VecDeque<SignedTxVariant> {
+    // Accounts whose nonces are used by every element of the queue.
+    let mut element_accounts = Vec::with_capacity(txs.len());
+    // Elements touching each account, in the order of their nonces.
+    let mut account_queues: HashMap<AccountId, VecDeque<usize>> = HashMap::new();
+    {
+        let mut account_nonces: HashMap<AccountId, Vec<(Nonce, usize)>> = HashMap::new();
+        for (index, element) in txs.iter().enumerate() {
+            let mut nonces: HashMap<AccountId, Nonce> = HashMap::new();
+            for tx in element.get_transactions() {
+                if let Ok(account_id) = tx.account_id() {
+                    let nonce = nonces.entry(account_id).or_insert_with(|| tx.nonce());
+                    *nonce = std::cmp::min(*nonce, tx.nonce());
+                }
+            }
+            element_accounts.push(nonces.keys().copied().collect::<Vec<_>>());
+            for (account_id, nonce) in nonces {
+                account_nonces
+                    .entry(account_id)
+                    .or_default()
+                    .push((nonce, index));
+            }
+        }
+        for (account_id, mut nonces) in account_nonces {
+            nonces.sort_unstable();
+            account_queues.insert(
+                account_id,
+                nonces.into_iter().map(|(_, index)| index).collect(),
+            );
+        }
+    }
+
+    // Element can be selected once it's the first one in the queues of all its accounts.
+    let mut blocked_by: Vec<usize> = element_accounts.iter().map(Vec::len).collect();
+    for queue in account_queues.values() {
+        if let Some(&head) = queue.front() {
+            blocked_by[head] -= 1;
+        }
+    }
+
+    let mut candidates = BinaryHeap::new();
+    for (index, blocked_by) in blocked_by.iter().enumerate() {
+        if *blocked_by == 0 {
+            candidates.push(ReadyTxPriority {
+                fee_per_chunk: fees_per_chunk[index].clone(),
+                index,
+            });
+        }
+    }
+
+    let mut txs: Vec<Option<SignedTxVariant>> = txs.into_iter().map(Some).collect();
+    let mut result = VecDeque::with_capacity(txs.len());
+    while let Some(ReadyTxPriority { index, .. }) = candidates.pop() {
+        result.push_back(txs[index].take().expect("transaction was already selected"));
+
+        for account_id in &element_accounts[index] {
+            let queue = account_queues
+                .get_mut(account_id)
+                .expect("account queue must exist");
+            queue.pop_front();
+            if let Some(&next) = queue.front() {
+                blocked_by[next] -= 1;
+                if blocked_by[next] == 0 {
+                    candidates.push(ReadyTxPriority {
+                        fee_per_chunk: fees_per_chunk[next].clone(),
+                        index: next,
+                    });
+                }
+            }
+        }
+    }
+
+    // Batches may depend on each other through several accounts so that none of them
+    // can go first. Such elements are left in their original order.
+    result.extend(txs.into_iter().flatten());
+    result
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct MempoolTransactionsQueue {
     /// Transactions ready for execution.
@@ -43,17 +155,21 @@ pub(crate) struct MempoolTransactionsQueue {
     pending_l2_transactions: BinaryHeap<MempoolPendingTransaction>,
 
     l1_transactions: VecDeque<PriorityOp>,
+    /// Order in which ready transactions are selected.
+    ordering: MempoolOrdering,
 }
 
 impl MempoolTransactionsQueue {
     pub(crate) fn new(
         l1_transactions: VecDeque<PriorityOp>,
         l2_transactions: VecDeque<SignedTxVariant>,
+        ordering: MempoolOrdering,
     ) -> Self {
         let mut res = Self {
             ready_l2_transactions: Default::default(),
             pending_l2_transactions: Default::default(),
             l1_transactions,
+            ordering,
         };
         // Due to complexity of json structure in database for transactions it's easier and safer
         // to add even not ready txs to mempool and prepare them before when it's needed.
@@ -125,6 +241,39 @@ impl MempoolTransactionsQueue {
             .append(&mut ready_pending_l2_operations);
     }
 
+    /// Reorders ready transactions by their fee per chunk normalized to USD.
+    /// Does nothing unless the fee priority ordering is enabled.
+    async fn prioritize_ready_l2_transactions(
+        &mut self,
+        mempool_state: &MempoolState,
+    ) -> Result<(), TxAddError> {
+        if self.ordering != MempoolOrdering::FeePriority || self.ready_l2_transactions.len() < 2 {
+            return Ok(());
+        }
+
+        let txs: Vec<SignedTxVariant> = self.ready_l2_transactions.drain(..).collect();
+        let fee_tokens: HashSet<TokenId> = txs
+            .iter()
+            .flat_map(|element| element.get_transactions())
+            .filter_map(|tx| tx_fee(&tx))
+            .map(|(token_id, _)| token_id)
+            .collect();
+        let unit_prices = mempool_state.load_token_unit_prices(&fee_tokens).await?;
+
+        let chunks = mempool_state.required_chunks_for_all(&txs).await?;
+        let fees_per_chunk = txs
+            .iter()
+            .zip(chunks)
+            .map(|(element, chunks)| {
+                let fee_usd = total_fee_usd(&element.get_transactions(), &unit_prices);
+                fee_usd / BigUint::from(chunks.max(1))
+            })
+            .collect();
+
+        self.ready_l2_transactions = order_by_fee_priority(txs, fees_per_chunk);
+        Ok(())
+    }
+
     /// Collect txs depending on desired chunks and execution time
     pub(crate) async fn select_transactions(
         &mut self,
@@ -182,6 +331,7 @@ impl MempoolTransactionsQueue {
         mempool_state: &MempoolState,
     ) -> Result<(usize, Vec<SignedTxVariant>), TxAddError> {
         self.prepare_new_ready_l2_transactions(block_timestamp);
+        self.prioritize_ready_l2_transactions(mempool_state).await?;
 
         let mut txs_for_commit = Vec::new();
 
@@ -230,6 +380,26 @@ mod tests {
         })
     }
 
+    fn get_transfer(account_id: u32, nonce: u32) -> SignedTxVariant {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            500u32.into(),
+            20u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+
+        SignedTxVariant::Tx(SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        })
+    }
+
     fn get_withdraw() -> SignedTxVariant {
         let withdraw = Withdraw::new(
             AccountId(3),
@@ -256,6 +426,7 @@ mod tests {
             ready_l2_transactions: VecDeque::new(),
             pending_l2_transactions: BinaryHeap::new(),
             l1_transactions: Default::default(),
+            ordering: MempoolOrdering::Fifo,
         };
 
         transactions_queue.add_l1_transactions(vec![
@@ -360,6 +531,7 @@ mod tests {
             ready_l2_transactions: VecDeque::new(),
             pending_l2_transactions: BinaryHeap::new(),
             l1_transactions: Default::default(),
+            ordering: MempoolOrdering::Fifo,
         };
 
         let withdraw0 = get_withdraw();
@@ -416,4 +588,48 @@ mod tests {
             );
         }
     }
+
+    /// Checks that transactions with the higher fee go first, but the nonce order is preserved for each account.
+    #[test]
+    fn test_order_by_fee_priority() {
+        let first_account_tx0 = get_transfer(1, 0);
+        let first_account_tx1 = get_transfer(1, 1);
+        let second_account_tx0 = get_transfer(2, 0);
+        let txs = vec![
+            first_account_tx0.clone(),
+            second_account_tx0.clone(),
+            first_account_tx1.clone(),
+        ];
+        let fee = |value: u32| Ratio::from_integer(BigUint::from(value));
+
+        // The most profitable transaction cannot go before the previous transaction of the same account.
+        let ordered = order_by_fee_priority(txs.clone(), vec![fee(1), fee(5), fee(10)]);
+        let hashes: Vec<_> = ordered.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(
+            hashes,
+            vec![
+                second_account_tx0.hashes(),
+                first_account_tx0.hashes(),
+                first_account_tx1.hashes(),
+            ]
+        );
+
+        // Once the previous transaction is selected, the next one competes with the rest.
+        let ordered = order_by_fee_priority(txs.clone(), vec![fee(7), fee(5), fee(10)]);
+        let hashes: Vec<_> = ordered.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(
+            hashes,
+            vec![
+                first_account_tx0.hashes(),
+                first_account_tx1.hashes(),
+                second_account_tx0.hashes(),
+            ]
+        );
+
+        // Equal fees keep the original order.
+        let ordered = order_by_fee_priority(txs.clone(), vec![fee(1), fee(1), fee(1)]);
+        let hashes: Vec<_> = ordered.iter().map(SignedTxVariant::hashes).collect();
+        let expected: Vec<_> = txs.iter().map(SignedTxVariant::hashes).collect();
+        assert_eq!(hashes, expected);
+    }
 }
@@ -1,18 +1,60 @@
-use std::collections::HashSet;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use num::{rational::Ratio, BigUint, Zero};
+use tokio::sync::Mutex;
+
+use zksync_config::configs::chain::MempoolOrdering;
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
     tx::{error::TxAddError, TxHash},
-    Address, TransferOp, TransferToNewOp, ZkSyncTx,
+    Address, SignedZkSyncTx, TokenId, TokenLike, TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
 use crate::MempoolTransactionsQueue;
 
+/// Token prices used to compare the fees are reloaded after this interval.
+const TOKEN_PRICES_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Returns the token and the amount of the fee paid by the transaction.
+pub(crate) fn tx_fee(tx: &SignedZkSyncTx) -> Option<(TokenId, BigUint)> {
+    match tx.get_fee_info()? {
+        (_, TokenLike::Id(token_id), _, fee) => Some((token_id, fee)),
+        _ => None,
+    }
+}
+
+/// Returns the total fee in USD paid by the transactions.
+/// Fees paid in tokens with unknown price are not taken into account.
+pub(crate) fn total_fee_usd(
+    txs: &[SignedZkSyncTx],
+    unit_prices: &HashMap<TokenId, Ratio<BigUint>>,
+) -> Ratio<BigUint> {
+    txs.iter()
+        .filter_map(tx_fee)
+        .filter_map(|(token_id, fee)| {
+            unit_prices
+                .get(&token_id)
+                .map(|price| price * Ratio::from_integer(fee))
+        })
+        .fold(Ratio::zero(), |total, fee| total + fee)
+}
+
+/// USD prices of the smallest token units loaded from the database.
+#[derive(Debug)]
+struct TokenPricesCache {
+    loaded_at: Instant,
+    /// Tokens the prices were requested for, including the ones without a known price.
+    requested: HashSet<TokenId>,
+    prices: HashMap<TokenId, Ratio<BigUint>>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct MempoolState {
     db_pool: ConnectionPool,
+    token_prices: Arc<Mutex<Option<TokenPricesCache>>>,
 }
 
 impl MempoolState {
@@ -98,6 +140,115 @@ impl MempoolState {
         }
     }
 
+    /// Returns the number of chunks required by each of the elements.
+    /// Recipients of all the transfers are checked with a single query.
+    pub async fn required_chunks_for_all(
+        &self,
+        elements: &[SignedTxVariant],
+    ) -> Result<Vec<usize>, TxAddError> {
+        let start = Instant::now();
+        let recipients: Vec<Address> = elements
+            .iter()
+            .flat_map(SignedTxVariant::get_transactions)
+            .filter_map(|tx| match &tx.tx {
+                ZkSyncTx::Transfer(transfer) => Some(transfer.to),
+                _ => None,
+            })
+            .collect();
+        let existing_accounts = if recipients.is_empty() {
+            HashSet::new()
+        } else {
+            self.db_pool
+                .access_storage()
+                .await
+                .map_err(|_| TxAddError::DbError)?
+                .chain()
+                .account_schema()
+                .filter_existing_accounts(&recipients)
+                .await
+                .map_err(|_| TxAddError::DbError)?
+        };
+
+        let chunks = elements
+            .iter()
+            .map(|element| {
+                element
+                    .get_transactions()
+                    .iter()
+                    .map(|tx| match &tx.tx {
+                        ZkSyncTx::Transfer(transfer)
+                            if existing_accounts.contains(&transfer.to) =>
+                        {
+                            TransferOp::CHUNKS
+                        }
+                        ZkSyncTx::Transfer(_) => TransferToNewOp::CHUNKS,
+                        tx => tx.min_chunks(),
+                    })
+                    .sum()
+            })
+            .collect();
+        metrics::histogram!("mempool_state.required_chunks_for_all", start.elapsed());
+        Ok(chunks)
+    }
+
+    /// Returns the USD price of the smallest unit for each of the given tokens.
+    /// Tokens without a known price are omitted from the result.
+    ///
+    /// Prices are cached for `TOKEN_PRICES_CACHE_TTL`, all the missing prices are loaded
+    /// with a single query.
+    pub async fn load_token_unit_prices(
+        &self,
+        tokens: &HashSet<TokenId>,
+    ) -> Result<HashMap<TokenId, Ratio<BigUint>>, TxAddError> {
+        let start = Instant::now();
+        let mut cache = self.token_prices.lock().await;
+        let is_valid = cache.as_ref().map_or(false, |cache| {
+            cache.loaded_at.elapsed() < TOKEN_PRICES_CACHE_TTL
+                && cache.requested.is_superset(tokens)
+        });
+        if !is_valid {
+            let mut requested = tokens.clone();
+            if let Some(cache) = cache.as_ref() {
+                if cache.loaded_at.elapsed() < TOKEN_PRICES_CACHE_TTL {
+                    requested.extend(cache.requested.iter().copied());
+                }
+            }
+            let token_ids: Vec<TokenId> = requested.iter().copied().collect();
+            let prices = self
+                .db_pool
+                .access_storage()
+                .await
+                .map_err(|_| TxAddError::DbError)?
+                .tokens_schema()
+                .get_token_unit_prices(&token_ids)
+                .await
+                .map_err(|_| TxAddError::DbError)?;
+            *cache = Some(TokenPricesCache {
+                loaded_at: Instant::now(),
+                requested,
+                prices,
+            });
+        }
+
+        let cached_prices = &cache.as_ref().expect("cache was just filled").prices;
+        let prices = tokens
+            .iter()
+            .filter_map(|token_id| Some((*token_id, cached_prices.get(token_id)?.clone())))
+            .collect();
+        metrics::histogram!("mempool_state.load_token_unit_prices", start.elapsed());
+        Ok(prices)
+    }
+
+    /// Fills the token prices cache, so the given prices are used instead of the ones from the database.
+    #[cfg(test)]
+    pub(crate) async fn set_token_unit_prices(&self, prices: HashMap<TokenId, Ratio<BigUint>>) {
+        *self.token_prices.lock().await = Some(TokenPricesCache {
+            loaded_at: Instant::now(),
+            requested: prices.keys().copied().collect(),
+            prices,
+        });
+    }
+
     pub async fn collect_garbage(&self) {
         let mut storage = self.db_pool.access_storage().await.expect("Db error");
         // Remove any possible duplicates of already executed transactions
@@ -110,13 +261,60 @@ impl MempoolState {
             .expect("Db error");
     }
 
+    /// Marks the proposed transactions in the database, so they cannot be replaced anymore.
+    ///
+    /// Transactions removed from the mempool in the meantime (e.g. replaced) are dropped from the
+    /// proposal along with the subsequent transactions of the same accounts, since the latter would
+    /// fail because of the nonce gap.
+    pub async fn mark_txs_proposed(
+        &self,
+        txs: Vec<SignedTxVariant>,
+    ) -> Result<Vec<SignedTxVariant>, TxAddError> {
+        if txs.is_empty() {
+            return Ok(txs);
+        }
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        let tx_hashes: Vec<TxHash> = txs.iter().flat_map(SignedTxVariant::hashes).collect();
+        let marked = storage
+            .chain()
+            .mempool_schema()
+            .mark_txs_proposed(&tx_hashes)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
+        let mut skipped_accounts = HashSet::new();
+        let mut proposed_txs = Vec::with_capacity(txs.len());
+        for variant in txs {
+            let variant_txs = variant.get_transactions();
+            let accounts: Vec<_> = variant_txs
+                .iter()
+                .filter_map(|tx| tx.account_id().ok())
+                .collect();
+            let is_marked = variant_txs.iter().all(|tx| marked.contains(&tx.hash()));
+            if is_marked && !accounts.iter().any(|id| skipped_accounts.contains(id)) {
+                proposed_txs.push(variant);
+            } else {
+                skipped_accounts.extend(accounts);
+            }
+        }
+        Ok(proposed_txs)
+    }
+
     pub fn new(db_pool: ConnectionPool) -> Self {
-        Self { db_pool }
+        Self {
+            db_pool,
+            token_prices: Arc::default(),
+        }
     }
 
     pub async fn get_transaction_queue(
         &self,
         executed_txs: &[TxHash],
+        ordering: MempoolOrdering,
     ) -> Result<MempoolTransactionsQueue, TxAddError> {
         let mut storage = self
             .db_pool
@@ -144,7 +342,7 @@ impl MempoolState {
             .await
             .map_err(|_| TxAddError::DbError)?;
 
-        let transactions_queue = MempoolTransactionsQueue::new(priority_ops, mempool_txs);
+        let transactions_queue = MempoolTransactionsQueue::new(priority_ops, mempool_txs, ordering);
 
         Ok(transactions_queue)
     }
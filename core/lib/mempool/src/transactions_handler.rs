@@ -1,14 +1,19 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
+use num::BigUint;
 
-use zksync_storage::ConnectionPool;
+use zksync_notifier::{Notifier, NotifyEvent};
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     mempool::SignedTxsBatch,
-    tx::{error::TxAddError, TxEthSignature},
-    PriorityOp, SignedZkSyncTx,
+    tx::{error::TxAddError, TxEthSignature, TxHash},
+    AccountId, Nonce, PriorityOp, SerialId, SignedZkSyncTx, ZkSyncTx,
 };
 
-use crate::state::MempoolState;
+use crate::state::{total_fee_usd, tx_fee, MempoolState};
 
 #[derive(Debug)]
 pub enum MempoolTransactionRequest {
@@ -23,6 +28,12 @@ pub enum MempoolTransactionRequest {
         bool,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove priority ops reverted by the Ethereum reorg. Operations that were already
+    /// executed can't be removed, their ids are sent back.
+    InvalidatePriorityOps(
+        Vec<SerialId>,
+        oneshot::Sender<Result<Vec<SerialId>, TxAddError>>,
+    ),
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
@@ -34,44 +45,276 @@ pub enum MempoolTransactionRequest {
     ),
 }
 
+/// Checks whether the new transaction pays enough to replace the queued one.
+/// Replacement must pay the fee in the same token, and the fee should be increased
+/// at least by `fee_bump_percent`.
+fn is_sufficient_replacement(
+    queued_tx: &ZkSyncTx,
+    new_tx: &ZkSyncTx,
+    fee_bump_percent: u32,
+) -> bool {
+    match (queued_tx.get_fee_info(), new_tx.get_fee_info()) {
+        (Some((_, queued_token, _, queued_fee)), Some((_, new_token, _, new_fee))) => {
+            queued_token == new_token
+                && new_fee > queued_fee
+                && &new_fee * 100u32 >= queued_fee * (100 + fee_bump_percent)
+        }
+        _ => false,
+    }
+}
+
 pub(crate) struct MempoolTransactionsHandler {
     pub db_pool: ConnectionPool,
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolTransactionRequest>,
     pub max_block_size_chunks: usize,
+    pub replacement_fee_bump_percent: u32,
+    pub max_pending_txs_per_account: u32,
+    pub max_nonce_gap: u32,
+    pub max_mempool_size: u32,
+    pub notifier: Notifier,
+    /// Mempool size reported via the notifier.
+    pub mempool_size_notify_threshold: u32,
+    /// Whether the mempool size exceeded the threshold on the last check.
+    /// Used to send only one notification until the mempool size decreases.
+    pub mempool_size_reported: AtomicBool,
 }
 
 impl MempoolTransactionsHandler {
-    async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
-        // Correctness should be checked by `signature_checker`, thus
-        // `tx.check_correctness()` is not invoked here.
-        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
-            vlog::error!("Mempool storage access error: {}", err);
-            TxAddError::DbError
-        })?;
-
+    /// Checks that the transaction nonce is not lower than the committed nonce of the account
+    /// and is not too far ahead of it.
+    async fn check_nonce(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+        tx_nonce: Nonce,
+    ) -> Result<(), TxAddError> {
         let nonce = storage
             .chain()
             .account_schema()
-            // Close operation does not exist so we will never met this error
-            .estimate_nonce(tx.account_id().map_err(|_| TxAddError::Other)?)
+            .estimate_nonce(account_id)
             .await
             .map_err(|_| TxAddError::DbError)?
             .unwrap_or_default();
 
-        if tx.nonce() < nonce {
+        if tx_nonce < nonce {
             return Err(TxAddError::NonceMismatch);
         }
+        if *tx_nonce - *nonce > self.max_nonce_gap {
+            return Err(TxAddError::NonceGapTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Checks that accounts do not exceed the limit of queued transactions
+    /// after the given transactions are added to the mempool.
+    async fn check_pending_txs_limit(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        txs: &[SignedZkSyncTx],
+    ) -> Result<(), TxAddError> {
+        let mut new_txs_count: HashMap<AccountId, u32> = HashMap::new();
+        for tx in txs {
+            // Close operation does not exist so we will never met this error
+            let account_id = tx.account_id().map_err(|_| TxAddError::Other)?;
+            *new_txs_count.entry(account_id).or_default() += 1;
+        }
 
-        storage
+        for (account_id, new_txs_count) in new_txs_count {
+            let pending_txs_count = storage
+                .chain()
+                .mempool_schema()
+                .get_account_pending_txs_count(account_id)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+            if pending_txs_count + new_txs_count > self.max_pending_txs_per_account {
+                return Err(TxAddError::TooManyPendingTxs);
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes room in the mempool for the given transactions.
+    ///
+    /// If the mempool is full, the transactions paying the lowest fee in USD per chunk are evicted
+    /// from the tails of the account nonce chains, but only if they pay less than the new ones.
+    /// Otherwise the new transactions are rejected. Since several handlers may insert transactions
+    /// concurrently, the limit is not strictly enforced.
+    async fn ensure_mempool_capacity(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        txs: &[SignedZkSyncTx],
+    ) -> Result<(), TxAddError> {
+        let mempool_size = storage
             .chain()
             .mempool_schema()
-            .insert_tx(&tx)
+            .get_mempool_size()
             .await
             .map_err(|err| {
                 vlog::error!("Mempool storage access error: {}", err);
                 TxAddError::DbError
             })?;
+        self.report_mempool_size(mempool_size);
+        let txs_count = txs.len() as u32;
+        if mempool_size + txs_count <= self.max_mempool_size {
+            return Ok(());
+        }
+        if txs_count > self.max_mempool_size {
+            return Err(TxAddError::MempoolIsFull);
+        }
+
+        let to_evict = mempool_size + txs_count - self.max_mempool_size;
+        let fee_tokens: HashSet<_> = txs
+            .iter()
+            .filter_map(tx_fee)
+            .map(|(token, _)| token)
+            .collect();
+        let unit_prices = self
+            .mempool_state
+            .load_token_unit_prices(&fee_tokens)
+            .await?;
+        let chunks: usize = txs.iter().map(|tx| tx.tx.min_chunks()).sum();
+        let fee_per_chunk = total_fee_usd(txs, &unit_prices) / BigUint::from(chunks);
+
+        let cheapest_txs = storage
+            .chain()
+            .mempool_schema()
+            .get_lowest_fee_txs(to_evict)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        if cheapest_txs.len() < to_evict as usize
+            || cheapest_txs
+                .iter()
+                .any(|(_, tx_fee_per_chunk)| tx_fee_per_chunk >= &fee_per_chunk)
+        {
+            return Err(TxAddError::MempoolIsFull);
+        }
+
+        let cheapest_txs: Vec<TxHash> = cheapest_txs
+            .into_iter()
+            .map(|(tx_hash, _)| tx_hash)
+            .collect();
+        let evicted = storage
+            .chain()
+            .mempool_schema()
+            .evict_txs(&cheapest_txs)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        vlog::info!(
+            "Evicted {} transactions from the full mempool",
+            evicted.len()
+        );
+        metrics::counter!("mempool.evicted_txs", evicted.len() as u64);
+
+        Ok(())
+    }
+
+    /// Sends a notification once the mempool size reaches the threshold.
+    /// The notification is sent in the background, so the transactions are not delayed by it.
+    fn report_mempool_size(&self, mempool_size: u32) {
+        let exceeded = mempool_size >= self.mempool_size_notify_threshold;
+        let reported = self.mempool_size_reported.swap(exceeded, Ordering::Relaxed);
+        if exceeded && !reported {
+            vlog::warn!(
+                "Mempool size reached {} transactions out of maximum {}",
+                mempool_size,
+                self.max_mempool_size
+            );
+            let notifier = self.notifier.clone();
+            let event = NotifyEvent::MempoolSizeThreshold {
+                size: mempool_size,
+                max_size: self.max_mempool_size,
+            };
+            tokio::spawn(async move {
+                notifier.notify(event).await.unwrap_or_else(|err| {
+                    vlog::warn!("Failed to send the mempool size notification: {}", err);
+                });
+            });
+        }
+    }
+
+    async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        self.add_tx_to_storage(&mut storage, tx).await
+    }
+
+    async fn add_tx_to_storage(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: SignedZkSyncTx,
+    ) -> Result<(), TxAddError> {
+        // Correctness should be checked by `signature_checker`, thus
+        // `tx.check_correctness()` is not invoked here.
+
+        // Close operation does not exist so we will never met this error
+        let account_id = tx.account_id().map_err(|_| TxAddError::Other)?;
+        self.check_nonce(storage, account_id, tx.nonce()).await?;
+
+        // If there is a queued transaction with the same nonce, the new one may replace it
+        // by paying a higher fee. Block proposals are built from the database, so replacing
+        // the transaction in the storage also affects the next proposed block. Transactions
+        // that were already proposed for the execution cannot be replaced.
+        let queued_tx = storage
+            .chain()
+            .mempool_schema()
+            .get_pending_tx_by_nonce(account_id, tx.nonce())
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+
+        if let Some(queued_tx) = queued_tx {
+            if queued_tx.hash() == tx.hash() {
+                // The same transaction was submitted again, there is nothing to do.
+                return Ok(());
+            }
+            if !is_sufficient_replacement(&queued_tx.tx, &tx.tx, self.replacement_fee_bump_percent)
+            {
+                return Err(TxAddError::ReplacementUnderpriced);
+            }
+
+            let replaced = storage
+                .chain()
+                .mempool_schema()
+                .replace_tx(queued_tx.hash(), &tx)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+            if !replaced {
+                // Queued transaction was proposed for the execution (or replaced) in the meantime.
+                return Err(TxAddError::NonceMismatch);
+            }
+            metrics::increment_counter!("mempool.replaced_txs");
+        } else {
+            let txs = std::slice::from_ref(&tx);
+            self.check_pending_txs_limit(storage, txs).await?;
+            self.ensure_mempool_capacity(storage, txs).await?;
+
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&tx)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+        }
 
         let labels = vec![
             ("stage", "mempool".to_string()),
@@ -133,6 +376,33 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    /// Removes the priority operations reverted by the Ethereum reorg from the mempool.
+    /// Returns the ids of the reverted operations that were already executed.
+    async fn invalidate_priority_ops(
+        &mut self,
+        ops: Vec<SerialId>,
+    ) -> Result<Vec<SerialId>, TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        let executed_ops = storage
+            .chain()
+            .mempool_schema()
+            .remove_reverted_priority_ops(&ops)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        metrics::counter!(
+            "mempool.invalidated_priority_ops",
+            (ops.len() - executed_ops.len()) as u64
+        );
+
+        Ok(executed_ops)
+    }
+
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
@@ -152,24 +422,21 @@ impl MempoolTransactionsHandler {
         for tx in txs.iter() {
             // Correctness should be checked by `signature_checker`, thus
             // `tx.check_correctness()` is not invoked here.
-            let nonce = storage
-                .chain()
-                .account_schema()
-                // Close operation does not exist so we will never met this error
-                .estimate_nonce(tx.account_id().map_err(|_| TxAddError::Other)?)
-                .await
-                .map_err(|_| TxAddError::DbError)?
-                .unwrap_or_default();
-
-            if tx.nonce() < nonce {
-                return Err(TxAddError::NonceMismatch);
-            }
+            // Close operation does not exist so we will never met this error
+            let account_id = tx.account_id().map_err(|_| TxAddError::Other)?;
+            self.check_nonce(&mut storage, account_id, tx.nonce())
+                .await?;
         }
 
         if self.mempool_state.chunks_for_batch(&batch).await? > self.max_block_size_chunks {
             return Err(TxAddError::BatchTooBig);
         }
 
+        self.check_pending_txs_limit(&mut storage, &batch.txs)
+            .await?;
+        self.ensure_mempool_capacity(&mut storage, &batch.txs)
+            .await?;
+
         for tx in &batch.txs {
             let labels = vec![
                 ("stage", "mempool".to_string()),
@@ -209,7 +476,231 @@ impl MempoolTransactionsHandler {
                     let tx_add_result = self.add_priority_ops(ops, confirmed).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::InvalidatePriorityOps(ops, resp) => {
+                    let result = self.invalidate_priority_ops(ops).await;
+                    resp.send(result).unwrap_or_default();
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db_test_macro::test as db_test;
+    use num::rational::Ratio;
+    use zksync_types::{
+        tx::{Transfer, Withdraw},
+        AccountId, Address, Nonce, TokenId,
+    };
+
+    fn handler() -> MempoolTransactionsHandler {
+        let db_pool = ConnectionPool::new(Some(1));
+        let (_, requests) = mpsc::channel(1);
+        MempoolTransactionsHandler {
+            mempool_state: MempoolState::new(db_pool.clone()),
+            db_pool,
+            requests,
+            max_block_size_chunks: 100,
+            replacement_fee_bump_percent: 10,
+            max_pending_txs_per_account: 10,
+            max_nonce_gap: 10,
+            max_mempool_size: 1000,
+            notifier: Notifier::new(),
+            mempool_size_notify_threshold: 1000,
+            mempool_size_reported: AtomicBool::new(false),
+        }
+    }
+
+    fn signed(tx: ZkSyncTx) -> SignedZkSyncTx {
+        SignedZkSyncTx {
+            tx,
+            eth_sign_data: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn account_transfer(account_id: u32, nonce: u32, fee: u32) -> SignedZkSyncTx {
+        signed(ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(account_id),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u32.into(),
+            fee.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        ))))
+    }
+
+    fn transfer(token: u32, fee: u32) -> ZkSyncTx {
+        ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(token),
+            100u32.into(),
+            fee.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )))
+    }
+
+    #[test]
+    fn replacement_fee_bump() {
+        let queued_tx = transfer(0, 100);
+
+        // Fee should be increased at least by the configured percent.
+        assert!(!is_sufficient_replacement(
+            &queued_tx,
+            &transfer(0, 100),
+            10
+        ));
+        assert!(!is_sufficient_replacement(
+            &queued_tx,
+            &transfer(0, 109),
+            10
+        ));
+        assert!(is_sufficient_replacement(&queued_tx, &transfer(0, 110), 10));
+        assert!(is_sufficient_replacement(&queued_tx, &transfer(0, 101), 0));
+        assert!(!is_sufficient_replacement(&queued_tx, &transfer(0, 100), 0));
+
+        // Fee token cannot be changed.
+        assert!(!is_sufficient_replacement(
+            &queued_tx,
+            &transfer(1, 1000),
+            10
+        ));
+
+        // Transaction type doesn't matter as long as the fee is paid in the same token.
+        let withdraw = ZkSyncTx::Withdraw(Box::new(Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u32.into(),
+            200u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )));
+        assert!(is_sufficient_replacement(&queued_tx, &withdraw, 10));
+    }
+
+    /// Checks that the queued transaction is replaced only until it's proposed for the execution.
+    #[db_test]
+    async fn replace_proposed_tx(mut storage: StorageProcessor<'_>) {
+        let handler = handler();
+        let queued_tx = signed(transfer(0, 100));
+        let replacement = signed(transfer(0, 110));
+        handler
+            .add_tx_to_storage(&mut storage, queued_tx.clone())
+            .await
+            .unwrap();
+
+        let result = handler
+            .add_tx_to_storage(&mut storage, signed(transfer(0, 105)))
+            .await;
+        assert!(matches!(result, Err(TxAddError::ReplacementUnderpriced)));
+        handler
+            .add_tx_to_storage(&mut storage, replacement.clone())
+            .await
+            .unwrap();
+        let mut mempool = storage.chain().mempool_schema();
+        assert!(!mempool.contains_tx(queued_tx.hash()).await.unwrap());
+        assert!(mempool.contains_tx(replacement.hash()).await.unwrap());
+
+        // Once proposed, the transaction cannot be replaced even by a higher fee.
+        mempool
+            .mark_txs_proposed(&[replacement.hash()])
+            .await
+            .unwrap();
+        let result = handler
+            .add_tx_to_storage(&mut storage, signed(transfer(0, 1000)))
+            .await;
+        assert!(matches!(result, Err(TxAddError::NonceMismatch)));
+        let mut mempool = storage.chain().mempool_schema();
+        assert!(mempool.contains_tx(replacement.hash()).await.unwrap());
+        assert_eq!(
+            mempool
+                .get_replacement_tx_hash(replacement.hash())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    /// Checks that the transactions paying the lowest fee per chunk are evicted from the full mempool,
+    /// starting from the tails of the account nonce chains.
+    #[db_test]
+    async fn evict_cheapest_txs(mut storage: StorageProcessor<'_>) {
+        let mut handler = handler();
+        handler.max_mempool_size = 2;
+        // The smallest unit of the fee token costs 1 USD.
+        handler
+            .mempool_state
+            .set_token_unit_prices(
+                vec![(TokenId(0), Ratio::from_integer(1u32.into()))]
+                    .into_iter()
+                    .collect(),
+            )
+            .await;
+
+        // The first transaction of the account pays the lowest fee, but it cannot be evicted
+        // while the next one is in the mempool.
+        let cheap_tx = account_transfer(1, 0, 10);
+        let expensive_tx = account_transfer(1, 1, 1000);
+        for tx in [&cheap_tx, &expensive_tx] {
+            handler
+                .add_tx_to_storage(&mut storage, tx.clone())
+                .await
+                .unwrap();
+        }
+        let result = handler
+            .add_tx_to_storage(&mut storage, account_transfer(2, 0, 100))
+            .await;
+        assert!(matches!(result, Err(TxAddError::MempoolIsFull)));
+
+        let new_tx = account_transfer(2, 0, 2000);
+        handler
+            .add_tx_to_storage(&mut storage, new_tx.clone())
+            .await
+            .unwrap();
+        let mut mempool = storage.chain().mempool_schema();
+        assert!(!mempool.contains_tx(expensive_tx.hash()).await.unwrap());
+        assert!(mempool.is_tx_evicted(expensive_tx.hash()).await.unwrap());
+        assert!(mempool.contains_tx(cheap_tx.hash()).await.unwrap());
+        assert!(!mempool.is_tx_evicted(new_tx.hash()).await.unwrap());
+
+        // Now the first transaction of the account can be evicted. The withdrawal pays more
+        // than the next transfer, but less per chunk, so it's evicted in turn.
+        let withdraw = signed(ZkSyncTx::Withdraw(Box::new(Withdraw::new(
+            AccountId(3),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u32.into(),
+            60u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))));
+        handler
+            .add_tx_to_storage(&mut storage, withdraw.clone())
+            .await
+            .unwrap();
+        let new_tx = account_transfer(4, 0, 30);
+        handler
+            .add_tx_to_storage(&mut storage, new_tx.clone())
+            .await
+            .unwrap();
+        let mut mempool = storage.chain().mempool_schema();
+        assert!(mempool.is_tx_evicted(cheap_tx.hash()).await.unwrap());
+        assert!(mempool.is_tx_evicted(withdraw.hash()).await.unwrap());
+        assert!(mempool.contains_tx(new_tx.hash()).await.unwrap());
+        assert_eq!(mempool.get_mempool_size().await.unwrap(), 2);
+    }
+}
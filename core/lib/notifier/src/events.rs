@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use zksync_config::configs::notifier::Severity;
+use zksync_types::{tokens::Token, Address, H256};
+
+/// Event reported by the notifier.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotifyEvent {
+    /// New token was added to the database.
+    NewToken { token: Token },
+    /// New token failed the validation and can't be used to pay fees.
+    SuspiciousToken { token: Token, details: String },
+    /// Ethereum transaction failed and is going to be sent again.
+    EthTxFailed { tx_hash: H256, details: String },
+    /// Pre-flight check of the aggregated operation failed, so it's not sent.
+    EthPreflightFailed {
+        op_id: i64,
+        op_type: String,
+        revert_reason: String,
+    },
+    /// Transaction for the aggregated operation was not mined and is sent again with a higher gas price.
+    EthTxStuck {
+        op_id: i64,
+        op_type: String,
+        attempts: usize,
+    },
+    /// Ethereum sender is paused after the non-recoverable transaction failure.
+    EthSenderPaused { tx_hash: H256, details: String },
+    /// Operations of some type are held after reaching the spending limit.
+    EthSpendingLimitReached { op_type: String, details: String },
+    /// Balance of the operator account is below the configured minimum.
+    LowOperatorBalance { operator: Address, details: String },
+    /// Ethereum reorg reverted the already processed blocks.
+    L1Reorg {
+        first_reverted_block: u64,
+        details: String,
+    },
+    /// Amount of the pending prover jobs exceeds the threshold.
+    ProverQueueLag { pending_jobs: u32, threshold: u32 },
+    /// State keeper was throttled by the root hash calculator for too long.
+    RootHashCalculatorThrottled {
+        duration: Duration,
+        queue_size: usize,
+    },
+    /// Mempool size exceeds the threshold.
+    MempoolSizeThreshold { size: u32, max_size: u32 },
+    /// Forced exit request could not be fulfilled.
+    ForcedExitFailed { details: String },
+}
+
+impl NotifyEvent {
+    /// Returns the severity of the event used to route it to the backends.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::NewToken { .. } => Severity::Info,
+            Self::SuspiciousToken { .. }
+            | Self::EthTxFailed { .. }
+            | Self::EthPreflightFailed { .. }
+            | Self::EthTxStuck { .. }
+            | Self::EthSpendingLimitReached { .. }
+            | Self::ProverQueueLag { .. }
+            | Self::RootHashCalculatorThrottled { .. }
+            | Self::MempoolSizeThreshold { .. }
+            | Self::ForcedExitFailed { .. } => Severity::Warning,
+            Self::EthSenderPaused { .. }
+            | Self::LowOperatorBalance { .. }
+            | Self::L1Reorg { .. } => Severity::Critical,
+        }
+    }
+
+    /// Returns the name of the component reporting the event.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::NewToken { .. } | Self::SuspiciousToken { .. } => "token_handler_bot",
+            Self::EthTxFailed { .. }
+            | Self::EthPreflightFailed { .. }
+            | Self::EthTxStuck { .. }
+            | Self::EthSenderPaused { .. }
+            | Self::EthSpendingLimitReached { .. }
+            | Self::LowOperatorBalance { .. } => "eth_sender_bot",
+            Self::L1Reorg { .. } => "eth_watch_bot",
+            Self::ProverQueueLag { .. } => "prover_server_bot",
+            Self::RootHashCalculatorThrottled { .. } => "state_keeper_bot",
+            Self::MempoolSizeThreshold { .. } => "mempool_bot",
+            Self::ForcedExitFailed { .. } => "forced_exit_bot",
+        }
+    }
+
+    /// Returns the machine-readable kind of the event.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NewToken { .. } => "new_token",
+            Self::SuspiciousToken { .. } => "suspicious_token",
+            Self::EthTxFailed { .. } => "eth_tx_failed",
+            Self::EthPreflightFailed { .. } => "eth_preflight_failed",
+            Self::EthTxStuck { .. } => "eth_tx_stuck",
+            Self::EthSenderPaused { .. } => "eth_sender_paused",
+            Self::EthSpendingLimitReached { .. } => "eth_spending_limit_reached",
+            Self::LowOperatorBalance { .. } => "low_operator_balance",
+            Self::L1Reorg { .. } => "l1_reorg",
+            Self::ProverQueueLag { .. } => "prover_queue_lag",
+            Self::RootHashCalculatorThrottled { .. } => "root_hash_calculator_throttled",
+            Self::MempoolSizeThreshold { .. } => "mempool_size_threshold",
+            Self::ForcedExitFailed { .. } => "forced_exit_failed",
+        }
+    }
+
+    /// Returns the human-readable description of the event.
+    pub fn message(&self) -> String {
+        match self {
+            Self::NewToken { token } => format!(
+                "New token: id = {}, address = {}, symbol = {}, decimals = {}",
+                token.id, token.address, token.symbol, token.decimals,
+            ),
+            Self::SuspiciousToken { token, details } => format!(
+                "New token is {:?}: id = {}, address = {}, symbol = {}, decimals = {}. Issues: {}",
+                token.status, token.id, token.address, token.symbol, token.decimals, details,
+            ),
+            Self::EthTxFailed { tx_hash, details } => {
+                format!("Ethereum transaction {:#x} failed: {}", tx_hash, details)
+            }
+            Self::EthPreflightFailed {
+                op_id,
+                op_type,
+                revert_reason,
+            } => format!(
+                "Pre-flight check of the aggregated operation {} ({}) failed, transaction is not sent. Revert reason: {}",
+                op_id, op_type, revert_reason
+            ),
+            Self::EthTxStuck {
+                op_id,
+                op_type,
+                attempts,
+            } => format!(
+                "Transaction for the aggregated operation {} ({}) is stuck, it was sent {} times",
+                op_id, op_type, attempts
+            ),
+            Self::EthSenderPaused { tx_hash, details } => format!(
+                "Ethereum sender is paused after the transaction {:#x} failed: {}",
+                tx_hash, details
+            ),
+            Self::EthSpendingLimitReached { op_type, details } => format!(
+                "Spending limit for {} transactions is reached, transactions are held: {}",
+                op_type, details
+            ),
+            Self::LowOperatorBalance { operator, details } => format!(
+                "Balance of the operator account {:#x} is low: {}",
+                operator, details
+            ),
+            Self::L1Reorg {
+                first_reverted_block,
+                details,
+            } => format!(
+                "Ethereum reorg reverted the blocks starting from {}: {}",
+                first_reverted_block, details
+            ),
+            Self::ProverQueueLag {
+                pending_jobs,
+                threshold,
+            } => format!(
+                "Prover queue is lagging: {} pending jobs (threshold is {})",
+                pending_jobs, threshold
+            ),
+            Self::RootHashCalculatorThrottled {
+                duration,
+                queue_size,
+            } => format!(
+                "State keeper was throttled by the root hash calculator for {:?}, {} blocks are waiting for the root hash",
+                duration, queue_size
+            ),
+            Self::MempoolSizeThreshold { size, max_size } => format!(
+                "Mempool contains {} transactions out of maximum {}",
+                size, max_size
+            ),
+            Self::ForcedExitFailed { details } => {
+                format!("Forced exit request failed: {}", details)
+            }
+        }
+    }
+}
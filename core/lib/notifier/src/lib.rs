@@ -1,29 +1,164 @@
-use matter_most_notifier::MatterMostNotifier;
-use reqwest::Url;
-use zksync_types::tokens::Token;
+//! Notifications about the server events.
+//!
+//! Subsystems report typed [`NotifyEvent`]s via the [`Notifier`], which routes every event to
+//! the configured backends (generic JSON webhook, Slack, MatterMost, email). Each backend receives
+//! only the events at least as severe as its configured minimum.
 
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{Client, Url};
+use zksync_config::NotifierConfig;
+
+pub use self::{
+    events::NotifyEvent, matter_most_notifier::MatterMostNotifier, slack_notifier::SlackNotifier,
+    smtp_notifier::SmtpNotifier, webhook_notifier::WebhookNotifier,
+};
+pub use zksync_config::configs::notifier::Severity;
+
+mod events;
 mod matter_most_notifier;
+mod slack_notifier;
+mod smtp_notifier;
+mod webhook_notifier;
+
+#[cfg(test)]
+mod tests;
+
+/// Timeout for establishing the connection to the HTTP backend.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for the whole request to the HTTP backend, so an unresponsive backend doesn't hold the caller.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Creates the client used by the HTTP backends.
+fn http_client() -> Client {
+    Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to create the notifier HTTP client")
+}
 
+/// Destination of the notifications.
+#[async_trait::async_trait]
+pub trait NotifierBackend: Send + Sync {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()>;
+}
+
+#[derive(Clone)]
+struct Route {
+    backend: Arc<dyn NotifierBackend>,
+    min_severity: Severity,
+}
+
+/// Routes the events to the notifier backends according to their severity.
+/// Notifier without backends silently drops all the events.
+#[derive(Clone, Default)]
 pub struct Notifier {
-    matter_most_notifier: MatterMostNotifier,
+    routes: Vec<Route>,
+}
+
+impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notifier")
+            .field("backends", &self.routes.len())
+            .finish()
+    }
 }
 
 impl Notifier {
-    pub fn with_mattermost(webhook_url: Url) -> Self {
-        Self {
-            matter_most_notifier: MatterMostNotifier::new(webhook_url),
+    /// Creates a notifier without backends.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a notifier with the backends enabled in the config.
+    /// Backends with invalid settings are skipped with an error in the log.
+    pub fn from_config(config: &NotifierConfig) -> Self {
+        let mut notifier = Self::new();
+        if config.webhook.is_enabled() {
+            match Url::parse(&config.webhook.url) {
+                Ok(url) => {
+                    notifier = notifier
+                        .with_backend(WebhookNotifier::new(url), config.webhook.min_severity)
+                }
+                Err(err) => vlog::error!("Invalid notifier webhook URL: {}", err),
+            }
+        }
+        if config.slack.is_enabled() {
+            match Url::parse(&config.slack.url) {
+                Ok(url) => {
+                    notifier =
+                        notifier.with_backend(SlackNotifier::new(url), config.slack.min_severity)
+                }
+                Err(err) => vlog::error!("Invalid notifier Slack URL: {}", err),
+            }
+        }
+        if config.mattermost.is_enabled() {
+            match Url::parse(&config.mattermost.url) {
+                Ok(url) => {
+                    notifier = notifier
+                        .with_backend(MatterMostNotifier::new(url), config.mattermost.min_severity)
+                }
+                Err(err) => vlog::error!("Invalid notifier MatterMost URL: {}", err),
+            }
+        }
+        if config.smtp.is_enabled() {
+            match SmtpNotifier::new(&config.smtp) {
+                Ok(backend) => notifier = notifier.with_backend(backend, config.smtp.min_severity),
+                Err(err) => vlog::error!("Invalid notifier SMTP settings: {}", err),
+            }
+        }
+        notifier
+    }
+
+    /// Adds the backend receiving the events at least as severe as `min_severity`.
+    pub fn with_backend(
+        mut self,
+        backend: impl NotifierBackend + 'static,
+        min_severity: Severity,
+    ) -> Self {
+        self.routes.push(Route {
+            backend: Arc::new(backend),
+            min_severity,
+        });
+        self
+    }
+
+    /// Adds the MatterMost backend receiving all the events.
+    pub fn with_mattermost(self, webhook_url: Url) -> Self {
+        self.with_backend(MatterMostNotifier::new(webhook_url), Severity::Info)
+    }
+
+    /// Adds the MatterMost backend configured by the `webhook_url` field of the component config.
+    /// Empty or invalid URL is ignored.
+    pub fn with_mattermost_url(self, webhook_url: &str) -> Self {
+        match Url::parse(webhook_url) {
+            Ok(url) => self.with_mattermost(url),
+            Err(_) => self,
         }
     }
 
-    pub async fn send_new_token_notify(&self, token: Token) -> anyhow::Result<()> {
-        let token_info_msg = format!(
-            "New token: id = {}, address = {}, symbol = {}, decimals = {}",
-            token.id, token.address, token.symbol, token.decimals,
-        );
-        self.matter_most_notifier
-            .send_notify("token_handler_bot", &token_info_msg)
-            .await?;
+    /// Returns whether the notifier has no backends.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
 
-        Ok(())
+    /// Sends the event to all the backends accepting its severity.
+    /// Event is sent to every backend even if some of them fail, the first error is returned.
+    pub async fn notify(&self, event: NotifyEvent) -> anyhow::Result<()> {
+        let severity = event.severity();
+        let mut result = Ok(());
+        for route in &self.routes {
+            if severity < route.min_severity {
+                continue;
+            }
+            if let Err(err) = route.backend.send(&event).await {
+                metrics::increment_counter!("notifier.failed_notifications", "kind" => event.kind());
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
     }
 }
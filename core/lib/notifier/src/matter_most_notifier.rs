@@ -1,5 +1,8 @@
 use reqwest::{Client, Url};
 
+use crate::{http_client, NotifierBackend, NotifyEvent};
+
+/// Backend posting the events to the MatterMost incoming webhook.
 pub struct MatterMostNotifier {
     webhook_url: Url,
     client: Client,
@@ -9,7 +12,7 @@ impl MatterMostNotifier {
     pub fn new(webhook_url: Url) -> Self {
         Self {
             webhook_url,
-            client: Client::new(),
+            client: http_client(),
         }
     }
 
@@ -23,8 +26,16 @@ impl MatterMostNotifier {
             .post(self.webhook_url.clone())
             .json(&parameters)
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl NotifierBackend for MatterMostNotifier {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()> {
+        self.send_notify(event.source(), &event.message()).await
+    }
+}
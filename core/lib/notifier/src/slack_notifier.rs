@@ -0,0 +1,37 @@
+use reqwest::{Client, Url};
+
+use crate::{http_client, NotifierBackend, NotifyEvent};
+
+/// Backend posting the events to the Slack-compatible incoming webhook.
+pub struct SlackNotifier {
+    webhook_url: Url,
+    client: Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: Url) -> Self {
+        Self {
+            webhook_url,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotifierBackend for SlackNotifier {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()> {
+        let parameters = serde_json::json!({
+            "username": event.source(),
+            "text": format!("[{:?}] {}", event.severity(), event.message()),
+        });
+
+        self.client
+            .post(self.webhook_url.clone())
+            .json(&parameters)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
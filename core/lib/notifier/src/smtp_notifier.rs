@@ -0,0 +1,68 @@
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use zksync_config::configs::notifier::SmtpBackend;
+
+use crate::{NotifierBackend, NotifyEvent};
+
+/// Backend sending the events via email.
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    recipients: Vec<Mailbox>,
+}
+
+impl SmtpNotifier {
+    pub fn new(config: &SmtpBackend) -> anyhow::Result<Self> {
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.server)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.server)
+        }
+        .port(config.port);
+        if !config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            ));
+        }
+
+        let recipients = config
+            .recipients
+            .iter()
+            .filter(|recipient| !recipient.is_empty())
+            .map(|recipient| recipient.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+        anyhow::ensure!(
+            !recipients.is_empty(),
+            "No recipients for the SMTP notifier"
+        );
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from.parse()?,
+            recipients,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl NotifierBackend for SmtpNotifier {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()> {
+        let mut builder = Message::builder().from(self.from.clone()).subject(format!(
+            "[zkSync][{:?}] {}: {}",
+            event.severity(),
+            event.source(),
+            event.kind()
+        ));
+        for recipient in &self.recipients {
+            builder = builder.to(recipient.clone());
+        }
+        let email = builder.body(event.message())?;
+
+        self.transport.send(email).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use zksync_config::configs::notifier::{SmtpBackend, Thresholds, WebhookBackend};
+use zksync_types::H256;
+
+use super::*;
+
+/// Local HTTP server standing in for the webhooks, records the JSON bodies of the received requests.
+struct WebhookStandIn {
+    url: Url,
+    received: Arc<Mutex<Vec<Value>>>,
+}
+
+impl WebhookStandIn {
+    async fn spawn(status: u16) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let requests = received.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(Self::serve(stream, status, requests.clone()));
+            }
+        });
+
+        Self { url, received }
+    }
+
+    async fn serve(mut stream: TcpStream, status: u16, requests: Arc<Mutex<Vec<Value>>>) {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            // Read the request head.
+            let head_len = loop {
+                if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            };
+            let head = String::from_utf8_lossy(&buffer[..head_len]).to_lowercase();
+            let content_length: usize = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map(|value| value.trim().parse().unwrap())
+                .unwrap_or_default();
+
+            // Read the request body.
+            while buffer.len() < head_len + content_length {
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let body: Vec<u8> = buffer.drain(..head_len + content_length).collect();
+            let json = serde_json::from_slice(&body[head_len..]).unwrap();
+            requests.lock().unwrap().push(json);
+
+            let response = format!("HTTP/1.1 {} Stand-in\r\ncontent-length: 0\r\n\r\n", status);
+            if stream.write_all(response.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn received(&self) -> Vec<Value> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+fn warning_event() -> NotifyEvent {
+    NotifyEvent::MempoolSizeThreshold {
+        size: 9500,
+        max_size: 10000,
+    }
+}
+
+fn critical_event() -> NotifyEvent {
+    NotifyEvent::EthSenderPaused {
+        tx_hash: H256::repeat_byte(0x11),
+        details: "Reverted".to_string(),
+    }
+}
+
+fn disabled_config() -> NotifierConfig {
+    let disabled_webhook = WebhookBackend {
+        url: String::new(),
+        min_severity: Severity::Info,
+    };
+    NotifierConfig {
+        webhook: disabled_webhook.clone(),
+        slack: disabled_webhook.clone(),
+        mattermost: disabled_webhook,
+        smtp: SmtpBackend {
+            server: String::new(),
+            port: 587,
+            starttls: true,
+            username: String::new(),
+            password: String::new(),
+            from: "zksync@localhost".to_string(),
+            recipients: Vec::new(),
+            min_severity: Severity::Critical,
+        },
+        thresholds: Thresholds {
+            prover_queue_lag: 100,
+            mempool_size_percent: 90,
+            root_hash_throttle: 30000,
+        },
+    }
+}
+
+/// Checks the payloads sent by the webhook backends.
+#[tokio::test]
+async fn backend_payloads() {
+    let webhook = WebhookStandIn::spawn(200).await;
+    let slack = WebhookStandIn::spawn(200).await;
+    let mattermost = WebhookStandIn::spawn(200).await;
+    let notifier = Notifier::new()
+        .with_backend(WebhookNotifier::new(webhook.url.clone()), Severity::Info)
+        .with_backend(SlackNotifier::new(slack.url.clone()), Severity::Info)
+        .with_mattermost(mattermost.url.clone());
+
+    let event = critical_event();
+    notifier.notify(event.clone()).await.unwrap();
+
+    assert_eq!(
+        webhook.received(),
+        vec![serde_json::json!({
+            "kind": "eth_sender_paused",
+            "severity": "Critical",
+            "source": "eth_sender_bot",
+            "message": event.message(),
+        })]
+    );
+    assert_eq!(
+        slack.received(),
+        vec![serde_json::json!({
+            "username": "eth_sender_bot",
+            "text": format!("[Critical] {}", event.message()),
+        })]
+    );
+    assert_eq!(
+        mattermost.received(),
+        vec![serde_json::json!({
+            "username": "eth_sender_bot",
+            "text": event.message(),
+        })]
+    );
+}
+
+/// Checks that the events are sent only to the backends accepting their severity.
+#[tokio::test]
+async fn routing_by_severity() {
+    let webhook = WebhookStandIn::spawn(200).await;
+    let slack = WebhookStandIn::spawn(200).await;
+    let notifier = Notifier::new()
+        .with_backend(WebhookNotifier::new(webhook.url.clone()), Severity::Warning)
+        .with_backend(SlackNotifier::new(slack.url.clone()), Severity::Critical);
+
+    let token = zksync_types::Token::default();
+    notifier
+        .notify(NotifyEvent::NewToken { token })
+        .await
+        .unwrap();
+    assert!(webhook.received().is_empty());
+    assert!(slack.received().is_empty());
+
+    notifier.notify(warning_event()).await.unwrap();
+    assert_eq!(webhook.received().len(), 1);
+    assert!(slack.received().is_empty());
+
+    notifier.notify(critical_event()).await.unwrap();
+    assert_eq!(webhook.received().len(), 2);
+    assert_eq!(slack.received().len(), 1);
+    assert_eq!(webhook.received()[1]["kind"], "eth_sender_paused");
+}
+
+/// Checks that the failure of one backend doesn't prevent sending the event to the others.
+#[tokio::test]
+async fn failed_backend() {
+    let failing = WebhookStandIn::spawn(500).await;
+    let webhook = WebhookStandIn::spawn(200).await;
+    let notifier = Notifier::new()
+        .with_backend(WebhookNotifier::new(failing.url.clone()), Severity::Info)
+        .with_backend(WebhookNotifier::new(webhook.url.clone()), Severity::Info);
+
+    assert!(notifier.notify(warning_event()).await.is_err());
+    assert_eq!(failing.received().len(), 1);
+    assert_eq!(webhook.received().len(), 1);
+}
+
+/// Checks the backends created from the config.
+#[tokio::test]
+async fn notifier_from_config() {
+    let config = disabled_config();
+    let notifier = Notifier::from_config(&config);
+    assert!(notifier.is_empty());
+    // Events are silently dropped.
+    notifier.notify(critical_event()).await.unwrap();
+
+    let webhook = WebhookStandIn::spawn(200).await;
+    let slack = WebhookStandIn::spawn(200).await;
+    let mut config = disabled_config();
+    config.webhook.url = webhook.url.to_string();
+    config.slack.url = slack.url.to_string();
+    config.slack.min_severity = Severity::Critical;
+    // SMTP backend without recipients is skipped.
+    config.smtp.server = "127.0.0.1".to_string();
+    let notifier = Notifier::from_config(&config).with_mattermost_url("");
+
+    notifier.notify(warning_event()).await.unwrap();
+    notifier.notify(critical_event()).await.unwrap();
+    assert_eq!(webhook.received().len(), 2);
+    assert_eq!(slack.received().len(), 1);
+}
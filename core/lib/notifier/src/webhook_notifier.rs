@@ -0,0 +1,43 @@
+use reqwest::{Client, Url};
+
+use crate::{http_client, NotifierBackend, NotifyEvent};
+
+/// Backend posting the events to the generic webhook as JSON objects:
+///
+/// ```json
+/// { "kind": "eth_sender_paused", "severity": "Critical", "source": "eth_sender_bot", "message": "..." }
+/// ```
+pub struct WebhookNotifier {
+    webhook_url: Url,
+    client: Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(webhook_url: Url) -> Self {
+        Self {
+            webhook_url,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotifierBackend for WebhookNotifier {
+    async fn send(&self, event: &NotifyEvent) -> anyhow::Result<()> {
+        let parameters = serde_json::json!({
+            "kind": event.kind(),
+            "severity": format!("{:?}", event.severity()),
+            "source": event.source(),
+            "message": event.message(),
+        });
+
+        self.client
+            .post(self.webhook_url.clone())
+            .json(&parameters)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
@@ -91,6 +91,20 @@ impl ZkSyncState {
         empty
     }
 
+    /// Creates a state containing only a part of the accounts, e.g. the ones required
+    /// to execute some transactions. `next_free_id` is the ID of the next account
+    /// to be created in the complete state.
+    pub fn from_partial_acc_map(
+        accounts: AccountMap,
+        nfts: HashMap<TokenId, NFT>,
+        next_free_id: AccountId,
+    ) -> Self {
+        let mut state = Self::from_acc_map(accounts);
+        state.nfts = nfts;
+        state.next_free_id = std::cmp::max(state.next_free_id, next_free_id);
+        state
+    }
+
     pub fn new(
         balance_tree: AccountTree,
         account_id_by_address: HashMap<Address, AccountId>,
@@ -1070,6 +1084,32 @@ mod tests {
         assert_eq!(*state.next_free_id, 10);
     }
 
+    /// Checks if next_free_id of the partial state is not affected by the set of loaded accounts.
+    #[test]
+    fn from_partial_acc_map() {
+        let mut accounts = AccountMap::default();
+        accounts.insert(
+            AccountId(3),
+            Account::default_with_address(&Address::repeat_byte(1)),
+        );
+        accounts.insert(
+            AccountId(7),
+            Account::default_with_address(&Address::repeat_byte(2)),
+        );
+
+        let state =
+            ZkSyncState::from_partial_acc_map(accounts.clone(), HashMap::new(), AccountId(100));
+        assert_eq!(*state.next_free_id, 100);
+        assert_eq!(
+            state.get_account_by_address(&Address::repeat_byte(2)),
+            Some((AccountId(7), accounts[&AccountId(7)].clone()))
+        );
+
+        // The loaded accounts can't be overwritten by the new ones.
+        let state = ZkSyncState::from_partial_acc_map(accounts, HashMap::new(), AccountId(5));
+        assert_eq!(*state.next_free_id, 8);
+    }
+
     /// Checks if insert_account panics if account has id greater that next_free_id.
     #[should_panic(
         expected = "assertion failed: id == NFT_STORAGE_ACCOUNT_ID || id <= self.next_free_id"
@@ -0,0 +1,15 @@
+ALTER TABLE mempool_txs ADD COLUMN account_id BIGINT;
+ALTER TABLE mempool_txs ADD COLUMN nonce BIGINT;
+
+-- Fill the new columns for the transactions that are already in the mempool.
+UPDATE mempool_txs SET
+    account_id = COALESCE(tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId')::bigint,
+    nonce = (tx->>'nonce')::bigint;
+
+CREATE INDEX IF NOT EXISTS mempool_txs_account_id_nonce_index ON mempool_txs (account_id, nonce);
+
+CREATE TABLE IF NOT EXISTS mempool_replaced_txs (
+    tx_hash TEXT PRIMARY KEY,
+    replaced_by TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE mempool_txs DROP COLUMN fee;
+ALTER TABLE mempool_txs DROP COLUMN fee_token;
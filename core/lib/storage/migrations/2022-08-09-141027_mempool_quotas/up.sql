@@ -0,0 +1,7 @@
+ALTER TABLE mempool_txs ADD COLUMN fee_token INTEGER;
+ALTER TABLE mempool_txs ADD COLUMN fee NUMERIC;
+
+-- Fill the new columns for the transactions that are already in the mempool.
+UPDATE mempool_txs SET
+    fee_token = COALESCE(tx->>'feeToken', tx->>'token')::integer,
+    fee = (tx->>'fee')::numeric;
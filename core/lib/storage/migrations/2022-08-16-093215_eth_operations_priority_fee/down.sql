@@ -0,0 +1 @@
+ALTER TABLE eth_operations DROP COLUMN IF EXISTS last_used_priority_fee;
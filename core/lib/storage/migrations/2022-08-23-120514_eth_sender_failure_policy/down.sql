@@ -0,0 +1,2 @@
+ALTER TABLE eth_parameters DROP COLUMN IF EXISTS paused;
+ALTER TABLE eth_operations DROP COLUMN IF EXISTS failed_attempts;
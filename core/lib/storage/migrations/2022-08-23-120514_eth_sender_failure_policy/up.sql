@@ -0,0 +1,2 @@
+ALTER TABLE eth_operations ADD COLUMN failed_attempts BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE eth_parameters ADD COLUMN paused BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+ALTER TABLE eth_operations DROP COLUMN IF EXISTS operator_address;
+DROP TABLE IF EXISTS eth_operator_accounts;
@@ -0,0 +1,11 @@
+-- Nonces and gas price statistics of the additional operator accounts of the Ethereum sender.
+-- The main operator account keeps using `eth_parameters`.
+CREATE TABLE eth_operator_accounts (
+    address BYTEA PRIMARY KEY,
+    nonce BIGINT NOT NULL,
+    gas_price_limit BIGINT NOT NULL,
+    average_gas_price BIGINT
+);
+
+-- Operator account that sent the transactions of the operation, `NULL` for the main account.
+ALTER TABLE eth_operations ADD COLUMN operator_address BYTEA;
@@ -0,0 +1,19 @@
+-- Fees paid for the mined Ethereum transactions, including the failed ones.
+-- Used to apply the spending limits of the Ethereum sender and to reconcile L1 costs per block.
+-- Entries are kept when the operations of the reverted blocks are removed, since the fees were paid anyway.
+CREATE TABLE eth_tx_fees (
+    tx_hash BYTEA PRIMARY KEY,
+    eth_op_id BIGINT NOT NULL,
+    op_type TEXT NOT NULL,
+    -- Range of the zkSync blocks of the aggregated operation, if any.
+    from_block BIGINT,
+    to_block BIGINT,
+    gas_used NUMERIC NOT NULL,
+    gas_price NUMERIC NOT NULL,
+    fee NUMERIC NOT NULL,
+    success BOOLEAN NOT NULL,
+    confirmed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX eth_tx_fees_op_type_confirmed_at_idx ON eth_tx_fees (op_type, confirmed_at);
+CREATE INDEX eth_tx_fees_blocks_idx ON eth_tx_fees (from_block, to_block);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS tokens_symbol_lower_idx;
+CREATE INDEX IF NOT EXISTS tokens_symbol_lower_idx ON tokens (lower(symbol));
+ALTER TABLE tokens DROP COLUMN status;
+DROP TYPE token_status;
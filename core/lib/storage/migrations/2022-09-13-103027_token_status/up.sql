@@ -0,0 +1,5 @@
+CREATE TYPE token_status AS ENUM ('Valid', 'Flagged', 'Quarantined');
+ALTER TABLE tokens ADD COLUMN status token_status NOT NULL DEFAULT 'Valid';
+-- Tokens are looked up by the symbol regardless of the case, so symbols must not differ by the case only.
+DROP INDEX IF EXISTS tokens_symbol_lower_idx;
+CREATE UNIQUE INDEX tokens_symbol_lower_idx ON tokens (lower(symbol));
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fee_quote_snapshots;
+DROP TABLE IF EXISTS ticker_price_history;
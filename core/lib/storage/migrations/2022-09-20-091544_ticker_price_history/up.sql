@@ -0,0 +1,30 @@
+-- Time series of the token prices reported by the ticker.
+-- Old samples are downsampled and removed according to the ticker configuration.
+CREATE TABLE ticker_price_history (
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON UPDATE CASCADE,
+    usd_price NUMERIC NOT NULL,
+    sampled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (token_id, sampled_at)
+);
+
+-- Prices used by the fee ticker for the fees of the submitted transactions, stored to audit the charged fees.
+CREATE TABLE fee_quote_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    -- Hash of the transaction (or the batch) the fee was charged for.
+    tx_hash BYTEA NOT NULL,
+    token_id INTEGER NOT NULL,
+    -- Fee types of the quoted transactions, JSON array.
+    fee_types JSONB NOT NULL,
+    token_price_usd NUMERIC NOT NULL,
+    token_price_updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    eth_price_usd NUMERIC NOT NULL,
+    eth_price_updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    gas_price_wei NUMERIC NOT NULL,
+    total_fee NUMERIC NOT NULL,
+    subsidized_total_fee NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX fee_quote_snapshots_token_id_created_at_idx ON fee_quote_snapshots (token_id, created_at);
+CREATE INDEX fee_quote_snapshots_tx_hash_idx ON fee_quote_snapshots (tx_hash);
+CREATE INDEX fee_quote_snapshots_created_at_idx ON fee_quote_snapshots (created_at);
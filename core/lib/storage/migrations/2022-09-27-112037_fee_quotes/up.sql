@@ -0,0 +1,16 @@
+-- Fee quotes issued by the fee ticker. The quoted fee is accepted for the matching transactions
+-- until the quote expires. Quotes are kept after the expiration to audit the charged fees.
+CREATE TABLE fee_quotes (
+    id BIGSERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL,
+    -- Fee types and addresses of the quoted transactions, JSON array.
+    txs JSONB NOT NULL,
+    total_fee NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Hash of the transaction (or the batch) which used the quote.
+    used_by_tx_hash BYTEA,
+    used_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX fee_quotes_created_at_idx ON fee_quotes (created_at);
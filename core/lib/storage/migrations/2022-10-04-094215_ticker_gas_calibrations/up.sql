@@ -0,0 +1,16 @@
+-- Gas costs calibrated by the fee ticker from the gas used by the confirmed Ethereum transactions.
+-- The latest calibration is used for the fee calculation, the history is kept for the analysis.
+CREATE TABLE ticker_gas_calibrations (
+    id BIGSERIAL PRIMARY KEY,
+    -- Gas used per chunk by the transactions of each aggregated operation type,
+    -- excluding the gas estimated for the operations themselves.
+    commit_cost_per_chunk BIGINT NOT NULL,
+    proof_cost_per_chunk BIGINT NOT NULL,
+    execute_cost_per_chunk BIGINT NOT NULL,
+    -- Smoothed and bounded sum of the costs above used by the fee ticker.
+    amortized_cost_per_chunk BIGINT NOT NULL,
+    -- Smoothed and bounded ratio of the gas price paid by the confirmed transactions
+    -- to the average gas price of the gas adjuster in percent.
+    gas_price_scale_percent BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
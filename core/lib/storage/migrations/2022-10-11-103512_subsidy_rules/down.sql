@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS subsidies_subsidy_type_address_idx;
+ALTER TABLE subsidies DROP COLUMN IF EXISTS address;
+DROP TABLE IF EXISTS subsidy_rules;
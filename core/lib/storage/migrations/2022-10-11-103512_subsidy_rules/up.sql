@@ -0,0 +1,30 @@
+-- Declarative rules describing the transactions which fees are subsidized.
+-- The rule name is used as the `subsidy_type` of the subsidies applied by the rule.
+CREATE TABLE subsidy_rules (
+    name VARCHAR PRIMARY KEY,
+    -- Rules are checked in the ascending order of the priority.
+    priority INT NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    -- Match criteria, any value matches if the criterion is NULL.
+    -- Transaction fee types in their JSON representation.
+    tx_types JSONB NULL,
+    -- Tokens the fee is paid in.
+    token_ids INT[] NULL,
+    -- Addresses of the transaction senders.
+    addresses BYTEA[] NULL,
+    -- IPs of the request origin.
+    ips TEXT[] NULL,
+    valid_from TIMESTAMP WITH TIME ZONE NULL,
+    valid_until TIMESTAMP WITH TIME ZONE NULL,
+    -- USD amounts are stored scaled by 10^6 the same way as in the `subsidies` table.
+    -- Maximum fee paid by the user for the matched transaction.
+    max_fee_usd_scale6 BIGINT NOT NULL,
+    -- Total amount that can be subsidized by the rule.
+    budget_usd_scale6 BIGINT NOT NULL,
+    -- Amount that can be subsidized for a single sender, unlimited if NULL.
+    max_per_address_usd_scale6 BIGINT NULL
+);
+
+-- Sender of the subsidized transactions, used to track the per-address subsidy caps.
+ALTER TABLE subsidies ADD COLUMN address BYTEA NULL;
+CREATE INDEX IF NOT EXISTS subsidies_subsidy_type_address_idx ON subsidies (subsidy_type, address);
@@ -0,0 +1,3 @@
+-- Set once the transaction is proposed to the state keeper. Proposed transactions are going to be
+-- executed, so they cannot be replaced anymore.
+ALTER TABLE mempool_txs ADD COLUMN proposed BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS mempool_evicted_txs;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS chunks;
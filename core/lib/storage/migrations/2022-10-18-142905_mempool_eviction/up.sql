@@ -0,0 +1,9 @@
+-- Minimal amount of chunks used by the standalone transaction. Used to compare fees per chunk when
+-- transactions are evicted from the full mempool.
+ALTER TABLE mempool_txs ADD COLUMN chunks INTEGER;
+
+-- Transactions evicted from the full mempool. Kept for some time to report their status.
+CREATE TABLE IF NOT EXISTS mempool_evicted_txs (
+    tx_hash TEXT PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
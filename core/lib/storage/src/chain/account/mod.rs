@@ -1,4 +1,5 @@
 // Built-in deps
+use std::collections::HashSet;
 use std::time::Instant;
 // External imports
 use num::{BigUint, Zero};
@@ -193,6 +194,35 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(result.is_some())
     }
 
+    /// Returns the addresses of the given ones which belong to the existing accounts.
+    pub async fn filter_existing_accounts(
+        &mut self,
+        addresses: &[Address],
+    ) -> QueryResult<HashSet<Address>> {
+        let start = Instant::now();
+        let addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.as_bytes().to_vec())
+            .collect();
+
+        let records = sqlx::query!(
+            "SELECT address FROM account_creates WHERE address = ANY($1)",
+            &addresses
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let existing = records
+            .into_iter()
+            .map(|record| Address::from_slice(&record.address))
+            .collect();
+        metrics::histogram!(
+            "sql.chain.account.filter_existing_accounts",
+            start.elapsed()
+        );
+        Ok(existing)
+    }
+
     /// Obtains both committed and verified state for the account by its address.
     pub async fn account_state_by_address(
         &mut self,
@@ -448,6 +478,23 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(address)
     }
 
+    /// Returns the ID that would be assigned to the next created account,
+    /// according to the committed state.
+    pub async fn next_free_account_id(&mut self) -> QueryResult<AccountId> {
+        let start = Instant::now();
+        let max_account_id = sqlx::query!(
+            "SELECT MAX(account_id) FROM account_creates WHERE is_create = true AND account_id != $1",
+            i64::from(*NFT_STORAGE_ACCOUNT_ID)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .max;
+
+        let next_free_id = max_account_id.map(|id| id as u32 + 1).unwrap_or_default();
+        metrics::histogram!("sql.chain.account.next_free_account_id", start.elapsed());
+        Ok(AccountId(next_free_id))
+    }
+
     /// Obtains the last committed block that affects the account.
     pub async fn last_committed_block_with_update_for_acc(
         &mut self,
@@ -1,7 +1,14 @@
 // Built-in deps
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+use std::{
+    collections::{HashSet, VecDeque},
+    convert::TryFrom,
+    str::FromStr,
+    time::Instant,
+};
 // External imports
 use itertools::Itertools;
+use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_api_types::v02::pagination::PaginationDirection;
 use zksync_api_types::v02::transaction::{
@@ -11,9 +18,10 @@ use zksync_types::{
     block::IncompleteBlock,
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce,
+    PriorityOp, SerialId, SignedZkSyncTx, TokenLike, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
+use zksync_utils::{big_decimal_to_ratio, biguint_to_big_decimal};
 // Local imports
 use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
 use crate::{QueryResult, StorageProcessor};
@@ -24,6 +32,16 @@ use crate::chain::operations::records::{
 
 pub mod records;
 
+/// Returns the fee token and the fee amount of the transaction as they are stored in the `mempool_txs` table.
+fn fee_columns(tx: &ZkSyncTx) -> (Option<i32>, Option<BigDecimal>) {
+    match tx.get_fee_info() {
+        Some((_, TokenLike::Id(token_id), _, fee)) => {
+            (Some(*token_id as i32), Some(biguint_to_big_decimal(fee)))
+        }
+        _ => (None, None),
+    }
+}
+
 /// Schema for persisting transactions awaiting for the execution.
 ///
 /// This schema holds the transactions that are received by the `mempool` module, but not yet have
@@ -165,14 +183,21 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .eth_sign_data
                 .as_ref()
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let account_id = first_tx_data.account_id().ok().map(|id| *id as i64);
+            let nonce = *first_tx_data.nonce() as i64;
+            let (fee_token, fee) = fee_columns(&first_tx_data.tx);
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data)
-                VALUES ($1, $2, $3, $4)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, account_id, nonce, fee_token, fee)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
                 tx_hash,
                 tx,
                 first_tx_data.created_at,
                 eth_sign_data,
+                account_id,
+                nonce,
+                fee_token,
+                fee,
             )
             .execute(transaction.conn())
             .await?;
@@ -199,15 +224,22 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .eth_sign_data
                 .as_ref()
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let account_id = tx_data.account_id().ok().map(|id| *id as i64);
+            let nonce = *tx_data.nonce() as i64;
+            let (fee_token, fee) = fee_columns(&tx_data.tx);
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_id, nonce, fee_token, fee)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
                 tx_hash,
                 tx,
                 tx_data.created_at,
                 eth_sign_data,
-                batch_id
+                batch_id,
+                account_id,
+                nonce,
+                fee_token,
+                fee,
             )
             .execute(transaction.conn())
             .await?;
@@ -251,15 +283,24 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .eth_sign_data
             .as_ref()
             .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+        let account_id = tx_data.account_id().ok().map(|id| *id as i64);
+        let nonce = *tx_data.nonce() as i64;
+        let (fee_token, fee) = fee_columns(&tx_data.tx);
+        let chunks = tx_data.tx.min_chunks() as i32;
 
         sqlx::query!(
-            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_id, nonce, fee_token, fee, chunks)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
             tx_hash,
             tx,
             tx_data.created_at,
             eth_sign_data,
             batch_id,
+            account_id,
+            nonce,
+            fee_token,
+            fee,
+            chunks,
         )
         .execute(self.0.conn())
         .await?;
@@ -353,6 +394,220 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(mempool_tx)
     }
 
+    /// Returns a queued standalone transaction (i.e. not a part of some batch) of the account
+    /// with the given nonce.
+    pub async fn get_pending_tx_by_nonce(
+        &mut self,
+        account_id: AccountId,
+        nonce: Nonce,
+    ) -> QueryResult<Option<SignedZkSyncTx>> {
+        let start = Instant::now();
+
+        let mempool_tx = sqlx::query_as!(
+            MempoolTx,
+            "SELECT * FROM mempool_txs
+            WHERE account_id = $1 AND nonce = $2 AND batch_id = 0 AND reverted = false
+            ORDER BY id
+            LIMIT 1",
+            *account_id as i64,
+            *nonce as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_pending_tx_by_nonce");
+        mempool_tx
+            .map(SignedZkSyncTx::try_from)
+            .transpose()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Atomically replaces a queued transaction with a new one (e.g. the one paying a higher fee).
+    /// The hash of the replaced transaction is remembered, so its status can be reported later.
+    ///
+    /// Returns `false` and leaves the mempool untouched if the replaced transaction is not
+    /// in the mempool anymore or was already proposed for the execution.
+    pub async fn replace_tx(
+        &mut self,
+        replaced_tx_hash: TxHash,
+        tx_data: &SignedZkSyncTx,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let replaced_tx_hash = hex::encode(replaced_tx_hash.as_ref());
+        let new_tx_hash = hex::encode(tx_data.tx.hash().as_ref());
+
+        let removed = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = $1 AND batch_id = 0 AND reverted = false AND proposed = false",
+            &replaced_tx_hash
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .insert_tx(tx_data)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO mempool_replaced_txs (tx_hash, replaced_by, created_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT (tx_hash) DO UPDATE SET replaced_by = $2, created_at = now()",
+            &replaced_tx_hash,
+            &new_tx_hash
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.replace_tx", start.elapsed());
+        Ok(true)
+    }
+
+    /// Marks transactions as proposed for the execution, so they cannot be replaced anymore.
+    ///
+    /// Returns hashes of the marked transactions. Transactions that were removed from the mempool
+    /// in the meantime (e.g. replaced) are not returned and should not be executed.
+    pub async fn mark_txs_proposed(&mut self, txs: &[TxHash]) -> QueryResult<HashSet<TxHash>> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
+
+        let marked = sqlx::query!(
+            "UPDATE mempool_txs SET proposed = true
+            WHERE tx_hash = ANY($1) AND reverted = false
+            RETURNING tx_hash",
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| TxHash::from_str(&format!("0x{}", row.tx_hash)))
+        .collect::<Result<_, _>>()?;
+
+        metrics::histogram!("sql.chain.mempool.mark_txs_proposed", start.elapsed());
+        Ok(marked)
+    }
+
+    /// Returns the hash of the transaction which has replaced the given one.
+    pub async fn get_replacement_tx_hash(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<TxHash>> {
+        let start = Instant::now();
+
+        let tx_hash = hex::encode(tx_hash.as_ref());
+        let replaced_by = sqlx::query!(
+            "SELECT replaced_by FROM mempool_replaced_txs
+            WHERE tx_hash = $1",
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| TxHash::from_str(&format!("0x{}", record.replaced_by)))
+        .transpose()?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_replacement_tx_hash");
+        Ok(replaced_by)
+    }
+
+    /// Removes records about replaced transactions that are older than `max_age`.
+    pub async fn remove_replaced_txs(&mut self, max_age: chrono::Duration) -> QueryResult<()> {
+        let start = Instant::now();
+
+        let offset = chrono::Utc::now() - max_age;
+        sqlx::query!(
+            "DELETE FROM mempool_replaced_txs WHERE created_at < $1",
+            offset
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.remove_replaced_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the transactions evicted from the full mempool and remembers them, so their
+    /// status can be reported later. Transactions proposed for the execution are not evicted.
+    ///
+    /// Returns hashes of the evicted transactions.
+    pub async fn evict_txs(&mut self, txs: &[TxHash]) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
+
+        let evicted: Vec<String> = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = ANY($1) AND batch_id = 0 AND proposed = false
+            RETURNING tx_hash",
+            &tx_hashes
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.tx_hash)
+        .collect();
+
+        sqlx::query!(
+            "INSERT INTO mempool_evicted_txs (tx_hash, created_at)
+            SELECT u.tx_hash, now() FROM UNNEST ($1::text[]) AS u(tx_hash)
+            ON CONFLICT (tx_hash) DO UPDATE SET created_at = now()",
+            &evicted
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        let evicted = evicted
+            .into_iter()
+            .map(|tx_hash| TxHash::from_str(&format!("0x{}", tx_hash)))
+            .collect::<Result<_, _>>()?;
+
+        metrics::histogram!("sql.chain.mempool.evict_txs", start.elapsed());
+        Ok(evicted)
+    }
+
+    /// Checks whether the transaction was evicted from the full mempool.
+    pub async fn is_tx_evicted(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let tx_hash = hex::encode(tx_hash.as_ref());
+        let evicted = sqlx::query!(
+            "SELECT EXISTS (SELECT 1 FROM mempool_evicted_txs WHERE tx_hash = $1) AS \"evicted!\"",
+            &tx_hash
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .evicted;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "is_tx_evicted");
+        Ok(evicted)
+    }
+
+    /// Removes records about evicted transactions that are older than `max_age`.
+    pub async fn remove_evicted_txs(&mut self, max_age: chrono::Duration) -> QueryResult<()> {
+        let start = Instant::now();
+
+        let offset = chrono::Utc::now() - max_age;
+        sqlx::query!(
+            "DELETE FROM mempool_evicted_txs WHERE created_at < $1",
+            offset
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.remove_evicted_txs", start.elapsed());
+        Ok(())
+    }
+
     /// Removes transactions that are already committed.
     /// Though it's unlikely that mempool schema will ever contain a committed
     /// transaction, it's better to ensure that we won't process the same transaction
@@ -575,6 +830,64 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes priority operations reverted by the Ethereum reorg from the mempool, unless they are
+    /// already executed either in the committed blocks or in the pending block. Returns the ids of
+    /// the executed ones.
+    ///
+    /// Executed operations are locked against concurrent inserts, so the pending block can't be
+    /// updated between the classification and the removal.
+    pub async fn remove_reverted_priority_ops(
+        &mut self,
+        ids: &[SerialId],
+    ) -> QueryResult<Vec<SerialId>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!("LOCK TABLE executed_priority_operations IN SHARE MODE")
+            .execute(transaction.conn())
+            .await?;
+
+        let last_committed_op = transaction
+            .chain()
+            .operations_schema()
+            .get_max_priority_op_serial_id()
+            .await?;
+        let last_pending_op = transaction
+            .chain()
+            .block_schema()
+            .load_pending_block()
+            .await?
+            .and_then(|block| {
+                block
+                    .success_operations
+                    .iter()
+                    .filter_map(|op| match op {
+                        ExecutedOperations::PriorityOp(op) => Some(op.priority_op.serial_id),
+                        ExecutedOperations::Tx(_) => None,
+                    })
+                    .max()
+            });
+        let last_executed_op = last_committed_op.max(last_pending_op);
+
+        let (executed_ops, unprocessed_ops): (Vec<_>, Vec<_>) = ids
+            .iter()
+            .copied()
+            .partition(|serial_id| Some(*serial_id) <= last_executed_op);
+        if !unprocessed_ops.is_empty() {
+            transaction
+                .chain()
+                .mempool_schema()
+                .remove_priority_ops_from_mempool(&unprocessed_ops)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.remove_reverted_priority_ops",
+            start.elapsed()
+        );
+        Ok(executed_ops)
+    }
+
     /// Returns mempool size.
     pub async fn get_mempool_size(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
@@ -588,6 +901,121 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(size.unwrap_or(0) as u32)
     }
 
+    /// Returns the number of transactions of the given account waiting for the execution.
+    pub async fn get_account_pending_txs_count(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<u32> {
+        let start = Instant::now();
+
+        let count = sqlx::query!(
+            "SELECT COUNT(*) FROM mempool_txs
+            WHERE account_id = $1 AND reverted = false",
+            *account_id as i64
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_account_pending_txs_count");
+        Ok(count.unwrap_or(0) as u32)
+    }
+
+    /// Returns the nonce that the next transaction of the account should have, taking into account
+    /// the transactions waiting for the execution, starting from the committed nonce of the account.
+    ///
+    /// Transactions after a nonce gap are not taken into account, since they cannot be executed
+    /// until the gap is filled. Reverted transactions are taken into account only if they were
+    /// executed successfully, since failed transactions don't change the nonce.
+    pub async fn get_pending_nonce(
+        &mut self,
+        account_id: AccountId,
+        committed_nonce: Nonce,
+    ) -> QueryResult<Nonce> {
+        let start = Instant::now();
+
+        let nonces = sqlx::query!(
+            r#"
+            SELECT DISTINCT mempool_txs.nonce AS "nonce!" FROM mempool_txs
+            LEFT JOIN mempool_reverted_txs_meta
+                ON mempool_reverted_txs_meta.tx_hash = mempool_txs.tx_hash
+            WHERE mempool_txs.account_id = $1 AND mempool_txs.nonce >= $2
+                AND (mempool_txs.reverted = false OR mempool_reverted_txs_meta.success = true)
+            ORDER BY 1
+            "#,
+            *account_id as i64,
+            *committed_nonce as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut pending_nonce = committed_nonce;
+        for record in nonces {
+            if record.nonce != *pending_nonce as i64 {
+                break;
+            }
+            *pending_nonce += 1;
+        }
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_pending_nonce");
+        Ok(pending_nonce)
+    }
+
+    /// Returns up to `limit` standalone transactions (i.e. not a part of some batch) that should be
+    /// evicted first from the full mempool, along with their fees in USD per chunk.
+    ///
+    /// Transactions are evicted from the tail of the account nonce chain, so a transaction is
+    /// ranked by the highest fee per chunk among itself and the subsequent transactions of
+    /// the same account. Accounts having a batch or a proposed transaction after it are skipped.
+    /// Transactions paying the fee in a token without a known price are considered to be
+    /// the cheapest ones.
+    pub async fn get_lowest_fee_txs(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<(TxHash, Ratio<BigUint>)>> {
+        let start = Instant::now();
+
+        let records = sqlx::query!(
+            r#"
+            SELECT tx_hash AS "tx_hash!", fee_per_chunk AS "fee_per_chunk!" FROM (
+                SELECT mempool_txs.id, mempool_txs.tx_hash, mempool_txs.nonce,
+                    MAX(
+                        COALESCE(mempool_txs.fee * ticker_price.usd_price / power(10::numeric, tokens.decimals), 0)
+                        / GREATEST(COALESCE(mempool_txs.chunks, 1), 1)
+                    ) OVER account_tail AS fee_per_chunk,
+                    BOOL_OR(mempool_txs.batch_id <> 0 OR mempool_txs.proposed) OVER account_tail AS pinned
+                FROM mempool_txs
+                LEFT JOIN tokens ON tokens.id = mempool_txs.fee_token
+                LEFT JOIN ticker_price ON ticker_price.token_id = mempool_txs.fee_token
+                WHERE mempool_txs.reverted = false
+                WINDOW account_tail AS (
+                    PARTITION BY mempool_txs.account_id
+                    ORDER BY mempool_txs.nonce DESC, mempool_txs.id DESC
+                    ROWS UNBOUNDED PRECEDING
+                )
+            ) AS txs
+            WHERE NOT pinned
+            ORDER BY fee_per_chunk ASC, nonce DESC, id DESC
+            LIMIT $1
+            "#,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let txs = records
+            .into_iter()
+            .map(|record| {
+                let tx_hash = TxHash::from_str(&format!("0x{}", record.tx_hash))?;
+                let fee_per_chunk = big_decimal_to_ratio(&record.fee_per_chunk)?;
+                Ok((tx_hash, fee_per_chunk))
+            })
+            .collect::<QueryResult<Vec<_>>>()?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_lowest_fee_txs");
+        Ok(txs)
+    }
+
     /// Get info about batch in mempool.
     pub async fn get_queued_batch_info(
         &mut self,
@@ -869,6 +1297,8 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
             let block_index = block_index.map(|b| b as i32);
             let nonce = signed_tx.nonce();
+            let account_id = signed_tx.account_id().ok().map(|id| *id as i64);
+            let (fee_token, fee) = fee_columns(&signed_tx.tx);
             let from_account = signed_tx.from_account().as_bytes().to_vec();
             let to_account = signed_tx.to_account().map(|a| a.as_bytes().to_vec());
             let primary_account_address = signed_tx.account().as_bytes().to_vec();
@@ -909,14 +1339,18 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?;
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted)
-                VALUES ($1, $2, $3, $4, $5, $6, true)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted, account_id, nonce, fee_token, fee)
+                VALUES ($1, $2, $3, $4, $5, $6, true, $7, $8, $9, $10)",
                 tx_hash,
                 tx_value,
                 created_at,
                 eth_sign_data,
                 batch_id.unwrap_or(0i64),
                 next_priority_op_serial_id as i64,
+                account_id,
+                *nonce as i64,
+                fee_token,
+                fee,
             )
             .execute(transaction.conn())
             .await?;
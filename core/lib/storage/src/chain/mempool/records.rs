@@ -3,7 +3,7 @@ use std::convert::TryFrom;
 
 // External imports
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{types::BigDecimal, FromRow};
 
 // Workspace imports
 use zksync_types::{PriorityOp, SignedZkSyncTx, H256};
@@ -33,6 +33,18 @@ pub(crate) struct MempoolTx {
     pub next_priority_op_serial_id: Option<i64>,
     #[allow(dead_code)]
     pub reverted: bool,
+    #[allow(dead_code)]
+    pub account_id: Option<i64>,
+    #[allow(dead_code)]
+    pub nonce: Option<i64>,
+    #[allow(dead_code)]
+    pub fee_token: Option<i32>,
+    #[allow(dead_code)]
+    pub fee: Option<BigDecimal>,
+    #[allow(dead_code)]
+    pub proposed: bool,
+    #[allow(dead_code)]
+    pub chunks: Option<i32>,
 }
 
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
@@ -682,8 +682,13 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         block_number: BlockNumber,
     ) -> QueryResult<AccountUpdates> {
         let start = Instant::now();
+        // There are no updates before the genesis block, so the block zero has no diff.
+        let previous_block = match block_number.checked_sub(1) {
+            Some(previous_block) => BlockNumber(previous_block),
+            None => return Ok(AccountUpdates::new()),
+        };
         let result = self
-            .load_state_diff(block_number - 1, Some(block_number))
+            .load_state_diff(previous_block, Some(block_number))
             .await
             .map(|diff| diff.unwrap_or_default().1);
 
@@ -691,6 +696,164 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         result
     }
 
+    /// Returns the number of account updates made in a specific block.
+    pub async fn get_state_diff_count_for_block(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+            SELECT (
+                (SELECT COUNT(*) FROM account_balance_updates WHERE block_number = $1)
+                + (SELECT COUNT(*) FROM account_creates WHERE block_number = $1)
+                + (SELECT COUNT(*) FROM account_pubkey_updates WHERE block_number = $1)
+                + (SELECT COUNT(*) FROM mint_nft_updates WHERE block_number = $1)
+            ) AS "count!"
+            "#,
+            i64::from(*block_number)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!(
+            "sql.chain.state.get_state_diff_count_for_block",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
+    /// Loads at most `limit` account updates made in a specific block, skipping
+    /// the first `offset` of them. Updates are ordered the same way as in `load_state_diff_for_block`.
+    pub async fn load_state_diff_page_for_block(
+        &mut self,
+        block_number: BlockNumber,
+        offset: u32,
+        limit: u32,
+    ) -> QueryResult<AccountUpdates> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // Update order ids are unique within the block, so the requested page is
+        // determined by the range of ids it covers.
+        let order_ids: Vec<i32> = sqlx::query!(
+            r#"
+            SELECT update_order_id AS "update_order_id!" FROM (
+                SELECT update_order_id FROM account_balance_updates WHERE block_number = $1
+                UNION ALL
+                SELECT update_order_id FROM account_creates WHERE block_number = $1
+                UNION ALL
+                SELECT update_order_id FROM account_pubkey_updates WHERE block_number = $1
+                UNION ALL
+                SELECT update_order_id FROM mint_nft_updates WHERE block_number = $1
+            ) AS updates
+            ORDER BY update_order_id
+            LIMIT $2 OFFSET $3
+            "#,
+            i64::from(*block_number),
+            i64::from(limit),
+            i64::from(offset)
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.update_order_id)
+        .collect();
+
+        let (first_id, last_id) = match (order_ids.first(), order_ids.last()) {
+            (Some(first_id), Some(last_id)) => (*first_id, *last_id),
+            _ => {
+                transaction.commit().await?;
+                return Ok(AccountUpdates::new());
+            }
+        };
+
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            r#"
+            SELECT * FROM account_balance_updates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id <= $3
+            "#,
+            i64::from(*block_number),
+            first_id,
+            last_id
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            r#"
+            SELECT * FROM account_creates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id <= $3
+            "#,
+            i64::from(*block_number),
+            first_id,
+            last_id
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            r#"
+            SELECT * FROM account_pubkey_updates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id <= $3
+            "#,
+            i64::from(*block_number),
+            first_id,
+            last_id
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mint_nft_diffs = sqlx::query_as!(
+            StorageMintNFTUpdate,
+            r#"
+            SELECT * FROM mint_nft_updates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id <= $3
+            "#,
+            i64::from(*block_number),
+            first_id,
+            last_id
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        let mut account_diff = Vec::new();
+        account_diff.extend(
+            account_balance_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_creation_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_pubkey_diff
+                .into_iter()
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(mint_nft_diffs.into_iter().map(StorageAccountDiff::from));
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+        let account_updates = account_diff
+            .into_iter()
+            .take(limit as usize)
+            .map(|d| d.into())
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.state.load_state_diff_page_for_block",
+            start.elapsed()
+        );
+        Ok(account_updates)
+    }
+
     pub async fn get_mint_nft_update(&mut self, token_id: TokenId) -> QueryResult<Option<NFT>> {
         let start = Instant::now();
         let nft = sqlx::query_as!(
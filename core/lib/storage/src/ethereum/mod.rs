@@ -2,7 +2,7 @@
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
 use anyhow::format_err;
-use num::{BigInt, BigUint};
+use num::{bigint::ToBigInt, BigInt, BigUint};
 use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_types::{
@@ -11,10 +11,13 @@ use zksync_types::{
     event::{
         account::AccountStateChangeStatus, block::BlockStatus, transaction::TransactionStatus,
     },
-    BlockNumber, H256, U256,
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
-use self::records::{ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
+use self::records::{
+    ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation, StorageETHTxFee,
+    StorageGasUsage, StorageProcessedOperations,
+};
 use crate::{chain::operations::records::StoredAggregatedOperation, QueryResult, StorageProcessor};
 use chrono::{DateTime, Utc};
 
@@ -93,6 +96,9 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 .expect("Stored operation type must have a valid value");
             let last_used_gas_price =
                 U256::from_str(&eth_op.last_used_gas_price.to_string()).unwrap();
+            let last_used_priority_fee = eth_op
+                .last_used_priority_fee
+                .map(|fee| U256::from_str(&fee.to_string()).unwrap());
             let used_tx_hashes = eth_tx_hashes
                 .iter()
                 .map(|entry| H256::from_slice(&entry.tx_hash))
@@ -106,10 +112,15 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 nonce: eth_op.nonce.into(),
                 last_deadline_block: eth_op.last_deadline_block as u64,
                 last_used_gas_price,
+                last_used_priority_fee,
                 used_tx_hashes,
                 encoded_tx_data: eth_op.raw_tx,
                 confirmed: eth_op.confirmed,
                 final_hash,
+                failed_attempts: eth_op.failed_attempts as u64,
+                operator: eth_op
+                    .operator_address
+                    .map(|address| Address::from_slice(&address)),
             };
 
             ops.push_back(eth_op);
@@ -221,6 +232,31 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         operation: Option<(i64, AggregatedOperation)>,
         last_deadline_block: i64,
         last_used_gas_price: BigUint,
+        last_used_priority_fee: Option<BigUint>,
+        raw_tx: Vec<u8>,
+    ) -> QueryResult<InsertedOperationResponse> {
+        self.save_new_operator_eth_tx(
+            None,
+            op_type,
+            operation,
+            last_deadline_block,
+            last_used_gas_price,
+            last_used_priority_fee,
+            raw_tx,
+        )
+        .await
+    }
+
+    /// Same as `save_new_eth_tx`, but the nonce is assigned from the given operator account.
+    /// The main operator account is used if `operator` is `None`.
+    pub async fn save_new_operator_eth_tx(
+        &mut self,
+        operator: Option<Address>,
+        op_type: AggregatedActionType,
+        operation: Option<(i64, AggregatedOperation)>,
+        last_deadline_block: i64,
+        last_used_gas_price: BigUint,
+        last_used_priority_fee: Option<BigUint>,
         raw_tx: Vec<u8>,
     ) -> QueryResult<InsertedOperationResponse> {
         let start = Instant::now();
@@ -228,19 +264,23 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
 
         // It's important to assign nonce within the same db transaction
         // as saving the operation to avoid the state divergence.
-        let nonce = EthereumSchema(&mut transaction).get_next_nonce().await?;
+        let nonce = EthereumSchema(&mut transaction)
+            .get_next_operator_nonce(operator)
+            .await?;
 
         // Create and insert the operation.
 
         // Obtain the operation ID for the follow-up queried.
         let last_used_gas_price = BigDecimal::from(BigInt::from(last_used_gas_price));
+        let last_used_priority_fee =
+            last_used_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         let eth_op_id = sqlx::query!(
             "
-                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO eth_operations (op_type, nonce, last_deadline_block, last_used_gas_price, last_used_priority_fee, raw_tx, operator_address)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
                 RETURNING id
             ",
-            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, raw_tx,
+            op_type.to_string(), nonce, last_deadline_block, last_used_gas_price, last_used_priority_fee, raw_tx, operator.as_ref().map(Address::as_bytes),
         )
         .fetch_one(transaction.conn())
         .await?
@@ -274,21 +314,29 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(response)
     }
 
-    /// Returns whether the operation with the given id was confirmed.
-    /// If the operation with such id does not exist, then it returns Ok(false).
-    pub async fn is_aggregated_op_confirmed(&mut self, id: i64) -> QueryResult<bool> {
+    /// Returns whether the last Ethereum operation of the given types stored before the
+    /// operation with the given id was confirmed.
+    /// If there is no such operation, then it returns Ok(true).
+    pub async fn is_previous_eth_op_confirmed(
+        &mut self,
+        eth_op_id: i64,
+        op_types: &[AggregatedActionType],
+    ) -> QueryResult<bool> {
         let start = Instant::now();
-        let confirmed = sqlx::query_as!(
-            StorageETHOperation,
-            "SELECT * FROM eth_operations WHERE id <= $1 ORDER BY ID DESC LIMIT 1",
-            id
+        let op_types: Vec<_> = op_types.iter().map(ToString::to_string).collect();
+        let confirmed = sqlx::query!(
+            "SELECT confirmed FROM eth_operations
+            WHERE id < $1 AND op_type = ANY($2)
+            ORDER BY id DESC LIMIT 1",
+            eth_op_id,
+            &op_types
         )
         .fetch_optional(self.0.conn())
         .await?
         .map(|op| op.confirmed)
-        .unwrap_or(false);
+        .unwrap_or(true);
 
-        metrics::histogram!("sql.ethereum.is_aggregated_op_confirmed", start.elapsed());
+        metrics::histogram!("sql.ethereum.is_previous_eth_op_confirmed", start.elapsed());
         Ok(confirmed)
     }
 
@@ -323,21 +371,24 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
     }
 
     /// Updates the Ethereum operation by adding a new tx data.
-    /// The new deadline block / gas value are placed instead of old values to the main entry.
+    /// The new deadline block / gas values are placed instead of old values to the main entry.
     pub async fn update_eth_tx(
         &mut self,
         eth_op_id: i64,
         new_deadline_block: i64,
         new_gas_value: BigUint,
+        new_priority_fee: Option<BigUint>,
     ) -> QueryResult<()> {
         let start = Instant::now();
         // Update the stored tx.
         let new_gas_price = BigDecimal::from(BigInt::from(new_gas_value));
+        let new_priority_fee = new_priority_fee.map(|fee| BigDecimal::from(BigInt::from(fee)));
         sqlx::query!(
             "UPDATE eth_operations 
-            SET last_used_gas_price = $1, last_deadline_block = $2
-            WHERE id = $3",
+            SET last_used_gas_price = $1, last_used_priority_fee = $2, last_deadline_block = $3
+            WHERE id = $4",
             new_gas_price,
+            new_priority_fee,
             new_deadline_block,
             eth_op_id
         )
@@ -486,6 +537,67 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(params)
     }
 
+    /// Prepares the Ethereum operation with a failed transaction to be sent again.
+    ///
+    /// The nonce of the failed transaction is already used, so the operation gets the next
+    /// available nonce of the operator account (the main one if `operator` is `None`).
+    /// The operation is reassigned to that account. Hashes of the previously sent transactions
+    /// are removed, since none of them can be confirmed anymore. Returns the new nonce of the operation.
+    pub async fn reset_failed_eth_tx(
+        &mut self,
+        eth_op_id: i64,
+        operator: Option<Address>,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let nonce = EthereumSchema(&mut transaction)
+            .get_next_operator_nonce(operator)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE eth_operations
+            SET nonce = $1, failed_attempts = failed_attempts + 1, operator_address = $3
+            WHERE id = $2",
+            nonce,
+            eth_op_id,
+            operator.as_ref().map(Address::as_bytes)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!("DELETE FROM eth_tx_hashes WHERE eth_op_id = $1", eth_op_id)
+            .execute(transaction.conn())
+            .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.ethereum.reset_failed_eth_tx", start.elapsed());
+        Ok(nonce)
+    }
+
+    /// Sets the flag showing whether the Ethereum sender is paused after a failed transaction.
+    pub async fn set_eth_sender_paused(&mut self, paused: bool) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_parameters SET paused = $1 WHERE id = true",
+            paused
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.set_eth_sender_paused", start.elapsed());
+        Ok(())
+    }
+
+    /// Checks whether the Ethereum sender is paused after a failed transaction.
+    pub async fn is_eth_sender_paused(&mut self) -> QueryResult<bool> {
+        let start = Instant::now();
+        let params = self.load_eth_params().await?;
+
+        metrics::histogram!("sql.ethereum.is_eth_sender_paused", start.elapsed());
+        Ok(params.paused)
+    }
+
     /// Marks the stored Ethereum transaction as confirmed (and thus the associated `Operation`
     /// is marked as confirmed as well).
     pub async fn confirm_eth_tx(&mut self, hash: &H256) -> QueryResult<()> {
@@ -580,6 +692,175 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores the fee paid for the mined Ethereum transaction along with the block range
+    /// of the associated aggregated operation. Failed transactions are stored as well, since
+    /// the gas used by them is paid anyway.
+    pub async fn save_eth_tx_fee(
+        &mut self,
+        hash: &H256,
+        gas_used: BigUint,
+        gas_price: BigUint,
+        success: bool,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let eth_op_id = EthereumSchema(&mut transaction).get_eth_op_id(hash).await?;
+        let fee = BigDecimal::from(BigInt::from(&gas_used * &gas_price));
+        let gas_used = BigDecimal::from(BigInt::from(gas_used));
+        let gas_price = BigDecimal::from(BigInt::from(gas_price));
+
+        sqlx::query!(
+            "INSERT INTO eth_tx_fees (tx_hash, eth_op_id, op_type, from_block, to_block, gas_used, gas_price, fee, success)
+            SELECT $1, eth_operations.id, eth_operations.op_type, aggregate_operations.from_block, aggregate_operations.to_block, $3, $4, $5, $6
+            FROM eth_operations
+            LEFT JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.eth_op_id = eth_operations.id
+            LEFT JOIN aggregate_operations ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+            WHERE eth_operations.id = $2
+            ON CONFLICT (tx_hash) DO NOTHING",
+            hash.as_bytes(),
+            eth_op_id,
+            gas_used,
+            gas_price,
+            fee,
+            success
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.ethereum.save_eth_tx_fee", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the total fee paid for the Ethereum transactions of the given type mined
+    /// since the given moment.
+    pub async fn load_total_eth_tx_fee(
+        &mut self,
+        op_type: AggregatedActionType,
+        since: DateTime<Utc>,
+    ) -> QueryResult<BigUint> {
+        let start = Instant::now();
+        let total_fee = sqlx::query!(
+            r#"SELECT SUM(fee) as "total_fee" FROM eth_tx_fees
+            WHERE op_type = $1 AND confirmed_at >= $2"#,
+            op_type.to_string(),
+            since
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .total_fee
+        .map(|fee| fee.to_bigint().unwrap().to_biguint().unwrap())
+        .unwrap_or_default();
+
+        metrics::histogram!("sql.ethereum.load_total_eth_tx_fee", start.elapsed());
+        Ok(total_fee)
+    }
+
+    /// Loads the fees paid for the mined Ethereum transactions of the aggregated operations
+    /// including any of the blocks in the given range.
+    pub async fn load_eth_tx_fees(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<StorageETHTxFee>> {
+        let start = Instant::now();
+        let fees = sqlx::query_as!(
+            StorageETHTxFee,
+            "SELECT * FROM eth_tx_fees
+            WHERE from_block <= $2 AND to_block >= $1
+            ORDER BY eth_op_id, confirmed_at",
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_eth_tx_fees", start.elapsed());
+        Ok(fees)
+    }
+
+    /// Loads the gas used by the Ethereum transactions mined since the given moment and
+    /// the average gas price paid for it, grouped by the type of the aggregated operation.
+    /// Every group also contains the totals of the blocks processed by these transactions.
+    /// Gas used by the failed transactions is included, while their blocks are only counted
+    /// once processed successfully.
+    pub async fn load_gas_usage(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<StorageGasUsage>> {
+        let start = Instant::now();
+        let usage = sqlx::query_as!(
+            StorageGasUsage,
+            r#"
+            SELECT
+                fees.op_type as "op_type!",
+                SUM(fees.gas_used)::bigint as "gas_used!",
+                COALESCE(SUM(fees.fee) / NULLIF(SUM(fees.gas_used), 0), 0)::bigint as "gas_price!",
+                COALESCE(SUM(blocks_totals.blocks) FILTER (WHERE fees.success), 0)::bigint as "blocks!",
+                COALESCE(SUM(blocks_totals.commit_gas_limit) FILTER (WHERE fees.success), 0)::bigint as "commit_gas_limit!",
+                COALESCE(SUM(blocks_totals.verify_gas_limit) FILTER (WHERE fees.success), 0)::bigint as "verify_gas_limit!"
+            FROM eth_tx_fees AS fees
+            CROSS JOIN LATERAL (
+                SELECT
+                    COUNT(*) AS blocks,
+                    COALESCE(SUM(commit_gas_limit), 0) AS commit_gas_limit,
+                    COALESCE(SUM(verify_gas_limit), 0) AS verify_gas_limit
+                FROM blocks
+                WHERE blocks.number >= fees.from_block AND blocks.number <= fees.to_block
+            ) AS blocks_totals
+            WHERE fees.confirmed_at >= $1 AND fees.from_block IS NOT NULL
+            GROUP BY fees.op_type
+            ORDER BY fees.op_type
+            "#,
+            since
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_gas_usage", start.elapsed());
+        Ok(usage)
+    }
+
+    /// Loads the number of the operations executed in the blocks processed by the successful
+    /// Ethereum transactions mined since the given moment, grouped by the type of the aggregated
+    /// operation and the type of the executed operation. Failed transactions are not included.
+    pub async fn load_processed_operations(
+        &mut self,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<StorageProcessedOperations>> {
+        let start = Instant::now();
+        let operations = sqlx::query_as!(
+            StorageProcessedOperations,
+            r#"
+            SELECT
+                fees.op_type as "op_type!",
+                executed.operation_type as "operation_type!",
+                COUNT(*) as "count!"
+            FROM eth_tx_fees AS fees
+            INNER JOIN (
+                SELECT block_number, operation->>'type' AS operation_type
+                FROM executed_transactions
+                WHERE success = true
+                UNION ALL
+                SELECT block_number, operation->>'type' AS operation_type
+                FROM executed_priority_operations
+            ) AS executed
+            ON executed.block_number >= fees.from_block AND executed.block_number <= fees.to_block
+            WHERE fees.confirmed_at >= $1 AND fees.success = true
+            GROUP BY fees.op_type, executed.operation_type
+            ORDER BY fees.op_type, executed.operation_type
+            "#,
+            since
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_processed_operations", start.elapsed());
+        Ok(operations)
+    }
+
     /// Obtains the next nonce to use and updates the corresponding entry in the database
     /// for the next invocation.
     ///
@@ -612,6 +893,101 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(old_nonce_value)
     }
 
+    /// Stores the operator account used in addition to the main one, if it's not stored yet.
+    /// The gas price limit of the account is initialized with the one of the main account.
+    pub async fn initialize_operator_account(
+        &mut self,
+        address: Address,
+        nonce: i64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operator_accounts (address, nonce, gas_price_limit)
+            SELECT $1, $2, gas_price_limit FROM eth_parameters WHERE id = true
+            ON CONFLICT (address) DO NOTHING",
+            address.as_bytes(),
+            nonce
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.initialize_operator_account", start.elapsed());
+        Ok(())
+    }
+
+    /// Obtains the next nonce of the operator account and updates the stored one.
+    /// The main operator account is used if `operator` is `None`.
+    pub async fn get_next_operator_nonce(&mut self, operator: Option<Address>) -> QueryResult<i64> {
+        let address = match operator {
+            Some(address) => address,
+            None => return self.get_next_nonce().await,
+        };
+
+        let start = Instant::now();
+        let new_nonce = sqlx::query!(
+            "UPDATE eth_operator_accounts
+            SET nonce = nonce + 1
+            WHERE address = $1
+            RETURNING nonce",
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .nonce;
+
+        metrics::histogram!("sql.ethereum.get_next_operator_nonce", start.elapsed());
+        Ok(new_nonce - 1)
+    }
+
+    /// Loads the gas price limit of the operator account used by GasAdjuster.
+    pub async fn load_operator_gas_price_limit(&mut self, address: Address) -> QueryResult<U256> {
+        let start = Instant::now();
+        let gas_price_limit = sqlx::query!(
+            "SELECT gas_price_limit FROM eth_operator_accounts WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .gas_price_limit;
+
+        let gas_price_limit =
+            U256::try_from(gas_price_limit).expect("Negative gas limit value stored in DB");
+
+        metrics::histogram!(
+            "sql.ethereum.load_operator_gas_price_limit",
+            start.elapsed()
+        );
+        Ok(gas_price_limit)
+    }
+
+    /// Updates the gas price limit and average gas price of the operator account used by GasAdjuster.
+    pub async fn update_operator_gas_price(
+        &mut self,
+        address: Address,
+        gas_price_limit: U256,
+        average_gas_price: U256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let gas_price_limit: i64 =
+            i64::try_from(gas_price_limit).expect("Can't convert U256 to i64");
+        let average_gas_price: i64 =
+            i64::try_from(average_gas_price).expect("Can't convert U256 to i64");
+
+        sqlx::query!(
+            "UPDATE eth_operator_accounts
+            SET gas_price_limit = $1, average_gas_price = $2
+            WHERE address = $3",
+            gas_price_limit,
+            average_gas_price,
+            address.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.update_operator_gas_price", start.elapsed());
+        Ok(())
+    }
+
     /// Method that internally initializes the `eth_parameters` table.
     /// Since in db tests the database is empty, we must provide a possibility
     /// to initialize required db fields.
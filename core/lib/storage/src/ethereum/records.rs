@@ -15,6 +15,9 @@ pub struct StorageETHOperation {
     pub last_deadline_block: i64,
     pub last_used_gas_price: BigDecimal,
     pub created_at: Option<DateTime<Utc>>,
+    pub last_used_priority_fee: Option<BigDecimal>,
+    pub failed_attempts: i64,
+    pub operator_address: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -30,6 +33,9 @@ pub struct ETHOperationData {
     pub agg_op_id: Option<i64>,
     pub arguments: Option<serde_json::Value>,
     pub created_at: Option<DateTime<Utc>>,
+    pub last_used_priority_fee: Option<BigDecimal>,
+    pub failed_attempts: i64,
+    pub operator_address: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
@@ -39,6 +45,42 @@ pub struct ETHTxHash {
     pub tx_hash: Vec<u8>,
 }
 
+/// Fee paid for the mined Ethereum transaction.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageETHTxFee {
+    pub tx_hash: Vec<u8>,
+    pub eth_op_id: i64,
+    pub op_type: String,
+    pub from_block: Option<i64>,
+    pub to_block: Option<i64>,
+    pub gas_used: BigDecimal,
+    pub gas_price: BigDecimal,
+    pub fee: BigDecimal,
+    pub success: bool,
+    pub confirmed_at: DateTime<Utc>,
+}
+
+/// Gas used by the confirmed Ethereum transactions of the same type and the average gas price
+/// paid for it, along with the number and the estimated gas limits of the processed blocks.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageGasUsage {
+    pub op_type: String,
+    pub gas_used: i64,
+    pub gas_price: i64,
+    pub blocks: i64,
+    pub commit_gas_limit: i64,
+    pub verify_gas_limit: i64,
+}
+
+/// Number of the operations of the same type in the blocks processed by the confirmed
+/// Ethereum transactions of the same type.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageProcessedOperations {
+    pub op_type: String,
+    pub operation_type: String,
+    pub count: i64,
+}
+
 #[derive(Debug, FromRow, PartialEq)]
 pub struct ETHParams {
     pub id: bool,
@@ -48,6 +90,7 @@ pub struct ETHParams {
     pub last_committed_block: i64,
     pub last_verified_block: i64,
     pub last_executed_block: i64,
+    pub paused: bool,
 }
 
 /// A slice of `ETHParams` structure with only stats part in it.
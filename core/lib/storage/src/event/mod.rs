@@ -94,6 +94,36 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         Ok(events)
     }
 
+    /// Load at most `limit` events from the database with the `id` greater than `from`.
+    /// Used to replay the history for the clients that resume the event stream.
+    pub async fn fetch_events_batch(
+        &mut self,
+        from: EventId,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let start = Instant::now();
+        let events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT
+                id,
+                block_number,
+                event_type as "event_type!: EventType",
+                event_data
+            FROM events WHERE id > $1
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+            *from as i64,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.event.fetch_events_batch", start.elapsed());
+        Ok(events)
+    }
+
     /// Load the id of the latest event in the database.
     /// Returns `None` if the `events` table is empty.
     pub async fn get_last_event_id(&mut self) -> QueryResult<Option<EventId>> {
@@ -37,6 +37,8 @@
 //! - ethereum, for the data associated with the Ethereum blockchain.
 //! - prover, for the data on prover jobs, proofs, etc.
 //! - tokens, for storing and loading known tokens.
+//! - ticker, for the token price history and the prices used for the fee quotes.
+//! - subsidy_rules, for the rules describing the subsidized transactions.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
 //!
 //! The chain module includes the following schemas:
@@ -98,7 +100,9 @@ pub mod forced_exit_requests;
 pub mod listener;
 pub mod misc;
 pub mod prover;
+pub mod subsidy_rules;
 pub mod test_data;
+pub mod ticker;
 pub mod tokens;
 pub mod utils;
 
@@ -243,6 +247,14 @@ impl<'a> StorageProcessor<'a> {
         misc::MiscSchema(self)
     }
 
+    pub fn ticker_schema(&mut self) -> ticker::TickerSchema<'_, 'a> {
+        ticker::TickerSchema(self)
+    }
+
+    pub fn subsidy_rules_schema(&mut self) -> subsidy_rules::SubsidyRulesSchema<'_, 'a> {
+        subsidy_rules::SubsidyRulesSchema(self)
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
@@ -2,6 +2,8 @@
 use std::time::Instant;
 // External imports
 use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::Address;
 // Local imports
 
 use self::records::Subsidy;
@@ -22,8 +24,8 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
 
         sqlx::query!(
             r#"
-            INSERT INTO subsidies ( tx_hash, usd_amount_scale6, full_cost_usd_scale6, token_id, token_amount, full_cost_token, subsidy_type )
-            VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+            INSERT INTO subsidies ( tx_hash, usd_amount_scale6, full_cost_usd_scale6, token_id, token_amount, full_cost_token, subsidy_type, address )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 )
             "#,
             subsidy.tx_hash.as_ref(),
             subsidy.usd_amount_scaled as i64,
@@ -31,7 +33,8 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
             subsidy.token_id.0 as i32,
             subsidy.token_amount,
             subsidy.full_cost_token,
-            subsidy.subsidy_type
+            subsidy.subsidy_type,
+            subsidy.address.as_ref().map(|address| address.as_bytes())
         )
         .execute(self.0.conn())
         .await?;
@@ -40,6 +43,74 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores the subsidy if the total amount subsidized by its type stays within `budget_usd_scaled`
+    /// and the amount subsidized for its sender stays within `max_per_address_usd_scaled`.
+    /// Subsidies of the same type are stored one at a time, so the concurrent requests can't exceed
+    /// the limits. Returns the id of the stored subsidy or `None` if the limits are exceeded.
+    pub async fn store_subsidy_within_budget(
+        &mut self,
+        subsidy: Subsidy,
+        budget_usd_scaled: u64,
+        max_per_address_usd_scaled: Option<u64>,
+    ) -> QueryResult<Option<i32>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // The lock is held until the end of the outermost transaction.
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtext($1))",
+            subsidy.subsidy_type
+        )
+        .execute(transaction.conn())
+        .await?;
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO subsidies ( tx_hash, usd_amount_scale6, full_cost_usd_scale6, token_id, token_amount, full_cost_token, subsidy_type, address )
+            SELECT $1::bytea, $2::bigint, $3::bigint, $4::integer, $5::numeric, $6::numeric, $7::varchar, $8::bytea
+            WHERE (
+                SELECT COALESCE(SUM(usd_amount_scale6), 0) FROM subsidies
+                WHERE subsidy_type = $7
+            ) + $2 <= $9::bigint
+            AND (
+                $10::bigint IS NULL OR (
+                    SELECT COALESCE(SUM(usd_amount_scale6), 0) FROM subsidies
+                    WHERE subsidy_type = $7 AND address = $8
+                ) + $2 <= $10
+            )
+            RETURNING id
+            "#,
+            subsidy.tx_hash.as_ref(),
+            subsidy.usd_amount_scaled as i64,
+            subsidy.full_cost_usd_scaled as i64,
+            subsidy.token_id.0 as i32,
+            subsidy.token_amount,
+            subsidy.full_cost_token,
+            subsidy.subsidy_type,
+            subsidy.address.as_ref().map(|address| address.as_bytes()),
+            budget_usd_scaled as i64,
+            max_per_address_usd_scaled.map(|cap| cap as i64)
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .map(|row| row.id);
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.misc.store_subsidy_within_budget", start.elapsed());
+        Ok(id)
+    }
+
+    /// Removes the subsidies with the given ids, e.g. if the subsidized transaction is rejected.
+    pub async fn remove_subsidies(&mut self, ids: &[i32]) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!("DELETE FROM subsidies WHERE id = ANY($1)", ids)
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.misc.remove_subsidies", start.elapsed());
+        Ok(())
+    }
+
     /// Loads tokens from the database starting from the given id with the given limit in the ascending order.
     pub async fn get_total_used_subsidy_for_type(
         &mut self,
@@ -64,4 +135,31 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         metrics::histogram!("sql.token.get_total_used_subsidy_for_type", start.elapsed());
         Ok(sum)
     }
+
+    /// Loads the total amount subsidized for the transactions of the given sender.
+    pub async fn get_total_used_subsidy_for_address(
+        &mut self,
+        subsidy_type: &str,
+        address: Address,
+    ) -> QueryResult<BigDecimal> {
+        let start = Instant::now();
+        let sum = sqlx::query!(
+            r#"
+            SELECT SUM(usd_amount_scale6) as total FROM subsidies
+            WHERE subsidy_type = $1 AND address = $2
+            "#,
+            subsidy_type,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .total
+        .unwrap_or_else(|| BigDecimal::from(0));
+
+        metrics::histogram!(
+            "sql.misc.get_total_used_subsidy_for_address",
+            start.elapsed()
+        );
+        Ok(sum)
+    }
 }
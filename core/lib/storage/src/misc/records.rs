@@ -2,7 +2,7 @@
 use sqlx::types::BigDecimal;
 // Workspace imports
 // Local imports
-use zksync_types::{tx::TxHash, TokenId};
+use zksync_types::{tx::TxHash, Address, TokenId};
 
 pub struct Subsidy {
     pub tx_hash: TxHash,
@@ -12,4 +12,6 @@ pub struct Subsidy {
     pub token_amount: BigDecimal,
     pub full_cost_token: BigDecimal,
     pub subsidy_type: String,
+    /// Sender of the subsidized transaction.
+    pub address: Option<Address>,
 }
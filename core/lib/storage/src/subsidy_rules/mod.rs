@@ -0,0 +1,96 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+// Local imports
+use self::records::StorageSubsidyRule;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Subsidy rules schema stores the rules describing the transactions which fees are subsidized.
+/// The amounts subsidized by the rules are stored by the `MiscSchema`.
+#[derive(Debug)]
+pub struct SubsidyRulesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> SubsidyRulesSchema<'a, 'c> {
+    /// Loads all the subsidy rules in the order they should be checked.
+    pub async fn load_subsidy_rules(&mut self) -> QueryResult<Vec<StorageSubsidyRule>> {
+        let start = Instant::now();
+        let rules = sqlx::query_as!(
+            StorageSubsidyRule,
+            "SELECT * FROM subsidy_rules ORDER BY priority ASC, name ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_rules.load_subsidy_rules", start.elapsed());
+        Ok(rules)
+    }
+
+    /// Loads the subsidy rule by its name.
+    pub async fn load_subsidy_rule(
+        &mut self,
+        name: &str,
+    ) -> QueryResult<Option<StorageSubsidyRule>> {
+        let start = Instant::now();
+        let rule = sqlx::query_as!(
+            StorageSubsidyRule,
+            "SELECT * FROM subsidy_rules WHERE name = $1",
+            name
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_rules.load_subsidy_rule", start.elapsed());
+        Ok(rule)
+    }
+
+    /// Stores the subsidy rule, the rule with the same name is replaced.
+    pub async fn store_subsidy_rule(&mut self, rule: StorageSubsidyRule) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO subsidy_rules (
+                name, priority, enabled, tx_types, token_ids, addresses, ips, valid_from, valid_until,
+                max_fee_usd_scale6, budget_usd_scale6, max_per_address_usd_scale6
+            )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12 )
+            ON CONFLICT ( name ) DO UPDATE SET
+                priority = $2, enabled = $3, tx_types = $4, token_ids = $5, addresses = $6, ips = $7,
+                valid_from = $8, valid_until = $9, max_fee_usd_scale6 = $10, budget_usd_scale6 = $11,
+                max_per_address_usd_scale6 = $12
+            "#,
+            rule.name,
+            rule.priority,
+            rule.enabled,
+            rule.tx_types,
+            rule.token_ids.as_deref(),
+            rule.addresses.as_deref(),
+            rule.ips.as_deref(),
+            rule.valid_from,
+            rule.valid_until,
+            rule.max_fee_usd_scale6,
+            rule.budget_usd_scale6,
+            rule.max_per_address_usd_scale6
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_rules.store_subsidy_rule", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the subsidy rule. Returns `false` if there is no rule with the given name.
+    /// The subsidies applied by the rule are kept.
+    pub async fn remove_subsidy_rule(&mut self, name: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM subsidy_rules WHERE name = $1", name)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected();
+
+        metrics::histogram!("sql.subsidy_rules.remove_subsidy_rule", start.elapsed());
+        Ok(removed == 1)
+    }
+}
@@ -0,0 +1,27 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+// Local imports
+
+/// Fee subsidy rule. Criteria set to `None` match any transaction.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StorageSubsidyRule {
+    pub name: String,
+    pub priority: i32,
+    pub enabled: bool,
+    /// Transaction fee types in the JSON representation.
+    pub tx_types: Option<Value>,
+    /// Tokens the fee is paid in.
+    pub token_ids: Option<Vec<i32>>,
+    /// Addresses of the transaction senders.
+    pub addresses: Option<Vec<Vec<u8>>>,
+    /// IPs of the request origin.
+    pub ips: Option<Vec<String>>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_fee_usd_scale6: i64,
+    pub budget_usd_scale6: i64,
+    pub max_per_address_usd_scale6: Option<i64>,
+}
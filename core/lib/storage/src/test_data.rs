@@ -4,6 +4,7 @@
 use std::ops::Deref;
 
 // External imports
+use chrono::Utc;
 use num::{BigUint, Zero};
 use once_cell::sync::Lazy;
 use parity_crypto::publickey::{Generator, Random};
@@ -19,8 +20,10 @@ use zksync_types::{
         AggregatedActionType, AggregatedOperation, BlocksCommitOperation,
         BlocksCreateProofOperation, BlocksExecuteOperation, BlocksProofOperation,
     },
+    priority_ops::FullExit,
     tx::{EthSignData, PackedEthSignature, TxEthSignature},
-    Action, Address, Operation, H256, NFT,
+    Action, Address, ExecutedPriorityOp, FullExitOp, Operation, PriorityOp, ZkSyncOp,
+    ZkSyncPriorityOp, H256, NFT,
     {
         block::{Block, ExecutedOperations},
         AccountId, AccountUpdate, BlockNumber, Nonce, PubKeyHash, TokenId,
@@ -220,6 +223,36 @@ pub fn gen_sample_pending_block(
     }
 }
 
+/// Generates executed full exit priority operation with the given serial ID.
+pub fn gen_full_exit_priority_op(serial_id: u64) -> ExecutedPriorityOp {
+    let priority_op = FullExit {
+        account_id: AccountId(0),
+        eth_address: Address::zero(),
+        token: TokenId(0),
+        is_legacy: false,
+    };
+    ExecutedPriorityOp {
+        priority_op: PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::FullExit(priority_op.clone()),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 0,
+            eth_block_index: None,
+        },
+        op: ZkSyncOp::FullExit(Box::new(FullExitOp {
+            priority_op,
+            withdraw_amount: None,
+            creator_account_id: None,
+            creator_address: None,
+            serial_id: None,
+            content_hash: None,
+        })),
+        block_index: 0,
+        created_at: Utc::now(),
+    }
+}
+
 /// Generates dummy operation with the unique `new_root_hash` in the block and
 /// given set of transactions..
 pub fn gen_unique_operation_with_txs(
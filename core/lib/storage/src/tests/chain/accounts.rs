@@ -4,7 +4,7 @@ use num::{BigUint, Zero};
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID};
 use zksync_types::{
     aggregated_operations::AggregatedActionType, helpers::apply_updates, AccountId, AccountMap,
-    AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind,
+    AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind, TokenStatus,
 };
 // Local imports
 use super::block::apply_random_updates;
@@ -155,6 +155,20 @@ async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         );
     }
 
+    // Only the addresses of the created accounts are returned.
+    let unknown_address = Address::random();
+    let mut addresses: Vec<_> = accounts_block
+        .values()
+        .map(|account| account.address)
+        .collect();
+    let existing = AccountSchema(&mut storage)
+        .filter_existing_accounts(&[addresses.clone(), vec![unknown_address]].concat())
+        .await?;
+    addresses.sort();
+    let mut existing: Vec<_> = existing.into_iter().collect();
+    existing.sort();
+    assert_eq!(existing, addresses);
+
     // Now add a proof, verify block and apply a state update.
     OperationsSchema(&mut storage)
         .store_aggregated_action(gen_unique_aggregated_operation(
@@ -337,6 +351,7 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            status: TokenStatus::Valid,
         })
         .await?;
     storage
@@ -348,6 +363,7 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            status: TokenStatus::Valid,
         })
         .await?;
 
@@ -467,6 +483,7 @@ async fn test_get_nft_owner(mut storage: StorageProcessor<'_>) -> QueryResult<()
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            status: TokenStatus::Valid,
         })
         .await?;
     storage
@@ -275,6 +275,7 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
                 Default::default(),
             )
             .await?;
@@ -318,6 +319,7 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
                         Some((id, op)),
                         100,
                         100u32.into(),
+                        None,
                         Default::default(),
                     )
                     .await?;
@@ -483,6 +485,7 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
                 Default::default(),
             )
             .await?;
@@ -516,6 +519,7 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
                     Default::default(),
                 )
                 .await?;
@@ -618,6 +622,7 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                 Some((id, op)),
                 100,
                 100u32.into(),
+                None,
                 Default::default(),
             )
             .await?;
@@ -655,6 +660,7 @@ async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResu
                     Some((id, op)),
                     100,
                     100u32.into(),
+                    None,
                     Default::default(),
                 )
                 .await?;
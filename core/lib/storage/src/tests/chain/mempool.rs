@@ -1,17 +1,18 @@
 // External imports
 use chrono::Utc;
+use num::{rational::Ratio, BigUint};
 // Workspace imports
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
-    block::{Block, ExecutedOperations},
+    block::{Block, ExecutedOperations, PendingBlock},
     mempool::SignedTxVariant,
     priority_ops::FullExit,
     tx::{ChangePubKey, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
-    SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+    SignedZkSyncTx, TokenId, TokenPrice, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 // Local imports
-use crate::test_data::gen_eth_sign_data;
+use crate::test_data::{gen_eth_sign_data, gen_full_exit_priority_op};
 use crate::tests::db_test;
 use crate::{
     chain::{
@@ -22,6 +23,7 @@ use crate::{
         },
         operations_ext::OperationsExtSchema,
     },
+    tokens::TokensSchema,
     QueryResult, StorageProcessor,
 };
 
@@ -356,6 +358,286 @@ async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks that a queued transaction can be replaced and the replacement is remembered.
+#[db_test]
+async fn replace_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    let replaced_tx = txs[0].clone();
+    let replacement = Transfer::new(
+        AccountId(42),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        20u32.into(),
+        Nonce(10),
+        Default::default(),
+        None,
+    );
+    let replacement = SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(replacement)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    };
+
+    // The transaction must be found by its account and nonce.
+    let pending_tx = MempoolSchema(&mut storage)
+        .get_pending_tx_by_nonce(AccountId(42), Nonce(10))
+        .await?
+        .expect("transaction must be in the mempool");
+    assert_eq!(pending_tx.hash(), replaced_tx.hash());
+    assert!(MempoolSchema(&mut storage)
+        .get_pending_tx_by_nonce(AccountId(42), Nonce(11))
+        .await?
+        .is_none());
+
+    assert!(
+        MempoolSchema(&mut storage)
+            .replace_tx(replaced_tx.hash(), &replacement)
+            .await?
+    );
+
+    // The old transaction is gone, and the new one has taken its place.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(replaced_tx.hash())
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(replacement.hash())
+            .await?
+    );
+    let pending_tx = MempoolSchema(&mut storage)
+        .get_pending_tx_by_nonce(AccountId(42), Nonce(10))
+        .await?
+        .expect("transaction must be in the mempool");
+    assert_eq!(pending_tx.hash(), replacement.hash());
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_replacement_tx_hash(replaced_tx.hash())
+            .await?,
+        Some(replacement.hash())
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_replacement_tx_hash(replacement.hash())
+            .await?,
+        None
+    );
+
+    // Transaction that is not in the mempool anymore cannot be replaced.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(replaced_tx.hash(), &txs[1])
+            .await?
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage).get_mempool_size().await?,
+        txs.len() as u32
+    );
+
+    Ok(())
+}
+
+/// Checks that transactions proposed for the execution cannot be replaced.
+#[db_test]
+async fn replace_proposed_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    // Transactions removed from the mempool are not marked.
+    MempoolSchema(&mut storage)
+        .remove_tx(txs[2].hash().as_ref())
+        .await?;
+    let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+    let marked = MempoolSchema(&mut storage)
+        .mark_txs_proposed(&tx_hashes)
+        .await?;
+    assert_eq!(marked.len(), 2);
+    assert!(!marked.contains(&txs[2].hash()));
+
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(txs[0].hash(), &txs[2])
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(txs[0].hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(txs[2].hash())
+            .await?
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_replacement_tx_hash(txs[0].hash())
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
+/// Checks that the pending nonce of the account doesn't skip nonce gaps.
+#[db_test]
+async fn pending_nonce(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for nonce in [5, 6, 8] {
+        let transfer = Transfer::new(
+            AccountId(7),
+            Address::random(),
+            Address::random(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+        let tx = SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        };
+        MempoolSchema(&mut storage).insert_tx(&tx).await?;
+    }
+
+    for (committed_nonce, pending_nonce) in [(4, 4), (5, 7), (6, 7), (7, 7), (8, 9), (9, 9)] {
+        assert_eq!(
+            MempoolSchema(&mut storage)
+                .get_pending_nonce(AccountId(7), Nonce(committed_nonce))
+                .await?,
+            Nonce(pending_nonce)
+        );
+    }
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_pending_nonce(AccountId(8), Nonce(3))
+            .await?,
+        Nonce(3)
+    );
+
+    Ok(())
+}
+
+/// Checks pending transactions quotas and the eviction of the cheapest transactions.
+#[db_test]
+async fn pending_txs_quotas(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    // Transactions from batches are not considered for eviction.
+    MempoolSchema(&mut storage)
+        .insert_batch(&gen_transfers(2), Vec::new())
+        .await?;
+
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_account_pending_txs_count(AccountId(42))
+            .await?,
+        1
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_account_pending_txs_count(AccountId(1000))
+            .await?,
+        0
+    );
+
+    // ETH costs 1 USD.
+    TokensSchema(&mut storage)
+        .update_historical_ticker_price(
+            TokenId(0),
+            TokenPrice {
+                usd_price: Ratio::from_integer(1u32.into()),
+                last_updated: Utc::now(),
+            },
+        )
+        .await?;
+
+    let lowest_fee_txs = MempoolSchema(&mut storage).get_lowest_fee_txs(10).await?;
+    let tx_hashes: Vec<_> = lowest_fee_txs.iter().map(|(tx_hash, _)| *tx_hash).collect();
+    // `ChangePubKey` pays no fee, while the withdrawal pays as much as the first transfer,
+    // but uses more chunks.
+    assert_eq!(
+        tx_hashes,
+        vec![txs[3].hash(), txs[2].hash(), txs[0].hash(), txs[1].hash()]
+    );
+    assert_eq!(lowest_fee_txs[0].1, Ratio::from_integer(0u32.into()));
+    assert_eq!(
+        lowest_fee_txs[3].1,
+        Ratio::new(10u32.into(), BigUint::from(10u32).pow(18))
+    );
+
+    let lowest_fee_txs = MempoolSchema(&mut storage).get_lowest_fee_txs(1).await?;
+    assert_eq!(lowest_fee_txs.len(), 1);
+    assert_eq!(lowest_fee_txs[0].0, txs[3].hash());
+
+    // The withdrawal cannot be evicted before the next transaction of the account,
+    // which pays a higher fee.
+    let next_tx = Transfer::new(
+        AccountId(33),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        1000u32.into(),
+        Nonce(13),
+        Default::default(),
+        None,
+    );
+    let next_tx = SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(next_tx)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    };
+    MempoolSchema(&mut storage).insert_tx(&next_tx).await?;
+    // Proposed transactions are not evicted.
+    MempoolSchema(&mut storage)
+        .mark_txs_proposed(&[txs[3].hash()])
+        .await?;
+
+    let lowest_fee_txs = MempoolSchema(&mut storage).get_lowest_fee_txs(10).await?;
+    let tx_hashes: Vec<_> = lowest_fee_txs.iter().map(|(tx_hash, _)| *tx_hash).collect();
+    assert_eq!(
+        tx_hashes,
+        vec![txs[0].hash(), txs[1].hash(), next_tx.hash(), txs[2].hash()]
+    );
+    assert_eq!(lowest_fee_txs[2].1, lowest_fee_txs[3].1);
+
+    let evicted = MempoolSchema(&mut storage)
+        .evict_txs(&[next_tx.hash(), txs[3].hash()])
+        .await?;
+    assert_eq!(evicted, vec![next_tx.hash()]);
+    assert!(
+        MempoolSchema(&mut storage)
+            .is_tx_evicted(next_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .is_tx_evicted(txs[3].hash())
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(txs[3].hash())
+            .await?
+    );
+
+    Ok(())
+}
+
 /// Checks that batch is got from mempool correctly
 #[db_test]
 async fn test_get_batch_info_from_mempool(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -512,3 +794,52 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
     assert_eq!(block_tx.variance_name(), "FullExit");
     Ok(())
 }
+
+/// Checks that reverted priority operations are removed from the mempool unless
+/// they are executed in the committed or the pending block.
+#[db_test]
+async fn remove_reverted_priority_ops(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let ops: Vec<_> = (1..=4)
+        .map(|serial_id| gen_full_exit_priority_op(serial_id).priority_op)
+        .collect();
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&ops, true)
+        .await?;
+
+    // Operation 1 is executed in the committed block, operation 2 in the pending one.
+    OperationsSchema(&mut storage)
+        .store_executed_priority_op(NewExecutedPriorityOperation::prepare_stored_priority_op(
+            gen_full_exit_priority_op(1),
+            BlockNumber(1),
+        ))
+        .await?;
+    storage
+        .chain()
+        .block_schema()
+        .save_pending_block(PendingBlock {
+            number: BlockNumber(2),
+            chunks_left: 10,
+            unprocessed_priority_op_before: 2,
+            pending_block_iteration: 1,
+            success_operations: vec![ExecutedOperations::PriorityOp(Box::new(
+                gen_full_exit_priority_op(2),
+            ))],
+            failed_txs: Vec::new(),
+            timestamp: 0,
+        })
+        .await?;
+
+    let executed_ops = MempoolSchema(&mut storage)
+        .remove_reverted_priority_ops(&[2, 3, 4])
+        .await?;
+    assert_eq!(executed_ops, vec![2]);
+
+    let mempool_ops: Vec<_> = MempoolSchema(&mut storage)
+        .get_confirmed_priority_ops()
+        .await?
+        .into_iter()
+        .map(|op| op.serial_id)
+        .collect();
+    assert_eq!(mempool_ops, vec![1, 2]);
+    Ok(())
+}
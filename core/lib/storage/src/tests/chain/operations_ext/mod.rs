@@ -110,7 +110,14 @@ async fn confirm_eth_op(
     let eth_tx_hash = dummy_ethereum_tx_hash(op.0);
     let response = storage
         .ethereum_schema()
-        .save_new_eth_tx(op_type, Some(op), 100, 100u32.into(), Default::default())
+        .save_new_eth_tx(
+            op_type,
+            Some(op),
+            100,
+            100u32.into(),
+            None,
+            Default::default(),
+        )
         .await?;
     storage
         .ethereum_schema()
@@ -269,6 +269,57 @@ async fn test_remove_account_updates(mut storage: StorageProcessor<'_>) -> Query
     Ok(())
 }
 
+/// Checks that the state diff of a single block is paginated in the same order as it is loaded.
+#[db_test]
+async fn state_diff_page_for_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    let (accounts_block_1, updates_block_1) = apply_random_updates(AccountMap::default(), &mut rng);
+    let (_accounts_block_2, updates_block_2) = apply_random_updates(accounts_block_1, &mut rng);
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates_block_1, 0)
+        .await?;
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(2), &updates_block_2, 0)
+        .await?;
+
+    // There is nothing before the genesis block.
+    let diff = StateSchema(&mut storage)
+        .load_state_diff_for_block(BlockNumber(0))
+        .await?;
+    assert!(diff.is_empty());
+
+    let diff = StateSchema(&mut storage)
+        .load_state_diff_for_block(BlockNumber(2))
+        .await?;
+    let count = StateSchema(&mut storage)
+        .get_state_diff_count_for_block(BlockNumber(2))
+        .await?;
+    assert_eq!(count as usize, diff.len());
+
+    for offset in 0..=count {
+        for limit in 1..4 {
+            let page = StateSchema(&mut storage)
+                .load_state_diff_page_for_block(BlockNumber(2), offset, limit)
+                .await?;
+            let expected: Vec<_> = diff
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect();
+            assert_eq!(page, expected);
+        }
+    }
+
+    let count = StateSchema(&mut storage)
+        .get_state_diff_count_for_block(BlockNumber(3))
+        .await?;
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
 /// Tests `get_mint_nft_update` and `get_mint_nft_update_by_creator_and_nonce` methods
 #[db_test]
 async fn test_get_mint_nft_update(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
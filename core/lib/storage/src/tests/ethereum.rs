@@ -1,14 +1,18 @@
 // Built-in deps
 use std::str::FromStr;
 // External imports
+use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    block::ExecutedOperations,
     ethereum::ETHOperation,
-    BlockNumber, H256, U256,
+    Address, BlockNumber, H256, U256,
 };
 // Local imports
-use crate::test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS};
+use crate::test_data::{
+    gen_full_exit_priority_op, gen_sample_block, gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS,
+};
 use crate::tests::db_test;
 use crate::{
     chain::operations::OperationsSchema, ethereum::EthereumSchema, QueryResult, StorageProcessor,
@@ -23,6 +27,7 @@ pub struct EthereumTxParams {
     hash: H256,
     deadline_block: u64,
     gas_price: BigUint,
+    priority_fee: Option<BigUint>,
     raw_tx: Vec<u8>,
 }
 
@@ -36,6 +41,7 @@ impl EthereumTxParams {
             hash: H256::from_low_u64_ne(op_id as u64),
             deadline_block: 100,
             gas_price: 1000u32.into(),
+            priority_fee: None,
             raw_tx: Default::default(),
         }
     }
@@ -44,6 +50,10 @@ impl EthereumTxParams {
         let op_type = AggregatedActionType::from_str(self.op_type.as_ref())
             .expect("Stored operation type must have a valid value");
         let last_used_gas_price = U256::from_str(&self.gas_price.to_string()).unwrap();
+        let last_used_priority_fee = self
+            .priority_fee
+            .as_ref()
+            .map(|fee| U256::from_str(&fee.to_string()).unwrap());
         let used_tx_hashes = vec![self.hash];
 
         ETHOperation {
@@ -53,10 +63,13 @@ impl EthereumTxParams {
             nonce: nonce.into(),
             last_deadline_block: self.deadline_block,
             last_used_gas_price,
+            last_used_priority_fee,
             used_tx_hashes,
             encoded_tx_data: self.raw_tx.clone(),
             confirmed: false,
             final_hash: None,
+            failed_attempts: 0,
+            operator: None,
         }
     }
 }
@@ -116,6 +129,7 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
             params.op.clone(),
             params.deadline_block as i64,
             params.gas_price.clone(),
+            params.priority_fee.clone(),
             params.raw_tx.clone(),
         )
         .await?;
@@ -148,14 +162,16 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         .get_aggregated_op_that_affects_block(AggregatedActionType::CreateProofBlocks, block_number)
         .await?;
 
-    // Create one more Ethereum transaction.
-    let params_2 = EthereumTxParams::new("CommitBlocks".into(), op);
+    // Create one more Ethereum transaction, this time an EIP-1559 one.
+    let mut params_2 = EthereumTxParams::new("CommitBlocks".into(), op);
+    params_2.priority_fee = Some(100u32.into());
     let response_2 = EthereumSchema(&mut storage)
         .save_new_eth_tx(
             AggregatedActionType::CreateProofBlocks,
             params_2.op.clone(),
             params_2.deadline_block as i64,
             params_2.gas_price.clone(),
+            params_2.priority_fee.clone(),
             params_2.raw_tx.clone(),
         )
         .await?;
@@ -270,6 +286,7 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             params.op.clone(),
             params.deadline_block as i64,
             params.gas_price.clone(),
+            params.priority_fee.clone(),
             params.raw_tx.clone(),
         )
         .await?;
@@ -328,6 +345,7 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
             verify_params.op,
             verify_params.deadline_block as i64,
             verify_params.gas_price.clone(),
+            verify_params.priority_fee.clone(),
             verify_params.raw_tx.clone(),
         )
         .await?;
@@ -358,6 +376,282 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
     Ok(())
 }
 
+/// Checks that the operation with a failed transaction gets a new nonce and loses the old hashes.
+#[db_test]
+async fn ethereum_reset_failed_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let new_nonce = EthereumSchema(&mut storage)
+        .reset_failed_eth_tx(response.id, None)
+        .await?;
+    assert_eq!(new_nonce, response.nonce.as_u64() as i64 + 1);
+
+    // Operation should be loaded with the hash of the resent transaction only.
+    let new_hash = H256::from_low_u64_ne(1000);
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &new_hash)
+        .await?;
+    let unconfirmed_operations = EthereumSchema(&mut storage)
+        .load_unconfirmed_operations()
+        .await?;
+    let eth_op = unconfirmed_operations[0].clone();
+    assert_eq!(eth_op.nonce, new_nonce.into());
+    assert_eq!(eth_op.used_tx_hashes, vec![new_hash]);
+    assert_eq!(eth_op.failed_attempts, 1);
+
+    // Check the paused state of the sender.
+    assert!(!EthereumSchema(&mut storage).is_eth_sender_paused().await?);
+    EthereumSchema(&mut storage)
+        .set_eth_sender_paused(true)
+        .await?;
+    assert!(EthereumSchema(&mut storage).is_eth_sender_paused().await?);
+
+    Ok(())
+}
+
+/// Checks that the fees paid for the mined transactions, including the failed ones, are stored
+/// and summed up by type.
+#[db_test]
+async fn ethereum_tx_fees(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let since = Utc::now() - Duration::hours(1);
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .load_total_eth_tx_fee(AggregatedActionType::CommitBlocks, since)
+            .await?,
+        BigUint::from(0u32)
+    );
+
+    // The failed transaction is paid for as well.
+    let failed_hash = H256::repeat_byte(0xff);
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &failed_hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .save_eth_tx_fee(&failed_hash, 10000u32.into(), 1000u32.into(), false)
+        .await?;
+
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&params.hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .save_eth_tx_fee(&params.hash, 21000u32.into(), 1000u32.into(), true)
+        .await?;
+    // The fee is stored only once per transaction.
+    EthereumSchema(&mut storage)
+        .save_eth_tx_fee(&params.hash, 42000u32.into(), 1000u32.into(), true)
+        .await?;
+
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .load_total_eth_tx_fee(AggregatedActionType::CommitBlocks, since)
+            .await?,
+        BigUint::from(31_000_000u32)
+    );
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .load_total_eth_tx_fee(AggregatedActionType::ExecuteBlocks, since)
+            .await?,
+        BigUint::from(0u32)
+    );
+    // Fees paid before the window are not taken into account.
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .load_total_eth_tx_fee(
+                AggregatedActionType::CommitBlocks,
+                Utc::now() + Duration::hours(1)
+            )
+            .await?,
+        BigUint::from(0u32)
+    );
+
+    let fees = EthereumSchema(&mut storage)
+        .load_eth_tx_fees(block_number, block_number)
+        .await?;
+    assert_eq!(fees.len(), 2);
+    let failed_fee = fees.iter().find(|fee| !fee.success).unwrap();
+    assert_eq!(failed_fee.tx_hash, failed_hash.as_bytes().to_vec());
+    let fee = fees.iter().find(|fee| fee.success).unwrap();
+    assert_eq!(fee.eth_op_id, response.id);
+    assert_eq!(fee.tx_hash, params.hash.as_bytes().to_vec());
+    assert_eq!(fee.op_type, "CommitBlocks");
+    assert_eq!(fee.from_block, Some(i64::from(*block_number)));
+    assert_eq!(fee.to_block, Some(i64::from(*block_number)));
+    assert!(EthereumSchema(&mut storage)
+        .load_eth_tx_fees(block_number + 1, block_number + 1)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the gas used by the mined transactions is summed up by type along with
+/// the totals and the operations of the processed blocks.
+#[db_test]
+async fn ethereum_gas_usage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+
+    let block_number = BlockNumber(1);
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(
+            block_number,
+            BLOCK_SIZE_CHUNKS,
+            vec![
+                ExecutedOperations::PriorityOp(Box::new(gen_full_exit_priority_op(1))),
+                ExecutedOperations::PriorityOp(Box::new(gen_full_exit_priority_op(2))),
+            ],
+        ))
+        .await?;
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+
+    let since = Utc::now() - Duration::hours(1);
+    // Pending transactions are not taken into account.
+    assert!(EthereumSchema(&mut storage)
+        .load_gas_usage(since)
+        .await?
+        .is_empty());
+    assert!(EthereumSchema(&mut storage)
+        .load_processed_operations(since)
+        .await?
+        .is_empty());
+
+    // Gas used by the failed transaction is counted, but the blocks are not processed by it.
+    let failed_hash = H256::repeat_byte(0xff);
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &failed_hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .save_eth_tx_fee(&failed_hash, 100_000u32.into(), 1000u32.into(), false)
+        .await?;
+    let usage = EthereumSchema(&mut storage).load_gas_usage(since).await?;
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].gas_used, 100_000);
+    assert_eq!(usage[0].gas_price, 1000);
+    assert_eq!(usage[0].blocks, 0);
+    assert!(EthereumSchema(&mut storage)
+        .load_processed_operations(since)
+        .await?
+        .is_empty());
+
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&params.hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .save_eth_tx_fee(&params.hash, 300_000u32.into(), 2000u32.into(), true)
+        .await?;
+
+    let usage = EthereumSchema(&mut storage).load_gas_usage(since).await?;
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].op_type, "CommitBlocks");
+    assert_eq!(usage[0].gas_used, 400_000);
+    // (100_000 * 1000 + 300_000 * 2000) / 400_000
+    assert_eq!(usage[0].gas_price, 1750);
+    assert_eq!(usage[0].blocks, 1);
+    assert_eq!(usage[0].commit_gas_limit, 1_000_000);
+    assert_eq!(usage[0].verify_gas_limit, 1_500_000);
+
+    let operations = EthereumSchema(&mut storage)
+        .load_processed_operations(since)
+        .await?;
+    assert_eq!(operations.len(), 1);
+    assert_eq!(operations[0].op_type, "CommitBlocks");
+    assert_eq!(operations[0].operation_type, "FullExit");
+    assert_eq!(operations[0].count, 2);
+
+    // Transactions confirmed before the window are not taken into account.
+    assert!(EthereumSchema(&mut storage)
+        .load_gas_usage(Utc::now() + Duration::hours(1))
+        .await?
+        .is_empty());
+    assert!(EthereumSchema(&mut storage)
+        .load_processed_operations(Utc::now() + Duration::hours(1))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
 /// Simple test for store/load of (average) gas price.
 #[db_test]
 async fn ethereum_gas_update(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -381,6 +675,146 @@ async fn ethereum_gas_update(mut storage: StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks that the additional operator account has its own nonce and gas price statistics.
+#[db_test]
+async fn ethereum_operator_account(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+    let operator = Address::from_low_u64_be(1);
+    let operator_nonce = 10;
+    storage
+        .ethereum_schema()
+        .initialize_operator_account(operator, operator_nonce)
+        .await?;
+    // Repeated initialization doesn't affect the stored account.
+    storage
+        .ethereum_schema()
+        .initialize_operator_account(operator, 0)
+        .await?;
+
+    // Store the operation for every account and check that the nonces are independent.
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let main_response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    assert_eq!(main_response.nonce, 0.into());
+
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(
+            AggregatedActionType::PublishProofBlocksOnchain,
+            block_number,
+        )
+        .await?;
+    let params = EthereumTxParams::new("PublishProofBlocksOnchain".into(), op);
+    let operator_response = storage
+        .ethereum_schema()
+        .save_new_operator_eth_tx(
+            Some(operator),
+            AggregatedActionType::PublishProofBlocksOnchain,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.priority_fee.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    assert_eq!(operator_response.nonce, operator_nonce.into());
+
+    // The sending account is stored along with the operation.
+    for (id, hash_byte) in [(main_response.id, 1), (operator_response.id, 2)] {
+        storage
+            .ethereum_schema()
+            .add_hash_entry(id, &H256::repeat_byte(hash_byte))
+            .await?;
+    }
+    let unconfirmed_operators: Vec<_> = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .into_iter()
+        .map(|op| op.operator)
+        .collect();
+    assert_eq!(unconfirmed_operators, vec![None, Some(operator)]);
+
+    let new_nonce = storage
+        .ethereum_schema()
+        .reset_failed_eth_tx(operator_response.id, Some(operator))
+        .await?;
+    assert_eq!(new_nonce, operator_nonce + 1);
+    assert_eq!(storage.ethereum_schema().get_next_nonce().await?, 1);
+
+    // Operations of different types don't wait for each other to be confirmed.
+    assert!(
+        storage
+            .ethereum_schema()
+            .is_previous_eth_op_confirmed(
+                operator_response.id,
+                &[AggregatedActionType::PublishProofBlocksOnchain]
+            )
+            .await?
+    );
+    assert!(
+        !storage
+            .ethereum_schema()
+            .is_previous_eth_op_confirmed(
+                operator_response.id,
+                &[
+                    AggregatedActionType::CommitBlocks,
+                    AggregatedActionType::PublishProofBlocksOnchain
+                ]
+            )
+            .await?
+    );
+
+    // Gas price statistics are initialized with the ones of the main account.
+    let main_price_limit = storage.ethereum_schema().load_gas_price_limit().await?;
+    let operator_price_limit = storage
+        .ethereum_schema()
+        .load_operator_gas_price_limit(operator)
+        .await?;
+    assert_eq!(operator_price_limit, main_price_limit);
+    storage
+        .ethereum_schema()
+        .update_operator_gas_price(operator, main_price_limit + 1i32, main_price_limit)
+        .await?;
+    let operator_price_limit = storage
+        .ethereum_schema()
+        .load_operator_gas_price_limit(operator)
+        .await?;
+    assert_eq!(operator_price_limit, main_price_limit + 1i32);
+    assert_eq!(
+        storage.ethereum_schema().load_gas_price_limit().await?,
+        main_price_limit
+    );
+
+    Ok(())
+}
+
 /// Check update eth parameters
 #[db_test]
 async fn test_update_eth_parameters(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -72,6 +72,7 @@ async fn store_operation(
             Some((id, op)),
             100,
             100u32.into(),
+            None,
             Default::default(),
         )
         .await?;
@@ -176,6 +177,14 @@ async fn test_block_events(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     }
     // Fetch new events.
     let events = fetch_new_events(&mut storage, last_event_id).await?;
+    // Events can be loaded in batches as well.
+    let batch = storage
+        .event_schema()
+        .fetch_events_batch(last_event_id, 2)
+        .await?;
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0].id, *events[0].id as i64);
+    assert_eq!(batch[1].id, *events[1].id as i64);
     // Update the offset.
     last_event_id = events.last().unwrap().id;
     let expected_len = TO_BLOCK as usize + 1;
@@ -1,5 +1,5 @@
 use sqlx::types::BigDecimal;
-use zksync_types::TokenId;
+use zksync_types::{Address, TokenId};
 
 use crate::tests::db_test;
 use crate::{misc::records::Subsidy, misc::MiscSchema};
@@ -15,6 +15,7 @@ fn get_subsidy(name: String, value: u64) -> Subsidy {
         token_amount: BigDecimal::from(100),
         full_cost_token: BigDecimal::from(200),
         subsidy_type: name,
+        address: None,
     }
 }
 
@@ -54,3 +55,101 @@ async fn stored_subsidy(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that the subsidies are tracked per sender address.
+#[db_test]
+async fn stored_subsidy_for_address(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let subsidy_name = "subsidy".to_string();
+    let address = Address::repeat_byte(1);
+    let another_address = Address::repeat_byte(2);
+
+    let subsidy = |name: &str, value, address| Subsidy {
+        address: Some(address),
+        ..get_subsidy(name.to_string(), value)
+    };
+    MiscSchema(&mut storage)
+        .store_subsidy(subsidy(&subsidy_name, 10, address))
+        .await?;
+    MiscSchema(&mut storage)
+        .store_subsidy(subsidy(&subsidy_name, 20, another_address))
+        .await?;
+    MiscSchema(&mut storage)
+        .store_subsidy(subsidy("another_subsidy", 40, address))
+        .await?;
+    MiscSchema(&mut storage)
+        .store_subsidy(subsidy(&subsidy_name, 15, address))
+        .await?;
+
+    let used_by_address = MiscSchema(&mut storage)
+        .get_total_used_subsidy_for_address(&subsidy_name, address)
+        .await?;
+    assert_eq!(used_by_address, BigDecimal::from(25));
+    let used_by_another_address = MiscSchema(&mut storage)
+        .get_total_used_subsidy_for_address(&subsidy_name, Address::repeat_byte(3))
+        .await?;
+    assert_eq!(used_by_another_address, BigDecimal::from(0));
+
+    let total = MiscSchema(&mut storage)
+        .get_total_used_subsidy_for_type(&subsidy_name)
+        .await?;
+    assert_eq!(total, BigDecimal::from(45));
+
+    Ok(())
+}
+
+/// Checks that the subsidies are stored only within the budget and the per-address cap.
+#[db_test]
+async fn stored_subsidy_within_budget(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let subsidy_name = "subsidy".to_string();
+    let address = Address::repeat_byte(1);
+    let another_address = Address::repeat_byte(2);
+
+    let subsidy = |value, address| Subsidy {
+        address: Some(address),
+        ..get_subsidy(subsidy_name.clone(), value)
+    };
+
+    // Unlimited per address.
+    let first_id = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(60, address), 100, None)
+        .await?;
+    assert!(first_id.is_some());
+    let exceeding_budget = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(50, another_address), 100, None)
+        .await?;
+    assert!(exceeding_budget.is_none());
+    let within_budget = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(40, another_address), 100, None)
+        .await?;
+    assert!(within_budget.is_some());
+
+    // The budget is exhausted, but the removed subsidies are not taken into account.
+    MiscSchema(&mut storage)
+        .remove_subsidies(&[first_id.unwrap()])
+        .await?;
+    let total = MiscSchema(&mut storage)
+        .get_total_used_subsidy_for_type(&subsidy_name)
+        .await?;
+    assert_eq!(total, BigDecimal::from(40));
+
+    // Capped per address.
+    let within_cap = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(30, address), 200, Some(30))
+        .await?;
+    assert!(within_cap.is_some());
+    let exceeding_cap = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(10, address), 200, Some(30))
+        .await?;
+    assert!(exceeding_cap.is_none());
+    let exceeding_cap = MiscSchema(&mut storage)
+        .store_subsidy_within_budget(subsidy(10, another_address), 200, Some(30))
+        .await?;
+    assert!(exceeding_cap.is_none());
+
+    let total = MiscSchema(&mut storage)
+        .get_total_used_subsidy_for_type(&subsidy_name)
+        .await?;
+    assert_eq!(total, BigDecimal::from(70));
+
+    Ok(())
+}
@@ -31,6 +31,8 @@ mod event;
 mod forced_exit_requests;
 mod misc;
 mod prover;
+mod subsidy_rules;
+mod ticker;
 mod tokens;
 
 pub use db_test_macro::test as db_test;
@@ -0,0 +1,91 @@
+// Built-in imports
+// External imports
+use chrono::{TimeZone, Utc};
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use crate::tests::db_test;
+use crate::{subsidy_rules::records::StorageSubsidyRule, QueryResult, StorageProcessor};
+
+fn rule(name: &str, priority: i32) -> StorageSubsidyRule {
+    StorageSubsidyRule {
+        name: name.to_string(),
+        priority,
+        enabled: true,
+        tx_types: Some(serde_json::json!([{ "ChangePubKey": "CREATE2" }])),
+        token_ids: Some(vec![0, 1]),
+        addresses: Some(vec![Address::repeat_byte(1).as_bytes().to_vec()]),
+        ips: Some(vec!["127.0.0.1".to_string()]),
+        valid_from: Some(Utc.timestamp(1000, 0)),
+        valid_until: None,
+        max_fee_usd_scale6: 0,
+        budget_usd_scale6: 1_000_000_000,
+        max_per_address_usd_scale6: Some(10_000_000),
+    }
+}
+
+/// Checks the store/load/remove routine for the subsidy rules.
+#[db_test]
+async fn test_subsidy_rules(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .subsidy_rules_schema()
+        .load_subsidy_rules()
+        .await?
+        .is_empty());
+
+    let partner_rule = rule("partner", 1);
+    let cpk_rule = StorageSubsidyRule {
+        tx_types: None,
+        token_ids: None,
+        addresses: None,
+        ips: None,
+        valid_from: None,
+        max_per_address_usd_scale6: None,
+        ..rule("cpk", 0)
+    };
+    storage
+        .subsidy_rules_schema()
+        .store_subsidy_rule(partner_rule.clone())
+        .await?;
+    storage
+        .subsidy_rules_schema()
+        .store_subsidy_rule(cpk_rule.clone())
+        .await?;
+
+    // Rules are loaded in the order of the priority.
+    let rules = storage.subsidy_rules_schema().load_subsidy_rules().await?;
+    assert_eq!(rules, vec![cpk_rule.clone(), partner_rule.clone()]);
+
+    // The rule with the same name is replaced.
+    let updated_rule = StorageSubsidyRule {
+        enabled: false,
+        budget_usd_scale6: 2_000_000_000,
+        ..partner_rule
+    };
+    storage
+        .subsidy_rules_schema()
+        .store_subsidy_rule(updated_rule.clone())
+        .await?;
+    let stored = storage
+        .subsidy_rules_schema()
+        .load_subsidy_rule("partner")
+        .await?;
+    assert_eq!(stored, Some(updated_rule));
+
+    assert!(
+        storage
+            .subsidy_rules_schema()
+            .remove_subsidy_rule("partner")
+            .await?
+    );
+    assert!(
+        !storage
+            .subsidy_rules_schema()
+            .remove_subsidy_rule("partner")
+            .await?
+    );
+    let rules = storage.subsidy_rules_schema().load_subsidy_rules().await?;
+    assert_eq!(rules, vec![cpk_rule]);
+
+    Ok(())
+}
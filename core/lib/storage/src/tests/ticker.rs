@@ -0,0 +1,349 @@
+// Built-in imports
+use std::{str::FromStr, time::Duration};
+// External imports
+use chrono::{TimeZone, Utc};
+use num::{rational::Ratio, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{tx::TxHash, TokenId, TokenPrice};
+// Local imports
+use crate::tests::db_test;
+use crate::{
+    ticker::records::{FeeQuoteSnapshot, NewFeeQuote, NewGasCalibration},
+    QueryResult, StorageProcessor,
+};
+
+const TOKEN_ID: TokenId = TokenId(0);
+
+fn price(usd_price: u32, timestamp: i64) -> TokenPrice {
+    TokenPrice {
+        usd_price: Ratio::from_integer(BigUint::from(usd_price)),
+        last_updated: Utc.timestamp(timestamp, 0),
+    }
+}
+
+/// Converts the prices into the comparable pairs of price and timestamp.
+fn to_pairs(prices: &[TokenPrice]) -> Vec<(Ratio<BigUint>, i64)> {
+    prices
+        .iter()
+        .map(|price| (price.usd_price.clone(), price.last_updated.timestamp()))
+        .collect()
+}
+
+/// Checks that the price samples are stored and aggregated by the requested resolution.
+#[db_test]
+async fn test_price_history(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let samples = vec![
+        price(10, 0),
+        price(20, 60),
+        price(30, 3600),
+        price(40, 7260),
+    ];
+    for sample in &samples {
+        storage
+            .ticker_schema()
+            .store_price_sample(TOKEN_ID, sample)
+            .await?;
+    }
+    // Duplicate samples are ignored.
+    storage
+        .ticker_schema()
+        .store_price_sample(TOKEN_ID, &price(50, 0))
+        .await?;
+
+    let from = Utc.timestamp(0, 0);
+    let to = Utc.timestamp(10_000, 0);
+    let history = storage
+        .ticker_schema()
+        .load_price_history(TOKEN_ID, from, to, Duration::from_secs(1))
+        .await?;
+    assert_eq!(to_pairs(&history), to_pairs(&samples));
+
+    let history = storage
+        .ticker_schema()
+        .load_price_history(TOKEN_ID, from, to, Duration::from_secs(3600))
+        .await?;
+    assert_eq!(
+        to_pairs(&history),
+        to_pairs(&[price(15, 0), price(30, 3600), price(40, 7200)])
+    );
+
+    // The range end is exclusive.
+    let history = storage
+        .ticker_schema()
+        .load_price_history(
+            TOKEN_ID,
+            from,
+            Utc.timestamp(3600, 0),
+            Duration::from_secs(1),
+        )
+        .await?;
+    assert_eq!(history.len(), 2);
+
+    Ok(())
+}
+
+/// Checks that the old samples are removed and the samples older than the downsampling cutoff
+/// are thinned out.
+#[db_test]
+async fn test_compact_price_history(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for timestamp in &[0, 3600, 3660, 3720, 7200, 7260] {
+        storage
+            .ticker_schema()
+            .store_price_sample(TOKEN_ID, &price(1, *timestamp))
+            .await?;
+    }
+
+    let removed = storage
+        .ticker_schema()
+        .compact_price_history(
+            Utc.timestamp(3600, 0),
+            Utc.timestamp(7200, 0),
+            Duration::from_secs(3600),
+        )
+        .await?;
+    assert_eq!(removed, 3);
+
+    let history = storage
+        .ticker_schema()
+        .load_price_history(
+            TOKEN_ID,
+            Utc.timestamp(0, 0),
+            Utc.timestamp(10_000, 0),
+            Duration::from_secs(1),
+        )
+        .await?;
+    let timestamps: Vec<_> = to_pairs(&history)
+        .into_iter()
+        .map(|(_, timestamp)| timestamp)
+        .collect();
+    assert_eq!(timestamps, vec![3600, 7200, 7260]);
+
+    Ok(())
+}
+
+/// Checks the store/load routine for the fee quote snapshots.
+#[db_test]
+async fn test_fee_quote_snapshots(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let tx_hash = TxHash::default();
+    let snapshot = FeeQuoteSnapshot {
+        tx_hash: tx_hash.as_ref().to_vec(),
+        token_id: *TOKEN_ID as i32,
+        fee_types: serde_json::json!(["Transfer"]),
+        token_price_usd: BigDecimal::from_str("1800.5").unwrap(),
+        token_price_updated_at: Utc.timestamp(100, 0),
+        eth_price_usd: BigDecimal::from_str("1800.5").unwrap(),
+        eth_price_updated_at: Utc.timestamp(100, 0),
+        gas_price_wei: BigDecimal::from(30_000_000_000u64),
+        total_fee: BigDecimal::from(1000),
+        subsidized_total_fee: BigDecimal::from(500),
+    };
+    let id = storage
+        .ticker_schema()
+        .store_fee_quote_snapshot(snapshot.clone())
+        .await?;
+
+    let now = Utc::now();
+    let snapshots = storage
+        .ticker_schema()
+        .load_fee_quote_snapshots(
+            TOKEN_ID,
+            now - chrono::Duration::hours(1),
+            now + chrono::Duration::hours(1),
+        )
+        .await?;
+    assert_eq!(snapshots.len(), 1);
+    let stored = &snapshots[0];
+    assert_eq!(stored.id, id);
+    assert_eq!(stored.fee_types, snapshot.fee_types);
+    assert_eq!(stored.token_price_usd, snapshot.token_price_usd);
+    assert_eq!(stored.gas_price_wei, snapshot.gas_price_wei);
+    assert_eq!(stored.subsidized_total_fee, snapshot.subsidized_total_fee);
+
+    // Snapshots of the other tokens are not loaded.
+    let snapshots = storage
+        .ticker_schema()
+        .load_fee_quote_snapshots(
+            TokenId(1),
+            now - chrono::Duration::hours(1),
+            now + chrono::Duration::hours(1),
+        )
+        .await?;
+    assert!(snapshots.is_empty());
+
+    let stored = storage
+        .ticker_schema()
+        .load_fee_quote_snapshot(&tx_hash)
+        .await?
+        .expect("Snapshot is not found by the transaction hash");
+    assert_eq!(stored.id, id);
+    assert_eq!(stored.tx_hash, snapshot.tx_hash);
+
+    // Snapshots created before the cutoff are removed.
+    let removed = storage
+        .ticker_schema()
+        .remove_fee_quote_snapshots(now - chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(removed, 0);
+    let removed = storage
+        .ticker_schema()
+        .remove_fee_quote_snapshots(now + chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(removed, 1);
+    assert!(storage
+        .ticker_schema()
+        .load_fee_quote_snapshot(&tx_hash)
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks the store/load routine for the fee quotes and that the quote can be claimed only once.
+#[db_test]
+async fn test_fee_quotes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    let quote = NewFeeQuote {
+        token_id: *TOKEN_ID as i32,
+        txs: serde_json::json!([["Transfer", "0x0000000000000000000000000000000000000001"]]),
+        total_fee: BigDecimal::from(1000),
+        expires_at: now + chrono::Duration::hours(1),
+    };
+    let id = storage
+        .ticker_schema()
+        .store_fee_quote(quote.clone())
+        .await?;
+
+    let stored = storage
+        .ticker_schema()
+        .load_fee_quote(id)
+        .await?
+        .expect("Quote should be stored");
+    assert_eq!(stored.token_id, quote.token_id);
+    assert_eq!(stored.txs, quote.txs);
+    assert_eq!(stored.total_fee, quote.total_fee);
+    assert_eq!(stored.expires_at, quote.expires_at);
+    assert!(stored.used_at.is_none());
+    assert!(storage
+        .ticker_schema()
+        .load_fee_quote(id + 1)
+        .await?
+        .is_none());
+
+    let tx_hash = TxHash::default();
+    let claimed = storage
+        .ticker_schema()
+        .claim_fee_quote(id, &tx_hash)
+        .await?
+        .expect("Quote should be claimed");
+    assert_eq!(claimed.used_by_tx_hash, Some(tx_hash.as_ref().to_vec()));
+    assert!(claimed.used_at.is_some());
+    // The quote is already used.
+    assert!(storage
+        .ticker_schema()
+        .claim_fee_quote(id, &tx_hash)
+        .await?
+        .is_none());
+
+    // Only the claiming transaction can release the quote.
+    let other_hash = TxHash::from_slice(&[1; 32]).unwrap();
+    assert!(
+        !storage
+            .ticker_schema()
+            .release_fee_quote(id, &other_hash)
+            .await?
+    );
+    assert!(
+        storage
+            .ticker_schema()
+            .release_fee_quote(id, &tx_hash)
+            .await?
+    );
+    let stored = storage.ticker_schema().load_fee_quote(id).await?.unwrap();
+    assert!(stored.used_by_tx_hash.is_none());
+    assert!(stored.used_at.is_none());
+
+    // The expired quote can't be claimed.
+    let expired_id = storage
+        .ticker_schema()
+        .store_fee_quote(NewFeeQuote {
+            expires_at: now - chrono::Duration::hours(1),
+            ..quote
+        })
+        .await?;
+    assert!(storage
+        .ticker_schema()
+        .claim_fee_quote(expired_id, &tx_hash)
+        .await?
+        .is_none());
+
+    // Only the expired quote is removed, the unused one is kept until it expires.
+    assert_eq!(
+        storage
+            .ticker_schema()
+            .remove_fee_quotes(now, now - chrono::Duration::days(1))
+            .await?,
+        1
+    );
+    assert!(storage
+        .ticker_schema()
+        .load_fee_quote(expired_id)
+        .await?
+        .is_none());
+    storage
+        .ticker_schema()
+        .claim_fee_quote(id, &tx_hash)
+        .await?
+        .expect("Quote should be claimed");
+    // The used quote is removed after the retention period.
+    assert_eq!(
+        storage
+            .ticker_schema()
+            .remove_fee_quotes(now, now + chrono::Duration::days(1))
+            .await?,
+        1
+    );
+    assert!(storage.ticker_schema().load_fee_quote(id).await?.is_none());
+
+    Ok(())
+}
+
+/// Checks that the latest gas cost calibration is loaded.
+#[db_test]
+async fn test_gas_calibrations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .ticker_schema()
+        .load_last_gas_calibration()
+        .await?
+        .is_none());
+
+    let calibration = |amortized_cost_per_chunk| NewGasCalibration {
+        commit_cost_per_chunk: 100,
+        proof_cost_per_chunk: 50,
+        execute_cost_per_chunk: 30,
+        amortized_cost_per_chunk,
+        gas_price_scale_percent: 120,
+    };
+    storage
+        .ticker_schema()
+        .store_gas_calibration(calibration(200))
+        .await?;
+    let id = storage
+        .ticker_schema()
+        .store_gas_calibration(calibration(180))
+        .await?;
+
+    let last = storage
+        .ticker_schema()
+        .load_last_gas_calibration()
+        .await?
+        .expect("Calibration should be stored");
+    assert_eq!(last.id, id);
+    assert_eq!(last.commit_cost_per_chunk, 100);
+    assert_eq!(last.proof_cost_per_chunk, 50);
+    assert_eq!(last.execute_cost_per_chunk, 30);
+    assert_eq!(last.amortized_cost_per_chunk, 180);
+    assert_eq!(last.gas_price_scale_percent, 120);
+
+    Ok(())
+}
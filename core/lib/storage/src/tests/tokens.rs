@@ -7,7 +7,7 @@ use num::{rational::Ratio, BigUint};
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
     tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    Token, TokenId, TokenKind, TokenLike, TokenPrice, TokenStatus, WithdrawNFTOp, ZkSyncOp, H256,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
@@ -15,7 +15,7 @@ use crate::tests::db_test;
 use crate::{
     chain::account::records::StorageMintNFTUpdate,
     diff::StorageAccountDiff,
-    tokens::{TokensSchema, STORED_USD_PRICE_PRECISION},
+    tokens::{StoreTokenError, TokensSchema, STORED_USD_PRICE_PRECISION},
     QueryResult, StorageProcessor,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
@@ -39,6 +39,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 18,
         kind: TokenKind::ERC20,
         is_nft: false,
+        status: TokenStatus::Valid,
     };
     assert_eq!(tokens[&TokenId(0)], eth_token);
 
@@ -50,6 +51,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 9,
         kind: TokenKind::ERC20,
         is_nft: false,
+        status: TokenStatus::Valid,
     };
     let token_b = Token {
         id: TokenId(2),
@@ -58,6 +60,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 6,
         kind: TokenKind::None,
         is_nft: false,
+        status: TokenStatus::Valid,
     };
     let nft = Token {
         id: TokenId(MIN_NFT_TOKEN_ID),
@@ -66,6 +69,7 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         decimals: 0,
         kind: TokenKind::NFT,
         is_nft: true,
+        status: TokenStatus::Valid,
     };
 
     TokensSchema(&mut storage)
@@ -135,6 +139,73 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks the store/update routine for the token validation status.
+#[db_test]
+async fn test_token_status(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(
+        TokenId(1),
+        "0000000000000000000000000000000000000001".parse().unwrap(),
+        "ERC20-1",
+        18,
+        TokenKind::ERC20,
+    )
+    .with_status(TokenStatus::Flagged);
+    storage
+        .tokens_schema()
+        .store_token(token.clone())
+        .await
+        .expect("Store token query failed");
+
+    let loaded = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(token.id))
+        .await?
+        .expect("token not found");
+    assert_eq!(loaded, token);
+
+    storage
+        .tokens_schema()
+        .update_token_status(token.id, TokenStatus::Quarantined)
+        .await?;
+    let tokens = storage.tokens_schema().load_tokens().await?;
+    assert_eq!(tokens[&token.id].status, TokenStatus::Quarantined);
+    // Status of the other tokens is not changed.
+    assert_eq!(tokens[&TokenId(0)].status, TokenStatus::Valid);
+    Ok(())
+}
+
+/// Checks that the symbols of the tokens can't differ by the case only.
+#[db_test]
+async fn test_token_symbol_case(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(
+        TokenId(1),
+        Address::repeat_byte(1),
+        "DAI",
+        18,
+        TokenKind::ERC20,
+    );
+    storage
+        .tokens_schema()
+        .store_token(token)
+        .await
+        .expect("Store token query failed");
+
+    let token = Token::new(
+        TokenId(2),
+        Address::repeat_byte(2),
+        "dai",
+        18,
+        TokenKind::ERC20,
+    );
+    let result = storage.tokens_schema().store_token(token).await;
+    assert!(
+        matches!(result, Err(StoreTokenError::TokenAlreadyExistsError(_))),
+        "{:?}",
+        result
+    );
+    Ok(())
+}
+
 /// Checks the store/load routine for `ticker_price` table.
 #[db_test]
 async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -176,6 +247,17 @@ async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()>
         price.last_updated.timestamp()
     );
 
+    // Unit prices take the token decimals into account, tokens without price are omitted.
+    let unit_prices = storage
+        .tokens_schema()
+        .get_token_unit_prices(&[TOKEN_ID, TokenId(1)])
+        .await?;
+    assert_eq!(unit_prices.len(), 1);
+    assert_eq!(
+        unit_prices[&TOKEN_ID],
+        expected_price / BigUint::from(10u32).pow(18)
+    );
+
     Ok(())
 }
 
@@ -0,0 +1,375 @@
+// Built-in deps
+use std::time::{Duration, Instant};
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{tx::TxHash, TokenId, TokenPrice};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+// Local imports
+use self::records::{
+    FeeQuoteSnapshot, NewFeeQuote, NewGasCalibration, StoredFeeQuote, StoredFeeQuoteSnapshot,
+    StoredGasCalibration, StoredPriceSample,
+};
+use crate::{tokens::STORED_USD_PRICE_PRECISION, QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Ticker schema stores the history of the token prices and the prices used for the fee quotes.
+#[derive(Debug)]
+pub struct TickerSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> TickerSchema<'a, 'c> {
+    /// Appends the price sample to the price history of the token.
+    /// The sample timestamp is taken from `price.last_updated`, duplicate samples are ignored.
+    pub async fn store_price_sample(
+        &mut self,
+        token_id: TokenId,
+        price: &TokenPrice,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let usd_price_rounded = ratio_to_big_decimal(&price.usd_price, STORED_USD_PRICE_PRECISION);
+        sqlx::query!(
+            r#"
+            INSERT INTO ticker_price_history ( token_id, usd_price, sampled_at )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT ( token_id, sampled_at ) DO NOTHING
+            "#,
+            *token_id as i32,
+            usd_price_rounded,
+            price.last_updated
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ticker.store_price_sample", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the price history of the token in the `[from, to)` range.
+    /// Samples are averaged over the `resolution` intervals, the `last_updated` field of the returned
+    /// prices is the start of the interval.
+    pub async fn load_price_history(
+        &mut self,
+        token_id: TokenId,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution: Duration,
+    ) -> QueryResult<Vec<TokenPrice>> {
+        let start = Instant::now();
+        let resolution = resolution.as_secs().max(1) as i64;
+        let samples = sqlx::query_as!(
+            StoredPriceSample,
+            r#"
+            SELECT
+                to_timestamp((floor(extract(epoch FROM sampled_at))::bigint / $4 * $4)::double precision) as "sampled_at!",
+                avg(usd_price) as "usd_price!"
+            FROM ticker_price_history
+            WHERE token_id = $1 AND sampled_at >= $2 AND sampled_at < $3
+            GROUP BY 1
+            ORDER BY 1 ASC
+            "#,
+            *token_id as i32,
+            from,
+            to,
+            resolution
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let prices = samples
+            .into_iter()
+            .map(|sample| TokenPrice {
+                usd_price: big_decimal_to_ratio(&sample.usd_price)
+                    .expect("Price could not be negative"),
+                last_updated: sample.sampled_at,
+            })
+            .collect();
+        metrics::histogram!("sql.ticker.load_price_history", start.elapsed());
+        Ok(prices)
+    }
+
+    /// Removes the price samples taken before `retention_cutoff`. Samples taken before
+    /// `downsample_cutoff` are downsampled: only the first sample of every `interval` is kept.
+    ///
+    /// Returns the number of the removed samples.
+    pub async fn compact_price_history(
+        &mut self,
+        retention_cutoff: DateTime<Utc>,
+        downsample_cutoff: DateTime<Utc>,
+        interval: Duration,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let interval = interval.as_secs().max(1) as i64;
+        let mut transaction = self.0.start_transaction().await?;
+
+        let expired = sqlx::query!(
+            "DELETE FROM ticker_price_history WHERE sampled_at < $1",
+            retention_cutoff
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        let downsampled = sqlx::query!(
+            r#"
+            DELETE FROM ticker_price_history AS samples
+            USING ticker_price_history AS earlier
+            WHERE samples.sampled_at < $1
+                AND earlier.token_id = samples.token_id
+                AND earlier.sampled_at < samples.sampled_at
+                AND floor(extract(epoch FROM earlier.sampled_at))::bigint / $2
+                    = floor(extract(epoch FROM samples.sampled_at))::bigint / $2
+            "#,
+            downsample_cutoff,
+            interval
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.ticker.compact_price_history", start.elapsed());
+        Ok(expired + downsampled)
+    }
+
+    /// Stores the prices used for the fee of the submitted transaction, returns the identifier of the snapshot.
+    pub async fn store_fee_quote_snapshot(
+        &mut self,
+        snapshot: FeeQuoteSnapshot,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO fee_quote_snapshots (
+                tx_hash, token_id, fee_types, token_price_usd, token_price_updated_at, eth_price_usd,
+                eth_price_updated_at, gas_price_wei, total_fee, subsidized_total_fee
+            )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10 )
+            RETURNING id
+            "#,
+            snapshot.tx_hash,
+            snapshot.token_id,
+            snapshot.fee_types,
+            snapshot.token_price_usd,
+            snapshot.token_price_updated_at,
+            snapshot.eth_price_usd,
+            snapshot.eth_price_updated_at,
+            snapshot.gas_price_wei,
+            snapshot.total_fee,
+            snapshot.subsidized_total_fee
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.ticker.store_fee_quote_snapshot", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the fee quote snapshots for the token created in the `[from, to)` range.
+    pub async fn load_fee_quote_snapshots(
+        &mut self,
+        token_id: TokenId,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> QueryResult<Vec<StoredFeeQuoteSnapshot>> {
+        let start = Instant::now();
+        let snapshots = sqlx::query_as!(
+            StoredFeeQuoteSnapshot,
+            r#"
+            SELECT * FROM fee_quote_snapshots
+            WHERE token_id = $1 AND created_at >= $2 AND created_at < $3
+            ORDER BY id ASC
+            "#,
+            *token_id as i32,
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ticker.load_fee_quote_snapshots", start.elapsed());
+        Ok(snapshots)
+    }
+
+    /// Loads the fee quote snapshot stored for the transaction (or the batch) with the given hash.
+    pub async fn load_fee_quote_snapshot(
+        &mut self,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<StoredFeeQuoteSnapshot>> {
+        let start = Instant::now();
+        let snapshot = sqlx::query_as!(
+            StoredFeeQuoteSnapshot,
+            "SELECT * FROM fee_quote_snapshots WHERE tx_hash = $1 ORDER BY id DESC LIMIT 1",
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ticker.load_fee_quote_snapshot", start.elapsed());
+        Ok(snapshot)
+    }
+
+    /// Removes the fee quote snapshots created before `cutoff`.
+    /// Returns the number of the removed snapshots.
+    pub async fn remove_fee_quote_snapshots(&mut self, cutoff: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM fee_quote_snapshots WHERE created_at < $1",
+            cutoff
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.ticker.remove_fee_quote_snapshots", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Stores the fee quote, returns the identifier of the quote.
+    pub async fn store_fee_quote(&mut self, quote: NewFeeQuote) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO fee_quotes ( token_id, txs, total_fee, expires_at )
+            VALUES ( $1, $2, $3, $4 )
+            RETURNING id
+            "#,
+            quote.token_id,
+            quote.txs,
+            quote.total_fee,
+            quote.expires_at
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.ticker.store_fee_quote", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the fee quote by its identifier.
+    pub async fn load_fee_quote(&mut self, id: i64) -> QueryResult<Option<StoredFeeQuote>> {
+        let start = Instant::now();
+        let quote = sqlx::query_as!(StoredFeeQuote, "SELECT * FROM fee_quotes WHERE id = $1", id)
+            .fetch_optional(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.ticker.load_fee_quote", start.elapsed());
+        Ok(quote)
+    }
+
+    /// Claims the fee quote for the transaction (or the batch) with the given hash.
+    /// The quote is claimed only if it's neither used nor expired, so that concurrent
+    /// submissions can't redeem the same quote twice.
+    /// Returns the claimed quote or `None` if the quote can't be claimed.
+    pub async fn claim_fee_quote(
+        &mut self,
+        id: i64,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<StoredFeeQuote>> {
+        let start = Instant::now();
+        let quote = sqlx::query_as!(
+            StoredFeeQuote,
+            r#"
+            UPDATE fee_quotes SET used_by_tx_hash = $2, used_at = now()
+            WHERE id = $1 AND used_at IS NULL AND expires_at > now()
+            RETURNING *
+            "#,
+            id,
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ticker.claim_fee_quote", start.elapsed());
+        Ok(quote)
+    }
+
+    /// Releases the fee quote claimed by the transaction (or the batch) with the given hash,
+    /// e.g. if the transaction was rejected by the mempool.
+    /// Returns `false` if the quote isn't claimed by this transaction.
+    pub async fn release_fee_quote(&mut self, id: i64, tx_hash: &TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated = sqlx::query!(
+            r#"
+            UPDATE fee_quotes SET used_by_tx_hash = NULL, used_at = NULL
+            WHERE id = $1 AND used_by_tx_hash = $2
+            "#,
+            id,
+            tx_hash.as_ref()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.ticker.release_fee_quote", start.elapsed());
+        Ok(updated == 1)
+    }
+
+    /// Removes the unused fee quotes expired before `expired_before` and the quotes
+    /// used before `used_before`. Returns the number of the removed quotes.
+    pub async fn remove_fee_quotes(
+        &mut self,
+        expired_before: DateTime<Utc>,
+        used_before: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            r#"
+            DELETE FROM fee_quotes
+            WHERE (used_at IS NULL AND expires_at < $1) OR used_at < $2
+            "#,
+            expired_before,
+            used_before
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.ticker.remove_fee_quotes", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Stores the gas cost calibration, returns the identifier of the calibration.
+    pub async fn store_gas_calibration(
+        &mut self,
+        calibration: NewGasCalibration,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO ticker_gas_calibrations (
+                commit_cost_per_chunk, proof_cost_per_chunk, execute_cost_per_chunk,
+                amortized_cost_per_chunk, gas_price_scale_percent
+            )
+            VALUES ( $1, $2, $3, $4, $5 )
+            RETURNING id
+            "#,
+            calibration.commit_cost_per_chunk,
+            calibration.proof_cost_per_chunk,
+            calibration.execute_cost_per_chunk,
+            calibration.amortized_cost_per_chunk,
+            calibration.gas_price_scale_percent
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.ticker.store_gas_calibration", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the latest gas cost calibration, if any.
+    pub async fn load_last_gas_calibration(&mut self) -> QueryResult<Option<StoredGasCalibration>> {
+        let start = Instant::now();
+        let calibration = sqlx::query_as!(
+            StoredGasCalibration,
+            "SELECT * FROM ticker_gas_calibrations ORDER BY id DESC LIMIT 1"
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ticker.load_last_gas_calibration", start.elapsed());
+        Ok(calibration)
+    }
+}
@@ -0,0 +1,93 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+/// Token price sample, possibly averaged over the time interval.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredPriceSample {
+    pub usd_price: BigDecimal,
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// Prices used by the fee ticker to calculate the fee of the submitted transaction (or batch).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeQuoteSnapshot {
+    pub tx_hash: Vec<u8>,
+    pub token_id: i32,
+    /// Fee types of the quoted transactions.
+    pub fee_types: Value,
+    pub token_price_usd: BigDecimal,
+    pub token_price_updated_at: DateTime<Utc>,
+    pub eth_price_usd: BigDecimal,
+    pub eth_price_updated_at: DateTime<Utc>,
+    pub gas_price_wei: BigDecimal,
+    pub total_fee: BigDecimal,
+    pub subsidized_total_fee: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredFeeQuoteSnapshot {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub token_id: i32,
+    pub fee_types: Value,
+    pub token_price_usd: BigDecimal,
+    pub token_price_updated_at: DateTime<Utc>,
+    pub eth_price_usd: BigDecimal,
+    pub eth_price_updated_at: DateTime<Utc>,
+    pub gas_price_wei: BigDecimal,
+    pub total_fee: BigDecimal,
+    pub subsidized_total_fee: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fee quote issued by the fee ticker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewFeeQuote {
+    pub token_id: i32,
+    /// Fee types and addresses of the quoted transactions.
+    pub txs: Value,
+    pub total_fee: BigDecimal,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredFeeQuote {
+    pub id: i64,
+    pub token_id: i32,
+    pub txs: Value,
+    pub total_fee: BigDecimal,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_by_tx_hash: Option<Vec<u8>>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+/// Gas costs calibrated from the gas used by the confirmed Ethereum transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewGasCalibration {
+    /// Gas used per chunk by the commit transactions excluding the estimated cost of the operations.
+    pub commit_cost_per_chunk: i64,
+    /// Gas used per chunk by the proof transactions.
+    pub proof_cost_per_chunk: i64,
+    /// Gas used per chunk by the execute transactions excluding the estimated cost of the operations.
+    pub execute_cost_per_chunk: i64,
+    /// Smoothed and bounded amortized gas cost per chunk used by the fee ticker.
+    pub amortized_cost_per_chunk: i64,
+    /// Smoothed and bounded scale of the average gas price used by the fee ticker in percent.
+    pub gas_price_scale_percent: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredGasCalibration {
+    pub id: i64,
+    pub commit_cost_per_chunk: i64,
+    pub proof_cost_per_chunk: i64,
+    pub execute_cost_per_chunk: i64,
+    pub amortized_cost_per_chunk: i64,
+    pub gas_price_scale_percent: i64,
+    pub created_at: DateTime<Utc>,
+}
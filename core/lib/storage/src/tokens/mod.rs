@@ -11,9 +11,11 @@ use zksync_api_types::v02::{
     token::ApiNFT,
 };
 use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
-use zksync_utils::ratio_to_big_decimal;
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind, TokenStatus,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
@@ -38,15 +40,16 @@ pub enum StoreTokenError {
 }
 
 impl<'a, 'c> TokensSchema<'a, 'c> {
-    /// Persists the new token in the database.
+    /// Persists the new token in the database. Fails if the token with the same id, address
+    /// or symbol (regardless of the case) already exists.
     pub async fn store_token(&mut self, token: Token) -> Result<(), StoreTokenError> {
         let start = Instant::now();
 
         let token_from_db: Option<Token> = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
-            WHERE id = $1 OR address = $2 OR symbol = $3
+            SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
+            WHERE id = $1 OR address = $2 OR lower(symbol) = lower($3)
             LIMIT 1
             "#,
             *token.id as i32,
@@ -64,7 +67,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             if token_from_db.id == token.id {
                 matched_parameters.push(format!("id = {}", token.id));
             }
-            if token_from_db.symbol == token.symbol {
+            if token_from_db.symbol.to_lowercase() == token.symbol.to_lowercase() {
                 matched_parameters.push(format!("symbol = {}", token.symbol));
             }
             if token_from_db.address == token.address {
@@ -80,16 +83,18 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         }
 
         let kind: TokenKind = token.kind.into();
+        let status: TokenStatus = token.status.into();
         sqlx::query!(
             r#"
-            INSERT INTO tokens ( id, address, symbol, decimals, kind )
-            VALUES ( $1, $2, $3, $4, $5 )
+            INSERT INTO tokens ( id, address, symbol, decimals, kind, status )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
             "#,
             token.id.0 as i32,
             address_to_stored_string(&token.address),
             token.symbol,
             i16::from(token.decimals),
-            kind as TokenKind
+            kind as TokenKind,
+            status as TokenStatus
         )
         .execute(self.0.conn())
         .await
@@ -104,19 +109,21 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     pub async fn store_or_update_token(&mut self, token: Token) -> QueryResult<()> {
         let start = Instant::now();
         let kind: TokenKind = token.kind.into();
+        let status: TokenStatus = token.status.into();
         sqlx::query!(
             r#"
-            INSERT INTO tokens ( id, address, symbol, decimals, kind )
-            VALUES ( $1, $2, $3, $4, $5 )
+            INSERT INTO tokens ( id, address, symbol, decimals, kind, status )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
             ON CONFLICT (id)
             DO
-              UPDATE SET address = $2, symbol = $3, decimals = $4, kind = $5
+              UPDATE SET address = $2, symbol = $3, decimals = $4, kind = $5, status = $6
             "#,
             *token.id as i32,
             address_to_stored_string(&token.address),
             token.symbol,
             i16::from(token.decimals),
-            kind as TokenKind
+            kind as TokenKind,
+            status as TokenStatus
         )
         .execute(self.0.conn())
         .await?;
@@ -125,6 +132,29 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Updates the validation status of the token.
+    pub async fn update_token_status(
+        &mut self,
+        token_id: TokenId,
+        status: zksync_types::TokenStatus,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let status: TokenStatus = status.into();
+        sqlx::query!(
+            r#"
+            UPDATE tokens SET status = $2
+            WHERE id = $1
+            "#,
+            *token_id as i32,
+            status as TokenStatus
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.update_token_status", start.elapsed());
+        Ok(())
+    }
+
     /// Loads tokens from the database starting from the given id with the given limit in the ascending order.
     pub async fn load_tokens_asc(
         &mut self,
@@ -136,7 +166,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
             WHERE id >= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id ASC
             LIMIT $2
@@ -163,7 +193,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
             WHERE id <= $1 AND kind = 'ERC20'::token_kind
             ORDER BY id DESC
             LIMIT $2
@@ -240,7 +270,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         let tokens = sqlx::query_as!(
             DbToken,
             r#"
-            SELECT id, address, decimals, kind as "kind: _", symbol
+            SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol
             FROM tokens
             INNER JOIN ticker_market_volume
             ON tokens.id = ticker_market_volume.token_id
@@ -410,7 +440,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
                     WHERE id = $1
                     LIMIT 1
                     "#,
@@ -423,7 +453,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
                     WHERE address = $1
                     LIMIT 1
                     "#,
@@ -442,7 +472,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 sqlx::query_as!(
                     DbToken,
                     r#"
-                    SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+                    SELECT id, address, decimals, kind as "kind: _", status as "status: _", symbol FROM tokens
                     WHERE lower(symbol) = lower($1)
                     LIMIT 1
                     "#,
@@ -527,6 +557,37 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(db_price.map(|p| p.into()))
     }
 
+    /// Loads the USD price of the smallest unit for each of the given tokens.
+    /// Tokens without a known price are omitted from the result.
+    pub async fn get_token_unit_prices(
+        &mut self,
+        token_ids: &[TokenId],
+    ) -> QueryResult<HashMap<TokenId, Ratio<BigUint>>> {
+        let start = Instant::now();
+        let token_ids: Vec<i32> = token_ids.iter().map(|token_id| **token_id as i32).collect();
+        let records = sqlx::query!(
+            r#"
+            SELECT ticker_price.token_id, ticker_price.usd_price / power(10::numeric, tokens.decimals) AS "unit_price!"
+            FROM ticker_price
+            INNER JOIN tokens ON tokens.id = ticker_price.token_id
+            WHERE ticker_price.token_id = ANY($1)
+            "#,
+            &token_ids
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let prices = records
+            .into_iter()
+            .map(|record| {
+                let unit_price = big_decimal_to_ratio(&record.unit_price)?;
+                Ok((TokenId(record.token_id as u32), unit_price))
+            })
+            .collect::<QueryResult<_>>()?;
+        metrics::histogram!("sql.token.get_token_unit_prices", start.elapsed());
+        Ok(prices)
+    }
+
     /// Updates price in USD for the given token.
     ///
     /// Note, that the price precision cannot be greater than `STORED_USD_PRICE_PRECISION`,
@@ -21,6 +21,7 @@ pub struct DbToken {
     pub symbol: String,
     pub decimals: i16,
     pub kind: TokenKind,
+    pub status: TokenStatus,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, sqlx::Type)]
@@ -51,6 +52,34 @@ impl From<zksync_types::TokenKind> for TokenKind {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "token_status")]
+pub enum TokenStatus {
+    Valid,
+    Flagged,
+    Quarantined,
+}
+
+impl From<TokenStatus> for zksync_types::TokenStatus {
+    fn from(status: TokenStatus) -> zksync_types::TokenStatus {
+        match status {
+            TokenStatus::Valid => zksync_types::TokenStatus::Valid,
+            TokenStatus::Flagged => zksync_types::TokenStatus::Flagged,
+            TokenStatus::Quarantined => zksync_types::TokenStatus::Quarantined,
+        }
+    }
+}
+
+impl From<zksync_types::TokenStatus> for TokenStatus {
+    fn from(status: zksync_types::TokenStatus) -> TokenStatus {
+        match status {
+            zksync_types::TokenStatus::Valid => TokenStatus::Valid,
+            zksync_types::TokenStatus::Flagged => TokenStatus::Flagged,
+            zksync_types::TokenStatus::Quarantined => TokenStatus::Quarantined,
+        }
+    }
+}
+
 impl From<Token> for DbToken {
     fn from(token: Token) -> Self {
         Self {
@@ -59,6 +88,7 @@ impl From<Token> for DbToken {
             symbol: token.symbol,
             decimals: token.decimals as i16,
             kind: token.kind.into(),
+            status: token.status.into(),
         }
     }
 }
@@ -72,6 +102,7 @@ impl From<DbToken> for Token {
             val.decimals as u8,
             val.kind.into(),
         )
+        .with_status(val.status.into())
     }
 }
 
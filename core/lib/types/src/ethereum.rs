@@ -4,7 +4,7 @@
 use thiserror::Error;
 // Local uses
 use crate::aggregated_operations::{AggregatedActionType, AggregatedOperation};
-use zksync_basic_types::{H256, U256};
+use zksync_basic_types::{Address, H256, U256};
 
 /// Numerical identifier of the Ethereum operation.
 pub type EthOpId = i64;
@@ -18,12 +18,16 @@ pub struct ETHOperation {
     pub op_type: AggregatedActionType,
     /// Optional ZKSync operation associated with Ethereum operation.
     pub op: Option<(i64, AggregatedOperation)>,
-    /// Used nonce (fixed for all the sent transactions).
+    /// Used nonce (fixed for all the sent transactions, unless a transaction has failed).
     pub nonce: U256,
     /// Deadline block of the last sent transaction.
     pub last_deadline_block: u64,
     /// Gas price used in the last sent transaction.
+    /// For EIP-1559 transactions it's the max fee per gas.
     pub last_used_gas_price: U256,
+    /// Priority fee per gas used in the last sent transaction.
+    /// Set only for EIP-1559 transactions.
+    pub last_used_priority_fee: Option<U256>,
     /// Hashes of all the sent transactions.
     pub used_tx_hashes: Vec<H256>,
     /// Tx payload (not signed).
@@ -34,6 +38,11 @@ pub struct ETHOperation {
     /// Hash of the accepted Ethereum transaction (if operation
     /// is confirmed).
     pub final_hash: Option<H256>,
+    /// Number of failed transactions that were sent again with a new nonce.
+    pub failed_attempts: u64,
+    /// Address of the additional operator account sending the transactions,
+    /// or `None` if they are sent from the main operator account.
+    pub operator: Option<Address>,
 }
 
 impl ETHOperation {
@@ -61,9 +70,12 @@ impl PartialEq for ETHOperation {
         (self.id == other.id)
             && (self.last_deadline_block == other.last_deadline_block)
             && (self.last_used_gas_price == other.last_used_gas_price)
+            && (self.last_used_priority_fee == other.last_used_priority_fee)
             && (self.used_tx_hashes == other.used_tx_hashes)
             && (self.confirmed == other.confirmed)
             && (self.final_hash == other.final_hash)
+            && (self.failed_attempts == other.failed_attempts)
+            && (self.operator == other.operator)
     }
 }
 
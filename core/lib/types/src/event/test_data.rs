@@ -96,3 +96,30 @@ pub fn get_transaction_event(
         data: EventData::Transaction(tx_event),
     }
 }
+
+/// Construct transaction event with the given serialized transaction,
+/// account id, token and status. The type of the transaction is taken
+/// from the `type` field of the `tx`.
+pub fn get_transaction_event_with_data(
+    tx: serde_json::Value,
+    account_id: AccountId,
+    token_id: TokenId,
+    status: TransactionStatus,
+) -> ZkSyncEvent {
+    let tx_event = TransactionEvent {
+        tx_hash: String::new(),
+        account_id,
+        token_id,
+        block_number: BlockNumber(0),
+        tx,
+        status,
+        fail_reason: None,
+        created_at: Utc::now(),
+        tx_type: OnceCell::new(),
+    };
+    ZkSyncEvent {
+        id: EventId(0),
+        block_number: BlockNumber(0),
+        data: EventData::Transaction(tx_event),
+    }
+}
@@ -1,12 +1,13 @@
 // Built-in uses
 // External uses
 use chrono::{DateTime, Utc};
+use num::BigUint;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 // Workspace uses
 // Local uses
 use super::account::AccountStateChangeStatus;
-use crate::{block::ExecutedOperations, AccountId, BlockNumber, TokenId};
+use crate::{block::ExecutedOperations, AccountId, Address, BlockNumber, TokenId};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -94,6 +95,46 @@ impl TransactionEvent {
             .tx_type
             .get_or_init(|| serde_json::from_value(self.tx["type"].clone()).unwrap())
     }
+
+    /// Returns the amount of tokens moved by the operation.
+    ///
+    /// Returns [`None`] for operations without a single amount (e.g. swaps or NFT operations).
+    pub fn amount(&self) -> Option<BigUint> {
+        let amount = match self.tx_type() {
+            TransactionType::Transfer | TransactionType::Withdraw => &self.tx["amount"],
+            TransactionType::Deposit => &self.tx["priority_op"]["amount"],
+            _ => return None,
+        };
+        amount.as_str()?.parse().ok()
+    }
+
+    /// Returns the token the fee was paid in.
+    ///
+    /// Returns [`None`] for priority operations, since they don't pay fees in L2.
+    pub fn fee_token(&self) -> Option<TokenId> {
+        match self.tx_type() {
+            TransactionType::Transfer | TransactionType::Withdraw | TransactionType::ForcedExit => {
+                Some(self.token_id)
+            }
+            TransactionType::WithdrawNFT
+            | TransactionType::MintNFT
+            | TransactionType::Swap
+            | TransactionType::ChangePubKey => {
+                serde_json::from_value(self.tx["feeToken"].clone()).ok()
+            }
+            TransactionType::FullExit | TransactionType::Deposit => None,
+        }
+    }
+
+    /// Returns the address of the NFT creator for the `MintNFT` transaction.
+    pub fn nft_creator_address(&self) -> Option<Address> {
+        match self.tx_type() {
+            TransactionType::MintNFT => {
+                serde_json::from_value(self.tx["creatorAddress"].clone()).ok()
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<AccountStateChangeStatus> for TransactionStatus {
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use num::rational::Ratio;
 use num::BigUint;
 use serde::{Deserialize, Serialize};
@@ -33,10 +34,16 @@ pub struct Fee {
     pub gas_price_wei: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub gas_fee: BigUint,
+    /// Part of the `gas_fee` charged over the L1 cost estimated with the current gas price.
+    #[serde(default, with = "BigUintSerdeAsRadix10Str")]
+    pub margin_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Quote binding the `total_fee`, if issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<FeeQuote>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -44,6 +51,9 @@ pub struct Fee {
 pub struct BatchFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub gas_fee: BigUint,
+    /// Part of the `gas_fee` charged over the L1 cost estimated with the current gas price.
+    #[serde(default, with = "BigUintSerdeAsRadix10Str")]
+    pub margin_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
@@ -55,13 +65,30 @@ pub struct BatchFee {
 pub struct TotalFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Quote binding the `total_fee`, if issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<FeeQuote>,
+}
+
+/// Fee quote issued by the server. Until the quote expires, the quoted fee is accepted
+/// for the transactions of the same types, token and addresses referencing the quote.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeQuote {
+    pub id: i64,
+    pub expires_at: DateTime<Utc>,
 }
 
 impl BatchFee {
-    pub fn new(zkp_fee: Ratio<BigUint>, gas_fee: Ratio<BigUint>) -> Self {
+    pub fn new(
+        zkp_fee: Ratio<BigUint>,
+        gas_fee: Ratio<BigUint>,
+        margin_fee: Ratio<BigUint>,
+    ) -> Self {
         let (zkp_fee, gas_fee, total_fee) = total_fee(&zkp_fee, &gas_fee);
         Self {
             gas_fee,
+            margin_fee: round_fee(&margin_fee),
             zkp_fee,
             total_fee,
         }
@@ -73,6 +100,7 @@ impl Fee {
         fee_type: OutputFeeType,
         zkp_fee: Ratio<BigUint>,
         gas_fee: Ratio<BigUint>,
+        margin_fee: Ratio<BigUint>,
         gas_tx_amount: BigUint,
         gas_price_wei: BigUint,
     ) -> Self {
@@ -82,15 +110,21 @@ impl Fee {
             gas_tx_amount,
             gas_price_wei,
             gas_fee,
+            margin_fee: round_fee(&margin_fee),
             zkp_fee,
             total_fee,
+            quote: None,
         }
     }
 }
 
+fn round_fee(fee: &Ratio<BigUint>) -> BigUint {
+    round_precision(fee, 18).ceil().to_integer()
+}
+
 fn total_fee(zkp_fee: &Ratio<BigUint>, gas_fee: &Ratio<BigUint>) -> (BigUint, BigUint, BigUint) {
-    let zkp_fee = round_precision(zkp_fee, 18).ceil().to_integer();
-    let gas_fee = round_precision(gas_fee, 18).ceil().to_integer();
+    let zkp_fee = round_fee(zkp_fee);
+    let gas_fee = round_fee(gas_fee);
 
     let total_fee = zkp_fee.clone() + gas_fee.clone();
     (
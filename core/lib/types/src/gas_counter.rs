@@ -269,7 +269,7 @@ impl GasCounter {
     }
 
     /// Increases the value by 30%.
-    fn scale_up(value: U256) -> U256 {
+    pub fn scale_up(value: U256) -> U256 {
         value * U256::from(130) / U256::from(100)
     }
 }
@@ -60,7 +60,7 @@ mod tests;
 
 pub use self::account::{Account, AccountUpdate, PubKeyHash};
 pub use self::block::{ExecutedOperations, ExecutedPriorityOp, ExecutedTx};
-pub use self::fee::{BatchFee, Fee, OutputFeeType, TotalFee};
+pub use self::fee::{BatchFee, Fee, FeeQuote, OutputFeeType, TotalFee};
 pub use self::operations::{
     ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, SwapOp, TransferOp,
     TransferToNewOp, WithdrawNFTOp, WithdrawOp, ZkSyncOp,
@@ -68,7 +68,7 @@ pub use self::operations::{
 pub use self::priority_ops::{Deposit, FullExit, PriorityOp, ZkSyncPriorityOp};
 pub use self::register_factory::RegisterNFTFactoryEvent;
 pub use self::tokens::{
-    NewTokenEvent, Token, TokenInfo, TokenKind, TokenLike, TokenPrice, TxFeeTypes, NFT,
+    NewTokenEvent, Token, TokenInfo, TokenKind, TokenLike, TokenPrice, TokenStatus, TxFeeTypes, NFT,
 };
 pub use self::tx::{
     ForcedExit, MintNFT, Order, SignedZkSyncTx, Swap, Transfer, Withdraw, WithdrawNFT, ZkSyncTx,
@@ -121,6 +121,9 @@ pub struct Token {
     pub decimals: u8,
     pub kind: TokenKind,
     pub is_nft: bool,
+    /// Result of the token validation performed when the token was added.
+    #[serde(default)]
+    pub status: TokenStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
@@ -136,6 +139,30 @@ impl Default for TokenKind {
     }
 }
 
+/// Status assigned to the token by the validation of its contract.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TokenStatus {
+    /// Token behaves as a regular ERC20 token.
+    Valid,
+    /// Token metadata is malformed, default symbol or decimals are used instead.
+    Flagged,
+    /// Token contract is not ERC20-compliant, or it takes a fee on transfers or rebases balances.
+    Quarantined,
+}
+
+impl Default for TokenStatus {
+    fn default() -> Self {
+        Self::Valid
+    }
+}
+
+impl TokenStatus {
+    /// Returns `true` if the token can be safely used to pay fees.
+    pub fn is_safe(self) -> bool {
+        matches!(self, Self::Valid)
+    }
+}
+
 impl Token {
     pub fn new(id: TokenId, address: Address, symbol: &str, decimals: u8, kind: TokenKind) -> Self {
         Self {
@@ -145,9 +172,16 @@ impl Token {
             decimals,
             kind,
             is_nft: matches!(kind, TokenKind::NFT),
+            status: TokenStatus::Valid,
         }
     }
 
+    /// Sets the validation status of the token.
+    pub fn with_status(mut self, status: TokenStatus) -> Self {
+        self.status = status;
+        self
+    }
+
     pub fn new_nft(id: TokenId, symbol: &str) -> Self {
         Self {
             id,
@@ -156,6 +190,7 @@ impl Token {
             decimals: 0,
             kind: TokenKind::NFT,
             is_nft: true,
+            status: TokenStatus::Valid,
         }
     }
 }
@@ -57,6 +57,18 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Replacement transaction fee is too low")]
+    ReplacementUnderpriced,
+
+    #[error("Too many pending transactions for the account")]
+    TooManyPendingTxs,
+
+    #[error("Tx nonce is too far ahead of the committed account nonce")]
+    NonceGapTooLarge,
+
+    #[error("Mempool is full")]
+    MempoolIsFull,
 }
 
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
@@ -7,8 +7,8 @@ use tokio::runtime::Runtime;
 use zksync_core::{
     committer::CommitRequest,
     state_keeper::{
-        start_root_hash_calculator, StateKeeperTestkitRequest, ZkSyncStateInitParams,
-        ZkSyncStateKeeper,
+        start_root_hash_calculator, StateKeeperHalt, StateKeeperTestkitRequest,
+        ZkSyncStateInitParams, ZkSyncStateKeeper,
     },
     tx_event_emitter::ProcessedOperations,
 };
@@ -73,6 +73,7 @@ pub fn spawn_state_keeper(
         max_miniblock_iterations,
         max_miniblock_iterations,
         processed_tx_events_sender,
+        StateKeeperHalt::default(),
     );
 
     let (stop_state_keeper_sender, stop_state_keeper_receiver) = oneshot::channel::<()>();
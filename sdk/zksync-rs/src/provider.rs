@@ -13,7 +13,7 @@ use num::BigUint;
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    Address, BlockNumber, TokenLike, TxFeeTypes,
 };
 
 // Local uses
@@ -241,6 +241,21 @@ impl RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    /// Requests the Merkle proof of the account token balance in the finalized block.
+    /// The last finalized block is used if `block` is not set.
+    ///
+    /// The proof should be checked with `utils::verify_account_balance_proof` against
+    /// the root hash obtained from a trusted source, e.g. the zkSync contract.
+    pub async fn account_proof(
+        &self,
+        address: Address,
+        token: impl Into<TokenLike>,
+        block: Option<BlockNumber>,
+    ) -> Result<AccountBalanceProof, ClientError> {
+        let msg = JsonRpcRequest::account_proof(address, token.into(), block);
+        self.send_and_deserialize(&msg).await
+    }
+
     /// Performs a POST query to the JSON RPC endpoint,
     /// and decodes the response, returning the decoded `serde_json::Value`.
     /// `Ok` is returned only for successful calls, for any kind of error
@@ -324,7 +339,7 @@ mod messages {
     use serde::Serialize;
     use zksync_types::{
         tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
-        Address, TokenLike, TxFeeTypes,
+        Address, BlockNumber, TokenLike, TxFeeTypes,
     };
 
     #[derive(Debug, Serialize)]
@@ -412,6 +427,14 @@ mod messages {
             Self::create("get_eth_tx_for_withdrawal", json_values![withdrawal_hash])
         }
 
+        pub fn account_proof(
+            address: Address,
+            token: TokenLike,
+            block: Option<BlockNumber>,
+        ) -> Self {
+            Self::create("account_proof", json_values![address, token, block])
+        }
+
         pub fn get_tx_fee(tx_type: TxFeeTypes, address: Address, token_symbol: TokenLike) -> Self {
             let params = json_values![tx_type, address, token_symbol];
             Self::create("get_tx_fee", params)
@@ -3,7 +3,11 @@ use std::collections::HashMap;
 use num::BigUint;
 use serde::{Deserialize, Serialize};
 
-use zksync_types::{AccountId, Address, Nonce, PubKeyHash, Token, TokenId, H256};
+use zksync_crypto::{
+    serialization::{FrSerde, VecFrSerde},
+    Fr,
+};
+use zksync_types::{AccountId, Address, BlockNumber, Nonce, PubKeyHash, Token, TokenId, H256};
 use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
 pub type Tokens = HashMap<String, Token>;
@@ -57,6 +61,34 @@ pub struct AccountInfo {
     pub verified: AccountState,
 }
 
+/// Account fields that are hashed into the leaf of the account tree.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountLeaf {
+    pub nonce: Nonce,
+    pub pub_key_hash: PubKeyHash,
+    pub address: Address,
+}
+
+/// Merkle proof of the account token balance in the state of the finalized block.
+/// Can be checked with `utils::verify_account_balance_proof`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalanceProof {
+    pub block_number: BlockNumber,
+    #[serde(with = "FrSerde")]
+    pub root_hash: Fr,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub account: AccountLeaf,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub balance: BigUint,
+    #[serde(with = "VecFrSerde")]
+    pub balance_path: Vec<Fr>,
+    #[serde(with = "VecFrSerde")]
+    pub account_path: Vec<Fr>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockInfo {
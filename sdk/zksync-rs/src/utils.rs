@@ -4,13 +4,23 @@ use std::time::Instant;
 use num::BigUint;
 use sha2::{Digest, Sha256};
 
-use zksync_crypto::bellman::{pairing::ff::PrimeField, PrimeFieldRepr};
+use zksync_crypto::bellman::{
+    pairing::ff::{Field, PrimeField},
+    PrimeFieldRepr,
+};
+use zksync_crypto::circuit::{account::Balance, utils::eth_address_to_fr};
 use zksync_crypto::franklin_crypto::alt_babyjubjub::fs::FsRepr;
-use zksync_crypto::{priv_key_from_fs, Fs, PrivateKey};
+use zksync_crypto::merkle_tree::{hasher::Hasher, RescueHasher};
+use zksync_crypto::params::{
+    account_tree_depth, balance_tree_depth, ADDRESS_WIDTH, BALANCE_BIT_WIDTH, FR_BIT_WIDTH,
+    FR_BIT_WIDTH_PADDED, LEAF_DATA_BIT_WIDTH, NEW_PUBKEY_HASH_WIDTH, NONCE_BIT_WIDTH,
+};
+use zksync_crypto::primitives::{GetBits, GetBitsFixed};
+use zksync_crypto::{priv_key_from_fs, Engine, Fr, Fs, PrivateKey};
 use zksync_eth_signer::EthereumSigner;
 use zksync_types::{AccountId, U256};
 
-use crate::{error::ClientError, provider::Provider, wallet::Wallet};
+use crate::{error::ClientError, provider::Provider, types::AccountBalanceProof, wallet::Wallet};
 
 // Public re-exports.
 pub use zksync_types::helpers::{
@@ -101,6 +111,65 @@ pub fn biguint_to_u256(value: BigUint) -> U256 {
     U256::from_little_endian(&bytes)
 }
 
+/// Computes the root of the Merkle tree given the leaf bits and the hashes of the sibling nodes
+/// starting from the leaf level. Directions are determined by the bits of the leaf index.
+fn merkle_root(hasher: &RescueHasher<Engine>, leaf_bits: Vec<bool>, index: u32, path: &[Fr]) -> Fr {
+    let mut hash = hasher.hash_bits(leaf_bits);
+    for (level, sibling) in path.iter().enumerate() {
+        hash = if (index >> level) & 1 == 1 {
+            hasher.compress(sibling, &hash, level)
+        } else {
+            hasher.compress(&hash, sibling, level)
+        };
+    }
+    hash
+}
+
+/// Checks that the account token balance from the proof is included in the state with the root hash
+/// from the same proof. The account leaf is hashed in the same way as in the zkSync circuit.
+///
+/// Note that the proof only binds the balance to the root hash it contains, so the root hash
+/// must be compared with the one obtained from a trusted source, e.g. the zkSync contract.
+pub fn verify_account_balance_proof(proof: &AccountBalanceProof) -> bool {
+    if proof.balance_path.len() != balance_tree_depth()
+        || proof.account_path.len() != account_tree_depth()
+        || proof.balance.bits() as usize > BALANCE_BIT_WIDTH
+    {
+        return false;
+    }
+
+    let hasher = RescueHasher::<Engine>::default();
+
+    let balance = Balance::<Engine> {
+        value: Fr::from_str(&proof.balance.to_string()).expect("Balance fits into the field"),
+    };
+    let balance_root = merkle_root(
+        &hasher,
+        balance.get_bits_le(),
+        *proof.token_id,
+        &proof.balance_path,
+    );
+    let state_root = hasher.hash_elements(vec![balance_root, Fr::zero()]);
+
+    let nonce = Fr::from_str(&proof.account.nonce.to_string()).expect("Nonce fits into the field");
+    let mut leaf_bits = Vec::with_capacity(LEAF_DATA_BIT_WIDTH);
+    leaf_bits.extend(nonce.get_bits_le_fixed(NONCE_BIT_WIDTH));
+    leaf_bits.extend(
+        proof
+            .account
+            .pub_key_hash
+            .as_fr()
+            .get_bits_le_fixed(NEW_PUBKEY_HASH_WIDTH),
+    );
+    leaf_bits.extend(eth_address_to_fr(&proof.account.address).get_bits_le_fixed(ADDRESS_WIDTH));
+    let mut state_root_bits = state_root.get_bits_le_fixed(FR_BIT_WIDTH);
+    state_root_bits.resize(FR_BIT_WIDTH_PADDED, false);
+    leaf_bits.extend(state_root_bits);
+
+    let root_hash = merkle_root(&hasher, leaf_bits, *proof.account_id, &proof.account_path);
+    root_hash == proof.root_hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
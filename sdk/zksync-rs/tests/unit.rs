@@ -772,3 +772,98 @@ mod wallet_tests {
         assert_eq!(eth_provider.contract_address().as_bytes(), expected_address);
     }
 }
+
+#[cfg(test)]
+mod balance_proof_tests {
+    use super::*;
+    use num::BigUint;
+    use zksync::types::{AccountBalanceProof, AccountLeaf};
+    use zksync_crypto::{
+        circuit::account::CircuitAccount,
+        params::{account_tree_depth, NFT_TOKEN_ID},
+        Engine,
+    };
+    use zksync_types::{Account, AccountTree, Address, BlockNumber, PubKeyHash};
+
+    fn build_proof(account_id: AccountId, token_id: TokenId) -> AccountBalanceProof {
+        let mut tree = AccountTree::new(account_tree_depth());
+        for id in 0..4u32 {
+            let mut account = Account::default_with_address(&Address::repeat_byte(id as u8 + 1));
+            account.nonce = Nonce(id * 3);
+            account.pub_key_hash =
+                PubKeyHash::from_hex("sync:8d6c3e5ee5b0bc7e6cb9fcb0e2a9fb6b3a4f0d2e").unwrap();
+            account.set_balance(TokenId(0), BigUint::from(1_000_000u32 + id));
+            account.set_balance(TokenId(2), BigUint::from(7u32));
+            account.set_balance(NFT_TOKEN_ID, BigUint::from(1u32));
+            tree.insert(id, account);
+        }
+
+        let account = tree.get(*account_id).cloned().unwrap();
+        let account_path = tree.merkle_path(*account_id);
+        let balance_path = CircuitAccount::<Engine>::from(account.clone())
+            .subtree
+            .merkle_path(*token_id);
+
+        AccountBalanceProof {
+            block_number: BlockNumber(1),
+            root_hash: tree.root_hash(),
+            account_id,
+            token_id,
+            account: AccountLeaf {
+                nonce: account.nonce,
+                pub_key_hash: account.pub_key_hash,
+                address: account.address,
+            },
+            balance: account.get_balance(token_id),
+            balance_path: balance_path.into_iter().map(|(hash, _)| hash).collect(),
+            account_path: account_path.into_iter().map(|(hash, _)| hash).collect(),
+        }
+    }
+
+    #[test]
+    fn test_verify_account_balance_proof() {
+        for &(account_id, token_id) in &[
+            (AccountId(0), TokenId(0)),
+            (AccountId(3), TokenId(2)),
+            (AccountId(1), TokenId(5)),
+            (AccountId(2), NFT_TOKEN_ID),
+        ] {
+            let proof = build_proof(account_id, token_id);
+            assert!(verify_account_balance_proof(&proof));
+
+            // Serialization doesn't affect the proof.
+            let json = serde_json::to_value(&proof).unwrap();
+            let deserialized: AccountBalanceProof = serde_json::from_value(json).unwrap();
+            assert!(verify_account_balance_proof(&deserialized));
+        }
+    }
+
+    #[test]
+    fn test_verify_account_balance_proof_tampered() {
+        let proof = build_proof(AccountId(1), TokenId(0));
+
+        let mut tampered = proof.clone();
+        tampered.balance += 1u32;
+        assert!(!verify_account_balance_proof(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.token_id = TokenId(2);
+        assert!(!verify_account_balance_proof(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.account_id = AccountId(2);
+        assert!(!verify_account_balance_proof(&tampered));
+
+        let mut tampered = proof.clone();
+        *tampered.account.nonce += 1;
+        assert!(!verify_account_balance_proof(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.account_path.pop();
+        assert!(!verify_account_balance_proof(&tampered));
+
+        let mut tampered = proof;
+        tampered.balance_path.swap(0, 1);
+        assert!(!verify_account_balance_proof(&tampered));
+    }
+}
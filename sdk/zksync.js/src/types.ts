@@ -498,7 +498,7 @@ export interface ApiL1TxReceipt {
     id: number;
 }
 
-export type L2TxStatus = 'queued' | 'committed' | 'finalized' | 'rejected';
+export type L2TxStatus = 'queued' | 'committed' | 'finalized' | 'rejected' | 'replaced';
 
 export interface ApiL2TxReceipt {
     txHash: string;